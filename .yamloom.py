@@ -262,6 +262,29 @@ def generate_rust_release(crate_name: str) -> Workflow:
     )
 
 
+wasm_check_workflow = Workflow(
+    name='Check WebAssembly Build',
+    on=Events(
+        push=PushEvent(branches=['main']),
+        pull_request=PullRequestEvent(),
+        workflow_dispatch=WorkflowDispatchEvent(),
+    ),
+    jobs={
+        'wasm-check': Job(
+            steps=[
+                Checkout(),
+                SetupRust(),
+                script('rustup target add wasm32-unknown-unknown'),
+                script(
+                    'cargo check --target wasm32-unknown-unknown -p gluex-ccdb -p gluex-rcdb'
+                    ' --features gluex-ccdb/wasm,gluex-rcdb/wasm'
+                ),
+            ],
+            runs_on='ubuntu-latest',
+        )
+    },
+)
+
 release_please_workflow = Workflow(
     name='Release Please',
     on=Events(
@@ -293,4 +316,6 @@ if __name__ == '__main__':
     generate_python_release('gluex-ccdb-py').dump('.github/workflows/maturin_gluex_ccdb.yml')
     generate_python_release('gluex-rcdb-py').dump('.github/workflows/maturin_gluex_rcdb.yml')
     generate_python_release('gluex-lumi-py').dump('.github/workflows/maturin_gluex_lumi.yml')
+    generate_python_release('gluex-py').dump('.github/workflows/maturin_gluex.yml')
+    wasm_check_workflow.dump('.github/workflows/wasm.yml')
     release_please_workflow.dump('.github/workflows/release-please.yml')
@@ -0,0 +1,77 @@
+//! Derive macro for `gluex_ccdb::typed::CcdbTable`.
+//!
+//! This crate is re-exported by `gluex-ccdb` when its `derive` feature is enabled and is not meant
+//! to be used directly.
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, LitStr};
+
+/// Implements `gluex_ccdb::typed::CcdbTable` for a struct with named fields.
+///
+/// Each field is read from the column with the same name; `#[ccdb(column = "name")]` reads it
+/// from another column instead. Field types must implement `gluex_ccdb::typed::FromCell`.
+#[proc_macro_derive(CcdbTable, attributes(ccdb))]
+pub fn derive_ccdb_table(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(
+            input,
+            "CcdbTable can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(Error::new_spanned(
+            &data.fields,
+            "CcdbTable can only be derived for structs with named fields",
+        ));
+    };
+    let mut idents = Vec::new();
+    let mut columns = Vec::new();
+    for field in &fields.named {
+        let ident = field.ident.clone().expect("named fields have identifiers");
+        columns.push(column_name(field)?.unwrap_or_else(|| ident.to_string()));
+        idents.push(ident);
+    }
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::gluex_ccdb::typed::CcdbTable for #name #ty_generics #where_clause {
+            const COLUMNS: &'static [&'static str] = &[#(#columns),*];
+
+            fn from_row(
+                row: &::gluex_ccdb::data::RowView<'_>,
+            ) -> ::core::result::Result<Self, ::gluex_ccdb::data::CCDBDataError> {
+                ::core::result::Result::Ok(Self {
+                    #(#idents: ::gluex_ccdb::typed::cell(row, #columns)?,)*
+                })
+            }
+        }
+    })
+}
+
+/// Reads the column name from a field's `#[ccdb(column = "...")]` attribute, if it has one.
+fn column_name(field: &syn::Field) -> syn::Result<Option<String>> {
+    let mut column = None;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("ccdb"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("column") {
+                column = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else {
+                Err(meta.error("unsupported ccdb attribute, expected `column = \"...\"`"))
+            }
+        })?;
+    }
+    Ok(column)
+}
@@ -1,4 +1,26 @@
+import asyncio
+import os
+from collections.abc import Iterator
 from datetime import datetime
+from types import TracebackType
+from typing import Any
+
+import awkward as ak
+import numpy as np
+import numpy.typing as npt
+import pandas as pd
+
+class CCDBError(RuntimeError): ...
+class SqliteError(CCDBError): ...
+class DataError(CCDBError): ...
+class NotFoundError(CCDBError): ...
+class DirectoryNotFoundError(NotFoundError): ...
+class TableNotFoundError(NotFoundError): ...
+class VariationNotFoundError(NotFoundError): ...
+class InvalidPathError(CCDBError): ...
+class ParseError(CCDBError): ...
+class LimitError(CCDBError): ...
+class RunPeriodError(CCDBError): ...
 
 class ColumnType:
     @property
@@ -28,6 +50,42 @@ class TypeTableMeta:
     @property
     def comment(self) -> str: ...
 
+class VariationMeta:
+    @property
+    def id(self) -> int: ...
+    @property
+    def name(self) -> str: ...
+    @property
+    def description(self) -> str: ...
+    @property
+    def comment(self) -> str: ...
+    @property
+    def parent_id(self) -> int: ...
+    @property
+    def is_locked(self) -> bool: ...
+    @property
+    def is_deprecated(self) -> bool: ...
+    @property
+    def go_back_behavior(self) -> int: ...
+    @property
+    def created(self) -> datetime | None: ...
+
+class Request:
+    def __init__(self, request: str) -> None: ...
+    @property
+    def path(self) -> str: ...
+    @property
+    def runs(self) -> list[int]: ...
+    @property
+    def variation(self) -> str: ...
+    @property
+    def timestamp(self) -> datetime: ...
+    @property
+    def event(self) -> int | None: ...
+    @property
+    def flags(self) -> list[str]: ...
+    def has_flag(self, flag: str) -> bool: ...
+
 class Column:
     @property
     def name(self) -> str: ...
@@ -57,6 +115,22 @@ class Data:
     def row(self, row: int) -> RowView: ...
     def rows(self) -> list[RowView]: ...
     def value(self, column: int | str, row: int) -> object | None: ...
+    def to_numpy(self) -> dict[str, npt.NDArray[np.generic]]: ...
+    def to_structured(self) -> npt.NDArray[np.void]: ...
+    def __arrow_c_stream__(self, requested_schema: object | None = None) -> object: ...
+    def to_pandas(self) -> pd.DataFrame: ...
+    def to_awkward(self) -> ak.Array: ...
+    def __len__(self) -> int: ...
+    def __getitem__(self, row: int) -> dict[str, Any]: ...
+    def __iter__(self) -> DataIter: ...
+
+class DataIter(Iterator[dict[str, Any]]):
+    def __iter__(self) -> DataIter: ...
+    def __next__(self) -> dict[str, Any]: ...
+
+class FetchIter(Iterator[tuple[int, Data]]):
+    def __iter__(self) -> FetchIter: ...
+    def __next__(self) -> tuple[int, Data]: ...
 
 class TypeTableHandle:
     @property
@@ -64,6 +138,12 @@ class TypeTableHandle:
     @property
     def id(self) -> int: ...
     @property
+    def n_rows(self) -> int: ...
+    @property
+    def n_columns(self) -> int: ...
+    @property
+    def comment(self) -> str: ...
+    @property
     def meta(self) -> TypeTableMeta: ...
     def full_path(self) -> str: ...
     def columns(self) -> list[ColumnMeta]: ...
@@ -74,6 +154,14 @@ class TypeTableHandle:
         variation: str | None = None,
         timestamp: str | datetime | None = None,
     ) -> dict[int, Data]: ...
+    def fetch_iter(
+        self,
+        *,
+        runs: list[int] | None = None,
+        variation: str | None = None,
+        timestamp: str | datetime | None = None,
+        chunk_size: int = 1000,
+    ) -> FetchIter: ...
     def fetch_run_period(
         self,
         *,
@@ -84,20 +172,42 @@ class TypeTableHandle:
     ) -> dict[int, Data]: ...
 
 class DirectoryHandle:
+    @property
+    def name(self) -> str: ...
+    @property
+    def comment(self) -> str: ...
     def full_path(self) -> str: ...
     def parent(self) -> DirectoryHandle | None: ...
     def dirs(self) -> list[DirectoryHandle]: ...
     def dir(self, path: str) -> DirectoryHandle: ...
     def tables(self) -> list[TypeTableHandle]: ...
     def table(self, name: str) -> TypeTableHandle: ...
+    def ls(self) -> list[DirectoryHandle | TypeTableHandle]: ...
+    def tree(self, max_depth: int | None = None) -> str: ...
 
 class CCDB:
-    def __init__(self, path: str) -> None: ...
+    def __init__(self, path: str | os.PathLike[str]) -> None: ...
     @property
     def connection_path(self) -> str: ...
+    @property
+    def closed(self) -> bool: ...
+    def close(self) -> None: ...
+    def __enter__(self) -> CCDB: ...
+    def __exit__(
+        self,
+        exc_type: type[BaseException] | None,
+        exc_value: BaseException | None,
+        traceback: TracebackType | None,
+    ) -> bool: ...
     def dir(self, path: str) -> DirectoryHandle: ...
     def table(self, path: str) -> TypeTableHandle: ...
+    def variations(self) -> list[VariationMeta]: ...
+    def variation_children(self, id: int) -> list[VariationMeta]: ...
+    def variation_chain(self, name: str) -> list[VariationMeta]: ...
     def root(self) -> DirectoryHandle: ...
+    def ls(self, path: str = "/") -> list[DirectoryHandle | TypeTableHandle]: ...
+    def tree(self, path: str = "/", max_depth: int | None = None) -> str: ...
+    def request(self, request: str | Request) -> dict[int, Data]: ...
     def fetch(
         self,
         path: str,
@@ -106,6 +216,23 @@ class CCDB:
         variation: str | None = None,
         timestamp: str | datetime | None = None,
     ) -> dict[int, Data]: ...
+    def fetch_async(
+        self,
+        path: str,
+        *,
+        runs: list[int] | None = None,
+        variation: str | None = None,
+        timestamp: str | datetime | None = None,
+    ) -> asyncio.Future[dict[int, Data]]: ...
+    def fetch_iter(
+        self,
+        path: str,
+        *,
+        runs: list[int] | None = None,
+        variation: str | None = None,
+        timestamp: str | datetime | None = None,
+        chunk_size: int = 1000,
+    ) -> FetchIter: ...
     def fetch_run_period(
         self,
         path: str,
@@ -115,15 +242,47 @@ class CCDB:
         variation: str | None = None,
         timestamp: str | datetime | None = None,
     ) -> dict[int, Data]: ...
+    def fetch_dataframe(
+        self,
+        path: str,
+        *,
+        runs: list[int] | None = None,
+        variation: str | None = None,
+        timestamp: str | datetime | None = None,
+    ) -> pd.DataFrame: ...
+
+def fetch_dataframe(
+    connection: str | os.PathLike[str],
+    path: str,
+    *,
+    runs: list[int] | None = None,
+    variation: str | None = None,
+    timestamp: str | datetime | None = None,
+) -> pd.DataFrame: ...
 
 __all__ = [
     "CCDB",
+    "CCDBError",
+    "SqliteError",
+    "DataError",
+    "NotFoundError",
+    "DirectoryNotFoundError",
+    "TableNotFoundError",
+    "VariationNotFoundError",
+    "InvalidPathError",
+    "ParseError",
+    "LimitError",
+    "RunPeriodError",
     "Column",
     "ColumnMeta",
     "ColumnType",
     "Data",
+    "DataIter",
     "DirectoryHandle",
+    "FetchIter",
     "RowView",
     "TypeTableHandle",
     "TypeTableMeta",
+    "VariationMeta",
+    "fetch_dataframe",
 ]
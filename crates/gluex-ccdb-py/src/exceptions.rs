@@ -0,0 +1,121 @@
+//! Python exception hierarchy raised by the `gluex_ccdb` bindings.
+//!
+//! Every exception derives from [`CCDBError`], which itself subclasses `RuntimeError` so that
+//! existing `except RuntimeError` handlers keep working.
+
+use ::gluex_ccdb::CCDBError as RustCCDBError;
+use pyo3::{create_exception, exceptions::PyRuntimeError, prelude::*, PyErr};
+
+create_exception!(
+    gluex_ccdb,
+    CCDBError,
+    PyRuntimeError,
+    "Base class for all errors raised by gluex_ccdb."
+);
+create_exception!(
+    gluex_ccdb,
+    SqliteError,
+    CCDBError,
+    "The underlying SQLite database reported an error."
+);
+create_exception!(
+    gluex_ccdb,
+    DataError,
+    CCDBError,
+    "A stored payload could not be decoded or had an unexpected shape."
+);
+create_exception!(
+    gluex_ccdb,
+    NotFoundError,
+    CCDBError,
+    "Base class for lookups of directories, tables, or variations that do not exist."
+);
+create_exception!(
+    gluex_ccdb,
+    DirectoryNotFoundError,
+    NotFoundError,
+    "The requested directory does not exist."
+);
+create_exception!(
+    gluex_ccdb,
+    TableNotFoundError,
+    NotFoundError,
+    "The requested type table does not exist."
+);
+create_exception!(
+    gluex_ccdb,
+    VariationNotFoundError,
+    NotFoundError,
+    "The requested variation does not exist."
+);
+create_exception!(
+    gluex_ccdb,
+    InvalidPathError,
+    CCDBError,
+    "A table or directory path was malformed."
+);
+create_exception!(
+    gluex_ccdb,
+    ParseError,
+    CCDBError,
+    "A request string or timestamp could not be parsed."
+);
+create_exception!(
+    gluex_ccdb,
+    LimitError,
+    CCDBError,
+    "A fetch exceeded the handle's run, memory, or time limits."
+);
+create_exception!(
+    gluex_ccdb,
+    RunPeriodError,
+    CCDBError,
+    "A run period or REST version could not be resolved."
+);
+
+/// Converts a Rust [`RustCCDBError`] into the matching Python exception.
+pub(crate) fn to_py_err(err: RustCCDBError) -> PyErr {
+    let message = err.to_string();
+    match err {
+        RustCCDBError::SqliteError(_) => SqliteError::new_err(message),
+        RustCCDBError::CompressedSnapshotError(_)
+        | RustCCDBError::BundleError(_)
+        | RustCCDBError::NewerSchemaError(_)
+        | RustCCDBError::UnsupportedSourceError(_) => CCDBError::new_err(message),
+        RustCCDBError::CCDBDataError(_) => DataError::new_err(message),
+        RustCCDBError::DirectoryNotFoundError(_) => DirectoryNotFoundError::new_err(message),
+        RustCCDBError::TableNotFoundError(_) => TableNotFoundError::new_err(message),
+        RustCCDBError::InvalidPathError(_) => InvalidPathError::new_err(message),
+        RustCCDBError::VariationNotFoundError(_) => VariationNotFoundError::new_err(message),
+        RustCCDBError::ParseRequestError(_) | RustCCDBError::ParseTimestampError(_) => {
+            ParseError::new_err(message)
+        }
+        RustCCDBError::RestVersionError(_) | RustCCDBError::RunPeriodError(_) => {
+            RunPeriodError::new_err(message)
+        }
+        RustCCDBError::LimitError(_) => LimitError::new_err(message),
+    }
+}
+
+/// Registers every exception class on the `gluex_ccdb` module.
+pub(crate) fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add("CCDBError", py.get_type::<CCDBError>())?;
+    m.add("SqliteError", py.get_type::<SqliteError>())?;
+    m.add("DataError", py.get_type::<DataError>())?;
+    m.add("NotFoundError", py.get_type::<NotFoundError>())?;
+    m.add(
+        "DirectoryNotFoundError",
+        py.get_type::<DirectoryNotFoundError>(),
+    )?;
+    m.add("TableNotFoundError", py.get_type::<TableNotFoundError>())?;
+    m.add(
+        "VariationNotFoundError",
+        py.get_type::<VariationNotFoundError>(),
+    )?;
+    m.add("InvalidPathError", py.get_type::<InvalidPathError>())?;
+    m.add("ParseError", py.get_type::<ParseError>())?;
+    m.add("LimitError", py.get_type::<LimitError>())?;
+    m.add("RunPeriodError", py.get_type::<RunPeriodError>())?;
+    Ok(())
+}
@@ -1,22 +1,38 @@
 use ::gluex_ccdb::{
-    context::Context,
+    context::{Context, Request},
     data::{self, Data, Value},
     database::{DirectoryHandle, TypeTableHandle, CCDB},
-    models::{ColumnMeta, ColumnType, TypeTableMeta},
+    models::{ColumnMeta, ColumnType, TypeTableMeta, VariationMeta},
+    stream::FetchIter,
     CCDBError,
 };
 use chrono::{DateTime, Utc};
+#[cfg(feature = "arrow")]
+use gluex_core::arrow::{ArrowArrayStream, ArrowColumn, ArrowValues};
 use gluex_core::{parsers::parse_timestamp, run_periods::RunPeriodError, RunNumber};
+use numpy::{ndarray::ArrayView1, PyArray1, PyArrayMethods};
+#[cfg(feature = "arrow")]
+use pyo3::types::PyCapsule;
 use pyo3::{
     conversion::IntoPyObject,
-    exceptions::PyRuntimeError,
+    exceptions::{PyIndexError, PyRuntimeError},
     prelude::*,
-    types::{PyFloat, PyInt, PyModule, PyString},
+    types::{PyDict, PyFloat, PyInt, PyModule, PyString, PyTuple},
 };
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+    collections::BTreeMap,
+    iter,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+mod exceptions;
 
 fn py_ccdb_error(err: CCDBError) -> PyErr {
-    PyRuntimeError::new_err(err.to_string())
+    exceptions::to_py_err(err)
 }
 
 /// Column type describing how a CCDB column is stored.
@@ -100,7 +116,7 @@ impl PyColumnMeta {
 ///     Column name as recorded in CCDB metadata.
 /// column_type : ColumnType
 ///     Storage type of the column values.
-#[pyclass(name = "Column", module = "gluex_ccdb", unsendable)]
+#[pyclass(name = "Column", module = "gluex_ccdb")]
 pub struct PyColumn {
     name: String,
     column_type: ColumnType,
@@ -239,6 +255,184 @@ impl PyTypeTableMeta {
     }
 }
 
+/// Metadata describing a CCDB variation.
+///
+/// Variations form a tree: lookups in a variation fall back to its parent (and so on up to
+/// ``"default"``) for runs without an assignment of their own.
+#[pyclass(name = "VariationMeta", module = "gluex_ccdb", frozen)]
+#[derive(Clone)]
+pub struct PyVariationMeta {
+    inner: VariationMeta,
+}
+
+#[pymethods]
+impl PyVariationMeta {
+    /// int: Identifier of the variation row.
+    #[getter]
+    fn id(&self) -> i64 {
+        self.inner.id()
+    }
+    /// str: Variation name.
+    #[getter]
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+    /// str: Descriptive text for the variation.
+    #[getter]
+    fn description(&self) -> &str {
+        self.inner.description()
+    }
+    /// str: Free-form comment associated with the variation.
+    #[getter]
+    fn comment(&self) -> &str {
+        self.inner.comment()
+    }
+    /// int: Identifier of the parent variation (``0`` for the root).
+    #[getter]
+    fn parent_id(&self) -> i64 {
+        self.inner.parent_id()
+    }
+    /// bool: True when the variation is locked.
+    #[getter]
+    fn is_locked(&self) -> bool {
+        self.inner.is_locked()
+    }
+    /// bool: True when the variation is deprecated.
+    #[getter]
+    fn is_deprecated(&self) -> bool {
+        self.inner.is_deprecated()
+    }
+    /// int: Behavior flag defining how lookups walk parent variations.
+    #[getter]
+    fn go_back_behavior(&self) -> i64 {
+        self.inner.go_back_behavior()
+    }
+    /// datetime.datetime | None: Creation time, or ``None`` if it cannot be parsed.
+    #[getter]
+    fn created(&self) -> Option<DateTime<Utc>> {
+        self.inner.created().ok()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "VariationMeta(name='{}', id={}, parent_id={})",
+            self.inner.name(),
+            self.inner.id(),
+            self.inner.parent_id()
+        )
+    }
+}
+
+/// Parsed CCDB request string.
+///
+/// Request strings follow the ``ccdb`` command-line grammar
+/// ``/path[:run[:variation[:time]]][:key=value...][:flag...]``: positional fields may be left
+/// empty, named ``run=``, ``event=``, ``variation=``, and ``time=`` options may follow in any
+/// order, and trailing bare words are collected as flags.
+///
+/// Parameters
+/// ----------
+/// request : str
+///     Request string, for example ``"/TARGET/density:30274:variation=mc:event=17"``.
+///
+/// Raises
+/// ------
+/// ParseError
+///     If the request string cannot be parsed.
+#[pyclass(name = "Request", module = "gluex_ccdb", frozen)]
+#[derive(Clone)]
+pub struct PyRequest {
+    inner: Request,
+}
+
+#[pymethods]
+impl PyRequest {
+    /// __init__(self, request)
+    ///
+    /// Parameters
+    /// ----------
+    /// request : str
+    ///     Request string to parse.
+    #[new]
+    fn new(request: &str) -> PyResult<Self> {
+        Ok(Self {
+            inner: request
+                .parse()
+                .map_err(|err| py_ccdb_error(CCDBError::from(err)))?,
+        })
+    }
+    /// str: Absolute path of the requested table.
+    #[getter]
+    fn path(&self) -> &str {
+        self.inner.path.full_path()
+    }
+    /// list[int]: Runs to fetch (``[0]`` when no run was given).
+    #[getter]
+    fn runs(&self) -> Vec<RunNumber> {
+        self.inner.context.runs.clone()
+    }
+    /// str: Variation to resolve (``"default"`` when none was given).
+    #[getter]
+    fn variation(&self) -> &str {
+        &self.inner.context.variation
+    }
+    /// datetime.datetime: Timestamp used to select assignments (now when none was given).
+    #[getter]
+    fn timestamp(&self) -> DateTime<Utc> {
+        self.inner.context.timestamp
+    }
+    /// int | None: Event number within the run, if given.
+    #[getter]
+    fn event(&self) -> Option<u64> {
+        self.inner.event
+    }
+    /// list[str]: Trailing flags in the order given, without duplicates.
+    #[getter]
+    fn flags(&self) -> Vec<String> {
+        self.inner.flags.clone()
+    }
+    /// has_flag(self, flag)
+    ///
+    /// Parameters
+    /// ----------
+    /// flag : str
+    ///     Flag to look for.
+    ///
+    /// Returns
+    /// -------
+    /// bool
+    ///     True when ``flag`` was given as a trailing flag.
+    fn has_flag(&self, flag: &str) -> bool {
+        self.inner.has_flag(flag)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Request(path='{}', runs={:?}, variation='{}', event={}, flags={:?})",
+            self.inner.path.full_path(),
+            self.inner.context.runs,
+            self.inner.context.variation,
+            self.inner
+                .event
+                .map_or_else(|| "None".to_string(), |event| event.to_string()),
+            self.inner.flags
+        )
+    }
+}
+
+#[cfg(feature = "arrow")]
+fn column_to_arrow(column: &data::Column) -> ArrowValues {
+    match column {
+        data::Column::Int(values) => ArrowValues::Int32(values.clone()),
+        data::Column::UInt(values) => ArrowValues::UInt32(values.clone()),
+        data::Column::Long(values) => ArrowValues::Int64(values.clone()),
+        data::Column::ULong(values) => ArrowValues::UInt64(values.clone()),
+        data::Column::Double(values) => ArrowValues::Float64(values.clone()),
+        data::Column::Bool(values) => ArrowValues::Boolean(values.clone()),
+        data::Column::String(values) => ArrowValues::Utf8(values.clone()),
+    }
+}
+
 /// Column-major dataset returned from CCDB fetch operations.
 ///
 /// Attributes
@@ -251,7 +445,13 @@ impl PyTypeTableMeta {
 ///     Names for each column in positional order.
 /// column_types : list[ColumnType]
 ///     Storage type for each column in positional order.
-#[pyclass(name = "Data", module = "gluex_ccdb", unsendable)]
+///
+/// Notes
+/// -----
+/// ``Data`` behaves as a sequence of rows: ``len(data)`` is the row count, ``data[i]`` returns
+/// row ``i`` (negative indices count from the end) as a ``dict`` keyed by column name, and
+/// iterating yields those dictionaries in row order.
+#[pyclass(name = "Data", module = "gluex_ccdb")]
 pub struct PyData {
     inner: Arc<Data>,
 }
@@ -384,6 +584,139 @@ impl PyData {
         }
     }
 
+    /// to_numpy(self)
+    ///
+    /// Returns
+    /// -------
+    /// dict[str, numpy.ndarray]
+    ///     One 1-D array per column keyed by column name in positional order. Double columns
+    ///     are read-only views of the fetched data (no copy is made), other numeric and boolean
+    ///     columns are copied into arrays of the matching dtype, and string columns are returned
+    ///     as object arrays.
+    pub fn to_numpy<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyDict>> {
+        let py = slf.py();
+        let data = Arc::clone(&slf.borrow().inner);
+        let arrays = PyDict::new(py);
+        for (idx, name) in data.column_names().iter().enumerate() {
+            let column = data
+                .column(idx)
+                .ok_or_else(|| PyRuntimeError::new_err("column index out of range"))?;
+            arrays.set_item(name, column_to_numpy(slf.as_any(), column)?)?;
+        }
+        Ok(arrays)
+    }
+
+    /// to_structured(self)
+    ///
+    /// Returns
+    /// -------
+    /// numpy.ndarray
+    ///     1-D structured array with one record per row and one field per column, named and
+    ///     typed as in ``to_numpy``. Each field is filled from its column array in a single
+    ///     vectorized assignment, so the result owns a copy of the data.
+    pub fn to_structured<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyAny>> {
+        let py = slf.py();
+        let arrays = Self::to_numpy(slf)?;
+        let mut fields = Vec::with_capacity(arrays.len());
+        for (name, array) in arrays.iter() {
+            fields.push(PyTuple::new(py, [name, array.getattr("dtype")?])?);
+        }
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("dtype", fields)?;
+        let records = py.import("numpy")?.call_method(
+            "empty",
+            (slf.borrow().inner.n_rows(),),
+            Some(&kwargs),
+        )?;
+        for (name, array) in arrays.iter() {
+            records.set_item(name, array)?;
+        }
+        Ok(records)
+    }
+
+    /// __arrow_c_stream__(self, requested_schema=None)
+    ///
+    /// Export the dataset through the Arrow PyCapsule interface.
+    ///
+    /// Parameters
+    /// ----------
+    /// requested_schema : object, optional
+    ///     Ignored; the columns are always exported with their native types.
+    ///
+    /// Returns
+    /// -------
+    /// PyCapsule
+    ///     ``arrow_array_stream`` capsule yielding one record batch with a column per CCDB
+    ///     column, so ``pyarrow.table(data)`` or ``polars.DataFrame(data)`` work without pandas.
+    ///
+    /// Notes
+    /// -----
+    /// Only available when built with the ``arrow`` feature.
+    #[cfg(feature = "arrow")]
+    #[pyo3(signature = (requested_schema=None))]
+    pub fn __arrow_c_stream__<'py>(
+        &self,
+        py: Python<'py>,
+        requested_schema: Option<Bound<'py, PyAny>>,
+    ) -> PyResult<Bound<'py, PyCapsule>> {
+        let _ = requested_schema;
+        let columns = self
+            .inner
+            .iter_columns()
+            .map(|(name, _, column)| ArrowColumn::new(name, column_to_arrow(column)))
+            .collect();
+        PyCapsule::new_with_destructor(
+            py,
+            ArrowArrayStream::new(columns),
+            Some(c"arrow_array_stream".to_owned()),
+            |_, _| {},
+        )
+    }
+
+    /// to_pandas(self)
+    ///
+    /// Returns
+    /// -------
+    /// pandas.DataFrame
+    ///     DataFrame with one column per CCDB column, named and typed as in ``to_numpy``.
+    pub fn to_pandas<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyAny>> {
+        slf.py()
+            .import("pandas")?
+            .call_method1("DataFrame", (Self::to_numpy(slf)?,))
+    }
+
+    /// to_awkward(self)
+    ///
+    /// Returns
+    /// -------
+    /// awkward.Array
+    ///     Record array with one entry per row and one field per column. Numeric and boolean
+    ///     fields are built from the arrays returned by ``to_numpy``; string fields are
+    ///     awkward string arrays.
+    ///
+    /// Notes
+    /// -----
+    /// Requires the ``awkward`` extra.
+    pub fn to_awkward<'py>(slf: &Bound<'py, Self>) -> PyResult<Bound<'py, PyAny>> {
+        let py = slf.py();
+        let ak = py.import("awkward")?;
+        let data = Arc::clone(&slf.borrow().inner);
+        let fields = PyDict::new(py);
+        for (idx, name) in data.column_names().iter().enumerate() {
+            let column = data
+                .column(idx)
+                .ok_or_else(|| PyRuntimeError::new_err("column index out of range"))?;
+            let field = match column {
+                data::Column::String(values) => ak.call_method1("Array", (values.clone(),))?,
+                _ => ak.call_method1("from_numpy", (column_to_numpy(slf.as_any(), column)?,))?,
+            };
+            fields.set_item(name, field)?;
+        }
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("depth_limit", 1)?;
+        ak.call_method("zip", (fields,), Some(&kwargs))
+    }
+
     fn __repr__(&self) -> String {
         let cols: Vec<String> = self
             .inner
@@ -399,6 +732,51 @@ impl PyData {
             cols.join(", ")
         )
     }
+
+    fn __len__(&self) -> usize {
+        self.inner.n_rows()
+    }
+
+    fn __getitem__<'py>(&self, py: Python<'py>, row: isize) -> PyResult<Bound<'py, PyDict>> {
+        let n_rows = self.inner.n_rows();
+        let idx = if row < 0 {
+            n_rows.checked_sub(row.unsigned_abs())
+        } else {
+            Some(row.unsigned_abs()).filter(|&idx| idx < n_rows)
+        }
+        .ok_or_else(|| PyIndexError::new_err("row index out of range"))?;
+        row_to_dict(py, &self.inner, idx)
+    }
+
+    fn __iter__(&self) -> PyDataIter {
+        PyDataIter {
+            data: Arc::clone(&self.inner),
+            next_row: 0,
+        }
+    }
+}
+
+/// Iterator over the rows of a :class:`Data` object, yielding one ``dict`` per row.
+#[pyclass(name = "DataIter", module = "gluex_ccdb")]
+pub struct PyDataIter {
+    data: Arc<Data>,
+    next_row: usize,
+}
+
+#[pymethods]
+impl PyDataIter {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__<'py>(&mut self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDict>>> {
+        if self.next_row >= self.data.n_rows() {
+            return Ok(None);
+        }
+        let row = row_to_dict(py, &self.data, self.next_row)?;
+        self.next_row += 1;
+        Ok(Some(row))
+    }
 }
 
 /// Lightweight view of a single row in a CCDB result set.
@@ -497,9 +875,10 @@ impl PyRowView {
 ///     Unique table identifier in CCDB.
 /// meta : TypeTableMeta
 ///     Metadata describing row/column counts and comments.
-#[pyclass(name = "TypeTableHandle", module = "gluex_ccdb", unsendable)]
+#[pyclass(name = "TypeTableHandle", module = "gluex_ccdb")]
 pub struct PyTypeTableHandle {
     inner: TypeTableHandle,
+    closed: Closed,
 }
 
 #[pymethods]
@@ -514,6 +893,21 @@ impl PyTypeTableHandle {
     pub fn id(&self) -> i64 {
         self.inner.id()
     }
+    /// int: Number of rows in each dataset of this table.
+    #[getter]
+    pub fn n_rows(&self) -> i64 {
+        self.inner.meta().n_rows()
+    }
+    /// int: Number of columns in this table.
+    #[getter]
+    pub fn n_columns(&self) -> i64 {
+        self.inner.meta().n_columns()
+    }
+    /// str: Free-form comment attached to the table.
+    #[getter]
+    pub fn comment(&self) -> &str {
+        self.inner.meta().comment()
+    }
     /// TypeTableMeta: Metadata such as row counts and comments.
     #[getter]
     pub fn meta(&self) -> PyTypeTableMeta {
@@ -531,10 +925,10 @@ impl PyTypeTableHandle {
     /// -------
     /// list[ColumnMeta]
     ///     Metadata for each column in order.
-    pub fn columns(&self) -> PyResult<Vec<PyColumnMeta>> {
-        Ok(self
-            .inner
-            .columns()
+    pub fn columns(&self, py: Python<'_>) -> PyResult<Vec<PyColumnMeta>> {
+        self.closed.check()?;
+        Ok(py
+            .detach(|| self.inner.columns())
             .map_err(py_ccdb_error)?
             .into_iter()
             .map(|m| PyColumnMeta { inner: m })
@@ -558,14 +952,15 @@ impl PyTypeTableHandle {
     #[pyo3(signature = (*, runs=None, variation=None, timestamp=None))]
     pub fn fetch(
         &self,
+        py: Python<'_>,
         runs: Option<Vec<RunNumber>>,
         variation: Option<String>,
         timestamp: Option<Bound<'_, PyAny>>,
     ) -> PyResult<BTreeMap<RunNumber, PyData>> {
+        self.closed.check()?;
         let ctx = build_context(runs, variation, timestamp)?;
-        Ok(self
-            .inner
-            .fetch(&ctx)
+        Ok(py
+            .detach(|| self.inner.fetch(&ctx))
             .map_err(py_ccdb_error)?
             .into_iter()
             .map(|(run, data)| {
@@ -579,6 +974,44 @@ impl PyTypeTableHandle {
             .collect())
     }
 
+    /// fetch_iter(self, *, runs=None, variation=None, timestamp=None, chunk_size=1000)
+    ///
+    /// Parameters
+    /// ----------
+    /// runs : list[int] | None, optional
+    ///     Run numbers to query; defaults to run 0 when omitted.
+    /// variation : str | None, optional
+    ///     Variation branch to resolve (default "default").
+    /// timestamp : datetime | str | None, optional
+    ///     Timestamp used to select historical assignments.
+    /// chunk_size : int, default 1000
+    ///     Number of runs loaded from the database at a time.
+    ///
+    /// Returns
+    /// -------
+    /// FetchIter
+    ///     Iterator yielding ``(run, Data)`` pairs in run order.
+    ///
+    /// Notes
+    /// -----
+    /// Datasets are loaded ``chunk_size`` runs at a time (with the GIL released) as the
+    /// iterator advances, so only one chunk is held in memory at once.
+    #[pyo3(signature = (*, runs=None, variation=None, timestamp=None, chunk_size=1000))]
+    pub fn fetch_iter(
+        &self,
+        runs: Option<Vec<RunNumber>>,
+        variation: Option<String>,
+        timestamp: Option<Bound<'_, PyAny>>,
+        chunk_size: usize,
+    ) -> PyResult<PyFetchIter> {
+        PyFetchIter::new(
+            &self.inner,
+            &build_context(runs, variation, timestamp)?,
+            chunk_size,
+            &self.closed,
+        )
+    }
+
     /// fetch_run_period(self, *, run_period, rest_version=None, variation=None, timestamp=None)
     ///
     /// Parameters
@@ -599,11 +1032,13 @@ impl PyTypeTableHandle {
     #[pyo3(signature = (*, run_period, rest_version=None, variation=None, timestamp=None))]
     pub fn fetch_run_period(
         &self,
+        py: Python<'_>,
         run_period: &str,
         rest_version: Option<usize>,
         variation: Option<String>,
         timestamp: Option<Bound<'_, PyAny>>,
     ) -> PyResult<BTreeMap<RunNumber, PyData>> {
+        self.closed.check()?;
         let mut ctx = Context::default()
             .with_run_period(
                 run_period
@@ -618,9 +1053,8 @@ impl PyTypeTableHandle {
         if let Some(ts) = parse_py_timestamp(timestamp)? {
             ctx.timestamp = ts;
         }
-        Ok(self
-            .inner
-            .fetch(&ctx)
+        Ok(py
+            .detach(|| self.inner.fetch(&ctx))
             .map_err(py_ccdb_error)?
             .into_iter()
             .map(|(run, data)| {
@@ -642,19 +1076,96 @@ impl PyTypeTableHandle {
     }
 }
 
+/// Lazy iterator over per-run datasets of a CCDB table.
+///
+/// Returned by :meth:`CCDB.fetch_iter` and :meth:`TypeTableHandle.fetch_iter`; yields
+/// ``(run, Data)`` pairs.
+#[pyclass(name = "FetchIter", module = "gluex_ccdb")]
+pub struct PyFetchIter {
+    inner: FetchIter,
+    closed: Closed,
+}
+
+impl PyFetchIter {
+    fn new(
+        table: &TypeTableHandle,
+        context: &Context,
+        chunk_size: usize,
+        closed: &Closed,
+    ) -> PyResult<Self> {
+        closed.check()?;
+        if chunk_size == 0 {
+            return Err(PyRuntimeError::new_err("chunk_size must be positive"));
+        }
+        Ok(Self {
+            inner: table.fetch_iter(context).with_chunk_size(chunk_size),
+            closed: closed.clone(),
+        })
+    }
+}
+
+#[pymethods]
+impl PyFetchIter {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<(RunNumber, PyData)>> {
+        self.closed.check()?;
+        let inner = &mut self.inner;
+        let Some(entry) = py.detach(|| inner.next()) else {
+            return Ok(None);
+        };
+        let (run, data) = entry.map_err(py_ccdb_error)?;
+        Ok(Some((
+            run,
+            PyData {
+                inner: Arc::new(data),
+            },
+        )))
+    }
+}
+
 /// Handle to a CCDB directory, mirroring the Rust API for navigation.
 ///
 /// Attributes
 /// ----------
 /// full_path : str
 ///     Absolute directory path within CCDB.
-#[pyclass(name = "DirectoryHandle", module = "gluex_ccdb", unsendable)]
+#[pyclass(name = "DirectoryHandle", module = "gluex_ccdb")]
 pub struct PyDirectoryHandle {
     inner: DirectoryHandle,
+    closed: Closed,
+}
+
+impl PyDirectoryHandle {
+    fn wrap_dir(&self, inner: DirectoryHandle) -> Self {
+        Self {
+            inner,
+            closed: self.closed.clone(),
+        }
+    }
+
+    fn wrap_table(&self, inner: TypeTableHandle) -> PyTypeTableHandle {
+        PyTypeTableHandle {
+            inner,
+            closed: self.closed.clone(),
+        }
+    }
 }
 
 #[pymethods]
 impl PyDirectoryHandle {
+    /// str: Directory name (without parent components).
+    #[getter]
+    pub fn name(&self) -> &str {
+        self.inner.meta().name()
+    }
+    /// str: Free-form comment attached to the directory.
+    #[getter]
+    pub fn comment(&self) -> &str {
+        self.inner.meta().comment()
+    }
     /// str: Full path of this directory.
     pub fn full_path(&self) -> String {
         self.inner.full_path()
@@ -666,7 +1177,7 @@ impl PyDirectoryHandle {
     /// DirectoryHandle | None
     ///     Parent directory or ``None`` when at the root.
     pub fn parent(&self) -> Option<Self> {
-        self.inner.parent().map(|inner| Self { inner })
+        self.inner.parent().map(|inner| self.wrap_dir(inner))
     }
     /// dirs(self)
     ///
@@ -678,7 +1189,7 @@ impl PyDirectoryHandle {
         self.inner
             .dirs()
             .into_iter()
-            .map(|inner| Self { inner })
+            .map(|inner| self.wrap_dir(inner))
             .collect()
     }
     /// dir(self, name)
@@ -693,9 +1204,7 @@ impl PyDirectoryHandle {
     /// DirectoryHandle
     ///     Handle to the requested subdirectory.
     pub fn dir(&self, name: &str) -> PyResult<Self> {
-        Ok(Self {
-            inner: self.inner.dir(name).map_err(py_ccdb_error)?,
-        })
+        Ok(self.wrap_dir(self.inner.dir(name).map_err(py_ccdb_error)?))
     }
     /// tables(self)
     ///
@@ -707,7 +1216,7 @@ impl PyDirectoryHandle {
         self.inner
             .tables()
             .into_iter()
-            .map(|inner| PyTypeTableHandle { inner })
+            .map(|inner| self.wrap_table(inner))
             .collect()
     }
     /// table(self, name)
@@ -722,9 +1231,43 @@ impl PyDirectoryHandle {
     /// TypeTableHandle
     ///     Handle to the requested table.
     pub fn table(&self, name: &str) -> PyResult<PyTypeTableHandle> {
-        Ok(PyTypeTableHandle {
-            inner: self.inner.table(name).map_err(py_ccdb_error)?,
-        })
+        Ok(self.wrap_table(self.inner.table(name).map_err(py_ccdb_error)?))
+    }
+    /// ls(self)
+    ///
+    /// Returns
+    /// -------
+    /// list[DirectoryHandle | TypeTableHandle]
+    ///     Child directories followed by tables directly under this directory, each sorted by name.
+    pub fn ls(&self, py: Python<'_>) -> PyResult<Vec<Py<PyAny>>> {
+        let (dirs, tables) = sorted_children(&self.inner);
+        let mut entries = Vec::with_capacity(dirs.len() + tables.len());
+        for inner in dirs {
+            entries.push(Py::new(py, self.wrap_dir(inner))?.into_any());
+        }
+        for inner in tables {
+            entries.push(Py::new(py, self.wrap_table(inner))?.into_any());
+        }
+        Ok(entries)
+    }
+    /// tree(self, max_depth=None)
+    ///
+    /// Parameters
+    /// ----------
+    /// max_depth : int | None, optional
+    ///     Maximum number of directory levels to descend (unlimited when omitted).
+    ///
+    /// Returns
+    /// -------
+    /// str
+    ///     Text rendering of the directory tree. Tables are annotated with their
+    ///     ``rows x columns`` shape and comment.
+    #[pyo3(signature = (max_depth=None))]
+    pub fn tree(&self, max_depth: Option<usize>) -> String {
+        let mut out = self.inner.full_path();
+        out.push('\n');
+        render_tree(&self.inner, "", 0, max_depth, &mut out);
+        out
     }
     fn __repr__(&self) -> String {
         format!("Directory(\"{}\")", self.full_path())
@@ -738,11 +1281,41 @@ impl PyDirectoryHandle {
 ///
 /// Parameters
 /// ----------
-/// path : str
+/// path : str or os.PathLike
 ///     Filesystem path to an existing CCDB SQLite database file.
-#[pyclass(name = "CCDB", module = "gluex_ccdb", unsendable)]
+#[pyclass(name = "CCDB", module = "gluex_ccdb")]
 pub struct PyCCDB {
-    inner: CCDB,
+    inner: Option<CCDB>,
+    connection_path: String,
+    closed: Closed,
+}
+
+/// Flag set by [`PyCCDB::close`] and shared with every handle and iterator derived from the
+/// database, so they stop querying once it is closed.
+#[derive(Clone, Default)]
+struct Closed(Arc<AtomicBool>);
+
+impl Closed {
+    fn close(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+
+    fn check(&self) -> PyResult<()> {
+        if self.0.load(Ordering::Acquire) {
+            Err(exceptions::CCDBError::new_err("CCDB handle is closed"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl PyCCDB {
+    /// Returns the open database, or an error if the handle has been closed.
+    fn db(&self) -> PyResult<&CCDB> {
+        self.inner
+            .as_ref()
+            .ok_or_else(|| exceptions::CCDBError::new_err("CCDB handle is closed"))
+    }
 }
 
 #[pymethods]
@@ -751,12 +1324,15 @@ impl PyCCDB {
     ///
     /// Parameters
     /// ----------
-    /// path : str
+    /// path : str or os.PathLike
     ///     Filesystem path to an existing CCDB SQLite database file.
     #[new]
-    pub fn new(path: &str) -> PyResult<Self> {
+    pub fn new(py: Python<'_>, path: PathBuf) -> PyResult<Self> {
+        let inner = py.detach(|| CCDB::open(path)).map_err(py_ccdb_error)?;
         Ok(Self {
-            inner: CCDB::open(path).map_err(py_ccdb_error)?,
+            connection_path: inner.connection_path().to_string(),
+            inner: Some(inner),
+            closed: Closed::default(),
         })
     }
 
@@ -773,7 +1349,8 @@ impl PyCCDB {
     ///     Handle to the requested directory.
     pub fn dir(&self, path: &str) -> PyResult<PyDirectoryHandle> {
         Ok(PyDirectoryHandle {
-            inner: self.inner.dir(path).map_err(py_ccdb_error)?,
+            inner: self.db()?.dir(path).map_err(py_ccdb_error)?,
+            closed: self.closed.clone(),
         })
     }
     /// table(self, path)
@@ -789,9 +1366,75 @@ impl PyCCDB {
     ///     Handle to the requested table.
     pub fn table(&self, path: &str) -> PyResult<PyTypeTableHandle> {
         Ok(PyTypeTableHandle {
-            inner: self.inner.table(path).map_err(py_ccdb_error)?,
+            inner: self.db()?.table(path).map_err(py_ccdb_error)?,
+            closed: self.closed.clone(),
         })
     }
+    /// variations(self)
+    ///
+    /// Returns
+    /// -------
+    /// list[VariationMeta]
+    ///     Every variation in the database ordered by identifier.
+    pub fn variations(&self, py: Python<'_>) -> PyResult<Vec<PyVariationMeta>> {
+        let db = self.db()?;
+        let variations = py.detach(|| db.variations()).map_err(py_ccdb_error)?;
+        Ok(variations
+            .into_iter()
+            .map(|inner| PyVariationMeta { inner })
+            .collect())
+    }
+    /// variation_children(self, id)
+    ///
+    /// Parameters
+    /// ----------
+    /// id : int
+    ///     Identifier of the parent variation (see ``VariationMeta.id``).
+    ///
+    /// Returns
+    /// -------
+    /// list[VariationMeta]
+    ///     The variations directly below it, ordered by identifier.
+    pub fn variation_children(&self, py: Python<'_>, id: i64) -> PyResult<Vec<PyVariationMeta>> {
+        let db = self.db()?;
+        let children = py
+            .detach(|| db.variation_children(id))
+            .map_err(py_ccdb_error)?;
+        Ok(children
+            .into_iter()
+            .map(|inner| PyVariationMeta { inner })
+            .collect())
+    }
+    /// variation_chain(self, name)
+    ///
+    /// Parameters
+    /// ----------
+    /// name : str
+    ///     Variation to start from.
+    ///
+    /// Returns
+    /// -------
+    /// list[VariationMeta]
+    ///     The variation followed by each of its ancestors up to the root, in the order used to
+    ///     resolve assignments.
+    ///
+    /// Raises
+    /// ------
+    /// VariationNotFoundError
+    ///     If the variation does not exist.
+    pub fn variation_chain(&self, py: Python<'_>, name: &str) -> PyResult<Vec<PyVariationMeta>> {
+        let db = self.db()?;
+        let chain = py
+            .detach(|| {
+                let start = db.variation(name)?;
+                db.variation_chain(&start)
+            })
+            .map_err(py_ccdb_error)?;
+        Ok(chain
+            .into_iter()
+            .map(|inner| PyVariationMeta { inner })
+            .collect())
+    }
     /// fetch(self, path, *, runs=None, variation=None, timestamp=None)
     ///
     /// Parameters
@@ -812,15 +1455,61 @@ impl PyCCDB {
     #[pyo3(signature = (path, *, runs=None, variation=None, timestamp=None))]
     pub fn fetch(
         &self,
+        py: Python<'_>,
         path: &str,
         runs: Option<Vec<RunNumber>>,
         variation: Option<String>,
         timestamp: Option<Bound<'_, PyAny>>,
     ) -> PyResult<BTreeMap<RunNumber, PyData>> {
         let ctx = build_context(runs, variation, timestamp)?;
-        Ok(self
-            .inner
-            .fetch(path, &ctx)
+        let db = self.db()?;
+        Ok(py
+            .detach(|| db.fetch(path, &ctx))
+            .map_err(py_ccdb_error)?
+            .into_iter()
+            .map(|(run, data)| {
+                (
+                    run,
+                    PyData {
+                        inner: Arc::new(data),
+                    },
+                )
+            })
+            .collect())
+    }
+
+    /// request(self, request)
+    ///
+    /// Parameters
+    /// ----------
+    /// request : str | Request
+    ///     CCDB request string of the form ``"/path:run:variation:timestamp"``, as accepted by
+    ///     the ``ccdb`` command-line tool, or an already parsed :class:`Request`. Trailing fields
+    ///     may be omitted or left empty, for example ``"/PHOTON_BEAM/endpoint_energy::mc"``; see
+    ///     :class:`Request` for named options and flags.
+    ///
+    /// Returns
+    /// -------
+    /// dict[int, Data]
+    ///     Mapping of run number to fetched dataset.
+    ///
+    /// Raises
+    /// ------
+    /// RuntimeError
+    ///     If the request string cannot be parsed or the table does not exist.
+    pub fn request(
+        &self,
+        py: Python<'_>,
+        request: Bound<'_, PyAny>,
+    ) -> PyResult<BTreeMap<RunNumber, PyData>> {
+        let request = if let Ok(request) = request.cast::<PyRequest>() {
+            request.get().inner.clone()
+        } else {
+            PyRequest::new(&request.extract::<String>()?)?.inner
+        };
+        let db = self.db()?;
+        Ok(py
+            .detach(|| db.fetch(request.path.full_path(), &request.context))
             .map_err(py_ccdb_error)?
             .into_iter()
             .map(|(run, data)| {
@@ -834,6 +1523,73 @@ impl PyCCDB {
             .collect())
     }
 
+    /// fetch_async(self, path, *, runs=None, variation=None, timestamp=None)
+    ///
+    /// Awaitable variant of :meth:`CCDB.fetch`.
+    ///
+    /// Parameters
+    /// ----------
+    /// Same as :meth:`CCDB.fetch`.
+    ///
+    /// Returns
+    /// -------
+    /// asyncio.Future[dict[int, Data]]
+    ///     Future resolving to the same mapping returned by :meth:`CCDB.fetch`.
+    ///
+    /// Notes
+    /// -----
+    /// The query runs on the event loop's default executor with the GIL released, so the
+    /// event loop is not blocked. Must be called while an event loop is running.
+    #[pyo3(signature = (*args, **kwargs))]
+    pub fn fetch_async<'py>(
+        slf: &Bound<'py, Self>,
+        args: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        run_in_executor(slf.py(), slf.getattr("fetch")?, args, kwargs)
+    }
+
+    /// fetch_iter(self, path, *, runs=None, variation=None, timestamp=None, chunk_size=1000)
+    ///
+    /// Parameters
+    /// ----------
+    /// path : str
+    ///     Absolute or relative table path.
+    /// runs : list[int] | None, optional
+    ///     Run numbers to query; defaults to run 0 when omitted.
+    /// variation : str | None, optional
+    ///     Variation branch to resolve (default "default").
+    /// timestamp : datetime | str | None, optional
+    ///     Timestamp used to select historical assignments.
+    /// chunk_size : int, default 1000
+    ///     Number of runs loaded from the database at a time.
+    ///
+    /// Returns
+    /// -------
+    /// FetchIter
+    ///     Iterator yielding ``(run, Data)`` pairs in run order.
+    ///
+    /// Notes
+    /// -----
+    /// Datasets are loaded ``chunk_size`` runs at a time (with the GIL released) as the
+    /// iterator advances, so only one chunk is held in memory at once.
+    #[pyo3(signature = (path, *, runs=None, variation=None, timestamp=None, chunk_size=1000))]
+    pub fn fetch_iter(
+        &self,
+        path: &str,
+        runs: Option<Vec<RunNumber>>,
+        variation: Option<String>,
+        timestamp: Option<Bound<'_, PyAny>>,
+        chunk_size: usize,
+    ) -> PyResult<PyFetchIter> {
+        PyFetchIter::new(
+            &self.db()?.table(path).map_err(py_ccdb_error)?,
+            &build_context(runs, variation, timestamp)?,
+            chunk_size,
+            &self.closed,
+        )
+    }
+
     /// fetch_run_period(self, path, *, run_period, rest_version=None, variation=None, timestamp=None)
     ///
     /// Parameters
@@ -856,6 +1612,7 @@ impl PyCCDB {
     #[pyo3(signature = (path, *, run_period, rest_version=None, variation=None, timestamp=None))]
     pub fn fetch_run_period(
         &self,
+        py: Python<'_>,
         path: &str,
         run_period: &str,
         rest_version: Option<usize>,
@@ -876,9 +1633,9 @@ impl PyCCDB {
         if let Some(ts) = parse_py_timestamp(timestamp)? {
             ctx.timestamp = ts;
         }
-        Ok(self
-            .inner
-            .fetch(path, &ctx)
+        let db = self.db()?;
+        Ok(py
+            .detach(|| db.fetch(path, &ctx))
             .map_err(py_ccdb_error)?
             .into_iter()
             .map(|(run, data)| {
@@ -892,6 +1649,79 @@ impl PyCCDB {
             .collect())
     }
 
+    /// fetch_dataframe(self, path, *, runs=None, variation=None, timestamp=None)
+    ///
+    /// Parameters
+    /// ----------
+    /// path : str
+    ///     Absolute or relative table path.
+    /// runs : list[int] | None, optional
+    ///     Run numbers to query; defaults to run 0 when omitted.
+    /// variation : str | None, optional
+    ///     Variation branch to resolve (default "default").
+    /// timestamp : datetime | str | None, optional
+    ///     Timestamp used to select historical assignments.
+    ///
+    /// Returns
+    /// -------
+    /// pandas.DataFrame
+    ///     Rows for every fetched run concatenated in run order, with a leading ``run`` column
+    ///     followed by one typed column per CCDB column.
+    #[pyo3(signature = (path, *, runs=None, variation=None, timestamp=None))]
+    pub fn fetch_dataframe<'py>(
+        &self,
+        py: Python<'py>,
+        path: &str,
+        runs: Option<Vec<RunNumber>>,
+        variation: Option<String>,
+        timestamp: Option<Bound<'py, PyAny>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx = build_context(runs, variation, timestamp)?;
+        let db = self.db()?;
+        let columns: Vec<String> = py
+            .detach(|| db.table(path).and_then(|table| table.columns()))
+            .map_err(py_ccdb_error)?
+            .into_iter()
+            .map(|column| column.name().to_string())
+            .collect();
+        let datasets = py.detach(|| db.fetch(path, &ctx)).map_err(py_ccdb_error)?;
+        datasets_to_pandas(py, &columns, datasets)
+    }
+
+    /// ls(self, path="/")
+    ///
+    /// Parameters
+    /// ----------
+    /// path : str, default "/"
+    ///     Absolute directory path to list.
+    ///
+    /// Returns
+    /// -------
+    /// list[DirectoryHandle | TypeTableHandle]
+    ///     Child directories followed by tables directly under ``path``, each sorted by name.
+    #[pyo3(signature = (path="/"))]
+    pub fn ls(&self, py: Python<'_>, path: &str) -> PyResult<Vec<Py<PyAny>>> {
+        self.dir(path)?.ls(py)
+    }
+
+    /// tree(self, path="/", max_depth=None)
+    ///
+    /// Parameters
+    /// ----------
+    /// path : str, default "/"
+    ///     Absolute directory path to render.
+    /// max_depth : int | None, optional
+    ///     Maximum number of directory levels to descend (unlimited when omitted).
+    ///
+    /// Returns
+    /// -------
+    /// str
+    ///     Text rendering of the directory tree, as produced by :meth:`DirectoryHandle.tree`.
+    #[pyo3(signature = (path="/", max_depth=None))]
+    pub fn tree(&self, path: &str, max_depth: Option<usize>) -> PyResult<String> {
+        Ok(self.dir(path)?.tree(max_depth))
+    }
+
     /// root(self)
     ///
     /// Returns
@@ -900,23 +1730,69 @@ impl PyCCDB {
     ///     Handle to the root directory.
     pub fn root(&self) -> PyResult<PyDirectoryHandle> {
         Ok(PyDirectoryHandle {
-            inner: self.inner.root(),
+            inner: self.db()?.root(),
+            closed: self.closed.clone(),
         })
     }
     /// str: Filesystem path that was used to open the database.
     #[getter]
     pub fn connection_path(&self) -> &str {
-        self.inner.connection_path()
+        &self.connection_path
+    }
+    /// bool: Whether :meth:`CCDB.close` has been called on this handle.
+    #[getter]
+    pub fn closed(&self) -> bool {
+        self.inner.is_none()
+    }
+
+    /// close(self)
+    ///
+    /// Release this handle's SQLite connection. Further queries through the handle, or through
+    /// table handles and iterators obtained from it, raise ``CCDBError`` (a ``RuntimeError``).
+    /// Directory handles can still be navigated, and cached metadata such as table names stays
+    /// readable. The connection itself is freed once the derived objects are garbage collected.
+    /// Calling ``close`` more than once is a no-op.
+    pub fn close(&mut self) {
+        self.inner = None;
+        self.closed.close();
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyResult<PyRef<'_, Self>> {
+        slf.db()?;
+        Ok(slf)
+    }
+
+    #[pyo3(signature = (_exc_type=None, _exc_value=None, _traceback=None))]
+    fn __exit__(
+        &mut self,
+        _exc_type: Option<&Bound<'_, PyAny>>,
+        _exc_value: Option<&Bound<'_, PyAny>>,
+        _traceback: Option<&Bound<'_, PyAny>>,
+    ) -> bool {
+        self.close();
+        false
     }
 
     fn __repr__(&self) -> String {
-        format!("CCDB(\"{}\")", self.inner.connection_path())
+        let state = if self.closed() { ", closed" } else { "" };
+        format!("CCDB(\"{}\"{state})", self.connection_path)
     }
     fn __str__(&self) -> String {
         self.__repr__()
     }
 }
 
+fn row_to_dict<'py>(py: Python<'py>, data: &Data, row: usize) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    for (idx, name) in data.column_names().iter().enumerate() {
+        match data.value(idx, row) {
+            Some(v) => dict.set_item(name, value_to_py(py, v)?)?,
+            None => dict.set_item(name, py.None())?,
+        }
+    }
+    Ok(dict)
+}
+
 fn value_to_py(py: Python<'_>, value: Value<'_>) -> PyResult<Py<PyAny>> {
     Ok(match value {
         Value::Int(v) => PyInt::new(py, *v).unbind().into(),
@@ -934,6 +1810,72 @@ fn value_to_py(py: Python<'_>, value: Value<'_>) -> PyResult<Py<PyAny>> {
     })
 }
 
+fn datasets_to_pandas<'py>(
+    py: Python<'py>,
+    column_names: &[String],
+    datasets: BTreeMap<RunNumber, Data>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let numpy = py.import("numpy")?;
+    let mut runs: Vec<RunNumber> = Vec::new();
+    let mut columns: Vec<Vec<Bound<'py, PyAny>>> = vec![Vec::new(); column_names.len()];
+    for (run, data) in datasets {
+        runs.extend(iter::repeat_n(run, data.n_rows()));
+        let data = Bound::new(
+            py,
+            PyData {
+                inner: Arc::new(data),
+            },
+        )?;
+        let inner = Arc::clone(&data.borrow().inner);
+        for (idx, arrays) in columns.iter_mut().enumerate() {
+            let column = inner
+                .column(idx)
+                .ok_or_else(|| PyRuntimeError::new_err("column index out of range"))?;
+            arrays.push(column_to_numpy(data.as_any(), column)?);
+        }
+    }
+    let frame = PyDict::new(py);
+    frame.set_item("run", PyArray1::from_vec(py, runs))?;
+    for (name, arrays) in column_names.iter().zip(columns) {
+        if arrays.is_empty() {
+            frame.set_item(name, numpy.call_method1("array", (Vec::<f64>::new(),))?)?;
+        } else {
+            frame.set_item(name, numpy.call_method1("concatenate", (arrays,))?)?;
+        }
+    }
+    py.import("pandas")?.call_method1("DataFrame", (frame,))
+}
+
+fn column_to_numpy<'py>(
+    owner: &Bound<'py, PyAny>,
+    column: &data::Column,
+) -> PyResult<Bound<'py, PyAny>> {
+    let py = owner.py();
+    Ok(match column {
+        data::Column::Int(v) => PyArray1::from_slice(py, v).into_any(),
+        data::Column::UInt(v) => PyArray1::from_slice(py, v).into_any(),
+        data::Column::Long(v) => PyArray1::from_slice(py, v).into_any(),
+        data::Column::ULong(v) => PyArray1::from_slice(py, v).into_any(),
+        data::Column::Double(v) => {
+            // SAFETY: `owner` holds the `Arc<Data>` which owns `v`, and fetched data is never
+            // mutated or reallocated, so the buffer outlives the array which keeps `owner` alive.
+            let array = unsafe {
+                PyArray1::borrow_from_array(&ArrayView1::from(v.as_slice()), owner.clone())
+            };
+            array.readwrite().make_nonwriteable();
+            array.into_any()
+        }
+        data::Column::Bool(v) => PyArray1::from_slice(py, v).into_any(),
+        data::Column::String(v) => PyArray1::from_vec(
+            py,
+            v.iter()
+                .map(|s| PyString::new(py, s).into_any().unbind())
+                .collect(),
+        )
+        .into_any(),
+    })
+}
+
 fn parse_py_timestamp(ts: Option<Bound<'_, PyAny>>) -> PyResult<Option<DateTime<Utc>>> {
     let Some(val) = ts else {
         return Ok(None);
@@ -942,7 +1884,7 @@ fn parse_py_timestamp(ts: Option<Bound<'_, PyAny>>) -> PyResult<Option<DateTime<
         return Ok(Some(dt));
     }
     if let Ok(s) = val.extract::<String>() {
-        let parsed = parse_timestamp(&s).map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+        let parsed = parse_timestamp(&s).map_err(|e| py_ccdb_error(CCDBError::from(e)))?;
         return Ok(Some(parsed));
     }
     Err(PyRuntimeError::new_err("timestamp must be str or datetime"))
@@ -964,6 +1906,72 @@ fn parse_column_index(data: &Data, column: Bound<'_, PyAny>) -> PyResult<usize>
     Err(PyRuntimeError::new_err("column must be int or str"))
 }
 
+/// Schedules `func(*args, **kwargs)` on the running event loop's default executor.
+fn run_in_executor<'py>(
+    py: Python<'py>,
+    func: Bound<'py, PyAny>,
+    args: &Bound<'py, PyTuple>,
+    kwargs: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let partial_args: Vec<Bound<'py, PyAny>> = iter::once(func).chain(args.iter()).collect();
+    let call = py
+        .import("functools")?
+        .getattr("partial")?
+        .call(PyTuple::new(py, partial_args)?, kwargs)?;
+    py.import("asyncio")?
+        .call_method0("get_running_loop")?
+        .call_method1("run_in_executor", (py.None(), call))
+}
+
+fn sorted_children(dir: &DirectoryHandle) -> (Vec<DirectoryHandle>, Vec<TypeTableHandle>) {
+    let mut dirs = dir.dirs();
+    dirs.sort_unstable_by(|a, b| a.meta().name().cmp(b.meta().name()));
+    let mut tables = dir.tables();
+    tables.sort_unstable_by(|a, b| a.name().cmp(b.name()));
+    (dirs, tables)
+}
+
+fn render_tree(
+    dir: &DirectoryHandle,
+    prefix: &str,
+    depth: usize,
+    max_depth: Option<usize>,
+    out: &mut String,
+) {
+    if max_depth.is_some_and(|max| depth >= max) {
+        return;
+    }
+    let (dirs, tables) = sorted_children(dir);
+    let total = dirs.len() + tables.len();
+    for (i, child) in dirs.iter().enumerate() {
+        let last = i + 1 == total;
+        out.push_str(prefix);
+        out.push_str(if last { "└── " } else { "├── " });
+        out.push_str(child.meta().name());
+        out.push_str("/\n");
+        let child_prefix = format!("{prefix}{}", if last { "    " } else { "│   " });
+        render_tree(child, &child_prefix, depth + 1, max_depth, out);
+    }
+    for (i, table) in tables.iter().enumerate() {
+        let last = dirs.len() + i + 1 == total;
+        let meta = table.meta();
+        out.push_str(prefix);
+        out.push_str(if last { "└── " } else { "├── " });
+        #[allow(clippy::format_push_string)]
+        out.push_str(&format!(
+            "{} [{}x{}]",
+            table.name(),
+            meta.n_rows(),
+            meta.n_columns()
+        ));
+        if let Some(comment) = meta.comment().lines().next().filter(|c| !c.is_empty()) {
+            out.push_str("  # ");
+            out.push_str(comment);
+        }
+        out.push('\n');
+    }
+}
+
 fn build_context(
     runs: Option<Vec<RunNumber>>,
     variation: Option<String>,
@@ -982,17 +1990,55 @@ fn build_context(
     Ok(ctx)
 }
 
-#[pymodule]
+/// fetch_dataframe(connection, path, *, runs=None, variation=None, timestamp=None)
+///
+/// Parameters
+/// ----------
+/// connection : str or os.PathLike
+///     Filesystem path to an existing CCDB SQLite database file.
+/// path : str
+///     Absolute or relative table path.
+/// runs : list[int] | None, optional
+///     Run numbers to query; defaults to run 0 when omitted.
+/// variation : str | None, optional
+///     Variation branch to resolve (default "default").
+/// timestamp : datetime | str | None, optional
+///     Timestamp used to select historical assignments.
+///
+/// Returns
+/// -------
+/// pandas.DataFrame
+///     Same as ``CCDB(connection).fetch_dataframe(path, ...)``.
+#[pyfunction]
+#[pyo3(signature = (connection, path, *, runs=None, variation=None, timestamp=None))]
+pub fn fetch_dataframe<'py>(
+    py: Python<'py>,
+    connection: PathBuf,
+    path: &str,
+    runs: Option<Vec<RunNumber>>,
+    variation: Option<String>,
+    timestamp: Option<Bound<'py, PyAny>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    PyCCDB::new(py, connection)?.fetch_dataframe(py, path, runs, variation, timestamp)
+}
+
+#[pymodule(gil_used = false)]
 /// Python module initializer for `gluex_ccdb` bindings.
 pub fn gluex_ccdb(_py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
+    exceptions::register(m)?;
     m.add_class::<PyCCDB>()?;
     m.add_class::<PyTypeTableHandle>()?;
     m.add_class::<PyDirectoryHandle>()?;
     m.add_class::<PyData>()?;
+    m.add_class::<PyDataIter>()?;
+    m.add_class::<PyFetchIter>()?;
     m.add_class::<PyRowView>()?;
     m.add_class::<PyColumn>()?;
     m.add_class::<PyColumnMeta>()?;
     m.add_class::<PyTypeTableMeta>()?;
+    m.add_class::<PyVariationMeta>()?;
+    m.add_class::<PyRequest>()?;
     m.add_class::<PyColumnType>()?;
+    m.add_function(wrap_pyfunction!(fetch_dataframe, m)?)?;
     Ok(())
 }
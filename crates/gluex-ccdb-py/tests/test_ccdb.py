@@ -2,8 +2,10 @@
 
 from __future__ import annotations
 
+import asyncio
 import datetime as dt
 import os
+from concurrent.futures import ThreadPoolExecutor
 from pathlib import Path
 
 import gluex_ccdb
@@ -62,6 +64,60 @@ def test_directory_and_table_metadata(db: gluex_ccdb.CCDB):
     assert [c.column_type.name for c in columns] == ["double", "double", "double"]
 
 
+def test_open_accepts_pathlike():
+    path = resolve_db_path()
+    client = gluex_ccdb.CCDB(path)
+    assert client.connection_path == str(path)
+    assert client.table(TABLE_PATH).full_path() == TABLE_PATH
+
+
+def test_context_manager_closes_connection():
+    with gluex_ccdb.CCDB(str(resolve_db_path())) as client:
+        assert not client.closed
+        assert client.table(TABLE_PATH).full_path() == TABLE_PATH
+    assert client.closed
+    with pytest.raises(RuntimeError, match="closed"):
+        client.fetch(TABLE_PATH, runs=[0])
+    client.close()
+
+
+def test_derived_handles_fail_after_close():
+    client = gluex_ccdb.CCDB(str(resolve_db_path()))
+    table = client.table(TABLE_PATH)
+    directory = client.dir("/test/demo")
+    rows = client.fetch_iter(TABLE_PATH, runs=[0], timestamp=FIRST_AVAILABLE)
+    client.close()
+    # Cached metadata stays readable, but nothing queries the closed database.
+    assert table.full_path() == TABLE_PATH
+    assert directory.table("mytable").name == "mytable"
+    with pytest.raises(gluex_ccdb.CCDBError, match="closed"):
+        table.fetch(runs=[0], timestamp=FIRST_AVAILABLE)
+    with pytest.raises(gluex_ccdb.CCDBError, match="closed"):
+        directory.table("mytable").columns()
+    with pytest.raises(gluex_ccdb.CCDBError, match="closed"):
+        next(rows)
+
+
+def test_ls_and_tree(db: gluex_ccdb.CCDB):
+    entries = db.ls("/test/demo")
+    tables = [e for e in entries if isinstance(e, gluex_ccdb.TypeTableHandle)]
+    assert "mytable" in [t.name for t in tables]
+    names = [t.name for t in tables]
+    assert names == sorted(names)
+    mytable = db.table(TABLE_PATH)
+    assert (mytable.n_rows, mytable.n_columns) == (2, 3)
+    assert mytable.comment == mytable.meta.comment
+
+    root_names = [e.name for e in db.ls()]
+    assert "test" in root_names
+
+    rendered = db.tree("/test")
+    assert rendered.splitlines()[0] == "/test"
+    assert "demo/" in rendered
+    assert "mytable [2x3]" in rendered
+    assert db.tree("/test", max_depth=0) == "/test\n"
+
+
 def test_fetch_across_runs_timestamps_and_variations(db: gluex_ccdb.CCDB):
     table = db.table(TABLE_PATH)
 
@@ -101,3 +157,191 @@ def test_fetch_across_runs_timestamps_and_variations(db: gluex_ccdb.CCDB):
             "double",
         ]
         assert [value for _, _, value in row_columns] == [4.0, 5.0, 6.0]
+
+
+def test_request_string_matches_fetch(db: gluex_ccdb.CCDB):
+    via_request = db.request(f"{TABLE_PATH}:2:mc:2013-02-22 19:40:35")
+    via_fetch = db.table(TABLE_PATH).fetch(
+        runs=[2], variation="mc", timestamp=FIRST_AVAILABLE
+    )
+    assert set(via_request) == {2}
+    assert via_request[2].value("z", 1) == via_fetch[2].value("z", 1)
+
+    with pytest.raises(RuntimeError):
+        db.request(f"{TABLE_PATH}:not-a-run")
+
+
+def test_request_parses_named_options_and_flags(db: gluex_ccdb.CCDB):
+    request = gluex_ccdb.Request(
+        f"{TABLE_PATH}:variation=mc:run=2:time=2013-02-22 19:40:35:event=7:nocache"
+    )
+    assert request.path == TABLE_PATH
+    assert request.runs == [2]
+    assert request.variation == "mc"
+    assert request.event == 7
+    assert request.flags == ["nocache"]
+    assert request.has_flag("nocache")
+    via_request = db.request(request)
+    via_string = db.request(f"{TABLE_PATH}:2:mc:2013-02-22 19:40:35")
+    assert via_request[2].value("z", 1) == via_string[2].value("z", 1)
+
+    with pytest.raises(gluex_ccdb.ParseError):
+        gluex_ccdb.Request(f"{TABLE_PATH}:2:run=3")
+
+
+def test_variations_and_chain(db: gluex_ccdb.CCDB):
+    variations = db.variations()
+    names = [v.name for v in variations]
+    assert "default" in names
+    assert "mc" in names
+    assert [v.id for v in variations] == sorted(v.id for v in variations)
+
+    chain = db.variation_chain("mc")
+    assert chain[0].name == "mc"
+    assert chain[-1].name == "default"
+    assert chain[0].parent_id == chain[1].id
+    with pytest.raises(gluex_ccdb.VariationNotFoundError):
+        db.variation_chain("no-such-variation")
+
+    children = db.variation_children(chain[1].id)
+    assert "mc" in [v.name for v in children]
+    assert all(v.parent_id == chain[1].id for v in children)
+    assert db.variation_children(chain[0].id + 10_000) == []
+
+
+def test_errors_use_exception_hierarchy(db: gluex_ccdb.CCDB):
+    with pytest.raises(gluex_ccdb.TableNotFoundError):
+        db.table("/test/demo/missing")
+    with pytest.raises(gluex_ccdb.DirectoryNotFoundError):
+        db.dir("/definitely/not/here")
+    with pytest.raises(gluex_ccdb.VariationNotFoundError):
+        db.fetch(TABLE_PATH, runs=[0], variation="no-such-variation")
+    with pytest.raises(gluex_ccdb.ParseError):
+        db.fetch(TABLE_PATH, runs=[0], timestamp="not a timestamp")
+    assert issubclass(gluex_ccdb.TableNotFoundError, gluex_ccdb.NotFoundError)
+    assert issubclass(gluex_ccdb.NotFoundError, gluex_ccdb.CCDBError)
+    assert issubclass(gluex_ccdb.CCDBError, RuntimeError)
+
+
+def test_data_sequence_protocol(db: gluex_ccdb.CCDB):
+    data = db.fetch(TABLE_PATH, runs=[0], timestamp="2020-02-01 00:00:00")[0]
+    assert len(data) == data.n_rows == 2
+    assert data[0] == {"x": 1.0, "y": 2.0, "z": 3.0}
+    assert data[1]["z"] == 6.0
+    assert data[-1] == data[1]
+    assert list(data) == [data[0], data[1]]
+    with pytest.raises(IndexError):
+        data[2]
+    with pytest.raises(IndexError):
+        data[-3]
+
+
+def test_fetch_iter_matches_fetch(db: gluex_ccdb.CCDB):
+    runs = [3, 1, 2, 0, 1]
+    eager = db.fetch(TABLE_PATH, runs=runs, timestamp="2020-02-01 00:00:00")
+    lazy = db.fetch_iter(
+        TABLE_PATH, runs=runs, timestamp="2020-02-01 00:00:00", chunk_size=3
+    )
+    pairs = list(lazy)
+    assert [run for run, _ in pairs] == [0, 1, 2, 3]
+    for run, data in pairs:
+        assert data.value("x", 0) == eager[run].value("x", 0)
+
+    table_iter = db.table(TABLE_PATH).fetch_iter(
+        runs=[2], variation="mc", timestamp=FIRST_AVAILABLE
+    )
+    assert [run for run, _ in table_iter] == [2]
+
+
+def test_fetch_from_threads_matches_sequential(db: gluex_ccdb.CCDB):
+    def query(run: int) -> float:
+        data = db.fetch(TABLE_PATH, runs=[run], timestamp="2020-02-01 00:00:00")[run]
+        return data.value("x", 1)
+
+    with ThreadPoolExecutor(max_workers=4) as pool:
+        threaded = list(pool.map(query, [0, 1, 2, 3] * 4))
+    assert threaded == [4.0] * 16
+
+
+def test_fetch_async_matches_fetch(db: gluex_ccdb.CCDB):
+    async def query():
+        return await db.fetch_async(
+            TABLE_PATH, runs=[0, 1], timestamp="2020-02-01 00:00:00"
+        )
+
+    result = asyncio.run(query())
+    assert set(result) == {0, 1}
+    assert result[1].value("x", 0) == 1.0
+
+
+def test_data_to_numpy(db: gluex_ccdb.CCDB):
+    np = pytest.importorskip("numpy")
+    data = db.fetch(TABLE_PATH, runs=[0], timestamp="2020-02-01 00:00:00")[0]
+
+    arrays = data.to_numpy()
+    assert list(arrays) == ["x", "y", "z"]
+    for array in arrays.values():
+        assert array.dtype == np.float64
+        assert array.shape == (2,)
+        assert not array.flags.writeable
+    np.testing.assert_array_equal(arrays["x"], [1.0, 4.0])
+    np.testing.assert_array_equal(arrays["z"], [3.0, 6.0])
+
+
+def test_data_to_structured(db: gluex_ccdb.CCDB):
+    np = pytest.importorskip("numpy")
+    data = db.fetch(TABLE_PATH, runs=[0], timestamp="2020-02-01 00:00:00")[0]
+
+    records = data.to_structured()
+    assert records.shape == (2,)
+    assert records.dtype.names == ("x", "y", "z")
+    assert all(records.dtype[name] == np.float64 for name in records.dtype.names)
+    assert records[1]["y"] == 5.0
+    np.testing.assert_array_equal(records["z"], [3.0, 6.0])
+
+
+def test_data_to_pandas_and_fetch_dataframe(db: gluex_ccdb.CCDB):
+    pytest.importorskip("pandas")
+    data = db.fetch(TABLE_PATH, runs=[0], timestamp="2020-02-01 00:00:00")[0]
+
+    df = data.to_pandas()
+    assert list(df.columns) == ["x", "y", "z"]
+    assert df["y"].tolist() == [2.0, 5.0]
+
+    combined = db.fetch_dataframe(
+        TABLE_PATH, runs=[0, 1, 2, 3], timestamp="2020-02-01 00:00:00"
+    )
+    assert list(combined.columns) == ["run", "x", "y", "z"]
+    assert combined["run"].tolist() == [0, 0, 1, 1, 2, 2, 3, 3]
+    assert combined["x"].tolist() == [1.0, 4.0] * 4
+
+    empty = db.fetch_dataframe(TABLE_PATH, runs=[0], timestamp="2013-02-22 19:40:34")
+    assert list(empty.columns) == ["run", "x", "y", "z"]
+    assert len(empty) == 0
+
+    module_level = gluex_ccdb.fetch_dataframe(
+        db.connection_path, TABLE_PATH, runs=[0], timestamp="2020-02-01 00:00:00"
+    )
+    assert module_level["z"].tolist() == [3.0, 6.0]
+
+
+def test_data_arrow_stream(db: gluex_ccdb.CCDB):
+    pa = pytest.importorskip("pyarrow")
+    data = db.fetch(TABLE_PATH, runs=[0], timestamp="2020-02-01 00:00:00")[0]
+
+    table = pa.table(data)
+    assert table.column_names == ["x", "y", "z"]
+    assert table.schema.field("x").type == pa.float64()
+    assert table.column("y").to_pylist() == [2.0, 5.0]
+    assert pa.table(data).equals(table)
+
+
+def test_data_to_awkward(db: gluex_ccdb.CCDB):
+    ak = pytest.importorskip("awkward")
+    data = db.fetch(TABLE_PATH, runs=[0], timestamp="2020-02-01 00:00:00")[0]
+
+    records = data.to_awkward()
+    assert records.fields == ["x", "y", "z"]
+    assert len(records) == 2
+    assert ak.to_list(records["x"]) == [1.0, 4.0]
+    assert ak.to_list(records[1]) == {"x": 4.0, "y": 5.0, "z": 6.0}
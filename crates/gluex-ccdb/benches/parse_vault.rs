@@ -19,7 +19,9 @@ fn load_layout_and_vaults() -> (Arc<ColumnLayout>, Vec<String>, usize) {
     #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
     let n_rows = table.meta().n_rows() as usize;
 
-    let connection = db.connection();
+    let connection = db
+        .try_connection()
+        .expect("benchmark database is an SQLite file");
     let mut stmt = connection
         .prepare_cached(
             "SELECT cs.vault
@@ -0,0 +1,97 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+pub use arrow_array;
+pub use arrow_schema;
+
+use arrow_array::{
+    ArrayRef, BooleanArray, Float64Array, Int32Array, Int64Array, RecordBatch, StringArray,
+    UInt32Array, UInt64Array,
+};
+use arrow_schema::{ArrowError, DataType, Field, Schema};
+use arrow_select::concat::concat_batches;
+use gluex_core::RunNumber;
+
+use crate::{
+    data::{Column, Data},
+    models::ColumnType,
+};
+
+/// Name of the column [`runs_to_arrow`] adds in front of the table's columns.
+pub const RUN_NUMBER_COLUMN: &str = "run_number";
+
+impl Data {
+    /// Converts the table into an Arrow [`RecordBatch`] with one non-nullable column per CCDB
+    /// column, keeping the column names and order.
+    ///
+    /// Column types map to `int32`, `uint32`, `int64`, `uint64`, `float64`, `bool`, and `utf8`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if Arrow rejects the batch, which does not happen for well-formed data.
+    pub fn to_arrow(&self) -> Result<RecordBatch, ArrowError> {
+        let fields: Vec<Field> = self
+            .column_names()
+            .iter()
+            .zip(self.column_types())
+            .map(|(name, column_type)| Field::new(name, data_type(*column_type), false))
+            .collect();
+        let arrays = (0..self.n_columns())
+            .filter_map(|index| self.column(index))
+            .map(column_array)
+            .collect();
+        RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)
+    }
+}
+
+/// Concatenates the tables fetched for several runs into one [`RecordBatch`], in run order, with
+/// a leading `int64` [`RUN_NUMBER_COLUMN`] column naming the run each row belongs to.
+///
+/// With no runs, the batch holds only the (empty) run number column.
+///
+/// # Errors
+///
+/// Returns an error if the runs' tables have different columns.
+pub fn runs_to_arrow(data: &BTreeMap<RunNumber, Data>) -> Result<RecordBatch, ArrowError> {
+    let run_field = Field::new(RUN_NUMBER_COLUMN, DataType::Int64, false);
+    let batches = data
+        .iter()
+        .map(|(run, data)| {
+            let batch = data.to_arrow()?;
+            let mut fields = vec![Arc::new(run_field.clone())];
+            fields.extend(batch.schema().fields().iter().cloned());
+            let mut columns: Vec<ArrayRef> =
+                vec![Arc::new(Int64Array::from(vec![*run; data.n_rows()]))];
+            columns.extend(batch.columns().iter().cloned());
+            RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let schema = batches.first().map_or_else(
+        || Arc::new(Schema::new(vec![run_field.clone()])),
+        RecordBatch::schema,
+    );
+    concat_batches(&schema, &batches)
+}
+
+fn data_type(column_type: ColumnType) -> DataType {
+    match column_type {
+        ColumnType::Int => DataType::Int32,
+        ColumnType::UInt => DataType::UInt32,
+        ColumnType::Long => DataType::Int64,
+        ColumnType::ULong => DataType::UInt64,
+        ColumnType::Double => DataType::Float64,
+        ColumnType::String => DataType::Utf8,
+        ColumnType::Bool => DataType::Boolean,
+    }
+}
+
+fn column_array(column: &Column) -> ArrayRef {
+    match column {
+        Column::Int(values) => Arc::new(Int32Array::from(values.clone())),
+        Column::UInt(values) => Arc::new(UInt32Array::from(values.clone())),
+        Column::Long(values) => Arc::new(Int64Array::from(values.clone())),
+        Column::ULong(values) => Arc::new(UInt64Array::from(values.clone())),
+        Column::Double(values) => Arc::new(Float64Array::from(values.clone())),
+        Column::Bool(values) => Arc::new(BooleanArray::from(values.clone())),
+        Column::String(values) => Arc::new(StringArray::from(values.clone())),
+    }
+}
@@ -0,0 +1,195 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use gluex_core::RunNumber;
+use tokio::task::{spawn_blocking, JoinError};
+
+use crate::{
+    context::Context,
+    data::Data,
+    database::{DirectoryHandle, TypeTableHandle, CCDB},
+    models::{ColumnMeta, VariationMeta},
+    CCDBResult,
+};
+
+/// Handle to a CCDB database for use from async code running on a `tokio` runtime.
+///
+/// Every method that reads the database runs the corresponding [`CCDB`] call on `tokio`'s
+/// blocking thread pool (see [`tokio::task::spawn_blocking`]), so a slow fetch never stalls the
+/// executor. Lookups answered from the metadata loaded at open time ([`AsyncCCDB::dir`],
+/// [`AsyncCCDB::table`]) run inline.
+///
+/// Clones share the wrapped handle's storage backend. A handle from [`AsyncCCDB::open`] has one
+/// `SQLite` connection, so concurrent fetches on its clones are served one at a time; open it
+/// with [`AsyncCCDB::open_pooled`] to let up to the pool size of them read at once.
+///
+/// ```no_run
+/// use gluex_ccdb::{asynchronous::AsyncCCDB, context::Context};
+///
+/// # async fn run() -> gluex_ccdb::CCDBResult<()> {
+/// let db = AsyncCCDB::open_pooled("/data/ccdb.sqlite", 4).await?;
+/// let ctx = Context::default().with_run_range(55_000..=55_010);
+/// for (run, data) in db.fetch("/PHOTON_BEAM/endpoint_energy", &ctx).await? {
+///     println!("{run}: {:?}", data.double(0, 0));
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Panics
+///
+/// The async methods resume a panic raised by the blocking call, and panic if the runtime shuts
+/// down before the call finishes.
+#[derive(Clone)]
+pub struct AsyncCCDB {
+    db: CCDB,
+}
+
+impl AsyncCCDB {
+    /// Wraps an open [`CCDB`] handle.
+    #[must_use]
+    pub fn new(db: CCDB) -> Self {
+        Self { db }
+    }
+
+    /// Opens a CCDB file without blocking the executor (see [`CCDB::open`]).
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the database cannot be opened.
+    pub async fn open(path: impl Into<PathBuf>) -> CCDBResult<Self> {
+        let path = path.into();
+        join(spawn_blocking(move || CCDB::open(path)).await).map(Self::new)
+    }
+
+    /// Opens a CCDB file with a pool of `pool_size` connections without blocking the executor
+    /// (see [`CCDB::open_pooled`]), so up to `pool_size` fetches on clones of the handle run at
+    /// once.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the database cannot be opened.
+    pub async fn open_pooled(path: impl Into<PathBuf>, pool_size: usize) -> CCDBResult<Self> {
+        let path = path.into();
+        join(spawn_blocking(move || CCDB::open_pooled(path, pool_size)).await).map(Self::new)
+    }
+
+    /// Returns the wrapped blocking handle.
+    #[must_use]
+    pub fn blocking(&self) -> &CCDB {
+        &self.db
+    }
+
+    /// Unwraps the blocking handle.
+    #[must_use]
+    pub fn into_blocking(self) -> CCDB {
+        self.db
+    }
+
+    /// Runs `f` with the blocking handle on `tokio`'s blocking thread pool, for calls this type
+    /// does not wrap.
+    pub async fn run<T, F>(&self, f: F) -> T
+    where
+        F: FnOnce(&CCDB) -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let db = self.db.clone();
+        join(spawn_blocking(move || f(&db)).await)
+    }
+
+    /// Resolves a directory path into a handle (see [`CCDB::dir`]).
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the directory cannot be found.
+    pub fn dir(&self, path: &str) -> CCDBResult<DirectoryHandle> {
+        self.db.dir(path)
+    }
+
+    /// Resolves a table path into a handle (see [`CCDB::table`]).
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the table cannot be found.
+    pub fn table(&self, path: &str) -> CCDBResult<TypeTableHandle> {
+        self.db.table(path)
+    }
+
+    /// Loads the column metadata of a table (see [`TypeTableHandle::columns`]).
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the columns cannot be read.
+    pub async fn columns(&self, table: &TypeTableHandle) -> CCDBResult<Vec<ColumnMeta>> {
+        let table = table.clone();
+        join(spawn_blocking(move || table.columns()).await)
+    }
+
+    /// Loads the metadata of a variation (see [`CCDB::variation`]).
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the variation cannot be found.
+    pub async fn variation(&self, name: &str) -> CCDBResult<VariationMeta> {
+        let name = name.to_string();
+        self.run(move |db| db.variation(&name)).await
+    }
+
+    /// Lists every variation in the database (see [`CCDB::variations`]).
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the variations cannot be read.
+    pub async fn variations(&self) -> CCDBResult<Vec<VariationMeta>> {
+        self.run(CCDB::variations).await
+    }
+
+    /// Fetches data for a table path (see [`CCDB::fetch`]).
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the table path does not exist or an error occurs while
+    /// fetching data.
+    pub async fn fetch(&self, path: &str, ctx: &Context) -> CCDBResult<BTreeMap<RunNumber, Data>> {
+        let (path, ctx) = (path.to_string(), ctx.clone());
+        self.run(move |db| db.fetch(&path, &ctx)).await
+    }
+
+    /// Fetches data for a resolved table handle (see [`TypeTableHandle::fetch`]).
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if an error occurs while fetching data.
+    pub async fn fetch_table(
+        &self,
+        table: &TypeTableHandle,
+        ctx: &Context,
+    ) -> CCDBResult<BTreeMap<RunNumber, Data>> {
+        let (table, ctx) = (table.clone(), ctx.clone());
+        join(spawn_blocking(move || table.fetch(&ctx)).await)
+    }
+
+    /// Parses a request string and fetches it (see [`CCDB::request`]).
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the request string cannot be parsed or the fetch fails.
+    pub async fn request(&self, request_string: &str) -> CCDBResult<BTreeMap<RunNumber, Data>> {
+        let request_string = request_string.to_string();
+        self.run(move |db| db.request(&request_string)).await
+    }
+}
+
+impl From<CCDB> for AsyncCCDB {
+    fn from(db: CCDB) -> Self {
+        Self::new(db)
+    }
+}
+
+/// Returns the result of a blocking task, resuming its panic if it had one.
+fn join<T>(result: Result<T, JoinError>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(err) if err.is_panic() => std::panic::resume_unwind(err.into_panic()),
+        Err(err) => panic!("blocking CCDB task did not finish: {err}"),
+    }
+}
@@ -0,0 +1,83 @@
+use gluex_core::{limits::QueryBudget, query_plan::QueryPlanStep};
+
+pub use gluex_core::sqlite::{Row, SqlValue, SqliteBackend};
+
+use crate::CCDBResult;
+
+/// Storage behind a [`crate::database::CCDB`] handle.
+///
+/// The query layer (path lookup, variation chains, and assignment resolution) is written once
+/// against this trait and issues plain `SELECT` statements over the CCDB schema; a backend only
+/// has to execute them. [`SqliteBackend`] serves `SQLite` snapshots, and other stores (a `MySQL`
+/// server, an HTTP service, an in-memory fixture) can be plugged in with
+/// [`crate::database::CCDB::from_backend`].
+pub trait StorageBackend: Send + Sync {
+    /// Returns the location reported for the database, such as a file path or URL.
+    fn location(&self) -> &str;
+
+    /// Runs a read-only statement with positional parameters and calls `on_row` for every
+    /// result row, in order, stopping at the first error it returns.
+    ///
+    /// Backends should stop a statement that is still running once `budget`'s deadline passes and
+    /// report it as the budget's timeout error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the statement fails, runs past the deadline, or `on_row` fails.
+    fn query(
+        &self,
+        sql: &str,
+        params: &[SqlValue],
+        budget: Option<&QueryBudget>,
+        on_row: &mut dyn FnMut(Row<'_>) -> CCDBResult<()>,
+    ) -> CCDBResult<()>;
+
+    /// Returns the plan the backend would use to run a statement, without running it.
+    /// Backends that cannot explain their statements return no steps.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the statement cannot be prepared.
+    fn explain(&self, sql: &str, params: &[SqlValue]) -> CCDBResult<Vec<QueryPlanStep>> {
+        let _ = (sql, params);
+        Ok(Vec::new())
+    }
+
+    /// Returns the SQL expression that converts a Unix timestamp parameter (`?`) into a value
+    /// comparable with the local-time `created` columns of the CCDB schema.
+    ///
+    /// The default is the `SQLite` form, `datetime(?, 'unixepoch', 'localtime')`; backends for
+    /// other SQL dialects override it (a `MySQL` server would use `FROM_UNIXTIME(?)`).
+    fn timestamp_sql(&self) -> &'static str {
+        "datetime(?, 'unixepoch', 'localtime')"
+    }
+
+    /// Returns the backend as a [`SqliteBackend`], if it is one.
+    fn sqlite(&self) -> Option<&SqliteBackend> {
+        None
+    }
+}
+
+impl StorageBackend for SqliteBackend {
+    fn location(&self) -> &str {
+        SqliteBackend::location(self)
+    }
+
+    fn query(
+        &self,
+        sql: &str,
+        params: &[SqlValue],
+        budget: Option<&QueryBudget>,
+        on_row: &mut dyn FnMut(Row<'_>) -> CCDBResult<()>,
+    ) -> CCDBResult<()> {
+        SqliteBackend::query(self, sql, params, budget, on_row)
+    }
+
+    fn explain(&self, sql: &str, params: &[SqlValue]) -> CCDBResult<Vec<QueryPlanStep>> {
+        Ok(SqliteBackend::explain(self, sql, params)?)
+    }
+
+    fn sqlite(&self) -> Option<&SqliteBackend> {
+        Some(self)
+    }
+}
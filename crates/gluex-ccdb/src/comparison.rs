@@ -0,0 +1,430 @@
+use std::fmt;
+
+use gluex_core::{run_periods::RunPeriod, RunNumber};
+
+use crate::{
+    context::Context,
+    data::{Data, Value},
+    database::CCDB,
+    CCDBResult,
+};
+
+/// A stretch of runs within a run period whose constants are identical.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstantsSegment {
+    /// First run of the stretch.
+    pub first_run: RunNumber,
+    /// Last run of the stretch.
+    pub last_run: RunNumber,
+    /// Number of runs in the stretch that have constants.
+    pub n_runs: usize,
+    /// Constants as text, indexed by row and then column.
+    pub values: Vec<Vec<String>>,
+}
+
+/// The constants of one table over one run period.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeriodConstants {
+    /// Run period the constants were fetched for.
+    pub run_period: RunPeriod,
+    /// Runs with identical constants, in run order.
+    pub segments: Vec<ConstantsSegment>,
+}
+
+impl PeriodConstants {
+    /// Returns the segment covering the most runs, which stands for the whole period in
+    /// comparisons, or `None` if no run of the period has constants.
+    #[must_use]
+    pub fn representative(&self) -> Option<&ConstantsSegment> {
+        self.segments
+            .iter()
+            .enumerate()
+            .max_by_key(|(index, segment)| (segment.n_runs, std::cmp::Reverse(*index)))
+            .map(|(_, segment)| segment)
+    }
+
+    /// Returns the runs at which the constants change within the period.
+    #[must_use]
+    pub fn change_points(&self) -> Vec<RunNumber> {
+        self.segments
+            .iter()
+            .skip(1)
+            .map(|segment| segment.first_run)
+            .collect()
+    }
+}
+
+/// A cell whose value differs from the reference: the first period with constants in a
+/// [`TableComparison`], or the first context of a [`TableDiff`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellDifference {
+    /// Row of the cell.
+    pub row: usize,
+    /// Column of the cell.
+    pub column: usize,
+    /// Value in the reference.
+    pub reference: String,
+    /// Value in the compared period or context.
+    pub value: String,
+    /// Relative change in percent, when both values are numbers and the reference is not zero.
+    pub percent: Option<f64>,
+}
+
+/// Differences between the representative constants of a period and those of the reference
+/// period.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeriodDifference {
+    /// The compared period.
+    pub run_period: RunPeriod,
+    /// True when the two periods have tables of different shapes, in which case `cells` is empty.
+    pub shape_changed: bool,
+    /// Every cell that differs.
+    pub cells: Vec<CellDifference>,
+}
+
+impl PeriodDifference {
+    /// Largest absolute percent difference over the cells, if any is numeric.
+    #[must_use]
+    pub fn max_percent(&self) -> Option<f64> {
+        self.cells
+            .iter()
+            .filter_map(|cell| cell.percent.map(f64::abs))
+            .reduce(f64::max)
+    }
+}
+
+/// Comparison of one table's constants across several run periods.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableComparison {
+    /// Full path of the table.
+    pub table: String,
+    /// Column names in positional order.
+    pub column_names: Vec<String>,
+    /// Constants of each period, in the order requested.
+    pub periods: Vec<PeriodConstants>,
+    /// Differences of every later period with constants from the first period with constants.
+    pub differences: Vec<PeriodDifference>,
+}
+
+/// How the constants of one run differ between the two contexts of [`CCDB::diff`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum RunChange {
+    /// The run has constants only under the second context.
+    Added,
+    /// The run has constants only under the first context.
+    Removed,
+    /// The two tables have different shapes, so cells are not compared.
+    ShapeChanged,
+    /// Cells whose values differ, with the first context as the reference.
+    Cells(Vec<CellDifference>),
+}
+
+/// A run whose constants differ between the two contexts of [`CCDB::diff`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunDifference {
+    /// The run.
+    pub run: RunNumber,
+    /// How its constants differ.
+    pub change: RunChange,
+}
+
+/// Per-run, per-cell differences of one table between two contexts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableDiff {
+    /// Full path of the table.
+    pub table: String,
+    /// Column names in positional order.
+    pub column_names: Vec<String>,
+    /// Runs whose constants differ, in run order; runs with identical constants are left out.
+    pub runs: Vec<RunDifference>,
+}
+
+impl TableDiff {
+    /// Returns true if the constants are identical for every run.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty()
+    }
+}
+
+fn cell_text(value: Value<'_>) -> String {
+    match value {
+        Value::Int(v) => v.to_string(),
+        Value::UInt(v) => v.to_string(),
+        Value::Long(v) => v.to_string(),
+        Value::ULong(v) => v.to_string(),
+        Value::Double(v) => v.to_string(),
+        Value::Bool(v) => v.to_string(),
+        Value::String(v) => v.to_string(),
+    }
+}
+
+fn cells(data: &Data) -> Vec<Vec<String>> {
+    (0..data.n_rows())
+        .map(|row| {
+            (0..data.n_columns())
+                .map(|column| data.value(column, row).map(cell_text).unwrap_or_default())
+                .collect()
+        })
+        .collect()
+}
+
+fn compare(reference: &[Vec<String>], values: &[Vec<String>]) -> (bool, Vec<CellDifference>) {
+    let same_shape = reference.len() == values.len()
+        && reference
+            .iter()
+            .zip(values)
+            .all(|(a, b)| a.len() == b.len());
+    if !same_shape {
+        return (true, Vec::new());
+    }
+    let mut differences = Vec::new();
+    for (row, (a, b)) in reference.iter().zip(values).enumerate() {
+        for (column, (a, b)) in a.iter().zip(b).enumerate() {
+            if a == b {
+                continue;
+            }
+            let percent = match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+                (Ok(a), Ok(b)) if a != 0.0 => Some((b - a) / a.abs() * 100.0),
+                _ => None,
+            };
+            differences.push(CellDifference {
+                row,
+                column,
+                reference: a.clone(),
+                value: b.clone(),
+                percent,
+            });
+        }
+    }
+    (false, differences)
+}
+
+impl CCDB {
+    /// Compares the constants of `tables` across `run_periods` for a calibration review.
+    ///
+    /// Each table is fetched for every run of each period using the variation and timestamp of
+    /// `context` (its runs are ignored). Within a period, runs with identical constants are
+    /// grouped into [`ConstantsSegment`]s, whose boundaries are the change points; the segment
+    /// covering the most runs represents the period, and every later period is compared cell by
+    /// cell with the first period that has constants.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a table or the context's variation does not exist, or if fetching
+    /// constants fails.
+    pub fn compare_run_periods(
+        &self,
+        tables: &[&str],
+        run_periods: &[RunPeriod],
+        context: &Context,
+    ) -> CCDBResult<Vec<TableComparison>> {
+        let mut comparisons = Vec::new();
+        for path in tables {
+            let table = self.table(path)?;
+            let mut periods = Vec::new();
+            for &run_period in run_periods {
+                let data = table.fetch(&context.clone().with_run_period(run_period, None)?)?;
+                let mut segments: Vec<ConstantsSegment> = Vec::new();
+                for (run, data) in &data {
+                    let values = cells(data);
+                    match segments.last_mut() {
+                        Some(segment) if segment.values == values => {
+                            segment.last_run = *run;
+                            segment.n_runs += 1;
+                        }
+                        _ => segments.push(ConstantsSegment {
+                            first_run: *run,
+                            last_run: *run,
+                            n_runs: 1,
+                            values,
+                        }),
+                    }
+                }
+                periods.push(PeriodConstants {
+                    run_period,
+                    segments,
+                });
+            }
+            let mut representatives = periods
+                .iter()
+                .filter_map(|period| Some((period.run_period, period.representative()?)));
+            let differences = match representatives.next() {
+                Some((_, reference)) => representatives
+                    .map(|(run_period, segment)| {
+                        let (shape_changed, cells) = compare(&reference.values, &segment.values);
+                        PeriodDifference {
+                            run_period,
+                            shape_changed,
+                            cells,
+                        }
+                    })
+                    .collect(),
+                None => Vec::new(),
+            };
+            comparisons.push(TableComparison {
+                table: table.full_path(),
+                column_names: table.columns()?.iter().map(|c| c.name.clone()).collect(),
+                periods,
+                differences,
+            });
+        }
+        Ok(comparisons)
+    }
+
+    /// Fetches the table at `path` under two contexts, such as two variations or two
+    /// timestamps, and reports every run whose constants differ, cell by cell.
+    ///
+    /// Runs selected by either context are compared; a run with constants under only one of them
+    /// is reported as [`RunChange::Added`] or [`RunChange::Removed`]. Cells are compared as
+    /// text, with the first context as the reference for [`CellDifference::percent`].
+    ///
+    /// ```no_run
+    /// use gluex_ccdb::{context::Context, database::CCDB};
+    ///
+    /// let db = CCDB::open("ccdb.sqlite")?;
+    /// let runs = Context::default().with_run_range(30_000..=30_100);
+    /// let diff = db.diff(
+    ///     "/PHOTON_BEAM/endpoint_energy",
+    ///     &runs,
+    ///     &runs.clone().with_variation("mc"),
+    /// )?;
+    /// print!("{diff}");
+    /// # Ok::<(), gluex_ccdb::CCDBError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table or either context's variation does not exist, or if
+    /// fetching constants fails.
+    pub fn diff(&self, path: &str, ctx_a: &Context, ctx_b: &Context) -> CCDBResult<TableDiff> {
+        let table = self.table(path)?;
+        let a = table.fetch(ctx_a)?;
+        let mut b = table.fetch(ctx_b)?;
+        let mut runs = Vec::new();
+        for (run, data) in &a {
+            let change = match b.remove(run) {
+                None => RunChange::Removed,
+                Some(other) => match compare(&cells(data), &cells(&other)) {
+                    (true, _) => RunChange::ShapeChanged,
+                    (false, differences) if differences.is_empty() => continue,
+                    (false, differences) => RunChange::Cells(differences),
+                },
+            };
+            runs.push(RunDifference { run: *run, change });
+        }
+        runs.extend(b.into_keys().map(|run| RunDifference {
+            run,
+            change: RunChange::Added,
+        }));
+        runs.sort_by_key(|difference| difference.run);
+        Ok(TableDiff {
+            table: table.full_path(),
+            column_names: table.columns()?.iter().map(|c| c.name.clone()).collect(),
+            runs,
+        })
+    }
+}
+
+impl fmt::Display for TableComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.table)?;
+        for period in &self.periods {
+            let name = period.run_period.short_name();
+            let Some(representative) = period.representative() else {
+                writeln!(f, "  {name}: no constants")?;
+                continue;
+            };
+            writeln!(
+                f,
+                "  {name}: {} segment(s), representative runs {}-{} ({} runs)",
+                period.segments.len(),
+                representative.first_run,
+                representative.last_run,
+                representative.n_runs
+            )?;
+            let change_points = period.change_points();
+            if !change_points.is_empty() {
+                writeln!(
+                    f,
+                    "    changes at runs {}",
+                    change_points
+                        .iter()
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )?;
+            }
+        }
+        for difference in &self.differences {
+            let name = difference.run_period.short_name();
+            if difference.shape_changed {
+                writeln!(f, "  {name}: table shape differs from the reference")?;
+                continue;
+            }
+            match difference.max_percent() {
+                _ if difference.cells.is_empty() => {
+                    writeln!(f, "  {name}: identical to the reference")?;
+                }
+                Some(max) => writeln!(
+                    f,
+                    "  {name}: {} cell(s) differ, max {max:.3}%",
+                    difference.cells.len()
+                )?,
+                None => writeln!(f, "  {name}: {} cell(s) differ", difference.cells.len())?,
+            }
+            for cell in &difference.cells {
+                let column = self
+                    .column_names
+                    .get(cell.column)
+                    .map_or_else(|| cell.column.to_string(), Clone::clone);
+                write!(
+                    f,
+                    "    [{}][{column}] {} -> {}",
+                    cell.row, cell.reference, cell.value
+                )?;
+                match cell.percent {
+                    Some(percent) => writeln!(f, " ({percent:+.3}%)")?,
+                    None => writeln!(f)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TableDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.table)?;
+        if self.is_empty() {
+            return writeln!(f, "  identical");
+        }
+        for difference in &self.runs {
+            let run = difference.run;
+            match &difference.change {
+                RunChange::Added => writeln!(f, "  run {run}: only in the second context")?,
+                RunChange::Removed => writeln!(f, "  run {run}: only in the first context")?,
+                RunChange::ShapeChanged => writeln!(f, "  run {run}: table shape differs")?,
+                RunChange::Cells(cells) => {
+                    writeln!(f, "  run {run}: {} cell(s) differ", cells.len())?;
+                    for cell in cells {
+                        let column = self
+                            .column_names
+                            .get(cell.column)
+                            .map_or_else(|| cell.column.to_string(), Clone::clone);
+                        write!(
+                            f,
+                            "    [{}][{column}] {} -> {}",
+                            cell.row, cell.reference, cell.value
+                        )?;
+                        match cell.percent {
+                            Some(percent) => writeln!(f, " ({percent:+.3}%)")?,
+                            None => writeln!(f)?,
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
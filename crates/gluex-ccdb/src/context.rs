@@ -4,9 +4,10 @@ use gluex_core::{
     errors::ParseTimestampError,
     parsers::parse_timestamp,
     run_periods::{resolve_rest_version, RunPeriod},
+    run_set::{RunContext, RunSet},
     RunNumber,
 };
-use std::{ops::Bound, str::FromStr};
+use std::str::FromStr;
 use thiserror::Error;
 
 use crate::CCDBResult;
@@ -147,25 +148,8 @@ impl Context {
     }
     /// Replaces the run list with all runs inside the supplied range.
     #[must_use]
-    pub fn with_run_range(mut self, run_range: impl std::ops::RangeBounds<RunNumber>) -> Self {
-        let start = match run_range.start_bound() {
-            Bound::Included(&s) => s,
-            Bound::Excluded(&s) => s.saturating_add(1),
-            Bound::Unbounded => MIN_RUN_NUMBER,
-        }
-        .max(MIN_RUN_NUMBER);
-        let end = match run_range.end_bound() {
-            Bound::Included(&e) => e,
-            Bound::Excluded(&e) => e.saturating_sub(1),
-            Bound::Unbounded => MAX_RUN_NUMBER,
-        }
-        .min(MAX_RUN_NUMBER);
-        self.runs = if start > end {
-            Vec::new()
-        } else {
-            (start..=end).collect()
-        };
-        self
+    pub fn with_run_range(self, run_range: impl std::ops::RangeBounds<RunNumber>) -> Self {
+        self.with_run_set(RunSet::range(run_range))
     }
     /// Sets the variation branch for subsequent queries.
     #[must_use]
@@ -190,6 +174,40 @@ impl Context {
     }
 }
 
+/// Builds a default-variation context at the current time for the runs in `runs`, so a parsed
+/// selection string can be used directly (`"S18-41234".parse::<RunSet>()?.into()`).
+impl From<RunSet> for Context {
+    fn from(runs: RunSet) -> Self {
+        Context::default().with_run_set(runs)
+    }
+}
+
+impl RunContext for Context {
+    /// Expands the selection into an explicit run list clamped to
+    /// [`MIN_RUN_NUMBER`]..=[`MAX_RUN_NUMBER`].
+    fn with_run_set(mut self, runs: RunSet) -> Self {
+        self.runs = match runs {
+            RunSet::Runs(runs) => runs
+                .into_iter()
+                .map(|r| r.clamp(MIN_RUN_NUMBER, MAX_RUN_NUMBER))
+                .collect(),
+            other => match other.bounds() {
+                Some(bounds) => {
+                    let start = (*bounds.start()).max(MIN_RUN_NUMBER);
+                    let end = (*bounds.end()).min(MAX_RUN_NUMBER);
+                    (start..=end).collect()
+                }
+                None => Vec::new(),
+            },
+        };
+        self
+    }
+
+    fn run_set(&self) -> RunSet {
+        RunSet::runs(self.runs.iter().copied())
+    }
+}
+
 /// Errors that can occur when parsing a [`Request`] string.
 #[derive(Error, Debug)]
 pub enum ParseRequestError {
@@ -202,15 +220,67 @@ pub enum ParseRequestError {
     /// Run number was not a valid integer.
     #[error("invalid run number: {0}")]
     InvalidRunNumberError(String),
+    /// Event number was not a valid non-negative integer.
+    #[error("invalid event number: {0}")]
+    InvalidEventNumberError(String),
+    /// A named `key=value` option used a key other than `run`, `event`, `variation`, or `time`.
+    #[error("unknown request option \"{0}\"")]
+    UnknownOptionError(String),
+    /// A field was given more than once, positionally or by name.
+    #[error("request field \"{0}\" given more than once")]
+    DuplicateFieldError(String),
+    /// A trailing flag was not a word of letters, digits, `_`, or `-` starting with a letter.
+    #[error("invalid request flag \"{0}\"")]
+    InvalidFlagError(String),
 }
 
 /// Parsed representation of a CCDB request string, containing both the [`NamePath`] and [`Context`].
+///
+/// Request strings follow the `ccdb` command-line grammar, a table path followed by
+/// colon-separated fields:
+///
+/// ```text
+/// /path[:run[:variation[:time]]][:key=value...][:flag...]
+/// ```
+///
+/// - The positional fields are the run number, the variation, and the timestamp, in that order.
+///   Any of them may be left empty to keep its default, and the timestamp may itself contain
+///   colons (`2018-08-01 12:00:00`).
+/// - Named options `run=`, `event=`, `variation=`, and `time=` set the same fields (plus the event
+///   number) in any order. Positional fields must come before them, and no field may be given
+///   twice.
+/// - Bare words after the positional fields or any named option are flags, collected in
+///   [`Request::flags`] for callers to interpret.
+///
+/// ```
+/// use gluex_ccdb::context::Request;
+///
+/// let request: Request = "/TARGET/density:30274:variation=mc:event=17:nocache".parse()?;
+/// assert_eq!(request.path.full_path(), "/TARGET/density");
+/// assert_eq!(request.context.runs, [30274]);
+/// assert_eq!(request.context.variation, "mc");
+/// assert_eq!(request.event, Some(17));
+/// assert!(request.has_flag("nocache"));
+/// # Ok::<(), gluex_ccdb::context::ParseRequestError>(())
+/// ```
 #[derive(Debug, Clone)]
 pub struct Request {
     /// Absolute path to the requested table.
     pub path: NamePath,
     /// Context describing run/variation/timestamp selection.
     pub context: Context,
+    /// Event number within the run, if given. Constants are assigned per run, so it does not
+    /// affect which assignment is resolved.
+    pub event: Option<u64>,
+    /// Trailing flags in the order given, without duplicates.
+    pub flags: Vec<String>,
+}
+impl Request {
+    /// True when `flag` was given as a trailing flag.
+    #[must_use]
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.flags.iter().any(|f| f == flag)
+    }
 }
 impl FromStr for Request {
     type Err = ParseRequestError;
@@ -218,31 +288,102 @@ impl FromStr for Request {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let (path_str, rest) = s.split_once(':').map_or((s, None), |(p, r)| (p, Some(r)));
         let path = NamePath::from_str(path_str)?;
-        let mut run: Option<RunNumber> = None;
-        let mut variation: Option<String> = None;
-        let mut timestamp: Option<DateTime<Utc>> = None;
-        if let Some(rest) = rest {
-            let mut parts: Vec<&str> = rest.splitn(3, ':').collect();
-            while parts.len() < 3 {
-                parts.push("");
+        let fields = rest.map_or_else(|| Ok(RequestFields::default()), RequestFields::parse)?;
+        let timestamp = fields.time.as_deref().map(parse_timestamp).transpose()?;
+        Ok(Request {
+            path,
+            context: Context::new(fields.run.map(|r| vec![r]), fields.variation, timestamp),
+            event: fields.event,
+            flags: fields.flags,
+        })
+    }
+}
+
+const POSITIONAL_FIELDS: [&str; 3] = ["run", "variation", "time"];
+
+#[derive(Default)]
+struct RequestFields {
+    run: Option<RunNumber>,
+    event: Option<u64>,
+    variation: Option<String>,
+    time: Option<String>,
+    flags: Vec<String>,
+}
+impl RequestFields {
+    fn parse(fields: &str) -> Result<Self, ParseRequestError> {
+        let mut parsed = Self::default();
+        let mut position = 0;
+        let mut in_time = false;
+        for token in fields.split(':') {
+            if in_time && token.starts_with(|c: char| c.is_ascii_digit()) && !token.contains('=') {
+                if let Some(time) = &mut parsed.time {
+                    time.push(':');
+                    time.push_str(token);
+                }
+                continue;
             }
-            let (run_s, var_s, time_s) = (parts[0], parts[1], parts[2]);
-            if !run_s.is_empty() {
-                run =
-                    Some(run_s.parse::<RunNumber>().map_err(|_| {
-                        ParseRequestError::InvalidRunNumberError(run_s.to_string())
-                    })?);
+            if let Some((key, value)) = token.split_once('=') {
+                position = POSITIONAL_FIELDS.len();
+                parsed.set(key.trim(), value.trim())?;
+                in_time = key.trim() == "time" && parsed.time.is_some();
+            } else if let Some(key) = POSITIONAL_FIELDS.get(position) {
+                parsed.set(key, token.trim())?;
+                in_time = *key == "time" && parsed.time.is_some();
+                position += 1;
+            } else {
+                parsed.flag(token.trim())?;
+                in_time = false;
             }
-            if !var_s.is_empty() {
-                variation = Some(var_s.to_string());
+        }
+        Ok(parsed)
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), ParseRequestError> {
+        if value.is_empty() {
+            return match key {
+                "run" | "event" | "variation" | "time" => Ok(()),
+                _ => Err(ParseRequestError::UnknownOptionError(key.to_string())),
+            };
+        }
+        let duplicate = || ParseRequestError::DuplicateFieldError(key.to_string());
+        match key {
+            "run" if self.run.is_some() => return Err(duplicate()),
+            "run" => {
+                self.run =
+                    Some(value.parse::<RunNumber>().map_err(|_| {
+                        ParseRequestError::InvalidRunNumberError(value.to_string())
+                    })?);
             }
-            if !time_s.is_empty() {
-                timestamp = Some(parse_timestamp(time_s)?);
+            "event" if self.event.is_some() => return Err(duplicate()),
+            "event" => {
+                self.event =
+                    Some(value.parse::<u64>().map_err(|_| {
+                        ParseRequestError::InvalidEventNumberError(value.to_string())
+                    })?);
             }
+            "variation" if self.variation.is_some() => return Err(duplicate()),
+            "variation" => self.variation = Some(value.to_string()),
+            "time" if self.time.is_some() => return Err(duplicate()),
+            "time" => self.time = Some(value.to_string()),
+            _ => return Err(ParseRequestError::UnknownOptionError(key.to_string())),
         }
-        Ok(Request {
-            path,
-            context: Context::new(run.map(|r| vec![r]), variation, timestamp),
-        })
+        Ok(())
+    }
+
+    fn flag(&mut self, flag: &str) -> Result<(), ParseRequestError> {
+        if flag.is_empty() {
+            return Ok(());
+        }
+        if !flag.starts_with(|c: char| c.is_ascii_alphabetic())
+            || !flag
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(ParseRequestError::InvalidFlagError(flag.to_string()));
+        }
+        if !self.flags.iter().any(|f| f == flag) {
+            self.flags.push(flag.to_string());
+        }
+        Ok(())
     }
 }
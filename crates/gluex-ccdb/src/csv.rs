@@ -0,0 +1,134 @@
+use std::{collections::BTreeMap, io::Write};
+
+use gluex_core::RunNumber;
+
+use crate::data::{Data, Value};
+
+/// Name of the column [`write_runs_csv`] adds in front of the table's columns.
+pub const RUN_NUMBER_COLUMN: &str = "run_number";
+
+impl Data {
+    /// Writes the table as CSV: a header row with the column names, then one line per row.
+    ///
+    /// Fields containing the delimiter, a double quote, or a line break are quoted as in
+    /// RFC 4180; numbers are written in their shortest round-trip form.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `writer` fails.
+    pub fn write_csv<W: Write>(&self, writer: W) -> std::io::Result<()> {
+        self.write_delimited(writer, ',')
+    }
+
+    /// Writes the table like [`Data::write_csv`], but with `delimiter` between fields (for
+    /// example `'\t'` for TSV).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `writer` fails.
+    pub fn write_delimited<W: Write>(&self, mut writer: W, delimiter: char) -> std::io::Result<()> {
+        write_record(
+            &mut writer,
+            delimiter,
+            self.column_names().iter().map(String::as_str),
+        )?;
+        self.write_rows(&mut writer, delimiter, None)
+    }
+
+    fn write_rows<W: Write>(
+        &self,
+        writer: &mut W,
+        delimiter: char,
+        run: Option<RunNumber>,
+    ) -> std::io::Result<()> {
+        let run = run.map(|run| run.to_string());
+        for row in 0..self.n_rows() {
+            let cells: Vec<String> = (0..self.n_columns())
+                .map(|column| self.value(column, row).map(text).unwrap_or_default())
+                .collect();
+            write_record(
+                writer,
+                delimiter,
+                run.iter().chain(&cells).map(String::as_str),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes the tables fetched for several runs as one CSV document, in run order, with a leading
+/// [`RUN_NUMBER_COLUMN`] column naming the run each row belongs to.
+///
+/// The header comes from the first run's table; with no runs, only the run number header is
+/// written.
+///
+/// # Errors
+///
+/// Returns an error if writing to `writer` fails.
+pub fn write_runs_csv<W: Write>(
+    data: &BTreeMap<RunNumber, Data>,
+    writer: W,
+) -> std::io::Result<()> {
+    write_runs_delimited(data, writer, ',')
+}
+
+/// Writes the tables fetched for several runs like [`write_runs_csv`], but with `delimiter`
+/// between fields.
+///
+/// # Errors
+///
+/// Returns an error if writing to `writer` fails.
+pub fn write_runs_delimited<W: Write>(
+    data: &BTreeMap<RunNumber, Data>,
+    mut writer: W,
+    delimiter: char,
+) -> std::io::Result<()> {
+    let columns = data
+        .values()
+        .next()
+        .map(Data::column_names)
+        .unwrap_or_default();
+    write_record(
+        &mut writer,
+        delimiter,
+        std::iter::once(RUN_NUMBER_COLUMN).chain(columns.iter().map(String::as_str)),
+    )?;
+    for (run, data) in data {
+        data.write_rows(&mut writer, delimiter, Some(*run))?;
+    }
+    Ok(())
+}
+
+fn write_record<'a, W: Write>(
+    writer: &mut W,
+    delimiter: char,
+    fields: impl Iterator<Item = &'a str>,
+) -> std::io::Result<()> {
+    let mut line = String::new();
+    for (index, field) in fields.enumerate() {
+        if index > 0 {
+            line.push(delimiter);
+        }
+        if field.contains([delimiter, '"', '\n', '\r']) {
+            line.push('"');
+            line.push_str(&field.replace('"', "\"\""));
+            line.push('"');
+        } else {
+            line.push_str(field);
+        }
+    }
+    line.push('\n');
+    writer.write_all(line.as_bytes())
+}
+
+fn text(value: Value<'_>) -> String {
+    match value {
+        Value::Int(v) => v.to_string(),
+        Value::UInt(v) => v.to_string(),
+        Value::Long(v) => v.to_string(),
+        Value::ULong(v) => v.to_string(),
+        Value::Double(v) => v.to_string(),
+        Value::Bool(v) => v.to_string(),
+        Value::String(v) => v.to_string(),
+    }
+}
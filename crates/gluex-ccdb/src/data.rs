@@ -223,6 +223,12 @@ pub struct RowView<'a> {
     column_types: &'a [ColumnType],
 }
 impl<'a> RowView<'a> {
+    /// Returns the index of this row within its table.
+    #[must_use]
+    pub fn index(&self) -> usize {
+        self.row
+    }
+
     /// Returns a typed cell by positional column index.
     #[must_use]
     pub fn value(&self, column: usize) -> Option<Value<'a>> {
@@ -434,6 +440,15 @@ impl Data {
     /// This method will return an error if the parsed number of columns does not equal the
     /// expected number from the database or if any of the column contents cannot be parsed into
     /// their respective data types.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "trace",
+            name = "ccdb.parse_vault",
+            skip_all,
+            fields(bytes = vault.len(), n_rows, n_columns = layout.column_count())
+        )
+    )]
     pub fn from_vault(
         vault: &str,
         layout: Arc<ColumnLayout>,
@@ -531,6 +546,20 @@ impl Data {
         })
     }
 
+    /// Assembles a table from already decoded columns, which must match `layout` and hold
+    /// `n_rows` values each.
+    pub(crate) fn from_columns(
+        n_rows: usize,
+        layout: Arc<ColumnLayout>,
+        columns: Vec<Column>,
+    ) -> Self {
+        Data {
+            n_rows,
+            layout,
+            columns,
+        }
+    }
+
     /// Number of rows in the dataset.
     #[must_use]
     pub fn n_rows(&self) -> usize {
@@ -804,6 +833,21 @@ pub enum CCDBDataError {
         /// The unparsed contents of the cell.
         text: String,
     },
+    /// A typed row refers to a column the table does not have.
+    #[error("column not found: {0}")]
+    MissingColumn(String),
+    /// A typed row expects a different type than the column stores.
+    #[error("column {column} is {found}, but {expected} was requested (row {row})")]
+    ColumnTypeMismatch {
+        /// The name of the column.
+        column: String,
+        /// The row index of the cell.
+        row: usize,
+        /// The Rust type the cell was requested as.
+        expected: &'static str,
+        /// The stored column type.
+        found: ColumnType,
+    },
     /// Failed to retrieve a row due to an out-of-bounds index.
     #[error("row index {requested} out of bounds (n_rows={n_rows})")]
     RowOutOfBounds {
@@ -1,23 +1,77 @@
 use crate::{
+    backend::{Row, SqlValue, SqliteBackend, StorageBackend},
     context::{Context, Request},
     data::{ColumnLayout, Data},
     models::{
         AssignmentMetaLite, ColumnMeta, ColumnType, ConstantSetMeta, DirectoryMeta, TypeTableMeta,
-        VariationMeta,
+        UserMeta, VariationMeta,
     },
+    typed::CcdbTable,
+    vault_cache::{self, VaultCache},
     CCDBError, CCDBResult,
 };
 use chrono::{DateTime, Utc};
 use dashmap::DashMap;
-use gluex_core::{Id, RunNumber};
-use parking_lot::{Mutex, MutexGuard};
-use rusqlite::{Connection, OpenFlags};
+#[cfg(feature = "provenance")]
+use gluex_core::provenance::{CcdbQuery, DatabaseKind, Query, Recorder};
+use gluex_core::{
+    deprecation::{DeprecatedItem, Deprecation, DeprecationWarnings},
+    limits::{QueryBudget, QueryLimits},
+    metrics::{Metrics, MetricsReport},
+    query_plan::QueryPlan,
+    retry::RetryPolicy,
+    schema::SchemaMode,
+    sql_audit::{text_literal, SqlAudit},
+    Id, RunNumber,
+};
+use parking_lot::MutexGuard;
+use rusqlite::{Connection, OpenFlags, MAIN_DB};
 use std::{
     collections::{BTreeMap, HashMap, HashSet},
     path::Path,
     sync::Arc,
 };
 
+/// Path reported for databases opened with [`CCDB::open_from_bytes`].
+const MEMORY_PATH: &str = ":memory:";
+
+/// Newest `schemaVersions` entry this crate knows how to read.
+pub const SUPPORTED_SCHEMA_VERSION: i64 = 5;
+
+const COLUMNS_SQL: &str = "SELECT id, created, modified, name, typeId, columnType, `order`, comment
+     FROM columns
+     WHERE typeId = ?
+     ORDER BY `order`";
+
+/// Assignment query, with `{vault}`, `{tables}`, and `{timestamp}` filled in by
+/// [`assignments_sql`].
+const ASSIGNMENTS_SQL: &str = "SELECT
+         a.id, a.created, a.constantSetId,
+         cs.id, cs.created, cs.modified, {vault}, cs.constantTypeId,
+         rr.runMin, rr.runMax
+     FROM assignments a
+     JOIN constantSets cs ON cs.id = a.constantSetId
+     JOIN runRanges rr ON rr.id = a.runRangeId
+     WHERE cs.constantTypeId {tables}
+       AND a.created <= {timestamp}
+       AND a.variationId = ?
+       AND rr.runMax >= ?
+       AND rr.runMin <= ?";
+
+/// Builds the assignment query for `n_tables` tables in the SQL dialect of `backend`, leaving
+/// the vaults out (as empty strings) unless `vaults` is set.
+fn assignments_sql(backend: &dyn StorageBackend, n_tables: usize, vaults: bool) -> String {
+    let tables = if n_tables == 1 {
+        "= ?".to_string()
+    } else {
+        format!("IN ({})", vec!["?"; n_tables].join(", "))
+    };
+    ASSIGNMENTS_SQL
+        .replace("{vault}", if vaults { "cs.vault" } else { "''" })
+        .replace("{tables}", &tables)
+        .replace("{timestamp}", backend.timestamp_sql())
+}
+
 fn normalize_path(base: &str, path: &str) -> String {
     let mut segments: Vec<String> = Vec::new();
     let mut push_parts = |value: &str| {
@@ -45,10 +99,94 @@ fn normalize_path(base: &str, path: &str) -> String {
     }
 }
 
+/// Asks the backend for the plan of `sql` (bypassing any audit, since nothing is read).
+fn explain(backend: &dyn StorageBackend, sql: &str, params: &[i64]) -> CCDBResult<QueryPlan> {
+    let values: Vec<SqlValue> = params.iter().copied().map(SqlValue::Integer).collect();
+    Ok(QueryPlan {
+        sql: sql.to_string(),
+        params: params.iter().map(ToString::to_string).collect(),
+        steps: backend.explain(sql, &values)?,
+    })
+}
+
+fn variation_from_row(r: &Row<'_>) -> rusqlite::Result<VariationMeta> {
+    Ok(VariationMeta {
+        id: r.get(0)?,
+        created: r.get(1)?,
+        modified: r.get(2)?,
+        name: r.get(3)?,
+        description: r.get(4).unwrap_or_default(),
+        author_id: r.get(5)?,
+        comment: r.get(6).unwrap_or_default(),
+        parent_id: r.get(7)?,
+        is_locked: r.get(8).unwrap_or_default(),
+        lock_time: r.get(9).unwrap_or_default(),
+        locked_by_user_id: r.get(10).unwrap_or_default(),
+        go_back_behavior: r.get(11).unwrap_or_default(),
+        go_back_time: r.get(12).unwrap_or_default(),
+        is_deprecated: r.get(13).unwrap_or_default(),
+        deprecated_by_user_id: r.get(14).unwrap_or_default(),
+    })
+}
+
+fn user_from_row(r: &Row<'_>) -> rusqlite::Result<UserMeta> {
+    Ok(UserMeta {
+        id: r.get(0)?,
+        created: r.get(1).unwrap_or_default(),
+        last_action_time: r.get(2).unwrap_or_default(),
+        name: r.get(3)?,
+        roles: r.get(4).unwrap_or_default(),
+        info: r.get(5).unwrap_or_default(),
+    })
+}
+
+/// Assignment chosen for a run, along with its constant set.
+pub(crate) struct ResolvedAssignment {
+    pub(crate) id: Id,
+    pub(crate) constant_set: Arc<ConstantSetMeta>,
+}
+
+/// One row of the assignment query: the assignment, its constant set, and its run range.
+type AssignmentRow = (AssignmentMetaLite, ConstantSetMeta, RunNumber, RunNumber);
+
+/// Picks, for each of `runs`, the most recently created assignment among `rows` whose run range
+/// contains it.
+fn newest_assignments(
+    runs: &HashSet<RunNumber>,
+    rows: &[&AssignmentRow],
+) -> CCDBResult<BTreeMap<RunNumber, ResolvedAssignment>> {
+    let mut best: BTreeMap<RunNumber, ResolvedAssignment> = BTreeMap::new();
+    let mut best_created: HashMap<RunNumber, DateTime<Utc>> = HashMap::new(); // timestamp map
+    let mut constant_set_cache: HashMap<Id, Arc<ConstantSetMeta>> = HashMap::new();
+    for &run in runs {
+        for (meta, constant_set, rmin, rmax) in rows.iter().copied() {
+            if run >= *rmin && run <= *rmax {
+                let cur_best = best_created.get(&run);
+                let created = meta.created()?;
+                if cur_best.is_none_or(|t| created > *t) {
+                    let cs_entry = constant_set_cache
+                        .entry(constant_set.id)
+                        .or_insert_with(|| Arc::new(constant_set.clone()))
+                        .clone();
+                    best.insert(
+                        run,
+                        ResolvedAssignment {
+                            id: meta.id,
+                            constant_set: cs_entry,
+                        },
+                    );
+                    best_created.insert(run, created);
+                }
+            }
+        }
+    }
+    Ok(best)
+}
+
 /// Read-only client for the Jefferson Lab Calibration and Conditions Database.
 #[derive(Clone)]
 pub struct CCDB {
-    connection: Arc<Mutex<Connection>>,
+    backend: Arc<dyn StorageBackend>,
     connection_path: String,
     variation_cache: Arc<DashMap<String, VariationMeta>>,
     variation_chain_cache: Arc<DashMap<Id, Vec<VariationMeta>>>,
@@ -57,20 +195,155 @@ pub struct CCDB {
     table_meta: Arc<DashMap<Id, TypeTableMeta>>,
     table_by_dir_name: Arc<DashMap<(Id, String), Id>>,
     column_layouts: Arc<DashMap<Id, Arc<ColumnLayout>>>,
+    user_cache: Arc<DashMap<Id, UserMeta>>,
+    #[cfg(feature = "provenance")]
+    recorder: Option<Recorder>,
+    metrics: Option<Metrics>,
+    sql_audit: Option<SqlAudit>,
+    deprecations: Option<DeprecationWarnings>,
+    vault_cache: Option<VaultCache>,
+    vault_database: Option<u64>,
+    limits: QueryLimits,
+    schema_version: Option<i64>,
+}
+
+/// Opens a read-only connection to a CCDB `SQLite` file.
+fn read_only_connection(path: &Path) -> CCDBResult<Connection> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    conn.pragma_update(None, "foreign_keys", "ON")?; // TODO: check
+    Ok(conn)
 }
 
 impl CCDB {
     /// Opens a read-only connection to an existing CCDB `SQLite` database file.
     ///
+    /// With the `compressed` feature, `.gz` and `.zst` files are decompressed into a cache
+    /// directory on first use and the cached copy is opened (see `gluex_core::compressed`).
+    /// With the `bundle` feature, the CCDB snapshot of an offline bundle (`.gluex`) is extracted
+    /// into the same directory and opened (see `gluex_core::bundle`).
+    ///
+    /// Opening is retried while the file is busy, locked, or fails with an I/O error, as configured
+    /// by the `GLUEX_RETRY_*` environment variables (see `gluex_core::retry`); by default a single
+    /// attempt is made.
+    ///
+    /// Snapshots with a newer schema than [`SUPPORTED_SCHEMA_VERSION`] are handled according to
+    /// the `GLUEX_SCHEMA_COMPAT` environment variable (see [`CCDB::open_with_schema_mode`]).
+    ///
     /// # Errors
     ///
-    /// This method returns an error if the database cannot be opened.
+    /// This method returns an error if the database cannot be opened, if a compressed file
+    /// cannot be decompressed or a bundle cannot be extracted, or if the snapshot's schema is too new (see
+    /// [`gluex_core::schema`]).
     pub fn open(path: impl AsRef<Path>) -> CCDBResult<Self> {
-        let path_str = path.as_ref().to_string_lossy().to_string();
-        let conn = Connection::open_with_flags(&path, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+        Self::open_with_schema_mode(path, SchemaMode::from_env())
+    }
+    /// Opens a CCDB `SQLite` database file, choosing how to treat a snapshot whose schema is newer
+    /// than [`SUPPORTED_SCHEMA_VERSION`].
+    ///
+    /// In [`SchemaMode::Strict`] such snapshots are rejected with
+    /// [`CCDBError::NewerSchemaError`]. In [`SchemaMode::BestEffort`] they open, and only the
+    /// tables and columns this crate knows are read; unknown column types decode as strings.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the database cannot be opened, if a compressed file
+    /// cannot be decompressed or a bundle cannot be extracted, or if the snapshot's schema is too new in strict mode.
+    pub fn open_with_schema_mode(path: impl AsRef<Path>, mode: SchemaMode) -> CCDBResult<Self> {
+        Self::open_pool(path.as_ref(), mode, 1)
+    }
+    /// Opens a CCDB `SQLite` database file like [`CCDB::open`], but with a pool of `pool_size`
+    /// read-only connections (at least one) shared by the handle and all its clones.
+    ///
+    /// A handle normally runs its statements on a single connection, so threads querying through
+    /// clones of it wait for each other. With a pool, each statement takes an idle connection,
+    /// letting up to `pool_size` threads (for example, the workers of a web server) query at once.
+    ///
+    /// ```no_run
+    /// use gluex_ccdb::{context::Context, database::CCDB};
+    ///
+    /// let db = CCDB::open_pooled("ccdb.sqlite", 4)?;
+    /// std::thread::scope(|scope| {
+    ///     for run in [30_000, 30_500, 31_000, 31_500] {
+    ///         let db = db.clone();
+    ///         scope.spawn(move || db.fetch("/TARGET/density", &Context::default().with_runs([run])));
+    ///     }
+    /// });
+    /// # Ok::<(), gluex_ccdb::CCDBError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`CCDB::open`].
+    pub fn open_pooled(path: impl AsRef<Path>, pool_size: usize) -> CCDBResult<Self> {
+        Self::open_pool(path.as_ref(), SchemaMode::from_env(), pool_size)
+    }
+    fn open_pool(path: &Path, mode: SchemaMode, pool_size: usize) -> CCDBResult<Self> {
+        #[cfg(feature = "bundle")]
+        let path = &gluex_core::bundle::unbundled_path(path, gluex_core::bundle::CCDB_FILE)?;
+        #[cfg(feature = "compressed")]
+        let path = &gluex_core::compressed::decompressed_path(path)?;
+        let path_str = path.to_string_lossy().to_string();
+        RetryPolicy::from_env().run(
+            |_| {
+                let mut backend = SqliteBackend::new(read_only_connection(path)?, path_str.clone());
+                for _ in 1..pool_size {
+                    backend = backend.with_connection(read_only_connection(path)?);
+                }
+                Self::from_backend(Arc::new(backend), mode)
+            },
+            CCDBError::is_transient,
+        )
+    }
+    /// Opens a read-only CCDB database from the bytes of an `SQLite` file held in memory.
+    ///
+    /// The bytes are copied into an in-memory database (`SQLite`'s deserialize API), so snapshots
+    /// embedded with `include_bytes!`, downloaded over HTTP, or read from an object store can be
+    /// queried without writing a temporary file. Unlike `CCDB::open_memvfs`, this works on
+    /// every target. [`CCDB::connection_path`] reports `:memory:` for such handles.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if `bytes` is not a CCDB `SQLite` database.
+    pub fn open_from_bytes(bytes: &[u8]) -> CCDBResult<Self> {
+        let mut conn = Connection::open_in_memory()?;
+        conn.deserialize_read_exact(MAIN_DB, bytes, bytes.len(), true)?;
+        Self::from_connection(conn, MEMORY_PATH.to_string(), SchemaMode::from_env())
+    }
+    fn from_connection(conn: Connection, path_str: String, mode: SchemaMode) -> CCDBResult<Self> {
         conn.pragma_update(None, "foreign_keys", "ON")?; // TODO: check
+        Self::from_backend(Arc::new(SqliteBackend::new(conn, path_str)), mode)
+    }
+    /// Builds a client on top of any [`StorageBackend`], such as a server connection or an
+    /// in-memory store, treating a schema newer than [`SUPPORTED_SCHEMA_VERSION`] according to
+    /// `mode` (see [`CCDB::open_with_schema_mode`]).
+    ///
+    /// Directory and table metadata are loaded immediately; everything else is read through the
+    /// backend on demand.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the schema version cannot be read, if the schema is too new
+    /// in strict mode, or if the directory and table metadata cannot be loaded.
+    pub fn from_backend(backend: Arc<dyn StorageBackend>, mode: SchemaMode) -> CCDBResult<Self> {
+        let mut schema_version = None;
+        backend.query(
+            "SELECT MAX(schemaVersion) FROM schemaVersions",
+            &[],
+            None,
+            &mut |row| {
+                schema_version = row.get::<Option<i64>>(0)?;
+                Ok(())
+            },
+        )?;
+        if let Some(version) = schema_version {
+            mode.check("CCDB", version, SUPPORTED_SCHEMA_VERSION)?;
+        }
+        // in-memory copies have no identity that outlives the process, so they are never cached
+        let vault_database = (backend.location() != MEMORY_PATH)
+            .then(|| vault_cache::database_key(backend.location(), schema_version));
         let db = CCDB {
-            connection: Arc::new(Mutex::new(conn)),
+            connection_path: backend.location().to_string(),
+            backend,
             variation_cache: Arc::new(DashMap::new()),
             variation_chain_cache: Arc::new(DashMap::new()),
             directory_meta: Arc::new(DashMap::new()),
@@ -78,15 +351,224 @@ impl CCDB {
             table_meta: Arc::new(DashMap::new()),
             table_by_dir_name: Arc::new(DashMap::new()),
             column_layouts: Arc::new(DashMap::new()),
-            connection_path: path_str,
+            user_cache: Arc::new(DashMap::new()),
+            #[cfg(feature = "provenance")]
+            recorder: None,
+            metrics: None,
+            sql_audit: SqlAudit::from_env(),
+            deprecations: DeprecationWarnings::from_env(),
+            vault_cache: VaultCache::from_env(),
+            vault_database,
+            limits: QueryLimits::from_env(),
+            schema_version,
         };
         db.load_directories()?;
         db.load_tables()?;
         Ok(db)
     }
+    /// Loads an CCDB `SQLite` snapshot held in memory into the in-memory VFS that backs `SQLite`
+    /// on `wasm32-unknown-unknown`, then opens it.
+    ///
+    /// This lets browser applications use a snapshot fetched over HTTP or picked by the user
+    /// without a server. `name` identifies the snapshot inside the VFS.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if `bytes` is not an `SQLite` database, if a snapshot named
+    /// `name` was already imported, or if the database cannot be opened.
+    #[cfg(all(feature = "wasm", target_family = "wasm", target_os = "unknown"))]
+    pub fn open_memvfs(name: &str, bytes: &[u8]) -> CCDBResult<Self> {
+        sqlite_wasm_rs::MemVfsUtil::<sqlite_wasm_rs::WasmOsCallback>::new()
+            .import_db(name, bytes)?;
+        Self::open(name)
+    }
     /// Returns the underlying [`rusqlite::Connection`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the handle is not backed by a [`SqliteBackend`], such as one built with
+    /// [`CCDB::from_backend`] over another store. Use [`CCDB::try_connection`] instead.
+    #[deprecated(note = "panics on handles that are not backed by SQLite; use `try_connection`")]
     pub fn connection(&self) -> MutexGuard<'_, Connection> {
-        self.connection.lock()
+        self.try_connection()
+            .expect("CCDB handle is not backed by an SQLite database")
+    }
+    /// Returns the underlying [`rusqlite::Connection`], or `None` if the handle is not backed by
+    /// a [`SqliteBackend`].
+    pub fn try_connection(&self) -> Option<MutexGuard<'_, Connection>> {
+        self.backend.sqlite().map(SqliteBackend::connection)
+    }
+    /// Returns whether the handle reads an `SQLite` file on disk that [`CCDB::reopen`] can open
+    /// again (or that a snapshot can attach).
+    pub(crate) fn is_reopenable(&self) -> bool {
+        self.backend.sqlite().is_some() && Path::new(&self.connection_path).is_file()
+    }
+    /// Opens another read-only connection to this handle's database file and returns a handle
+    /// that reads through it while sharing this one's caches and settings.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn reopen(&self) -> CCDBResult<Self> {
+        let conn = read_only_connection(Path::new(&self.connection_path))?;
+        let mut db = self.clone();
+        db.backend = Arc::new(SqliteBackend::new(conn, self.connection_path.clone()));
+        Ok(db)
+    }
+    /// Returns the storage backend this handle reads from.
+    #[must_use]
+    pub fn backend(&self) -> &dyn StorageBackend {
+        self.backend.as_ref()
+    }
+    /// Runs `sql` through the backend and maps every result row with `map`.
+    fn select<T>(
+        &self,
+        sql: &str,
+        params: &[SqlValue],
+        budget: Option<&QueryBudget>,
+        map: impl Fn(&Row<'_>) -> rusqlite::Result<T>,
+    ) -> CCDBResult<Vec<T>> {
+        let mut rows = Vec::new();
+        self.backend.query(sql, params, budget, &mut |row| {
+            rows.push(map(&row)?);
+            Ok(())
+        })?;
+        Ok(rows)
+    }
+    /// Returns a handle that records every table fetch into `recorder`.
+    ///
+    /// Handles derived from the returned client (directories, tables) record as well; the
+    /// original client is unaffected.
+    #[cfg(feature = "provenance")]
+    #[must_use]
+    pub fn with_recorder(mut self, recorder: Recorder) -> Self {
+        recorder.register_database(&self.connection_path, DatabaseKind::Ccdb);
+        self.recorder = Some(recorder);
+        self
+    }
+    /// Returns a handle that counts and times every table fetch and query into `metrics`.
+    ///
+    /// Handles derived from the returned client (directories, tables) report as well; the
+    /// original client is unaffected.
+    #[must_use]
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+    /// Returns the totals recorded so far, or `None` if no collector was attached with
+    /// [`CCDB::with_metrics`].
+    #[must_use]
+    pub fn metrics(&self) -> Option<MetricsReport> {
+        self.metrics.as_ref().map(Metrics::report)
+    }
+    /// Returns a handle that logs every SQL statement it issues, with bound parameters, to
+    /// `audit` (in a dry run, assignment queries are logged but not executed).
+    ///
+    /// Handles derived from the returned client (directories, tables) log as well; the original
+    /// client is unaffected. Handles opened while `GLUEX_SQL_AUDIT` is set start with an audit
+    /// that echoes to stderr (see [`gluex_core::sql_audit`]).
+    #[must_use]
+    pub fn with_sql_audit(mut self, audit: SqlAudit) -> Self {
+        self.sql_audit = Some(audit);
+        self
+    }
+    /// Returns the attached SQL audit, if any.
+    #[must_use]
+    pub fn sql_audit(&self) -> Option<&SqlAudit> {
+        self.sql_audit.as_ref()
+    }
+    /// Returns a handle that reports fetches touching a deprecated table, directory, or variation
+    /// to `warnings`, or that stays silent if `warnings` is `None`.
+    ///
+    /// Handles derived from the returned client (directories, tables) report as well; the
+    /// original client is unaffected. Handles start with a channel that only collects warnings,
+    /// or with none if `GLUEX_DEPRECATION_WARNINGS=off` (see [`gluex_core::deprecation`]).
+    #[must_use]
+    pub fn with_deprecation_warnings(mut self, warnings: Option<DeprecationWarnings>) -> Self {
+        self.deprecations = warnings;
+        self
+    }
+    /// Returns the attached deprecation warning channel, if any.
+    #[must_use]
+    pub fn deprecation_warnings(&self) -> Option<&DeprecationWarnings> {
+        self.deprecations.as_ref()
+    }
+    /// Returns a handle that keeps decoded vaults in `cache` and reads them back from it on later
+    /// fetches, including in other processes, or that always parses vaults if `cache` is `None`.
+    ///
+    /// Handles derived from the returned client (directories, tables) use the cache as well; the
+    /// original client is unaffected. Handles opened while `GLUEX_VAULT_CACHE` is set start with
+    /// a cache (see [`crate::vault_cache::VAULT_CACHE_ENV`]). Hits and misses are counted as
+    /// `ccdb.vault_cache` in the attached metrics. Handles from [`CCDB::open_from_bytes`] never
+    /// use the cache, since their entries could not be told apart from another copy's.
+    #[must_use]
+    pub fn with_vault_cache(mut self, cache: Option<VaultCache>) -> Self {
+        self.vault_cache = cache;
+        self
+    }
+    /// Returns the attached vault cache, if any.
+    #[must_use]
+    pub fn vault_cache(&self) -> Option<&VaultCache> {
+        self.vault_cache.as_ref()
+    }
+    /// Returns the vault cache along with the key of this database's entries in it, if vaults
+    /// are cached.
+    fn active_vault_cache(&self) -> Option<(&VaultCache, u64)> {
+        self.vault_cache.as_ref().zip(self.vault_database)
+    }
+    /// Reports a deprecated item to the attached channel (and as a `tracing` event).
+    fn deprecated(&self, item: DeprecatedItem, name: String, note: &str) {
+        let Some(warnings) = &self.deprecations else {
+            return;
+        };
+        let deprecation = Deprecation::new(self.connection_path.clone(), item, name, note);
+        #[cfg(feature = "tracing")]
+        let event = deprecation.clone();
+        if warnings.warn(deprecation) {
+            #[cfg(feature = "tracing")]
+            tracing::warn!(
+                database = %event.database,
+                item = %event.item,
+                name = %event.name,
+                replacement = ?event.replacement,
+                "{event}"
+            );
+        }
+    }
+    /// Returns a handle whose fetches fail with [`CCDBError::LimitError`] when they exceed
+    /// `limits`.
+    ///
+    /// A fetch's run count is checked before any query runs, and its result size is estimated
+    /// from the stored payloads before they are decoded. Handles derived from the returned client
+    /// (directories, tables) share the limits. Handles start with the limits set in the
+    /// environment (see [`gluex_core::limits`]).
+    #[must_use]
+    pub fn with_limits(mut self, limits: QueryLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+    /// Returns the limits applied to every fetch.
+    #[must_use]
+    pub fn limits(&self) -> QueryLimits {
+        self.limits
+    }
+    /// Returns the newest schema version recorded in the snapshot's `schemaVersions` table, if
+    /// any.
+    #[must_use]
+    pub fn schema_version(&self) -> Option<i64> {
+        self.schema_version
+    }
+    /// Logs a statement to the attached audit and returns whether it should be executed.
+    fn audit(&self, sql: &str, params: impl FnOnce() -> Vec<String>, reads_data: bool) -> bool {
+        self.sql_audit
+            .as_ref()
+            .is_none_or(|audit| audit.log(&self.connection_path, sql, params(), reads_data))
+    }
+    fn cache_lookup(&self, cache: &str, hit: bool) {
+        if let Some(metrics) = &self.metrics {
+            if hit {
+                metrics.cache_hit(cache);
+            } else {
+                metrics.cache_miss(cache);
+            }
+        }
     }
     /// Returns the filesystem path used to open the database.
     #[must_use]
@@ -94,31 +576,31 @@ impl CCDB {
         &self.connection_path
     }
     fn load_directories(&self) -> CCDBResult<()> {
-        let connection = self.connection();
-        let mut stmt = connection.prepare(
+        let rows = self.select(
             "SELECT id, created, modified, name, parentId, authorId, comment,
                     isDeprecated, deprecatedByUserId, isLocked, lockedByUserId
              FROM directories",
+            &[],
+            None,
+            |row| {
+                Ok(DirectoryMeta {
+                    id: row.get(0)?,
+                    created: row.get(1)?,
+                    modified: row.get(2)?,
+                    name: row.get(3)?,
+                    parent_id: row.get(4)?,
+                    author_id: row.get(5)?,
+                    comment: row.get(6).unwrap_or_default(),
+                    is_deprecated: row.get(7).unwrap_or_default(),
+                    deprecated_by_user_id: row.get(8).unwrap_or_default(),
+                    is_locked: row.get(9).unwrap_or_default(),
+                    locked_by_user_id: row.get(10).unwrap_or_default(),
+                })
+            },
         )?;
-        let rows = stmt.query_map([], |row| {
-            Ok(DirectoryMeta {
-                id: row.get(0)?,
-                created: row.get(1)?,
-                modified: row.get(2)?,
-                name: row.get(3)?,
-                parent_id: row.get(4)?,
-                author_id: row.get(5)?,
-                comment: row.get(6).unwrap_or_default(),
-                is_deprecated: row.get(7).unwrap_or_default(),
-                deprecated_by_user_id: row.get(8).unwrap_or_default(),
-                is_locked: row.get(9).unwrap_or_default(),
-                locked_by_user_id: row.get(10).unwrap_or_default(),
-            })
-        })?;
         self.directory_meta.clear();
         self.directory_by_path.clear();
         for dir in rows {
-            let dir = dir?;
             let id = dir.id;
             let path = self.build_dir_path_from_meta(&dir);
             self.directory_by_path.insert(path, id);
@@ -141,36 +623,36 @@ impl CCDB {
         }
     }
     fn load_tables(&self) -> CCDBResult<()> {
-        let connection = self.connection();
-        let mut stmt = connection.prepare(
+        let rows = self.select(
             "SELECT id, created, modified, directoryId, name,
                     nRows, nColumns, nAssignments, authorId, comment,
                     isDeprecated, deprecatedByUserId, isLocked, lockedByUserId, lockTime
              FROM typeTables",
+            &[],
+            None,
+            |row| {
+                Ok(TypeTableMeta {
+                    id: row.get(0)?,
+                    created: row.get(1)?,
+                    modified: row.get(2)?,
+                    directory_id: row.get(3)?,
+                    name: row.get(4)?,
+                    n_rows: row.get(5)?,
+                    n_columns: row.get(6)?,
+                    n_assignments: row.get(7)?,
+                    author_id: row.get(8)?,
+                    comment: row.get(9).unwrap_or_default(),
+                    is_deprecated: row.get(10).unwrap_or_default(),
+                    deprecated_by_user_id: row.get(11).unwrap_or_default(),
+                    is_locked: row.get(12).unwrap_or_default(),
+                    locked_by_user_id: row.get(13).unwrap_or_default(),
+                    lock_time: row.get(14).unwrap_or_default(),
+                })
+            },
         )?;
-        let rows = stmt.query_map([], |row| {
-            Ok(TypeTableMeta {
-                id: row.get(0)?,
-                created: row.get(1)?,
-                modified: row.get(2)?,
-                directory_id: row.get(3)?,
-                name: row.get(4)?,
-                n_rows: row.get(5)?,
-                n_columns: row.get(6)?,
-                n_assignments: row.get(7)?,
-                author_id: row.get(8)?,
-                comment: row.get(9).unwrap_or_default(),
-                is_deprecated: row.get(10).unwrap_or_default(),
-                deprecated_by_user_id: row.get(11).unwrap_or_default(),
-                is_locked: row.get(12).unwrap_or_default(),
-                locked_by_user_id: row.get(13).unwrap_or_default(),
-                lock_time: row.get(14).unwrap_or_default(),
-            })
-        })?;
         self.table_meta.clear();
         self.table_by_dir_name.clear();
         for table in rows {
-            let table = table?;
             let id = table.id;
             let key = (table.directory_id, table.name.clone());
             self.table_by_dir_name.insert(key, id);
@@ -216,6 +698,26 @@ impl CCDB {
         })
     }
 
+    /// Returns handles to every directory in the database, in no particular order.
+    pub(crate) fn all_directories(&self) -> Vec<DirectoryHandle> {
+        self.directory_meta
+            .iter()
+            .map(|meta| DirectoryHandle {
+                db: self.clone(),
+                meta: meta.value().clone(),
+            })
+            .collect()
+    }
+    /// Returns handles to every table in the database, in no particular order.
+    pub(crate) fn all_tables(&self) -> Vec<TypeTableHandle> {
+        self.table_meta
+            .iter()
+            .map(|meta| TypeTableHandle {
+                db: self.clone(),
+                meta: meta.value().clone(),
+            })
+            .collect()
+    }
     /// Resolves a table path ("/dir/name") into a handle.
     ///
     /// # Errors
@@ -236,84 +738,106 @@ impl CCDB {
     ///
     /// This method returns an error if the variation cannot be found.
     pub fn variation(&self, name: &str) -> CCDBResult<VariationMeta> {
-        if let Some(v) = self.variation_cache.get(name) {
-            return Ok(v.clone());
+        let cached = self.variation_cache.get(name).map(|v| v.clone());
+        self.cache_lookup("ccdb.variations", cached.is_some());
+        if let Some(v) = cached {
+            return Ok(v);
         }
-        let connection = self.connection();
-        let mut stmt = connection.prepare_cached(
-            "SELECT id, created, modified, name, description, authorId, comment,
+        let sql = "SELECT id, created, modified, name, description, authorId, comment,
                     parentId, isLocked, lockTime, lockedByUserId,
                     goBackBehavior, goBackTime, isDeprecated, deprecatedByUserId
              FROM variations
-             WHERE name = ?",
+             WHERE name = ?";
+        self.audit(sql, || vec![text_literal(name)], false);
+        let found = self.select(
+            sql,
+            &[SqlValue::Text(name.to_string())],
+            None,
+            variation_from_row,
         )?;
-        let mut rows = stmt.query([name])?;
-        if let Some(r) = rows.next()? {
-            let var = VariationMeta {
-                id: r.get(0)?,
-                created: r.get(1)?,
-                modified: r.get(2)?,
-                name: r.get(3)?,
-                description: r.get(4).unwrap_or_default(),
-                author_id: r.get(5)?,
-                comment: r.get(6).unwrap_or_default(),
-                parent_id: r.get(7)?,
-                is_locked: r.get(8).unwrap_or_default(),
-                lock_time: r.get(9).unwrap_or_default(),
-                locked_by_user_id: r.get(10).unwrap_or_default(),
-                go_back_behavior: r.get(11).unwrap_or_default(),
-                go_back_time: r.get(12).unwrap_or_default(),
-                is_deprecated: r.get(13).unwrap_or_default(),
-                deprecated_by_user_id: r.get(14).unwrap_or_default(),
-            };
+        if let Some(var) = found.into_iter().next() {
             self.variation_cache.insert(name.to_string(), var.clone());
             Ok(var)
         } else {
             Err(CCDBError::VariationNotFoundError(name.to_string()))
         }
     }
+    /// Lists every variation in the database ordered by identifier.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the variations table cannot be queried.
+    pub fn variations(&self) -> CCDBResult<Vec<VariationMeta>> {
+        let sql = "SELECT id, created, modified, name, description, authorId, comment,
+                    parentId, isLocked, lockTime, lockedByUserId,
+                    goBackBehavior, goBackTime, isDeprecated, deprecatedByUserId
+             FROM variations
+             ORDER BY id";
+        self.audit(sql, Vec::new, false);
+        let variations = self.select(sql, &[], None, variation_from_row)?;
+        for var in &variations {
+            self.variation_cache
+                .entry(var.name.clone())
+                .or_insert_with(|| var.clone());
+        }
+        Ok(variations)
+    }
+    /// Lists the variations whose parent is the variation with identifier `id`, ordered by
+    /// identifier, so the hierarchy can be walked from the root downwards.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the variations table cannot be queried.
+    pub fn variation_children(&self, id: Id) -> CCDBResult<Vec<VariationMeta>> {
+        let sql = "SELECT id, created, modified, name, description, authorId, comment,
+                    parentId, isLocked, lockTime, lockedByUserId,
+                    goBackBehavior, goBackTime, isDeprecated, deprecatedByUserId
+             FROM variations
+             WHERE parentId = ? AND id != parentId
+             ORDER BY id";
+        self.audit(sql, || vec![id.to_string()], false);
+        let children = self.select(sql, &[SqlValue::Integer(id)], None, variation_from_row)?;
+        for var in &children {
+            self.variation_cache
+                .entry(var.name.clone())
+                .or_insert_with(|| var.clone());
+        }
+        Ok(children)
+    }
     /// Resolves a variation chain from the given starting variation up to the root.
     ///
     /// # Errors
     ///
     /// This method returns an error if any of the variations cannot be found.
     pub fn variation_chain(&self, start: &VariationMeta) -> CCDBResult<Vec<VariationMeta>> {
-        if let Some(cached) = self.variation_chain_cache.get(&start.id) {
-            return Ok(cached.clone());
+        let cached = self
+            .variation_chain_cache
+            .get(&start.id)
+            .map(|chain| chain.clone());
+        self.cache_lookup("ccdb.variation_chains", cached.is_some());
+        if let Some(chain) = cached {
+            return Ok(chain);
         }
         let mut chain = Vec::new();
         let mut current = start.clone();
 
         chain.push(current.clone());
-        let connection = self.connection();
-        let mut stmt = connection.prepare_cached(
-            "SELECT id, created, modified, name, description, authorId, comment,
+        let sql = "SELECT id, created, modified, name, description, authorId, comment,
                     parentId, isLocked, lockTime, lockedByUserId,
                     goBackBehavior, goBackTime, isDeprecated, deprecatedByUserId
              FROM variations
-             WHERE id = ?",
-        )?;
+             WHERE id = ?";
 
         while current.parent_id > 0 {
-            let mut rows = stmt.query([current.parent_id])?;
-            if let Some(r) = rows.next()? {
-                current = VariationMeta {
-                    id: r.get(0)?,
-                    created: r.get(1)?,
-                    modified: r.get(2)?,
-                    name: r.get(3)?,
-                    description: r.get(4).unwrap_or_default(),
-                    author_id: r.get(5)?,
-                    comment: r.get(6).unwrap_or_default(),
-                    parent_id: r.get(7)?,
-                    is_locked: r.get(8).unwrap_or_default(),
-                    lock_time: r.get(9).unwrap_or_default(),
-                    locked_by_user_id: r.get(10).unwrap_or_default(),
-                    go_back_behavior: r.get(11).unwrap_or(0),
-                    go_back_time: r.get(12).unwrap_or_default(),
-                    is_deprecated: r.get(13).unwrap_or_default(),
-                    deprecated_by_user_id: r.get(14).unwrap_or_default(),
-                };
+            self.audit(sql, || vec![current.parent_id.to_string()], false);
+            let parent = self.select(
+                sql,
+                &[SqlValue::Integer(current.parent_id)],
+                None,
+                variation_from_row,
+            )?;
+            if let Some(parent) = parent.into_iter().next() {
+                current = parent;
                 chain.push(current.clone());
             } else {
                 break;
@@ -323,7 +847,47 @@ impl CCDB {
         self.variation_chain_cache.insert(start.id, chain.clone());
         Ok(chain)
     }
-    /// Parses a request string of the form "/path:run:variation:timestamp" (see [`Request`]) and fetches data.
+    /// Looks up the user with identifier `id`, as referenced by the `author_id` fields of
+    /// directories, tables, variations, and assignments, caching repeated lookups.
+    ///
+    /// Returns [`None`] when no such user exists (for example, when the author was removed).
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the users table cannot be queried.
+    pub fn user(&self, id: Id) -> CCDBResult<Option<UserMeta>> {
+        let cached = self.user_cache.get(&id).map(|user| user.clone());
+        self.cache_lookup("ccdb.users", cached.is_some());
+        if cached.is_some() {
+            return Ok(cached);
+        }
+        let sql = "SELECT id, created, lastActionTime, name, roles, info FROM users WHERE id = ?";
+        self.audit(sql, || vec![id.to_string()], false);
+        let found = self
+            .select(sql, &[SqlValue::Integer(id)], None, user_from_row)?
+            .into_iter()
+            .next();
+        if let Some(user) = &found {
+            self.user_cache.insert(id, user.clone());
+        }
+        Ok(found)
+    }
+    /// Lists every user in the database ordered by identifier.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the users table cannot be queried.
+    pub fn users(&self) -> CCDBResult<Vec<UserMeta>> {
+        let sql = "SELECT id, created, lastActionTime, name, roles, info FROM users ORDER BY id";
+        self.audit(sql, Vec::new, false);
+        let users = self.select(sql, &[], None, user_from_row)?;
+        for user in &users {
+            self.user_cache.insert(user.id, user.clone());
+        }
+        Ok(users)
+    }
+    /// Parses a request string of the form "/path:run:variation:timestamp" (see [`Request`] for
+    /// the full grammar, including named options and flags) and fetches data.
     ///
     /// # Errors
     ///
@@ -344,6 +908,301 @@ impl CCDB {
         let table = self.table(path)?;
         table.fetch(ctx)
     }
+
+    /// Fetches a table path like [`CCDB::fetch`] and reads every row into `T` (see
+    /// [`TypeTableHandle::fetch_typed`]).
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the table path does not exist, an error occurs while
+    /// fetching data, or a row cannot be read into `T`.
+    pub fn fetch_typed<T: CcdbTable>(
+        &self,
+        path: &str,
+        ctx: &Context,
+    ) -> CCDBResult<BTreeMap<RunNumber, Vec<T>>> {
+        self.table(path)?.fetch_typed(ctx)
+    }
+
+    /// Fetches several tables for the same [`Context`], returning each table's constants keyed
+    /// by the path it was requested with.
+    ///
+    /// This gives the same result as calling [`CCDB::fetch`] once per path, but the variation
+    /// chain is resolved once and each variation in it is searched with a single assignment
+    /// query covering every table that still has unresolved runs, rather than one query per
+    /// table. Query limits apply to the batch as a whole.
+    ///
+    /// ```no_run
+    /// use gluex_ccdb::{context::Context, database::CCDB};
+    ///
+    /// let db = CCDB::open("ccdb.sqlite")?;
+    /// let ctx = Context::default().with_run_range(30_000..=30_100);
+    /// let tables = db.fetch_many(["/PHOTON_BEAM/endpoint_energy", "/TARGET/density"], &ctx)?;
+    /// for (run, data) in &tables["/TARGET/density"] {
+    ///     println!("{run}: {:?}", data.double(0, 0));
+    /// }
+    /// # Ok::<(), gluex_ccdb::CCDBError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if any table path does not exist, or if resolving
+    /// assignments or decoding payloads fails for any of them.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            name = "ccdb.fetch_many",
+            skip_all,
+            fields(variation = %ctx.variation, runs = ctx.runs.len())
+        )
+    )]
+    pub fn fetch_many<S>(
+        &self,
+        paths: S,
+        ctx: &Context,
+    ) -> CCDBResult<BTreeMap<String, BTreeMap<RunNumber, Data>>>
+    where
+        S: IntoIterator,
+        S::Item: AsRef<str>,
+    {
+        let tables = paths
+            .into_iter()
+            .map(|path| Ok((path.as_ref().to_string(), self.table(path.as_ref())?)))
+            .collect::<CCDBResult<Vec<_>>>()?;
+        let timer = self
+            .metrics
+            .as_ref()
+            .map(|metrics| metrics.timer("ccdb.fetch_many"));
+        let mut budget = self.limits.start();
+        budget.check_runs(ctx.runs.len())?;
+        let runs: Vec<RunNumber> = if ctx.runs.is_empty() {
+            vec![0]
+        } else {
+            ctx.runs.clone()
+        };
+        let mut table_ids: Vec<Id> = tables.iter().map(|(_, table)| table.meta.id).collect();
+        table_ids.sort_unstable();
+        table_ids.dedup();
+        let mut assignments =
+            self.resolve_assignments(&table_ids, &runs, &ctx.variation, ctx.timestamp, &budget)?;
+        let mut results = BTreeMap::new();
+        for (path, table) in tables {
+            let resolved = assignments.entry(table.meta.id).or_default();
+            let data = table.load_resolved(ctx, &runs, resolved, &mut budget)?;
+            results.insert(path, data);
+        }
+        if let Some(timer) = timer {
+            timer.finish(
+                results
+                    .values()
+                    .flat_map(BTreeMap::values)
+                    .map(Data::n_rows)
+                    .sum(),
+            );
+        }
+        Ok(results)
+    }
+
+    /// Returns the `SQLite` query plans of the statements behind [`CCDB::fetch`] for a table path
+    /// (see [`TypeTableHandle::explain_fetch`]).
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the table path or the context's variation does not exist,
+    /// or if `SQLite` cannot prepare a statement.
+    pub fn explain_fetch(&self, path: &str, ctx: &Context) -> CCDBResult<Vec<QueryPlan>> {
+        self.table(path)?.explain_fetch(ctx)
+    }
+    /// Resolves the assignment each of `runs` uses in every table of `table_ids`, walking the
+    /// variation chain from `variation` and searching each variation with one query for all
+    /// tables that still have unresolved runs.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            name = "ccdb.resolve_assignments",
+            skip_all,
+            fields(
+                variation = %variation,
+                tables = table_ids.len(),
+                runs = runs.len(),
+                resolved = tracing::field::Empty
+            )
+        )
+    )]
+    pub(crate) fn resolve_assignments(
+        &self,
+        table_ids: &[Id],
+        runs: &[RunNumber],
+        variation: &str,
+        timestamp: DateTime<Utc>,
+        budget: &QueryBudget,
+    ) -> CCDBResult<HashMap<Id, BTreeMap<RunNumber, ResolvedAssignment>>> {
+        let mut final_assignments: HashMap<Id, BTreeMap<RunNumber, ResolvedAssignment>> =
+            HashMap::new();
+        let (Some(&min_run), Some(&max_run)) = (runs.iter().min(), runs.iter().max()) else {
+            return Ok(final_assignments);
+        };
+        let start_var_meta = self.variation(variation)?;
+        let var_chain = self.variation_chain(&start_var_meta)?;
+        let mut unresolved: HashMap<Id, HashSet<RunNumber>> = table_ids
+            .iter()
+            .map(|id| (*id, runs.iter().copied().collect()))
+            .collect();
+        let mut timestamp = timestamp;
+        for var_meta in var_chain {
+            let pending: Vec<Id> = table_ids
+                .iter()
+                .copied()
+                .filter(|id| unresolved.get(id).is_some_and(|runs| !runs.is_empty()))
+                .collect();
+            if pending.is_empty() {
+                break;
+            }
+            let rows =
+                self.query_assignments(&pending, &var_meta, timestamp, min_run, max_run, budget)?;
+            for id in pending {
+                let Some(table_runs) = unresolved.get_mut(&id) else {
+                    continue;
+                };
+                let table_rows: Vec<&AssignmentRow> = rows
+                    .iter()
+                    .filter(|(_, constant_set, _, _)| constant_set.constant_type_id == id)
+                    .collect();
+                let partial = newest_assignments(table_runs, &table_rows)?;
+                let resolved = final_assignments.entry(id).or_default();
+                for (run, meta) in partial {
+                    resolved.insert(run, meta);
+                    table_runs.remove(&run);
+                }
+            }
+            // parents are searched no later than the go-back time of the variation we leave
+            if let Some(cutoff) = var_meta.go_back_cutoff() {
+                timestamp = timestamp.min(cutoff);
+            }
+        }
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record(
+            "resolved",
+            final_assignments.values().map(BTreeMap::len).sum::<usize>(),
+        );
+        Ok(final_assignments)
+    }
+    /// Runs the assignment query of one variation for the tables in `table_ids`.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            name = "ccdb.query",
+            skip_all,
+            fields(
+                sql = tracing::field::Empty,
+                variation = %var_meta.name,
+                rows = tracing::field::Empty
+            )
+        )
+    )]
+    fn query_assignments(
+        &self,
+        table_ids: &[Id],
+        var_meta: &VariationMeta,
+        timestamp: DateTime<Utc>,
+        min_run: RunNumber,
+        max_run: RunNumber,
+        budget: &QueryBudget,
+    ) -> CCDBResult<Vec<AssignmentRow>> {
+        budget.check_time()?;
+        let timer = self
+            .metrics
+            .as_ref()
+            .map(|metrics| metrics.timer("ccdb.assignments"));
+        // with a vault cache, vaults are read only for the constant sets it misses
+        let sql = assignments_sql(
+            self.backend(),
+            table_ids.len(),
+            self.active_vault_cache().is_none(),
+        );
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("sql", sql.as_str());
+        let mut params: Vec<SqlValue> = table_ids.iter().map(|id| SqlValue::Integer(*id)).collect();
+        params.extend([
+            SqlValue::Integer(timestamp.timestamp()),
+            SqlValue::Integer(var_meta.id),
+            SqlValue::Integer(min_run),
+            SqlValue::Integer(max_run),
+        ]);
+        let execute = self.audit(
+            &sql,
+            || {
+                table_ids
+                    .iter()
+                    .map(ToString::to_string)
+                    .chain([
+                        timestamp.timestamp().to_string(),
+                        var_meta.id.to_string(),
+                        min_run.to_string(),
+                        max_run.to_string(),
+                    ])
+                    .collect()
+            },
+            true,
+        );
+        if !execute {
+            return Ok(Vec::new());
+        }
+        let rows = self.select(&sql, &params, Some(budget), |row| {
+            let meta = AssignmentMetaLite {
+                id: row.get(0)?,
+                created: row.get(1)?,
+                constant_set_id: row.get(2)?,
+            };
+            let constant_set = ConstantSetMeta {
+                id: row.get(3)?,
+                created: row.get(4)?,
+                modified: row.get(5)?,
+                vault: row.get(6)?,
+                constant_type_id: row.get(7)?,
+            };
+            let run_min: RunNumber = row.get(8)?;
+            let run_max: RunNumber = row.get(9)?;
+            Ok((meta, constant_set, run_min, run_max))
+        })?;
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("rows", rows.len());
+        if let Some(timer) = timer {
+            timer.finish(rows.len());
+        }
+        Ok(rows)
+    }
+    /// Reads the vault text of each constant set in `ids`, for assignments resolved without
+    /// their vaults.
+    fn constant_set_vaults(
+        &self,
+        ids: &[Id],
+        budget: &QueryBudget,
+    ) -> CCDBResult<HashMap<Id, String>> {
+        let mut vaults = HashMap::with_capacity(ids.len());
+        // stay below SQLite's default limit on statement parameters
+        for chunk in ids.chunks(500) {
+            let sql = format!(
+                "SELECT id, vault FROM constantSets WHERE id IN ({})",
+                vec!["?"; chunk.len()].join(", ")
+            );
+            if !self.audit(
+                &sql,
+                || chunk.iter().map(ToString::to_string).collect(),
+                true,
+            ) {
+                continue;
+            }
+            let params: Vec<SqlValue> = chunk.iter().map(|id| SqlValue::Integer(*id)).collect();
+            vaults.extend(self.select(&sql, &params, Some(budget), |row| {
+                Ok((row.get::<Id>(0)?, row.get::<String>(1)?))
+            })?);
+        }
+        Ok(vaults)
+    }
 }
 
 /// Handle to a CCDB directory, allowing navigation and table discovery.
@@ -503,35 +1362,59 @@ impl TypeTableHandle {
     pub fn columns(&self) -> CCDBResult<Vec<ColumnMeta>> {
         Ok(self.column_layout()?.columns().to_vec())
     }
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            name = "ccdb.query",
+            skip_all,
+            fields(sql = COLUMNS_SQL, table = self.meta.id, rows = tracing::field::Empty)
+        )
+    )]
     fn load_column_metadata(&self) -> CCDBResult<Vec<ColumnMeta>> {
-        let connection = self.db.connection();
-        let mut stmt = connection.prepare_cached(
-            "SELECT id, created, modified, name, typeId, columnType, `order`, comment
-             FROM columns
-             WHERE typeId = ?
-             ORDER BY `order`",
-        )?;
-        let columns = stmt
-            .query_map([self.meta.id], |row| {
+        let timer = self
+            .db
+            .metrics
+            .as_ref()
+            .map(|metrics| metrics.timer("ccdb.columns"));
+        self.db
+            .audit(COLUMNS_SQL, || vec![self.meta.id.to_string()], false);
+        let columns = self.db.select(
+            COLUMNS_SQL,
+            &[SqlValue::Integer(self.meta.id)],
+            None,
+            |row| {
                 Ok(ColumnMeta {
                     id: row.get(0)?,
                     created: row.get(1)?,
                     modified: row.get(2)?,
                     name: row.get(3).unwrap_or_default(),
                     type_id: row.get(4)?,
-                    column_type: ColumnType::type_from_str(&row.get::<_, String>(5)?)
+                    column_type: ColumnType::type_from_str(&row.get::<String>(5)?)
                         .unwrap_or_default(),
                     order: row.get(6)?,
                     comment: row.get(7).unwrap_or_default(),
                 })
-            })?
-            .collect::<Result<Vec<ColumnMeta>, _>>()?;
+            },
+        )?;
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("rows", columns.len());
+        if let Some(timer) = timer {
+            timer.finish(columns.len());
+        }
         Ok(columns)
     }
 
     fn column_layout(&self) -> CCDBResult<Arc<ColumnLayout>> {
-        if let Some(existing) = self.db.column_layouts.get(&self.meta.id) {
-            return Ok(existing.clone());
+        let cached = self
+            .db
+            .column_layouts
+            .get(&self.meta.id)
+            .map(|layout| layout.clone());
+        self.db
+            .cache_lookup("ccdb.column_layouts", cached.is_some());
+        if let Some(layout) = cached {
+            return Ok(layout);
         }
         let columns = self.load_column_metadata()?;
         let layout = Arc::new(ColumnLayout::new(columns));
@@ -544,127 +1427,183 @@ impl TypeTableHandle {
     ///
     /// Returns an error if resolving assignments fails, if any SQL queries fail, or if vault data
     /// cannot be decoded for the requested runs.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            name = "ccdb.fetch",
+            skip_all,
+            fields(table = %self.full_path(), variation = %ctx.variation, runs = ctx.runs.len())
+        )
+    )]
     pub fn fetch(&self, ctx: &Context) -> CCDBResult<BTreeMap<RunNumber, Data>> {
+        let timer = self
+            .db
+            .metrics
+            .as_ref()
+            .map(|metrics| metrics.timer("ccdb.fetch"));
+        let mut budget = self.db.limits.start();
+        budget.check_runs(ctx.runs.len())?;
         let runs: Vec<RunNumber> = if ctx.runs.is_empty() {
             vec![0]
         } else {
             ctx.runs.clone() // PERF: is this ever expensive?
         };
-        let assignments = self.resolve_assignments(&runs, &ctx.variation, ctx.timestamp)?;
+        let assignments =
+            self.resolve_assignments(&runs, &ctx.variation, ctx.timestamp, &budget)?;
+        let data = self.load_resolved(ctx, &runs, &assignments, &mut budget)?;
+        if let Some(timer) = timer {
+            timer.finish(data.values().map(Data::n_rows).sum());
+        }
+        Ok(data)
+    }
+    /// Reports deprecations, records the fetch, and decodes the payloads of the assignments
+    /// resolved for `runs` in `ctx`.
+    fn load_resolved(
+        &self,
+        ctx: &Context,
+        #[cfg_attr(not(feature = "provenance"), allow(unused_variables))] runs: &[RunNumber],
+        assignments: &BTreeMap<RunNumber, ResolvedAssignment>,
+        budget: &mut QueryBudget,
+    ) -> CCDBResult<BTreeMap<RunNumber, Data>> {
+        self.report_deprecations(&ctx.variation);
+        #[cfg(feature = "provenance")]
+        if let Some(recorder) = &self.db.recorder {
+            recorder.record(Query::Ccdb(CcdbQuery {
+                database: self.db.connection_path.clone(),
+                table: self.full_path(),
+                variation: ctx.variation.clone(),
+                timestamp: ctx.timestamp,
+                runs: runs.to_vec(),
+                assignments: assignments
+                    .iter()
+                    .map(|(run, assignment)| (*run, assignment.id))
+                    .collect(),
+            }));
+        }
         if assignments.is_empty() {
-            return Ok(BTreeMap::new());
+            Ok(BTreeMap::new())
+        } else {
+            self.load_vaults(assignments, budget)
         }
-        self.load_vaults(&assignments)
     }
-    fn resolve_assignments(
+    /// Fetches data like [`TypeTableHandle::fetch`] and reads every row into `T`, keeping the
+    /// table's row order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the fetch fails, or if the table lacks a column `T` reads or stores it
+    /// with a different type.
+    pub fn fetch_typed<T: CcdbTable>(
         &self,
-        runs: &[RunNumber],
-        variation: &str,
-        timestamp: DateTime<Utc>,
-    ) -> CCDBResult<BTreeMap<RunNumber, Arc<ConstantSetMeta>>> {
-        if runs.is_empty() {
-            return Ok(BTreeMap::new());
+        ctx: &Context,
+    ) -> CCDBResult<BTreeMap<RunNumber, Vec<T>>> {
+        self.fetch(ctx)?
+            .into_iter()
+            .map(|(run, data)| Ok((run, T::from_data(&data)?)))
+            .collect()
+    }
+    /// Reports this table, its directories, and `variation` if they are deprecated.
+    fn report_deprecations(&self, variation: &str) {
+        if self.db.deprecations.is_none() {
+            return;
         }
-        let min_run = *runs.iter().min().expect("this is a bug, please report it!");
-        let max_run = *runs.iter().max().expect("this is a bug, please report it!");
-        let start_var_meta = self.db.variation(variation)?;
-        let var_chain = self.db.variation_chain(&start_var_meta)?;
-        let mut final_assignments: BTreeMap<RunNumber, Arc<ConstantSetMeta>> = BTreeMap::new();
-        let mut unresolved: HashSet<RunNumber> = runs.iter().copied().collect();
-        for var_meta in var_chain {
-            if unresolved.is_empty() {
-                break;
-            }
-            let partial = self.resolve_assignments_for_variation(
-                &unresolved,
-                &var_meta,
-                timestamp,
-                min_run,
-                max_run,
-            )?;
-            for (run, meta) in partial {
-                final_assignments.insert(run, meta);
-                unresolved.remove(&run);
+        if self.meta.is_deprecated {
+            self.db.deprecated(
+                DeprecatedItem::CcdbTable,
+                self.full_path(),
+                &self.meta.comment,
+            );
+        }
+        let mut directory = self
+            .db
+            .directory_meta
+            .get(&self.meta.directory_id)
+            .map(|meta| meta.clone());
+        while let Some(meta) = directory {
+            directory = self
+                .db
+                .directory_meta
+                .get(&meta.parent_id)
+                .map(|parent| parent.clone());
+            if meta.is_deprecated {
+                let comment = meta.comment.clone();
+                let path = DirectoryHandle {
+                    db: self.db.clone(),
+                    meta,
+                }
+                .full_path();
+                self.db
+                    .deprecated(DeprecatedItem::CcdbDirectory, path, &comment);
             }
         }
-        Ok(final_assignments)
+        let variation = self
+            .db
+            .variation_cache
+            .get(variation)
+            .map(|meta| meta.clone());
+        if let Some(variation) = variation.filter(|variation| variation.is_deprecated) {
+            self.db.deprecated(
+                DeprecatedItem::CcdbVariation,
+                variation.name.clone(),
+                &variation.comment,
+            );
+        }
     }
-    fn resolve_assignments_for_variation(
-        &self,
-        runs: &HashSet<RunNumber>,
-        var_meta: &VariationMeta,
-        timestamp: DateTime<Utc>,
-        min_run: RunNumber,
-        max_run: RunNumber,
-    ) -> CCDBResult<BTreeMap<RunNumber, Arc<ConstantSetMeta>>> {
-        let connection = self.db.connection();
-        let mut stmt = connection.prepare_cached(
-            "SELECT
-                 a.id, a.created, a.constantSetId,
-                 cs.id, cs.created, cs.modified, cs.vault, cs.constantTypeId,
-                 rr.runMin, rr.runMax
-             FROM assignments a
-             JOIN constantSets cs ON cs.id = a.constantSetId
-             JOIN runRanges rr ON rr.id = a.runRangeId
-             WHERE cs.constantTypeId = ?
-               AND a.created <= datetime(?, 'unixepoch', 'localtime')
-               AND a.variationId = ?
-               AND rr.runMax >= ?
-               AND rr.runMin <= ?",
-        )?;
-        let valid_assignments = stmt
-            .query_map(
-                (
+    /// Returns the `SQLite` query plans of the statements [`TypeTableHandle::fetch`] runs for
+    /// this table: the column lookup, then the assignment lookup for the requested variation
+    /// (which is repeated for each parent variation with the same plan). Nothing is executed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the context's variation does not exist or `SQLite` cannot prepare a
+    /// statement.
+    pub fn explain_fetch(&self, ctx: &Context) -> CCDBResult<Vec<QueryPlan>> {
+        let min_run = ctx.runs.iter().min().copied().unwrap_or(0);
+        let max_run = ctx.runs.iter().max().copied().unwrap_or(0);
+        let variation = self.db.variation(&ctx.variation)?;
+        let backend = self.db.backend();
+        Ok(vec![
+            explain(backend, COLUMNS_SQL, &[self.meta.id])?,
+            explain(
+                backend,
+                &assignments_sql(backend, 1, self.db.active_vault_cache().is_none()),
+                &[
                     self.meta.id,
-                    timestamp.timestamp(),
-                    var_meta.id,
+                    ctx.timestamp.timestamp(),
+                    variation.id,
                     min_run,
                     max_run,
-                ),
-                |row| {
-                    let meta = AssignmentMetaLite {
-                        id: row.get(0)?,
-                        created: row.get(1)?,
-                        constant_set_id: row.get(2)?,
-                    };
-                    let constant_set = ConstantSetMeta {
-                        id: row.get(3)?,
-                        created: row.get(4)?,
-                        modified: row.get(5)?,
-                        vault: row.get(6)?,
-                        constant_type_id: row.get(7)?,
-                    };
-                    let run_min: RunNumber = row.get(8)?;
-                    let run_max: RunNumber = row.get(9)?;
-                    Ok((meta, constant_set, run_min, run_max))
-                },
-            )?
-            .collect::<Result<Vec<(AssignmentMetaLite, ConstantSetMeta, RunNumber, RunNumber)>, _>>(
-            )?;
-        let mut best: BTreeMap<RunNumber, Arc<ConstantSetMeta>> = BTreeMap::new();
-        let mut best_created: HashMap<RunNumber, DateTime<Utc>> = HashMap::new(); // timestamp map
-        let mut constant_set_cache: HashMap<Id, Arc<ConstantSetMeta>> = HashMap::new();
-        for &run in runs {
-            for (meta, constant_set, rmin, rmax) in &valid_assignments {
-                if run >= *rmin && run <= *rmax {
-                    let cur_best = best_created.get(&run);
-                    let created = meta.created()?;
-                    if cur_best.is_none_or(|t| created > *t) {
-                        let cs_entry = constant_set_cache
-                            .entry(constant_set.id)
-                            .or_insert_with(|| Arc::new(constant_set.clone()))
-                            .clone();
-                        best.insert(run, cs_entry);
-                        best_created.insert(run, created);
-                    }
-                }
-            }
-        }
-        Ok(best)
+                ],
+            )?,
+        ])
     }
+    pub(crate) fn resolve_assignments(
+        &self,
+        runs: &[RunNumber],
+        variation: &str,
+        timestamp: DateTime<Utc>,
+        budget: &QueryBudget,
+    ) -> CCDBResult<BTreeMap<RunNumber, ResolvedAssignment>> {
+        Ok(self
+            .db
+            .resolve_assignments(&[self.meta.id], runs, variation, timestamp, budget)?
+            .remove(&self.meta.id)
+            .unwrap_or_default())
+    }
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            name = "ccdb.load_vaults",
+            skip_all,
+            fields(table = self.meta.id, assignments = assignments.len())
+        )
+    )]
     fn load_vaults(
         &self,
-        assignments: &BTreeMap<RunNumber, Arc<ConstantSetMeta>>,
+        assignments: &BTreeMap<RunNumber, ResolvedAssignment>,
+        budget: &mut QueryBudget,
     ) -> CCDBResult<BTreeMap<RunNumber, Data>> {
         if assignments.is_empty() {
             return Ok(BTreeMap::new());
@@ -672,14 +1611,71 @@ impl TypeTableHandle {
         let layout = self.column_layout()?;
         #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
         let n_rows = self.meta.n_rows as usize;
-        assignments
+        let Some((cache, database)) = self.db.active_vault_cache() else {
+            for assignment in assignments.values() {
+                budget.add_bytes(assignment.constant_set.vault.len())?;
+            }
+            budget.check_time()?;
+            return assignments
+                .iter()
+                .map(|(run, assignment)| {
+                    let vault = &assignment.constant_set.vault;
+                    Ok((*run, Data::from_vault(vault, layout.clone(), n_rows)?))
+                })
+                .collect();
+        };
+        let mut data = BTreeMap::new();
+        let mut misses = Vec::new();
+        for (run, assignment) in assignments {
+            let constant_set = &assignment.constant_set;
+            let cached = cache.load(
+                database,
+                constant_set.id,
+                &constant_set.modified,
+                &layout,
+                n_rows,
+            );
+            self.db.cache_lookup("ccdb.vault_cache", cached.is_some());
+            if let Some((cached, vault_len)) = cached {
+                budget.add_bytes(vault_len)?;
+                data.insert(*run, cached);
+            } else {
+                misses.push((*run, constant_set));
+            }
+        }
+        if misses.is_empty() {
+            return Ok(data);
+        }
+        let mut ids: Vec<Id> = misses
             .iter()
-            .map(|(run, constant_set)| {
-                Ok((
-                    *run,
-                    Data::from_vault(&constant_set.vault, layout.clone(), n_rows)?,
-                ))
-            })
-            .collect::<CCDBResult<BTreeMap<RunNumber, Data>>>()
+            .map(|(_, constant_set)| constant_set.id)
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        let vaults = self.db.constant_set_vaults(&ids, budget)?;
+        for (_, constant_set) in &misses {
+            budget.add_bytes(vaults.get(&constant_set.id).map_or(0, String::len))?;
+        }
+        budget.check_time()?;
+        let mut stored = HashSet::new();
+        for (run, constant_set) in misses {
+            let Some(vault) = vaults.get(&constant_set.id) else {
+                continue;
+            };
+            let parsed = Data::from_vault(vault, layout.clone(), n_rows)?;
+            if stored.insert(constant_set.id) {
+                cache.store(
+                    database,
+                    constant_set.id,
+                    &constant_set.modified,
+                    vault.len(),
+                    &parsed,
+                    &layout,
+                );
+            }
+            data.insert(run, parsed);
+        }
+        cache.trim();
+        Ok(data)
     }
 }
@@ -5,14 +5,49 @@
 use gluex_core::errors::ParseTimestampError;
 use thiserror::Error;
 
+/// Conversion of fetched tables to Arrow record batches.
+#[cfg(feature = "arrow")]
+pub mod arrow;
+/// Async wrappers that run CCDB queries on `tokio`'s blocking thread pool.
+#[cfg(feature = "async")]
+pub mod asynchronous;
+/// Storage backends the query layer reads through, with `SQLite` as the built-in one.
+pub mod backend;
+/// Comparison of table constants across run periods for calibration reviews.
+pub mod comparison;
 /// Context handling for run-, variation-, and timestamp-aware requests.
 pub mod context;
+/// CSV and other delimited text export of fetched tables.
+pub mod csv;
 /// Column-oriented data structures returned from CCDB queries.
 pub mod data;
 /// High-level database entry points and handles to CCDB objects.
 pub mod database;
 /// Lightweight structs that mirror CCDB tables.
 pub mod models;
+/// Stacks of CCDB files where lookups fall through from override snapshots to the official one.
+pub mod overlay;
+/// Concurrent fetches of several tables over per-thread read-only connections.
+#[cfg(feature = "parallel")]
+pub mod parallel;
+/// Background fetching of upcoming table and run-window requests.
+#[cfg(not(all(target_family = "wasm", target_os = "unknown")))]
+pub mod prefetch;
+/// Query recording and manifest verification.
+#[cfg(feature = "provenance")]
+pub mod provenance;
+/// Routing of fetches to the CCDB file that serves each run.
+pub mod router;
+/// Discovery of tables by glob patterns over their paths and by text search.
+pub mod search;
+/// Extraction and merging of standalone CCDB snapshot files.
+pub mod snapshot;
+/// Lazy, chunked iteration over the per-run constants of a table.
+pub mod stream;
+/// Reading table rows into user-defined structs.
+pub mod typed;
+/// On-disk cache of decoded vaults, reused across processes.
+pub mod vault_cache;
 
 /// Convenience alias for functions that can return a [`CCDBError`].
 pub type CCDBResult<T> = Result<T, CCDBError>;
@@ -23,6 +58,18 @@ pub enum CCDBError {
     /// Wrapper around [`rusqlite::Error`].
     #[error("{0}")]
     SqliteError(#[from] rusqlite::Error),
+    /// A compressed snapshot could not be decompressed.
+    #[cfg(feature = "compressed")]
+    #[error("{0}")]
+    CompressedSnapshotError(#[from] gluex_core::compressed::CompressedSnapshotError),
+    /// A database could not be extracted from an offline bundle.
+    #[cfg(feature = "bundle")]
+    #[error("{0}")]
+    BundleError(#[from] gluex_core::bundle::BundleError),
+    /// Snapshot bytes could not be loaded into the in-memory `SQLite` VFS used on WebAssembly.
+    #[cfg(all(feature = "wasm", target_family = "wasm", target_os = "unknown"))]
+    #[error("{0}")]
+    WasmVfsError(#[from] sqlite_wasm_rs::MemVfsError),
     /// Wrapper around data parsing or shape errors when decoding payloads.
     #[error("{0}")]
     CCDBDataError(#[from] crate::data::CCDBDataError),
@@ -50,10 +97,44 @@ pub enum CCDBError {
     /// Error parsing the requested run period.
     #[error("{0}")]
     RunPeriodError(#[from] gluex_core::run_periods::RunPeriodError),
+    /// A fetch exceeded the handle's resource limits.
+    #[error("{0}")]
+    LimitError(#[from] gluex_core::limits::LimitError),
+    /// The snapshot uses a newer schema than this build supports.
+    #[error("{0}")]
+    NewerSchemaError(#[from] gluex_core::schema::NewerSchemaError),
+    /// The operation needs an `SQLite` file on disk, but the handle reads from memory or another
+    /// backend.
+    #[error("unsupported source: {0}")]
+    UnsupportedSourceError(String),
+}
+
+impl CCDBError {
+    /// Returns true for errors that may go away on a later attempt: `SQLite` reporting the file
+    /// as busy or locked, or an I/O error such as a network filesystem timing out.
+    #[must_use]
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            Self::SqliteError(rusqlite::Error::SqliteFailure(error, _))
+                if matches!(
+                    error.code,
+                    rusqlite::ErrorCode::DatabaseBusy
+                        | rusqlite::ErrorCode::DatabaseLocked
+                        | rusqlite::ErrorCode::SystemIoFailure
+                )
+        )
+    }
 }
 
 /// Re-exports of the most commonly used types and constructors.
 pub mod prelude {
-    pub use crate::{context::Context, database::CCDB, CCDBError, CCDBResult};
-    pub use gluex_core::RunNumber;
+    pub use crate::{
+        context::Context, database::CCDB, overlay::CcdbOverlay, router::CcdbRouter, CCDBError,
+        CCDBResult,
+    };
+    pub use gluex_core::{
+        run_set::{RunContext, RunSet},
+        RunNumber,
+    };
 }
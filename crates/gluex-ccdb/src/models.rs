@@ -5,6 +5,8 @@ use std::fmt::Display;
 
 /// Typed representation of a column type.
 #[derive(Debug, Copy, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum ColumnType {
     /// A column of signed integers (i32).
     Int,
@@ -60,6 +62,7 @@ impl Display for ColumnType {
 
 /// Metadata row describing a column belonging to a CCDB constant type.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ColumnMeta {
     pub(crate) id: Id,
     pub(crate) created: String,
@@ -121,6 +124,7 @@ impl ColumnMeta {
 
 /// Metadata describing a directory entry that groups constant types.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DirectoryMeta {
     pub(crate) id: Id,
     pub(crate) created: String,
@@ -200,6 +204,7 @@ impl DirectoryMeta {
 
 /// Metadata describing a CCDB type table containing constants.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TypeTableMeta {
     pub(crate) id: Id,
     pub(crate) created: String,
@@ -307,6 +312,7 @@ impl TypeTableMeta {
 
 /// Metadata describing a stored set of constants for a type table.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ConstantSetMeta {
     pub(crate) id: Id,
     pub(crate) created: String,
@@ -351,6 +357,7 @@ impl ConstantSetMeta {
 
 /// Metadata describing an assignment of a constant set to a run/event range.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AssignmentMeta {
     pub(crate) id: Id,
     pub(crate) created: String,
@@ -418,6 +425,7 @@ impl AssignmentMeta {
 
 /// Lightweight assignment row containing only identity and creation info.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AssignmentMetaLite {
     pub(crate) id: Id,
     pub(crate) created: String,
@@ -446,6 +454,7 @@ impl AssignmentMetaLite {
 
 /// Metadata describing a variation that partitions assignments.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VariationMeta {
     pub(crate) id: Id,
     pub(crate) created: String,
@@ -504,7 +513,9 @@ impl VariationMeta {
     pub fn locked_by_user_id(&self) -> Id {
         self.locked_by_user_id
     }
-    /// Behavior flag defining how lookups walk parent variations.
+    /// Behavior flag defining how lookups walk parent variations: `0` searches the parent with the
+    /// requested timestamp, any other value searches it as of [`VariationMeta::go_back_time`] at
+    /// the latest (see [`VariationMeta::go_back_cutoff`]).
     #[must_use]
     pub fn go_back_behavior(&self) -> i64 {
         self.go_back_behavior
@@ -551,10 +562,24 @@ impl VariationMeta {
     pub fn go_back_time(&self) -> CCDBResult<DateTime<Utc>> {
         Ok(parse_timestamp(&self.go_back_time)?)
     }
+    /// Latest timestamp at which the parent variation is searched when a lookup falls back from
+    /// this one, or `None` if the parent is searched with the requested timestamp.
+    ///
+    /// This matches the C++ and Python CCDB clients, which only clamp the parent search when
+    /// `goBackBehavior` is nonzero and `goBackTime` converts to a positive Unix time: a zeroed
+    /// (`0000-00-00 00:00:00`), pre-epoch, or unparsable go-back time means no cutoff.
+    #[must_use]
+    pub fn go_back_cutoff(&self) -> Option<DateTime<Utc>> {
+        if self.go_back_behavior == 0 {
+            return None;
+        }
+        self.go_back_time().ok().filter(|time| time.timestamp() > 0)
+    }
 }
 
 /// Metadata describing an inclusive range of run numbers.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RunRangeMeta {
     pub(crate) id: Id,
     pub(crate) created: String,
@@ -611,6 +636,7 @@ impl RunRangeMeta {
 
 /// Metadata describing an inclusive event range bound to a run.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EventRangeMeta {
     pub(crate) id: Id,
     pub(crate) created: String,
@@ -664,3 +690,58 @@ impl EventRangeMeta {
         Ok(parse_timestamp(&self.modified)?)
     }
 }
+
+/// Metadata describing a CCDB user, the author referenced by `author_id` fields.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UserMeta {
+    pub(crate) id: Id,
+    pub(crate) created: String,
+    pub(crate) last_action_time: String,
+    pub(crate) name: String,
+    pub(crate) roles: String,
+    pub(crate) info: String,
+}
+
+impl UserMeta {
+    /// Identifier of the user row.
+    #[must_use]
+    pub fn id(&self) -> Id {
+        self.id
+    }
+    /// Login name of the user.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Roles granted to the user, as stored in CCDB's comma-separated list.
+    #[must_use]
+    pub fn roles(&self) -> Vec<&str> {
+        self.roles
+            .split(',')
+            .map(str::trim)
+            .filter(|role| !role.is_empty())
+            .collect()
+    }
+    /// Free-form description of the user, usually their full name.
+    #[must_use]
+    pub fn info(&self) -> &str {
+        &self.info
+    }
+    /// Timestamp describing when the user was created.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stored creation timestamp cannot be parsed as a UTC datetime.
+    pub fn created(&self) -> CCDBResult<DateTime<Utc>> {
+        Ok(parse_timestamp(&self.created)?)
+    }
+    /// Timestamp of the user's most recent action.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the stored timestamp cannot be parsed as a UTC datetime.
+    pub fn last_action_time(&self) -> CCDBResult<DateTime<Utc>> {
+        Ok(parse_timestamp(&self.last_action_time)?)
+    }
+}
@@ -0,0 +1,131 @@
+use std::{collections::BTreeMap, path::Path};
+
+use gluex_core::RunNumber;
+
+use crate::{
+    context::{Context, Request},
+    data::Data,
+    database::CCDB,
+    CCDBError, CCDBResult,
+};
+
+/// Table data together with the overlay layer it was read from.
+pub struct LayeredData {
+    /// Index of the layer (in [`CcdbOverlay::layers`]) that satisfied the request.
+    pub layer: usize,
+    /// Constants resolved from that layer.
+    pub data: Data,
+}
+
+/// An ordered stack of CCDB files, searched from the first (highest priority) layer down.
+///
+/// Each requested run is answered by the first layer that resolves an assignment for it, so a
+/// small local snapshot holding new constants can be stacked on top of the official database
+/// without modifying it. Layers that lack the requested table or variation are skipped.
+///
+/// ```no_run
+/// use gluex_ccdb::{context::Context, overlay::CcdbOverlay};
+///
+/// let overlay = CcdbOverlay::open(["my_constants.sqlite", "ccdb.sqlite"])?;
+/// let ctx = Context::default().with_run_range(30_274..=30_280);
+/// for (run, resolved) in overlay.fetch("/PHOTON_BEAM/endpoint_energy", &ctx)? {
+///     let source = overlay.layers()[resolved.layer].connection_path();
+///     println!("{run}: {:?} from {source}", resolved.data.double(0, 0));
+/// }
+/// # Ok::<(), gluex_ccdb::CCDBError>(())
+/// ```
+#[derive(Clone)]
+pub struct CcdbOverlay {
+    layers: Vec<CCDB>,
+}
+
+impl CcdbOverlay {
+    /// Stacks already opened databases, highest priority first.
+    #[must_use]
+    pub fn new(layers: Vec<CCDB>) -> Self {
+        Self { layers }
+    }
+
+    /// Opens each path with [`CCDB::open`] and stacks them, highest priority first.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if any of the databases cannot be opened.
+    pub fn open<P: AsRef<Path>>(paths: impl IntoIterator<Item = P>) -> CCDBResult<Self> {
+        Ok(Self::new(
+            paths
+                .into_iter()
+                .map(CCDB::open)
+                .collect::<CCDBResult<_>>()?,
+        ))
+    }
+
+    /// Returns the layers, highest priority first.
+    #[must_use]
+    pub fn layers(&self) -> &[CCDB] {
+        &self.layers
+    }
+
+    /// Fetches the table at `path` for every run in `ctx`, falling through the layers until each
+    /// run is resolved.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if no layer contains the table, if no layer that contains it
+    /// defines the requested variation, or if fetching from any layer fails.
+    pub fn fetch(&self, path: &str, ctx: &Context) -> CCDBResult<BTreeMap<RunNumber, LayeredData>> {
+        let mut unresolved: Vec<RunNumber> = if ctx.runs.is_empty() {
+            vec![0]
+        } else {
+            ctx.runs.clone()
+        };
+        let mut resolved = BTreeMap::new();
+        let mut last_error = None;
+        let mut searched = false;
+        for (layer, db) in self.layers.iter().enumerate() {
+            if unresolved.is_empty() {
+                break;
+            }
+            let table = match db.table(path) {
+                Ok(table) => table,
+                Err(err @ CCDBError::TableNotFoundError(_)) => {
+                    last_error.get_or_insert(err);
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+            let layer_ctx = Context {
+                runs: unresolved.clone(),
+                ..ctx.clone()
+            };
+            let data = match table.fetch(&layer_ctx) {
+                Ok(data) => data,
+                Err(err @ CCDBError::VariationNotFoundError(_)) => {
+                    last_error = Some(err);
+                    continue;
+                }
+                Err(err) => return Err(err),
+            };
+            searched = true;
+            unresolved.retain(|run| !data.contains_key(run));
+            resolved.extend(
+                data.into_iter()
+                    .map(|(run, data)| (run, LayeredData { layer, data })),
+            );
+        }
+        match last_error {
+            Some(err) if !searched => Err(err),
+            _ => Ok(resolved),
+        }
+    }
+
+    /// Parses a request string (see [`Request`]) and fetches it with [`CcdbOverlay::fetch`].
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the request string cannot be parsed or the fetch fails.
+    pub fn request(&self, request_string: &str) -> CCDBResult<BTreeMap<RunNumber, LayeredData>> {
+        let request: Request = request_string.parse()?;
+        self.fetch(request.path.full_path(), &request.context)
+    }
+}
@@ -0,0 +1,71 @@
+use std::collections::BTreeMap;
+
+use gluex_core::RunNumber;
+use rayon::prelude::*;
+
+use crate::{context::Context, data::Data, database::CCDB, CCDBResult};
+
+impl CCDB {
+    /// Fetches several tables for the same [`Context`] concurrently on the rayon thread pool,
+    /// returning each table's constants keyed by the path it was requested with.
+    ///
+    /// A single handle serializes its queries on one connection, so this opens a separate
+    /// read-only connection to the same file for each rayon task and fetches one table per
+    /// connection. The connections share this handle's metadata caches, limits, metrics, and
+    /// auditing; query limits apply to each table separately, as in [`CCDB::fetch`]. Handles
+    /// that are not backed by an `SQLite` file (in-memory snapshots and custom backends) cannot
+    /// be reopened and fall back to [`CCDB::fetch_many`].
+    ///
+    /// ```no_run
+    /// use gluex_ccdb::{context::Context, database::CCDB};
+    ///
+    /// let db = CCDB::open("ccdb.sqlite")?;
+    /// let ctx = Context::default().with_run_range(30_000..=30_100);
+    /// let tables = db.fetch_par(["/PHOTON_BEAM/endpoint_energy", "/TARGET/density"], &ctx)?;
+    /// for (run, data) in &tables["/TARGET/density"] {
+    ///     println!("{run}: {:?}", data.double(0, 0));
+    /// }
+    /// # Ok::<(), gluex_ccdb::CCDBError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if any table path does not exist, if a connection cannot be
+    /// opened, or if fetching any of the tables fails.
+    pub fn fetch_par<S>(
+        &self,
+        paths: S,
+        ctx: &Context,
+    ) -> CCDBResult<BTreeMap<String, BTreeMap<RunNumber, Data>>>
+    where
+        S: IntoIterator,
+        S::Item: AsRef<str>,
+    {
+        let mut paths: Vec<String> = paths
+            .into_iter()
+            .map(|path| path.as_ref().to_string())
+            .collect();
+        paths.sort_unstable();
+        paths.dedup();
+        for path in &paths {
+            self.table(path)?;
+        }
+        if paths.len() < 2 || !self.is_reopenable() {
+            return self.fetch_many(&paths, ctx);
+        }
+        paths
+            .into_par_iter()
+            .map_init(
+                || None,
+                |worker: &mut Option<CCDB>, path| {
+                    let db = match worker {
+                        Some(db) => db,
+                        None => worker.insert(self.reopen()?),
+                    };
+                    let data = db.fetch(&path, ctx)?;
+                    Ok((path, data))
+                },
+            )
+            .collect()
+    }
+}
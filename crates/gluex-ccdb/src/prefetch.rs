@@ -0,0 +1,115 @@
+use std::{
+    collections::BTreeMap,
+    panic,
+    sync::mpsc::{self, Receiver},
+    thread::{self, JoinHandle},
+};
+
+use gluex_core::RunNumber;
+
+use crate::{context::Context, data::Data, database::CCDB, CCDBResult};
+
+/// One fetch to run ahead of the caller: a table and the runs, variation, and timestamp to fetch
+/// it for.
+#[derive(Debug, Clone)]
+pub struct PrefetchRequest {
+    /// Full path of the table.
+    pub table: String,
+    /// Runs (typically a window of the runs being processed), variation, and timestamp.
+    pub context: Context,
+}
+
+impl PrefetchRequest {
+    /// Creates a request for `table` in `context`.
+    pub fn new(table: impl Into<String>, context: Context) -> Self {
+        Self {
+            table: table.into(),
+            context,
+        }
+    }
+
+    /// Splits the runs of `context` into consecutive windows of at most `size` runs (at least
+    /// one), one request per window.
+    #[must_use]
+    pub fn windows(table: &str, context: &Context, size: usize) -> Vec<Self> {
+        context
+            .runs
+            .chunks(size.max(1))
+            .map(|runs| {
+                Self::new(
+                    table,
+                    Context {
+                        runs: runs.to_vec(),
+                        ..context.clone()
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
+/// A completed [`PrefetchRequest`] with the constants it returned.
+pub struct PrefetchedBatch {
+    /// The request, as submitted.
+    pub request: PrefetchRequest,
+    /// The constants for each run of the request, or the error fetching them.
+    pub data: CCDBResult<BTreeMap<RunNumber, Data>>,
+}
+
+/// Runs a sequence of fetches on a background thread, a bounded number of batches ahead of the
+/// caller, and hands them out in request order.
+///
+/// Created by [`CCDB::prefetch`]. Iterating yields one [`PrefetchedBatch`] per request; while the
+/// caller processes one batch, the next ones are resolved and decoded. Dropping the prefetcher
+/// stops the background thread after the fetch it is running.
+pub struct Prefetcher {
+    receiver: Receiver<PrefetchedBatch>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl Iterator for Prefetcher {
+    type Item = PrefetchedBatch;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Ok(batch) = self.receiver.recv() {
+            return Some(batch);
+        }
+        if let Some(worker) = self.worker.take() {
+            if let Err(payload) = worker.join() {
+                panic::resume_unwind(payload);
+            }
+        }
+        None
+    }
+}
+
+impl CCDB {
+    /// Fetches `requests` in order on a background thread, keeping up to `depth` batches (at
+    /// least one) ready ahead of the caller.
+    ///
+    /// Event-loop consumers that fetch constants for one window of runs, process them, and move
+    /// to the next window can use this to overlap the queries and payload decoding of the next
+    /// window with their own work. Errors are reported per batch and do not stop later requests.
+    #[must_use]
+    pub fn prefetch(
+        &self,
+        requests: impl IntoIterator<Item = PrefetchRequest>,
+        depth: usize,
+    ) -> Prefetcher {
+        let requests: Vec<PrefetchRequest> = requests.into_iter().collect();
+        let (sender, receiver) = mpsc::sync_channel(depth.saturating_sub(1));
+        let db = self.clone();
+        let worker = thread::spawn(move || {
+            for request in requests {
+                let data = db.fetch(&request.table, &request.context);
+                if sender.send(PrefetchedBatch { request, data }).is_err() {
+                    break;
+                }
+            }
+        });
+        Prefetcher {
+            receiver,
+            worker: Some(worker),
+        }
+    }
+}
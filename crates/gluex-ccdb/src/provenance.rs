@@ -0,0 +1,93 @@
+use gluex_core::provenance::{sha256_file, DatabaseKind, Discrepancy, Manifest, Query};
+
+use crate::{database::CCDB, CCDBResult};
+
+impl CCDB {
+    /// Replays the CCDB queries in `manifest` against this database and reports every difference.
+    ///
+    /// Each recorded fetch is resolved again with its original table, runs, variation, and
+    /// timestamp, and the resulting assignment ids are compared with the recorded ones. The hash
+    /// of this database file is also compared with the hashes of the CCDB files in the manifest.
+    /// An empty result means the manifest is reproduced exactly.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if an SQL query fails while replaying. Missing tables or
+    /// variations are reported as discrepancies instead.
+    pub fn verify_manifest(&self, manifest: &Manifest) -> CCDBResult<Vec<Discrepancy>> {
+        let mut discrepancies = Vec::new();
+        let recorded_hashes: Vec<&str> = manifest
+            .databases
+            .values()
+            .filter(|record| record.kind == DatabaseKind::Ccdb)
+            .filter_map(|record| record.sha256.as_deref())
+            .collect();
+        if !recorded_hashes.is_empty() {
+            match sha256_file(self.connection_path()) {
+                Ok(hash) if recorded_hashes.contains(&hash.as_str()) => {}
+                Ok(hash) => discrepancies.push(Discrepancy {
+                    query: None,
+                    message: format!(
+                        "CCDB file {} has hash {hash}, which is not recorded in the manifest",
+                        self.connection_path()
+                    ),
+                }),
+                Err(err) => discrepancies.push(Discrepancy {
+                    query: None,
+                    message: format!("could not hash {}: {err}", self.connection_path()),
+                }),
+            }
+        }
+        for (index, query) in manifest.queries.iter().enumerate() {
+            let Query::Ccdb(query) = query else {
+                continue;
+            };
+            let table = match self.table(&query.table) {
+                Ok(table) => table,
+                Err(err) => {
+                    discrepancies.push(Discrepancy {
+                        query: Some(index),
+                        message: err.to_string(),
+                    });
+                    continue;
+                }
+            };
+            let resolved = match table.resolve_assignments(
+                &query.runs,
+                &query.variation,
+                query.timestamp,
+                &self.limits().start(),
+            ) {
+                Ok(resolved) => resolved,
+                Err(crate::CCDBError::SqliteError(err)) => return Err(err.into()),
+                Err(err) => {
+                    discrepancies.push(Discrepancy {
+                        query: Some(index),
+                        message: err.to_string(),
+                    });
+                    continue;
+                }
+            };
+            for run in &query.runs {
+                let expected = query.assignments.get(run).copied();
+                let actual = resolved.get(run).map(|assignment| assignment.id);
+                if expected != actual {
+                    discrepancies.push(Discrepancy {
+                        query: Some(index),
+                        message: format!(
+                            "{} run {run}: recorded assignment {}, resolved {}",
+                            query.table,
+                            describe(expected),
+                            describe(actual)
+                        ),
+                    });
+                }
+            }
+        }
+        Ok(discrepancies)
+    }
+}
+
+fn describe(id: Option<gluex_core::Id>) -> String {
+    id.map_or_else(|| "none".to_string(), |id| id.to_string())
+}
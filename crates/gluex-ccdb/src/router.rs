@@ -0,0 +1,130 @@
+use std::{collections::BTreeMap, ops::RangeBounds, path::Path};
+
+use gluex_core::{routing::RunRouter, run_set::RunSet, RunNumber};
+
+use crate::{
+    context::{Context, Request},
+    data::Data,
+    database::CCDB,
+    CCDBResult,
+};
+
+/// CCDB files keyed by the run ranges they serve, queried as a single database.
+///
+/// Every run of a fetch is answered by the first route whose range contains it, or by the default
+/// database if no route does, so an analysis spanning several run periods can read constants from
+/// a dedicated calibration file (such as a `PrimEx` CCDB) and the standard one through one handle.
+/// Runs that are neither routed nor covered by a default are left out of the result.
+///
+/// ```no_run
+/// use gluex_ccdb::{context::Context, database::CCDB, router::CcdbRouter};
+///
+/// let router = CcdbRouter::new()
+///     .open_route(61_000..=61_999, "primex_ccdb.sqlite")?
+///     .open_default("ccdb.sqlite")?;
+/// let ctx = Context::default().with_runs([30_274, 61_321]);
+/// for (run, data) in router.fetch("/PHOTON_BEAM/endpoint_energy", &ctx)? {
+///     let source = router.handle(run).map(CCDB::connection_path);
+///     println!("{run}: {:?} from {source:?}", data.double(0, 0));
+/// }
+/// # Ok::<(), gluex_ccdb::CCDBError>(())
+/// ```
+#[derive(Clone, Default)]
+pub struct CcdbRouter {
+    router: RunRouter<CCDB>,
+}
+
+impl CcdbRouter {
+    /// Creates a router without routes or default database.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serves the runs within `runs` from `db`, unless an earlier route already covers them.
+    #[must_use]
+    pub fn with_route(mut self, runs: impl RangeBounds<RunNumber>, db: CCDB) -> Self {
+        self.router = self.router.with_route(runs, db);
+        self
+    }
+
+    /// Serves every run that no route covers from `db`.
+    #[must_use]
+    pub fn with_default(mut self, db: CCDB) -> Self {
+        self.router = self.router.with_default(db);
+        self
+    }
+
+    /// Opens `path` with [`CCDB::open`] and adds it as a route (see [`CcdbRouter::with_route`]).
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the database cannot be opened.
+    pub fn open_route(
+        self,
+        runs: impl RangeBounds<RunNumber>,
+        path: impl AsRef<Path>,
+    ) -> CCDBResult<Self> {
+        Ok(self.with_route(runs, CCDB::open(path)?))
+    }
+
+    /// Opens `path` with [`CCDB::open`] and makes it the default database.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the database cannot be opened.
+    pub fn open_default(self, path: impl AsRef<Path>) -> CCDBResult<Self> {
+        Ok(self.with_default(CCDB::open(path)?))
+    }
+
+    /// Returns the routing table.
+    #[must_use]
+    pub fn router(&self) -> &RunRouter<CCDB> {
+        &self.router
+    }
+
+    /// Returns the database that serves `run`.
+    #[must_use]
+    pub fn handle(&self, run: RunNumber) -> Option<&CCDB> {
+        self.router.route(run)
+    }
+
+    /// Fetches the table at `path` for every run in `ctx`, asking each database only for the runs
+    /// it serves.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if a database that serves one of the runs lacks the table or
+    /// the requested variation, or if fetching from it fails.
+    pub fn fetch(&self, path: &str, ctx: &Context) -> CCDBResult<BTreeMap<RunNumber, Data>> {
+        let runs = if ctx.runs.is_empty() {
+            RunSet::runs([0])
+        } else {
+            RunSet::runs(ctx.runs.iter().copied())
+        };
+        let mut resolved = BTreeMap::new();
+        for (_, db, runs) in self.router.plan(&runs) {
+            let RunSet::Runs(runs) = runs else {
+                continue;
+            };
+            resolved.extend(db.fetch(
+                path,
+                &Context {
+                    runs,
+                    ..ctx.clone()
+                },
+            )?);
+        }
+        Ok(resolved)
+    }
+
+    /// Parses a request string (see [`Request`]) and fetches it with [`CcdbRouter::fetch`].
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the request string cannot be parsed or the fetch fails.
+    pub fn request(&self, request_string: &str) -> CCDBResult<BTreeMap<RunNumber, Data>> {
+        let request: Request = request_string.parse()?;
+        self.fetch(request.path.full_path(), &request.context)
+    }
+}
@@ -0,0 +1,183 @@
+pub use regex;
+
+use regex::Regex;
+
+use crate::database::{TypeTableHandle, CCDB};
+
+/// Kind of CCDB object a [`SearchHit`] refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SearchTarget {
+    /// A directory.
+    Directory,
+    /// A type table.
+    Table,
+}
+
+/// How strongly a [`SearchHit`] matched, from strongest to weakest; results are ranked in this
+/// order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SearchMatch {
+    /// The query matched the whole name.
+    ExactName,
+    /// The query matched part of the name.
+    Name,
+    /// The query matched the comment only.
+    Comment,
+}
+
+/// One result of [`CCDB::search`] or [`CCDB::search_regex`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchHit {
+    /// Absolute path of the directory or table.
+    pub path: String,
+    /// Whether the hit is a directory or a table.
+    pub target: SearchTarget,
+    /// How the query matched.
+    pub matched: SearchMatch,
+    /// Comment of the directory or table.
+    pub comment: String,
+}
+
+impl CCDB {
+    /// Returns every table whose full path matches a glob `pattern`, sorted by path.
+    ///
+    /// Patterns are matched one path component at a time: `*` matches any run of characters
+    /// within a component, `?` matches a single character, and a `**` component matches any
+    /// number of directories, including none. Patterns without a leading `/` are taken from the
+    /// root. Only the cached directory and table metadata is searched, so no queries are run.
+    ///
+    /// ```no_run
+    /// use gluex_ccdb::database::CCDB;
+    ///
+    /// let db = CCDB::open("ccdb.sqlite")?;
+    /// for table in db.find_tables("/PHOTON_BEAM/**/lumi/*") {
+    ///     println!("{}", table.full_path());
+    /// }
+    /// # Ok::<(), gluex_ccdb::CCDBError>(())
+    /// ```
+    #[must_use]
+    pub fn find_tables(&self, pattern: &str) -> Vec<TypeTableHandle> {
+        let pattern: Vec<Vec<char>> = components(pattern)
+            .map(|component| component.chars().collect())
+            .collect();
+        let mut tables: Vec<(String, TypeTableHandle)> = self
+            .all_tables()
+            .into_iter()
+            .map(|table| (table.full_path(), table))
+            .filter(|(path, _)| {
+                let path: Vec<Vec<char>> = components(path)
+                    .map(|component| component.chars().collect())
+                    .collect();
+                matches_path(&pattern, &path)
+            })
+            .collect();
+        tables.sort_by(|(a, _), (b, _)| a.cmp(b));
+        tables.into_iter().map(|(_, table)| table).collect()
+    }
+
+    /// Searches table names, directory names, and comments for `query`, ignoring case, like the
+    /// `search` command of the official `ccdb` CLI.
+    ///
+    /// Hits are ranked by [`SearchMatch`] (whole-name matches first, then partial name matches,
+    /// then comment matches) and then sorted by path.
+    /// Only the cached metadata is searched, so no queries are run.
+    ///
+    /// ```no_run
+    /// use gluex_ccdb::database::CCDB;
+    ///
+    /// let db = CCDB::open("ccdb.sqlite")?;
+    /// for hit in db.search("livetime") {
+    ///     println!("{:?} {} ({})", hit.matched, hit.path, hit.comment);
+    /// }
+    /// # Ok::<(), gluex_ccdb::CCDBError>(())
+    /// ```
+    #[must_use]
+    pub fn search(&self, query: &str) -> Vec<SearchHit> {
+        let query = query.to_lowercase();
+        self.search_with(|text| {
+            let text = text.to_lowercase();
+            if text == query {
+                Some(true)
+            } else {
+                text.contains(&query).then_some(false)
+            }
+        })
+    }
+
+    /// Searches like [`CCDB::search`], but with a regular expression; a name counts as an exact
+    /// match when `pattern` matches all of it. Case-insensitive searches can be written with the
+    /// `(?i)` flag.
+    #[must_use]
+    pub fn search_regex(&self, pattern: &Regex) -> Vec<SearchHit> {
+        self.search_with(|text| {
+            pattern
+                .find(text)
+                .map(|found| found.start() == 0 && found.end() == text.len())
+        })
+    }
+
+    /// Runs a search with `find`, which returns `None` when a text does not match and otherwise
+    /// whether it matched as a whole.
+    fn search_with(&self, find: impl Fn(&str) -> Option<bool>) -> Vec<SearchHit> {
+        let hit = |path: String, target, name: &str, comment: &str| {
+            let matched = match find(name) {
+                Some(true) => SearchMatch::ExactName,
+                Some(false) => SearchMatch::Name,
+                None => {
+                    find(comment)?;
+                    SearchMatch::Comment
+                }
+            };
+            Some(SearchHit {
+                path,
+                target,
+                matched,
+                comment: comment.to_string(),
+            })
+        };
+        let tables = self.all_tables().into_iter().filter_map(|table| {
+            hit(
+                table.full_path(),
+                SearchTarget::Table,
+                table.name(),
+                table.meta().comment(),
+            )
+        });
+        let directories = self.all_directories().into_iter().filter_map(|dir| {
+            hit(
+                dir.full_path(),
+                SearchTarget::Directory,
+                dir.meta().name(),
+                dir.meta().comment(),
+            )
+        });
+        let mut hits: Vec<SearchHit> = tables.chain(directories).collect();
+        hits.sort_by(|a, b| (a.matched, &a.path).cmp(&(b.matched, &b.path)));
+        hits
+    }
+}
+
+fn components(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/').filter(|component| !component.is_empty())
+}
+
+fn matches_path(pattern: &[Vec<char>], path: &[Vec<char>]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((component, rest)) if component[..] == ['*', '*'] => {
+            (0..=path.len()).any(|skip| matches_path(rest, &path[skip..]))
+        }
+        Some((component, rest)) => path.split_first().is_some_and(|(name, tail)| {
+            matches_component(component, name) && matches_path(rest, tail)
+        }),
+    }
+}
+
+fn matches_component(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|skip| matches_component(rest, &name[skip..])),
+        Some(('?', rest)) => !name.is_empty() && matches_component(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && matches_component(rest, &name[1..]),
+    }
+}
@@ -0,0 +1,425 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::RangeInclusive,
+    path::Path,
+};
+
+use gluex_core::{Id, RunNumber};
+use rusqlite::{params, Connection, OpenFlags};
+
+use crate::{
+    context::Context,
+    database::{TypeTableHandle, CCDB},
+    models::VariationMeta,
+    CCDBError, CCDBResult,
+};
+
+/// Tables that are small and needed to resolve any request, so they are copied in full.
+const FULL_TABLES: &[&str] = &["schemaVersions", "users", "variations"];
+
+const SCHEMA_SQL: &str = "SELECT sql FROM src.sqlite_master
+     WHERE type IN ('table', 'index') AND sql IS NOT NULL AND name NOT LIKE 'sqlite_%'
+     ORDER BY type = 'index', name";
+
+const MERGE_MAP_SQL: &str = "
+    CREATE TEMP TABLE map_directories(old INTEGER PRIMARY KEY, new INTEGER, is_new INTEGER);
+    WITH RECURSIVE m(old, new, is_new) AS (
+        SELECT 0, 0, 0
+        UNION ALL
+        SELECT o.id,
+               COALESCE(b.id, (SELECT COALESCE(MAX(id), 0) FROM main.directories) + o.id),
+               b.id IS NULL
+        FROM ovl.directories o
+        JOIN m ON o.parentId = m.old
+        LEFT JOIN main.directories b ON b.parentId = m.new AND b.name = o.name
+        WHERE o.id != 0
+    )
+    INSERT INTO temp.map_directories SELECT * FROM m WHERE old != 0;
+    CREATE TEMP TABLE map_typeTables(old INTEGER PRIMARY KEY, new INTEGER, is_new INTEGER);
+    INSERT INTO temp.map_typeTables
+        SELECT o.id,
+               COALESCE(b.id, (SELECT COALESCE(MAX(id), 0) FROM main.typeTables) + o.id),
+               b.id IS NULL
+        FROM ovl.typeTables o
+        JOIN temp.map_directories d ON d.old = o.directoryId
+        LEFT JOIN main.typeTables b ON b.directoryId = d.new AND b.name = o.name;
+    CREATE TEMP TABLE map_variations(old INTEGER PRIMARY KEY, new INTEGER, is_new INTEGER);
+    INSERT INTO temp.map_variations
+        SELECT o.id,
+               COALESCE(b.id, (SELECT COALESCE(MAX(id), 0) FROM main.variations) + o.id),
+               b.id IS NULL
+        FROM ovl.variations o
+        LEFT JOIN main.variations b ON b.name = o.name;
+    CREATE TEMP TABLE map_runRanges(old INTEGER PRIMARY KEY, new INTEGER);
+    INSERT INTO temp.map_runRanges
+        SELECT id, (SELECT COALESCE(MAX(id), 0) FROM main.runRanges) + id FROM ovl.runRanges;
+    CREATE TEMP TABLE map_constantSets(old INTEGER PRIMARY KEY, new INTEGER);
+    INSERT INTO temp.map_constantSets
+        SELECT id, (SELECT COALESCE(MAX(id), 0) FROM main.constantSets) + id
+        FROM ovl.constantSets;";
+
+const TABLES_SQL: &str = "
+    INSERT INTO main.typeTables SELECT * FROM src.typeTables WHERE id IN temp.keep_tables;
+    INSERT INTO main.columns SELECT * FROM src.columns WHERE typeId IN temp.keep_tables;
+    WITH RECURSIVE needed(id) AS (
+        SELECT directoryId FROM main.typeTables
+        UNION
+        SELECT d.parentId FROM src.directories d JOIN needed ON d.id = needed.id
+    )
+    INSERT INTO main.directories SELECT * FROM src.directories WHERE id IN needed;";
+
+const EXTRACT_ASSIGNMENTS_SQL: &str = "
+    INSERT INTO main.assignments
+        SELECT a.* FROM src.assignments a
+        JOIN temp.keep_runs k
+        JOIN src.constantSets cs ON cs.id = a.constantSetId
+        JOIN src.runRanges rr ON rr.id = a.runRangeId
+        WHERE cs.constantTypeId IN temp.keep_tables
+          AND rr.runMax >= k.run_min
+          AND rr.runMin <= k.run_max;";
+
+const EXPORT_ROWS_SQL: &str = "
+    INSERT INTO main.variations SELECT * FROM src.variations WHERE id IN temp.keep_variations;
+    INSERT INTO main.assignments SELECT * FROM src.assignments WHERE id IN temp.keep_assignments;";
+
+const CONSTANTS_SQL: &str = "
+    INSERT INTO main.constantSets
+        SELECT * FROM src.constantSets WHERE id IN (SELECT constantSetId FROM main.assignments);
+    INSERT INTO main.runRanges
+        SELECT * FROM src.runRanges WHERE id IN (SELECT runRangeId FROM main.assignments);";
+
+impl CCDB {
+    /// Writes a trimmed copy of this database to `output`.
+    ///
+    /// Each entry in `paths` may name a table or a directory (which selects every table
+    /// below it). The snapshot keeps the full schema, all variations and users, the selected
+    /// tables with their columns and parent directories, and every assignment (including
+    /// superseded ones, so timestamped requests still resolve) whose run range overlaps
+    /// `runs`. Other tables are created empty.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the handle does not read an `SQLite` file on disk (see
+    /// [`CCDB::open_from_bytes`]), if a path cannot be resolved, if `output` already contains
+    /// conflicting tables, or if any `SQLite` operation fails.
+    pub fn extract_snapshot<S>(
+        &self,
+        output: impl AsRef<Path>,
+        paths: &[S],
+        runs: RangeInclusive<RunNumber>,
+    ) -> CCDBResult<()>
+    where
+        S: AsRef<str>,
+    {
+        let table_ids = self.resolve_table_ids(paths)?;
+        self.write_snapshot(output.as_ref(), &table_ids, |conn| {
+            conn.execute_batch("CREATE TEMP TABLE keep_runs(run_min INTEGER, run_max INTEGER);")?;
+            conn.execute(
+                "INSERT INTO temp.keep_runs(run_min, run_max) VALUES (?1, ?2)",
+                params![runs.start(), runs.end()],
+            )?;
+            for table in FULL_TABLES {
+                if has_table(conn, table)? {
+                    conn.execute_batch(&format!(
+                        "INSERT INTO main.{table} SELECT * FROM src.{table};"
+                    ))?;
+                }
+            }
+            conn.execute_batch(EXTRACT_ASSIGNMENTS_SQL)?;
+            conn.execute_batch("DROP TABLE temp.keep_runs;")
+        })
+    }
+
+    /// Writes a minimal standalone snapshot to `output` that reproduces the constants of `paths`
+    /// for `ctx`.
+    ///
+    /// Entries in `paths` name tables or directories, as in [`CCDB::extract_snapshot`]. Unlike
+    /// that method, only the assignments `ctx` actually resolves are kept, along with their
+    /// constant sets and run ranges, the variations in the chain of `ctx.variation`, and the
+    /// selected tables with their columns and parent directories. Users are copied in full so
+    /// authors can still be named (see [`CCDB::user`]). Fetching the same paths for the same runs
+    /// and variation from the snapshot gives the same constants, which makes it a small,
+    /// reproducible record of the calibrations an analysis used.
+    ///
+    /// ```no_run
+    /// use gluex_ccdb::{context::Context, database::CCDB};
+    ///
+    /// let db = CCDB::open("ccdb.sqlite")?;
+    /// let ctx = Context::default().with_run_range(30_000..=30_100);
+    /// db.export_snapshot(&["/PHOTON_BEAM/endpoint_energy", "/TARGET"], &ctx, "calib.sqlite")?;
+    /// # Ok::<(), gluex_ccdb::CCDBError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the handle does not read an `SQLite` file on disk, if a
+    /// path or the context's variation cannot be resolved, if `output` already contains
+    /// conflicting tables, or if any `SQLite` operation fails.
+    pub fn export_snapshot<S>(
+        &self,
+        paths: &[S],
+        ctx: &Context,
+        output: impl AsRef<Path>,
+    ) -> CCDBResult<()>
+    where
+        S: AsRef<str>,
+    {
+        let table_ids = self.resolve_table_ids(paths)?;
+        let variations: BTreeSet<Id> = self
+            .variation_chain(&self.variation(&ctx.variation)?)?
+            .iter()
+            .map(VariationMeta::id)
+            .collect();
+        let runs = if ctx.runs.is_empty() {
+            vec![0]
+        } else {
+            ctx.runs.clone()
+        };
+        let table_list: Vec<Id> = table_ids.iter().copied().collect();
+        let assignments: BTreeSet<Id> = self
+            .resolve_assignments(
+                &table_list,
+                &runs,
+                &ctx.variation,
+                ctx.timestamp,
+                &self.limits().start(),
+            )?
+            .values()
+            .flat_map(BTreeMap::values)
+            .map(|assignment| assignment.id)
+            .collect();
+        self.write_snapshot(output.as_ref(), &table_ids, |conn| {
+            conn.execute_batch(
+                "CREATE TEMP TABLE keep_variations(id INTEGER PRIMARY KEY);
+                 CREATE TEMP TABLE keep_assignments(id INTEGER PRIMARY KEY);",
+            )?;
+            insert_ids(conn, "keep_variations", &variations)?;
+            insert_ids(conn, "keep_assignments", &assignments)?;
+            for table in ["schemaVersions", "users"] {
+                if has_table(conn, table)? {
+                    conn.execute_batch(&format!(
+                        "INSERT INTO main.{table} SELECT * FROM src.{table};"
+                    ))?;
+                }
+            }
+            conn.execute_batch(EXPORT_ROWS_SQL)?;
+            conn.execute_batch("DROP TABLE temp.keep_variations; DROP TABLE temp.keep_assignments;")
+        })
+    }
+
+    /// Creates a snapshot at `output` with this database's schema, the tables in `table_ids`,
+    /// and whatever rows `copy_rows` inserts, followed by the constant sets and run ranges of the
+    /// copied assignments.
+    ///
+    /// The rows are copied by attaching this database's file, so handles that do not read an
+    /// `SQLite` file on disk are rejected before `output` is touched.
+    fn write_snapshot(
+        &self,
+        output: &Path,
+        table_ids: &BTreeSet<Id>,
+        copy_rows: impl FnOnce(&Connection) -> rusqlite::Result<()>,
+    ) -> CCDBResult<()> {
+        self.check_snapshot_source()?;
+        let mut conn = Connection::open(output)?;
+        conn.execute("ATTACH DATABASE ?1 AS src", [self.connection_path()])?;
+        let tx = conn.transaction()?;
+        copy_schema(&tx)?;
+        tx.execute_batch("CREATE TEMP TABLE keep_tables(id INTEGER PRIMARY KEY);")?;
+        insert_ids(&tx, "keep_tables", table_ids)?;
+        copy_rows(&tx)?;
+        tx.execute_batch(TABLES_SQL)?;
+        tx.execute_batch(CONSTANTS_SQL)?;
+        tx.execute_batch("DROP TABLE temp.keep_tables;")?;
+        tx.commit()?;
+        conn.execute("DETACH DATABASE src", [])?;
+        Ok(())
+    }
+
+    /// Fails unless this handle reads an `SQLite` file that [`CCDB::write_snapshot`] can attach.
+    fn check_snapshot_source(&self) -> CCDBResult<()> {
+        if self.is_reopenable() {
+            return Ok(());
+        }
+        Err(CCDBError::UnsupportedSourceError(format!(
+            "snapshots can only be written from an SQLite file on disk, not {}",
+            self.connection_path()
+        )))
+    }
+
+    fn resolve_table_ids<S>(&self, paths: &[S]) -> CCDBResult<BTreeSet<Id>>
+    where
+        S: AsRef<str>,
+    {
+        let mut ids = BTreeSet::new();
+        for path in paths {
+            let path = path.as_ref();
+            if let Ok(table) = self.table(path) {
+                ids.insert(table.id());
+                continue;
+            }
+            let mut pending = vec![self.dir(path).map_err(|_| {
+                CCDBError::InvalidPathError(format!("{path} is neither a table nor a directory"))
+            })?];
+            while let Some(dir) = pending.pop() {
+                ids.extend(dir.tables().iter().map(TypeTableHandle::id));
+                pending.extend(dir.dirs());
+            }
+        }
+        Ok(ids)
+    }
+}
+
+/// Merges the CCDB file at `overlay` on top of the one at `base`, writing the result to `output`.
+///
+/// Directories and tables are matched by path and variations by name; anything that does not
+/// already exist in `base` is added with a fresh id. Every run range, constant set, and
+/// assignment in `overlay` is appended with remapped ids, so the overlay's assignments take
+/// precedence wherever they are newer than the base ones (CCDB always resolves the most
+/// recently created assignment). Columns of tables that already exist in `base` are left
+/// untouched. Neither input file is modified.
+///
+/// # Errors
+///
+/// This method returns an error if `output` already exists, if the overlay's tables do not
+/// have the same columns as the base ones, or if any `SQLite` operation fails.
+pub fn merge(
+    base: impl AsRef<Path>,
+    overlay: impl AsRef<Path>,
+    output: impl AsRef<Path>,
+) -> CCDBResult<()> {
+    let output = output.as_ref().to_string_lossy().to_string();
+    Connection::open_with_flags(base, OpenFlags::SQLITE_OPEN_READ_ONLY)?
+        .execute("VACUUM INTO ?1", [&output])?;
+    let mut conn = Connection::open(&output)?;
+    conn.execute(
+        "ATTACH DATABASE ?1 AS ovl",
+        [overlay.as_ref().to_string_lossy()],
+    )?;
+    let tx = conn.transaction()?;
+    tx.execute_batch(MERGE_MAP_SQL)?;
+    insert_remapped(
+        &tx,
+        "directories",
+        &[("id", "m.new"), ("parentId", "COALESCE(p.new, o.parentId)")],
+        "JOIN temp.map_directories m ON m.old = o.id
+         LEFT JOIN temp.map_directories p ON p.old = o.parentId
+         WHERE m.is_new",
+    )?;
+    insert_remapped(
+        &tx,
+        "typeTables",
+        &[("id", "m.new"), ("directoryId", "d.new")],
+        "JOIN temp.map_typeTables m ON m.old = o.id
+         JOIN temp.map_directories d ON d.old = o.directoryId
+         WHERE m.is_new",
+    )?;
+    insert_remapped(
+        &tx,
+        "columns",
+        &[
+            (
+                "id",
+                "(SELECT COALESCE(MAX(id), 0) FROM main.columns) + o.id",
+            ),
+            ("typeId", "t.new"),
+        ],
+        "JOIN temp.map_typeTables t ON t.old = o.typeId WHERE t.is_new",
+    )?;
+    insert_remapped(
+        &tx,
+        "variations",
+        &[("id", "m.new"), ("parentId", "COALESCE(p.new, o.parentId)")],
+        "JOIN temp.map_variations m ON m.old = o.id
+         LEFT JOIN temp.map_variations p ON p.old = o.parentId
+         WHERE m.is_new",
+    )?;
+    insert_remapped(
+        &tx,
+        "runRanges",
+        &[("id", "m.new")],
+        "JOIN temp.map_runRanges m ON m.old = o.id",
+    )?;
+    insert_remapped(
+        &tx,
+        "constantSets",
+        &[("id", "m.new"), ("constantTypeId", "t.new")],
+        "JOIN temp.map_constantSets m ON m.old = o.id
+         JOIN temp.map_typeTables t ON t.old = o.constantTypeId",
+    )?;
+    insert_remapped(
+        &tx,
+        "assignments",
+        &[
+            (
+                "id",
+                "(SELECT COALESCE(MAX(id), 0) FROM main.assignments) + o.id",
+            ),
+            ("variationId", "v.new"),
+            ("runRangeId", "r.new"),
+            ("constantSetId", "c.new"),
+        ],
+        "JOIN temp.map_variations v ON v.old = o.variationId
+         JOIN temp.map_runRanges r ON r.old = o.runRangeId
+         JOIN temp.map_constantSets c ON c.old = o.constantSetId",
+    )?;
+    tx.commit()?;
+    conn.execute("DETACH DATABASE ovl", [])?;
+    Ok(())
+}
+
+/// Copies rows of `table` from the attached `ovl` database (aliased `o`) into `main`,
+/// replacing the listed columns with SQL expressions. `tail` supplies joins and filters.
+fn insert_remapped(
+    conn: &Connection,
+    table: &str,
+    remaps: &[(&str, &str)],
+    tail: &str,
+) -> rusqlite::Result<usize> {
+    let columns = conn
+        .prepare(&format!("PRAGMA main.table_info(\"{table}\")"))?
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    let targets = columns
+        .iter()
+        .map(|column| format!("\"{column}\""))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let values = columns
+        .iter()
+        .map(|column| {
+            remaps.iter().find(|(name, _)| name == column).map_or_else(
+                || format!("o.\"{column}\""),
+                |(_, expr)| (*expr).to_string(),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    conn.execute(
+        &format!("INSERT INTO main.\"{table}\" ({targets}) SELECT {values} FROM ovl.\"{table}\" o {tail}"),
+        [],
+    )
+}
+
+fn copy_schema(conn: &Connection) -> rusqlite::Result<()> {
+    let statements = conn
+        .prepare(SCHEMA_SQL)?
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    for sql in statements {
+        conn.execute_batch(&sql)?;
+    }
+    Ok(())
+}
+
+fn has_table(conn: &Connection, name: &str) -> rusqlite::Result<bool> {
+    conn.prepare("SELECT 1 FROM src.sqlite_master WHERE type = 'table' AND name = ?1")?
+        .exists([name])
+}
+
+fn insert_ids(conn: &Connection, table: &str, ids: &BTreeSet<Id>) -> rusqlite::Result<()> {
+    let mut stmt = conn.prepare(&format!("INSERT INTO temp.{table}(id) VALUES (?1)"))?;
+    for id in ids {
+        stmt.execute([id])?;
+    }
+    Ok(())
+}
@@ -0,0 +1,121 @@
+use std::collections::{btree_map, VecDeque};
+
+use gluex_core::RunNumber;
+
+use crate::{
+    context::Context,
+    data::Data,
+    database::{TypeTableHandle, CCDB},
+    CCDBResult,
+};
+
+/// Number of runs [`FetchIter`] fetches at a time unless told otherwise.
+pub const DEFAULT_CHUNK_SIZE: usize = 1000;
+
+/// Lazy iterator over the per-run constants of one table, yielding `(run, data)` pairs in
+/// ascending run order.
+///
+/// Created by [`TypeTableHandle::fetch_iter`] and [`CCDB::fetch_iter`]. Runs are resolved and
+/// decoded a chunk at a time, so at most one chunk of payloads is held in memory no matter how
+/// many runs the context selects. Runs without an assignment are skipped, exactly as in
+/// [`TypeTableHandle::fetch`]. After a chunk fails, its error is yielded and iteration ends.
+pub struct FetchIter {
+    table: TypeTableHandle,
+    context: Context,
+    pending: VecDeque<RunNumber>,
+    buffered: btree_map::IntoIter<RunNumber, Data>,
+    chunk_size: usize,
+}
+
+impl FetchIter {
+    fn new(table: TypeTableHandle, mut context: Context) -> Self {
+        let mut runs = std::mem::take(&mut context.runs);
+        if runs.is_empty() {
+            runs.push(0);
+        }
+        runs.sort_unstable();
+        runs.dedup();
+        Self {
+            table,
+            context,
+            pending: runs.into(),
+            buffered: btree_map::IntoIter::default(),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    /// Sets how many runs are fetched per query (at least one, [`DEFAULT_CHUNK_SIZE`] by
+    /// default). Larger chunks mean fewer queries but more memory per chunk.
+    #[must_use]
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Number of selected runs not yet fetched; runs already fetched but not yet yielded are not
+    /// counted.
+    #[must_use]
+    pub fn remaining_runs(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+impl Iterator for FetchIter {
+    type Item = CCDBResult<(RunNumber, Data)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(entry) = self.buffered.next() {
+                return Some(Ok(entry));
+            }
+            if self.pending.is_empty() {
+                return None;
+            }
+            let take = self.chunk_size.min(self.pending.len());
+            self.context.runs = self.pending.drain(..take).collect();
+            match self.table.fetch(&self.context) {
+                Ok(data) => self.buffered = data.into_iter(),
+                Err(err) => {
+                    self.pending.clear();
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
+
+impl TypeTableHandle {
+    /// Fetches this table like [`TypeTableHandle::fetch`], but lazily: the returned iterator
+    /// fetches the context's runs [`DEFAULT_CHUNK_SIZE`] at a time (see
+    /// [`FetchIter::with_chunk_size`]) and yields `(run, data)` pairs as it goes, so scans over
+    /// large run ranges need memory for only one chunk.
+    ///
+    /// ```no_run
+    /// use gluex_ccdb::{context::Context, database::CCDB};
+    ///
+    /// let db = CCDB::open("ccdb.sqlite")?;
+    /// let table = db.table("/PHOTON_BEAM/pair_spectrometer/lumi/trig_live")?;
+    /// let ctx = Context::default().with_run_range(30_000..=40_000);
+    /// for entry in table.fetch_iter(&ctx).with_chunk_size(500) {
+    ///     let (run, data) = entry?;
+    ///     println!("{run}: {} rows", data.n_rows());
+    /// }
+    /// # Ok::<(), gluex_ccdb::CCDBError>(())
+    /// ```
+    #[must_use]
+    pub fn fetch_iter(&self, ctx: &Context) -> FetchIter {
+        FetchIter::new(self.clone(), ctx.clone())
+    }
+}
+
+impl CCDB {
+    /// Looks up the table at `path` and returns a lazy [`FetchIter`] over its constants for the
+    /// runs in `ctx`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table does not exist. Query errors are yielded by the iterator.
+    pub fn fetch_iter(&self, path: &str, ctx: &Context) -> CCDBResult<FetchIter> {
+        Ok(self.table(path)?.fetch_iter(ctx))
+    }
+}
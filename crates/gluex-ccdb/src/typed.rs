@@ -0,0 +1,139 @@
+use crate::{
+    data::{CCDBDataError, Data, RowView, Value},
+    models::ColumnType,
+};
+
+#[cfg(feature = "derive")]
+pub use gluex_ccdb_derive::CcdbTable;
+
+/// A Rust type that one row of a CCDB table can be read into.
+///
+/// With the `derive` feature, `#[derive(CcdbTable)]` implements it by reading every named field
+/// from the column of the same name, or from the column given with `#[ccdb(column = "...")]`. The
+/// derived implementation is equivalent to this hand-written one:
+///
+/// ```no_run
+/// use gluex_ccdb::{
+///     context::Context,
+///     data::{CCDBDataError, RowView},
+///     database::CCDB,
+///     typed::{cell, CcdbTable},
+/// };
+///
+/// // #[derive(CcdbTable)]
+/// struct PsAccept {
+///     // #[ccdb(column = "Emin")]
+///     e_min: f64,
+///     // #[ccdb(column = "Emax")]
+///     e_max: f64,
+/// }
+///
+/// impl CcdbTable for PsAccept {
+///     const COLUMNS: &'static [&'static str] = &["Emin", "Emax"];
+///
+///     fn from_row(row: &RowView<'_>) -> Result<Self, CCDBDataError> {
+///         Ok(Self {
+///             e_min: cell(row, "Emin")?,
+///             e_max: cell(row, "Emax")?,
+///         })
+///     }
+/// }
+///
+/// let db = CCDB::open("ccdb.sqlite")?;
+/// let ctx = Context::default().with_run(30_274);
+/// let table = db.table("/PHOTON_BEAM/pair_spectrometer/lumi/PS_accept")?;
+/// for (run, rows) in table.fetch_typed::<PsAccept>(&ctx)? {
+///     println!("{run}: {} to {} GeV", rows[0].e_min, rows[0].e_max);
+/// }
+/// # Ok::<(), gluex_ccdb::CCDBError>(())
+/// ```
+pub trait CcdbTable: Sized {
+    /// Names of the columns the type reads.
+    const COLUMNS: &'static [&'static str];
+
+    /// Reads one row.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a column is missing or stores a different type than its field.
+    fn from_row(row: &RowView<'_>) -> Result<Self, CCDBDataError>;
+
+    /// Reads every row of a table, in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any row cannot be read (see [`CcdbTable::from_row`]).
+    fn from_data(data: &Data) -> Result<Vec<Self>, CCDBDataError> {
+        data.iter_rows().map(|row| Self::from_row(&row)).collect()
+    }
+}
+
+/// A Rust type a single CCDB cell converts to.
+///
+/// Each type matches exactly one [`ColumnType`]; values are never converted between column types.
+pub trait FromCell<'a>: Sized {
+    /// Name of the type, as reported in [`CCDBDataError::ColumnTypeMismatch`].
+    const TYPE_NAME: &'static str;
+
+    /// Converts a cell, or returns `None` if it stores a different type.
+    fn from_cell(value: Value<'a>) -> Option<Self>;
+}
+
+macro_rules! impl_from_cell {
+    ($ty:ty, $name:literal, $method:ident) => {
+        impl<'a> FromCell<'a> for $ty {
+            const TYPE_NAME: &'static str = $name;
+
+            fn from_cell(value: Value<'a>) -> Option<Self> {
+                value.$method()
+            }
+        }
+    };
+}
+
+impl_from_cell!(i32, "i32", as_int);
+impl_from_cell!(u32, "u32", as_uint);
+impl_from_cell!(i64, "i64", as_long);
+impl_from_cell!(u64, "u64", as_ulong);
+impl_from_cell!(f64, "f64", as_double);
+impl_from_cell!(bool, "bool", as_bool);
+impl_from_cell!(&'a str, "&str", as_str);
+
+impl<'a> FromCell<'a> for String {
+    const TYPE_NAME: &'static str = "String";
+
+    fn from_cell(value: Value<'a>) -> Option<Self> {
+        value.as_str().map(str::to_string)
+    }
+}
+
+/// Reads the cell of column `name` in `row` as `T`; this is what derived [`CcdbTable`]
+/// implementations call for each field.
+///
+/// # Errors
+///
+/// Returns [`CCDBDataError::MissingColumn`] if the table has no such column, or
+/// [`CCDBDataError::ColumnTypeMismatch`] if it stores a different type than `T`.
+pub fn cell<'a, T: FromCell<'a>>(row: &RowView<'a>, name: &str) -> Result<T, CCDBDataError> {
+    let value = row
+        .named_value(name)
+        .ok_or_else(|| CCDBDataError::MissingColumn(name.to_string()))?;
+    T::from_cell(value).ok_or_else(|| CCDBDataError::ColumnTypeMismatch {
+        column: name.to_string(),
+        row: row.index(),
+        expected: T::TYPE_NAME,
+        found: column_type(value),
+    })
+}
+
+fn column_type(value: Value<'_>) -> ColumnType {
+    match value {
+        Value::Int(_) => ColumnType::Int,
+        Value::UInt(_) => ColumnType::UInt,
+        Value::Long(_) => ColumnType::Long,
+        Value::ULong(_) => ColumnType::ULong,
+        Value::Double(_) => ColumnType::Double,
+        Value::Bool(_) => ColumnType::Bool,
+        Value::String(_) => ColumnType::String,
+    }
+}
@@ -0,0 +1,314 @@
+use std::{
+    env, fs,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
+
+use gluex_core::{cache::cache_dir, Id};
+
+use crate::{
+    data::{Column, ColumnLayout, Data},
+    models::ColumnType,
+};
+
+/// Environment variable that enables the vault cache for every database opened by the process.
+///
+/// `on` caches in the `vaults` subdirectory of [`cache_dir`]; any other value except `off` is
+/// taken as the cache directory itself.
+pub const VAULT_CACHE_ENV: &str = "GLUEX_VAULT_CACHE";
+
+/// Size the entries of a cache may reach before the least recently used ones are evicted.
+pub const DEFAULT_MAX_BYTES: u64 = 1 << 30;
+
+/// First bytes of every cache entry, including the format version.
+const MAGIC: &[u8; 8] = b"GLXVLT02";
+
+/// On-disk cache of decoded vaults, so tables fetched by an earlier process are read back in
+/// binary form instead of being parsed from text again.
+///
+/// Entries are keyed by the database (its canonical path and schema version), the constant set
+/// id, a hash of the table's columns and row count, and the constant set's `modified` timestamp,
+/// so a hit is found from the assignment metadata alone and the vault text is only read from the
+/// database on a miss. Databases sharing a cache directory never see each other's entries, even
+/// where their constant set ids coincide, and a changed schema or an edited constant set gets a
+/// new entry. Once the entries outgrow [`VaultCache::max_bytes`], the least recently
+/// used ones are deleted. Reading and writing entries is best effort: a missing, corrupt, or
+/// unwritable entry falls back to parsing the vault.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaultCache {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+impl VaultCache {
+    /// Creates a cache that stores its entries in `dir`, which is created on first write, and
+    /// holds at most [`DEFAULT_MAX_BYTES`].
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            max_bytes: DEFAULT_MAX_BYTES,
+        }
+    }
+
+    /// Returns a cache that evicts the least recently used entries once they take up more than
+    /// `max_bytes`.
+    #[must_use]
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Returns the size the entries may reach before some are evicted.
+    #[must_use]
+    pub fn max_bytes(&self) -> u64 {
+        self.max_bytes
+    }
+
+    /// Returns the default cache directory, the `vaults` subdirectory of [`cache_dir`].
+    #[must_use]
+    pub fn default_dir() -> PathBuf {
+        cache_dir().join("vaults")
+    }
+
+    /// Builds a cache from [`VAULT_CACHE_ENV`], or `None` if the variable is unset, empty, or
+    /// `off`.
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let value = env::var_os(VAULT_CACHE_ENV)?;
+        match value.to_str().map(str::trim) {
+            Some("" | "off") => None,
+            Some("on") => Some(Self::new(Self::default_dir())),
+            _ => Some(Self::new(value)),
+        }
+    }
+
+    /// Returns the directory holding the entries.
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Deletes every entry.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory exists but cannot be removed.
+    pub fn clear(&self) -> io::Result<()> {
+        match fs::remove_dir_all(&self.dir) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the decoded table cached for a constant set and the length of its vault text, if
+    /// there is a valid entry.
+    pub(crate) fn load(
+        &self,
+        database: u64,
+        constant_set: Id,
+        modified: &str,
+        layout: &Arc<ColumnLayout>,
+        n_rows: usize,
+    ) -> Option<(Data, usize)> {
+        let path = self.entry(database, constant_set, modified, layout, n_rows);
+        let mut bytes = Vec::new();
+        let mut file = fs::File::open(&path).ok()?;
+        file.read_to_end(&mut bytes).ok()?;
+        let (vault_len, columns) = decode(&bytes, layout.column_types(), n_rows)?;
+        // mark the entry as recently used for eviction
+        let _ = file.set_modified(SystemTime::now());
+        Some((
+            Data::from_columns(n_rows, layout.clone(), columns),
+            vault_len,
+        ))
+    }
+
+    /// Writes the decoded table of a constant set, ignoring failures.
+    pub(crate) fn store(
+        &self,
+        database: u64,
+        constant_set: Id,
+        modified: &str,
+        vault_len: usize,
+        data: &Data,
+        layout: &ColumnLayout,
+    ) {
+        let path = self.entry(database, constant_set, modified, layout, data.n_rows());
+        let part = path.with_extension(format!("{}.part", std::process::id()));
+        let written = fs::create_dir_all(&self.dir)
+            .and_then(|()| fs::File::create(&part))
+            .and_then(|mut file| file.write_all(&encode(vault_len, data)))
+            .and_then(|()| fs::rename(&part, &path));
+        if written.is_err() {
+            let _ = fs::remove_file(&part);
+        }
+    }
+
+    /// Deletes the least recently used entries until the rest fit in [`VaultCache::max_bytes`],
+    /// ignoring failures.
+    pub(crate) fn trim(&self) {
+        let Ok(dir) = fs::read_dir(&self.dir) else {
+            return;
+        };
+        let mut entries: Vec<(SystemTime, u64, PathBuf)> = dir
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let path = entry.path();
+                if path.extension()? != "bin" {
+                    return None;
+                }
+                let metadata = entry.metadata().ok()?;
+                Some((metadata.modified().ok()?, metadata.len(), path))
+            })
+            .collect();
+        let mut total: u64 = entries.iter().map(|(_, len, _)| len).sum();
+        if total <= self.max_bytes {
+            return;
+        }
+        entries.sort_unstable();
+        for (_, len, path) in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            if fs::remove_file(path).is_ok() {
+                total -= len;
+            }
+        }
+    }
+
+    fn entry(
+        &self,
+        database: u64,
+        constant_set: Id,
+        modified: &str,
+        layout: &ColumnLayout,
+        n_rows: usize,
+    ) -> PathBuf {
+        let mut schema = Fnv::default();
+        for (name, column_type) in layout.column_names().iter().zip(layout.column_types()) {
+            schema.write(name.as_bytes());
+            schema.write(&[0]);
+            schema.write(column_type.as_str().as_bytes());
+            schema.write(&[0]);
+        }
+        schema.write(&n_rows.to_le_bytes());
+        let mut version = Fnv::default();
+        version.write(modified.as_bytes());
+        self.dir.join(format!(
+            "{database:016x}-{constant_set}-{:016x}-{:016x}.bin",
+            schema.0, version.0
+        ))
+    }
+}
+
+/// Key of the entries of the database at `location` (a file path or a backend location) with the
+/// given schema version.
+pub(crate) fn database_key(location: &str, schema_version: Option<i64>) -> u64 {
+    let mut key = Fnv::default();
+    match fs::canonicalize(location) {
+        Ok(path) => key.write(path.as_os_str().as_encoded_bytes()),
+        Err(_) => key.write(location.as_bytes()),
+    }
+    key.write(&[0]);
+    key.write(&schema_version.unwrap_or_default().to_le_bytes());
+    key.0
+}
+
+/// 64-bit FNV-1a hash, which is stable across processes and Rust versions.
+struct Fnv(u64);
+
+impl Default for Fnv {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+fn encode(vault_len: usize, data: &Data) -> Vec<u8> {
+    let mut bytes = MAGIC.to_vec();
+    bytes.extend((vault_len as u64).to_le_bytes());
+    for index in 0..data.n_columns() {
+        match data.column(index) {
+            Some(Column::Int(values)) => bytes.extend(values.iter().flat_map(|v| v.to_le_bytes())),
+            Some(Column::UInt(values)) => bytes.extend(values.iter().flat_map(|v| v.to_le_bytes())),
+            Some(Column::Long(values)) => bytes.extend(values.iter().flat_map(|v| v.to_le_bytes())),
+            Some(Column::ULong(values)) => {
+                bytes.extend(values.iter().flat_map(|v| v.to_le_bytes()));
+            }
+            Some(Column::Double(values)) => {
+                bytes.extend(values.iter().flat_map(|v| v.to_le_bytes()));
+            }
+            Some(Column::Bool(values)) => bytes.extend(values.iter().map(|v| u8::from(*v))),
+            Some(Column::String(values)) => {
+                for value in values {
+                    bytes.extend((value.len() as u64).to_le_bytes());
+                    bytes.extend(value.as_bytes());
+                }
+            }
+            None => {}
+        }
+    }
+    bytes
+}
+
+fn decode(
+    bytes: &[u8],
+    column_types: &[ColumnType],
+    n_rows: usize,
+) -> Option<(usize, Vec<Column>)> {
+    let mut reader = Reader(bytes.strip_prefix(MAGIC)?);
+    let vault_len = usize::try_from(u64::from_le_bytes(reader.array()?)).ok()?;
+    let columns = column_types
+        .iter()
+        .map(|column_type| {
+            Some(match column_type {
+                ColumnType::Int => Column::Int(reader.values(n_rows, i32::from_le_bytes)?),
+                ColumnType::UInt => Column::UInt(reader.values(n_rows, u32::from_le_bytes)?),
+                ColumnType::Long => Column::Long(reader.values(n_rows, i64::from_le_bytes)?),
+                ColumnType::ULong => Column::ULong(reader.values(n_rows, u64::from_le_bytes)?),
+                ColumnType::Double => Column::Double(reader.values(n_rows, f64::from_le_bytes)?),
+                ColumnType::Bool => Column::Bool(reader.values(n_rows, |[v]: [u8; 1]| v != 0)?),
+                ColumnType::String => Column::String(
+                    (0..n_rows)
+                        .map(|_| reader.string())
+                        .collect::<Option<_>>()?,
+                ),
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    reader.0.is_empty().then_some((vault_len, columns))
+}
+
+/// Cursor over the bytes of a cache entry.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let (head, tail) = self.0.split_at_checked(len)?;
+        self.0 = tail;
+        Some(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.take(N)?.try_into().ok()
+    }
+
+    fn values<const N: usize, T>(&mut self, n: usize, read: fn([u8; N]) -> T) -> Option<Vec<T>> {
+        (0..n).map(|_| self.array().map(read)).collect()
+    }
+
+    fn string(&mut self) -> Option<String> {
+        let len = usize::try_from(u64::from_le_bytes(self.array()?)).ok()?;
+        String::from_utf8(self.take(len)?.to_vec()).ok()
+    }
+}
@@ -1,7 +1,12 @@
 #![allow(missing_docs)]
 
 use chrono::{Datelike, Timelike};
-use gluex_ccdb::{context::Context, database::CCDB, models::ColumnMeta, CCDBResult};
+use gluex_ccdb::{
+    context::Context,
+    database::CCDB,
+    models::{ColumnMeta, VariationMeta},
+    CCDBResult,
+};
 use gluex_core::{errors::ParseTimestampError, parsers::parse_timestamp};
 use std::path::PathBuf;
 
@@ -150,3 +155,113 @@ fn fetch_respects_runs_variations_and_timestamps() -> CCDBResult<()> {
     }
     Ok(())
 }
+
+#[test]
+fn variations_can_be_listed_and_chained() -> CCDBResult<()> {
+    let db = open_db();
+    let variations = db.variations()?;
+    let names = variations
+        .iter()
+        .map(VariationMeta::name)
+        .collect::<Vec<_>>();
+    assert!(names.contains(&"default"));
+    assert!(names.contains(&"mc"));
+    assert!(variations.windows(2).all(|w| w[0].id() < w[1].id()));
+
+    let mc = db.variation("mc")?;
+    let chain = db.variation_chain(&mc)?;
+    assert_eq!(chain.first().map(VariationMeta::name), Some("mc"));
+    assert_eq!(chain.last().map(VariationMeta::name), Some("default"));
+
+    let children = db.variation_children(chain[1].id())?;
+    assert!(children.iter().any(|child| child.name() == "mc"));
+    assert!(children
+        .iter()
+        .all(|child| child.parent_id() == chain[1].id()));
+    Ok(())
+}
+
+#[test]
+fn table_authors_resolve_to_users() -> CCDBResult<()> {
+    let db = open_db();
+    let users = db.users()?;
+    assert!(users.windows(2).all(|w| w[0].id() < w[1].id()));
+    let author_id = db.table(TABLE_PATH)?.meta().author_id();
+    let author = db.user(author_id)?.expect("table author exists");
+    assert!(users.iter().any(|user| user.name() == author.name()));
+    Ok(())
+}
+
+#[test]
+fn extracted_snapshot_reproduces_fetch() -> CCDBResult<()> {
+    let db = open_db();
+    let output =
+        std::env::temp_dir().join(format!("gluex-ccdb-snapshot-{}.sqlite", std::process::id()));
+    let _ = std::fs::remove_file(&output);
+    db.extract_snapshot(&output, &["/test/demo"], 0..=2)?;
+    let snapshot = CCDB::open(&output)?;
+    let ctx = Context::default()
+        .with_runs([1, 2])
+        .with_timestamp(parse_timestamp("2020-02-01 00:00:00")?);
+    let expected = db.fetch(TABLE_PATH, &ctx)?;
+    let actual = snapshot.fetch(TABLE_PATH, &ctx)?;
+    assert_eq!(
+        actual.keys().collect::<Vec<_>>(),
+        expected.keys().collect::<Vec<_>>()
+    );
+    for (run, data) in &actual {
+        assert_eq!(
+            data.named_double("x", 1),
+            expected[run].named_double("x", 1)
+        );
+    }
+    assert!(snapshot
+        .fetch(TABLE_PATH, &Context::default().with_run(3))?
+        .is_empty());
+    std::fs::remove_file(&output).ok();
+    Ok(())
+}
+
+#[test]
+fn merging_a_snapshot_onto_itself_preserves_fetch() -> CCDBResult<()> {
+    let db = open_db();
+    let output =
+        std::env::temp_dir().join(format!("gluex-ccdb-merged-{}.sqlite", std::process::id()));
+    let _ = std::fs::remove_file(&output);
+    gluex_ccdb::snapshot::merge(ccdb_path(), ccdb_path(), &output)?;
+    let merged = CCDB::open(&output)?;
+    assert_eq!(merged.variations()?.len(), db.variations()?.len());
+    assert_eq!(
+        merged.dir("/test/demo")?.tables().len(),
+        db.dir("/test/demo")?.tables().len()
+    );
+    let ctx = Context::default().with_runs([0, 1, 2, 3]);
+    let expected = db.fetch(TABLE_PATH, &ctx)?;
+    let actual = merged.fetch(TABLE_PATH, &ctx)?;
+    for (run, data) in &expected {
+        assert_eq!(actual[run].named_double("z", 1), data.named_double("z", 1));
+    }
+    std::fs::remove_file(&output).ok();
+    Ok(())
+}
+
+#[cfg(feature = "provenance")]
+#[test]
+fn recorded_fetches_verify_against_the_same_database() -> CCDBResult<()> {
+    use gluex_core::provenance::{Query, Recorder};
+
+    let recorder = Recorder::new();
+    let db = open_db().with_recorder(recorder.clone());
+    let ctx = Context::default()
+        .with_runs([0, 1, 2, 3])
+        .with_timestamp(parse_timestamp("2020-02-01 00:00:00")?);
+    db.fetch(TABLE_PATH, &ctx)?;
+    let manifest = recorder.manifest();
+    let [Query::Ccdb(query)] = manifest.queries.as_slice() else {
+        panic!("expected a single CCDB query, got {:?}", manifest.queries);
+    };
+    assert_eq!(query.table, TABLE_PATH);
+    assert_eq!(query.assignments.len(), 4);
+    assert!(open_db().verify_manifest(&manifest)?.is_empty());
+    Ok(())
+}
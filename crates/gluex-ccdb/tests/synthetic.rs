@@ -0,0 +1,1436 @@
+#![allow(missing_docs)]
+
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use gluex_ccdb::{
+    backend::{Row, SqlValue, SqliteBackend, StorageBackend},
+    context::{Context, ParseRequestError, Request},
+    database::{TypeTableHandle, CCDB},
+    models::{UserMeta, VariationMeta},
+    overlay::CcdbOverlay,
+    prefetch::PrefetchRequest,
+    router::CcdbRouter,
+    CCDBError, CCDBResult,
+};
+use gluex_core::{
+    deprecation::{DeprecatedItem, DeprecationWarnings},
+    limits::{LimitError, QueryBudget, QueryLimits},
+    metrics::Metrics,
+    run_periods::RunPeriod,
+    run_set::{RunContext, RunSet},
+    schema::SchemaMode,
+    sql_audit::{AuditMode, SqlAudit},
+};
+use gluex_testdata::{
+    ccdb::{Assignment, CcdbBuilder, DEFAULT_USER},
+    TestDataError,
+};
+
+const TABLE_PATH: &str = "/test/demo/mytable";
+
+fn synthetic_db(name: &str) -> CCDB {
+    let path = temp_path(name);
+    let _ = std::fs::remove_file(&path);
+    CcdbBuilder::new()
+        .variation("mc", "default")
+        .table(TABLE_PATH, 2, &[("x", "double"), ("label", "string")])
+        .comment("synthetic table")
+        .table("/test/counts", 1, &[("n", "int")])
+        .assign(Assignment::new(
+            TABLE_PATH,
+            0..=100,
+            [["1.5", "a"], ["2.5", "b|c"]],
+        ))
+        .assign(Assignment::new(
+            TABLE_PATH,
+            50..=60,
+            [["9", "x"], ["8", "y"]],
+        ))
+        .assign(
+            Assignment::new(TABLE_PATH, 0..=100, [["7", "mc"], ["6", "mc"]]).with_variation("mc"),
+        )
+        .assign(Assignment::new("/test/counts", 0..=10, [[42]]))
+        .write(&path)
+        .expect("failed to write synthetic CCDB");
+    CCDB::open(&path).expect("failed to open synthetic CCDB")
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "gluex-ccdb-synthetic-{}-{name}.sqlite",
+        std::process::id()
+    ))
+}
+
+#[test]
+fn synthetic_tables_are_listed() -> CCDBResult<()> {
+    let db = synthetic_db("list");
+    let dir = db.dir("/test")?;
+    let mut tables: Vec<String> = dir
+        .tables()
+        .iter()
+        .map(TypeTableHandle::full_path)
+        .collect();
+    tables.sort();
+    assert_eq!(tables, vec!["/test/counts".to_string()]);
+    let table = db.table(TABLE_PATH)?;
+    assert_eq!(table.meta().n_rows(), 2);
+    assert_eq!(table.meta().n_columns(), 2);
+    assert_eq!(table.meta().comment(), "synthetic table");
+    Ok(())
+}
+
+#[test]
+fn synthetic_latest_assignment_wins() -> CCDBResult<()> {
+    let db = synthetic_db("latest");
+    let data = db.fetch(TABLE_PATH, &Context::default().with_runs([10, 55]))?;
+    let early = data.get(&10).expect("missing run 10");
+    assert_eq!(early.named_double("x", 0), Some(1.5));
+    assert_eq!(early.named_string("label", 1), Some("b|c"));
+    let overridden = data.get(&55).expect("missing run 55");
+    assert_eq!(overridden.named_double("x", 0), Some(9.0));
+    Ok(())
+}
+
+#[test]
+fn synthetic_variation_and_missing_runs() -> CCDBResult<()> {
+    let db = synthetic_db("variation");
+    let data = db.fetch(
+        TABLE_PATH,
+        &Context::default().with_variation("mc").with_run(55),
+    )?;
+    assert_eq!(
+        data.get(&55).and_then(|d| d.named_double("x", 0)),
+        Some(7.0)
+    );
+    let counts = db.fetch("/test/counts", &Context::default().with_runs([5, 500]))?;
+    assert_eq!(counts.get(&5).and_then(|d| d.named_int("n", 0)), Some(42));
+    assert!(!counts.contains_key(&500));
+    Ok(())
+}
+
+#[test]
+fn synthetic_go_back_time_clamps_parent_lookups() -> CCDBResult<()> {
+    let epoch = CcdbBuilder::epoch();
+    let path = temp_path("go-back");
+    let _ = std::fs::remove_file(&path);
+    CcdbBuilder::new()
+        .variation("frozen", "default")
+        .go_back("frozen", epoch + chrono::Duration::days(2))
+        .table("/test/counts", 1, &[("n", "int")])
+        .assign(
+            Assignment::new("/test/counts", 0..=100, [[1]])
+                .with_created(epoch + chrono::Duration::days(1)),
+        )
+        .assign(
+            Assignment::new("/test/counts", 0..=100, [[2]])
+                .with_created(epoch + chrono::Duration::days(3)),
+        )
+        .write(&path)
+        .expect("failed to write go-back CCDB");
+    let db = CCDB::open(&path)?;
+    let n = |ctx: Context| -> CCDBResult<Option<i32>> {
+        Ok(db
+            .fetch("/test/counts", &ctx.with_run(5))?
+            .get(&5)
+            .and_then(|d| d.named_int("n", 0)))
+    };
+    assert_eq!(n(Context::default())?, Some(2));
+    assert_eq!(n(Context::default().with_variation("frozen"))?, Some(1));
+    assert_eq!(
+        n(Context::default()
+            .with_variation("frozen")
+            .with_timestamp(epoch + chrono::Duration::hours(1)))?,
+        None
+    );
+
+    // go-back enabled with a zeroed or unparsable time searches the parent without a cutoff
+    for go_back_time in ["0000-00-00 00:00:00", "not a time"] {
+        rusqlite::Connection::open(&path)?.execute(
+            "UPDATE variations SET goBackTime = ?1 WHERE name = 'frozen'",
+            [go_back_time],
+        )?;
+        let db = CCDB::open(&path)?;
+        let frozen = db.variation("frozen")?;
+        assert_eq!(frozen.go_back_behavior(), 1);
+        assert_eq!(frozen.go_back_cutoff(), None);
+        let data = db.fetch(
+            "/test/counts",
+            &Context::default().with_variation("frozen").with_run(5),
+        )?;
+        assert_eq!(data[&5].named_int("n", 0), Some(2));
+    }
+    Ok(())
+}
+
+#[test]
+fn synthetic_rejects_bad_shapes() {
+    let path = temp_path("shape");
+    let _ = std::fs::remove_file(&path);
+    let result = CcdbBuilder::new()
+        .table("/a/b", 1, &[("x", "int")])
+        .assign(Assignment::new("/a/b", 0..=1, [[1, 2]]))
+        .write(&path);
+    assert!(result.is_err());
+}
+
+#[test]
+fn synthetic_run_set_expands_to_runs() -> CCDBResult<()> {
+    let db = synthetic_db("run_set");
+    let ctx = Context::default().with_run_set(RunSet::range(99..102));
+    assert_eq!(ctx.runs, vec![99, 100, 101]);
+    let data = db.fetch(TABLE_PATH, &ctx)?;
+    assert_eq!(data.keys().copied().collect::<Vec<_>>(), vec![99, 100]);
+    Ok(())
+}
+
+#[test]
+fn synthetic_selection_string_converts_to_context() -> CCDBResult<()> {
+    let db = synthetic_db("selection");
+    let runs: RunSet = "[1..3]+[99..101]-2".parse().expect("valid selection");
+    let ctx = Context::from(runs);
+    assert_eq!(ctx.runs, vec![1, 3, 99, 100, 101]);
+    let data = db.fetch(TABLE_PATH, &ctx)?;
+    assert_eq!(
+        data.keys().copied().collect::<Vec<_>>(),
+        vec![1, 3, 99, 100]
+    );
+    Ok(())
+}
+
+#[test]
+fn synthetic_metrics_count_fetches_and_cache_hits() -> CCDBResult<()> {
+    let metrics = Metrics::new();
+    let db = synthetic_db("metrics").with_metrics(metrics.clone());
+    let ctx = Context::default().with_runs([1, 55]);
+    db.fetch(TABLE_PATH, &ctx)?;
+    db.fetch(TABLE_PATH, &ctx)?;
+    let report = db.metrics().expect("metrics attached");
+    assert_eq!(report, metrics.report());
+    let fetch = report.operations["ccdb.fetch"];
+    assert_eq!(fetch.count, 2);
+    assert_eq!(fetch.rows, 8);
+    assert_eq!(report.operations["ccdb.columns"].count, 1);
+    let layouts = report.caches["ccdb.column_layouts"];
+    assert_eq!((layouts.hits, layouts.misses), (1, 1));
+    assert_eq!(layouts.hit_rate(), Some(0.5));
+    metrics.reset();
+    assert!(db
+        .metrics()
+        .is_some_and(|report| report.operations.is_empty()));
+    assert!(synthetic_db("no_metrics").metrics().is_none());
+    Ok(())
+}
+
+#[test]
+fn synthetic_overlay_falls_through_to_lower_layers() -> CCDBResult<()> {
+    let path = temp_path("overlay_top");
+    let _ = std::fs::remove_file(&path);
+    CcdbBuilder::new()
+        .table(TABLE_PATH, 2, &[("x", "double"), ("label", "string")])
+        .assign(Assignment::new(
+            TABLE_PATH,
+            0..=10,
+            [["100", "o"], ["200", "p"]],
+        ))
+        .write(&path)
+        .expect("failed to write override CCDB");
+    let overlay = CcdbOverlay::new(vec![CCDB::open(&path)?, synthetic_db("overlay_base")]);
+    let data = overlay.fetch(TABLE_PATH, &Context::default().with_runs([5, 55]))?;
+    assert_eq!(data[&5].layer, 0);
+    assert_eq!(data[&5].data.named_double("x", 0), Some(100.0));
+    assert_eq!(data[&55].layer, 1);
+    assert_eq!(data[&55].data.named_double("x", 0), Some(9.0));
+    let counts = overlay.request("/test/counts:5")?;
+    assert_eq!(counts[&5].layer, 1);
+    assert!(matches!(
+        overlay.fetch("/missing", &Context::default()),
+        Err(CCDBError::TableNotFoundError(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn synthetic_router_sends_runs_to_the_database_serving_them() -> CCDBResult<()> {
+    let path = temp_path("router_period");
+    let _ = std::fs::remove_file(&path);
+    CcdbBuilder::new()
+        .table(TABLE_PATH, 2, &[("x", "double"), ("label", "string")])
+        .assign(Assignment::new(
+            TABLE_PATH,
+            0..=100,
+            [["100", "o"], ["200", "p"]],
+        ))
+        .write(&path)
+        .expect("failed to write run-period CCDB");
+    let router = CcdbRouter::new()
+        .with_route(50..=60, CCDB::open(&path)?)
+        .with_default(synthetic_db("router_default"));
+    let data = router.fetch(TABLE_PATH, &Context::default().with_runs([5, 55, 70]))?;
+    assert_eq!(data.len(), 3);
+    assert_eq!(data[&5].named_double("x", 0), Some(1.5));
+    assert_eq!(data[&55].named_double("x", 0), Some(100.0));
+    assert_eq!(data[&70].named_double("x", 0), Some(1.5));
+    assert_eq!(
+        router.handle(55).map(CCDB::connection_path),
+        Some(path.to_string_lossy().as_ref())
+    );
+    assert_eq!(router.request("/test/counts:5")?.len(), 1);
+    assert!(matches!(
+        router.request("/test/counts:55"),
+        Err(CCDBError::TableNotFoundError(_))
+    ));
+    let unrouted = CcdbRouter::new().with_route(50..=60, CCDB::open(&path)?);
+    assert_eq!(
+        unrouted
+            .fetch(TABLE_PATH, &Context::default().with_runs([5, 55]))?
+            .into_keys()
+            .collect::<Vec<_>>(),
+        vec![55]
+    );
+    Ok(())
+}
+
+/// Forwards every statement to a `SQLite` snapshot and counts them.
+struct CountingBackend {
+    inner: SqliteBackend,
+    statements: AtomicUsize,
+}
+
+impl StorageBackend for CountingBackend {
+    fn location(&self) -> &'static str {
+        "counting://synthetic"
+    }
+
+    fn query(
+        &self,
+        sql: &str,
+        params: &[SqlValue],
+        budget: Option<&QueryBudget>,
+        on_row: &mut dyn FnMut(Row<'_>) -> CCDBResult<()>,
+    ) -> CCDBResult<()> {
+        self.statements.fetch_add(1, Ordering::Relaxed);
+        self.inner.query(sql, params, budget, on_row)
+    }
+
+    // an equivalent spelling, so the query layer can be seen using the backend's dialect
+    fn timestamp_sql(&self) -> &'static str {
+        "datetime(? + 0, 'unixepoch', 'localtime')"
+    }
+}
+
+#[test]
+fn synthetic_custom_backends_serve_the_query_layer() -> CCDBResult<()> {
+    let path = synthetic_db("custom_backend").connection_path().to_string();
+    let backend = Arc::new(CountingBackend {
+        inner: SqliteBackend::new(rusqlite::Connection::open(&path)?, path),
+        statements: AtomicUsize::new(0),
+    });
+    let db = CCDB::from_backend(backend.clone(), SchemaMode::Strict)?;
+    assert_eq!(db.connection_path(), "counting://synthetic");
+    assert!(db.try_connection().is_none());
+    let loaded = backend.statements.load(Ordering::Relaxed);
+    let ctx = Context::default().with_runs([5, 55]).with_variation("mc");
+    let data = db.fetch(TABLE_PATH, &ctx)?;
+    assert_eq!(data[&5].named_double("x", 0), Some(7.0));
+    assert_eq!(data[&55].named_string("label", 0), Some("mc"));
+    assert!(backend.statements.load(Ordering::Relaxed) > loaded);
+    let plans = db.explain_fetch(TABLE_PATH, &ctx)?;
+    assert!(plans.iter().all(|plan| plan.steps.is_empty()));
+    assert!(plans.iter().any(|plan| plan.sql.contains("datetime(? + 0")));
+    assert!(synthetic_db("sqlite_backend").try_connection().is_some());
+
+    // a store that cannot answer the schema query is an error, not an unversioned database
+    let empty = temp_path("empty_backend");
+    let _ = std::fs::remove_file(&empty);
+    let empty = Arc::new(CountingBackend {
+        inner: SqliteBackend::new(
+            rusqlite::Connection::open(&empty)?,
+            empty.display().to_string(),
+        ),
+        statements: AtomicUsize::new(0),
+    });
+    assert!(matches!(
+        CCDB::from_backend(empty, SchemaMode::Strict),
+        Err(CCDBError::SqliteError(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn synthetic_pooled_handles_query_from_many_threads() -> CCDBResult<()> {
+    let path = synthetic_db("pooled").connection_path().to_string();
+    let db = CCDB::open_pooled(&path, 4)?;
+    assert_eq!(db.backend().sqlite().map(SqliteBackend::pool_size), Some(4));
+    assert_eq!(
+        CCDB::open_pooled(&path, 0)?
+            .backend()
+            .sqlite()
+            .map(SqliteBackend::pool_size),
+        Some(1)
+    );
+    let values = std::thread::scope(|scope| {
+        let handles: Vec<_> = [5, 55, 5, 55, 5, 55, 5, 55]
+            .into_iter()
+            .map(|run| {
+                let db = db.clone();
+                scope.spawn(move || {
+                    let data = db.fetch(TABLE_PATH, &Context::default().with_runs([run]))?;
+                    Ok(data[&run].double(0, 0))
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("fetch thread panicked"))
+            .collect::<CCDBResult<Vec<_>>>()
+    })?;
+    assert_eq!(
+        values,
+        [1.5, 9.0, 1.5, 9.0, 1.5, 9.0, 1.5, 9.0].map(Some).to_vec()
+    );
+    Ok(())
+}
+
+#[test]
+fn synthetic_find_tables_matches_glob_patterns() {
+    let db = synthetic_db("find_tables");
+    let paths = |pattern: &str| {
+        db.find_tables(pattern)
+            .iter()
+            .map(TypeTableHandle::full_path)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(paths("/test/**/*"), ["/test/counts", TABLE_PATH]);
+    assert_eq!(paths("/test/*"), ["/test/counts"]);
+    assert_eq!(paths("**/counts"), ["/test/counts"]);
+    assert_eq!(paths("/test/**/my*"), [TABLE_PATH]);
+    assert_eq!(paths("/test/demo/mytabl?"), [TABLE_PATH]);
+    assert_eq!(paths("test/c*s"), ["/test/counts"]);
+    assert!(paths("/test/demo/mytabl").is_empty());
+    assert!(paths("/nope/**").is_empty());
+}
+
+#[test]
+fn synthetic_search_ranks_name_and_comment_matches() {
+    use gluex_ccdb::search::{regex::Regex, SearchMatch, SearchTarget};
+
+    let db = synthetic_db("search");
+    let hits = db.search("DEMO");
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].path, "/test/demo");
+    assert_eq!(hits[0].target, SearchTarget::Directory);
+    assert_eq!(hits[0].matched, SearchMatch::ExactName);
+
+    let ranked: Vec<_> = db
+        .search("t")
+        .into_iter()
+        .map(|hit| (hit.matched, hit.path))
+        .collect();
+    assert_eq!(
+        ranked,
+        [
+            (SearchMatch::Name, "/test".to_string()),
+            (SearchMatch::Name, "/test/counts".to_string()),
+            (SearchMatch::Name, TABLE_PATH.to_string()),
+        ]
+    );
+
+    let hits = db.search("synthetic");
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].path, TABLE_PATH);
+    assert_eq!(hits[0].matched, SearchMatch::Comment);
+    assert_eq!(hits[0].comment, "synthetic table");
+
+    let hits = db.search_regex(&Regex::new("^my.*e$").expect("valid regex"));
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].target, SearchTarget::Table);
+    assert_eq!(hits[0].matched, SearchMatch::ExactName);
+    assert!(db.search("nothing like this").is_empty());
+}
+
+#[test]
+fn synthetic_variation_children_walk_the_hierarchy() -> CCDBResult<()> {
+    let db = synthetic_db("variation_children");
+    let default = db.variation("default")?;
+    let children = db.variation_children(default.id())?;
+    assert_eq!(
+        children.iter().map(VariationMeta::name).collect::<Vec<_>>(),
+        ["mc"]
+    );
+    assert!(db.variation_children(children[0].id())?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn synthetic_variations_are_listed_and_chained() -> CCDBResult<()> {
+    let path = temp_path("variation_chain");
+    let _ = std::fs::remove_file(&path);
+    CcdbBuilder::new()
+        .variation("calib", "default")
+        .variation("mc", "calib")
+        .variation("test", "default")
+        .table(TABLE_PATH, 1, &[("x", "double")])
+        .write(&path)
+        .expect("failed to write synthetic CCDB");
+    let db = CCDB::open(&path)?;
+    let variations = db.variations()?;
+    assert_eq!(
+        variations
+            .iter()
+            .map(VariationMeta::name)
+            .collect::<Vec<_>>(),
+        ["default", "calib", "mc", "test"]
+    );
+    assert!(variations.windows(2).all(|w| w[0].id() < w[1].id()));
+
+    let chain = db.variation_chain(&db.variation("mc")?)?;
+    assert_eq!(
+        chain.iter().map(VariationMeta::name).collect::<Vec<_>>(),
+        ["mc", "calib", "default"]
+    );
+    let children = db.variation_children(chain[1].id())?;
+    assert_eq!(
+        children.iter().map(VariationMeta::name).collect::<Vec<_>>(),
+        ["mc"]
+    );
+    assert!(children
+        .iter()
+        .all(|child| child.parent_id() == chain[1].id()));
+    assert_eq!(db.variation_children(chain[2].id())?.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn synthetic_export_snapshot_keeps_only_resolved_rows() -> CCDBResult<()> {
+    let db = synthetic_db("export_source");
+    let output = temp_path("export_snapshot");
+    let _ = std::fs::remove_file(&output);
+    let ctx = Context::default().with_runs([5, 55]);
+    db.export_snapshot(&[TABLE_PATH], &ctx, &output)?;
+
+    let exported = CCDB::open(&output)?;
+    let count = |table: &str| -> rusqlite::Result<i64> {
+        exported
+            .try_connection()
+            .expect("exported snapshot is an SQLite file")
+            .query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                row.get(0)
+            })
+    };
+    assert_eq!(count("assignments")?, 2);
+    assert_eq!(count("constantSets")?, 2);
+    assert_eq!(count("variations")?, 1);
+    assert_eq!(count("typeTables")?, 1);
+    let data = exported.fetch(TABLE_PATH, &ctx)?;
+    assert_eq!(data[&5].double(0, 0), Some(1.5));
+    assert_eq!(data[&55].double(0, 0), Some(9.0));
+    assert!(matches!(
+        exported.table("/test/counts"),
+        Err(CCDBError::TableNotFoundError(_))
+    ));
+
+    let _ = std::fs::remove_file(&output);
+    let mc = ctx.clone().with_variation("mc");
+    db.export_snapshot(&["/test"], &mc, &output)?;
+    let exported = CCDB::open(&output)?;
+    assert_eq!(exported.variations()?.len(), 2);
+    let tables = exported.fetch_many([TABLE_PATH, "/test/counts"], &mc)?;
+    assert_eq!(tables[TABLE_PATH][&55].double(0, 0), Some(7.0));
+    assert_eq!(tables["/test/counts"][&5].int(0, 0), Some(42));
+    assert!(!tables["/test/counts"].contains_key(&55));
+
+    let extracted = temp_path("extract_snapshot");
+    let _ = std::fs::remove_file(&extracted);
+    db.extract_snapshot(&extracted, &[TABLE_PATH], 0..=100)?;
+    let assignments: i64 = CCDB::open(&extracted)?
+        .try_connection()
+        .expect("extracted snapshot is an SQLite file")
+        .query_row("SELECT COUNT(*) FROM assignments", [], |row| row.get(0))?;
+    assert_eq!(assignments, 3);
+    assert!(matches!(
+        db.export_snapshot(&[TABLE_PATH], &ctx.with_variation("nope"), &output),
+        Err(CCDBError::VariationNotFoundError(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn synthetic_snapshots_need_an_sqlite_file() -> CCDBResult<()> {
+    let path = synthetic_db("snapshot_source")
+        .connection_path()
+        .to_string();
+    let bytes = std::fs::read(&path).expect("failed to read synthetic CCDB");
+    let in_memory = CCDB::open_from_bytes(&bytes)?;
+    let custom = CCDB::from_backend(
+        Arc::new(CountingBackend {
+            inner: SqliteBackend::new(rusqlite::Connection::open(&path)?, path),
+            statements: AtomicUsize::new(0),
+        }),
+        SchemaMode::Strict,
+    )?;
+    let output = temp_path("unsupported_snapshot");
+    let _ = std::fs::remove_file(&output);
+    let ctx = Context::default().with_runs([5]);
+    for db in [&in_memory, &custom] {
+        assert!(matches!(
+            db.export_snapshot(&[TABLE_PATH], &ctx, &output),
+            Err(CCDBError::UnsupportedSourceError(_))
+        ));
+        assert!(matches!(
+            db.extract_snapshot(&output, &[TABLE_PATH], 0..=100),
+            Err(CCDBError::UnsupportedSourceError(_))
+        ));
+    }
+    assert!(!output.exists());
+    Ok(())
+}
+
+#[test]
+fn synthetic_vault_cache_reuses_decoded_tables() -> CCDBResult<()> {
+    use gluex_ccdb::vault_cache::VaultCache;
+
+    let cache = VaultCache::new(temp_path("vault_cache_entries"));
+    cache.clear().expect("cache directory is removable");
+    let ctx = Context::default().with_runs([5, 55]);
+    let metrics = Metrics::new();
+    let db = synthetic_db("vault_cache")
+        .with_metrics(metrics.clone())
+        .with_vault_cache(Some(cache.clone()));
+    let parsed = db.fetch(TABLE_PATH, &ctx)?;
+    let entries = || {
+        std::fs::read_dir(cache.dir())
+            .map(Iterator::count)
+            .unwrap_or_default()
+    };
+    assert_eq!(entries(), 2);
+
+    let audit = SqlAudit::new(AuditMode::Log);
+    let reopened = CCDB::open(db.connection_path())?
+        .with_metrics(metrics.clone())
+        .with_sql_audit(audit.clone())
+        .with_vault_cache(Some(cache.clone()));
+    let cached = reopened.fetch(TABLE_PATH, &ctx)?;
+    let reads_vaults = || {
+        audit
+            .statements()
+            .iter()
+            .any(|statement| statement.sql.contains("vault"))
+    };
+    assert!(!reads_vaults());
+    for run in [5, 55] {
+        assert_eq!(cached[&run].n_rows(), parsed[&run].n_rows());
+        assert_eq!(cached[&run].double(0, 1), parsed[&run].double(0, 1));
+        assert_eq!(cached[&run].string(1, 1), parsed[&run].string(1, 1));
+    }
+    let lookups = metrics.report().caches["ccdb.vault_cache"];
+    assert_eq!((lookups.hits, lookups.misses), (2, 2));
+
+    for entry in std::fs::read_dir(cache.dir()).expect("cache directory exists") {
+        std::fs::write(entry.expect("entry is readable").path(), b"garbage")
+            .expect("entry is writable");
+    }
+    let repaired = reopened.fetch(TABLE_PATH, &ctx)?;
+    assert_eq!(repaired[&55].string(1, 0), Some("x"));
+    assert!(reads_vaults());
+    let lookups = metrics.report().caches["ccdb.vault_cache"];
+    assert_eq!((lookups.hits, lookups.misses), (2, 4));
+    assert_eq!(
+        reopened.fetch(TABLE_PATH, &ctx)?[&5].double(0, 0),
+        Some(1.5)
+    );
+    assert_eq!(metrics.report().caches["ccdb.vault_cache"].hits, 4);
+
+    let size = || {
+        std::fs::read_dir(cache.dir())
+            .expect("cache directory exists")
+            .map(|entry| entry.expect("entry is readable").metadata().unwrap().len())
+            .sum::<u64>()
+    };
+    let full = size();
+    cache.clear().expect("cache directory is removable");
+    assert_eq!(entries(), 0);
+    let bounded = |max_bytes| {
+        CCDB::open(db.connection_path())
+            .expect("synthetic CCDB opens")
+            .with_vault_cache(Some(cache.clone().with_max_bytes(max_bytes)))
+    };
+    assert_eq!(bounded(full).fetch(TABLE_PATH, &ctx)?.len(), 2);
+    assert_eq!(entries(), 2);
+    cache.clear().expect("cache directory is removable");
+    assert_eq!(bounded(full - 1).fetch(TABLE_PATH, &ctx)?.len(), 2);
+    assert_eq!(entries(), 1);
+    assert_eq!(bounded(0).fetch(TABLE_PATH, &ctx)?.len(), 2);
+    assert_eq!(entries(), 0);
+
+    let other = synthetic_db("vault_cache_other").with_vault_cache(Some(cache.clone()));
+    db.fetch(TABLE_PATH, &ctx)?;
+    other.fetch(TABLE_PATH, &ctx)?;
+    assert_eq!(entries(), 4);
+    let bytes = std::fs::read(db.connection_path()).expect("failed to read synthetic CCDB");
+    let in_memory = CCDB::open_from_bytes(&bytes)?.with_vault_cache(Some(cache.clone()));
+    assert_eq!(
+        in_memory.fetch(TABLE_PATH, &ctx)?[&5].double(0, 0),
+        Some(1.5)
+    );
+    assert_eq!(entries(), 4);
+    assert!(synthetic_db("vault_cache_off")
+        .with_vault_cache(None)
+        .vault_cache()
+        .is_none());
+    Ok(())
+}
+
+#[test]
+fn synthetic_users_name_authors() -> CCDBResult<()> {
+    let path = temp_path("users");
+    let _ = std::fs::remove_file(&path);
+    CcdbBuilder::new()
+        .user("jdoe", "Jane Doe")
+        .table(TABLE_PATH, 1, &[("x", "double")])
+        .write(&path)
+        .expect("failed to write synthetic CCDB");
+    let db = CCDB::open(&path)?;
+    let users = db.users()?;
+    assert_eq!(
+        users.iter().map(UserMeta::name).collect::<Vec<_>>(),
+        ["anonymous", "jdoe"]
+    );
+    assert_eq!(users[1].info(), "Jane Doe");
+    assert!(users[1].roles().is_empty());
+
+    let author_id = db.table(TABLE_PATH)?.meta().author_id();
+    assert_eq!(db.user(author_id)?.map(|user| user.id()), Some(1));
+    assert_eq!(db.user(2)?.map(|user| user.created().is_ok()), Some(true));
+    assert!(db.user(99)?.is_none());
+
+    let snapshot = temp_path("users_snapshot");
+    let _ = std::fs::remove_file(&snapshot);
+    db.export_snapshot(&[TABLE_PATH], &Context::default(), &snapshot)?;
+    assert_eq!(CCDB::open(&snapshot)?.users()?.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn synthetic_table_authors_resolve_to_users() -> CCDBResult<()> {
+    let path = temp_path("table_authors");
+    let _ = std::fs::remove_file(&path);
+    CcdbBuilder::new()
+        .user("jdoe", "Jane Doe")
+        .user("asmith", "Alex Smith")
+        .table(TABLE_PATH, 1, &[("x", "double")])
+        .author("asmith")
+        .table("/test/counts", 1, &[("n", "int")])
+        .write(&path)
+        .expect("failed to write synthetic CCDB");
+    let db = CCDB::open(&path)?;
+    let users = db.users()?;
+    assert!(users.windows(2).all(|w| w[0].id() < w[1].id()));
+    let author = db
+        .user(db.table(TABLE_PATH)?.meta().author_id())?
+        .expect("table author exists");
+    assert_eq!((author.name(), author.info()), ("asmith", "Alex Smith"));
+    assert!(users.iter().any(|user| user.name() == author.name()));
+    let default_author = db.user(db.table("/test/counts")?.meta().author_id())?;
+    assert_eq!(
+        default_author.as_ref().map(UserMeta::name),
+        Some(DEFAULT_USER)
+    );
+
+    let unknown = temp_path("unknown_author");
+    let _ = std::fs::remove_file(&unknown);
+    assert!(matches!(
+        CcdbBuilder::new()
+            .table(TABLE_PATH, 1, &[("x", "double")])
+            .author("nobody")
+            .write(&unknown),
+        Err(TestDataError::UnknownUser(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn synthetic_run_period_context_covers_the_period() -> CCDBResult<()> {
+    let period = RunPeriod::RP2018_08;
+    let ctx = Context::default().with_run_period(period, None)?;
+    assert_eq!(ctx.runs.first(), Some(&period.min_run()));
+    assert_eq!(ctx.runs.last(), Some(&period.max_run()));
+    assert_eq!(ctx.runs, Context::default().with_period(period).runs);
+    Ok(())
+}
+
+#[test]
+fn synthetic_request_strings_accept_named_options_and_flags() -> CCDBResult<()> {
+    let request: Request =
+        format!("{TABLE_PATH}:55::2099-01-01 12:00:00:event=9:nocache").parse()?;
+    assert_eq!(request.context.runs, [55]);
+    assert_eq!(request.context.variation, "default");
+    assert_eq!(
+        request.context.timestamp.to_rfc3339(),
+        "2099-01-01T12:00:00+00:00"
+    );
+    assert_eq!(request.event, Some(9));
+    assert_eq!(request.flags, ["nocache"]);
+
+    let named: Request = format!("{TABLE_PATH}:variation=mc:run=5:latest:latest").parse()?;
+    assert_eq!(named.context.runs, [5]);
+    assert_eq!(named.context.variation, "mc");
+    assert_eq!(named.event, None);
+    assert!(named.has_flag("latest") && named.flags.len() == 1);
+
+    for (input, expected) in [
+        (":5:run=6", "duplicate"),
+        (":5:colour=red", "unknown"),
+        (":5:::9lives", "flag"),
+        (":event=-1", "event"),
+    ] {
+        let err = format!("{TABLE_PATH}{input}")
+            .parse::<Request>()
+            .unwrap_err();
+        let matched = match err {
+            ParseRequestError::DuplicateFieldError(_) => "duplicate",
+            ParseRequestError::UnknownOptionError(_) => "unknown",
+            ParseRequestError::InvalidFlagError(_) => "flag",
+            ParseRequestError::InvalidEventNumberError(_) => "event",
+            _ => "other",
+        };
+        assert_eq!(matched, expected, "{input}");
+    }
+
+    let db = synthetic_db("request_grammar");
+    let fetched = db.request(&format!("{TABLE_PATH}:variation=mc:run=5"))?;
+    assert_eq!(fetched[&5].double(0, 0), Some(7.0));
+    Ok(())
+}
+
+#[test]
+fn synthetic_tables_export_as_delimited_text() -> CCDBResult<()> {
+    use gluex_ccdb::csv::{write_runs_csv, write_runs_delimited};
+
+    let db = synthetic_db("csv");
+    let data = db.fetch(TABLE_PATH, &Context::default().with_runs([10, 55]))?;
+    let mut csv = Vec::new();
+    data[&10]
+        .write_csv(&mut csv)
+        .expect("writing to a Vec does not fail");
+    assert_eq!(String::from_utf8_lossy(&csv), "x,label\n1.5,a\n2.5,b|c\n");
+
+    let mut csv = Vec::new();
+    write_runs_csv(&data, &mut csv).expect("writing to a Vec does not fail");
+    assert_eq!(
+        String::from_utf8_lossy(&csv),
+        "run_number,x,label\n10,1.5,a\n10,2.5,b|c\n55,9,x\n55,8,y\n"
+    );
+
+    let mut psv = Vec::new();
+    data[&10]
+        .write_delimited(&mut psv, '|')
+        .expect("writing to a Vec does not fail");
+    assert_eq!(
+        String::from_utf8_lossy(&psv),
+        "x|label\n1.5|a\n2.5|\"b|c\"\n"
+    );
+
+    let mut tsv = Vec::new();
+    write_runs_delimited(&std::collections::BTreeMap::new(), &mut tsv, '\t')
+        .expect("writing to a Vec does not fail");
+    assert_eq!(String::from_utf8_lossy(&tsv), "run_number\n");
+    Ok(())
+}
+
+#[test]
+fn synthetic_sql_audit_logs_and_dry_runs() -> CCDBResult<()> {
+    let ctx = Context::default().with_runs([5]).with_variation("mc");
+    let audit = SqlAudit::new(AuditMode::Log);
+    let db = synthetic_db("audit_log").with_sql_audit(audit.clone());
+    assert_eq!(db.fetch(TABLE_PATH, &ctx)?.len(), 1);
+    let statements = audit.statements();
+    assert!(statements.iter().all(|statement| statement.executed));
+    assert!(statements
+        .iter()
+        .any(|statement| statement.params == ["'mc'"]));
+
+    let dry_run = SqlAudit::new(AuditMode::DryRun);
+    let db = synthetic_db("audit_dry_run").with_sql_audit(dry_run.clone());
+    assert!(db.fetch(TABLE_PATH, &ctx)?.is_empty());
+    let skipped: Vec<_> = dry_run
+        .statements()
+        .into_iter()
+        .filter(|statement| !statement.executed)
+        .collect();
+    // one assignment query per variation in the chain mc -> default
+    assert_eq!(skipped.len(), 2);
+    assert!(skipped[0].sql.contains("FROM assignments"));
+    assert_eq!(skipped[0].params[3..], ["5", "5"]);
+    Ok(())
+}
+
+#[test]
+fn synthetic_fetch_many_batches_assignment_queries() -> CCDBResult<()> {
+    let ctx = Context::default().with_runs([5, 55]).with_variation("mc");
+    let audit = SqlAudit::new(AuditMode::Log);
+    let db = synthetic_db("fetch_many").with_sql_audit(audit.clone());
+    let tables = db.fetch_many([TABLE_PATH, "/test/counts"], &ctx)?;
+    let assignment_queries: Vec<_> = audit
+        .statements()
+        .into_iter()
+        .filter(|statement| statement.sql.contains("FROM assignments"))
+        .collect();
+    // mc is searched for both tables at once, default only for the table mc does not cover
+    assert_eq!(assignment_queries.len(), 2);
+    assert!(assignment_queries[0].sql.contains("IN (?, ?)"));
+    assert_eq!(
+        tables.keys().map(String::as_str).collect::<Vec<_>>(),
+        ["/test/counts", TABLE_PATH]
+    );
+    for (path, data) in &tables {
+        let single = db.fetch(path, &ctx)?;
+        assert_eq!(
+            data.keys().collect::<Vec<_>>(),
+            single.keys().collect::<Vec<_>>()
+        );
+        for (run, data) in data {
+            assert_eq!(data.n_rows(), single[run].n_rows());
+            assert_eq!(data.double(0, 0), single[run].double(0, 0));
+            assert_eq!(data.int(0, 0), single[run].int(0, 0));
+        }
+    }
+    assert_eq!(tables[TABLE_PATH][&55].double(0, 0), Some(7.0));
+    assert_eq!(tables["/test/counts"][&5].int(0, 0), Some(42));
+    assert!(!tables["/test/counts"].contains_key(&55));
+    assert!(matches!(
+        db.fetch_many([TABLE_PATH, "/test/missing"], &ctx),
+        Err(CCDBError::TableNotFoundError(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn synthetic_explain_reports_query_plans() -> CCDBResult<()> {
+    let ctx = Context::default().with_runs([5, 55]).with_variation("mc");
+    let audit = SqlAudit::new(AuditMode::Log);
+    let db = synthetic_db("explain").with_sql_audit(audit.clone());
+    let plans = db.explain_fetch(TABLE_PATH, &ctx)?;
+    assert_eq!(plans.len(), 2);
+    assert!(plans[0].sql.contains("FROM columns"));
+    assert!(plans[1].sql.contains("FROM assignments"));
+    assert_eq!(plans[1].params[3..], ["5", "55"]);
+    assert!(plans.iter().all(|plan| !plan.steps.is_empty()));
+    assert!(plans[1].to_string().contains("QUERY PLAN"));
+    assert!(audit
+        .statements()
+        .iter()
+        .all(|statement| !statement.sql.contains("FROM assignments")));
+    assert!(matches!(
+        db.explain_fetch(TABLE_PATH, &Context::default().with_variation("nope")),
+        Err(CCDBError::VariationNotFoundError(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn synthetic_limits_reject_runaway_fetches() -> CCDBResult<()> {
+    let db = synthetic_db("limits");
+    let ctx = Context::default().with_runs(0..=20);
+    let limited = db.clone().with_limits(QueryLimits::new().with_max_runs(20));
+    assert!(matches!(
+        limited.fetch(TABLE_PATH, &ctx),
+        Err(CCDBError::LimitError(LimitError::TooManyRuns { limit: 20 }))
+    ));
+    assert_eq!(
+        limited
+            .fetch(TABLE_PATH, &ctx.clone().with_runs(0..20))?
+            .len(),
+        20
+    );
+    let limited = db
+        .clone()
+        .with_limits(QueryLimits::new().with_max_result_bytes(100));
+    assert!(matches!(
+        limited.fetch(TABLE_PATH, &ctx),
+        Err(CCDBError::LimitError(LimitError::ResultTooLarge {
+            limit: 100
+        }))
+    ));
+    assert_eq!(
+        limited
+            .fetch(TABLE_PATH, &ctx.clone().with_runs([1]))?
+            .len(),
+        1
+    );
+    let limited = db.with_limits(QueryLimits::new().with_timeout(std::time::Duration::ZERO));
+    assert!(matches!(
+        limited.fetch(TABLE_PATH, &ctx),
+        Err(CCDBError::LimitError(LimitError::Timeout(_)))
+    ));
+    Ok(())
+}
+
+#[test]
+fn synthetic_newer_schema_needs_best_effort_mode() -> CCDBResult<()> {
+    let path = synthetic_db("schema").connection_path().to_string();
+    let connection = rusqlite::Connection::open(&path)?;
+    connection.execute_batch(
+        "UPDATE schemaVersions SET schemaVersion = 6;
+         ALTER TABLE assignments ADD COLUMN signature TEXT;",
+    )?;
+    drop(connection);
+    assert!(matches!(
+        CCDB::open_with_schema_mode(&path, SchemaMode::Strict),
+        Err(CCDBError::NewerSchemaError(err)) if err.found == 6 && err.supported == 5
+    ));
+    let db = CCDB::open_with_schema_mode(&path, SchemaMode::BestEffort)?;
+    assert_eq!(db.schema_version(), Some(6));
+    assert_eq!(
+        db.fetch(TABLE_PATH, &Context::default().with_runs([1]))?
+            .len(),
+        1
+    );
+    assert_eq!(synthetic_db("schema-current").schema_version(), Some(5));
+    Ok(())
+}
+
+#[cfg(feature = "arrow")]
+#[test]
+fn synthetic_tables_convert_to_arrow() -> Result<(), Box<dyn std::error::Error>> {
+    use gluex_ccdb::arrow::{
+        arrow_array::{cast::AsArray, types::Float64Type, types::Int64Type},
+        arrow_schema::DataType,
+        runs_to_arrow, RUN_NUMBER_COLUMN,
+    };
+
+    let db = synthetic_db("arrow");
+    let data = db.fetch(TABLE_PATH, &Context::default().with_runs([10, 55]))?;
+    let batch = data[&10].to_arrow()?;
+    assert_eq!(batch.num_rows(), 2);
+    assert_eq!(batch.schema().field(0).name(), "x");
+    assert_eq!(batch.schema().field(1).data_type(), &DataType::Utf8);
+    assert_eq!(batch.column(1).as_string::<i32>().value(1), "b|c");
+
+    let batch = runs_to_arrow(&data)?;
+    assert_eq!(batch.schema().field(0).name(), RUN_NUMBER_COLUMN);
+    assert_eq!(
+        batch
+            .column(0)
+            .as_primitive::<Int64Type>()
+            .values()
+            .to_vec(),
+        [10, 10, 55, 55]
+    );
+    assert_eq!(
+        batch
+            .column(1)
+            .as_primitive::<Float64Type>()
+            .values()
+            .to_vec(),
+        [1.5, 2.5, 9.0, 8.0]
+    );
+    assert_eq!(
+        runs_to_arrow(&std::collections::BTreeMap::new())?.num_columns(),
+        1
+    );
+    Ok(())
+}
+
+#[cfg(feature = "async")]
+#[tokio::test(flavor = "multi_thread")]
+async fn synthetic_async_fetches_match_blocking_ones() -> CCDBResult<()> {
+    use gluex_ccdb::asynchronous::AsyncCCDB;
+
+    synthetic_db("async");
+    let db = AsyncCCDB::open(temp_path("async")).await?;
+    let ctx = Context::default().with_runs([10, 55]);
+    let x = |data: &std::collections::BTreeMap<_, gluex_ccdb::data::Data>| {
+        data.iter()
+            .map(|(run, data)| {
+                (
+                    *run,
+                    data.named_column("x")
+                        .and_then(gluex_ccdb::data::Column::double),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    let data = db.fetch(TABLE_PATH, &ctx).await?;
+    assert_eq!(x(&data), x(&db.blocking().fetch(TABLE_PATH, &ctx)?));
+    let table = db.table(TABLE_PATH)?;
+    assert_eq!(x(&db.fetch_table(&table, &ctx).await?), x(&data));
+    assert_eq!(db.columns(&table).await?.len(), 2);
+    assert_eq!(
+        x(&db.request(&format!("{TABLE_PATH}:55")).await?),
+        x(&data)[1..]
+    );
+    assert!(matches!(
+        db.variation("missing").await,
+        Err(CCDBError::VariationNotFoundError(_))
+    ));
+
+    let pooled = AsyncCCDB::open_pooled(temp_path("async"), 3).await?;
+    assert_eq!(
+        pooled
+            .blocking()
+            .backend()
+            .sqlite()
+            .map(gluex_ccdb::backend::SqliteBackend::pool_size),
+        Some(3)
+    );
+    let fetches = (0..3).map(|_| {
+        let pooled = pooled.clone();
+        let ctx = ctx.clone();
+        tokio::spawn(async move { pooled.fetch(TABLE_PATH, &ctx).await })
+    });
+    for fetch in fetches.collect::<Vec<_>>() {
+        assert_eq!(x(&fetch.await.expect("fetch task completes")?), x(&data));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn synthetic_rows_read_into_derived_structs() -> CCDBResult<()> {
+    use gluex_ccdb::{data::CCDBDataError, typed::CcdbTable};
+
+    #[derive(CcdbTable, Debug, PartialEq)]
+    struct Row {
+        x: f64,
+        #[ccdb(column = "label")]
+        name: String,
+    }
+
+    #[derive(CcdbTable, Debug)]
+    struct WrongType {
+        #[allow(dead_code)]
+        label: f64,
+    }
+
+    assert_eq!(Row::COLUMNS, ["x", "label"]);
+    let db = synthetic_db("derive");
+    let rows = db.fetch_typed::<Row>(TABLE_PATH, &Context::default().with_runs([10, 55]))?;
+    assert_eq!(
+        rows[&10],
+        [
+            Row {
+                x: 1.5,
+                name: "a".to_string()
+            },
+            Row {
+                x: 2.5,
+                name: "b|c".to_string()
+            }
+        ]
+    );
+    assert_eq!(
+        rows[&55].iter().map(|row| row.x).collect::<Vec<_>>(),
+        [9.0, 8.0]
+    );
+    assert!(matches!(
+        db.table(TABLE_PATH)?
+            .fetch_typed::<WrongType>(&Context::default().with_run(10)),
+        Err(CCDBError::CCDBDataError(
+            CCDBDataError::ColumnTypeMismatch { row: 0, .. }
+        ))
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "bundle")]
+#[test]
+fn synthetic_bundles_open_like_snapshots() -> CCDBResult<()> {
+    use gluex_core::bundle::{write_bundle, BundleManifest, CCDB_FILE};
+
+    std::env::set_var(
+        "GLUEX_CACHE_DIR",
+        std::env::temp_dir().join(format!("gluex-ccdb-synthetic-cache-{}", std::process::id())),
+    );
+    synthetic_db("bundle");
+    let bundle = temp_path("bundle").with_extension("gluex");
+    write_bundle(
+        &bundle,
+        BundleManifest::new(0, 100, vec![TABLE_PATH.to_string()], Vec::new()),
+        &[(CCDB_FILE, &temp_path("bundle"))],
+    )?;
+    let db = CCDB::open(&bundle)?;
+    assert_ne!(db.connection_path(), bundle.to_string_lossy());
+    let data = db.fetch(TABLE_PATH, &Context::default().with_runs([55]))?;
+    assert_eq!(
+        data[&55]
+            .named_column("x")
+            .and_then(gluex_ccdb::data::Column::double),
+        Some(vec![9.0, 8.0])
+    );
+    Ok(())
+}
+
+#[test]
+fn synthetic_deprecated_tables_and_variations_warn_once() -> CCDBResult<()> {
+    synthetic_db("deprecated");
+    let path = temp_path("deprecated");
+    let conn = rusqlite::Connection::open(&path)?;
+    conn.execute(
+        "UPDATE typeTables SET isDeprecated = 1, comment = 'Replaced by /test/counts.'
+         WHERE name = 'mytable'",
+        [],
+    )?;
+    conn.execute(
+        "UPDATE variations SET isDeprecated = 1, comment = 'old MC' WHERE name = 'mc'",
+        [],
+    )?;
+    drop(conn);
+    let warnings = DeprecationWarnings::new();
+    let db = CCDB::open(&path)?.with_deprecation_warnings(Some(warnings.clone()));
+    db.fetch(TABLE_PATH, &Context::default().with_runs([1]))?;
+    db.fetch(TABLE_PATH, &Context::default().with_runs([2]))?;
+    db.fetch("/test/counts", &Context::default().with_runs([1]))?;
+    let reported = warnings.warnings();
+    assert_eq!(reported.len(), 1);
+    assert_eq!(reported[0].item, DeprecatedItem::CcdbTable);
+    assert_eq!(reported[0].name, TABLE_PATH);
+    assert_eq!(reported[0].replacement.as_deref(), Some("/test/counts"));
+
+    db.fetch(
+        TABLE_PATH,
+        &Context::default().with_variation("mc").with_runs([1]),
+    )?;
+    let reported = warnings.warnings();
+    assert_eq!(reported.len(), 2);
+    assert_eq!(reported[1].item, DeprecatedItem::CcdbVariation);
+    assert_eq!(reported[1].name, "mc");
+    assert_eq!(reported[1].replacement, None);
+    assert!(CCDB::open(&path)?
+        .with_deprecation_warnings(None)
+        .deprecation_warnings()
+        .is_none());
+    Ok(())
+}
+
+#[test]
+fn synthetic_prefetch_yields_batches_in_order() {
+    let db = synthetic_db("prefetch");
+    let ctx = Context::default().with_runs(45..=64);
+    let mut requests = PrefetchRequest::windows(TABLE_PATH, &ctx, 8);
+    assert_eq!(requests.len(), 3);
+    requests.insert(1, PrefetchRequest::new("/test/missing", ctx.clone()));
+    requests.push(PrefetchRequest::new(
+        "/test/counts",
+        ctx.clone().with_runs([5]),
+    ));
+    let batches: Vec<_> = db.prefetch(requests, 2).collect();
+    assert_eq!(batches.len(), 5);
+    assert!(matches!(
+        batches[1].data,
+        Err(CCDBError::TableNotFoundError(_))
+    ));
+    let mut runs = Vec::new();
+    for batch in batches
+        .iter()
+        .filter(|batch| batch.request.table == TABLE_PATH)
+    {
+        let data = batch.data.as_ref().expect("window should fetch");
+        assert_eq!(
+            data.keys().copied().collect::<Vec<_>>(),
+            batch.request.context.runs
+        );
+        runs.extend(data.keys().copied());
+    }
+    assert_eq!(runs, (45..=64).collect::<Vec<_>>());
+    assert_eq!(batches[4].data.as_ref().unwrap()[&5].n_rows(), 1);
+    let mut prefetcher = db.prefetch(PrefetchRequest::windows(TABLE_PATH, &ctx, 1), 1);
+    assert!(prefetcher.next().is_some());
+    drop(prefetcher);
+}
+
+#[test]
+fn synthetic_fetch_iter_streams_runs_in_chunks() -> CCDBResult<()> {
+    let db = synthetic_db("fetch-iter");
+    let ctx = Context::default().with_runs((95..=110).rev().chain(52..=53));
+    let expected = db.fetch(TABLE_PATH, &ctx)?;
+    let mut iter = db.fetch_iter(TABLE_PATH, &ctx)?.with_chunk_size(4);
+    assert_eq!(iter.remaining_runs(), 18);
+    let (run, data) = iter.next().expect("first run")?;
+    assert_eq!(run, 52);
+    assert_eq!(
+        data.named_column("x")
+            .and_then(gluex_ccdb::data::Column::double),
+        Some(vec![9.0, 8.0])
+    );
+    assert_eq!(iter.remaining_runs(), 14);
+    let mut runs = vec![run];
+    for entry in iter {
+        runs.push(entry?.0);
+    }
+    assert_eq!(runs, expected.keys().copied().collect::<Vec<_>>());
+    assert_eq!(runs.last(), Some(&100));
+
+    let mut failing = db
+        .table(TABLE_PATH)?
+        .fetch_iter(&ctx.clone().with_variation("missing"));
+    assert!(matches!(
+        failing.next(),
+        Some(Err(CCDBError::VariationNotFoundError(_)))
+    ));
+    assert!(failing.next().is_none());
+    Ok(())
+}
+
+#[test]
+fn synthetic_run_periods_are_compared() -> CCDBResult<()> {
+    let path = temp_path("compare");
+    let _ = std::fs::remove_file(&path);
+    CcdbBuilder::new()
+        .table("/test/gain", 1, &[("gain", "double"), ("label", "string")])
+        .assign(Assignment::new("/test/gain", 30000..=30100, [["2.0", "a"]]))
+        .assign(Assignment::new("/test/gain", 40000..=40009, [["2.0", "a"]]))
+        .assign(Assignment::new("/test/gain", 40010..=40100, [["2.5", "b"]]))
+        .write(&path)
+        .expect("failed to write synthetic CCDB");
+    let db = CCDB::open(&path)?;
+    let comparisons = db.compare_run_periods(
+        &["/test/gain"],
+        &[
+            RunPeriod::RP2017_01,
+            RunPeriod::RP2018_01,
+            RunPeriod::RP2018_08,
+        ],
+        &Context::default(),
+    )?;
+    let comparison = &comparisons[0];
+    assert_eq!(comparison.column_names, ["gain", "label"]);
+    let s18 = &comparison.periods[1];
+    assert_eq!(s18.change_points(), [40010]);
+    let representative = s18.representative().expect("S18 has constants");
+    assert_eq!(representative.first_run, 40010);
+    assert_eq!(representative.n_runs, 91);
+    assert!(comparison.periods[2].representative().is_none());
+    assert_eq!(comparison.differences.len(), 1);
+    let difference = &comparison.differences[0];
+    assert_eq!(difference.run_period, RunPeriod::RP2018_01);
+    assert_eq!(difference.cells.len(), 2);
+    assert_eq!(difference.cells[0].percent, Some(25.0));
+    assert_eq!(difference.cells[1].percent, None);
+    assert_eq!(difference.max_percent(), Some(25.0));
+    assert!(comparison.to_string().contains("changes at runs 40010"));
+    Ok(())
+}
+
+#[test]
+fn synthetic_diff_reports_changed_cells_per_run() -> CCDBResult<()> {
+    use gluex_ccdb::comparison::RunChange;
+
+    let db = synthetic_db("diff");
+    let default = Context::default().with_runs([5, 55]);
+    let diff = db.diff(TABLE_PATH, &default, &default.clone().with_variation("mc"))?;
+    assert_eq!(diff.table, TABLE_PATH);
+    assert_eq!(diff.column_names, ["x", "label"]);
+    assert_eq!(diff.runs.len(), 2);
+    let RunChange::Cells(cells) = &diff.runs[1].change else {
+        panic!("run 55 should differ cell by cell");
+    };
+    assert_eq!(diff.runs[1].run, 55);
+    assert_eq!(cells.len(), 4);
+    assert_eq!(
+        (cells[0].reference.as_str(), cells[0].value.as_str()),
+        ("9", "7")
+    );
+    assert!(cells[0]
+        .percent
+        .is_some_and(|percent| (percent + 22.222).abs() < 1e-3));
+    assert_eq!(cells[1].percent, None);
+    assert!(diff.to_string().contains("[1][label] y -> mc"));
+
+    assert!(db.diff(TABLE_PATH, &default, &default)?.is_empty());
+    let diff = db.diff(
+        "/test/counts",
+        &Context::default().with_runs([5]),
+        &Context::default().with_runs([5, 6]),
+    )?;
+    assert_eq!(diff.runs.len(), 1);
+    assert_eq!(diff.runs[0].run, 6);
+    assert_eq!(diff.runs[0].change, RunChange::Added);
+    assert!(matches!(
+        db.diff(
+            TABLE_PATH,
+            &default,
+            &default.clone().with_variation("nope")
+        ),
+        Err(CCDBError::VariationNotFoundError(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn synthetic_open_from_bytes() -> CCDBResult<()> {
+    let path = temp_path("bytes");
+    drop(synthetic_db("bytes"));
+    let bytes = std::fs::read(&path).expect("failed to read synthetic CCDB");
+    let db = CCDB::open_from_bytes(&bytes)?;
+    assert_eq!(db.connection_path(), ":memory:");
+    let data = db.fetch(TABLE_PATH, &Context::default().with_runs([55]))?;
+    assert_eq!(data[&55].named_double("x", 0), Some(9.0));
+    assert!(CCDB::open_from_bytes(b"not a database").is_err());
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn synthetic_metadata_round_trips_through_serde() -> CCDBResult<()> {
+    use gluex_ccdb::models::{ColumnMeta, TypeTableMeta, VariationMeta};
+
+    let db = synthetic_db("serde");
+    let table = db.table(TABLE_PATH)?;
+    let json = serde_json::to_value(table.meta()).unwrap();
+    assert_eq!(json["name"], "mytable");
+    assert_eq!(json["n_rows"], 2);
+    let meta: TypeTableMeta = serde_json::from_value(json).unwrap();
+    assert_eq!(meta.comment(), "synthetic table");
+
+    let columns = serde_json::to_string(&table.columns()?).unwrap();
+    assert!(columns.contains(r#""column_type":"string""#));
+    let columns: Vec<ColumnMeta> = serde_json::from_str(&columns).unwrap();
+    assert_eq!(columns[1].name(), "label");
+
+    let variation = serde_json::to_string(&db.variation("mc")?).unwrap();
+    let variation: VariationMeta = serde_json::from_str(&variation).unwrap();
+    assert_eq!(variation.name(), "mc");
+    Ok(())
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn synthetic_fetch_par_matches_fetch_many() -> CCDBResult<()> {
+    let ctx = Context::default().with_runs([5, 55]).with_variation("mc");
+    let db = synthetic_db("fetch_par");
+    let tables = db.fetch_par([TABLE_PATH, "/test/counts", TABLE_PATH], &ctx)?;
+    let batched = db.fetch_many([TABLE_PATH, "/test/counts"], &ctx)?;
+    assert_eq!(
+        tables.keys().collect::<Vec<_>>(),
+        batched.keys().collect::<Vec<_>>()
+    );
+    for (path, data) in &tables {
+        assert_eq!(
+            data.keys().collect::<Vec<_>>(),
+            batched[path].keys().collect::<Vec<_>>()
+        );
+        for (run, data) in data {
+            assert_eq!(data.double(0, 0), batched[path][run].double(0, 0));
+            assert_eq!(data.int(0, 0), batched[path][run].int(0, 0));
+        }
+    }
+    assert_eq!(tables[TABLE_PATH][&55].double(0, 0), Some(7.0));
+    assert!(matches!(
+        db.fetch_par([TABLE_PATH, "/test/missing"], &ctx),
+        Err(CCDBError::TableNotFoundError(_))
+    ));
+    let bytes = std::fs::read(temp_path("fetch_par")).expect("synthetic CCDB is readable");
+    let in_memory = CCDB::open_from_bytes(&bytes)?;
+    assert_eq!(
+        in_memory.fetch_par([TABLE_PATH, "/test/counts"], &ctx)?["/test/counts"][&5].int(0, 0),
+        Some(42)
+    );
+    Ok(())
+}
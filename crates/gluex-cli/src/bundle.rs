@@ -0,0 +1,144 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use clap::{Args, Subcommand};
+use gluex_ccdb::database::CCDB;
+use gluex_core::bundle::{self, BundleManifest, CCDB_FILE, RCDB_FILE};
+use gluex_lumi::cli::ConnectionArgs;
+use gluex_rcdb::database::RCDB;
+
+use crate::snapshot::{ensure_new, RunRangeArgs};
+
+#[derive(Subcommand)]
+pub(crate) enum Command {
+    /// Package trimmed CCDB and RCDB snapshots for a run selection into a single `.gluex` file.
+    Create(CreateArgs),
+    /// Check every database in a bundle against its manifest and print the manifest.
+    Inspect {
+        /// Bundle file
+        bundle: PathBuf,
+    },
+}
+
+#[derive(Args)]
+pub(crate) struct CreateArgs {
+    #[command(flatten)]
+    runs: RunRangeArgs,
+
+    /// CCDB table or directory to include (repeatable; without any, the bundle holds no CCDB)
+    #[arg(long = "table", value_name = "PATH")]
+    tables: Vec<String>,
+
+    /// RCDB condition to include (repeatable; defaults to every condition)
+    #[arg(long = "condition", value_name = "NAME")]
+    conditions: Vec<String>,
+
+    /// Output path for the bundle (must end in `.gluex`)
+    #[arg(short, long)]
+    output: PathBuf,
+}
+
+/// Temporary file next to `output` holding one database until it is bundled.
+fn part_path(output: &Path, file: &str) -> PathBuf {
+    let mut name = output.as_os_str().to_owned();
+    name.push(format!(".{file}.part"));
+    PathBuf::from(name)
+}
+
+fn create(
+    args: &CreateArgs,
+    connections: &ConnectionArgs,
+) -> Result<BundleManifest, Box<dyn std::error::Error>> {
+    if !bundle::is_bundle(&args.output) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "{} must have the .{} extension to be opened as a bundle",
+                args.output.display(),
+                bundle::BUNDLE_EXTENSION
+            ),
+        )
+        .into());
+    }
+    ensure_new(&args.output)?;
+    let runs = args.runs.runs();
+    let ccdb_part = part_path(&args.output, CCDB_FILE);
+    let rcdb_part = part_path(&args.output, RCDB_FILE);
+    let result = (|| {
+        let mut files = Vec::new();
+        if !args.tables.is_empty() {
+            let _ = fs::remove_file(&ccdb_part);
+            CCDB::open(connections.ccdb_path()?)?.extract_snapshot(
+                &ccdb_part,
+                &args.tables,
+                runs.clone(),
+            )?;
+            files.push((CCDB_FILE, ccdb_part.as_path()));
+        }
+        let _ = fs::remove_file(&rcdb_part);
+        RCDB::open(connections.rcdb_path()?)?.extract_snapshot(
+            &rcdb_part,
+            &args.conditions,
+            runs.clone(),
+        )?;
+        files.push((RCDB_FILE, rcdb_part.as_path()));
+        let manifest = BundleManifest::new(
+            *runs.start(),
+            *runs.end(),
+            args.tables.clone(),
+            args.conditions.clone(),
+        );
+        Ok(bundle::write_bundle(&args.output, manifest, &files)?)
+    })();
+    let _ = fs::remove_file(&ccdb_part);
+    let _ = fs::remove_file(&rcdb_part);
+    if result.is_err() {
+        let _ = fs::remove_file(&args.output);
+    }
+    result
+}
+
+fn print_manifest(manifest: &BundleManifest) {
+    println!("created\t{}", manifest.created.to_rfc3339());
+    println!("runs\t{}-{}", manifest.run_min, manifest.run_max);
+    println!("tables\t{}", manifest.tables.join(","));
+    if manifest.conditions.is_empty() {
+        println!("conditions\t(all)");
+    } else {
+        println!("conditions\t{}", manifest.conditions.join(","));
+    }
+    for (file, sha256) in &manifest.files {
+        println!("{file}\t{sha256}");
+    }
+}
+
+pub(crate) fn run(
+    command: Command,
+    connections: &ConnectionArgs,
+    json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match command {
+        Command::Create(args) => {
+            let manifest = create(&args, connections)?;
+            if json {
+                crate::json::print(&serde_json::json!({
+                    "written": args.output,
+                    "manifest": manifest,
+                }))?;
+            } else {
+                eprintln!("wrote {}", args.output.display());
+            }
+        }
+        Command::Inspect { bundle } => {
+            let manifest = bundle::verify(&bundle)?;
+            if json {
+                crate::json::print(&serde_json::to_value(&manifest)?)?;
+            } else {
+                print_manifest(&manifest);
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,241 @@
+use std::collections::BTreeMap;
+
+use clap::Subcommand;
+use gluex_ccdb::{
+    context::Context,
+    data::{Data, Value},
+    database::CCDB,
+    search::{regex::Regex, SearchHit, SearchTarget},
+};
+use gluex_core::{
+    metrics::Metrics,
+    provenance::Recorder,
+    run_periods::RunPeriod,
+    run_set::{RunContext, RunSet},
+    RunNumber,
+};
+use gluex_lumi::cli::ConnectionArgs;
+
+#[derive(Subcommand)]
+pub(crate) enum Command {
+    /// List the directories and tables inside a directory.
+    Ls {
+        /// Directory path
+        #[arg(default_value = "/")]
+        path: String,
+    },
+    /// Search table names, directory names, and comments (case-insensitive substring).
+    Search {
+        /// Text to look for
+        query: String,
+
+        /// Treat the query as a regular expression
+        #[arg(long)]
+        regex: bool,
+    },
+    /// List the variations defined in the database.
+    Variations,
+    /// Print a table's constants for the selected runs as tab-separated columns.
+    Fetch {
+        /// Table path (e.g. `/PHOTON_BEAM/pair_spectrometer/lumi/trig_live`)
+        table: String,
+
+        /// Run selection combining periods, runs, and ranges (e.g. `S18+F18-[41050..41100]`)
+        #[arg(long, conflicts_with_all = ["run_period", "runs"])]
+        select: Option<RunSet>,
+
+        /// Restrict to the runs of a run period (e.g. f18, s19)
+        #[arg(long, conflicts_with = "runs")]
+        run_period: Option<RunPeriod>,
+
+        /// Comma-separated run numbers (defaults to run 0)
+        #[arg(long, value_delimiter = ',')]
+        runs: Option<Vec<RunNumber>>,
+
+        /// Variation to resolve
+        #[arg(long, default_value = "default")]
+        variation: String,
+
+        /// Only use constants created at or before this timestamp
+        #[arg(long)]
+        timestamp: Option<String>,
+    },
+    /// Compare tables' constants across run periods (values, change points, percent differences).
+    Compare {
+        /// Table paths
+        #[arg(required = true)]
+        tables: Vec<String>,
+
+        /// Run period to include (repeatable; the first one with constants is the reference)
+        #[arg(long = "run-period", required = true)]
+        run_periods: Vec<RunPeriod>,
+
+        /// Variation to resolve
+        #[arg(long, default_value = "default")]
+        variation: String,
+
+        /// Only use constants created at or before this timestamp
+        #[arg(long)]
+        timestamp: Option<String>,
+    },
+    /// Resolve a CCDB request string (<path>:<run>:<variation>:<timestamp>).
+    Request {
+        /// Request string
+        request: String,
+    },
+}
+
+pub(crate) fn format_value(value: Value<'_>) -> String {
+    match value {
+        Value::Int(v) => v.to_string(),
+        Value::UInt(v) => v.to_string(),
+        Value::Long(v) => v.to_string(),
+        Value::ULong(v) => v.to_string(),
+        Value::Double(v) => v.to_string(),
+        Value::Bool(v) => v.to_string(),
+        Value::String(v) => v.to_string(),
+    }
+}
+
+fn print_data(data: &BTreeMap<RunNumber, Data>, json: bool) -> std::io::Result<()> {
+    if json {
+        return crate::json::print(&crate::json::ccdb_data(data));
+    }
+    let Some(first) = data.values().next() else {
+        return Ok(());
+    };
+    println!("run\t{}", first.column_names().join("\t"));
+    for (run, data) in data {
+        for row in 0..data.n_rows() {
+            let cells: Vec<String> = (0..data.n_columns())
+                .map(|column| {
+                    data.value(column, row)
+                        .map(format_value)
+                        .unwrap_or_default()
+                })
+                .collect();
+            println!("{run}\t{}", cells.join("\t"));
+        }
+    }
+    Ok(())
+}
+
+fn print_search(hits: Vec<SearchHit>, json: bool) -> std::io::Result<()> {
+    if json {
+        return crate::json::print(&crate::json::ccdb_search(&hits));
+    }
+    for hit in hits {
+        let path = match hit.target {
+            SearchTarget::Directory => format!("{}/", hit.path),
+            SearchTarget::Table => hit.path,
+        };
+        println!("{path}\t{}", hit.comment);
+    }
+    Ok(())
+}
+
+pub(crate) fn run(
+    command: Command,
+    connections: &ConnectionArgs,
+    recorder: Option<Recorder>,
+    metrics: Option<Metrics>,
+    json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut db = CCDB::open(connections.ccdb_path()?)?
+        .with_deprecation_warnings(crate::deprecation_warnings());
+    if let Some(recorder) = recorder {
+        db = db.with_recorder(recorder);
+    }
+    if let Some(metrics) = metrics {
+        db = db.with_metrics(metrics);
+    }
+    match command {
+        Command::Ls { path } if json => {
+            crate::json::print(&crate::json::ccdb_dir(&db.dir(&path)?))?;
+        }
+        Command::Ls { path } => {
+            let dir = db.dir(&path)?;
+            let mut dirs: Vec<String> = dir
+                .dirs()
+                .iter()
+                .map(|d| format!("{}/", d.meta().name()))
+                .collect();
+            dirs.sort();
+            let mut tables: Vec<String> = dir
+                .tables()
+                .iter()
+                .map(|t| {
+                    format!(
+                        "{} [{}x{}]",
+                        t.name(),
+                        t.meta().n_rows(),
+                        t.meta().n_columns()
+                    )
+                })
+                .collect();
+            tables.sort();
+            for entry in dirs.into_iter().chain(tables) {
+                println!("{entry}");
+            }
+        }
+        Command::Search { query, regex } => {
+            let hits = if regex {
+                db.search_regex(&Regex::new(&query)?)
+            } else {
+                db.search(&query)
+            };
+            print_search(hits, json)?;
+        }
+        Command::Variations if json => {
+            crate::json::print(&crate::json::ccdb_variations(&db.variations()?))?;
+        }
+        Command::Variations => {
+            for variation in db.variations()? {
+                println!("{}\t{}", variation.name(), variation.description());
+            }
+        }
+        Command::Fetch {
+            table,
+            select,
+            run_period,
+            runs,
+            variation,
+            timestamp,
+        } => {
+            let mut context = Context::default().with_variation(&variation);
+            if let Some(runs) = select {
+                context = context.with_run_set(runs);
+            } else if let Some(run_period) = run_period {
+                context = context.with_run_period(run_period, None)?;
+            } else if let Some(runs) = runs {
+                context = context.with_runs(runs);
+            }
+            if let Some(timestamp) = timestamp {
+                context = context.with_timestamp_string(&timestamp)?;
+            }
+            print_data(&db.fetch(&table, &context)?, json)?;
+        }
+        Command::Request { request } => print_data(&db.request(&request)?, json)?,
+        Command::Compare {
+            tables,
+            run_periods,
+            variation,
+            timestamp,
+        } => {
+            let mut context = Context::default().with_variation(&variation);
+            if let Some(timestamp) = timestamp {
+                context = context.with_timestamp_string(&timestamp)?;
+            }
+            let tables: Vec<&str> = tables.iter().map(String::as_str).collect();
+            let comparisons = db.compare_run_periods(&tables, &run_periods, &context)?;
+            if json {
+                crate::json::print(&crate::json::ccdb_comparisons(&comparisons))?;
+            } else {
+                for comparison in &comparisons {
+                    print!("{comparison}");
+                }
+            }
+        }
+    }
+    Ok(())
+}
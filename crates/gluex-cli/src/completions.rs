@@ -0,0 +1,252 @@
+use std::{env, path::PathBuf};
+
+use clap::{Arg, ArgAction, Command, CommandFactory, ValueEnum};
+use gluex_ccdb::database::CCDB;
+use gluex_core::run_periods::RunPeriod;
+use gluex_lumi::cli::ConnectionArgs;
+use gluex_rcdb::database::RCDB;
+use strum::IntoEnumIterator;
+
+/// Shells for which `gluex completions` can print a script.
+#[derive(Clone, Copy, ValueEnum)]
+pub(crate) enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+const BASH: &str = r#"_gluex() {
+    local IFS=$'\n'
+    COMPREPLY=($(gluex __complete -- "${COMP_WORDS[@]:0:$((COMP_CWORD + 1))}" 2>/dev/null))
+    if [[ ${#COMPREPLY[@]} -eq 1 && ${COMPREPLY[0]} == */ ]]; then
+        compopt -o nospace
+    fi
+}
+complete -o default -F _gluex gluex
+"#;
+
+const ZSH: &str = r#"_gluex() {
+    local -a candidates
+    candidates=(${(f)"$(gluex __complete -- "${(@)words[1,CURRENT]}" 2>/dev/null)"})
+    if (( ${#candidates} == 0 )); then
+        _files
+        return
+    fi
+    compadd -S '' -- ${(M)candidates:#*/}
+    compadd -- ${candidates:#*/}
+}
+compdef _gluex gluex
+"#;
+
+const FISH: &str = r"function __gluex_complete
+    gluex __complete -- (commandline -opc) (commandline -ct) 2>/dev/null
+end
+complete -c gluex -f -a '(__gluex_complete)'
+complete -c gluex -n 'not __gluex_complete | string length -q' -F
+";
+
+/// Prints the completion script for `shell`.
+pub(crate) fn print_script(shell: Shell) {
+    print!(
+        "{}",
+        match shell {
+            Shell::Bash => BASH,
+            Shell::Zsh => ZSH,
+            Shell::Fish => FISH,
+        }
+    );
+}
+
+/// Prints the completion candidates for the last of `words` (the command line up to the cursor,
+/// starting with the program name), one per line.
+pub(crate) fn print_candidates(words: &[String]) {
+    for candidate in candidates(words) {
+        println!("{candidate}");
+    }
+}
+
+fn candidates(words: &[String]) -> Vec<String> {
+    let mut root = crate::Cli::command();
+    root.build();
+    let (current, previous) = words
+        .split_last()
+        .map_or(("", &[][..]), |(current, previous)| {
+            (current.as_str(), previous)
+        });
+    let mut command = &root;
+    let mut path: Vec<&str> = Vec::new();
+    let mut positionals = 0;
+    let mut pending: Option<&Arg> = None;
+    let mut connections = ConnectionArgs::default();
+    for word in previous.iter().skip(1) {
+        if let Some(arg) = pending.take() {
+            // bash splits `--flag=value` into `--flag`, `=`, and `value`
+            if word == "=" {
+                pending = Some(arg);
+            } else {
+                set_connection(&mut connections, arg, word);
+            }
+            continue;
+        }
+        let (flag, value) = word.split_once('=').unwrap_or((word, ""));
+        if let Some(arg) = find_option(command, flag) {
+            if word.contains('=') {
+                set_connection(&mut connections, arg, value);
+            } else if arg.get_action().takes_values() {
+                pending = Some(arg);
+            }
+        } else if let Some(subcommand) = command.find_subcommand(word) {
+            command = subcommand;
+            path.push(subcommand.get_name());
+            positionals = 0;
+        } else if !word.starts_with('-') {
+            positionals += 1;
+        }
+    }
+    let mut candidates = if let Some(arg) = pending {
+        values(arg, &path, current, &connections)
+    } else if let Some((flag, value)) = current
+        .split_once('=')
+        .filter(|(flag, _)| flag.starts_with('-'))
+    {
+        find_option(command, flag)
+            .map(|arg| {
+                values(arg, &path, value, &connections)
+                    .into_iter()
+                    .map(|candidate| format!("{flag}={candidate}"))
+                    .collect()
+            })
+            .unwrap_or_default()
+    } else if current.starts_with('-') {
+        command
+            .get_arguments()
+            .filter(|arg| !arg.is_hide_set())
+            .filter_map(|arg| arg.get_long().map(|long| format!("--{long}")))
+            .collect()
+    } else if command.has_subcommands() {
+        command
+            .get_subcommands()
+            .filter(|subcommand| !subcommand.is_hide_set())
+            .map(|subcommand| subcommand.get_name().to_string())
+            .collect()
+    } else {
+        let mut args = command.get_positionals();
+        let last = command
+            .get_positionals()
+            .last()
+            .filter(|arg| matches!(arg.get_action(), ArgAction::Append));
+        args.nth(positionals)
+            .or(last)
+            .map(|arg| values(arg, &path, current, &connections))
+            .unwrap_or_default()
+    };
+    candidates.retain(|candidate| candidate.starts_with(current));
+    candidates
+}
+
+/// The option of `command` named by `flag` (`--long` or `-s`).
+fn find_option<'a>(command: &'a Command, flag: &str) -> Option<&'a Arg> {
+    if let Some(long) = flag.strip_prefix("--") {
+        command
+            .get_arguments()
+            .find(|arg| arg.get_long() == Some(long))
+    } else {
+        let mut short = flag.strip_prefix('-')?.chars();
+        let (Some(short), None) = (short.next(), short.next()) else {
+            return None;
+        };
+        command
+            .get_arguments()
+            .find(|arg| arg.get_short() == Some(short))
+    }
+}
+
+fn set_connection(connections: &mut ConnectionArgs, arg: &Arg, value: &str) {
+    match arg.get_id().as_str() {
+        "rcdb" => connections.rcdb = Some(PathBuf::from(value)),
+        "ccdb" => connections.ccdb = Some(PathBuf::from(value)),
+        _ => {}
+    }
+}
+
+/// Candidate values for `arg` of the subcommand at `path`, querying the databases where the
+/// values are table paths, variations, or condition names.
+fn values(arg: &Arg, path: &[&str], current: &str, connections: &ConnectionArgs) -> Vec<String> {
+    let id = arg.get_id().as_str();
+    match (path, id) {
+        (["ccdb", "ls"], "path") => open_ccdb(connections)
+            .map(|db| ccdb_paths(&db, current, true))
+            .unwrap_or_default(),
+        (["ccdb", "fetch"], "table") | (["ccdb", "request"], "request") | (_, "tables") => {
+            open_ccdb(connections)
+                .map(|db| ccdb_paths(&db, current, false))
+                .unwrap_or_default()
+        }
+        (_, "variation") => open_ccdb(connections)
+            .and_then(|db| db.variations().ok())
+            .map(|variations| variations.iter().map(|v| v.name().to_string()).collect())
+            .unwrap_or_default(),
+        (_, "conditions") => open_rcdb(connections)
+            .map(|db| {
+                db.condition_types()
+                    .iter()
+                    .map(|meta| meta.name().to_string())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        (_, "run_period" | "select") | (["lumi", ..], "runs") => RunPeriod::iter()
+            .map(|period| period.short_name().to_lowercase())
+            .collect(),
+        _ => arg
+            .get_possible_values()
+            .iter()
+            .filter(|value| !value.is_hide_set())
+            .map(|value| value.get_name().to_string())
+            .collect(),
+    }
+}
+
+/// Fills the databases not given on the command line from the environment and data directory.
+fn resolve(connections: &ConnectionArgs) -> ConnectionArgs {
+    let mut resolved = connections.clone();
+    resolved.rcdb = resolved
+        .rcdb
+        .or_else(|| env::var_os("RCDB_CONNECTION").map(PathBuf::from));
+    resolved.ccdb = resolved
+        .ccdb
+        .or_else(|| env::var_os("CCDB_CONNECTION").map(PathBuf::from));
+    crate::data_dir::fill_connections(&mut resolved);
+    resolved
+}
+
+fn open_ccdb(connections: &ConnectionArgs) -> Option<CCDB> {
+    CCDB::open(resolve(connections).ccdb?).ok()
+}
+
+fn open_rcdb(connections: &ConnectionArgs) -> Option<RCDB> {
+    RCDB::open(resolve(connections).rcdb?).ok()
+}
+
+/// Directories (with a trailing `/`) and, unless `dirs_only`, tables inside the directory that
+/// `word` points into, as paths extending `word`.
+pub(crate) fn ccdb_paths(db: &CCDB, word: &str, dirs_only: bool) -> Vec<String> {
+    let (parent, _) = word.rsplit_once('/').unwrap_or(("", word));
+    let Ok(dir) = db.dir(if parent.is_empty() { "/" } else { parent }) else {
+        return Vec::new();
+    };
+    let prefix = if parent.is_empty() && !word.is_empty() && !word.starts_with('/') {
+        String::new()
+    } else {
+        format!("{parent}/")
+    };
+    let mut paths: Vec<String> = dir
+        .dirs()
+        .iter()
+        .map(|d| format!("{prefix}{}/", d.meta().name()))
+        .collect();
+    if !dirs_only {
+        paths.extend(dir.tables().iter().map(|t| format!("{prefix}{}", t.name())));
+    }
+    paths.sort();
+    paths
+}
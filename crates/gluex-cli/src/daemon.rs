@@ -0,0 +1,286 @@
+//! A long-running query daemon, speaking the length-prefixed JSON protocol of
+//! [`gluex_core::daemon`] over a Unix socket. Results have the same shapes as `--json` output.
+
+use std::{
+    error::Error,
+    fs, io,
+    net::SocketAddr,
+    os::unix::net::{UnixListener, UnixStream},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+use clap::{Args, Subcommand};
+use gluex_ccdb::{context::Context as CCDBContext, database::CCDB};
+use gluex_core::{
+    daemon::{read_frame, write_frame, Client},
+    run_set::{RunContext, RunSet},
+};
+use gluex_lumi::cli::ConnectionArgs;
+use gluex_rcdb::{context::Context as RCDBContext, database::RCDB};
+use serde_json::{json, Value as JsonValue};
+
+use crate::{json, prometheus::ServerStats};
+
+#[derive(Args)]
+pub(crate) struct DaemonArgs {
+    /// Socket path (defaults to `daemon.sock` in the data directory)
+    #[arg(long, env = "GLUEX_DAEMON_SOCKET", global = true)]
+    socket: Option<PathBuf>,
+
+    /// Also serve Prometheus metrics at `http://ADDR/metrics`
+    #[arg(long, value_name = "ADDR")]
+    metrics_bind: Option<SocketAddr>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Send one JSON request to a running daemon and print the result.
+    Query {
+        /// Request object (e.g. `{"op": "ccdb/request", "request": "/PHOTON_BEAM/endpoint_energy:30274"}`)
+        request: String,
+    },
+    /// Check that a daemon is answering on the socket.
+    Ping,
+    /// Ask a running daemon to exit.
+    Stop,
+    /// Print the Prometheus metrics of a running daemon.
+    Metrics,
+}
+
+struct Daemon {
+    ccdb: Option<CCDB>,
+    rcdb: Option<RCDB>,
+    socket: PathBuf,
+    stopping: AtomicBool,
+    stats: Arc<ServerStats>,
+}
+
+type HandlerResult = Result<JsonValue, Box<dyn Error>>;
+
+fn invalid(message: impl Into<String>) -> Box<dyn Error> {
+    io::Error::new(io::ErrorKind::InvalidInput, message.into()).into()
+}
+
+fn str_field<'a>(request: &'a JsonValue, name: &str) -> Result<&'a str, Box<dyn Error>> {
+    request
+        .get(name)
+        .and_then(JsonValue::as_str)
+        .ok_or_else(|| invalid(format!("request is missing the string field \"{name}\"")))
+}
+
+fn opt_str_field<'a>(request: &'a JsonValue, name: &str) -> Option<&'a str> {
+    request.get(name).and_then(JsonValue::as_str)
+}
+
+fn run_set(request: &JsonValue) -> Result<Option<RunSet>, Box<dyn Error>> {
+    Ok(opt_str_field(request, "select")
+        .map(str::parse::<RunSet>)
+        .transpose()?)
+}
+
+impl Daemon {
+    fn ccdb(&self) -> Result<&CCDB, Box<dyn Error>> {
+        self.ccdb
+            .as_ref()
+            .ok_or_else(|| invalid("CCDB is not configured on this daemon"))
+    }
+
+    fn rcdb(&self) -> Result<&RCDB, Box<dyn Error>> {
+        self.rcdb
+            .as_ref()
+            .ok_or_else(|| invalid("RCDB is not configured on this daemon"))
+    }
+
+    fn handle(&self, request: &JsonValue) -> HandlerResult {
+        match str_field(request, "op")? {
+            "ping" => Ok(json!("pong")),
+            "metrics" => Ok(json!(self.stats.render())),
+            "stop" => {
+                self.stopping.store(true, Ordering::SeqCst);
+                Ok(JsonValue::Null)
+            }
+            "rcdb/conditions" => Ok(json::condition_types(self.rcdb()?)),
+            "rcdb/runs" => {
+                let context = run_set(request)?.map(RCDBContext::from).unwrap_or_default();
+                Ok(json!(self.rcdb()?.fetch_runs(&context)?))
+            }
+            "rcdb/fetch" => {
+                let names: Vec<&str> = request
+                    .get("conditions")
+                    .and_then(JsonValue::as_array)
+                    .ok_or_else(|| invalid("rcdb/fetch needs a \"conditions\" list"))?
+                    .iter()
+                    .filter_map(JsonValue::as_str)
+                    .collect();
+                let context = run_set(request)?.map(RCDBContext::from).unwrap_or_default();
+                Ok(json::rcdb_rows(&self.rcdb()?.fetch(&names, &context)?))
+            }
+            "ccdb/ls" => {
+                let path = opt_str_field(request, "path").unwrap_or("/");
+                Ok(json::ccdb_dir(&self.ccdb()?.dir(path)?))
+            }
+            "ccdb/variations" => Ok(json::ccdb_variations(&self.ccdb()?.variations()?)),
+            "ccdb/fetch" => {
+                let table = str_field(request, "table")?;
+                let mut context = CCDBContext::default();
+                if let Some(runs) = run_set(request)? {
+                    context = context.with_run_set(runs);
+                }
+                if let Some(variation) = opt_str_field(request, "variation") {
+                    context = context.with_variation(variation);
+                }
+                if let Some(timestamp) = opt_str_field(request, "timestamp") {
+                    context = context.with_timestamp_string(timestamp)?;
+                }
+                Ok(json::ccdb_data(&self.ccdb()?.fetch(table, &context)?))
+            }
+            "ccdb/request" => Ok(json::ccdb_data(
+                &self.ccdb()?.request(str_field(request, "request")?)?,
+            )),
+            op => Err(invalid(format!("unknown op \"{op}\""))),
+        }
+    }
+
+    fn serve_connection(&self, mut stream: UnixStream) -> io::Result<()> {
+        let _open = self.stats.open_connection();
+        while let Some(body) = read_frame(&mut stream)? {
+            let _in_flight = self.stats.start_request();
+            let response = match serde_json::from_slice::<JsonValue>(&body) {
+                Ok(request) => match self.handle(&request) {
+                    Ok(result) => json!({ "result": result }),
+                    Err(err) => json!({ "error": err.to_string() }),
+                },
+                Err(err) => json!({ "error": format!("invalid request: {err}") }),
+            };
+            if response.get("error").is_some() {
+                self.stats.request_failed();
+            }
+            write_frame(&mut stream, &serde_json::to_vec(&response)?)?;
+            if self.stopping.load(Ordering::SeqCst) {
+                // wake the accept loop so it sees the flag
+                let _ = UnixStream::connect(&self.socket);
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn socket_path(args: &DaemonArgs) -> io::Result<PathBuf> {
+    args.socket
+        .clone()
+        .or_else(|| crate::data_dir::data_dir().map(|dir| dir.join("daemon.sock")))
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "no socket path, pass --socket or set GLUEX_DAEMON_SOCKET",
+            )
+        })
+}
+
+/// Binds `socket`, replacing a stale socket file left behind by a daemon that is no longer
+/// running.
+fn bind(socket: &Path) -> io::Result<UnixListener> {
+    if socket.exists() {
+        if UnixStream::connect(socket).is_ok() {
+            return Err(io::Error::new(
+                io::ErrorKind::AddrInUse,
+                format!("a daemon is already listening on {}", socket.display()),
+            ));
+        }
+        fs::remove_file(socket)?;
+    }
+    if let Some(dir) = socket.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    UnixListener::bind(socket)
+}
+
+fn serve(
+    socket: PathBuf,
+    metrics_bind: Option<SocketAddr>,
+    connections: &ConnectionArgs,
+) -> Result<(), Box<dyn Error>> {
+    let databases = [("ccdb", &connections.ccdb), ("rcdb", &connections.rcdb)]
+        .into_iter()
+        .filter_map(|(name, path)| Some((name, path.as_ref()?.display().to_string())))
+        .collect();
+    let server_stats = ServerStats::new(databases);
+    let daemon = Arc::new(Daemon {
+        ccdb: connections
+            .ccdb
+            .as_ref()
+            .map(|path| CCDB::open(path).map(|db| db.with_metrics(server_stats.metrics())))
+            .transpose()?,
+        rcdb: connections
+            .rcdb
+            .as_ref()
+            .map(|path| RCDB::open(path).map(|db| db.with_metrics(server_stats.metrics())))
+            .transpose()?,
+        socket,
+        stopping: AtomicBool::new(false),
+        stats: server_stats,
+    });
+    if daemon.ccdb.is_none() && daemon.rcdb.is_none() {
+        return Err(invalid(
+            "--ccdb or --rcdb is required (or set CCDB_CONNECTION/RCDB_CONNECTION)",
+        ));
+    }
+    let listener = bind(&daemon.socket)?;
+    eprintln!("listening on {}", daemon.socket.display());
+    if let Some(addr) = metrics_bind {
+        let addr = crate::prometheus::serve_http(addr, Arc::clone(&daemon.stats))?;
+        eprintln!("serving metrics on http://{addr}/metrics");
+    }
+    for stream in listener.incoming() {
+        if daemon.stopping.load(Ordering::SeqCst) {
+            break;
+        }
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(err) => {
+                eprintln!("accept failed: {err}");
+                continue;
+            }
+        };
+        let daemon = Arc::clone(&daemon);
+        thread::spawn(move || {
+            if let Err(err) = daemon.serve_connection(stream) {
+                eprintln!("connection error: {err}");
+            }
+        });
+    }
+    fs::remove_file(&daemon.socket)?;
+    Ok(())
+}
+
+pub(crate) fn run(args: &DaemonArgs, connections: &ConnectionArgs) -> Result<(), Box<dyn Error>> {
+    let socket = socket_path(args)?;
+    let request = match &args.command {
+        None => return serve(socket, args.metrics_bind, connections),
+        Some(Command::Query { request }) => serde_json::from_str(request)?,
+        Some(Command::Ping) => json!({ "op": "ping" }),
+        Some(Command::Stop) => json!({ "op": "stop" }),
+        Some(Command::Metrics) => json!({ "op": "metrics" }),
+    };
+    let result = Client::connect(&socket)
+        .map_err(|err| format!("cannot reach a daemon on {}: {err}", socket.display()))?
+        .request(&request)
+        .map_err(|err| err.to_string())?;
+    match result {
+        JsonValue::Null => {}
+        JsonValue::String(text) if matches!(args.command, Some(Command::Metrics)) => {
+            print!("{text}");
+        }
+        result => json::print(&result)?,
+    }
+    Ok(())
+}
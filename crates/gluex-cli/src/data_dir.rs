@@ -0,0 +1,35 @@
+use std::{env, path::PathBuf};
+
+use gluex_lumi::cli::ConnectionArgs;
+
+/// File name used for the CCDB snapshot inside the data directory.
+pub(crate) const CCDB_FILE: &str = "ccdb.sqlite";
+/// File name used for the RCDB snapshot inside the data directory.
+pub(crate) const RCDB_FILE: &str = "rcdb.sqlite";
+
+/// Directory holding downloaded database snapshots: `$GLUEX_DATA_DIR`, then
+/// `$XDG_DATA_HOME/gluex`, then `~/.local/share/gluex`.
+pub(crate) fn data_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("GLUEX_DATA_DIR").filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(dir));
+    }
+    if let Some(dir) = env::var_os("XDG_DATA_HOME").filter(|dir| !dir.is_empty()) {
+        return Some(PathBuf::from(dir).join("gluex"));
+    }
+    env::var_os("HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(|home| PathBuf::from(home).join(".local/share/gluex"))
+}
+
+/// Points unset connections at snapshots previously stored in the data directory.
+pub(crate) fn fill_connections(connections: &mut ConnectionArgs) {
+    let Some(dir) = data_dir() else {
+        return;
+    };
+    if connections.ccdb.is_none() {
+        connections.ccdb = Some(dir.join(CCDB_FILE)).filter(|path| path.is_file());
+    }
+    if connections.rcdb.is_none() {
+        connections.rcdb = Some(dir.join(RCDB_FILE)).filter(|path| path.is_file());
+    }
+}
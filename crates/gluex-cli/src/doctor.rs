@@ -0,0 +1,508 @@
+//! Diagnoses the database configuration: where the snapshots come from, whether they open, their
+//! schema versions, which runs they cover, and how old they are.
+
+use std::{
+    env,
+    fmt::Write as _,
+    fs::{self, File},
+    io::{self, Read},
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use gluex_ccdb::database::CCDB;
+use gluex_core::{
+    compressed::Compression,
+    parsers::parse_timestamp,
+    run_periods::RunPeriod,
+    schema::{NewerSchemaError, SCHEMA_MODE_ENV},
+    RunNumber,
+};
+use gluex_lumi::cli::ConnectionArgs;
+use gluex_rcdb::{context::Context, database::RCDB};
+use serde_json::{json, Value as JsonValue};
+use strum::IntoEnumIterator;
+
+use crate::{
+    data_dir::{data_dir, CCDB_FILE, RCDB_FILE},
+    snapshot::Database,
+};
+
+/// Snapshots older than this many days are reported as stale.
+const STALE_DAYS: i64 = 30;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Status {
+    Ok,
+    Warn,
+    Fail,
+}
+
+impl Status {
+    fn as_str(self) -> &'static str {
+        match self {
+            Status::Ok => "ok",
+            Status::Warn => "warn",
+            Status::Fail => "fail",
+        }
+    }
+}
+
+/// The outcome of one check, with a suggested fix for anything that is not ok.
+struct Check {
+    database: &'static str,
+    name: &'static str,
+    status: Status,
+    message: String,
+    fix: Option<String>,
+}
+
+struct Report {
+    checks: Vec<Check>,
+}
+
+impl Report {
+    fn push(
+        &mut self,
+        database: Database,
+        name: &'static str,
+        status: Status,
+        message: impl Into<String>,
+        fix: Option<String>,
+    ) {
+        self.checks.push(Check {
+            database: db_name(database),
+            name,
+            status,
+            message: message.into(),
+            fix,
+        });
+    }
+
+    fn ok(&mut self, database: Database, name: &'static str, message: impl Into<String>) {
+        self.push(database, name, Status::Ok, message, None);
+    }
+
+    fn worst(&self) -> Status {
+        self.checks
+            .iter()
+            .map(|check| check.status)
+            .max()
+            .unwrap_or(Status::Ok)
+    }
+}
+
+fn db_name(database: Database) -> &'static str {
+    match database {
+        Database::Ccdb => "ccdb",
+        Database::Rcdb => "rcdb",
+    }
+}
+
+fn env_var(database: Database) -> &'static str {
+    match database {
+        Database::Ccdb => "CCDB_CONNECTION",
+        Database::Rcdb => "RCDB_CONNECTION",
+    }
+}
+
+fn days_ago(timestamp: &str) -> Option<i64> {
+    let then = parse_timestamp(timestamp).ok()?.timestamp();
+    let now = i64::try_from(SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs()).ok()?;
+    Some((now - then) / 86_400)
+}
+
+fn size(bytes: u64) -> String {
+    if bytes >= 1 << 20 {
+        format!("{} MiB", bytes >> 20)
+    } else {
+        format!("{} KiB", bytes.div_ceil(1 << 10))
+    }
+}
+
+/// Describes where `path` was configured.
+fn source(database: Database, path: &Path) -> String {
+    let file_name = match database {
+        Database::Ccdb => CCDB_FILE,
+        Database::Rcdb => RCDB_FILE,
+    };
+    if env::var_os(env_var(database)).is_some_and(|value| Path::new(&value) == path) {
+        format!("from {}", env_var(database))
+    } else if data_dir().is_some_and(|dir| dir.join(file_name) == path) {
+        "from the data directory".to_string()
+    } else {
+        format!("from --{}", db_name(database))
+    }
+}
+
+/// Checks that `path` names a readable `SQLite` file, returning false if later checks cannot run.
+fn check_file(report: &mut Report, database: Database, path: &Path) -> bool {
+    let name = db_name(database);
+    let text = path.to_string_lossy();
+    let fix_prefix = format!(
+        "set {}=/path/to/{name}.sqlite or run `gluex fetch-db {name}`",
+        env_var(database)
+    );
+    if let Some(rest) = text.strip_prefix("sqlite://") {
+        report.push(
+            database,
+            "path",
+            Status::Fail,
+            format!("{text} is a connection string, not a file path"),
+            Some(format!(
+                "drop the scheme: {}={}",
+                env_var(database),
+                rest.strip_prefix('/').unwrap_or(rest)
+            )),
+        );
+        return false;
+    }
+    if text.contains("://") {
+        report.push(
+            database,
+            "path",
+            Status::Fail,
+            format!("{text} is a server URL; the gluex tools read local SQLite snapshots"),
+            Some(format!(
+                "{fix_prefix} (`gluex sync {name}` keeps a snapshot current from the server)"
+            )),
+        );
+        return false;
+    }
+    let meta = match fs::metadata(path) {
+        Ok(meta) => meta,
+        Err(err) => {
+            report.push(
+                database,
+                "path",
+                Status::Fail,
+                format!("{}: {err}", path.display()),
+                Some(fix_prefix),
+            );
+            return false;
+        }
+    };
+    if !meta.is_file() {
+        report.push(
+            database,
+            "path",
+            Status::Fail,
+            format!("{} is not a file", path.display()),
+            Some(fix_prefix),
+        );
+        return false;
+    }
+    let mut header = [0u8; 16];
+    let readable = File::open(path).and_then(|mut file| file.read_exact(&mut header));
+    if let Err(err) = readable {
+        report.push(
+            database,
+            "path",
+            Status::Fail,
+            format!("cannot read {}: {err}", path.display()),
+            Some(format!("check the permissions of {}", path.display())),
+        );
+        return false;
+    }
+    if &header != b"SQLite format 3\0" && Compression::from_path(path).is_none() {
+        report.push(
+            database,
+            "path",
+            Status::Fail,
+            format!("{} is not an SQLite database", path.display()),
+            Some(fix_prefix),
+        );
+        return false;
+    }
+    report.ok(
+        database,
+        "path",
+        format!(
+            "{} ({}, {})",
+            path.display(),
+            source(database, path),
+            size(meta.len())
+        ),
+    );
+    true
+}
+
+/// Reports the age of a snapshot from the `fetch-db` sidecar, falling back to the newest data.
+fn check_age(report: &mut Report, database: Database, path: &Path, newest: Option<String>) {
+    let name = db_name(database);
+    let mut sidecar = path.as_os_str().to_os_string();
+    sidecar.push(".json");
+    let info: Option<JsonValue> = fs::read_to_string(sidecar)
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok());
+    let (what, date) = match info.as_ref().and_then(|info| {
+        info["snapshot_date"]
+            .as_str()
+            .or(info["downloaded"].as_str())
+    }) {
+        Some(date) => ("snapshot taken", Some(date.to_string())),
+        None => ("newest entry", newest),
+    };
+    let Some(date) = date else {
+        report.push(
+            database,
+            "age",
+            Status::Warn,
+            "cannot tell when the snapshot was taken",
+            None,
+        );
+        return;
+    };
+    match days_ago(&date) {
+        Some(days) if days > STALE_DAYS => report.push(
+            database,
+            "age",
+            Status::Warn,
+            format!("{what} {date} ({days} days ago)"),
+            Some(format!(
+                "run `gluex sync {name}` or `gluex fetch-db {name} --force`"
+            )),
+        ),
+        Some(days) => report.ok(database, "age", format!("{what} {date} ({days} days ago)")),
+        None => report.ok(database, "age", format!("{what} {date}")),
+    }
+}
+
+fn check_rcdb(report: &mut Report, path: &Path) {
+    let database = Database::Rcdb;
+    let db = match RCDB::open(path) {
+        Ok(db) => db,
+        Err(gluex_rcdb::RCDBError::NewerSchemaError(err)) => {
+            newer_schema(report, database, &err);
+            return;
+        }
+        Err(err) => {
+            report.push(
+                database,
+                "schema",
+                Status::Fail,
+                err.to_string(),
+                Some(
+                    "this is not an RCDB v2 snapshot; download one with `gluex fetch-db rcdb`"
+                        .to_string(),
+                ),
+            );
+            return;
+        }
+    };
+    schema_version(
+        report,
+        database,
+        db.schema_version(),
+        gluex_rcdb::database::SUPPORTED_SCHEMA_VERSION,
+    );
+    let runs: Vec<RunNumber> = match db.fetch_runs(&Context::default()) {
+        Ok(runs) => runs,
+        Err(err) => {
+            report.push(database, "runs", Status::Fail, err.to_string(), None);
+            return;
+        }
+    };
+    match (runs.first(), runs.last()) {
+        (Some(first), Some(last)) => {
+            let periods: Vec<String> = RunPeriod::iter()
+                .filter(|period| {
+                    let range = period.run_range();
+                    runs.iter().any(|run| range.contains(run))
+                })
+                .map(|period| period.short_name().to_string())
+                .collect();
+            let mut message = format!("{} runs from {first} to {last}", runs.len());
+            if !periods.is_empty() {
+                let _ = write!(message, " (run periods {})", periods.join(", "));
+            }
+            report.ok(database, "runs", message);
+        }
+        _ => report.push(
+            database,
+            "runs",
+            Status::Warn,
+            "the snapshot holds no runs",
+            Some("download a full snapshot with `gluex fetch-db rcdb --force`".to_string()),
+        ),
+    }
+    let mut newest = None;
+    let _ = db
+        .backend()
+        .query("SELECT MAX(started) FROM runs", &[], None, &mut |row| {
+            newest = row.get::<Option<String>>(0)?;
+            Ok(())
+        });
+    check_age(report, database, path, newest);
+}
+
+fn newer_schema(report: &mut Report, database: Database, err: &NewerSchemaError) {
+    report.push(
+        database,
+        "schema",
+        Status::Fail,
+        format!(
+            "version {} is newer than the supported version {}",
+            err.found, err.supported
+        ),
+        Some(format!(
+            "upgrade gluex, or set {SCHEMA_MODE_ENV}=best-effort to read it anyway"
+        )),
+    );
+}
+
+fn schema_version(report: &mut Report, database: Database, version: i64, supported: i64) {
+    if version > supported {
+        report.push(
+            database,
+            "schema",
+            Status::Warn,
+            format!("version {version} is newer than the supported version {supported}"),
+            Some("opened in best-effort mode; upgrade gluex to read it fully".to_string()),
+        );
+    } else {
+        report.ok(database, "schema", format!("version {version}"));
+    }
+}
+
+fn check_ccdb(report: &mut Report, path: &Path) {
+    let database = Database::Ccdb;
+    let db = match CCDB::open(path) {
+        Ok(db) => db,
+        Err(gluex_ccdb::CCDBError::NewerSchemaError(err)) => {
+            newer_schema(report, database, &err);
+            return;
+        }
+        Err(err) => {
+            report.push(
+                database,
+                "schema",
+                Status::Fail,
+                err.to_string(),
+                Some(
+                    "this is not a CCDB snapshot; download one with `gluex fetch-db ccdb`"
+                        .to_string(),
+                ),
+            );
+            return;
+        }
+    };
+    match db.schema_version() {
+        Some(version) => schema_version(
+            report,
+            database,
+            version,
+            gluex_ccdb::database::SUPPORTED_SCHEMA_VERSION,
+        ),
+        None => report.push(
+            database,
+            "schema",
+            Status::Warn,
+            "no schema version recorded",
+            Some("the file may be a partial copy; download one with `gluex fetch-db ccdb`".into()),
+        ),
+    }
+    let mut coverage = (0, None, None);
+    let result = db.backend().query(
+        "SELECT COUNT(*), MAX(a.created), MAX(CASE WHEN rr.runMax < 2147483647 THEN rr.runMax END)
+         FROM assignments a JOIN runRanges rr ON rr.id = a.runRangeId",
+        &[],
+        None,
+        &mut |row| {
+            coverage = (
+                row.get::<i64>(0)?,
+                row.get::<Option<String>>(1)?,
+                row.get::<Option<RunNumber>>(2)?,
+            );
+            Ok(())
+        },
+    );
+    match result.map(|()| coverage) {
+        Ok((0, ..)) => report.push(
+            database,
+            "runs",
+            Status::Warn,
+            "the snapshot holds no assignments",
+            Some("download a full snapshot with `gluex fetch-db ccdb --force`".to_string()),
+        ),
+        Ok((count, newest, last_run)) => {
+            let mut message = format!("{count} assignments");
+            if let Some(last_run) = last_run {
+                let _ = write!(message, ", bounded run ranges up to run {last_run}");
+            }
+            report.ok(database, "runs", message);
+            check_age(report, database, path, newest);
+        }
+        Err(err) => report.push(database, "runs", Status::Fail, err.to_string(), None),
+    }
+}
+
+fn check(report: &mut Report, database: Database, path: Option<&Path>) {
+    let name = db_name(database);
+    let Some(path) = path else {
+        report.push(
+            database,
+            "path",
+            Status::Fail,
+            "not configured",
+            Some(format!(
+                "pass --{name}, set {}, or run `gluex fetch-db {name}` to download a snapshot{}",
+                env_var(database),
+                data_dir()
+                    .map(|dir| format!(" into {}", dir.display()))
+                    .unwrap_or_default()
+            )),
+        );
+        return;
+    };
+    if !check_file(report, database, path) {
+        return;
+    }
+    match database {
+        Database::Ccdb => check_ccdb(report, path),
+        Database::Rcdb => check_rcdb(report, path),
+    }
+}
+
+pub(crate) fn run(
+    connections: &ConnectionArgs,
+    json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut report = Report { checks: Vec::new() };
+    check(&mut report, Database::Rcdb, connections.rcdb.as_deref());
+    check(&mut report, Database::Ccdb, connections.ccdb.as_deref());
+    if json {
+        let checks: Vec<JsonValue> = report
+            .checks
+            .iter()
+            .map(|check| {
+                json!({
+                    "database": check.database,
+                    "check": check.name,
+                    "status": check.status.as_str(),
+                    "message": check.message,
+                    "fix": check.fix,
+                })
+            })
+            .collect();
+        crate::json::print(&json!({ "checks": checks }))?;
+    } else {
+        for check in &report.checks {
+            println!(
+                "{:<6}{:<6}{:<8}{}",
+                check.status.as_str(),
+                check.database,
+                check.name,
+                check.message
+            );
+            if let Some(fix) = &check.fix {
+                println!("{:20}fix: {fix}", "");
+            }
+        }
+    }
+    if report.worst() == Status::Fail {
+        Err(io::Error::other("some checks failed").into())
+    } else {
+        Ok(())
+    }
+}
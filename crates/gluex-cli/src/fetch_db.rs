@@ -0,0 +1,524 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use clap::Args;
+use gluex_ccdb::database::CCDB;
+use gluex_core::{provenance::sha256_file, retry::RetryPolicy};
+use gluex_rcdb::database::RCDB;
+use serde::Serialize;
+
+use crate::{
+    data_dir::{data_dir, CCDB_FILE, RCDB_FILE},
+    snapshot::Database,
+};
+
+const CCDB_URL: &str = "https://halldweb.jlab.org/dist/ccdb.sqlite";
+const RCDB_URL: &str = "https://halldweb.jlab.org/dist/rcdb.sqlite";
+
+#[derive(Args)]
+pub(crate) struct FetchDbArgs {
+    /// Which database to download
+    #[arg(value_enum)]
+    database: Database,
+
+    /// Download from this URL instead of the official Jefferson Lab snapshot
+    #[arg(long)]
+    url: Option<String>,
+
+    /// Destination file (defaults to the data directory, see `GLUEX_DATA_DIR`)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Expected SHA-256 hash of the file, checked before it replaces the destination (defaults
+    /// to the hash published at `<url>.sha256`)
+    #[arg(long, value_name = "HEX")]
+    sha256: Option<String>,
+
+    /// Skip the SHA-256 check when no hash is given or published
+    #[arg(long, conflicts_with = "sha256")]
+    no_checksum: bool,
+
+    /// Replace an existing snapshot
+    #[arg(long)]
+    force: bool,
+}
+
+/// Sidecar written next to each downloaded snapshot.
+#[derive(Serialize)]
+struct SnapshotInfo<'a> {
+    url: &'a str,
+    size: u64,
+    sha256: &'a str,
+    /// `Last-Modified` reported by the server, i.e. when the snapshot was produced.
+    snapshot_date: Option<DateTime<Utc>>,
+    downloaded: DateTime<Utc>,
+}
+
+/// Result of one download attempt.
+struct Transfer {
+    total: Option<u64>,
+    last_modified: Option<DateTime<Utc>>,
+}
+
+/// Returns `path` with `.{suffix}` appended to its file name.
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Returns the validator identifying the version of the file being served: a strong `ETag` if
+/// there is one, otherwise `Last-Modified`. Weak `ETag`s cannot be used with `If-Range`.
+fn validator(response: &ureq::http::Response<ureq::Body>) -> Option<String> {
+    header_value(response, "etag")
+        .filter(|etag| !etag.starts_with("W/"))
+        .or_else(|| header_value(response, "last-modified"))
+}
+
+fn header_value(response: &ureq::http::Response<ureq::Body>, name: &str) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+/// Downloads `url` into `part`, continuing from its current length when the server supports
+/// range requests.
+///
+/// The validator of the version being downloaded is kept in `<part>.validator` and sent as
+/// `If-Range` when resuming, so a server that has replaced the file answers with the whole new
+/// version, which overwrites the partial one. A partial file without a validator is not resumed.
+fn download(url: &str, part: &Path) -> Result<Transfer, Box<dyn std::error::Error>> {
+    let validator_path = sibling(part, "validator");
+    let saved_validator = fs::read_to_string(&validator_path).ok();
+    let offset = match &saved_validator {
+        Some(_) => fs::metadata(part).map_or(0, |meta| meta.len()),
+        None => 0,
+    };
+    let mut request = ureq::get(url);
+    if let (true, Some(saved_validator)) = (offset > 0, &saved_validator) {
+        request = request
+            .header("Range", format!("bytes={offset}-"))
+            .header("If-Range", saved_validator.as_str());
+    }
+    let response = match request.call() {
+        Err(ureq::Error::StatusCode(416)) if offset > 0 => {
+            // The partial file already holds every byte.
+            return Ok(Transfer {
+                total: Some(offset),
+                last_modified: None,
+            });
+        }
+        result => result?,
+    };
+    let last_modified = header_value(&response, "last-modified")
+        .and_then(|value| DateTime::parse_from_rfc2822(&value).ok())
+        .map(|date| date.with_timezone(&Utc));
+    let length = header_value(&response, "content-length").and_then(|value| value.parse().ok());
+    let (mut file, start, total) = if response.status() == 206 {
+        let total = header_value(&response, "content-range")
+            .and_then(|value| value.rsplit('/').next()?.parse().ok())
+            .or_else(|| length.map(|length: u64| offset + length));
+        eprintln!("resuming {url} at {offset} bytes");
+        let file = OpenOptions::new().append(true).open(part)?;
+        (file, offset, total)
+    } else {
+        if offset > 0 {
+            eprintln!("{url} changed since the partial download; restarting");
+        }
+        (File::create(part)?, 0, length)
+    };
+    match validator(&response) {
+        Some(validator) => fs::write(&validator_path, validator)?,
+        None => remove_if_present(&validator_path)?,
+    }
+    let mut reader = response.into_body().into_reader();
+    let mut buffer = vec![0u8; 1 << 16];
+    let mut written = start;
+    let mut reported = 0;
+    loop {
+        let n = reader.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        file.write_all(&buffer[..n])?;
+        written += n as u64;
+        if written - reported >= 1 << 24 {
+            reported = written;
+            match total {
+                Some(total) => eprint!("\r{} / {} MiB", written >> 20, total >> 20),
+                None => eprint!("\r{} MiB", written >> 20),
+            }
+        }
+    }
+    if reported > 0 {
+        eprintln!();
+    }
+    file.sync_all()?;
+    Ok(Transfer {
+        total,
+        last_modified,
+    })
+}
+
+fn remove_if_present(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+        _ => Ok(()),
+    }
+}
+
+/// Removes a partial download and its validator, so the next run starts over.
+fn discard(part: &Path) -> io::Result<()> {
+    remove_if_present(part)?;
+    remove_if_present(&sibling(part, "validator"))
+}
+
+/// Reads the hash published next to `url` as `<url>.sha256`, in the `sha256sum` format.
+fn published_sha256(url: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let sidecar = format!("{url}.sha256");
+    let text = match ureq::get(&sidecar)
+        .call()
+        .and_then(|response| response.into_body().read_to_string())
+    {
+        Ok(text) => text,
+        Err(error) if is_transient(&error) => return Err(error.into()),
+        Err(error) => {
+            return Err(format!(
+                "cannot read the published hash {sidecar} ({error}); \
+                 pass --sha256 HEX, or --no-checksum to skip the check"
+            )
+            .into())
+        }
+    };
+    let hash = text.split_whitespace().next().unwrap_or_default();
+    if hash.len() != 64 || !hash.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return Err(format!("{sidecar} does not hold a SHA-256 hash").into());
+    }
+    Ok(hash.to_ascii_lowercase())
+}
+
+/// Returns true for connection failures, timeouts, and server-side errors, which are worth
+/// retrying; the partial file lets the next attempt resume where this one stopped.
+fn is_transient(error: &(dyn std::error::Error + 'static)) -> bool {
+    if let Some(error) = error.downcast_ref::<ureq::Error>() {
+        match error {
+            ureq::Error::StatusCode(status) => *status == 429 || *status >= 500,
+            ureq::Error::Io(_)
+            | ureq::Error::Timeout(_)
+            | ureq::Error::HostNotFound
+            | ureq::Error::ConnectionFailed
+            | ureq::Error::BodyStalled => true,
+            _ => false,
+        }
+    } else {
+        error.is::<io::Error>()
+    }
+}
+
+fn verify(
+    database: Database,
+    part: &Path,
+    transfer: &Transfer,
+    expected_sha256: Option<&str>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let size = fs::metadata(part)?.len();
+    if let Some(total) = transfer.total {
+        if size != total {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                format!("downloaded {size} bytes but the server reported {total}; rerun to resume"),
+            )
+            .into());
+        }
+    }
+    let sha256 = sha256_file(part)?;
+    if let Some(expected) = expected_sha256 {
+        if !sha256.eq_ignore_ascii_case(expected) {
+            // A corrupt file cannot be resumed, so start over next time.
+            discard(part)?;
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("SHA-256 mismatch: expected {expected}, got {sha256}"),
+            )
+            .into());
+        }
+    }
+    let mut header = [0u8; 16];
+    BufReader::new(File::open(part)?).read_exact(&mut header)?;
+    if &header != b"SQLite format 3\0" {
+        discard(part)?;
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "downloaded file is not an SQLite database",
+        )
+        .into());
+    }
+    match database {
+        Database::Ccdb => drop(CCDB::open(part)?),
+        Database::Rcdb => drop(RCDB::open(part)?),
+    }
+    Ok(sha256)
+}
+
+pub(crate) fn run(args: &FetchDbArgs, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let (default_url, file_name) = match args.database {
+        Database::Ccdb => (CCDB_URL, CCDB_FILE),
+        Database::Rcdb => (RCDB_URL, RCDB_FILE),
+    };
+    let url = args.url.as_deref().unwrap_or(default_url);
+    let output = match &args.output {
+        Some(output) => output.clone(),
+        None => data_dir()
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    "cannot determine a data directory; set GLUEX_DATA_DIR or pass --output",
+                )
+            })?
+            .join(file_name),
+    };
+    if output.exists() && !args.force {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!(
+                "{} already exists (use --force to replace it)",
+                output.display()
+            ),
+        )
+        .into());
+    }
+    if let Some(parent) = output
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(parent)?;
+    }
+    let part = sibling(&output, "part");
+    let retry = RetryPolicy::network().with_env();
+    let expected_sha256 = match (&args.sha256, args.no_checksum) {
+        (Some(sha256), _) => Some(sha256.clone()),
+        (None, false) => Some(retry.run(
+            |_| published_sha256(url),
+            |error| is_transient(error.as_ref()),
+        )?),
+        (None, true) => None,
+    };
+    let transfer = retry.run(
+        |attempt| {
+            if attempt > 1 {
+                eprintln!(
+                    "retrying {url} (attempt {attempt} of {})",
+                    retry.max_attempts
+                );
+            }
+            download(url, &part)
+        },
+        |error| is_transient(error.as_ref()),
+    )?;
+    let sha256 = verify(args.database, &part, &transfer, expected_sha256.as_deref())?;
+    fs::rename(&part, &output)?;
+    remove_if_present(&sibling(&part, "validator"))?;
+    let info = SnapshotInfo {
+        url,
+        size: fs::metadata(&output)?.len(),
+        sha256: &sha256,
+        snapshot_date: transfer.last_modified,
+        downloaded: Utc::now(),
+    };
+    serde_json::to_writer_pretty(File::create(sibling(&output, "json"))?, &info)?;
+    if json {
+        let mut value = serde_json::to_value(&info)?;
+        value["path"] = serde_json::json!(output);
+        crate::json::print(&value)?;
+    } else {
+        eprintln!("wrote {} (sha256 {sha256})", output.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        io::BufRead,
+        net::TcpListener,
+        sync::{Arc, Mutex},
+        thread,
+    };
+
+    use gluex_testdata::ccdb::{Assignment, CcdbBuilder};
+
+    use super::*;
+
+    /// Serves `files` (body and `ETag` by path) over HTTP on a local port, answering `Range`
+    /// requests unless `If-Range` names another version, and records every response status.
+    fn serve(
+        files: HashMap<&'static str, (Vec<u8>, &'static str)>,
+    ) -> (String, Arc<Mutex<Vec<u16>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind a local port");
+        let url = format!("http://{}", listener.local_addr().expect("local address"));
+        let statuses = Arc::new(Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&statuses);
+        thread::spawn(move || {
+            for mut stream in listener.incoming().map_while(Result::ok) {
+                let mut reader = BufReader::new(&stream);
+                let mut request = String::new();
+                let _ = reader.read_line(&mut request);
+                let mut headers = HashMap::new();
+                let mut line = String::new();
+                while reader.read_line(&mut line).is_ok_and(|n| n > 2) {
+                    if let Some((name, value)) = line.split_once(':') {
+                        headers.insert(name.to_ascii_lowercase(), value.trim().to_string());
+                    }
+                    line.clear();
+                }
+                let path = request.split_whitespace().nth(1).unwrap_or_default();
+                let (status, body, extra) = match files.get(path) {
+                    None => (404, &[][..], String::new()),
+                    Some((body, etag)) => {
+                        let start = headers
+                            .get("range")
+                            .and_then(|range| range.strip_prefix("bytes=")?.strip_suffix('-'))
+                            .and_then(|start| start.parse::<usize>().ok())
+                            .filter(|_| headers.get("if-range").is_none_or(|tag| tag == etag));
+                        match start {
+                            Some(start) => (
+                                206,
+                                &body[start..],
+                                format!(
+                                    "Content-Range: bytes {start}-{}/{}\r\nETag: {etag}\r\n",
+                                    body.len() - 1,
+                                    body.len()
+                                ),
+                            ),
+                            None => (200, &body[..], format!("ETag: {etag}\r\n")),
+                        }
+                    }
+                };
+                recorded.lock().expect("status log").push(status);
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {status} Test\r\nContent-Length: {}\r\nConnection: close\r\n{extra}\r\n",
+                    body.len()
+                );
+                let _ = stream.write_all(body);
+            }
+        });
+        (url, statuses)
+    }
+
+    /// Writes two versions of a CCDB snapshot that differ only in one value.
+    fn snapshots(dir: &Path) -> Result<(Vec<u8>, Vec<u8>), Box<dyn std::error::Error>> {
+        let _ = fs::remove_dir_all(dir);
+        fs::create_dir_all(dir)?;
+        let table = "/test/counts";
+        let mut versions = Vec::new();
+        for value in [1, 2] {
+            let path = dir.join(format!("v{value}.sqlite"));
+            CcdbBuilder::new()
+                .table(table, 1, &[("n", "int")])
+                .assign(Assignment::new(table, 0..=10, [[value]]))
+                .write(&path)?;
+            versions.push(fs::read(path)?);
+        }
+        let v2 = versions.pop().unwrap_or_default();
+        let v1 = versions.pop().unwrap_or_default();
+        Ok((v1, v2))
+    }
+
+    fn checksum(bytes: &[u8], dir: &Path) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let path = dir.join("hashed");
+        fs::write(&path, bytes)?;
+        Ok(format!("{}  ccdb.sqlite\n", sha256_file(&path)?).into_bytes())
+    }
+
+    fn args(url: &str, output: &Path) -> FetchDbArgs {
+        FetchDbArgs {
+            database: Database::Ccdb,
+            url: Some(format!("{url}/ccdb.sqlite")),
+            output: Some(output.to_path_buf()),
+            sha256: None,
+            no_checksum: false,
+            force: false,
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("gluex-fetch-{}-{name}", std::process::id()))
+    }
+
+    #[test]
+    fn resuming_a_replaced_snapshot_restarts_from_zero() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = temp_dir("replaced");
+        let (v1, v2) = snapshots(&dir)?;
+        let output = dir.join("ccdb.sqlite");
+        let part = sibling(&output, "part");
+        fs::write(&part, &v1[..v1.len() / 2])?;
+        fs::write(sibling(&part, "validator"), "\"v1\"")?;
+        let (url, statuses) = serve(HashMap::from([
+            ("/ccdb.sqlite", (v2.clone(), "\"v2\"")),
+            ("/ccdb.sqlite.sha256", (checksum(&v2, &dir)?, "\"sum\"")),
+        ]));
+
+        run(&args(&url, &output), false)?;
+        assert_eq!(fs::read(&output)?, v2);
+        assert_eq!(*statuses.lock().expect("status log"), [200, 200]);
+        assert!(!sibling(&part, "validator").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn resuming_an_unchanged_snapshot_appends() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = temp_dir("unchanged");
+        let (_, v2) = snapshots(&dir)?;
+        let output = dir.join("ccdb.sqlite");
+        let part = sibling(&output, "part");
+        fs::write(&part, &v2[..v2.len() / 2])?;
+        fs::write(sibling(&part, "validator"), "\"v2\"")?;
+        let (url, statuses) = serve(HashMap::from([
+            ("/ccdb.sqlite", (v2.clone(), "\"v2\"")),
+            ("/ccdb.sqlite.sha256", (checksum(&v2, &dir)?, "\"sum\"")),
+        ]));
+
+        run(&args(&url, &output), false)?;
+        assert_eq!(fs::read(&output)?, v2);
+        assert_eq!(*statuses.lock().expect("status log"), [200, 206]);
+        Ok(())
+    }
+
+    #[test]
+    fn the_published_checksum_is_checked_by_default() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = temp_dir("checksum");
+        let (v1, v2) = snapshots(&dir)?;
+        let output = dir.join("ccdb.sqlite");
+        let (url, _) = serve(HashMap::from([
+            ("/ccdb.sqlite", (v2.clone(), "\"v2\"")),
+            ("/ccdb.sqlite.sha256", (checksum(&v1, &dir)?, "\"sum\"")),
+        ]));
+        let error = run(&args(&url, &output), false)
+            .expect_err("a mismatched hash is rejected")
+            .to_string();
+        assert!(error.contains("SHA-256 mismatch"), "{error}");
+        assert!(!output.exists());
+        assert!(!sibling(&output, "part").exists());
+
+        let (url, _) = serve(HashMap::from([("/ccdb.sqlite", (v2.clone(), "\"v2\""))]));
+        let error = run(&args(&url, &output), false)
+            .expect_err("a missing hash is rejected")
+            .to_string();
+        assert!(error.contains("--no-checksum"), "{error}");
+
+        let mut unchecked = args(&url, &output);
+        unchecked.no_checksum = true;
+        run(&unchecked, false)?;
+        assert_eq!(fs::read(&output)?, v2);
+        Ok(())
+    }
+}
@@ -0,0 +1,359 @@
+use std::{error::Error, fmt};
+
+use gluex_core::parsers::parse_timestamp;
+use gluex_rcdb::{
+    conditions::{self, aliases, Expr},
+    models::ValueType,
+};
+
+/// Error raised for a filter expression that cannot be parsed or does not match the condition
+/// types of the database.
+#[derive(Debug)]
+pub(crate) struct FilterError(String);
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid filter: {}", self.0)
+    }
+}
+
+impl Error for FilterError {}
+
+fn error<T>(message: impl Into<String>) -> Result<T, FilterError> {
+    Err(FilterError(message.into()))
+}
+
+/// Keywords of the filter language (matched case-insensitively), offered by completion.
+pub(crate) const KEYWORDS: [&str; 5] = ["and", "or", "not", "in", "contains"];
+
+const SYMBOLS: [&str; 14] = [
+    "==", "!=", ">=", "<=", "&&", "||", ">", "<", "=", "!", "(", ")", "[", "]",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Symbol(&'static str),
+    Comma,
+    At,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Word(word) => write!(f, "{word}"),
+            Token::Quoted(text) => write!(f, "{text:?}"),
+            Token::Symbol(symbol) => write!(f, "{symbol}"),
+            Token::Comma => write!(f, ","),
+            Token::At => write!(f, "@"),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, FilterError> {
+    let mut tokens = Vec::new();
+    let mut rest = input.trim_start();
+    while let Some(ch) = rest.chars().next() {
+        if ch == '\'' || ch == '"' {
+            let Some(end) = rest[1..].find(ch) else {
+                return error(format!("unterminated string starting at {rest}"));
+            };
+            tokens.push(Token::Quoted(rest[1..=end].to_string()));
+            rest = &rest[end + 2..];
+        } else if ch == ',' {
+            tokens.push(Token::Comma);
+            rest = &rest[1..];
+        } else if ch == '@' {
+            tokens.push(Token::At);
+            rest = &rest[1..];
+        } else if let Some(symbol) = SYMBOLS.iter().find(|symbol| rest.starts_with(**symbol)) {
+            tokens.push(Token::Symbol(symbol));
+            rest = &rest[symbol.len()..];
+        } else {
+            let end = rest
+                .find(|c: char| c.is_whitespace() || "'\",@=!<>&|()[]".contains(c))
+                .unwrap_or(rest.len());
+            if end == 0 {
+                return error(format!("unexpected character {ch:?}"));
+            }
+            tokens.push(Token::Word(rest[..end].to_string()));
+            rest = &rest[end..];
+        }
+        rest = rest.trim_start();
+    }
+    Ok(tokens)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+/// Parses a filter expression written in the style of RCDB's Python queries, such as
+/// `@is_production and event_count > 500000 and run_type in ['hd_all.tsg', 'hd_all.tsg_ps']`.
+///
+/// Comparisons (`==`, `!=`, `>`, `>=`, `<`, `<=`, `in [...]`, `contains`) are typed by looking
+/// each condition up with `type_of`; a bare boolean condition matches runs where it is true, and
+/// `@name` expands a built-in alias. Clauses combine with `and`, `or`, `not`, and parentheses.
+pub(crate) fn parse(
+    input: &str,
+    type_of: &dyn Fn(&str) -> Option<ValueType>,
+) -> Result<Expr, FilterError> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        pos: 0,
+        type_of,
+    };
+    if parser.tokens.is_empty() {
+        return error("empty expression");
+    }
+    let expr = parser.parse_or()?;
+    match parser.next() {
+        None => Ok(expr),
+        Some(token) => error(format!("unexpected {token} after a complete expression")),
+    }
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    type_of: &'a dyn Fn(&str) -> Option<ValueType>,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, keyword: &str, symbol: &str) -> bool {
+        let matched = match self.peek() {
+            Some(Token::Word(word)) => word.eq_ignore_ascii_case(keyword),
+            Some(Token::Symbol(found)) => *found == symbol,
+            _ => false,
+        };
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), FilterError> {
+        match self.next() {
+            Some(Token::Symbol(found)) if found == symbol => Ok(()),
+            Some(token) => error(format!("expected {symbol} but found {token}")),
+            None => error(format!("expected {symbol} at the end of the expression")),
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, FilterError> {
+        let mut clauses = vec![self.parse_and()?];
+        while self.eat("or", "||") {
+            clauses.push(self.parse_and()?);
+        }
+        Ok(conditions::any(clauses))
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, FilterError> {
+        let mut clauses = vec![self.parse_not()?];
+        while self.eat("and", "&&") {
+            clauses.push(self.parse_not()?);
+        }
+        Ok(conditions::all(clauses))
+    }
+
+    fn parse_not(&mut self) -> Result<Expr, FilterError> {
+        if self.eat("not", "!") {
+            Ok(self.parse_not()?.negate())
+        } else {
+            self.parse_primary()
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, FilterError> {
+        match self.next() {
+            Some(Token::Symbol("(")) => {
+                let expr = self.parse_or()?;
+                self.expect_symbol(")")?;
+                Ok(expr)
+            }
+            Some(Token::At) => match self.next() {
+                Some(Token::Word(name)) => aliases::get(&name).map_or_else(
+                    || {
+                        error(format!(
+                            "unknown alias @{name} (known: {})",
+                            aliases::NAMES.join(", ")
+                        ))
+                    },
+                    Ok,
+                ),
+                _ => error("expected an alias name after @"),
+            },
+            Some(Token::Word(name)) if !KEYWORDS.iter().any(|kw| name.eq_ignore_ascii_case(kw)) => {
+                let Some(value_type) = (self.type_of)(&name) else {
+                    return error(format!("unknown condition {name}"));
+                };
+                self.parse_comparison(name, value_type)
+            }
+            Some(token) => error(format!("expected a condition but found {token}")),
+            None => error("expression ends where a condition was expected"),
+        }
+    }
+
+    fn parse_comparison(
+        &mut self,
+        name: String,
+        value_type: ValueType,
+    ) -> Result<Expr, FilterError> {
+        let op = match self.peek() {
+            Some(Token::Symbol("==" | "=")) => Op::Eq,
+            Some(Token::Symbol("!=")) => Op::Ne,
+            Some(Token::Symbol(">")) => Op::Gt,
+            Some(Token::Symbol(">=")) => Op::Ge,
+            Some(Token::Symbol("<")) => Op::Lt,
+            Some(Token::Symbol("<=")) => Op::Le,
+            _ => {
+                if self.eat("in", "") {
+                    let values = self.parse_list()?;
+                    return text_type(&name, value_type, "in")
+                        .map(|()| conditions::string_cond(name).isin(values));
+                }
+                if self.eat("contains", "") {
+                    let value = self.parse_value()?;
+                    return text_type(&name, value_type, "contains")
+                        .map(|()| conditions::string_cond(name).contains(value));
+                }
+                if value_type == ValueType::Bool {
+                    return Ok(conditions::bool_cond(name).is_true());
+                }
+                return error(format!(
+                    "{name} is a {} condition and needs a comparison",
+                    value_type.as_str()
+                ));
+            }
+        };
+        self.pos += 1;
+        let value = self.parse_value()?;
+        compare(name, value_type, op, &value)
+    }
+
+    fn parse_value(&mut self) -> Result<String, FilterError> {
+        match self.next() {
+            Some(Token::Word(word) | Token::Quoted(word)) => Ok(word),
+            Some(token) => error(format!("expected a value but found {token}")),
+            None => error("expression ends where a value was expected"),
+        }
+    }
+
+    fn parse_list(&mut self) -> Result<Vec<String>, FilterError> {
+        self.expect_symbol("[")?;
+        let mut values = Vec::new();
+        if self.eat("", "]") {
+            return Ok(values);
+        }
+        loop {
+            values.push(self.parse_value()?);
+            match self.next() {
+                Some(Token::Comma) => {}
+                Some(Token::Symbol("]")) => return Ok(values),
+                _ => return error("expected , or ] in a value list"),
+            }
+        }
+    }
+}
+
+fn text_type(name: &str, value_type: ValueType, op: &str) -> Result<(), FilterError> {
+    if value_type.is_textual() {
+        Ok(())
+    } else {
+        error(format!(
+            "{op} needs a string condition but {name} is {}",
+            value_type.as_str()
+        ))
+    }
+}
+
+fn compare(name: String, value_type: ValueType, op: Op, value: &str) -> Result<Expr, FilterError> {
+    let invalid = || {
+        FilterError(format!(
+            "{value:?} is not a valid {} value for {name}",
+            value_type.as_str()
+        ))
+    };
+    Ok(match value_type {
+        ValueType::Int => {
+            let value: i64 = value.parse().map_err(|_| invalid())?;
+            let field = conditions::int_cond(name);
+            match op {
+                Op::Eq => field.eq(value),
+                Op::Ne => field.ne(value),
+                Op::Gt => field.gt(value),
+                Op::Ge => field.ge(value),
+                Op::Lt => field.lt(value),
+                Op::Le => field.le(value),
+            }
+        }
+        ValueType::Float => {
+            let value: f64 = value.parse().map_err(|_| invalid())?;
+            let field = conditions::float_cond(name);
+            match op {
+                Op::Eq => field.eq(value),
+                Op::Ne => field.eq(value).negate(),
+                Op::Gt => field.gt(value),
+                Op::Ge => field.ge(value),
+                Op::Lt => field.lt(value),
+                Op::Le => field.le(value),
+            }
+        }
+        ValueType::Time => {
+            let value = parse_timestamp(value).map_err(|_| invalid())?;
+            let field = conditions::time_cond(name);
+            match op {
+                Op::Eq => field.eq(value),
+                Op::Ne => field.eq(value).negate(),
+                Op::Gt => field.gt(value),
+                Op::Ge => field.ge(value),
+                Op::Lt => field.lt(value),
+                Op::Le => field.le(value),
+            }
+        }
+        ValueType::Bool => {
+            let value = match value.to_ascii_lowercase().as_str() {
+                "true" | "1" => true,
+                "false" | "0" => false,
+                _ => return Err(invalid()),
+            };
+            let field = conditions::bool_cond(name.as_str());
+            match op {
+                Op::Eq if value => field.is_true(),
+                Op::Ne if !value => field.is_true(),
+                Op::Eq | Op::Ne => field.is_false(),
+                _ => return error(format!("{name} is a bool condition, use == or !=")),
+            }
+        }
+        ValueType::String | ValueType::Json | ValueType::Blob => {
+            let field = conditions::string_cond(name.as_str());
+            match op {
+                Op::Eq => field.eq(value),
+                Op::Ne => field.ne(value),
+                _ => {
+                    return error(format!(
+                        "{name} is a {} condition, use ==, !=, in, or contains",
+                        value_type.as_str()
+                    ))
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,37 @@
+use std::{io, net::SocketAddr};
+
+use clap::Args;
+use gluex_ccdb::database::CCDB;
+use gluex_lumi::cli::ConnectionArgs;
+use gluex_rcdb::database::RCDB;
+
+#[derive(Args)]
+pub(crate) struct GrpcArgs {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:50051")]
+    bind: SocketAddr,
+}
+
+pub(crate) fn run(
+    args: &GrpcArgs,
+    connections: &ConnectionArgs,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let ccdb = connections.ccdb.as_ref().map(CCDB::open).transpose()?;
+    let rcdb = connections.rcdb.as_ref().map(RCDB::open).transpose()?;
+    if ccdb.is_none() && rcdb.is_none() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "--ccdb or --rcdb is required (or set CCDB_CONNECTION/RCDB_CONNECTION)",
+        )
+        .into());
+    }
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(async {
+        eprintln!("serving gRPC on {}", args.bind);
+        gluex_grpc::serve(args.bind, ccdb, rcdb, async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await
+    })?;
+    Ok(())
+}
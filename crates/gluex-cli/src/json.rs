@@ -0,0 +1,277 @@
+//! JSON shapes shared by `--json` output and the HTTP server, so both emit the same schema.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    io,
+};
+
+use gluex_ccdb::{
+    comparison::{ConstantsSegment, TableComparison},
+    data::Data,
+    database::DirectoryHandle,
+    models::VariationMeta,
+    search::{SearchHit, SearchMatch, SearchTarget},
+};
+use gluex_core::RunNumber;
+use gluex_rcdb::{
+    database::RCDB,
+    explain::{RunExplanation, RunVerdict},
+};
+use serde_json::{json, Map, Value as JsonValue};
+
+/// Pretty-prints `value` to stdout, followed by a newline.
+pub(crate) fn print(value: &JsonValue) -> io::Result<()> {
+    serde_json::to_writer_pretty(io::stdout(), value)?;
+    println!();
+    Ok(())
+}
+
+pub(crate) fn rcdb_value(value: &gluex_rcdb::data::Value) -> JsonValue {
+    if let Some(text) = value.as_string() {
+        json!(text)
+    } else if let Some(int) = value.as_int() {
+        json!(int)
+    } else if let Some(float) = value.as_float() {
+        json!(float)
+    } else if let Some(flag) = value.as_bool() {
+        json!(flag)
+    } else if let Some(time) = value.as_time() {
+        json!(time.to_rfc3339())
+    } else {
+        JsonValue::Null
+    }
+}
+
+pub(crate) fn ccdb_value(value: gluex_ccdb::data::Value<'_>) -> JsonValue {
+    use gluex_ccdb::data::Value;
+    match value {
+        Value::Int(v) => json!(v),
+        Value::UInt(v) => json!(v),
+        Value::Long(v) => json!(v),
+        Value::ULong(v) => json!(v),
+        Value::Double(v) => json!(v),
+        Value::Bool(v) => json!(v),
+        Value::String(v) => json!(v),
+    }
+}
+
+/// `[{name, type, description}]`, sorted by name.
+pub(crate) fn condition_types(db: &RCDB) -> JsonValue {
+    let mut types = db.condition_types();
+    types.sort_by(|a, b| a.name().cmp(b.name()));
+    types
+        .iter()
+        .map(|t| {
+            json!({
+                "name": t.name(),
+                "type": t.value_type().as_str(),
+                "description": t.description(),
+            })
+        })
+        .collect()
+}
+
+/// `{run: {condition: value}}`.
+pub(crate) fn rcdb_rows(
+    rows: &BTreeMap<RunNumber, HashMap<String, gluex_rcdb::data::Value>>,
+) -> JsonValue {
+    let rows: Map<String, JsonValue> = rows
+        .iter()
+        .map(|(run, values)| {
+            let values: Map<String, JsonValue> = values
+                .iter()
+                .map(|(name, value)| (name.clone(), rcdb_value(value)))
+                .collect();
+            (run.to_string(), JsonValue::Object(values))
+        })
+        .collect();
+    JsonValue::Object(rows)
+}
+
+/// `[{run, verdict, filter?, clause?, values?}]`, with `verdict` one of `passed`, `excluded`,
+/// and `not_in_database`.
+pub(crate) fn rcdb_explanations(explanations: &[RunExplanation]) -> JsonValue {
+    explanations
+        .iter()
+        .map(|explanation| match &explanation.verdict {
+            RunVerdict::Passed => json!({"run": explanation.run, "verdict": "passed"}),
+            RunVerdict::NotInDatabase => {
+                json!({"run": explanation.run, "verdict": "not_in_database"})
+            }
+            RunVerdict::Excluded {
+                filter,
+                clause,
+                values,
+            } => {
+                let values: Map<String, JsonValue> = values
+                    .iter()
+                    .map(|(name, value)| {
+                        (
+                            name.clone(),
+                            value.as_ref().map_or(JsonValue::Null, rcdb_value),
+                        )
+                    })
+                    .collect();
+                json!({
+                    "run": explanation.run,
+                    "verdict": "excluded",
+                    "filter": filter,
+                    "clause": clause.to_string(),
+                    "values": values,
+                })
+            }
+        })
+        .collect()
+}
+
+/// `{path, dirs, tables: [{name, path, n_rows, n_columns, comment}]}`, sorted by name.
+pub(crate) fn ccdb_dir(dir: &DirectoryHandle) -> JsonValue {
+    let mut dirs: Vec<String> = dir
+        .dirs()
+        .iter()
+        .map(|d| d.meta().name().to_string())
+        .collect();
+    dirs.sort();
+    let mut tables: Vec<_> = dir.tables();
+    tables.sort_by(|a, b| a.name().cmp(b.name()));
+    let tables: Vec<JsonValue> = tables
+        .iter()
+        .map(|t| {
+            json!({
+                "name": t.name(),
+                "path": t.full_path(),
+                "n_rows": t.meta().n_rows(),
+                "n_columns": t.meta().n_columns(),
+                "comment": t.meta().comment(),
+            })
+        })
+        .collect();
+    json!({
+        "path": dir.full_path(),
+        "dirs": dirs,
+        "tables": tables,
+    })
+}
+
+/// `[{name, description, parent}]`, where `parent` is the parent variation's name or null.
+pub(crate) fn ccdb_variations(variations: &[VariationMeta]) -> JsonValue {
+    variations
+        .iter()
+        .map(|variation| {
+            let parent = variations
+                .iter()
+                .find(|parent| {
+                    parent.id() == variation.parent_id() && parent.id() != variation.id()
+                })
+                .map(VariationMeta::name);
+            json!({
+                "name": variation.name(),
+                "description": variation.description(),
+                "parent": parent,
+            })
+        })
+        .collect()
+}
+
+/// `[{path, kind, match, comment}]` in rank order, where `kind` is `"table"` or `"directory"`
+/// and `match` is `"exact_name"`, `"name"`, or `"comment"`.
+pub(crate) fn ccdb_search(hits: &[SearchHit]) -> JsonValue {
+    hits.iter()
+        .map(|hit| {
+            json!({
+                "path": hit.path,
+                "kind": match hit.target {
+                    SearchTarget::Table => "table",
+                    SearchTarget::Directory => "directory",
+                },
+                "match": match hit.matched {
+                    SearchMatch::ExactName => "exact_name",
+                    SearchMatch::Name => "name",
+                    SearchMatch::Comment => "comment",
+                },
+                "comment": hit.comment,
+            })
+        })
+        .collect()
+}
+
+/// `{run: {columns, rows}}`, with each row a list of values in column order.
+pub(crate) fn ccdb_data(data: &BTreeMap<RunNumber, Data>) -> JsonValue {
+    let runs: Map<String, JsonValue> = data
+        .iter()
+        .map(|(run, data)| {
+            let rows: Vec<Vec<JsonValue>> = (0..data.n_rows())
+                .map(|row| {
+                    (0..data.n_columns())
+                        .map(|column| data.value(column, row).map_or(JsonValue::Null, ccdb_value))
+                        .collect()
+                })
+                .collect();
+            (
+                run.to_string(),
+                json!({ "columns": data.column_names(), "rows": rows }),
+            )
+        })
+        .collect();
+    JsonValue::Object(runs)
+}
+
+/// `[{table, columns, periods: [{run_period, change_points, representative, segments}], differences}]`
+pub(crate) fn ccdb_comparisons(comparisons: &[TableComparison]) -> JsonValue {
+    let segment = |segment: &ConstantsSegment| {
+        json!({
+            "first_run": segment.first_run,
+            "last_run": segment.last_run,
+            "n_runs": segment.n_runs,
+            "values": segment.values,
+        })
+    };
+    comparisons
+        .iter()
+        .map(|comparison| {
+            let periods: Vec<JsonValue> = comparison
+                .periods
+                .iter()
+                .map(|period| {
+                    json!({
+                        "run_period": period.run_period.short_name(),
+                        "change_points": period.change_points(),
+                        "representative": period.representative().map(segment),
+                        "segments": period.segments.iter().map(segment).collect::<Vec<_>>(),
+                    })
+                })
+                .collect();
+            let differences: Vec<JsonValue> = comparison
+                .differences
+                .iter()
+                .map(|difference| {
+                    let cells: Vec<JsonValue> = difference
+                        .cells
+                        .iter()
+                        .map(|cell| {
+                            json!({
+                                "row": cell.row,
+                                "column": comparison.column_names.get(cell.column),
+                                "reference": cell.reference,
+                                "value": cell.value,
+                                "percent": cell.percent,
+                            })
+                        })
+                        .collect();
+                    json!({
+                        "run_period": difference.run_period.short_name(),
+                        "shape_changed": difference.shape_changed,
+                        "max_percent": difference.max_percent(),
+                        "cells": cells,
+                    })
+                })
+                .collect();
+            json!({
+                "table": comparison.table,
+                "columns": comparison.column_names,
+                "periods": periods,
+                "differences": differences,
+            })
+        })
+        .collect()
+}
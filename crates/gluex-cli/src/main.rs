@@ -0,0 +1,201 @@
+//! The `gluex` command-line tool, bundling the RCDB, CCDB, and luminosity commands behind one
+//! binary with shared connection handling.
+
+mod bundle;
+mod ccdb;
+mod completions;
+#[cfg(all(unix, feature = "daemon"))]
+mod daemon;
+mod data_dir;
+mod doctor;
+#[cfg(feature = "fetch")]
+mod fetch_db;
+mod filter;
+#[cfg(feature = "grpc")]
+mod grpc;
+mod json;
+#[cfg(feature = "materialize")]
+mod materialize;
+#[cfg(any(feature = "serve", all(unix, feature = "daemon")))]
+mod prometheus;
+mod provenance;
+mod rcdb;
+#[cfg(feature = "repl")]
+mod repl;
+#[cfg(feature = "serve")]
+mod serve;
+mod snapshot;
+#[cfg(feature = "sync")]
+mod sync;
+#[cfg(feature = "tui")]
+mod tui;
+mod watch;
+
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+use gluex_core::{deprecation::DeprecationWarnings, metrics::Metrics, provenance::Recorder};
+use gluex_lumi::cli::{ConnectionArgs, LumiArgs, OutputArgs};
+
+#[derive(Parser)]
+#[command(
+    name = "gluex",
+    version,
+    about = "Query RCDB, CCDB, and luminosity data for the GlueX experiment",
+    arg_required_else_help = true
+)]
+struct Cli {
+    #[command(flatten)]
+    connections: ConnectionArgs,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    /// Record the queries made by `rcdb` and `ccdb` commands (with resolved assignments and file
+    /// hashes) to this manifest
+    #[arg(long, global = true, value_name = "MANIFEST")]
+    record: Option<PathBuf>,
+
+    /// Print query counts, timings, rows returned, and cache hit rates of `rcdb` and `ccdb`
+    /// commands to stderr
+    #[arg(long, global = true)]
+    metrics: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Query run conditions from RCDB.
+    #[command(subcommand)]
+    Rcdb(rcdb::Command),
+    /// Query calibration constants from CCDB.
+    #[command(subcommand)]
+    Ccdb(ccdb::Command),
+    /// Compute flux and luminosity histograms (same options as `gluex-lumi`).
+    Lumi(LumiArgs),
+    /// Verify provenance manifests written with `--record`.
+    #[command(subcommand)]
+    Provenance(provenance::Command),
+    /// Check the database configuration and snapshots and suggest fixes for common problems.
+    Doctor,
+    /// Download the official CCDB or RCDB snapshot into the data directory.
+    #[cfg(feature = "fetch")]
+    FetchDb(fetch_db::FetchDbArgs),
+    /// Export CCDB tables and RCDB conditions for a run selection into memory-mappable Arrow IPC
+    /// files for repeated analysis passes.
+    #[cfg(feature = "materialize")]
+    Materialize(materialize::MaterializeArgs),
+    /// Package trimmed databases into offline analysis bundles that `--ccdb`/`--rcdb` (and the
+    /// RCDB and CCDB libraries) open directly.
+    #[command(subcommand)]
+    Bundle(bundle::Command),
+    /// Create trimmed database snapshots for tests, tutorials, and offline use.
+    #[command(subcommand)]
+    Snapshot(snapshot::Command),
+    /// Update a local CCDB or RCDB snapshot with the rows added to the live database since it was
+    /// last downloaded or synced.
+    #[cfg(feature = "sync")]
+    Sync(sync::SyncArgs),
+    /// Keep the databases open and answer queries from other processes over a Unix socket.
+    #[cfg(all(unix, feature = "daemon"))]
+    Daemon(daemon::DaemonArgs),
+    /// Poll the databases and report new runs (optionally matching a filter) and new CCDB
+    /// assignments as they appear.
+    Watch(watch::WatchArgs),
+    /// Serve RCDB and CCDB queries as JSON over HTTP.
+    #[cfg(feature = "serve")]
+    Serve(serve::ServeArgs),
+    /// Serve CCDB constants and RCDB conditions over gRPC.
+    #[cfg(feature = "grpc")]
+    Grpc(grpc::GrpcArgs),
+    /// Browse CCDB directories and tables and RCDB conditions per run interactively.
+    #[cfg(feature = "tui")]
+    Tui,
+    /// Query RCDB and CCDB interactively with filter expressions and request strings.
+    #[cfg(feature = "repl")]
+    Repl,
+    /// Print a shell completion script that completes CCDB paths, variations, and RCDB condition
+    /// names from the configured databases.
+    Completions {
+        /// Shell to generate the script for
+        shell: completions::Shell,
+    },
+    /// Print completion candidates for a partial command line (used by the completion scripts).
+    #[command(name = "__complete", hide = true)]
+    Complete {
+        /// The command line up to the cursor, starting with the program name
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        words: Vec<String>,
+    },
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cli = Cli::parse();
+    data_dir::fill_connections(&mut cli.connections);
+    let recorder = cli.record.as_ref().map(|_| Recorder::new());
+    let metrics = cli.metrics.then(Metrics::new);
+    let json = cli.output.json;
+    let result = match cli.command {
+        Command::Rcdb(command) => rcdb::run(
+            command,
+            &cli.connections,
+            recorder.clone(),
+            metrics.clone(),
+            json,
+        ),
+        Command::Ccdb(command) => ccdb::run(
+            command,
+            &cli.connections,
+            recorder.clone(),
+            metrics.clone(),
+            json,
+        ),
+        Command::Lumi(args) => {
+            gluex_lumi::cli::run_with_output(args, &cli.connections, &cli.output)
+        }
+        Command::Provenance(command) => provenance::run(command, &cli.connections, json),
+        Command::Doctor => doctor::run(&cli.connections, json),
+        #[cfg(feature = "fetch")]
+        Command::FetchDb(args) => fetch_db::run(&args, json),
+        #[cfg(feature = "materialize")]
+        Command::Materialize(args) => materialize::run(&args, &cli.connections, json),
+        Command::Bundle(command) => bundle::run(command, &cli.connections, json),
+        Command::Snapshot(command) => snapshot::run(command, &cli.connections, json),
+        #[cfg(feature = "sync")]
+        Command::Sync(args) => sync::run(&args, json),
+        #[cfg(all(unix, feature = "daemon"))]
+        Command::Daemon(args) => daemon::run(&args, &cli.connections),
+        Command::Watch(args) => watch::run(&args, &cli.connections, json),
+        #[cfg(feature = "serve")]
+        Command::Serve(args) => serve::run(&args, &cli.connections),
+        #[cfg(feature = "grpc")]
+        Command::Grpc(args) => grpc::run(&args, &cli.connections),
+        #[cfg(feature = "tui")]
+        Command::Tui => tui::run(&cli.connections),
+        #[cfg(feature = "repl")]
+        Command::Repl => repl::run(&cli.connections, recorder.clone(), metrics.clone()),
+        Command::Completions { shell } => {
+            completions::print_script(shell);
+            Ok(())
+        }
+        Command::Complete { words } => {
+            completions::print_candidates(&words);
+            Ok(())
+        }
+    };
+    if let (Some(path), Some(recorder)) = (&cli.record, &recorder) {
+        recorder.save(path)?;
+    }
+    if let Some(metrics) = &metrics {
+        eprint!("{}", metrics.report());
+    }
+    result
+}
+
+/// Deprecation warning channel for handles opened by commands that fetch data: it prints each
+/// warning to stderr, unless `GLUEX_DEPRECATION_WARNINGS=off`.
+pub(crate) fn deprecation_warnings() -> Option<DeprecationWarnings> {
+    DeprecationWarnings::from_env().map(|warnings| warnings.with_echo(true))
+}
@@ -0,0 +1,242 @@
+use std::{collections::BTreeMap, io, path::PathBuf};
+
+use clap::Args;
+use gluex_ccdb::{
+    context::Context as CCDBContext,
+    data::{Column, Data},
+    database::CCDB,
+    models::ColumnType,
+};
+use gluex_core::{
+    arrow::{ArrowColumn, ArrowValues},
+    columnar::{CacheManifest, ColumnarCache},
+    RunNumber,
+};
+use gluex_lumi::cli::ConnectionArgs;
+use gluex_rcdb::{
+    context::Context as RCDBContext, data::Value, database::RCDB, models::ValueType, RCDBError,
+};
+
+use crate::rcdb::RunSelectionArgs;
+
+#[derive(Args)]
+pub(crate) struct MaterializeArgs {
+    #[command(flatten)]
+    selection: RunSelectionArgs,
+
+    /// CCDB table to export (repeatable)
+    #[arg(
+        long = "table",
+        value_name = "PATH",
+        required_unless_present = "conditions"
+    )]
+    tables: Vec<String>,
+
+    /// RCDB condition to export (repeatable)
+    #[arg(long = "condition", value_name = "NAME")]
+    conditions: Vec<String>,
+
+    /// Variation to resolve the CCDB tables in
+    #[arg(long, default_value = "default")]
+    variation: String,
+
+    /// Only use constants created at or before this timestamp
+    #[arg(long)]
+    timestamp: Option<String>,
+
+    /// Cache directory to write
+    #[arg(short, long)]
+    output: PathBuf,
+}
+
+/// Empty values of the Arrow type matching a CCDB column type.
+fn empty_values(column_type: ColumnType) -> ArrowValues {
+    match column_type {
+        ColumnType::Int => ArrowValues::Int32(Vec::new()),
+        ColumnType::UInt => ArrowValues::UInt32(Vec::new()),
+        ColumnType::Long => ArrowValues::Int64(Vec::new()),
+        ColumnType::ULong => ArrowValues::UInt64(Vec::new()),
+        ColumnType::Double => ArrowValues::Float64(Vec::new()),
+        ColumnType::String => ArrowValues::Utf8(Vec::new()),
+        ColumnType::Bool => ArrowValues::Boolean(Vec::new()),
+    }
+}
+
+fn extend_values(values: &mut ArrowValues, column: &Column) -> bool {
+    match (values, column) {
+        (ArrowValues::Int32(values), Column::Int(column)) => values.extend_from_slice(column),
+        (ArrowValues::UInt32(values), Column::UInt(column)) => values.extend_from_slice(column),
+        (ArrowValues::Int64(values), Column::Long(column)) => values.extend_from_slice(column),
+        (ArrowValues::UInt64(values), Column::ULong(column)) => values.extend_from_slice(column),
+        (ArrowValues::Float64(values), Column::Double(column)) => values.extend_from_slice(column),
+        (ArrowValues::Boolean(values), Column::Bool(column)) => values.extend_from_slice(column),
+        (ArrowValues::Utf8(values), Column::String(column)) => values.extend_from_slice(column),
+        _ => return false,
+    }
+    true
+}
+
+/// Lays out a table's constants in long format: one row per run and table row, with `run` and
+/// `row` columns followed by the table's columns.
+fn table_columns(
+    db: &CCDB,
+    table: &str,
+    data: &BTreeMap<RunNumber, Data>,
+) -> Result<Vec<ArrowColumn>, Box<dyn std::error::Error>> {
+    let layout = db.table(table)?.columns()?;
+    let mut runs = Vec::new();
+    let mut rows = Vec::new();
+    let mut values: Vec<ArrowValues> = layout
+        .iter()
+        .map(|column| empty_values(column.column_type()))
+        .collect();
+    for (run, data) in data {
+        runs.extend(std::iter::repeat_n(*run, data.n_rows()));
+        rows.extend(0..u32::try_from(data.n_rows())?);
+        for (meta, values) in layout.iter().zip(&mut values) {
+            let extended = data
+                .named_column(meta.name())
+                .is_some_and(|column| extend_values(values, column));
+            if !extended {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "column {} of {table} has unexpected values for run {run}",
+                        meta.name()
+                    ),
+                )
+                .into());
+            }
+        }
+    }
+    let mut columns = vec![
+        ArrowColumn::new("run", ArrowValues::Int64(runs)),
+        ArrowColumn::new("row", ArrowValues::UInt32(rows)),
+    ];
+    columns.extend(
+        layout
+            .iter()
+            .zip(values)
+            .map(|(meta, values)| ArrowColumn::new(meta.name(), values)),
+    );
+    Ok(columns)
+}
+
+/// Lays out condition values with a `run` column and one nullable column per condition.
+fn condition_columns(
+    db: &RCDB,
+    names: &[String],
+    runs: &[RunNumber],
+) -> Result<Vec<ArrowColumn>, Box<dyn std::error::Error>> {
+    let data = db.fetch(
+        names,
+        &RCDBContext::default().with_runs(runs.iter().copied()),
+    )?;
+    let mut columns = vec![ArrowColumn::new(
+        "run",
+        ArrowValues::Int64(data.keys().copied().collect()),
+    )];
+    for name in names {
+        let value_type = db
+            .condition_type(name)
+            .ok_or_else(|| RCDBError::ConditionTypeNotFound(name.clone()))?
+            .value_type();
+        let validity = data
+            .values()
+            .map(|values| values.contains_key(name))
+            .collect();
+        let column = data.values().map(|values| values.get(name));
+        let values = match value_type {
+            ValueType::Int => ArrowValues::Int64(
+                column
+                    .map(|value| value.and_then(Value::as_int).unwrap_or_default())
+                    .collect(),
+            ),
+            ValueType::Float => ArrowValues::Float64(
+                column
+                    .map(|value| value.and_then(Value::as_float).unwrap_or_default())
+                    .collect(),
+            ),
+            ValueType::Bool => ArrowValues::Boolean(
+                column
+                    .map(|value| value.and_then(Value::as_bool).unwrap_or_default())
+                    .collect(),
+            ),
+            ValueType::Time => ArrowValues::TimestampMicros(
+                column
+                    .map(|value| {
+                        value
+                            .and_then(Value::as_time)
+                            .map_or(0, |time| time.timestamp_micros())
+                    })
+                    .collect(),
+            ),
+            ValueType::String | ValueType::Json | ValueType::Blob => ArrowValues::Utf8(
+                column
+                    .map(|value| {
+                        value
+                            .and_then(Value::as_string)
+                            .unwrap_or_default()
+                            .to_string()
+                    })
+                    .collect(),
+            ),
+        };
+        columns.push(ArrowColumn::new(name, values).with_validity(validity));
+    }
+    Ok(columns)
+}
+
+pub(crate) fn run(
+    args: &MaterializeArgs,
+    connections: &ConnectionArgs,
+    json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let rcdb = RCDB::open(connections.rcdb_path()?)?
+        .with_deprecation_warnings(crate::deprecation_warnings());
+    let runs = rcdb.fetch_runs(&args.selection.context())?;
+    let mut cache = ColumnarCache::create(
+        &args.output,
+        CacheManifest {
+            runs: runs.clone(),
+            variation: (!args.tables.is_empty()).then(|| args.variation.clone()),
+            timestamp: args.timestamp.clone(),
+            ..CacheManifest::default()
+        },
+    )?;
+    if !args.tables.is_empty() {
+        let ccdb = CCDB::open(connections.ccdb_path()?)?
+            .with_deprecation_warnings(crate::deprecation_warnings());
+        let mut context = CCDBContext::default()
+            .with_variation(&args.variation)
+            .with_runs(runs.iter().copied());
+        if let Some(timestamp) = &args.timestamp {
+            context = context.with_timestamp_string(timestamp)?;
+        }
+        for table in &args.tables {
+            let data = ccdb.fetch(table, &context)?;
+            cache.write_table(table, table_columns(&ccdb, table, &data)?)?;
+        }
+    }
+    if !args.conditions.is_empty() {
+        cache.write_conditions(condition_columns(&rcdb, &args.conditions, &runs)?)?;
+    }
+    cache.save()?;
+    if json {
+        crate::json::print(&serde_json::json!({
+            "written": args.output,
+            "runs": runs.len(),
+            "tables": args.tables,
+            "conditions": args.conditions,
+        }))?;
+    } else {
+        eprintln!(
+            "wrote {} ({} runs, {} tables, {} conditions)",
+            args.output.display(),
+            runs.len(),
+            args.tables.len(),
+            args.conditions.len()
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,180 @@
+//! Prometheus metrics for the server modes (`gluex serve` and `gluex daemon`).
+//!
+//! Both servers attach one [`Metrics`] collector to their database handles and count requests and
+//! client connections in a [`ServerStats`], which renders everything in the Prometheus text format
+//! for a `/metrics` endpoint.
+
+use std::{
+    fmt::Write as _,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use gluex_core::metrics::Metrics;
+
+/// Content type of the Prometheus text exposition format.
+pub(crate) const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Request and connection counters of a server, along with the query metrics of its databases.
+#[derive(Default)]
+pub(crate) struct ServerStats {
+    metrics: Metrics,
+    databases: Vec<(&'static str, String)>,
+    requests: AtomicU64,
+    errors: AtomicU64,
+    in_flight: AtomicU64,
+    connections: AtomicU64,
+    open_connections: AtomicU64,
+}
+
+/// Decrements a gauge of a [`ServerStats`] when dropped.
+pub(crate) struct Gauge {
+    stats: Arc<ServerStats>,
+    gauge: fn(&ServerStats) -> &AtomicU64,
+}
+
+impl Drop for Gauge {
+    fn drop(&mut self) {
+        (self.gauge)(&self.stats).fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl ServerStats {
+    /// Creates empty counters for a server with the given `(database, path)` handles open.
+    pub(crate) fn new(databases: Vec<(&'static str, String)>) -> Arc<Self> {
+        Arc::new(Self {
+            databases,
+            ..Self::default()
+        })
+    }
+
+    /// The collector to attach to the server's database handles.
+    pub(crate) fn metrics(&self) -> Metrics {
+        self.metrics.clone()
+    }
+
+    /// Counts a request, which stays in flight until the returned guard is dropped.
+    pub(crate) fn start_request(self: &Arc<Self>) -> Gauge {
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        self.in_flight.fetch_add(1, Ordering::Relaxed);
+        Gauge {
+            stats: Arc::clone(self),
+            gauge: |stats| &stats.in_flight,
+        }
+    }
+
+    /// Counts a request that failed.
+    pub(crate) fn request_failed(&self) {
+        self.errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts a client connection, which stays open until the returned guard is dropped.
+    pub(crate) fn open_connection(self: &Arc<Self>) -> Gauge {
+        self.connections.fetch_add(1, Ordering::Relaxed);
+        self.open_connections.fetch_add(1, Ordering::Relaxed);
+        Gauge {
+            stats: Arc::clone(self),
+            gauge: |stats| &stats.open_connections,
+        }
+    }
+
+    /// Renders every counter and the database query metrics in the Prometheus text format.
+    pub(crate) fn render(&self) -> String {
+        let mut out = self.metrics.report().to_prometheus("gluex");
+        for (name, kind, help, value) in [
+            (
+                "requests_total",
+                "counter",
+                "Requests received.",
+                &self.requests,
+            ),
+            (
+                "request_errors_total",
+                "counter",
+                "Requests answered with an error.",
+                &self.errors,
+            ),
+            (
+                "requests_in_flight",
+                "gauge",
+                "Requests being answered.",
+                &self.in_flight,
+            ),
+            (
+                "connections_total",
+                "counter",
+                "Client connections accepted.",
+                &self.connections,
+            ),
+            (
+                "connections_open",
+                "gauge",
+                "Client connections currently open.",
+                &self.open_connections,
+            ),
+        ] {
+            let _ = writeln!(out, "# HELP gluex_{name} {help}");
+            let _ = writeln!(out, "# TYPE gluex_{name} {kind}");
+            let _ = writeln!(out, "gluex_{name} {}", value.load(Ordering::Relaxed));
+        }
+        let _ = writeln!(
+            out,
+            "# HELP gluex_database_info Databases opened by the server."
+        );
+        let _ = writeln!(out, "# TYPE gluex_database_info gauge");
+        for (database, path) in &self.databases {
+            let path = path.replace('\\', "\\\\").replace('"', "\\\"");
+            let _ = writeln!(
+                out,
+                "gluex_database_info{{database=\"{database}\",path=\"{path}\"}} 1"
+            );
+        }
+        out
+    }
+}
+
+/// Serves `GET /metrics` over plain HTTP/1.1 on a background thread, for servers that do not
+/// otherwise speak HTTP.
+#[cfg(all(unix, feature = "daemon"))]
+pub(crate) fn serve_http(
+    addr: std::net::SocketAddr,
+    server: Arc<ServerStats>,
+) -> std::io::Result<std::net::SocketAddr> {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        thread,
+        time::Duration,
+    };
+
+    let listener = TcpListener::bind(addr)?;
+    let local = listener.local_addr()?;
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+            let mut reader = BufReader::new(&stream);
+            let mut request_line = String::new();
+            if reader.read_line(&mut request_line).is_err() {
+                continue;
+            }
+            // skip the headers
+            let mut header = String::new();
+            while reader.read_line(&mut header).is_ok_and(|n| n > 2) {
+                header.clear();
+            }
+            let request: Vec<&str> = request_line.split_whitespace().take(2).collect();
+            let (status, body) = match request[..] {
+                ["GET", "/metrics"] => ("200 OK", server.render()),
+                _ => ("404 Not Found", "not found\n".to_string()),
+            };
+            let _ = write!(
+                &stream,
+                "HTTP/1.1 {status}\r\nContent-Type: {CONTENT_TYPE}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+        }
+    });
+    Ok(local)
+}
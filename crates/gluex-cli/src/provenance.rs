@@ -0,0 +1,68 @@
+use std::{io, path::PathBuf};
+
+use clap::Subcommand;
+use gluex_ccdb::database::CCDB;
+use gluex_core::provenance::{Discrepancy, Manifest};
+use gluex_lumi::cli::ConnectionArgs;
+use gluex_rcdb::database::RCDB;
+
+#[derive(Subcommand)]
+pub(crate) enum Command {
+    /// Replay a manifest written with `--record` against the configured databases and report
+    /// any constants or runs that no longer match.
+    Verify {
+        /// Manifest file
+        manifest: PathBuf,
+    },
+}
+
+pub(crate) fn run(
+    command: Command,
+    connections: &ConnectionArgs,
+    json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match command {
+        Command::Verify { manifest } => {
+            let manifest = Manifest::load(&manifest)?;
+            let mut discrepancies: Vec<Discrepancy> = Vec::new();
+            let mut checked = 0;
+            if manifest.ccdb_queries().next().is_some() {
+                let db = CCDB::open(connections.ccdb_path()?)?;
+                discrepancies.extend(db.verify_manifest(&manifest)?);
+                checked += manifest.ccdb_queries().count();
+            }
+            if manifest.rcdb_queries().next().is_some() {
+                let db = RCDB::open(connections.rcdb_path()?)?;
+                discrepancies.extend(db.verify_manifest(&manifest)?);
+                checked += manifest.rcdb_queries().count();
+            }
+            if json {
+                let found: Vec<serde_json::Value> = discrepancies
+                    .iter()
+                    .map(|d| serde_json::json!({ "query": d.query, "message": d.message }))
+                    .collect();
+                crate::json::print(&serde_json::json!({
+                    "checked": checked,
+                    "discrepancies": found,
+                }))?;
+            } else {
+                for discrepancy in &discrepancies {
+                    match discrepancy.query {
+                        Some(index) => println!("query {index}: {}", discrepancy.message),
+                        None => println!("{}", discrepancy.message),
+                    }
+                }
+            }
+            if discrepancies.is_empty() {
+                eprintln!("{checked} queries verified");
+                Ok(())
+            } else {
+                Err(io::Error::other(format!(
+                    "{} discrepancies in {checked} queries",
+                    discrepancies.len()
+                ))
+                .into())
+            }
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use clap::{Args, Subcommand};
+use gluex_core::{
+    metrics::Metrics, provenance::Recorder, run_periods::RunPeriod, run_set::RunSet, RunNumber,
+};
+use gluex_lumi::cli::ConnectionArgs;
+use gluex_rcdb::{context::Context, data::Value, database::RCDB};
+
+#[derive(Subcommand)]
+pub(crate) enum Command {
+    /// List the condition types defined in the database.
+    Conditions,
+    /// Print the run numbers matching the selection, one per line.
+    Runs(RunSelectionArgs),
+    /// Print condition values for the selected runs as tab-separated columns.
+    Fetch {
+        /// Condition names to fetch.
+        #[arg(required = true)]
+        conditions: Vec<String>,
+
+        #[command(flatten)]
+        selection: RunSelectionArgs,
+    },
+    /// Explain for each run of the selection which filter excludes it, or that it passes.
+    Explain {
+        /// Filter expression the runs must satisfy (repeatable; e.g. `event_count > 500000`)
+        #[arg(long = "filter", value_name = "EXPR")]
+        filters: Vec<String>,
+
+        #[command(flatten)]
+        selection: RunSelectionArgs,
+    },
+}
+
+#[derive(Args)]
+pub(crate) struct RunSelectionArgs {
+    /// Run selection combining periods, runs, and ranges (e.g. `S18+F18-[41050..41100]-41234`)
+    #[arg(long, conflicts_with_all = ["run_period", "runs", "run_min", "run_max"])]
+    select: Option<RunSet>,
+
+    /// Restrict to the runs of a run period (e.g. f18, s19)
+    #[arg(long, conflicts_with_all = ["runs", "run_min", "run_max"])]
+    run_period: Option<RunPeriod>,
+
+    /// Comma-separated run numbers (e.g. 30274,30275)
+    #[arg(long, value_delimiter = ',', conflicts_with_all = ["run_min", "run_max"])]
+    runs: Option<Vec<RunNumber>>,
+
+    /// Inclusive lower bound of the run range
+    #[arg(long)]
+    run_min: Option<RunNumber>,
+
+    /// Inclusive upper bound of the run range
+    #[arg(long)]
+    run_max: Option<RunNumber>,
+}
+
+impl RunSelectionArgs {
+    pub(crate) fn context(&self) -> Context {
+        if let Some(runs) = &self.select {
+            return runs.clone().into();
+        }
+        selection_context(
+            self.run_period,
+            self.runs.as_deref(),
+            self.run_min,
+            self.run_max,
+        )
+    }
+}
+
+/// Builds a [`Context`] from the run selection options shared by the CLI and the HTTP server.
+pub(crate) fn selection_context(
+    run_period: Option<RunPeriod>,
+    runs: Option<&[RunNumber]>,
+    run_min: Option<RunNumber>,
+    run_max: Option<RunNumber>,
+) -> Context {
+    let context = Context::default();
+    if let Some(run_period) = run_period {
+        context.with_run_period(run_period)
+    } else if let Some(runs) = runs {
+        context.with_runs(runs.iter().copied())
+    } else {
+        match (run_min, run_max) {
+            (None, None) => context,
+            (Some(min), None) => context.with_run_range(min..),
+            (None, Some(max)) => context.with_run_range(..=max),
+            (Some(min), Some(max)) => context.with_run_range(min..=max),
+        }
+    }
+}
+
+pub(crate) fn format_value(value: &Value) -> String {
+    if let Some(text) = value.as_string() {
+        text.to_string()
+    } else if let Some(int) = value.as_int() {
+        int.to_string()
+    } else if let Some(float) = value.as_float() {
+        float.to_string()
+    } else if let Some(flag) = value.as_bool() {
+        flag.to_string()
+    } else if let Some(time) = value.as_time() {
+        time.to_rfc3339()
+    } else {
+        String::new()
+    }
+}
+
+pub(crate) fn run(
+    command: Command,
+    connections: &ConnectionArgs,
+    recorder: Option<Recorder>,
+    metrics: Option<Metrics>,
+    json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut db = RCDB::open(connections.rcdb_path()?)?
+        .with_deprecation_warnings(crate::deprecation_warnings());
+    if let Some(recorder) = recorder {
+        db = db.with_recorder(recorder);
+    }
+    if let Some(metrics) = metrics {
+        db = db.with_metrics(metrics);
+    }
+    match command {
+        Command::Conditions if json => {
+            crate::json::print(&crate::json::condition_types(&db))?;
+        }
+        Command::Conditions => {
+            let mut types = db.condition_types();
+            types.sort_by(|a, b| a.name().cmp(b.name()));
+            for condition_type in types {
+                println!(
+                    "{}\t{}\t{}",
+                    condition_type.name(),
+                    condition_type.value_type().as_str(),
+                    condition_type.description()
+                );
+            }
+        }
+        Command::Runs(selection) => {
+            let runs = db.fetch_runs(&selection.context())?;
+            if json {
+                crate::json::print(&runs.into())?;
+            } else {
+                for run in runs {
+                    println!("{run}");
+                }
+            }
+        }
+        Command::Fetch {
+            conditions,
+            selection,
+        } => {
+            let rows = db.fetch(&conditions, &selection.context())?;
+            if json {
+                crate::json::print(&crate::json::rcdb_rows(&rows))?;
+                return Ok(());
+            }
+            println!("run\t{}", conditions.join("\t"));
+            for (run, values) in rows {
+                let cells: Vec<String> = conditions
+                    .iter()
+                    .map(|name| values.get(name).map(format_value).unwrap_or_default())
+                    .collect();
+                println!("{run}\t{}", cells.join("\t"));
+            }
+        }
+        Command::Explain { filters, selection } => {
+            let type_of = |name: &str| db.condition_type(name).map(|meta| meta.value_type());
+            let mut context = selection.context();
+            for filter in &filters {
+                context = context.filter(crate::filter::parse(filter, &type_of)?);
+            }
+            let explanations = db.explain_selection(&context)?;
+            if json {
+                crate::json::print(&crate::json::rcdb_explanations(&explanations))?;
+            } else {
+                for explanation in explanations {
+                    println!("{explanation}");
+                }
+            }
+        }
+    }
+    Ok(())
+}
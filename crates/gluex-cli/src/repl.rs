@@ -0,0 +1,467 @@
+mod editor;
+
+use std::{
+    collections::BTreeMap,
+    io::{self, BufRead, IsTerminal},
+};
+
+use gluex_ccdb::{context::Context as CCDBContext, data::Data, database::CCDB};
+use gluex_core::{
+    errors::ParseRunSetError, metrics::Metrics, provenance::Recorder, run_set::RunSet, RunNumber,
+};
+use gluex_lumi::cli::ConnectionArgs;
+use gluex_rcdb::{conditions::aliases, context::Context as RCDBContext, database::RCDB};
+
+use self::editor::{Completion, LineEditor};
+use crate::filter;
+
+const COMMANDS: [&str; 11] = [
+    "help",
+    "conditions",
+    "runs",
+    "fetch",
+    "ls",
+    "variations",
+    "set",
+    "show",
+    "where",
+    "quit",
+    "exit",
+];
+
+const HELP: &str = "\
+RCDB
+  conditions                          list condition types
+  runs [<filter>]                     runs in the selection that match the filter
+  fetch <cond>[,<cond>...] [where <filter>]
+                                      condition values for the matching runs
+CCDB
+  ls [<dir>]                          list a directory
+  variations                          list variations
+  /<table>                            constants for the session runs and variation
+  /<table>:<run>:<variation>:<time>   resolve a request string as written
+Session
+  set runs <all|f18|30274..30300|S18+F18-41234>
+  set variation <name>
+  set timestamp <time|now>
+  show                                print the session settings
+  quit                                leave (also Ctrl-D)
+
+Filters follow RCDB's query syntax: comparisons (== != > >= < <= in [...] contains), bare
+boolean conditions, @aliases (e.g. @is_production), combined with and/or/not and parentheses:
+  runs @is_production and event_count > 500000 and run_type in ['hd_all.tsg']
+";
+
+/// The session's run selection, kept with the text it was parsed from for `show`.
+#[derive(Clone)]
+struct Selection {
+    text: String,
+    runs: RunSet,
+}
+
+impl Selection {
+    fn all() -> Self {
+        Self {
+            text: "all".to_string(),
+            runs: RunSet::All,
+        }
+    }
+
+    fn parse(text: &str) -> Result<Self, ParseRunSetError> {
+        Ok(Self {
+            text: text.trim().to_string(),
+            runs: text.parse()?,
+        })
+    }
+
+    fn rcdb_context(&self) -> RCDBContext {
+        self.runs.clone().into()
+    }
+
+    fn runs(&self) -> Vec<RunNumber> {
+        match &self.runs {
+            RunSet::All => Vec::new(),
+            RunSet::Runs(runs) => runs.clone(),
+            RunSet::Range(range) => range.clone().collect(),
+        }
+    }
+}
+
+impl std::fmt::Display for Selection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+struct Session {
+    rcdb: Option<RCDB>,
+    ccdb: Option<CCDB>,
+    selection: Selection,
+    variation: String,
+    timestamp: Option<String>,
+}
+
+type CommandResult = Result<(), Box<dyn std::error::Error>>;
+
+impl Session {
+    fn rcdb(&self) -> Result<&RCDB, io::Error> {
+        self.rcdb
+            .as_ref()
+            .ok_or_else(|| missing("--rcdb (or RCDB_CONNECTION)"))
+    }
+
+    fn ccdb(&self) -> Result<&CCDB, io::Error> {
+        self.ccdb
+            .as_ref()
+            .ok_or_else(|| missing("--ccdb (or CCDB_CONNECTION)"))
+    }
+
+    /// Runs one input line, returning false when the session should end.
+    fn execute(&mut self, line: &str) -> Result<bool, Box<dyn std::error::Error>> {
+        let line = line.trim();
+        let (command, rest) = line
+            .split_once(char::is_whitespace)
+            .map_or((line, ""), |(command, rest)| (command, rest.trim()));
+        match command {
+            "" => {}
+            "quit" | "exit" => return Ok(false),
+            "help" | "?" => print!("{HELP}"),
+            "conditions" => self.conditions()?,
+            "runs" => self.runs(rest)?,
+            "fetch" => self.fetch(rest)?,
+            "ls" => self.ls(rest)?,
+            "variations" => self.variations()?,
+            "set" => self.set(rest)?,
+            "show" => self.show(),
+            _ if command.starts_with('/') => self.request(line)?,
+            _ => {
+                return Err(invalid(format!(
+                    "unknown command {command:?}, type `help` for a list"
+                ))
+                .into())
+            }
+        }
+        Ok(true)
+    }
+
+    fn filter_context(&self, db: &RCDB, filter: &str) -> Result<RCDBContext, filter::FilterError> {
+        let context = self.selection.rcdb_context();
+        if filter.is_empty() {
+            return Ok(context);
+        }
+        let type_of = |name: &str| db.condition_type(name).map(|meta| meta.value_type());
+        Ok(context.filter(filter::parse(filter, &type_of)?))
+    }
+
+    fn conditions(&self) -> CommandResult {
+        let rows: Vec<Vec<String>> = self
+            .rcdb()?
+            .condition_types()
+            .into_iter()
+            .map(|meta| {
+                vec![
+                    meta.name().to_string(),
+                    meta.value_type().as_str().to_string(),
+                    meta.description().to_string(),
+                ]
+            })
+            .collect();
+        print_table(&["name", "type", "description"], &rows);
+        Ok(())
+    }
+
+    fn runs(&self, filter: &str) -> CommandResult {
+        let db = self.rcdb()?;
+        let runs = db.fetch_runs(&self.filter_context(db, filter)?)?;
+        for chunk in runs.chunks(10) {
+            let line: Vec<String> = chunk.iter().map(|run| format!("{run:>6}")).collect();
+            println!("{}", line.join(" "));
+        }
+        println!("({} runs)", runs.len());
+        Ok(())
+    }
+
+    fn fetch(&self, args: &str) -> CommandResult {
+        let db = self.rcdb()?;
+        let (names, filter) = match args.split_once(" where ") {
+            Some((names, filter)) => (names, filter.trim()),
+            None => (args, ""),
+        };
+        let names: Vec<&str> = names
+            .split([',', ' '])
+            .filter(|name| !name.is_empty())
+            .collect();
+        let values = db.fetch(&names, &self.filter_context(db, filter)?)?;
+        let mut header = vec!["run"];
+        header.extend(&names);
+        let rows: Vec<Vec<String>> = values
+            .iter()
+            .map(|(run, values)| {
+                std::iter::once(run.to_string())
+                    .chain(names.iter().map(|name| {
+                        values
+                            .get(*name)
+                            .map(crate::rcdb::format_value)
+                            .unwrap_or_default()
+                    }))
+                    .collect()
+            })
+            .collect();
+        print_table(&header, &rows);
+        Ok(())
+    }
+
+    fn ls(&self, path: &str) -> CommandResult {
+        let dir = self.ccdb()?.dir(if path.is_empty() { "/" } else { path })?;
+        let mut rows: Vec<Vec<String>> = dir
+            .dirs()
+            .iter()
+            .map(|d| vec![format!("{}/", d.meta().name()), String::new()])
+            .collect();
+        rows.sort();
+        let mut tables: Vec<Vec<String>> = dir
+            .tables()
+            .iter()
+            .map(|t| {
+                vec![
+                    t.name().to_string(),
+                    format!("{}x{}", t.meta().n_rows(), t.meta().n_columns()),
+                ]
+            })
+            .collect();
+        tables.sort();
+        rows.extend(tables);
+        print_table(&["name", "shape"], &rows);
+        Ok(())
+    }
+
+    fn variations(&self) -> CommandResult {
+        let rows: Vec<Vec<String>> = self
+            .ccdb()?
+            .variations()?
+            .iter()
+            .map(|v| vec![v.name().to_string(), v.description().to_string()])
+            .collect();
+        print_table(&["name", "description"], &rows);
+        Ok(())
+    }
+
+    fn request(&self, line: &str) -> CommandResult {
+        let db = self.ccdb()?;
+        let data = if line.contains(':') {
+            db.request(line)?
+        } else {
+            let mut context = CCDBContext::default()
+                .with_runs(self.selection.runs())
+                .with_variation(&self.variation);
+            if let Some(timestamp) = &self.timestamp {
+                context = context.with_timestamp_string(timestamp)?;
+            }
+            db.fetch(line, &context)?
+        };
+        print_data(&data);
+        Ok(())
+    }
+
+    fn set(&mut self, args: &str) -> CommandResult {
+        let (key, value) = args
+            .split_once(char::is_whitespace)
+            .map(|(key, value)| (key, value.trim()))
+            .ok_or_else(|| invalid("usage: set <runs|variation|timestamp> <value>"))?;
+        match key {
+            "runs" => self.selection = Selection::parse(value)?,
+            "variation" => value.clone_into(&mut self.variation),
+            "timestamp" if value == "now" => self.timestamp = None,
+            "timestamp" => {
+                gluex_core::parsers::parse_timestamp(value)?;
+                self.timestamp = Some(value.to_string());
+            }
+            _ => return Err(invalid(format!("unknown setting {key:?}")).into()),
+        }
+        self.show();
+        Ok(())
+    }
+
+    fn show(&self) {
+        println!(
+            "runs: {}  variation: {}  timestamp: {}",
+            self.selection,
+            self.variation,
+            self.timestamp.as_deref().unwrap_or("now")
+        );
+    }
+
+    fn complete(&self, before: &str) -> Completion {
+        let start = before
+            .rfind(|c: char| c.is_whitespace() || "(),[@!".contains(c))
+            .map_or(0, |i| i + 1);
+        let word = &before[start..];
+        let previous: Vec<&str> = before[..start].split_whitespace().collect();
+        let mut candidates: Vec<String> =
+            if (word.starts_with('/') && previous.is_empty()) || previous.first() == Some(&"ls") {
+                self.complete_path(word)
+            } else if before[..start].ends_with('@') {
+                aliases::NAMES.iter().map(ToString::to_string).collect()
+            } else if previous.is_empty() {
+                COMMANDS.iter().map(ToString::to_string).collect()
+            } else if previous == ["set"] {
+                vec!["runs".into(), "variation".into(), "timestamp".into()]
+            } else if previous == ["set", "variation"] {
+                self.ccdb
+                    .as_ref()
+                    .and_then(|db| db.variations().ok())
+                    .map(|variations| variations.iter().map(|v| v.name().to_string()).collect())
+                    .unwrap_or_default()
+            } else if matches!(previous[0], "runs" | "fetch") {
+                let mut names: Vec<String> = self
+                    .rcdb
+                    .as_ref()
+                    .map(|db| {
+                        db.condition_types()
+                            .iter()
+                            .map(|meta| meta.name().to_string())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                names.extend(filter::KEYWORDS.iter().map(ToString::to_string));
+                names.push("where".into());
+                names
+            } else {
+                Vec::new()
+            };
+        candidates.retain(|candidate| candidate.starts_with(word));
+        candidates.sort();
+        candidates.dedup();
+        Completion { start, candidates }
+    }
+
+    fn complete_path(&self, word: &str) -> Vec<String> {
+        self.ccdb
+            .as_ref()
+            .map(|db| crate::completions::ccdb_paths(db, word, false))
+            .unwrap_or_default()
+    }
+}
+
+fn missing(flag: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("no database configured, pass {flag}"),
+    )
+}
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message.into())
+}
+
+/// Prints rows as space-aligned columns under a header, followed by the row count.
+fn print_table<H: AsRef<str>>(header: &[H], rows: &[Vec<String>]) {
+    let mut widths: Vec<usize> = header.iter().map(|h| h.as_ref().chars().count()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let render = |cells: Vec<&str>| {
+        let padded: Vec<String> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect();
+        println!("{}", padded.join("  ").trim_end());
+    };
+    render(header.iter().map(AsRef::as_ref).collect());
+    let rules: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+    render(rules.iter().map(String::as_str).collect());
+    for row in rows {
+        render(row.iter().map(String::as_str).collect());
+    }
+    println!(
+        "({} {})",
+        rows.len(),
+        if rows.len() == 1 { "row" } else { "rows" }
+    );
+}
+
+fn print_data(data: &BTreeMap<RunNumber, Data>) {
+    let Some(first) = data.values().next() else {
+        println!("(no assignments)");
+        return;
+    };
+    let mut header = vec!["run".to_string()];
+    header.extend(first.column_names().iter().map(ToString::to_string));
+    let mut rows = Vec::new();
+    for (run, data) in data {
+        for row in 0..data.n_rows() {
+            rows.push(
+                std::iter::once(run.to_string())
+                    .chain((0..data.n_columns()).map(|column| {
+                        data.value(column, row)
+                            .map(crate::ccdb::format_value)
+                            .unwrap_or_default()
+                    }))
+                    .collect(),
+            );
+        }
+    }
+    print_table(&header, &rows);
+}
+
+pub(crate) fn run(
+    connections: &ConnectionArgs,
+    recorder: Option<Recorder>,
+    metrics: Option<Metrics>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let warnings = crate::deprecation_warnings();
+    let mut rcdb = connections
+        .rcdb
+        .as_ref()
+        .map(|path| RCDB::open(path).map(|db| db.with_deprecation_warnings(warnings.clone())))
+        .transpose()?;
+    let mut ccdb = connections
+        .ccdb
+        .as_ref()
+        .map(|path| CCDB::open(path).map(|db| db.with_deprecation_warnings(warnings)))
+        .transpose()?;
+    if rcdb.is_none() && ccdb.is_none() {
+        return Err(missing("--ccdb or --rcdb (or set CCDB_CONNECTION/RCDB_CONNECTION)").into());
+    }
+    if let Some(recorder) = recorder {
+        rcdb = rcdb.map(|db| db.with_recorder(recorder.clone()));
+        ccdb = ccdb.map(|db| db.with_recorder(recorder));
+    }
+    if let Some(metrics) = metrics {
+        rcdb = rcdb.map(|db| db.with_metrics(metrics.clone()));
+        ccdb = ccdb.map(|db| db.with_metrics(metrics));
+    }
+    let mut session = Session {
+        rcdb,
+        ccdb,
+        selection: Selection::all(),
+        variation: "default".to_string(),
+        timestamp: None,
+    };
+    if !io::stdin().is_terminal() {
+        for line in io::stdin().lock().lines() {
+            match session.execute(&line?) {
+                Ok(true) => {}
+                Ok(false) => break,
+                Err(err) => eprintln!("error: {err}"),
+            }
+        }
+        return Ok(());
+    }
+    let mut editor =
+        LineEditor::new(crate::data_dir::data_dir().map(|dir| dir.join("repl_history")));
+    println!("gluex repl, type `help` for commands and `quit` to leave");
+    while let Some(line) = editor.read_line("gluex> ", &|before| session.complete(before))? {
+        editor.add_history(&line);
+        match session.execute(&line) {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(err) => eprintln!("error: {err}"),
+        }
+    }
+    editor.save_history()?;
+    Ok(())
+}
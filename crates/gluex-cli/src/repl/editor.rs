@@ -0,0 +1,240 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+};
+
+use ratatui::crossterm::{
+    cursor::MoveToColumn,
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    queue,
+    terminal::{self, Clear, ClearType},
+};
+
+/// Maximum number of history entries kept on disk.
+const HISTORY_LIMIT: usize = 1000;
+
+/// Result of completing the word before the cursor: the byte offset where the word starts and
+/// the candidates that could replace it.
+pub(crate) struct Completion {
+    pub(crate) start: usize,
+    pub(crate) candidates: Vec<String>,
+}
+
+/// A minimal single-line editor with history and tab completion, driven by crossterm in raw mode.
+///
+/// Supports cursor movement (arrows, `Home`/`End`, `Ctrl-A`/`Ctrl-E`), deletion (`Backspace`,
+/// `Delete`, `Ctrl-U`, `Ctrl-K`, `Ctrl-W`), history recall (`Up`/`Down`), and completion (`Tab`).
+/// `Ctrl-C` discards the current line and `Ctrl-D` on an empty line ends input.
+pub(crate) struct LineEditor {
+    history: Vec<String>,
+    history_path: Option<PathBuf>,
+}
+
+impl LineEditor {
+    /// Creates an editor, loading previous history from `history_path` when it exists.
+    pub(crate) fn new(history_path: Option<PathBuf>) -> Self {
+        let history = history_path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|text| text.lines().map(str::to_string).collect())
+            .unwrap_or_default();
+        Self {
+            history,
+            history_path,
+        }
+    }
+
+    /// Appends a line to the history, skipping blanks and immediate repeats.
+    pub(crate) fn add_history(&mut self, line: &str) {
+        let line = line.trim();
+        if line.is_empty() || self.history.last().is_some_and(|last| last == line) {
+            return;
+        }
+        self.history.push(line.to_string());
+    }
+
+    /// Writes the most recent history entries back to the history file.
+    pub(crate) fn save_history(&self) -> io::Result<()> {
+        let Some(path) = &self.history_path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let start = self.history.len().saturating_sub(HISTORY_LIMIT);
+        let mut text = self.history[start..].join("\n");
+        text.push('\n');
+        fs::write(path, text)
+    }
+
+    /// Reads one line, returning `None` at end of input.
+    pub(crate) fn read_line(
+        &mut self,
+        prompt: &str,
+        complete: &dyn Fn(&str) -> Completion,
+    ) -> io::Result<Option<String>> {
+        terminal::enable_raw_mode()?;
+        let result = self.edit(prompt, complete);
+        terminal::disable_raw_mode()?;
+        result
+    }
+
+    fn edit(
+        &mut self,
+        prompt: &str,
+        complete: &dyn Fn(&str) -> Completion,
+    ) -> io::Result<Option<String>> {
+        let mut stdout = io::stdout();
+        let mut line = Buffer::default();
+        let mut recalled = self.history.len();
+        let mut draft = String::new();
+        line.draw(&mut stdout, prompt)?;
+        loop {
+            let Event::Key(KeyEvent {
+                code,
+                modifiers,
+                kind: KeyEventKind::Press,
+                ..
+            }) = event::read()?
+            else {
+                continue;
+            };
+            let ctrl = modifiers.contains(KeyModifiers::CONTROL);
+            match code {
+                KeyCode::Enter => {
+                    write!(stdout, "\r\n")?;
+                    return Ok(Some(line.text()));
+                }
+                KeyCode::Char('c') if ctrl => {
+                    write!(stdout, "^C\r\n")?;
+                    return Ok(Some(String::new()));
+                }
+                KeyCode::Char('d') if ctrl => {
+                    if line.chars.is_empty() {
+                        write!(stdout, "\r\n")?;
+                        return Ok(None);
+                    }
+                    line.delete();
+                }
+                KeyCode::Char('a') if ctrl => line.cursor = 0,
+                KeyCode::Char('e') if ctrl => line.cursor = line.chars.len(),
+                KeyCode::Char('u') if ctrl => {
+                    line.chars.drain(..line.cursor);
+                    line.cursor = 0;
+                }
+                KeyCode::Char('k') if ctrl => line.chars.truncate(line.cursor),
+                KeyCode::Char('w') if ctrl => line.delete_word(),
+                KeyCode::Char(ch) if !ctrl => line.insert(ch),
+                KeyCode::Backspace => line.backspace(),
+                KeyCode::Delete => line.delete(),
+                KeyCode::Left => line.cursor = line.cursor.saturating_sub(1),
+                KeyCode::Right => line.cursor = (line.cursor + 1).min(line.chars.len()),
+                KeyCode::Home => line.cursor = 0,
+                KeyCode::End => line.cursor = line.chars.len(),
+                KeyCode::Up if recalled > 0 => {
+                    if recalled == self.history.len() {
+                        draft = line.text();
+                    }
+                    recalled -= 1;
+                    line.set(&self.history[recalled]);
+                }
+                KeyCode::Down if recalled < self.history.len() => {
+                    recalled += 1;
+                    line.set(self.history.get(recalled).unwrap_or(&draft));
+                }
+                KeyCode::Tab => {
+                    let before: String = line.chars[..line.cursor].iter().collect();
+                    let completion = complete(&before);
+                    if let Some(replacement) = line.complete(&before, &completion) {
+                        write!(stdout, "\r\n{replacement}\r\n")?;
+                    }
+                }
+                _ => {}
+            }
+            line.draw(&mut stdout, prompt)?;
+        }
+    }
+}
+
+#[derive(Default)]
+struct Buffer {
+    chars: Vec<char>,
+    cursor: usize,
+}
+
+impl Buffer {
+    fn text(&self) -> String {
+        self.chars.iter().collect()
+    }
+
+    fn set(&mut self, text: &str) {
+        self.chars = text.chars().collect();
+        self.cursor = self.chars.len();
+    }
+
+    fn insert(&mut self, ch: char) {
+        self.chars.insert(self.cursor, ch);
+        self.cursor += 1;
+    }
+
+    fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            self.chars.remove(self.cursor);
+        }
+    }
+
+    fn delete(&mut self) {
+        if self.cursor < self.chars.len() {
+            self.chars.remove(self.cursor);
+        }
+    }
+
+    fn delete_word(&mut self) {
+        let mut start = self.cursor;
+        while start > 0 && self.chars[start - 1].is_whitespace() {
+            start -= 1;
+        }
+        while start > 0 && !self.chars[start - 1].is_whitespace() {
+            start -= 1;
+        }
+        self.chars.drain(start..self.cursor);
+        self.cursor = start;
+    }
+
+    /// Applies a completion of the text before the cursor. Inserts the single candidate or the
+    /// longest common prefix of several; when that adds nothing, returns the candidates to list.
+    fn complete(&mut self, before: &str, completion: &Completion) -> Option<String> {
+        let word = &before[completion.start..];
+        let mut candidates = completion.candidates.iter();
+        let first = candidates.next()?;
+        let common = candidates.fold(first.as_str(), |common, candidate| {
+            let len = common
+                .char_indices()
+                .zip(candidate.chars())
+                .find(|((_, a), b)| a != b)
+                .map_or_else(|| common.len().min(candidate.len()), |((i, _), _)| i);
+            &common[..len]
+        });
+        let mut insert = common.strip_prefix(word).unwrap_or_default().to_string();
+        if completion.candidates.len() == 1 && !common.ends_with('/') {
+            insert.push(' ');
+        }
+        if insert.is_empty() {
+            return (completion.candidates.len() > 1).then(|| completion.candidates.join("  "));
+        }
+        for ch in insert.chars() {
+            self.insert(ch);
+        }
+        None
+    }
+
+    fn draw(&self, out: &mut impl Write, prompt: &str) -> io::Result<()> {
+        let column = prompt.chars().count() + self.cursor;
+        queue!(out, MoveToColumn(0), Clear(ClearType::UntilNewLine))?;
+        write!(out, "{prompt}{}", self.text())?;
+        queue!(out, MoveToColumn(u16::try_from(column).unwrap_or(u16::MAX)))?;
+        out.flush()
+    }
+}
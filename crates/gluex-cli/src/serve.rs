@@ -0,0 +1,387 @@
+use std::{
+    io,
+    net::SocketAddr,
+    pin::Pin,
+    str::FromStr,
+    sync::Arc,
+    task::{Context as TaskContext, Poll},
+};
+
+use axum::{
+    extract::{Query, Request, State},
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::get,
+    serve::Listener,
+    Json, Router,
+};
+use clap::Args;
+use gluex_ccdb::{context::Context as CCDBContext, database::CCDB, CCDBError};
+use gluex_core::{
+    run_periods::RunPeriod,
+    run_set::{RunContext, RunSet},
+    RunNumber,
+};
+use gluex_lumi::cli::ConnectionArgs;
+use gluex_rcdb::{database::RCDB, RCDBError};
+use serde::Deserialize;
+use serde_json::{json, Value as JsonValue};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    net::{TcpListener, TcpStream},
+};
+
+use crate::{
+    json,
+    prometheus::{Gauge, ServerStats, CONTENT_TYPE},
+};
+
+#[derive(Args)]
+pub(crate) struct ServeArgs {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    bind: SocketAddr,
+}
+
+#[derive(Clone)]
+struct AppState {
+    ccdb: Option<CCDB>,
+    rcdb: Option<RCDB>,
+    stats: Arc<ServerStats>,
+}
+
+struct ApiError(StatusCode, String);
+
+impl ApiError {
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self(StatusCode::BAD_REQUEST, message.into())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+impl From<RCDBError> for ApiError {
+    fn from(err: RCDBError) -> Self {
+        let status = match err {
+            RCDBError::ConditionTypeNotFound(_) => StatusCode::NOT_FOUND,
+            RCDBError::SqliteError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::BAD_REQUEST,
+        };
+        Self(status, err.to_string())
+    }
+}
+
+impl From<CCDBError> for ApiError {
+    fn from(err: CCDBError) -> Self {
+        let status = match err {
+            CCDBError::DirectoryNotFoundError(_)
+            | CCDBError::TableNotFoundError(_)
+            | CCDBError::VariationNotFoundError(_) => StatusCode::NOT_FOUND,
+            CCDBError::SqliteError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::BAD_REQUEST,
+        };
+        Self(status, err.to_string())
+    }
+}
+
+type ApiResult = Result<Json<JsonValue>, ApiError>;
+
+impl AppState {
+    fn rcdb(&self) -> Result<RCDB, ApiError> {
+        self.rcdb.clone().ok_or_else(|| {
+            ApiError(
+                StatusCode::NOT_FOUND,
+                "RCDB is not configured on this server".to_string(),
+            )
+        })
+    }
+
+    fn ccdb(&self) -> Result<CCDB, ApiError> {
+        self.ccdb.clone().ok_or_else(|| {
+            ApiError(
+                StatusCode::NOT_FOUND,
+                "CCDB is not configured on this server".to_string(),
+            )
+        })
+    }
+}
+
+/// Runs blocking database work off the async executor.
+async fn blocking<F>(f: F) -> ApiResult
+where
+    F: FnOnce() -> ApiResult + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|err| ApiError(StatusCode::INTERNAL_SERVER_ERROR, err.to_string()))?
+}
+
+#[derive(Deserialize)]
+struct RunQuery {
+    select: Option<String>,
+    run_period: Option<String>,
+    runs: Option<String>,
+    run_min: Option<RunNumber>,
+    run_max: Option<RunNumber>,
+}
+
+impl RunQuery {
+    fn run_period(&self) -> Result<Option<RunPeriod>, ApiError> {
+        self.run_period
+            .as_deref()
+            .map(|s| RunPeriod::from_str(s).map_err(|err| ApiError::bad_request(err.to_string())))
+            .transpose()
+    }
+
+    fn runs(&self) -> Result<Option<Vec<RunNumber>>, ApiError> {
+        self.runs.as_deref().map(parse_runs).transpose()
+    }
+
+    fn rcdb_context(&self) -> Result<gluex_rcdb::context::Context, ApiError> {
+        if let Some(select) = &self.select {
+            return select
+                .parse::<RunSet>()
+                .map(Into::into)
+                .map_err(|err| ApiError::bad_request(err.to_string()));
+        }
+        Ok(crate::rcdb::selection_context(
+            self.run_period()?,
+            self.runs()?.as_deref(),
+            self.run_min,
+            self.run_max,
+        ))
+    }
+}
+
+fn parse_runs(s: &str) -> Result<Vec<RunNumber>, ApiError> {
+    s.split(',')
+        .filter(|part| !part.trim().is_empty())
+        .map(|part| {
+            part.trim()
+                .parse()
+                .map_err(|_| ApiError::bad_request(format!("invalid run number '{part}'")))
+        })
+        .collect()
+}
+
+fn split_names(s: &str) -> Vec<String> {
+    s.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+async fn rcdb_conditions(State(state): State<AppState>) -> ApiResult {
+    let db = state.rcdb()?;
+    blocking(move || Ok(Json(json::condition_types(&db)))).await
+}
+
+async fn rcdb_runs(State(state): State<AppState>, Query(query): Query<RunQuery>) -> ApiResult {
+    let db = state.rcdb()?;
+    let context = query.rcdb_context()?;
+    blocking(move || Ok(Json(json!(db.fetch_runs(&context)?)))).await
+}
+
+#[derive(Deserialize)]
+struct ConditionsQuery {
+    conditions: String,
+}
+
+async fn rcdb_fetch(
+    State(state): State<AppState>,
+    Query(conditions): Query<ConditionsQuery>,
+    Query(selection): Query<RunQuery>,
+) -> ApiResult {
+    let db = state.rcdb()?;
+    let names = split_names(&conditions.conditions);
+    let context = selection.rcdb_context()?;
+    blocking(move || Ok(Json(json::rcdb_rows(&db.fetch(&names, &context)?)))).await
+}
+
+#[derive(Deserialize)]
+struct CcdbLsQuery {
+    path: Option<String>,
+}
+
+async fn ccdb_ls(State(state): State<AppState>, Query(query): Query<CcdbLsQuery>) -> ApiResult {
+    let db = state.ccdb()?;
+    blocking(move || {
+        let dir = db.dir(query.path.as_deref().unwrap_or("/"))?;
+        Ok(Json(json::ccdb_dir(&dir)))
+    })
+    .await
+}
+
+#[derive(Deserialize)]
+struct CcdbFetchQuery {
+    table: String,
+    select: Option<String>,
+    run_period: Option<String>,
+    runs: Option<String>,
+    variation: Option<String>,
+    timestamp: Option<String>,
+}
+
+async fn ccdb_fetch(
+    State(state): State<AppState>,
+    Query(query): Query<CcdbFetchQuery>,
+) -> ApiResult {
+    let db = state.ccdb()?;
+    let mut context = CCDBContext::default();
+    if let Some(variation) = &query.variation {
+        context = context.with_variation(variation);
+    }
+    if let Some(select) = &query.select {
+        let runs = select
+            .parse::<RunSet>()
+            .map_err(|err| ApiError::bad_request(err.to_string()))?;
+        context = context.with_run_set(runs);
+    } else if let Some(run_period) = &query.run_period {
+        let run_period = RunPeriod::from_str(run_period)
+            .map_err(|err| ApiError::bad_request(err.to_string()))?;
+        context = context.with_run_period(run_period, None)?;
+    } else if let Some(runs) = &query.runs {
+        context = context.with_runs(parse_runs(runs)?);
+    }
+    if let Some(timestamp) = &query.timestamp {
+        context = context
+            .with_timestamp_string(timestamp)
+            .map_err(|err| ApiError::bad_request(err.to_string()))?;
+    }
+    blocking(move || Ok(Json(json::ccdb_data(&db.fetch(&query.table, &context)?)))).await
+}
+
+async fn metrics(State(state): State<AppState>) -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, CONTENT_TYPE)], state.stats.render())
+}
+
+/// Counts every request and the ones answered with an error status.
+async fn track(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let _in_flight = state.stats.start_request();
+    let response = next.run(request).await;
+    if response.status().is_client_error() || response.status().is_server_error() {
+        state.stats.request_failed();
+    }
+    response
+}
+
+fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/rcdb/conditions", get(rcdb_conditions))
+        .route("/rcdb/runs", get(rcdb_runs))
+        .route("/rcdb/fetch", get(rcdb_fetch))
+        .route("/ccdb/ls", get(ccdb_ls))
+        .route("/ccdb/fetch", get(ccdb_fetch))
+        .route("/metrics", get(metrics))
+        .layer(middleware::from_fn_with_state(state.clone(), track))
+        .with_state(state)
+}
+
+/// A TCP listener that counts open client connections.
+struct CountingListener {
+    listener: TcpListener,
+    stats: Arc<ServerStats>,
+}
+
+/// A client connection that stays counted as open until it is dropped.
+struct CountedStream {
+    stream: TcpStream,
+    _open: Gauge,
+}
+
+impl Listener for CountingListener {
+    type Io = CountedStream;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        let (stream, addr) = Listener::accept(&mut self.listener).await;
+        let stream = CountedStream {
+            stream,
+            _open: self.stats.open_connection(),
+        };
+        (stream, addr)
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        self.listener.local_addr()
+    }
+}
+
+impl AsyncRead for CountedStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut TaskContext<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for CountedStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut TaskContext<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.stream).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.stream).poll_shutdown(cx)
+    }
+}
+
+pub(crate) fn run(
+    args: &ServeArgs,
+    connections: &ConnectionArgs,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let databases = [("ccdb", &connections.ccdb), ("rcdb", &connections.rcdb)]
+        .into_iter()
+        .filter_map(|(name, path)| Some((name, path.as_ref()?.display().to_string())))
+        .collect();
+    let server_stats = ServerStats::new(databases);
+    let state = AppState {
+        ccdb: connections
+            .ccdb
+            .as_ref()
+            .map(|path| CCDB::open(path).map(|db| db.with_metrics(server_stats.metrics())))
+            .transpose()?,
+        rcdb: connections
+            .rcdb
+            .as_ref()
+            .map(|path| RCDB::open(path).map(|db| db.with_metrics(server_stats.metrics())))
+            .transpose()?,
+        stats: server_stats,
+    };
+    if state.ccdb.is_none() && state.rcdb.is_none() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "--ccdb or --rcdb is required (or set CCDB_CONNECTION/RCDB_CONNECTION)",
+        )
+        .into());
+    }
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(async {
+        let listener = CountingListener {
+            listener: TcpListener::bind(args.bind).await?,
+            stats: Arc::clone(&state.stats),
+        };
+        eprintln!("listening on http://{}", listener.local_addr()?);
+        axum::serve(listener, router(state))
+            .with_graceful_shutdown(async {
+                let _ = tokio::signal::ctrl_c().await;
+            })
+            .await
+    })?;
+    Ok(())
+}
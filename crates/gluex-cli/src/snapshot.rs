@@ -0,0 +1,142 @@
+use std::{io, ops::RangeInclusive, path::Path, path::PathBuf};
+
+use clap::{Args, Subcommand, ValueEnum};
+use gluex_ccdb::database::CCDB;
+use gluex_core::{run_periods::RunPeriod, RunNumber};
+use gluex_lumi::cli::ConnectionArgs;
+use gluex_rcdb::database::RCDB;
+
+#[derive(Subcommand)]
+pub(crate) enum Command {
+    /// Write trimmed copies of the CCDB and/or RCDB files containing only the selected runs,
+    /// tables, and conditions.
+    Extract(ExtractArgs),
+    /// Layer an override file (e.g. private calibrations or locally added runs) on top of a base
+    /// snapshot, writing the merged result to a new file.
+    Merge {
+        /// Which database the files hold
+        #[arg(value_enum)]
+        database: Database,
+
+        /// Base snapshot (left unmodified)
+        base: PathBuf,
+
+        /// Override snapshot whose contents take precedence
+        overlay: PathBuf,
+
+        /// Output path for the merged file
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub(crate) enum Database {
+    Ccdb,
+    Rcdb,
+}
+
+/// Run range shared by the commands that trim databases to a selection of runs.
+#[derive(Args)]
+#[allow(clippy::struct_field_names)]
+pub(crate) struct RunRangeArgs {
+    /// Restrict to the runs of a run period (e.g. f18, s19)
+    #[arg(long, conflicts_with_all = ["run_min", "run_max"])]
+    run_period: Option<RunPeriod>,
+
+    /// Inclusive lower bound of the run range
+    #[arg(long)]
+    run_min: Option<RunNumber>,
+
+    /// Inclusive upper bound of the run range
+    #[arg(long)]
+    run_max: Option<RunNumber>,
+}
+
+impl RunRangeArgs {
+    pub(crate) fn runs(&self) -> RangeInclusive<RunNumber> {
+        match self.run_period {
+            Some(run_period) => run_period.run_range(),
+            None => self.run_min.unwrap_or(0)..=self.run_max.unwrap_or(RunNumber::MAX),
+        }
+    }
+}
+
+#[derive(Args)]
+pub(crate) struct ExtractArgs {
+    #[command(flatten)]
+    runs: RunRangeArgs,
+
+    /// CCDB table or directory to keep (repeatable; directories keep every table below them)
+    #[arg(long = "table", value_name = "PATH", requires = "ccdb_out")]
+    tables: Vec<String>,
+
+    /// RCDB condition to keep (repeatable; defaults to every condition)
+    #[arg(long = "condition", value_name = "NAME", requires = "rcdb_out")]
+    conditions: Vec<String>,
+
+    /// Output path for the trimmed CCDB file
+    #[arg(long, requires = "tables", required_unless_present = "rcdb_out")]
+    ccdb_out: Option<PathBuf>,
+
+    /// Output path for the trimmed RCDB file
+    #[arg(long)]
+    rcdb_out: Option<PathBuf>,
+}
+
+pub(crate) fn ensure_new(path: &Path) -> io::Result<()> {
+    if path.exists() {
+        Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", path.display()),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+pub(crate) fn run(
+    command: Command,
+    connections: &ConnectionArgs,
+    json: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut written: Vec<PathBuf> = Vec::new();
+    match command {
+        Command::Extract(args) => {
+            let runs = args.runs.runs();
+            if let Some(output) = &args.ccdb_out {
+                ensure_new(output)?;
+                let db = CCDB::open(connections.ccdb_path()?)?;
+                db.extract_snapshot(output, &args.tables, runs.clone())?;
+                written.push(output.clone());
+            }
+            if let Some(output) = &args.rcdb_out {
+                ensure_new(output)?;
+                let db = RCDB::open(connections.rcdb_path()?)?;
+                db.extract_snapshot(output, &args.conditions, runs)?;
+                written.push(output.clone());
+            }
+        }
+        Command::Merge {
+            database,
+            base,
+            overlay,
+            output,
+        } => {
+            ensure_new(&output)?;
+            match database {
+                Database::Ccdb => gluex_ccdb::snapshot::merge(&base, &overlay, &output)?,
+                Database::Rcdb => gluex_rcdb::snapshot::merge(&base, &overlay, &output)?,
+            }
+            written.push(output);
+        }
+    }
+    if json {
+        crate::json::print(&serde_json::json!({ "written": written }))?;
+    } else {
+        for path in written {
+            eprintln!("wrote {}", path.display());
+        }
+    }
+    Ok(())
+}
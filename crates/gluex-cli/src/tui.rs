@@ -0,0 +1,536 @@
+use std::io;
+
+use gluex_ccdb::{
+    context::Context as CCDBContext,
+    database::{DirectoryHandle, TypeTableHandle, CCDB},
+};
+use gluex_core::RunNumber;
+use gluex_lumi::cli::ConnectionArgs;
+use gluex_rcdb::{context::Context as RCDBContext, database::RCDB};
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style, Stylize},
+    text::Line,
+    widgets::{Block, List, ListItem, ListState, Paragraph, Row, Table, TableState, Tabs},
+    DefaultTerminal, Frame,
+};
+
+/// Rows shown in a CCDB table preview; larger tables are truncated.
+const PREVIEW_ROWS: usize = 500;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum View {
+    Ccdb,
+    Rcdb,
+}
+
+enum Input {
+    Run(String),
+    Variation(String),
+}
+
+enum Entry {
+    Dir(DirectoryHandle),
+    Table(TypeTableHandle),
+}
+
+impl Entry {
+    fn label(&self) -> String {
+        match self {
+            Entry::Dir(dir) => format!("{}/", dir.meta().name()),
+            Entry::Table(table) => format!(
+                "{} [{}x{}]",
+                table.name(),
+                table.meta().n_rows(),
+                table.meta().n_columns()
+            ),
+        }
+    }
+}
+
+struct Preview {
+    table: TypeTableHandle,
+    title: String,
+    header: Vec<String>,
+    rows: Vec<Vec<String>>,
+    truncated: bool,
+}
+
+struct CcdbBrowser {
+    dir: DirectoryHandle,
+    entries: Vec<Entry>,
+    list: ListState,
+    run: RunNumber,
+    variation: String,
+    preview: Option<Preview>,
+    table: TableState,
+}
+
+impl CcdbBrowser {
+    fn new(db: &CCDB) -> Self {
+        let mut browser = Self {
+            dir: db.root(),
+            entries: Vec::new(),
+            list: ListState::default(),
+            run: 0,
+            variation: "default".to_string(),
+            preview: None,
+            table: TableState::default(),
+        };
+        browser.load_entries();
+        browser
+    }
+
+    fn load_entries(&mut self) {
+        let mut dirs = self.dir.dirs();
+        dirs.sort_by(|a, b| a.meta().name().cmp(b.meta().name()));
+        let mut tables = self.dir.tables();
+        tables.sort_by(|a, b| a.name().cmp(b.name()));
+        self.entries = dirs
+            .into_iter()
+            .map(Entry::Dir)
+            .chain(tables.into_iter().map(Entry::Table))
+            .collect();
+        self.list.select((!self.entries.is_empty()).then_some(0));
+    }
+
+    fn open_selected(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(entry) = self.list.selected().and_then(|i| self.entries.get(i)) else {
+            return Ok(());
+        };
+        match entry {
+            Entry::Dir(dir) => {
+                self.dir = dir.clone();
+                self.load_entries();
+                Ok(())
+            }
+            Entry::Table(table) => {
+                let table = table.clone();
+                self.load_preview(&table)
+            }
+        }
+    }
+
+    fn go_up(&mut self) {
+        if let Some(parent) = self.dir.parent() {
+            let previous = self.dir.meta().name().to_string();
+            self.dir = parent;
+            self.load_entries();
+            let index = self
+                .entries
+                .iter()
+                .position(|e| matches!(e, Entry::Dir(d) if d.meta().name() == previous));
+            if index.is_some() {
+                self.list.select(index);
+            }
+        }
+    }
+
+    fn load_preview(&mut self, table: &TypeTableHandle) -> Result<(), Box<dyn std::error::Error>> {
+        let context = CCDBContext::default()
+            .with_run(self.run)
+            .with_variation(&self.variation);
+        let title = format!(
+            "{} (run {}, variation {})",
+            table.full_path(),
+            self.run,
+            self.variation
+        );
+        let fetched = table.fetch(&context)?;
+        let preview = match fetched.into_values().next() {
+            Some(data) => Preview {
+                table: table.clone(),
+                title,
+                header: data.column_names().to_vec(),
+                rows: (0..data.n_rows().min(PREVIEW_ROWS))
+                    .map(|row| {
+                        (0..data.n_columns())
+                            .map(|column| {
+                                data.value(column, row)
+                                    .map(crate::ccdb::format_value)
+                                    .unwrap_or_default()
+                            })
+                            .collect()
+                    })
+                    .collect(),
+                truncated: data.n_rows() > PREVIEW_ROWS,
+            },
+            None => Preview {
+                table: table.clone(),
+                title,
+                header: Vec::new(),
+                rows: Vec::new(),
+                truncated: false,
+            },
+        };
+        self.preview = Some(preview);
+        self.table.select(None);
+        Ok(())
+    }
+
+    fn refresh_preview(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(table) = self.preview.as_ref().map(|p| p.table.clone()) {
+            self.load_preview(&table)?;
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let [left, right] =
+            Layout::horizontal([Constraint::Percentage(35), Constraint::Percentage(65)])
+                .areas(area);
+        let items: Vec<ListItem> = self
+            .entries
+            .iter()
+            .map(|e| ListItem::new(e.label()))
+            .collect();
+        let list = List::new(items)
+            .block(Block::bordered().title(self.dir.full_path()))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, left, &mut self.list);
+
+        match &self.preview {
+            Some(preview) if preview.header.is_empty() => frame.render_widget(
+                Paragraph::new("No assignment for this run, variation, and time.")
+                    .block(Block::bordered().title(preview.title.as_str())),
+                right,
+            ),
+            Some(preview) => {
+                let title = if preview.truncated {
+                    format!("{} [first {PREVIEW_ROWS} rows]", preview.title)
+                } else {
+                    preview.title.clone()
+                };
+                let widths = vec![Constraint::Fill(1); preview.header.len()];
+                let table = Table::new(preview.rows.iter().map(|r| Row::new(r.clone())), widths)
+                    .header(Row::new(preview.header.clone()).bold())
+                    .block(Block::bordered().title(title))
+                    .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+                frame.render_stateful_widget(table, right, &mut self.table);
+            }
+            None => frame.render_widget(
+                Paragraph::new("Select a table and press Enter to preview it.")
+                    .block(Block::bordered().title("Preview")),
+                right,
+            ),
+        }
+    }
+}
+
+struct RcdbBrowser {
+    db: RCDB,
+    names: Vec<String>,
+    runs: Vec<RunNumber>,
+    run_index: usize,
+    values: Vec<[String; 3]>,
+    table: TableState,
+}
+
+impl RcdbBrowser {
+    fn new(db: RCDB) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut names: Vec<String> = db
+            .condition_types()
+            .iter()
+            .map(|t| t.name().to_string())
+            .collect();
+        names.sort();
+        let runs = db.fetch_runs(&RCDBContext::default())?;
+        let mut browser = Self {
+            db,
+            names,
+            runs,
+            run_index: 0,
+            values: Vec::new(),
+            table: TableState::default(),
+        };
+        browser.load_values()?;
+        Ok(browser)
+    }
+
+    fn run(&self) -> Option<RunNumber> {
+        self.runs.get(self.run_index).copied()
+    }
+
+    fn load_values(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        self.values.clear();
+        let Some(run) = self.run() else {
+            return Ok(());
+        };
+        if self.names.is_empty() {
+            return Ok(());
+        }
+        let mut fetched = self
+            .db
+            .fetch(&self.names, &RCDBContext::default().with_run(run))?;
+        let values = fetched.remove(&run).unwrap_or_default();
+        for name in &self.names {
+            if let Some(value) = values.get(name) {
+                self.values.push([
+                    name.clone(),
+                    value.value_type().as_str().to_string(),
+                    crate::rcdb::format_value(value),
+                ]);
+            }
+        }
+        self.table.select((!self.values.is_empty()).then_some(0));
+        Ok(())
+    }
+
+    fn step(&mut self, forward: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let next = if forward {
+            (self.run_index + 1).min(self.runs.len().saturating_sub(1))
+        } else {
+            self.run_index.saturating_sub(1)
+        };
+        if next != self.run_index {
+            self.run_index = next;
+            self.load_values()?;
+        }
+        Ok(())
+    }
+
+    fn jump_to(&mut self, run: RunNumber) -> Result<(), Box<dyn std::error::Error>> {
+        self.run_index = match self.runs.binary_search(&run) {
+            Ok(index) => index,
+            Err(index) => index.min(self.runs.len().saturating_sub(1)),
+        };
+        self.load_values()
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let title = match self.run() {
+            Some(run) => format!("Run {run} ({} of {})", self.run_index + 1, self.runs.len()),
+            None => "No runs".to_string(),
+        };
+        let table = Table::new(
+            self.values.iter().map(|v| Row::new(v.clone())),
+            [
+                Constraint::Percentage(30),
+                Constraint::Length(8),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(Row::new(["condition", "type", "value"]).bold())
+        .block(Block::bordered().title(title))
+        .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(table, area, &mut self.table);
+    }
+}
+
+struct App {
+    view: View,
+    ccdb: Option<CcdbBrowser>,
+    rcdb: Option<RcdbBrowser>,
+    input: Option<Input>,
+    status: String,
+}
+
+impl App {
+    fn draw(&mut self, frame: &mut Frame) {
+        let [tabs_area, body, footer] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let mut titles = Vec::new();
+        if self.ccdb.is_some() {
+            titles.push((View::Ccdb, "CCDB"));
+        }
+        if self.rcdb.is_some() {
+            titles.push((View::Rcdb, "RCDB"));
+        }
+        let selected = titles.iter().position(|(view, _)| *view == self.view);
+        frame.render_widget(
+            Tabs::new(titles.iter().map(|(_, title)| *title))
+                .select(selected)
+                .highlight_style(Style::new().bold().reversed()),
+            tabs_area,
+        );
+
+        match self.view {
+            View::Ccdb => {
+                if let Some(ccdb) = &mut self.ccdb {
+                    ccdb.draw(frame, body);
+                }
+            }
+            View::Rcdb => {
+                if let Some(rcdb) = &mut self.rcdb {
+                    rcdb.draw(frame, body);
+                }
+            }
+        }
+
+        let footer_text = match &self.input {
+            Some(Input::Run(text)) => format!("run: {text}_"),
+            Some(Input::Variation(text)) => format!("variation: {text}_"),
+            None if !self.status.is_empty() => self.status.clone(),
+            None => match self.view {
+                View::Ccdb => {
+                    let (run, variation) = self
+                        .ccdb
+                        .as_ref()
+                        .map(|c| (c.run, c.variation.as_str()))
+                        .unwrap_or_default();
+                    format!(
+                        "run {run} | variation {variation} | ↑↓ move  Enter open  ⌫ up  r run  v variation  Tab switch  q quit"
+                    )
+                }
+                View::Rcdb => {
+                    "↑↓ move  n/p next/previous run  r jump to run  Tab switch  q quit".to_string()
+                }
+            },
+        };
+        frame.render_widget(Line::from(footer_text).dim(), footer);
+    }
+
+    fn toggle_view(&mut self) {
+        self.view = match self.view {
+            View::Ccdb if self.rcdb.is_some() => View::Rcdb,
+            View::Rcdb if self.ccdb.is_some() => View::Ccdb,
+            view => view,
+        };
+    }
+
+    fn commit_input(&mut self, input: Input) -> Result<(), Box<dyn std::error::Error>> {
+        match input {
+            Input::Run(text) => {
+                let run: RunNumber = text.trim().parse()?;
+                match self.view {
+                    View::Ccdb => {
+                        if let Some(ccdb) = &mut self.ccdb {
+                            ccdb.run = run;
+                            ccdb.refresh_preview()?;
+                        }
+                    }
+                    View::Rcdb => {
+                        if let Some(rcdb) = &mut self.rcdb {
+                            rcdb.jump_to(run)?;
+                        }
+                    }
+                }
+            }
+            Input::Variation(text) => {
+                if let Some(ccdb) = &mut self.ccdb {
+                    ccdb.variation = text.trim().to_string();
+                    ccdb.refresh_preview()?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Handles a key press, returning `false` when the application should exit.
+    fn handle_key(&mut self, code: KeyCode) -> Result<bool, Box<dyn std::error::Error>> {
+        if let Some(input) = &mut self.input {
+            let text = match input {
+                Input::Run(text) | Input::Variation(text) => text,
+            };
+            match code {
+                KeyCode::Enter => {
+                    if let Some(input) = self.input.take() {
+                        self.commit_input(input)?;
+                    }
+                }
+                KeyCode::Esc => self.input = None,
+                KeyCode::Backspace => {
+                    text.pop();
+                }
+                KeyCode::Char(c) => text.push(c),
+                _ => {}
+            }
+            return Ok(true);
+        }
+        self.status.clear();
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(false),
+            KeyCode::Tab | KeyCode::BackTab => self.toggle_view(),
+            KeyCode::Char('r') => self.input = Some(Input::Run(String::new())),
+            KeyCode::Char('v') if self.view == View::Ccdb => {
+                self.input = Some(Input::Variation(String::new()));
+            }
+            _ => match self.view {
+                View::Ccdb => {
+                    if let Some(ccdb) = &mut self.ccdb {
+                        match code {
+                            KeyCode::Up | KeyCode::Char('k') => ccdb.list.select_previous(),
+                            KeyCode::Down | KeyCode::Char('j') => ccdb.list.select_next(),
+                            KeyCode::PageUp => ccdb.table.scroll_up_by(10),
+                            KeyCode::PageDown => ccdb.table.scroll_down_by(10),
+                            KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => {
+                                ccdb.open_selected()?;
+                            }
+                            KeyCode::Backspace | KeyCode::Left | KeyCode::Char('h') => {
+                                ccdb.go_up();
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                View::Rcdb => {
+                    if let Some(rcdb) = &mut self.rcdb {
+                        match code {
+                            KeyCode::Up | KeyCode::Char('k') => rcdb.table.select_previous(),
+                            KeyCode::Down | KeyCode::Char('j') => rcdb.table.select_next(),
+                            KeyCode::Char('n') | KeyCode::Right => rcdb.step(true)?,
+                            KeyCode::Char('p') | KeyCode::Left => rcdb.step(false)?,
+                            _ => {}
+                        }
+                    }
+                }
+            },
+        }
+        Ok(true)
+    }
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, app: &mut App) -> io::Result<()> {
+    loop {
+        terminal.draw(|frame| app.draw(frame))?;
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match app.handle_key(key.code) {
+                Ok(true) => {}
+                Ok(false) => return Ok(()),
+                Err(err) => app.status = format!("error: {err}"),
+            }
+        }
+    }
+}
+
+pub(crate) fn run(connections: &ConnectionArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let ccdb = match &connections.ccdb {
+        Some(path) => Some(CcdbBrowser::new(&CCDB::open(path)?)),
+        None => None,
+    };
+    let rcdb = match &connections.rcdb {
+        Some(path) => Some(RcdbBrowser::new(RCDB::open(path)?)?),
+        None => None,
+    };
+    if ccdb.is_none() && rcdb.is_none() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "--ccdb or --rcdb is required (or set CCDB_CONNECTION/RCDB_CONNECTION)",
+        )
+        .into());
+    }
+    let mut app = App {
+        view: if ccdb.is_some() {
+            View::Ccdb
+        } else {
+            View::Rcdb
+        },
+        ccdb,
+        rcdb,
+        input: None,
+        status: String::new(),
+    };
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app);
+    ratatui::restore();
+    Ok(result?)
+}
@@ -0,0 +1,398 @@
+//! Polls the databases and reports new runs and new CCDB assignments as they appear.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    error::Error,
+    fs, io,
+    path::{Path, PathBuf},
+    process, thread,
+    time::{Duration, SystemTime},
+};
+
+use clap::Args;
+use gluex_ccdb::{
+    backend::SqlValue,
+    database::{DirectoryHandle, CCDB},
+};
+use gluex_core::{Id, RunNumber};
+use gluex_lumi::cli::ConnectionArgs;
+use gluex_rcdb::{conditions::Expr, context::Context, database::RCDB};
+use serde_json::{json, Value as JsonValue};
+
+use crate::filter;
+
+const NEW_ASSIGNMENTS_SQL: &str = "SELECT a.id, a.created, v.name, rr.runMin, rr.runMax,
+        cs.constantTypeId, COALESCE(a.comment, '')
+    FROM assignments a
+    JOIN variations v ON v.id = a.variationId
+    JOIN runRanges rr ON rr.id = a.runRangeId
+    JOIN constantSets cs ON cs.id = a.constantSetId
+    WHERE a.id > ?1
+    ORDER BY a.id";
+
+#[derive(Args)]
+pub(crate) struct WatchArgs {
+    /// Only report runs matching this RCDB filter (e.g. `@is_production and event_count > 1000000`)
+    #[arg(long)]
+    filter: Option<String>,
+
+    /// Only report assignments to this CCDB table or directory (repeatable; directories cover every
+    /// table below them)
+    #[arg(long = "table", value_name = "PATH")]
+    tables: Vec<String>,
+
+    /// Seconds between checks
+    #[arg(long, default_value_t = 30)]
+    interval: u64,
+
+    /// Shell command to run for every event, with its fields in `GLUEX_*` environment variables
+    #[arg(long, value_name = "COMMAND")]
+    exec: Option<String>,
+
+    /// Update the snapshots from the Jefferson Lab servers (as `gluex sync` does) before every check
+    #[cfg(feature = "sync")]
+    #[arg(long)]
+    sync: bool,
+}
+
+/// Something that appeared in a database since the previous check.
+enum Event {
+    Run(RunNumber),
+    Assignment {
+        id: Id,
+        created: String,
+        table: String,
+        variation: String,
+        run_min: RunNumber,
+        run_max: RunNumber,
+        comment: String,
+    },
+}
+
+impl Event {
+    fn to_json(&self) -> JsonValue {
+        match self {
+            Event::Run(run) => json!({ "event": "run", "run": run }),
+            Event::Assignment {
+                id,
+                created,
+                table,
+                variation,
+                run_min,
+                run_max,
+                comment,
+            } => json!({
+                "event": "assignment",
+                "id": id,
+                "created": created,
+                "table": table,
+                "variation": variation,
+                "run_min": run_min,
+                "run_max": run_max,
+                "comment": comment,
+            }),
+        }
+    }
+
+    fn env(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Event::Run(run) => vec![
+                ("GLUEX_EVENT", "run".into()),
+                ("GLUEX_RUN", run.to_string()),
+            ],
+            Event::Assignment {
+                id,
+                created,
+                table,
+                variation,
+                run_min,
+                run_max,
+                comment,
+            } => vec![
+                ("GLUEX_EVENT", "assignment".into()),
+                ("GLUEX_ASSIGNMENT_ID", id.to_string()),
+                ("GLUEX_CREATED", created.clone()),
+                ("GLUEX_TABLE", table.clone()),
+                ("GLUEX_VARIATION", variation.clone()),
+                ("GLUEX_RUN_MIN", run_min.to_string()),
+                ("GLUEX_RUN_MAX", run_max.to_string()),
+                ("GLUEX_COMMENT", comment.clone()),
+            ],
+        }
+    }
+
+    fn emit(&self, json: bool, exec: Option<&str>) {
+        if json {
+            println!("{}", self.to_json());
+        } else {
+            match self {
+                Event::Run(run) => println!("run\t{run}"),
+                Event::Assignment {
+                    id,
+                    table,
+                    variation,
+                    run_min,
+                    run_max,
+                    ..
+                } => println!("assignment\t{id}\t{table}\t{variation}\t{run_min}-{run_max}"),
+            }
+        }
+        if let Some(command) = exec {
+            match process::Command::new("sh")
+                .arg("-c")
+                .arg(command)
+                .envs(self.env())
+                .status()
+            {
+                Ok(status) if !status.success() => eprintln!("`{command}` exited with {status}"),
+                Ok(_) => {}
+                Err(err) => eprintln!("cannot run `{command}`: {err}"),
+            }
+        }
+    }
+}
+
+fn modified(path: &Path) -> io::Result<SystemTime> {
+    fs::metadata(path)?.modified()
+}
+
+/// Watches RCDB for new runs matching an optional filter.
+struct RunWatcher {
+    path: PathBuf,
+    filter: Option<String>,
+    modified: SystemTime,
+    since: RunNumber,
+    reported: BTreeSet<RunNumber>,
+}
+
+impl RunWatcher {
+    fn new(path: PathBuf, filter: Option<String>) -> Result<Self, Box<dyn Error>> {
+        let db = RCDB::open(&path)?;
+        // reject a bad filter before the first poll
+        if let Some(filter) = &filter {
+            parse_filter(&db, filter)?;
+        }
+        let since = db
+            .fetch_runs(&Context::default())?
+            .last()
+            .copied()
+            .unwrap_or(0);
+        Ok(Self {
+            modified: modified(&path)?,
+            path,
+            filter,
+            since,
+            reported: BTreeSet::new(),
+        })
+    }
+
+    fn poll(&mut self, force: bool) -> Result<Vec<Event>, Box<dyn Error>> {
+        let modified = modified(&self.path)?;
+        if !force && modified == self.modified {
+            return Ok(Vec::new());
+        }
+        self.modified = modified;
+        let db = RCDB::open(&self.path)?;
+        let mut context = Context::default().with_run_range(self.since + 1..);
+        if let Some(filter) = &self.filter {
+            context = context.filter(parse_filter(&db, filter)?);
+        }
+        Ok(db
+            .fetch_runs(&context)?
+            .into_iter()
+            .filter(|run| self.reported.insert(*run))
+            .map(Event::Run)
+            .collect())
+    }
+}
+
+fn parse_filter(db: &RCDB, filter: &str) -> Result<Expr, filter::FilterError> {
+    let type_of = |name: &str| db.condition_type(name).map(|meta| meta.value_type());
+    filter::parse(filter, &type_of)
+}
+
+/// Watches CCDB for new assignments, optionally restricted to some tables.
+struct AssignmentWatcher {
+    path: PathBuf,
+    tables: Vec<String>,
+    modified: SystemTime,
+    last_id: Id,
+}
+
+/// Collects the full path of every table below `dir`, keyed by table id.
+fn collect_tables(dir: &DirectoryHandle, tables: &mut BTreeMap<Id, String>) {
+    for table in dir.tables() {
+        tables.insert(table.id(), table.full_path());
+    }
+    for child in dir.dirs() {
+        collect_tables(&child, tables);
+    }
+}
+
+impl AssignmentWatcher {
+    fn new(path: PathBuf, tables: Vec<String>) -> Result<Self, Box<dyn Error>> {
+        let db = CCDB::open(&path)?;
+        for table in &tables {
+            if db.table(table).is_err() {
+                db.dir(table)?;
+            }
+        }
+        let mut last_id = 0;
+        db.backend().query(
+            "SELECT COALESCE(MAX(id), 0) FROM assignments",
+            &[],
+            None,
+            &mut |row| {
+                last_id = row.get(0)?;
+                Ok(())
+            },
+        )?;
+        Ok(Self {
+            modified: modified(&path)?,
+            path,
+            tables,
+            last_id,
+        })
+    }
+
+    fn watches(&self, table: &str) -> bool {
+        self.tables.is_empty()
+            || self.tables.iter().any(|prefix| {
+                let prefix = prefix.trim_end_matches('/');
+                table == prefix
+                    || table
+                        .strip_prefix(prefix)
+                        .is_some_and(|rest| rest.starts_with('/'))
+            })
+    }
+
+    fn poll(&mut self, force: bool) -> Result<Vec<Event>, Box<dyn Error>> {
+        let modified = modified(&self.path)?;
+        if !force && modified == self.modified {
+            return Ok(Vec::new());
+        }
+        self.modified = modified;
+        let db = CCDB::open(&self.path)?;
+        let mut tables = BTreeMap::new();
+        collect_tables(&db.root(), &mut tables);
+        let mut rows = Vec::new();
+        db.backend().query(
+            NEW_ASSIGNMENTS_SQL,
+            &[SqlValue::Integer(self.last_id)],
+            None,
+            &mut |row| {
+                rows.push((
+                    row.get::<Id>(0)?,
+                    row.get::<String>(1)?,
+                    row.get::<String>(2)?,
+                    row.get::<RunNumber>(3)?,
+                    row.get::<RunNumber>(4)?,
+                    row.get::<Id>(5)?,
+                    row.get::<String>(6)?,
+                ));
+                Ok(())
+            },
+        )?;
+        let mut events = Vec::new();
+        for (id, created, variation, run_min, run_max, table_id, comment) in rows {
+            self.last_id = self.last_id.max(id);
+            let Some(table) = tables.get(&table_id) else {
+                continue;
+            };
+            if self.watches(table) {
+                events.push(Event::Assignment {
+                    id,
+                    created,
+                    table: table.clone(),
+                    variation,
+                    run_min,
+                    run_max,
+                    comment,
+                });
+            }
+        }
+        Ok(events)
+    }
+}
+
+pub(crate) fn run(
+    args: &WatchArgs,
+    connections: &ConnectionArgs,
+    json: bool,
+) -> Result<(), Box<dyn Error>> {
+    #[cfg(feature = "sync")]
+    let sync = args.sync;
+    #[cfg(not(feature = "sync"))]
+    let sync = false;
+    let mut runs = if connections.rcdb.is_some() || args.filter.is_some() {
+        let path = connections.rcdb_path()?.to_path_buf();
+        Some(RunWatcher::new(path, args.filter.clone())?)
+    } else {
+        None
+    };
+    let mut assignments = if connections.ccdb.is_some() || !args.tables.is_empty() {
+        let path = connections.ccdb_path()?.to_path_buf();
+        Some(AssignmentWatcher::new(path, args.tables.clone())?)
+    } else {
+        None
+    };
+    if runs.is_none() && assignments.is_none() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "nothing to watch: pass --rcdb and/or --ccdb (or set RCDB_CONNECTION/CCDB_CONNECTION)",
+        )
+        .into());
+    }
+    if !json {
+        eprintln!(
+            "watching{}{} every {}s",
+            runs.as_ref()
+                .map(|w| format!(" {} (after run {})", w.path.display(), w.since))
+                .unwrap_or_default(),
+            assignments
+                .as_ref()
+                .map(|w| format!(" {} (after assignment {})", w.path.display(), w.last_id))
+                .unwrap_or_default(),
+            args.interval
+        );
+    }
+    loop {
+        thread::sleep(Duration::from_secs(args.interval));
+        if sync {
+            sync_snapshots(runs.as_ref(), assignments.as_ref());
+        }
+        if let Some(watcher) = &mut runs {
+            match watcher.poll(sync) {
+                Ok(events) => events
+                    .iter()
+                    .for_each(|event| event.emit(json, args.exec.as_deref())),
+                Err(err) => eprintln!("checking {} failed: {err}", watcher.path.display()),
+            }
+        }
+        if let Some(watcher) = &mut assignments {
+            match watcher.poll(sync) {
+                Ok(events) => events
+                    .iter()
+                    .for_each(|event| event.emit(json, args.exec.as_deref())),
+                Err(err) => eprintln!("checking {} failed: {err}", watcher.path.display()),
+            }
+        }
+    }
+}
+
+#[cfg(feature = "sync")]
+fn sync_snapshots(runs: Option<&RunWatcher>, assignments: Option<&AssignmentWatcher>) {
+    use crate::{snapshot::Database, sync};
+    let targets = [
+        runs.map(|w| (Database::Rcdb, &w.path)),
+        assignments.map(|w| (Database::Ccdb, &w.path)),
+    ];
+    for (database, path) in targets.into_iter().flatten() {
+        if let Err(err) = sync::sync(database, sync::default_url(database), path) {
+            eprintln!("syncing {} failed: {err}", path.display());
+        }
+    }
+}
+
+#[cfg(not(feature = "sync"))]
+fn sync_snapshots(_: Option<&RunWatcher>, _: Option<&AssignmentWatcher>) {}
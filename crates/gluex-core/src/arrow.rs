@@ -0,0 +1,459 @@
+//! Minimal export of columnar data through the [Arrow C stream interface].
+//!
+//! This module implements just enough of the Arrow C data interface to hand a single record
+//! batch of flat columns to an Arrow consumer (pyarrow, polars, duckdb, ...) without depending on
+//! the `arrow` crate. The column buffers are moved into the exported arrays, so consumers read
+//! them in place and free them through the `release` callbacks.
+//!
+//! [Arrow C stream interface]: https://arrow.apache.org/docs/format/CStreamInterface.html
+
+use std::{
+    ffi::{c_char, c_int, c_void, CString},
+    ptr,
+};
+
+const ARROW_FLAG_NULLABLE: i64 = 2;
+const EINVAL: c_int = 22;
+
+/// Values stored in an exported column.
+#[derive(Debug, Clone)]
+pub enum ArrowValues {
+    /// Signed 32-bit integers (`int32`).
+    Int32(Vec<i32>),
+    /// Unsigned 32-bit integers (`uint32`).
+    UInt32(Vec<u32>),
+    /// Signed 64-bit integers (`int64`).
+    Int64(Vec<i64>),
+    /// Unsigned 64-bit integers (`uint64`).
+    UInt64(Vec<u64>),
+    /// Double-precision floats (`float64`).
+    Float64(Vec<f64>),
+    /// Booleans, packed into a bitmap on export (`bool`).
+    Boolean(Vec<bool>),
+    /// UTF-8 strings (`utf8`).
+    Utf8(Vec<String>),
+    /// Microseconds since the Unix epoch in UTC (`timestamp[us, tz=UTC]`).
+    TimestampMicros(Vec<i64>),
+}
+
+impl ArrowValues {
+    /// Number of values in the column.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Int32(v) => v.len(),
+            Self::UInt32(v) => v.len(),
+            Self::Int64(v) | Self::TimestampMicros(v) => v.len(),
+            Self::UInt64(v) => v.len(),
+            Self::Float64(v) => v.len(),
+            Self::Boolean(v) => v.len(),
+            Self::Utf8(v) => v.len(),
+        }
+    }
+
+    /// Check if the column has no values.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn format(&self) -> &'static str {
+        match self {
+            Self::Int32(_) => "i",
+            Self::UInt32(_) => "I",
+            Self::Int64(_) => "l",
+            Self::UInt64(_) => "L",
+            Self::Float64(_) => "g",
+            Self::Boolean(_) => "b",
+            Self::Utf8(_) => "u",
+            Self::TimestampMicros(_) => "tsu:UTC",
+        }
+    }
+}
+
+/// Named column with optional validity (`false` marks a null entry).
+#[derive(Debug, Clone)]
+pub struct ArrowColumn {
+    name: String,
+    values: ArrowValues,
+    validity: Option<Vec<bool>>,
+}
+
+impl ArrowColumn {
+    /// Creates a column in which every value is valid.
+    pub fn new(name: impl Into<String>, values: ArrowValues) -> Self {
+        Self {
+            name: name.into(),
+            values,
+            validity: None,
+        }
+    }
+
+    /// Marks entries whose `validity` flag is `false` as null.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `validity` does not have one entry per value.
+    #[must_use]
+    pub fn with_validity(mut self, validity: Vec<bool>) -> Self {
+        assert_eq!(
+            validity.len(),
+            self.values.len(),
+            "validity length must match the number of values"
+        );
+        self.validity = if validity.iter().all(|&valid| valid) {
+            None
+        } else {
+            Some(validity)
+        };
+        self
+    }
+
+    /// Column name.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Column values.
+    #[must_use]
+    pub fn values(&self) -> &ArrowValues {
+        &self.values
+    }
+
+    /// Splits the column into its name, values, and validity.
+    #[cfg(feature = "columnar")]
+    pub(crate) fn into_parts(self) -> (String, ArrowValues, Option<Vec<bool>>) {
+        (self.name, self.values, self.validity)
+    }
+}
+
+/// `ArrowSchema` from the Arrow C data interface.
+#[repr(C)]
+#[derive(Debug)]
+pub struct ArrowSchema {
+    format: *const c_char,
+    name: *const c_char,
+    metadata: *const c_char,
+    flags: i64,
+    n_children: i64,
+    children: *mut *mut ArrowSchema,
+    dictionary: *mut ArrowSchema,
+    release: Option<unsafe extern "C" fn(*mut ArrowSchema)>,
+    private_data: *mut c_void,
+}
+
+/// `ArrowArray` from the Arrow C data interface.
+#[repr(C)]
+#[derive(Debug)]
+pub struct ArrowArray {
+    length: i64,
+    null_count: i64,
+    offset: i64,
+    n_buffers: i64,
+    n_children: i64,
+    buffers: *mut *const c_void,
+    children: *mut *mut ArrowArray,
+    dictionary: *mut ArrowArray,
+    release: Option<unsafe extern "C" fn(*mut ArrowArray)>,
+    private_data: *mut c_void,
+}
+
+/// `ArrowArrayStream` from the Arrow C stream interface.
+///
+/// The stream yields a single record batch. Dropping the stream releases it unless a consumer has
+/// already taken ownership (by copying the struct and clearing `release` in the original).
+#[repr(C)]
+#[derive(Debug)]
+pub struct ArrowArrayStream {
+    get_schema: Option<unsafe extern "C" fn(*mut ArrowArrayStream, *mut ArrowSchema) -> c_int>,
+    get_next: Option<unsafe extern "C" fn(*mut ArrowArrayStream, *mut ArrowArray) -> c_int>,
+    get_last_error: Option<unsafe extern "C" fn(*mut ArrowArrayStream) -> *const c_char>,
+    release: Option<unsafe extern "C" fn(*mut ArrowArrayStream)>,
+    private_data: *mut c_void,
+}
+
+// SAFETY: the stream exclusively owns its private data, which holds only owned buffers, and the
+// C stream interface allows moving a stream between threads as long as it is not shared.
+unsafe impl Send for ArrowArrayStream {}
+
+impl ArrowArrayStream {
+    /// Exports `columns` as a stream holding one record batch (a struct array of the columns).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the columns have different lengths.
+    #[must_use]
+    pub fn new(columns: Vec<ArrowColumn>) -> Self {
+        let n_rows = columns.first().map_or(0, |column| column.values.len());
+        assert!(
+            columns.iter().all(|column| column.values.len() == n_rows),
+            "all columns must have the same length"
+        );
+        let private = Box::new(StreamPrivate {
+            fields: columns
+                .iter()
+                .map(|column| (column.name.clone(), column.values.format()))
+                .collect(),
+            batch: Some((n_rows, columns)),
+        });
+        Self {
+            get_schema: Some(stream_get_schema),
+            get_next: Some(stream_get_next),
+            get_last_error: Some(stream_get_last_error),
+            release: Some(stream_release),
+            private_data: Box::into_raw(private).cast(),
+        }
+    }
+}
+
+impl Drop for ArrowArrayStream {
+    fn drop(&mut self) {
+        if let Some(release) = self.release {
+            // SAFETY: `release` is only set while `private_data` points to our `StreamPrivate`.
+            unsafe { release(self) };
+        }
+    }
+}
+
+struct StreamPrivate {
+    fields: Vec<(String, &'static str)>,
+    batch: Option<(usize, Vec<ArrowColumn>)>,
+}
+
+struct SchemaPrivate {
+    format: CString,
+    name: CString,
+    children: Vec<*mut ArrowSchema>,
+}
+
+fn to_cstring(value: &str) -> CString {
+    CString::new(value.replace('\0', "")).unwrap_or_default()
+}
+
+fn new_schema(format: &str, name: &str, flags: i64, children: Vec<ArrowSchema>) -> ArrowSchema {
+    let mut private = Box::new(SchemaPrivate {
+        format: to_cstring(format),
+        name: to_cstring(name),
+        children: children
+            .into_iter()
+            .map(|child| Box::into_raw(Box::new(child)))
+            .collect(),
+    });
+    ArrowSchema {
+        format: private.format.as_ptr(),
+        name: private.name.as_ptr(),
+        metadata: ptr::null(),
+        flags,
+        n_children: private.children.len() as i64,
+        children: private.children.as_mut_ptr(),
+        dictionary: ptr::null_mut(),
+        release: Some(schema_release),
+        private_data: Box::into_raw(private).cast(),
+    }
+}
+
+unsafe extern "C" fn schema_release(schema: *mut ArrowSchema) {
+    let Some(schema) = (unsafe { schema.as_mut() }) else {
+        return;
+    };
+    // SAFETY: `private_data` was created from a boxed `SchemaPrivate` in `new_schema`.
+    let private = unsafe { Box::from_raw(schema.private_data.cast::<SchemaPrivate>()) };
+    for child in private.children {
+        // SAFETY: each child was boxed in `new_schema`; consumers may only clear `release`.
+        let mut child = unsafe { Box::from_raw(child) };
+        if let Some(release) = child.release {
+            unsafe { release(&raw mut *child) };
+        }
+    }
+    schema.release = None;
+}
+
+enum Buffer {
+    Bytes(Vec<u8>),
+    I32(Vec<i32>),
+    U32(Vec<u32>),
+    I64(Vec<i64>),
+    U64(Vec<u64>),
+    F64(Vec<f64>),
+}
+
+impl Buffer {
+    fn as_ptr(&self) -> *const c_void {
+        match self {
+            Self::Bytes(v) => v.as_ptr().cast(),
+            Self::I32(v) => v.as_ptr().cast(),
+            Self::U32(v) => v.as_ptr().cast(),
+            Self::I64(v) => v.as_ptr().cast(),
+            Self::U64(v) => v.as_ptr().cast(),
+            Self::F64(v) => v.as_ptr().cast(),
+        }
+    }
+}
+
+struct ArrayPrivate {
+    _buffers: Vec<Option<Buffer>>,
+    buffer_ptrs: Vec<*const c_void>,
+    children: Vec<*mut ArrowArray>,
+}
+
+fn pack_bits(bits: &[bool]) -> Vec<u8> {
+    let mut packed = vec![0u8; bits.len().div_ceil(8)];
+    for (index, _) in bits.iter().enumerate().filter(|(_, &bit)| bit) {
+        packed[index / 8] |= 1 << (index % 8);
+    }
+    packed
+}
+
+fn new_array(
+    length: usize,
+    null_count: usize,
+    buffers: Vec<Option<Buffer>>,
+    children: Vec<ArrowArray>,
+) -> ArrowArray {
+    let buffer_ptrs = buffers
+        .iter()
+        .map(|buffer| buffer.as_ref().map_or(ptr::null(), Buffer::as_ptr))
+        .collect();
+    let mut private = Box::new(ArrayPrivate {
+        _buffers: buffers,
+        buffer_ptrs,
+        children: children
+            .into_iter()
+            .map(|child| Box::into_raw(Box::new(child)))
+            .collect(),
+    });
+    ArrowArray {
+        length: length as i64,
+        null_count: null_count as i64,
+        offset: 0,
+        n_buffers: private.buffer_ptrs.len() as i64,
+        n_children: private.children.len() as i64,
+        buffers: private.buffer_ptrs.as_mut_ptr(),
+        children: private.children.as_mut_ptr(),
+        dictionary: ptr::null_mut(),
+        release: Some(array_release),
+        private_data: Box::into_raw(private).cast(),
+    }
+}
+
+fn column_array(column: ArrowColumn) -> ArrowArray {
+    let length = column.values.len();
+    let null_count = column.validity.as_ref().map_or(0, |validity| {
+        validity.iter().filter(|&&valid| !valid).count()
+    });
+    let validity = column
+        .validity
+        .as_deref()
+        .map(|validity| Buffer::Bytes(pack_bits(validity)));
+    let buffers = match column.values {
+        ArrowValues::Int32(v) => vec![validity, Some(Buffer::I32(v))],
+        ArrowValues::UInt32(v) => vec![validity, Some(Buffer::U32(v))],
+        ArrowValues::Int64(v) | ArrowValues::TimestampMicros(v) => {
+            vec![validity, Some(Buffer::I64(v))]
+        }
+        ArrowValues::UInt64(v) => vec![validity, Some(Buffer::U64(v))],
+        ArrowValues::Float64(v) => vec![validity, Some(Buffer::F64(v))],
+        ArrowValues::Boolean(v) => vec![validity, Some(Buffer::Bytes(pack_bits(&v)))],
+        ArrowValues::Utf8(v) => {
+            let mut offsets = Vec::with_capacity(v.len() + 1);
+            let mut data = Vec::with_capacity(v.iter().map(String::len).sum());
+            offsets.push(0i32);
+            for value in &v {
+                data.extend_from_slice(value.as_bytes());
+                offsets.push(i32::try_from(data.len()).unwrap_or(i32::MAX));
+            }
+            vec![
+                validity,
+                Some(Buffer::I32(offsets)),
+                Some(Buffer::Bytes(data)),
+            ]
+        }
+    };
+    new_array(length, null_count, buffers, Vec::new())
+}
+
+unsafe extern "C" fn array_release(array: *mut ArrowArray) {
+    let Some(array) = (unsafe { array.as_mut() }) else {
+        return;
+    };
+    // SAFETY: `private_data` was created from a boxed `ArrayPrivate` in `new_array`.
+    let private = unsafe { Box::from_raw(array.private_data.cast::<ArrayPrivate>()) };
+    for child in &private.children {
+        // SAFETY: each child was boxed in `new_array`; consumers may only clear `release`.
+        let mut child = unsafe { Box::from_raw(*child) };
+        if let Some(release) = child.release {
+            unsafe { release(&raw mut *child) };
+        }
+    }
+    array.release = None;
+}
+
+unsafe extern "C" fn stream_get_schema(
+    stream: *mut ArrowArrayStream,
+    out: *mut ArrowSchema,
+) -> c_int {
+    // SAFETY: the consumer passes a live stream created by `ArrowArrayStream::new`.
+    let Some(private) = (unsafe { stream.as_ref() })
+        .and_then(|stream| unsafe { stream.private_data.cast::<StreamPrivate>().as_ref() })
+    else {
+        return EINVAL;
+    };
+    let children = private
+        .fields
+        .iter()
+        .map(|(name, format)| new_schema(format, name, ARROW_FLAG_NULLABLE, Vec::new()))
+        .collect();
+    // SAFETY: `out` points to writable, uninitialized schema storage owned by the consumer.
+    unsafe { out.write(new_schema("+s", "", 0, children)) };
+    0
+}
+
+unsafe extern "C" fn stream_get_next(stream: *mut ArrowArrayStream, out: *mut ArrowArray) -> c_int {
+    // SAFETY: the consumer passes a live stream created by `ArrowArrayStream::new`.
+    let Some(private) = (unsafe { stream.as_mut() })
+        .and_then(|stream| unsafe { stream.private_data.cast::<StreamPrivate>().as_mut() })
+    else {
+        return EINVAL;
+    };
+    let array = match private.batch.take() {
+        Some((n_rows, columns)) => new_array(
+            n_rows,
+            0,
+            vec![None],
+            columns.into_iter().map(column_array).collect(),
+        ),
+        // A released array marks the end of the stream.
+        None => ArrowArray {
+            length: 0,
+            null_count: 0,
+            offset: 0,
+            n_buffers: 0,
+            n_children: 0,
+            buffers: ptr::null_mut(),
+            children: ptr::null_mut(),
+            dictionary: ptr::null_mut(),
+            release: None,
+            private_data: ptr::null_mut(),
+        },
+    };
+    // SAFETY: `out` points to writable, uninitialized array storage owned by the consumer.
+    unsafe { out.write(array) };
+    0
+}
+
+unsafe extern "C" fn stream_get_last_error(_stream: *mut ArrowArrayStream) -> *const c_char {
+    ptr::null()
+}
+
+unsafe extern "C" fn stream_release(stream: *mut ArrowArrayStream) {
+    let Some(stream) = (unsafe { stream.as_mut() }) else {
+        return;
+    };
+    if !stream.private_data.is_null() {
+        // SAFETY: `private_data` was created from a boxed `StreamPrivate`.
+        drop(unsafe { Box::from_raw(stream.private_data.cast::<StreamPrivate>()) });
+        stream.private_data = ptr::null_mut();
+    }
+    stream.release = None;
+}
@@ -0,0 +1,523 @@
+//! Offline analysis bundles: trimmed CCDB and RCDB snapshots packed into a single file.
+//!
+//! A bundle (written by `gluex bundle create`) is a gzip-compressed tar archive with a `.gluex`
+//! extension holding a [`BundleManifest`] (`manifest.json`) followed by the trimmed databases
+//! (`ccdb.sqlite` and `rcdb.sqlite`). It can be copied to machines without access to the JLab
+//! databases and unpacked with `tar xzf`, but does not have to be: [`unbundled_path`] extracts one
+//! database into [`cache_dir`] on first use, and the `bundle` features of `gluex-ccdb` and
+//! `gluex-rcdb` route `CCDB::open`/`RCDB::open` through it, so a bundle path can be passed
+//! wherever a database path is expected. Every extraction checks the database against the SHA-256
+//! recorded in the manifest.
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+use flate2::{read::MultiGzDecoder, write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::{
+    compressed::{cache_dir, cache_entry, part_path, to_hex},
+    RunNumber,
+};
+
+/// File extension of bundles.
+pub const BUNDLE_EXTENSION: &str = "gluex";
+
+/// Name of the manifest inside a bundle.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Name of the CCDB snapshot inside a bundle.
+pub const CCDB_FILE: &str = "ccdb.sqlite";
+
+/// Name of the RCDB snapshot inside a bundle.
+pub const RCDB_FILE: &str = "rcdb.sqlite";
+
+/// Version of the bundle layout written by this build.
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+const BLOCK: usize = 512;
+
+/// Errors raised while writing or reading a bundle.
+#[derive(Error, Debug)]
+pub enum BundleError {
+    /// Reading the bundle or writing a file failed.
+    #[error("{path}: {source}")]
+    Io {
+        /// Bundle being read or written.
+        path: PathBuf,
+        /// Underlying error.
+        source: io::Error,
+    },
+    /// The file is not a valid bundle archive.
+    #[error("{path}: not a valid bundle ({reason})")]
+    InvalidArchive {
+        /// Bundle being read.
+        path: PathBuf,
+        /// What is wrong with it.
+        reason: String,
+    },
+    /// The manifest could not be read or written.
+    #[error("{path}: invalid bundle manifest: {source}")]
+    Manifest {
+        /// Bundle being read or written.
+        path: PathBuf,
+        /// Underlying error.
+        source: serde_json::Error,
+    },
+    /// The bundle was written in a newer layout than this build reads.
+    #[error("{path}: bundle format version {found} is newer than the supported version {BUNDLE_FORMAT_VERSION}")]
+    UnsupportedVersion {
+        /// Bundle being read.
+        path: PathBuf,
+        /// Format version declared by the manifest.
+        found: u32,
+    },
+    /// The bundle does not contain the requested database.
+    #[error("{path} does not contain {file}")]
+    MissingFile {
+        /// Bundle being read.
+        path: PathBuf,
+        /// Requested file.
+        file: String,
+    },
+    /// A file inside the bundle does not match the hash in the manifest.
+    #[error("{path}: SHA-256 mismatch for {file} (expected {expected}, found {found})")]
+    ChecksumMismatch {
+        /// Bundle being read.
+        path: PathBuf,
+        /// File inside the bundle.
+        file: String,
+        /// Hash listed in the manifest.
+        expected: String,
+        /// Hash of the file.
+        found: String,
+    },
+}
+
+/// Description of a bundle's contents.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleManifest {
+    /// Layout version of the bundle.
+    pub format_version: u32,
+    /// Time the bundle was created.
+    pub created: DateTime<Utc>,
+    /// First run of the selection.
+    pub run_min: RunNumber,
+    /// Last run of the selection.
+    pub run_max: RunNumber,
+    /// CCDB tables or directories kept in the CCDB snapshot.
+    pub tables: Vec<String>,
+    /// RCDB conditions kept in the RCDB snapshot (empty for every condition).
+    pub conditions: Vec<String>,
+    /// SHA-256 of every file in the bundle, by name.
+    pub files: BTreeMap<String, String>,
+}
+
+impl BundleManifest {
+    /// Creates a manifest for the runs `run_min..=run_max`, the given tables, and the given
+    /// conditions, timestamped now.
+    #[must_use]
+    pub fn new(
+        run_min: RunNumber,
+        run_max: RunNumber,
+        tables: Vec<String>,
+        conditions: Vec<String>,
+    ) -> Self {
+        Self {
+            format_version: BUNDLE_FORMAT_VERSION,
+            created: Utc::now(),
+            run_min,
+            run_max,
+            tables,
+            conditions,
+            files: BTreeMap::new(),
+        }
+    }
+}
+
+/// Returns true if `path` has the bundle extension.
+#[must_use]
+pub fn is_bundle(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == BUNDLE_EXTENSION)
+}
+
+/// Writes a bundle to `path` holding `manifest` and the given `(name, source file)` pairs, and
+/// returns the manifest with the hashes of the files filled in.
+///
+/// # Errors
+///
+/// Returns an error if a source file cannot be read, if one is too large for a tar entry, or if
+/// the bundle cannot be written.
+pub fn write_bundle(
+    path: &Path,
+    mut manifest: BundleManifest,
+    files: &[(&str, &Path)],
+) -> Result<BundleManifest, BundleError> {
+    let io_error = |source| BundleError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    manifest.files.clear();
+    for (name, source) in files {
+        let mut hasher = Sha256::new();
+        io::copy(
+            &mut BufReader::new(File::open(source).map_err(io_error)?),
+            &mut hasher,
+        )
+        .map_err(io_error)?;
+        manifest
+            .files
+            .insert((*name).to_string(), to_hex(&hasher.finalize()));
+    }
+    let json = serde_json::to_vec_pretty(&manifest).map_err(|source| BundleError::Manifest {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let mut archive = GzEncoder::new(
+        BufWriter::new(File::create(path).map_err(io_error)?),
+        Compression::default(),
+    );
+    let mtime = u64::try_from(manifest.created.timestamp()).unwrap_or_default();
+    write_entry(
+        &mut archive,
+        MANIFEST_FILE,
+        json.len() as u64,
+        mtime,
+        &mut &json[..],
+    )
+    .map_err(io_error)?;
+    for (name, source) in files {
+        let file = File::open(source).map_err(io_error)?;
+        let size = file.metadata().map_err(io_error)?.len();
+        write_entry(&mut archive, name, size, mtime, &mut BufReader::new(file))
+            .map_err(io_error)?;
+    }
+    archive.write_all(&[0; 2 * BLOCK]).map_err(io_error)?;
+    archive
+        .finish()
+        .and_then(|writer| writer.into_inner().map_err(io::IntoInnerError::into_error))
+        .and_then(|file| file.sync_all())
+        .map_err(io_error)?;
+    Ok(manifest)
+}
+
+/// Writes one regular file as a ustar entry.
+fn write_entry(
+    writer: &mut impl Write,
+    name: &str,
+    size: u64,
+    mtime: u64,
+    data: &mut impl Read,
+) -> io::Result<()> {
+    if name.len() > 100 || size >= 8 << 30 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{name} cannot be stored in a bundle"),
+        ));
+    }
+    let mut header = [0u8; BLOCK];
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    header[100..108].copy_from_slice(b"0000644\0");
+    header[108..116].copy_from_slice(b"0000000\0");
+    header[116..124].copy_from_slice(b"0000000\0");
+    header[124..136].copy_from_slice(format!("{size:011o}\0").as_bytes());
+    header[136..148].copy_from_slice(format!("{:011o}\0", mtime & 0o777_7777_7777).as_bytes());
+    header[156] = b'0';
+    header[257..265].copy_from_slice(b"ustar\x0000");
+    header[148..156].fill(b' ');
+    let checksum: u32 = header.iter().map(|&byte| u32::from(byte)).sum();
+    header[148..156].copy_from_slice(format!("{checksum:06o}\0 ").as_bytes());
+    writer.write_all(&header)?;
+    let copied = io::copy(&mut data.take(size), writer)?;
+    if copied != size {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            format!("{name} changed while it was bundled"),
+        ));
+    }
+    writer.write_all(&vec![0; padding(size)])
+}
+
+fn padding(size: u64) -> usize {
+    (BLOCK - usize::try_from(size % BLOCK as u64).unwrap_or_default()) % BLOCK
+}
+
+fn parse_octal(field: &[u8]) -> Option<u64> {
+    let text = std::str::from_utf8(field).ok()?;
+    let text = text.trim_matches(|c: char| c == '\0' || c == ' ');
+    if text.is_empty() {
+        return Some(0);
+    }
+    u64::from_str_radix(text, 8).ok()
+}
+
+/// Walks the regular files of a bundle, calling `visit` with each name and a reader over its
+/// contents; `visit` returns false to stop early.
+fn read_entries(
+    path: &Path,
+    mut visit: impl FnMut(&str, &mut dyn Read) -> Result<bool, BundleError>,
+) -> Result<(), BundleError> {
+    let io_error = |source| BundleError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    let invalid = |reason: &str| BundleError::InvalidArchive {
+        path: path.to_path_buf(),
+        reason: reason.to_string(),
+    };
+    let mut reader = MultiGzDecoder::new(BufReader::new(File::open(path).map_err(io_error)?));
+    let mut header = [0u8; BLOCK];
+    loop {
+        reader
+            .read_exact(&mut header)
+            .map_err(|err| match err.kind() {
+                io::ErrorKind::UnexpectedEof => invalid("truncated archive"),
+                io::ErrorKind::InvalidInput | io::ErrorKind::InvalidData => {
+                    invalid(&err.to_string())
+                }
+                _ => io_error(err),
+            })?;
+        if header.iter().all(|&byte| byte == 0) {
+            return Ok(());
+        }
+        let expected = parse_octal(&header[148..156]).ok_or_else(|| invalid("bad header"))?;
+        let found: u64 = header
+            .iter()
+            .enumerate()
+            .map(|(index, &byte)| {
+                if (148..156).contains(&index) {
+                    u64::from(b' ')
+                } else {
+                    u64::from(byte)
+                }
+            })
+            .sum();
+        if expected != found {
+            return Err(invalid("header checksum mismatch"));
+        }
+        let size = parse_octal(&header[124..136]).ok_or_else(|| invalid("bad entry size"))?;
+        let name_end = header[..100]
+            .iter()
+            .position(|&byte| byte == 0)
+            .unwrap_or(100);
+        let name = String::from_utf8_lossy(&header[..name_end]).into_owned();
+        let mut data = (&mut reader).take(size);
+        let keep_going = if matches!(header[156], b'0' | 0) {
+            visit(name.trim_start_matches("./"), &mut data)?
+        } else {
+            true
+        };
+        if !keep_going {
+            return Ok(());
+        }
+        io::copy(&mut data, &mut io::sink()).map_err(io_error)?;
+        if data.limit() > 0 {
+            return Err(invalid("truncated archive"));
+        }
+        io::copy(
+            &mut (&mut reader).take(padding(size) as u64),
+            &mut io::sink(),
+        )
+        .map_err(io_error)?;
+    }
+}
+
+fn parse_manifest(path: &Path, data: &mut dyn Read) -> Result<BundleManifest, BundleError> {
+    let manifest: BundleManifest =
+        serde_json::from_reader(data).map_err(|source| BundleError::Manifest {
+            path: path.to_path_buf(),
+            source,
+        })?;
+    if manifest.format_version > BUNDLE_FORMAT_VERSION {
+        return Err(BundleError::UnsupportedVersion {
+            path: path.to_path_buf(),
+            found: manifest.format_version,
+        });
+    }
+    Ok(manifest)
+}
+
+/// Copies `data` to `writer`, returning the SHA-256 of the copied bytes.
+fn copy_hashed(data: &mut dyn Read, writer: &mut impl Write) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let n = data.read(&mut buffer)?;
+        if n == 0 {
+            return Ok(to_hex(&hasher.finalize()));
+        }
+        hasher.update(&buffer[..n]);
+        writer.write_all(&buffer[..n])?;
+    }
+}
+
+fn check_hash(
+    path: &Path,
+    manifest: &BundleManifest,
+    file: &str,
+    found: String,
+) -> Result<(), BundleError> {
+    match manifest.files.get(file) {
+        Some(expected) if *expected == found => Ok(()),
+        Some(expected) => Err(BundleError::ChecksumMismatch {
+            path: path.to_path_buf(),
+            file: file.to_string(),
+            expected: expected.clone(),
+            found,
+        }),
+        None => Err(BundleError::InvalidArchive {
+            path: path.to_path_buf(),
+            reason: format!("{file} is not listed in the manifest"),
+        }),
+    }
+}
+
+/// Reads the manifest of the bundle at `path`.
+///
+/// # Errors
+///
+/// Returns an error if the file is not a bundle or its manifest is invalid.
+pub fn read_manifest(path: impl AsRef<Path>) -> Result<BundleManifest, BundleError> {
+    let path = path.as_ref();
+    let mut manifest = None;
+    read_entries(path, |name, data| {
+        if name == MANIFEST_FILE {
+            manifest = Some(parse_manifest(path, data)?);
+        }
+        Ok(manifest.is_none())
+    })?;
+    manifest.ok_or_else(|| BundleError::MissingFile {
+        path: path.to_path_buf(),
+        file: MANIFEST_FILE.to_string(),
+    })
+}
+
+/// Reads the manifest of the bundle at `path` and checks every file against its hash.
+///
+/// # Errors
+///
+/// Returns an error if the file is not a bundle, its manifest is invalid, or a file listed in the
+/// manifest is missing or does not match its hash.
+pub fn verify(path: impl AsRef<Path>) -> Result<BundleManifest, BundleError> {
+    let path = path.as_ref();
+    let mut manifest: Option<BundleManifest> = None;
+    let mut seen = Vec::new();
+    read_entries(path, |name, data| {
+        match &manifest {
+            None if name == MANIFEST_FILE => manifest = Some(parse_manifest(path, data)?),
+            None => {
+                return Err(BundleError::InvalidArchive {
+                    path: path.to_path_buf(),
+                    reason: "the manifest is not the first file".to_string(),
+                })
+            }
+            Some(manifest) => {
+                let found =
+                    copy_hashed(data, &mut io::sink()).map_err(|source| BundleError::Io {
+                        path: path.to_path_buf(),
+                        source,
+                    })?;
+                check_hash(path, manifest, name, found)?;
+                seen.push(name.to_string());
+            }
+        }
+        Ok(true)
+    })?;
+    let manifest = manifest.ok_or_else(|| BundleError::MissingFile {
+        path: path.to_path_buf(),
+        file: MANIFEST_FILE.to_string(),
+    })?;
+    if let Some(file) = manifest.files.keys().find(|file| !seen.contains(file)) {
+        return Err(BundleError::MissingFile {
+            path: path.to_path_buf(),
+            file: file.clone(),
+        });
+    }
+    Ok(manifest)
+}
+
+/// Returns a path to the database `file` ([`CCDB_FILE`] or [`RCDB_FILE`]) of the bundle at
+/// `path`.
+///
+/// Paths without the bundle extension are returned unchanged. The database of a bundle is
+/// extracted into [`cache_dir`] on first use (keyed by the bundle's path, size, and modification
+/// time) and checked against the hash in the manifest.
+///
+/// # Errors
+///
+/// Returns an error if the bundle cannot be read, does not contain `file`, or fails the hash
+/// check, or if the extracted copy cannot be written.
+pub fn unbundled_path(path: impl AsRef<Path>, file: &str) -> Result<PathBuf, BundleError> {
+    let path = path.as_ref();
+    if !is_bundle(path) {
+        return Ok(path.to_path_buf());
+    }
+    let io_error = |source| BundleError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    let stem = path
+        .file_stem()
+        .map_or_else(|| "bundle".into(), |stem| stem.to_string_lossy());
+    let database = file.strip_suffix(".sqlite").unwrap_or(file);
+    let target = cache_entry(path, &format!("{stem}-{database}")).map_err(io_error)?;
+    if target.is_file() {
+        return Ok(target);
+    }
+    fs::create_dir_all(target.parent().unwrap_or(&cache_dir())).map_err(io_error)?;
+    let part = part_path(&target);
+    let result = extract(path, file, &part).and_then(|()| {
+        fs::rename(&part, &target).map_err(io_error)?;
+        Ok(target)
+    });
+    if result.is_err() {
+        let _ = fs::remove_file(&part);
+    }
+    result
+}
+
+fn extract(path: &Path, file: &str, part: &Path) -> Result<(), BundleError> {
+    let io_error = |source| BundleError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    let mut manifest = None;
+    let mut extracted = false;
+    read_entries(path, |name, data| {
+        if name == MANIFEST_FILE {
+            manifest = Some(parse_manifest(path, data)?);
+        } else if name == file {
+            let manifest = manifest
+                .as_ref()
+                .ok_or_else(|| BundleError::InvalidArchive {
+                    path: path.to_path_buf(),
+                    reason: "the manifest is not the first file".to_string(),
+                })?;
+            let mut writer = BufWriter::new(File::create(part).map_err(io_error)?);
+            let found = copy_hashed(data, &mut writer).map_err(io_error)?;
+            writer
+                .into_inner()
+                .map_err(io::IntoInnerError::into_error)
+                .and_then(|file| file.sync_all())
+                .map_err(io_error)?;
+            check_hash(path, manifest, file, found)?;
+            extracted = true;
+        }
+        Ok(!extracted)
+    })?;
+    if extracted {
+        Ok(())
+    } else {
+        Err(BundleError::MissingFile {
+            path: path.to_path_buf(),
+            file: file.to_string(),
+        })
+    }
+}
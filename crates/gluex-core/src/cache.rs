@@ -0,0 +1,23 @@
+//! Location of the on-disk cache shared by the gluex crates.
+//!
+//! Decompressed snapshots, extracted bundles, and decoded CCDB vaults all live in subdirectories
+//! of [`cache_dir`], so pointing `GLUEX_CACHE_DIR` somewhere else moves (or isolates) all of them.
+use std::{env, path::PathBuf};
+
+/// Directory holding cached files: `$GLUEX_CACHE_DIR`, then `$XDG_CACHE_HOME/gluex`, then
+/// `~/.cache/gluex`, falling back to a `gluex` directory under the system temporary directory.
+#[must_use]
+pub fn cache_dir() -> PathBuf {
+    if let Some(dir) = env::var_os("GLUEX_CACHE_DIR").filter(|dir| !dir.is_empty()) {
+        return PathBuf::from(dir);
+    }
+    if let Some(dir) = env::var_os("XDG_CACHE_HOME").filter(|dir| !dir.is_empty()) {
+        return PathBuf::from(dir).join("gluex");
+    }
+    env::var_os("HOME")
+        .filter(|dir| !dir.is_empty())
+        .map_or_else(
+            || env::temp_dir().join("gluex"),
+            |home| PathBuf::from(home).join(".cache/gluex"),
+        )
+}
@@ -0,0 +1,382 @@
+//! Materialized columnar caches of CCDB tables and RCDB conditions.
+//!
+//! Analyses that make many passes over the same run selection can export the tables and
+//! conditions they use once (with `gluex materialize`) into a directory of Arrow IPC files and
+//! read those instead of querying `SQLite` on every pass. A cache directory holds a
+//! [`CacheManifest`] (`manifest.json`) describing the selection, one file per CCDB table below
+//! `ccdb/` (in long format, with a `run` and a `row` column followed by the table's columns), and
+//! an `rcdb.arrow` file with a `run` column and one nullable column per condition.
+//!
+//! The files are uncompressed, so [`ColumnarFile::open`] memory-maps them and hands out arrays
+//! that point into the mapping instead of copying them. pyarrow (`pyarrow.ipc.open_file`), polars
+//! (`polars.read_ipc`), and duckdb read the same files directly.
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+    ptr::NonNull,
+    sync::Arc,
+};
+
+pub use arrow_array;
+pub use arrow_schema;
+
+use arrow_array::{
+    ArrayRef, BooleanArray, Float64Array, Int32Array, Int64Array, RecordBatch, StringArray,
+    TimestampMicrosecondArray, UInt32Array, UInt64Array,
+};
+use arrow_buffer::{Buffer, NullBuffer, OffsetBuffer};
+use arrow_ipc::{
+    convert::fb_to_schema,
+    reader::{read_footer_length, FileDecoder},
+    root_as_footer,
+    writer::FileWriter,
+};
+use arrow_schema::{ArrowError, Field, Schema};
+use arrow_select::concat::concat_batches;
+use memmap2::Mmap;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    arrow::{ArrowColumn, ArrowValues},
+    RunNumber,
+};
+
+/// Name of the manifest file inside a cache directory.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Name of the RCDB conditions file inside a cache directory.
+pub const CONDITIONS_FILE: &str = "rcdb.arrow";
+
+/// Errors raised while writing or reading a columnar cache.
+#[derive(Error, Debug)]
+pub enum ColumnarError {
+    /// Reading or writing a cache file failed.
+    #[error("{0}")]
+    Io(#[from] io::Error),
+    /// Encoding or decoding Arrow data failed.
+    #[error("{0}")]
+    Arrow(#[from] ArrowError),
+    /// The manifest could not be read or written.
+    #[error("invalid cache manifest: {0}")]
+    Manifest(#[from] serde_json::Error),
+    /// The table is not part of the cache.
+    #[error("table {0} was not materialized in this cache")]
+    TableNotMaterialized(String),
+    /// The cache holds no RCDB conditions.
+    #[error("no RCDB conditions were materialized in this cache")]
+    ConditionsNotMaterialized,
+    /// The table path cannot be turned into a file name inside the cache.
+    #[error("invalid table path {0}")]
+    InvalidTablePath(String),
+}
+
+/// Description of what a cache directory holds.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheManifest {
+    /// Runs the cache was materialized for.
+    pub runs: Vec<RunNumber>,
+    /// CCDB variation the tables were resolved in.
+    pub variation: Option<String>,
+    /// CCDB timestamp the tables were resolved at, if one was given.
+    pub timestamp: Option<String>,
+    /// CCDB table paths and their files, relative to the cache directory.
+    pub tables: BTreeMap<String, String>,
+    /// RCDB condition names in the conditions file.
+    pub conditions: Vec<String>,
+}
+
+/// A cache directory, either being written or opened for reading.
+#[derive(Debug, Clone)]
+pub struct ColumnarCache {
+    dir: PathBuf,
+    manifest: CacheManifest,
+}
+
+impl ColumnarCache {
+    /// Creates (or reuses) the directory `dir` for a new cache described by `manifest`.
+    ///
+    /// The manifest's tables and conditions are filled in as they are written; nothing is
+    /// readable with [`ColumnarCache::open`] until [`ColumnarCache::save`] is called.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory cannot be created.
+    pub fn create(dir: impl Into<PathBuf>, manifest: CacheManifest) -> Result<Self, ColumnarError> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            manifest: CacheManifest {
+                tables: BTreeMap::new(),
+                conditions: Vec::new(),
+                ..manifest
+            },
+        })
+    }
+
+    /// Opens the cache in `dir`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the manifest is missing or invalid.
+    pub fn open(dir: impl Into<PathBuf>) -> Result<Self, ColumnarError> {
+        let dir = dir.into();
+        let manifest =
+            serde_json::from_reader(io::BufReader::new(File::open(dir.join(MANIFEST_FILE))?))?;
+        Ok(Self { dir, manifest })
+    }
+
+    /// Directory of the cache.
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Description of the cache's contents.
+    #[must_use]
+    pub fn manifest(&self) -> &CacheManifest {
+        &self.manifest
+    }
+
+    /// Writes the columns of the CCDB table `table` and adds it to the manifest.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table path is invalid or the file cannot be written.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the columns have different lengths.
+    pub fn write_table(
+        &mut self,
+        table: &str,
+        columns: Vec<ArrowColumn>,
+    ) -> Result<(), ColumnarError> {
+        let file = table_file(table)?;
+        let path = self.dir.join(&file);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        write_ipc(&path, columns)?;
+        self.manifest.tables.insert(table.to_string(), file);
+        Ok(())
+    }
+
+    /// Writes the RCDB conditions file (a `run` column followed by one column per condition) and
+    /// records the condition names in the manifest.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the columns have different lengths.
+    pub fn write_conditions(&mut self, columns: Vec<ArrowColumn>) -> Result<(), ColumnarError> {
+        self.manifest.conditions = columns
+            .iter()
+            .map(|column| column.name().to_string())
+            .filter(|name| name != "run")
+            .collect();
+        write_ipc(&self.dir.join(CONDITIONS_FILE), columns)
+    }
+
+    /// Writes the manifest, making the cache readable.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the manifest cannot be written.
+    pub fn save(&self) -> Result<(), ColumnarError> {
+        let manifest = serde_json::to_string_pretty(&self.manifest)?;
+        fs::write(self.dir.join(MANIFEST_FILE), manifest)?;
+        Ok(())
+    }
+
+    /// Memory-maps the file of the CCDB table `table`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ColumnarError::TableNotMaterialized`] if the table is not in the cache, or an
+    /// error if its file cannot be read.
+    pub fn table(&self, table: &str) -> Result<ColumnarFile, ColumnarError> {
+        let file = self
+            .manifest
+            .tables
+            .get(table)
+            .ok_or_else(|| ColumnarError::TableNotMaterialized(table.to_string()))?;
+        ColumnarFile::open(self.dir.join(file))
+    }
+
+    /// Memory-maps the RCDB conditions file.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ColumnarError::ConditionsNotMaterialized`] if the cache holds no conditions, or
+    /// an error if the file cannot be read.
+    pub fn conditions(&self) -> Result<ColumnarFile, ColumnarError> {
+        if self.manifest.conditions.is_empty() {
+            return Err(ColumnarError::ConditionsNotMaterialized);
+        }
+        ColumnarFile::open(self.dir.join(CONDITIONS_FILE))
+    }
+}
+
+/// File of a CCDB table inside a cache directory: `ccdb/<path>.arrow`.
+fn table_file(table: &str) -> Result<String, ColumnarError> {
+    let relative = table.trim_matches('/');
+    if relative
+        .split('/')
+        .any(|segment| matches!(segment, "" | "." | ".."))
+    {
+        return Err(ColumnarError::InvalidTablePath(table.to_string()));
+    }
+    Ok(format!("ccdb/{relative}.arrow"))
+}
+
+/// A memory-mapped Arrow IPC file holding one record batch.
+#[derive(Debug, Clone)]
+pub struct ColumnarFile {
+    batch: RecordBatch,
+}
+
+impl ColumnarFile {
+    /// Memory-maps the Arrow IPC file at `path`; batches of files with more than one are
+    /// concatenated.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be mapped or is not a valid Arrow IPC file.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, ColumnarError> {
+        let file = File::open(path)?;
+        // SAFETY: the mapping is read-only; cache files are written once and not modified while
+        // they are read.
+        let mmap = Arc::new(unsafe { Mmap::map(&file)? });
+        let buffer = match NonNull::new(mmap.as_ptr().cast_mut()) {
+            // SAFETY: the pointer and length describe the mapping, which the buffer keeps alive.
+            Some(ptr) if !mmap.is_empty() => unsafe {
+                Buffer::from_custom_allocation(ptr, mmap.len(), mmap.clone())
+            },
+            _ => return Err(invalid_file("file is empty").into()),
+        };
+        let trailer_start = buffer
+            .len()
+            .checked_sub(10)
+            .ok_or_else(|| invalid_file("file is too short"))?;
+        let trailer: [u8; 10] = buffer[trailer_start..]
+            .try_into()
+            .map_err(|_| invalid_file("file is too short"))?;
+        let footer_start = trailer_start
+            .checked_sub(read_footer_length(trailer)?)
+            .ok_or_else(|| invalid_file("footer length exceeds the file size"))?;
+        let footer = root_as_footer(&buffer[footer_start..trailer_start])
+            .map_err(|err| invalid_file(&err.to_string()))?;
+        let schema = Arc::new(fb_to_schema(
+            footer
+                .schema()
+                .ok_or_else(|| invalid_file("footer has no schema"))?,
+        ));
+        let mut decoder = FileDecoder::new(schema.clone(), footer.version());
+        let block_data = |block: &arrow_ipc::Block| {
+            let offset = usize::try_from(block.offset()).ok();
+            let length = usize::try_from(block.metaDataLength())
+                .ok()
+                .zip(usize::try_from(block.bodyLength()).ok())
+                .map(|(meta, body)| meta + body);
+            match offset.zip(length) {
+                Some((offset, length)) if offset + length <= buffer.len() => {
+                    Ok(buffer.slice_with_length(offset, length))
+                }
+                _ => Err(invalid_file("block lies outside the file")),
+            }
+        };
+        for block in footer.dictionaries().iter().flatten() {
+            decoder.read_dictionary(block, &block_data(block)?)?;
+        }
+        let mut batches = Vec::new();
+        for block in footer.recordBatches().iter().flatten() {
+            if let Some(batch) = decoder.read_record_batch(block, &block_data(block)?)? {
+                batches.push(batch);
+            }
+        }
+        let batch = match batches.len() {
+            0 => RecordBatch::new_empty(schema),
+            1 => batches.remove(0),
+            _ => concat_batches(&schema, &batches)?,
+        };
+        Ok(Self { batch })
+    }
+
+    /// The record batch holding the file's columns.
+    #[must_use]
+    pub fn batch(&self) -> &RecordBatch {
+        &self.batch
+    }
+
+    /// Consumes the file, returning its record batch.
+    #[must_use]
+    pub fn into_batch(self) -> RecordBatch {
+        self.batch
+    }
+
+    /// Number of rows in the file.
+    #[must_use]
+    pub fn num_rows(&self) -> usize {
+        self.batch.num_rows()
+    }
+
+    /// The column named `name`, if present.
+    #[must_use]
+    pub fn column(&self, name: &str) -> Option<&ArrayRef> {
+        self.batch.column_by_name(name)
+    }
+}
+
+fn invalid_file(message: &str) -> ArrowError {
+    ArrowError::ParseError(format!("invalid Arrow IPC file: {message}"))
+}
+
+/// Writes `columns` to `path` as an Arrow IPC file holding one record batch.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be written.
+///
+/// # Panics
+///
+/// Panics if the columns have different lengths.
+pub fn write_ipc(path: &Path, columns: Vec<ArrowColumn>) -> Result<(), ColumnarError> {
+    let (fields, arrays): (Vec<Field>, Vec<ArrayRef>) =
+        columns.into_iter().map(column_to_array).unzip();
+    let schema = Arc::new(Schema::new(fields));
+    let batch = RecordBatch::try_new(schema.clone(), arrays)?;
+    let mut writer = FileWriter::try_new(io::BufWriter::new(File::create(path)?), &schema)?;
+    writer.write(&batch)?;
+    writer.finish()?;
+    Ok(())
+}
+
+fn column_to_array(column: ArrowColumn) -> (Field, ArrayRef) {
+    let (name, values, validity) = column.into_parts();
+    let nulls = validity.map(NullBuffer::from);
+    let array: ArrayRef = match values {
+        ArrowValues::Int32(v) => Arc::new(Int32Array::new(v.into(), nulls)),
+        ArrowValues::UInt32(v) => Arc::new(UInt32Array::new(v.into(), nulls)),
+        ArrowValues::Int64(v) => Arc::new(Int64Array::new(v.into(), nulls)),
+        ArrowValues::UInt64(v) => Arc::new(UInt64Array::new(v.into(), nulls)),
+        ArrowValues::Float64(v) => Arc::new(Float64Array::new(v.into(), nulls)),
+        ArrowValues::Boolean(v) => Arc::new(BooleanArray::new(v.into(), nulls)),
+        ArrowValues::Utf8(v) => Arc::new(StringArray::new(
+            OffsetBuffer::from_lengths(v.iter().map(String::len)),
+            Buffer::from(v.concat().into_bytes()),
+            nulls,
+        )),
+        ArrowValues::TimestampMicros(v) => {
+            Arc::new(TimestampMicrosecondArray::new(v.into(), nulls).with_timezone("UTC"))
+        }
+    };
+    let field = Field::new(name, array.data_type().clone(), true);
+    (field, array)
+}
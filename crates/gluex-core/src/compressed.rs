@@ -0,0 +1,350 @@
+//! Transparent decompression of compressed database snapshots.
+//!
+//! Database snapshots are usually shipped compressed (`ccdb.sqlite.gz` or `ccdb.sqlite.zst`).
+//! [`decompressed_path`]
+//! turns such a path into an uncompressed copy inside [`cache_dir`], decompressing it on first use
+//! and reusing it afterwards, so `CCDB::open`/`RCDB::open` can accept compressed files directly.
+//!
+//! Cached copies are keyed by the source path, size, and modification time, so replacing the
+//! compressed file triggers a fresh decompression. Every decompression is checked: the gzip
+//! CRC-32 and length trailer (or the zstd frame checksum, when the frame has one) must match, the
+//! result must start with the `SQLite` file header, and
+//! when a `<file>.sha256` sidecar (as written by `sha256sum`) sits next to the compressed file, the
+//! compressed bytes must hash to it. Copies are written to a temporary file unique to the writer
+//! and renamed into place, so an interrupted decompression is never reused and jobs that start
+//! together never interleave their writes.
+use std::{
+    fmt::Write as _,
+    fs::{self, File},
+    io::{self, BufRead, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+    time::UNIX_EPOCH,
+};
+
+use flate2::bufread::MultiGzDecoder;
+use ruzstd::decoding::{
+    errors::{FrameDecoderError, ReadFrameHeaderError},
+    BlockDecodingStrategy, FrameDecoder,
+};
+
+pub use crate::cache::cache_dir;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+/// First bytes of every `SQLite` database file.
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// Compression formats recognized by file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// gzip (`.gz`).
+    Gzip,
+    /// Zstandard (`.zst`).
+    Zstd,
+}
+
+impl Compression {
+    /// Detects the compression format from the extension of `path`.
+    #[must_use]
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "gz" => Some(Compression::Gzip),
+            "zst" => Some(Compression::Zstd),
+            _ => None,
+        }
+    }
+}
+
+/// Errors that can occur while decompressing a snapshot.
+#[derive(Error, Debug)]
+pub enum CompressedSnapshotError {
+    /// Reading the compressed file or writing the cached copy failed (including gzip CRC or
+    /// length mismatches, zstd checksum mismatches, and corrupt frames).
+    #[error("failed to decompress {path}: {source}")]
+    Io {
+        /// Compressed file being read.
+        path: PathBuf,
+        /// Underlying error.
+        source: io::Error,
+    },
+    /// The decompressed data is not an `SQLite` database.
+    #[error("{0} does not contain an SQLite database")]
+    NotSqlite(PathBuf),
+    /// The compressed file does not match the hash in its `.sha256` sidecar, or the cached copy
+    /// does not match the data decompressed into it.
+    #[error("{path}: SHA-256 mismatch (expected {expected}, found {found})")]
+    ChecksumMismatch {
+        /// Compressed file or cached copy.
+        path: PathBuf,
+        /// Hash listed in the sidecar, or of the decompressed data.
+        expected: String,
+        /// Hash of the file.
+        found: String,
+    },
+}
+
+/// Returns a path to an uncompressed copy of `path`.
+///
+/// Paths without a recognized compression extension are returned unchanged; compressed files are
+/// decompressed into [`cache_dir`] on first use. Each caller decompresses into its own temporary
+/// file, so processes starting on the same snapshot at once never write to the same file, and the
+/// copy is hashed again after it is renamed into place.
+///
+/// # Errors
+///
+/// This function returns an error if the compressed file cannot be read or decompressed, if it
+/// fails an integrity check, or if the cached copy cannot be written.
+pub fn decompressed_path(path: impl AsRef<Path>) -> Result<PathBuf, CompressedSnapshotError> {
+    let path = path.as_ref();
+    let io_error = |source| CompressedSnapshotError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    let Some(compression) = Compression::from_path(path) else {
+        return Ok(path.to_path_buf());
+    };
+    let target = cached_path(path).map_err(io_error)?;
+    if target.is_file() {
+        return Ok(target);
+    }
+    let dir = target.parent().unwrap_or(Path::new("."));
+    fs::create_dir_all(dir).map_err(io_error)?;
+    let part = part_path(&target);
+    let result = decompress(path, &part, compression).and_then(|expected| {
+        fs::rename(&part, &target).map_err(io_error)?;
+        let found = hash_file(&target).map_err(io_error)?;
+        if found != expected {
+            let _ = fs::remove_file(&target);
+            return Err(CompressedSnapshotError::ChecksumMismatch {
+                path: target,
+                expected,
+                found,
+            });
+        }
+        Ok(target)
+    });
+    if result.is_err() {
+        let _ = fs::remove_file(&part);
+    }
+    result
+}
+
+/// Temporary file next to `target` that no other process or thread writes to.
+pub(crate) fn part_path(target: &Path) -> PathBuf {
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    target.with_extension(format!(
+        "{}-{}.part",
+        std::process::id(),
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+/// SHA-256 of the file at `path`, in lowercase hex.
+fn hash_file(path: &Path) -> io::Result<String> {
+    let mut reader = Hashing::new(File::open(path)?);
+    io::copy(&mut reader, &mut io::sink())?;
+    Ok(to_hex(&reader.hasher.finalize()))
+}
+
+/// Location of the cached copy of `path` inside [`cache_dir`].
+fn cached_path(path: &Path) -> io::Result<PathBuf> {
+    let stem = path
+        .file_stem()
+        .map_or_else(|| "snapshot".into(), |stem| stem.to_string_lossy());
+    cache_entry(path, stem.strip_suffix(".sqlite").unwrap_or(&stem))
+}
+
+/// Location of an `SQLite` file named after `name` and derived from `path` inside [`cache_dir`],
+/// keyed by the source path, size, and modification time.
+pub(crate) fn cache_entry(path: &Path, name: &str) -> io::Result<PathBuf> {
+    let metadata = fs::metadata(path)?;
+    let modified = metadata
+        .modified()?
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let source = fs::canonicalize(path)?;
+    let key = to_hex(&Sha256::digest(format!(
+        "{}\n{}\n{}",
+        source.display(),
+        metadata.len(),
+        modified.as_nanos()
+    )));
+    Ok(cache_dir()
+        .join("snapshots")
+        .join(format!("{name}-{}.sqlite", &key[..16])))
+}
+
+/// Decompresses `path` into `part`, returning the SHA-256 of the decompressed data.
+fn decompress(
+    path: &Path,
+    part: &Path,
+    compression: Compression,
+) -> Result<String, CompressedSnapshotError> {
+    let io_error = |source| CompressedSnapshotError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    let mut reader = BufReader::new(Hashing::new(File::open(path).map_err(io_error)?));
+    let mut decoder: Box<dyn Read + '_> = match compression {
+        Compression::Gzip => Box::new(MultiGzDecoder::new(&mut reader)),
+        Compression::Zstd => Box::new(ZstdDecoder::new(&mut reader)),
+    };
+    let mut header = [0u8; SQLITE_HEADER.len()];
+    decoder.read_exact(&mut header).map_err(|err| {
+        if err.kind() == io::ErrorKind::UnexpectedEof {
+            CompressedSnapshotError::NotSqlite(path.to_path_buf())
+        } else {
+            io_error(err)
+        }
+    })?;
+    if &header != SQLITE_HEADER {
+        return Err(CompressedSnapshotError::NotSqlite(path.to_path_buf()));
+    }
+    let mut writer = BufWriter::new(Hashing::new(File::create(part).map_err(io_error)?));
+    writer.write_all(&header).map_err(io_error)?;
+    io::copy(&mut decoder, &mut writer).map_err(io_error)?;
+    let written = writer
+        .into_inner()
+        .map_err(io::IntoInnerError::into_error)
+        .map_err(io_error)?;
+    written.inner.sync_all().map_err(io_error)?;
+    let decompressed = to_hex(&written.hasher.finalize());
+    drop(decoder);
+    io::copy(&mut reader, &mut io::sink()).map_err(io_error)?;
+    let reader = reader.into_inner();
+    let mut sidecar = path.as_os_str().to_owned();
+    sidecar.push(".sha256");
+    if let Ok(contents) = fs::read_to_string(PathBuf::from(sidecar)) {
+        let expected = contents
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let found = to_hex(&reader.hasher.finalize());
+        if expected != found {
+            return Err(CompressedSnapshotError::ChecksumMismatch {
+                path: path.to_path_buf(),
+                expected,
+                found,
+            });
+        }
+    }
+    Ok(decompressed)
+}
+
+/// Decoder for a stream of zstd frames, skipping skippable frames and checking each frame's
+/// content checksum when it has one.
+struct ZstdDecoder<R> {
+    source: R,
+    frame: FrameDecoder,
+    in_frame: bool,
+}
+
+impl<R: BufRead> ZstdDecoder<R> {
+    fn new(source: R) -> Self {
+        Self {
+            source,
+            frame: FrameDecoder::new(),
+            in_frame: false,
+        }
+    }
+
+    /// Starts the next data frame, returning `false` at the end of the input.
+    fn next_frame(&mut self) -> io::Result<bool> {
+        loop {
+            if self.source.fill_buf()?.is_empty() {
+                return Ok(false);
+            }
+            match self.frame.reset(&mut self.source) {
+                Ok(()) => return Ok(true),
+                Err(FrameDecoderError::ReadFrameHeaderError(ReadFrameHeaderError::SkipFrame {
+                    length,
+                    ..
+                })) => {
+                    let skipped =
+                        io::copy(&mut (&mut self.source).take(length.into()), &mut io::sink())?;
+                    if skipped < u64::from(length) {
+                        return Err(io::ErrorKind::UnexpectedEof.into());
+                    }
+                }
+                Err(err) => return Err(io::Error::new(io::ErrorKind::InvalidData, err)),
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Read for ZstdDecoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if !self.in_frame {
+                if !self.next_frame()? {
+                    return Ok(0);
+                }
+                self.in_frame = true;
+            }
+            while self.frame.can_collect() < buf.len() && !self.frame.is_finished() {
+                let needed = buf.len() - self.frame.can_collect();
+                self.frame
+                    .decode_blocks(&mut self.source, BlockDecodingStrategy::UptoBytes(needed))
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            }
+            let n = self.frame.read(buf)?;
+            if n > 0 || buf.is_empty() {
+                return Ok(n);
+            }
+            if let Some(expected) = self.frame.get_checksum_from_data() {
+                if self.frame.get_calculated_checksum() != Some(expected) {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "zstd frame checksum mismatch",
+                    ));
+                }
+            }
+            self.in_frame = false;
+        }
+    }
+}
+
+/// Reader that hashes everything read through it.
+/// Hashes every byte read from or written to `inner`.
+struct Hashing<T> {
+    inner: T,
+    hasher: Sha256,
+}
+
+impl<T> Hashing<T> {
+    fn new(inner: T) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+        }
+    }
+}
+
+impl<R: Read> Read for Hashing<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+impl<W: Write> Write for Hashing<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut out, byte| {
+        let _ = write!(out, "{byte:02x}");
+        out
+    })
+}
@@ -0,0 +1,112 @@
+//! Wire protocol and client for the `gluex daemon` query server.
+//!
+//! The daemon speaks length-prefixed JSON over a Unix socket: each frame is a 4-byte big-endian
+//! length followed by that many bytes of UTF-8 JSON. A client sends a request object such as
+//! `{"op": "ccdb/fetch", "table": "/PHOTON_BEAM/endpoint_energy", "select": "30274..30280"}` and
+//! receives either `{"result": ...}` or `{"error": "..."}`. Any number of requests may be sent over
+//! one connection, so a farm job can hold a [`Client`] for its whole lifetime instead of opening
+//! the databases itself.
+use std::{
+    io::{self, Read, Write},
+    os::unix::net::UnixStream,
+    path::Path,
+};
+
+use serde_json::Value as JsonValue;
+
+/// Largest frame either side will accept.
+pub const MAX_FRAME_LEN: usize = 64 * 1024 * 1024;
+
+/// Writes one length-prefixed frame.
+///
+/// # Errors
+///
+/// Returns an [`io::ErrorKind::InvalidInput`] error if `body` is longer than [`MAX_FRAME_LEN`],
+/// or any error from writing to `stream`.
+pub fn write_frame(stream: &mut impl Write, body: &[u8]) -> io::Result<()> {
+    let len = u32::try_from(body.len())
+        .ok()
+        .filter(|&len| len as usize <= MAX_FRAME_LEN)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "frame too large"))?;
+    stream.write_all(&len.to_be_bytes())?;
+    stream.write_all(body)?;
+    stream.flush()
+}
+
+/// Reads one length-prefixed frame, returning `None` when the peer closed the connection.
+///
+/// # Errors
+///
+/// Returns an [`io::ErrorKind::InvalidData`] error if the announced length exceeds
+/// [`MAX_FRAME_LEN`], or any error from reading `stream` (including a frame cut short).
+pub fn read_frame(stream: &mut impl Read) -> io::Result<Option<Vec<u8>>> {
+    let mut len = [0u8; 4];
+    match stream.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err),
+    }
+    let len = u32::from_be_bytes(len) as usize;
+    if len > MAX_FRAME_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("frame of {len} bytes exceeds the {MAX_FRAME_LEN} byte limit"),
+        ));
+    }
+    let mut body = vec![0u8; len];
+    stream.read_exact(&mut body)?;
+    Ok(Some(body))
+}
+
+/// A connection to a running daemon.
+///
+/// ```no_run
+/// use gluex_core::daemon::Client;
+/// use serde_json::json;
+///
+/// let mut client = Client::connect("/tmp/gluex/daemon.sock".as_ref())?;
+/// let rows = client.request(&json!({
+///     "op": "ccdb/fetch",
+///     "table": "/PHOTON_BEAM/endpoint_energy",
+///     "select": "30274..30280",
+/// }))?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct Client {
+    stream: UnixStream,
+}
+
+impl Client {
+    /// Connects to the daemon listening on `socket`.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error from connecting to the socket.
+    pub fn connect(socket: &Path) -> io::Result<Self> {
+        Ok(Self {
+            stream: UnixStream::connect(socket)?,
+        })
+    }
+
+    /// Sends `request` and returns its result, turning a daemon-side error into an [`io::Error`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request cannot be sent, the daemon closes the connection or sends
+    /// malformed JSON, or the daemon answers with `{"error": ...}`.
+    pub fn request(&mut self, request: &JsonValue) -> io::Result<JsonValue> {
+        write_frame(&mut self.stream, &serde_json::to_vec(request)?)?;
+        let body = read_frame(&mut self.stream)?.ok_or_else(|| {
+            io::Error::new(io::ErrorKind::UnexpectedEof, "daemon closed the connection")
+        })?;
+        let mut response: JsonValue = serde_json::from_slice(&body)?;
+        if let Some(error) = response.get("error").and_then(JsonValue::as_str) {
+            return Err(io::Error::other(error.to_string()));
+        }
+        Ok(response
+            .get_mut("result")
+            .map(JsonValue::take)
+            .unwrap_or_default())
+    }
+}
@@ -0,0 +1,234 @@
+//! Warnings for fetches that touch retired CCDB and RCDB metadata.
+//!
+//! CCDB marks directories, tables, and variations as deprecated; RCDB has no such flag, so a
+//! condition counts as withdrawn when its description starts with "deprecated", "withdrawn", or
+//! "obsolete". When a fetch touches one of these, the database handle reports a [`Deprecation`]
+//! to its [`DeprecationWarnings`] channel, which remembers it, passes it to an optional callback,
+//! and, if asked to, prints it to stderr. Each item is reported once per channel. If the comment or
+//! description names a successor ("use X instead", "replaced by X", "superseded by X"), the
+//! warning carries it as [`Deprecation::replacement`].
+//!
+//! Handles start with [`DeprecationWarnings::from_env`], which only collects warnings (and is
+//! absent if [`DEPRECATION_WARNINGS_ENV`] is `off`); applications that want them on stderr attach
+//! a channel built with [`DeprecationWarnings::with_echo`].
+use std::{
+    collections::HashSet,
+    env, fmt,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+/// Environment variable that turns off deprecation warnings when set to `off`.
+pub const DEPRECATION_WARNINGS_ENV: &str = "GLUEX_DEPRECATION_WARNINGS";
+
+/// Description prefixes that mark an RCDB condition as withdrawn.
+const WITHDRAWN_PREFIXES: [&str; 3] = ["deprecated", "withdrawn", "obsolete"];
+
+/// Phrases that introduce the successor of a retired item, paired with the word that ends it.
+const REPLACEMENT_PHRASES: [(&str, Option<&str>); 4] = [
+    ("use ", Some("instead")),
+    ("replaced by ", None),
+    ("superseded by ", None),
+    ("moved to ", None),
+];
+
+/// The kind of metadata a [`Deprecation`] refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeprecatedItem {
+    /// A CCDB directory containing a fetched table.
+    CcdbDirectory,
+    /// A fetched CCDB table.
+    CcdbTable,
+    /// The CCDB variation a fetch was resolved in.
+    CcdbVariation,
+    /// A fetched RCDB condition.
+    RcdbCondition,
+}
+
+impl fmt::Display for DeprecatedItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::CcdbDirectory => "CCDB directory",
+            Self::CcdbTable => "CCDB table",
+            Self::CcdbVariation => "CCDB variation",
+            Self::RcdbCondition => "RCDB condition",
+        })
+    }
+}
+
+/// A fetch touched a deprecated or withdrawn item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deprecation {
+    /// Path of the database file holding the item.
+    pub database: String,
+    /// What kind of item is retired.
+    pub item: DeprecatedItem,
+    /// Path or name of the item.
+    pub name: String,
+    /// Comment or description stored with the item, which may explain the retirement.
+    pub note: String,
+    /// Successor named by the note, if any.
+    pub replacement: Option<String>,
+}
+
+impl Deprecation {
+    /// Creates a deprecation, taking the replacement from `note` (see [`replacement_hint`]).
+    pub fn new(
+        database: impl Into<String>,
+        item: DeprecatedItem,
+        name: impl Into<String>,
+        note: impl Into<String>,
+    ) -> Self {
+        let note = note.into();
+        Self {
+            database: database.into(),
+            item,
+            name: name.into(),
+            replacement: replacement_hint(&note),
+            note,
+        }
+    }
+}
+
+impl fmt::Display for Deprecation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = if self.item == DeprecatedItem::RcdbCondition {
+            "withdrawn"
+        } else {
+            "deprecated"
+        };
+        write!(f, "{} {} is {status}", self.item, self.name)?;
+        if let Some(replacement) = &self.replacement {
+            write!(f, "; use {replacement} instead")?;
+        } else if !self.note.trim().is_empty() {
+            write!(f, " ({})", self.note.trim())?;
+        }
+        write!(f, " [{}]", self.database)
+    }
+}
+
+/// Returns true if an RCDB condition description marks the condition as withdrawn.
+#[must_use]
+pub fn is_withdrawn(description: &str) -> bool {
+    let description = description.trim_start_matches(|c: char| !c.is_alphanumeric());
+    WITHDRAWN_PREFIXES.iter().any(|prefix| {
+        description
+            .get(..prefix.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+    })
+}
+
+/// Extracts the successor named in a comment, e.g. `/PHOTON_BEAM/new` from
+/// `"Replaced by /PHOTON_BEAM/new."` or `calib-v2` from `"use calib-v2 instead"`.
+#[must_use]
+pub fn replacement_hint(note: &str) -> Option<String> {
+    let lower = note.to_ascii_lowercase();
+    REPLACEMENT_PHRASES.iter().find_map(|(phrase, terminator)| {
+        let start = lower.find(phrase)? + phrase.len();
+        let rest = &note[start..];
+        let mut words = rest.split_whitespace();
+        let candidate = words.next()?;
+        if let Some(terminator) = terminator {
+            if !words.next().is_some_and(|word| {
+                word.trim_end_matches(|c: char| !c.is_alphanumeric()) == *terminator
+            }) {
+                return None;
+            }
+        }
+        let candidate = candidate
+            .trim_matches(|c: char| matches!(c, '`' | '"' | '\'' | '(' | ')' | ',' | ';' | ':'))
+            .trim_end_matches('.');
+        (!candidate.is_empty()).then(|| candidate.to_string())
+    })
+}
+
+type Callback = Arc<dyn Fn(&Deprecation) + Send + Sync>;
+
+#[derive(Default)]
+struct State {
+    seen: HashSet<(DeprecatedItem, String, String)>,
+    warnings: Vec<Deprecation>,
+}
+
+/// Shared, thread-safe channel for deprecation warnings; cloning yields another handle to the
+/// same channel.
+#[derive(Clone, Default)]
+pub struct DeprecationWarnings {
+    echo: bool,
+    callback: Option<Callback>,
+    state: Arc<Mutex<State>>,
+}
+
+impl fmt::Debug for DeprecationWarnings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeprecationWarnings")
+            .field("echo", &self.echo)
+            .field("callback", &self.callback.is_some())
+            .field("warnings", &self.lock().warnings)
+            .finish()
+    }
+}
+
+impl DeprecationWarnings {
+    /// Creates a channel that only collects warnings.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a channel that only collects warnings, or `None` if [`DEPRECATION_WARNINGS_ENV`] is
+    /// `off`.
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        match env::var(DEPRECATION_WARNINGS_ENV) {
+            Ok(value) if value.trim().eq_ignore_ascii_case("off") => None,
+            _ => Some(Self::new()),
+        }
+    }
+
+    /// Also prints every warning to stderr when it is first reported.
+    #[must_use]
+    pub fn with_echo(mut self, echo: bool) -> Self {
+        self.echo = echo;
+        self
+    }
+
+    /// Calls `callback` with every warning when it is first reported.
+    #[must_use]
+    pub fn with_callback(
+        mut self,
+        callback: impl Fn(&Deprecation) + Send + Sync + 'static,
+    ) -> Self {
+        self.callback = Some(Arc::new(callback));
+        self
+    }
+
+    /// Reports a deprecation, returning false if the same item was already reported.
+    pub fn warn(&self, deprecation: Deprecation) -> bool {
+        let key = (
+            deprecation.item,
+            deprecation.database.clone(),
+            deprecation.name.clone(),
+        );
+        if !self.lock().seen.insert(key) {
+            return false;
+        }
+        if self.echo {
+            eprintln!("warning: {deprecation}");
+        }
+        if let Some(callback) = &self.callback {
+            callback(&deprecation);
+        }
+        self.lock().warnings.push(deprecation);
+        true
+    }
+
+    /// Returns a copy of every warning reported so far.
+    #[must_use]
+    pub fn warnings(&self) -> Vec<Deprecation> {
+        self.lock().warnings.clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
@@ -10,3 +10,23 @@ pub enum ParseTimestampError {
     #[error("invalid timestamp: {0}")]
     ChronoError(String),
 }
+
+/// Errors that can occur while parsing a run selection string such as `S18+F18-[41050..41100]`.
+#[derive(Error, Debug)]
+pub enum ParseRunSetError {
+    /// The selection, or one of its `+`/`-`/`,` separated terms, was empty.
+    #[error("run selection \"{0}\" has an empty term")]
+    EmptyTerm(String),
+    /// A term was not a run period, run number, range, or bracketed list.
+    #[error("invalid run selection term \"{0}\" (expected a run period, run number, or range)")]
+    InvalidTerm(String),
+    /// A `[` was not closed, or a `]` had no matching `[`.
+    #[error("unbalanced brackets in run selection \"{0}\"")]
+    UnbalancedBrackets(String),
+    /// A range's start was after its end.
+    #[error("run range {0}..{1} is empty")]
+    EmptyRange(crate::RunNumber, crate::RunNumber),
+    /// The selection would have to be expanded into more explicit runs than allowed.
+    #[error("run selection covers {0} separate runs, more than the limit of {limit}", limit = crate::run_set::MAX_EXPANDED_RUNS)]
+    TooManyRuns(u64),
+}
@@ -1,11 +1,36 @@
+#[cfg(feature = "arrow")]
+pub mod arrow;
+#[cfg(feature = "bundle")]
+pub mod bundle;
+pub mod cache;
+#[cfg(feature = "columnar")]
+pub mod columnar;
+#[cfg(feature = "compressed")]
+pub mod compressed;
 pub mod constants;
+#[cfg(all(feature = "daemon", unix))]
+pub mod daemon;
+pub mod deprecation;
 pub mod detectors;
 pub mod enums;
 pub mod errors;
 pub mod histograms;
+pub mod limits;
+pub mod metrics;
 pub mod parsers;
 pub mod particles;
+#[cfg(feature = "provenance")]
+pub mod provenance;
+pub mod query_plan;
+pub mod retry;
+pub mod routing;
 pub mod run_periods;
+pub mod run_set;
+pub mod schema;
+pub mod sql_audit;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod units;
 
 /// Primary integer identifier type used throughout CCDB and RCDB.
 pub type Id = i64;
@@ -15,3 +40,12 @@ pub type RunNumber = i64;
 
 /// REST versions of analysis reconstructions.
 pub type RestVersion = usize;
+
+/// Monotonic clock used for query deadlines, retry budgets, and metrics timings.
+///
+/// This is [`std::time::Instant`], except on `wasm32-unknown-unknown`, where reading that clock
+/// panics and `web_time::Instant` reads `performance.now()` instead.
+#[cfg(not(all(target_family = "wasm", target_os = "unknown")))]
+pub use std::time::Instant;
+#[cfg(all(target_family = "wasm", target_os = "unknown"))]
+pub use web_time::Instant;
@@ -0,0 +1,177 @@
+//! Resource limits that turn runaway CCDB and RCDB queries into errors.
+//!
+//! A [`QueryLimits`] is attached with `CCDB::with_limits`/`RCDB::with_limits`, or taken from the
+//! [`MAX_RUNS_ENV`], [`MAX_RESULT_BYTES_ENV`], and [`QUERY_TIMEOUT_ENV`] environment variables when
+//! a database is opened. Fetches then fail with a [`LimitError`] when they cover more runs than
+//! allowed, when their results would take more memory than allowed, or when they run longer than
+//! allowed, instead of exhausting a shared login node. Handles have no limits by default.
+use std::{env, time::Duration};
+
+use thiserror::Error;
+
+use crate::Instant;
+
+/// Environment variable holding the default maximum number of runs per fetch.
+pub const MAX_RUNS_ENV: &str = "GLUEX_MAX_RUNS";
+
+/// Environment variable holding the default maximum estimated result size of a fetch, in bytes.
+pub const MAX_RESULT_BYTES_ENV: &str = "GLUEX_MAX_RESULT_BYTES";
+
+/// Environment variable holding the default query timeout, in (possibly fractional) seconds.
+pub const QUERY_TIMEOUT_ENV: &str = "GLUEX_QUERY_TIMEOUT";
+
+/// A query exceeded one of its [`QueryLimits`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum LimitError {
+    /// The query covers more runs than allowed.
+    #[error(
+        "query covers more than {limit} runs (narrow the run selection or raise the run limit)"
+    )]
+    TooManyRuns {
+        /// Maximum number of runs allowed.
+        limit: usize,
+    },
+    /// The query's results would take more memory than allowed.
+    #[error("query results would exceed {limit} bytes (fetch fewer runs or conditions, or raise the memory limit)")]
+    ResultTooLarge {
+        /// Maximum estimated result size allowed, in bytes.
+        limit: usize,
+    },
+    /// The query ran longer than allowed.
+    #[error("query did not finish within {0:?}")]
+    Timeout(Duration),
+}
+
+/// Limits applied to every fetch made through a database handle.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueryLimits {
+    /// Maximum number of runs a single fetch may return.
+    pub max_runs: Option<usize>,
+    /// Maximum estimated size of a single fetch's results in memory, in bytes.
+    pub max_result_bytes: Option<usize>,
+    /// Maximum wall time of a single fetch.
+    pub timeout: Option<Duration>,
+}
+
+impl QueryLimits {
+    /// Creates limits that allow everything.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the limits from [`MAX_RUNS_ENV`], [`MAX_RESULT_BYTES_ENV`], and
+    /// [`QUERY_TIMEOUT_ENV`]; unset or unparsable variables leave the corresponding limit unset.
+    #[must_use]
+    pub fn from_env() -> Self {
+        Self {
+            max_runs: env::var(MAX_RUNS_ENV)
+                .ok()
+                .and_then(|value| value.trim().parse().ok()),
+            max_result_bytes: env::var(MAX_RESULT_BYTES_ENV)
+                .ok()
+                .and_then(|value| value.trim().parse().ok()),
+            timeout: env::var(QUERY_TIMEOUT_ENV)
+                .ok()
+                .and_then(|value| Duration::try_from_secs_f64(value.trim().parse().ok()?).ok()),
+        }
+    }
+
+    /// Sets the maximum number of runs per fetch.
+    #[must_use]
+    pub fn with_max_runs(mut self, max_runs: usize) -> Self {
+        self.max_runs = Some(max_runs);
+        self
+    }
+
+    /// Sets the maximum estimated result size per fetch, in bytes.
+    #[must_use]
+    pub fn with_max_result_bytes(mut self, max_result_bytes: usize) -> Self {
+        self.max_result_bytes = Some(max_result_bytes);
+        self
+    }
+
+    /// Sets the maximum wall time per fetch.
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Returns true if no limit is set.
+    #[must_use]
+    pub fn is_unlimited(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Starts tracking one fetch against these limits.
+    #[must_use]
+    pub fn start(&self) -> QueryBudget {
+        QueryBudget {
+            limits: *self,
+            deadline: self.timeout.map(|timeout| Instant::now() + timeout),
+            bytes: 0,
+        }
+    }
+}
+
+/// Resources used so far by one fetch, checked against its [`QueryLimits`].
+#[derive(Debug, Clone)]
+pub struct QueryBudget {
+    limits: QueryLimits,
+    deadline: Option<Instant>,
+    bytes: usize,
+}
+
+impl QueryBudget {
+    /// Returns the instant after which the fetch should be interrupted, if it has a timeout.
+    #[must_use]
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Checks that `runs` runs are within the run limit.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LimitError::TooManyRuns`] if `runs` exceeds the limit.
+    pub fn check_runs(&self, runs: usize) -> Result<(), LimitError> {
+        match self.limits.max_runs {
+            Some(limit) if runs > limit => Err(LimitError::TooManyRuns { limit }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Adds `bytes` to the estimated result size and checks it against the memory limit.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LimitError::ResultTooLarge`] if the total exceeds the limit.
+    pub fn add_bytes(&mut self, bytes: usize) -> Result<(), LimitError> {
+        self.bytes = self.bytes.saturating_add(bytes);
+        match self.limits.max_result_bytes {
+            Some(limit) if self.bytes > limit => Err(LimitError::ResultTooLarge { limit }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that the deadline has not passed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LimitError::Timeout`] if the fetch has run longer than the timeout.
+    pub fn check_time(&self) -> Result<(), LimitError> {
+        match (self.deadline, self.limits.timeout) {
+            (Some(deadline), Some(timeout)) if Instant::now() > deadline => {
+                Err(LimitError::Timeout(timeout))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The error to report when a statement was interrupted at the deadline.
+    #[must_use]
+    pub fn timeout_error(&self) -> LimitError {
+        LimitError::Timeout(self.limits.timeout.unwrap_or_default())
+    }
+}
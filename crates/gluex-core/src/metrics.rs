@@ -0,0 +1,313 @@
+//! Opt-in query metrics for the CCDB and RCDB database handles.
+//!
+//! A [`Metrics`] collector is attached with `CCDB::with_metrics`/`RCDB::with_metrics`. The handles
+//! then count every operation (fetches and the SQL statements behind them) along with its wall
+//! time and the rows it returned, and tally hits and misses of their in-memory metadata caches.
+//! [`Metrics::report`] (or `db.metrics()`) returns a [`MetricsReport`] that can be inspected or
+//! printed.
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Write as _},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+
+use crate::Instant;
+
+/// Totals for one kind of operation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OperationMetrics {
+    /// Number of times the operation ran.
+    pub count: u64,
+    /// Rows returned, summed over every run of the operation.
+    pub rows: u64,
+    /// Wall time, summed over every run of the operation.
+    pub total_time: Duration,
+    /// Longest single run of the operation.
+    pub max_time: Duration,
+}
+
+impl OperationMetrics {
+    /// Average wall time per run, or `None` if the operation never ran.
+    #[must_use]
+    pub fn mean_time(&self) -> Option<Duration> {
+        u32::try_from(self.count)
+            .ok()
+            .filter(|count| *count > 0)
+            .map(|count| self.total_time / count)
+    }
+}
+
+/// Hit and miss counts for one cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheMetrics {
+    /// Lookups answered from memory.
+    pub hits: u64,
+    /// Lookups that had to go to the database (or found nothing).
+    pub misses: u64,
+}
+
+impl CacheMetrics {
+    /// Fraction of lookups that hit, or `None` if the cache was never used.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn hit_rate(&self) -> Option<f64> {
+        let total = self.hits + self.misses;
+        (total > 0).then(|| self.hits as f64 / total as f64)
+    }
+}
+
+/// Everything recorded by a [`Metrics`] collector.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetricsReport {
+    /// Totals keyed by operation name (e.g. `ccdb.fetch`, `rcdb.query`).
+    pub operations: BTreeMap<String, OperationMetrics>,
+    /// Hit and miss counts keyed by cache name (e.g. `ccdb.column_layouts`).
+    pub caches: BTreeMap<String, CacheMetrics>,
+}
+
+/// Escapes a Prometheus label value.
+fn label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+impl MetricsReport {
+    /// Renders the totals in the Prometheus text exposition format, with every metric name
+    /// starting with `prefix` (e.g. `gluex`).
+    ///
+    /// Operations become `<prefix>_operations_total`, `<prefix>_operation_rows_total`,
+    /// `<prefix>_operation_seconds_total`, and `<prefix>_operation_seconds_max`, labelled by
+    /// `operation`; caches become `<prefix>_cache_hits_total`, `<prefix>_cache_misses_total`, and
+    /// `<prefix>_cache_hit_ratio`, labelled by `cache`.
+    #[must_use]
+    pub fn to_prometheus(&self, prefix: &str) -> String {
+        let mut out = String::new();
+        let ops = &self.operations;
+        let op = "operation";
+        family(
+            &mut out,
+            prefix,
+            "operations_total",
+            "counter",
+            "Number of times each operation ran.",
+            op,
+            ops,
+            |op| Some(op.count.to_string()),
+        );
+        family(
+            &mut out,
+            prefix,
+            "operation_rows_total",
+            "counter",
+            "Rows returned by each operation.",
+            op,
+            ops,
+            |op| Some(op.rows.to_string()),
+        );
+        family(
+            &mut out,
+            prefix,
+            "operation_seconds_total",
+            "counter",
+            "Wall time spent in each operation.",
+            op,
+            ops,
+            |op| Some(op.total_time.as_secs_f64().to_string()),
+        );
+        family(
+            &mut out,
+            prefix,
+            "operation_seconds_max",
+            "gauge",
+            "Longest single run of each operation.",
+            op,
+            ops,
+            |op| Some(op.max_time.as_secs_f64().to_string()),
+        );
+        let caches = &self.caches;
+        family(
+            &mut out,
+            prefix,
+            "cache_hits_total",
+            "counter",
+            "Cache lookups answered from memory.",
+            "cache",
+            caches,
+            |cache| Some(cache.hits.to_string()),
+        );
+        family(
+            &mut out,
+            prefix,
+            "cache_misses_total",
+            "counter",
+            "Cache lookups that went to the database.",
+            "cache",
+            caches,
+            |cache| Some(cache.misses.to_string()),
+        );
+        family(
+            &mut out,
+            prefix,
+            "cache_hit_ratio",
+            "gauge",
+            "Fraction of cache lookups that hit.",
+            "cache",
+            caches,
+            |cache| cache.hit_rate().map(|rate| rate.to_string()),
+        );
+        out
+    }
+}
+
+/// Writes one Prometheus metric family with a sample per entry of `items` (entries whose `value`
+/// is `None` are skipped).
+#[allow(clippy::too_many_arguments)]
+fn family<T>(
+    out: &mut String,
+    prefix: &str,
+    name: &str,
+    kind: &str,
+    help: &str,
+    label_name: &str,
+    items: &BTreeMap<String, T>,
+    value: impl Fn(&T) -> Option<String>,
+) {
+    let _ = writeln!(out, "# HELP {prefix}_{name} {help}");
+    let _ = writeln!(out, "# TYPE {prefix}_{name} {kind}");
+    for (key, item) in items {
+        if let Some(value) = value(item) {
+            let _ = writeln!(
+                out,
+                "{prefix}_{name}{{{label_name}=\"{}\"}} {value}",
+                label(key)
+            );
+        }
+    }
+}
+
+impl fmt::Display for MetricsReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<28} {:>8} {:>10} {:>12} {:>12} {:>12}",
+            "operation", "count", "rows", "total ms", "mean ms", "max ms"
+        )?;
+        for (name, op) in &self.operations {
+            writeln!(
+                f,
+                "{:<28} {:>8} {:>10} {:>12.3} {:>12.3} {:>12.3}",
+                name,
+                op.count,
+                op.rows,
+                op.total_time.as_secs_f64() * 1e3,
+                op.mean_time().unwrap_or_default().as_secs_f64() * 1e3,
+                op.max_time.as_secs_f64() * 1e3,
+            )?;
+        }
+        if !self.caches.is_empty() {
+            writeln!(f)?;
+            writeln!(
+                f,
+                "{:<28} {:>8} {:>10} {:>12}",
+                "cache", "hits", "misses", "hit rate"
+            )?;
+            for (name, cache) in &self.caches {
+                let rate = cache
+                    .hit_rate()
+                    .map_or_else(|| "-".to_string(), |rate| format!("{:.1}%", rate * 100.0));
+                writeln!(
+                    f,
+                    "{:<28} {:>8} {:>10} {:>12}",
+                    name, cache.hits, cache.misses, rate
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Shared, thread-safe metrics collector; cloning yields another handle to the same totals.
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    report: Arc<Mutex<MetricsReport>>,
+}
+
+impl Metrics {
+    /// Creates an empty collector.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds one run of `operation` that took `elapsed` and returned `rows` rows.
+    pub fn record(&self, operation: &str, elapsed: Duration, rows: usize) {
+        let mut report = self.lock();
+        let op = report.operations.entry(operation.to_string()).or_default();
+        op.count += 1;
+        op.rows += rows as u64;
+        op.total_time += elapsed;
+        op.max_time = op.max_time.max(elapsed);
+    }
+
+    /// Starts timing `operation`; call [`Timer::finish`] when it completes.
+    #[must_use]
+    pub fn timer(&self, operation: &'static str) -> Timer {
+        Timer {
+            metrics: self.clone(),
+            operation,
+            start: Instant::now(),
+        }
+    }
+
+    /// Counts a lookup in `cache` that was answered from memory.
+    pub fn cache_hit(&self, cache: &str) {
+        self.lock()
+            .caches
+            .entry(cache.to_string())
+            .or_default()
+            .hits += 1;
+    }
+
+    /// Counts a lookup in `cache` that was not answered from memory.
+    pub fn cache_miss(&self, cache: &str) {
+        self.lock()
+            .caches
+            .entry(cache.to_string())
+            .or_default()
+            .misses += 1;
+    }
+
+    /// Returns a copy of the totals recorded so far.
+    #[must_use]
+    pub fn report(&self) -> MetricsReport {
+        self.lock().clone()
+    }
+
+    /// Clears every total.
+    pub fn reset(&self) {
+        *self.lock() = MetricsReport::default();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, MetricsReport> {
+        self.report.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A running operation started with [`Metrics::timer`].
+#[derive(Debug)]
+pub struct Timer {
+    metrics: Metrics,
+    operation: &'static str,
+    start: Instant,
+}
+
+impl Timer {
+    /// Records the operation with the number of rows it returned.
+    pub fn finish(self, rows: usize) {
+        self.metrics
+            .record(self.operation, self.start.elapsed(), rows);
+    }
+}
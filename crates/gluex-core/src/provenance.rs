@@ -0,0 +1,229 @@
+//! Machine-readable records of the RCDB and CCDB queries an application performs.
+//!
+//! A [`Recorder`] is attached to database handles (`CCDB::with_recorder`,
+//! `RCDB::with_recorder`), which then append a [`Query`] for every fetch. The resulting
+//! [`Manifest`] lists the table paths, contexts, resolved assignment ids, run selections, and a
+//! SHA-256 hash of every database file involved, so it can be published alongside results and
+//! later replayed against a database to verify that the same constants and runs come back.
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    fs::File,
+    io::{self, BufReader, BufWriter, Read},
+    path::Path,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{Id, RunNumber};
+
+/// Version of the manifest layout written by this crate.
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// Which kind of database a record refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DatabaseKind {
+    /// Calibration and Conditions Database.
+    Ccdb,
+    /// Run Condition Database.
+    Rcdb,
+}
+
+/// A database file used while recording.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatabaseRecord {
+    /// Kind of database stored in the file.
+    pub kind: DatabaseKind,
+    /// Hex-encoded SHA-256 hash of the file, filled in when the manifest is saved.
+    pub sha256: Option<String>,
+}
+
+/// A single CCDB fetch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CcdbQuery {
+    /// Path of the database file the query ran against.
+    pub database: String,
+    /// Absolute table path.
+    pub table: String,
+    /// Requested variation.
+    pub variation: String,
+    /// Assignments created after this timestamp were ignored.
+    pub timestamp: DateTime<Utc>,
+    /// Requested runs.
+    pub runs: Vec<RunNumber>,
+    /// Assignment id resolved for each run that returned data.
+    pub assignments: BTreeMap<RunNumber, Id>,
+}
+
+/// A single RCDB fetch (or run-only query when `conditions` is empty).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RcdbQuery {
+    /// Path of the database file the query ran against.
+    pub database: String,
+    /// Requested condition names.
+    pub conditions: Vec<String>,
+    /// Human-readable description of the run selection.
+    pub selection: String,
+    /// Filter expressions applied to the selection.
+    pub filters: Vec<String>,
+    /// Runs returned by the query.
+    pub runs: Vec<RunNumber>,
+    /// Hex-encoded SHA-256 hash of the returned values, used to verify replays.
+    pub digest: String,
+}
+
+/// A recorded query.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Query {
+    /// A CCDB table fetch.
+    Ccdb(CcdbQuery),
+    /// An RCDB condition or run fetch.
+    Rcdb(RcdbQuery),
+}
+
+/// The full record written by a [`Recorder`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Layout version, see [`MANIFEST_VERSION`].
+    pub version: u32,
+    /// Database files keyed by the path they were opened from.
+    pub databases: BTreeMap<String, DatabaseRecord>,
+    /// Queries in the order they were performed.
+    pub queries: Vec<Query>,
+}
+
+impl Default for Manifest {
+    fn default() -> Self {
+        Self {
+            version: MANIFEST_VERSION,
+            databases: BTreeMap::new(),
+            queries: Vec::new(),
+        }
+    }
+}
+
+impl Manifest {
+    /// Reads a manifest previously written by [`Recorder::save`].
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the file cannot be read or is not a valid manifest.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        serde_json::from_reader(reader).map_err(io::Error::other)
+    }
+
+    /// Returns the recorded CCDB queries.
+    pub fn ccdb_queries(&self) -> impl Iterator<Item = &CcdbQuery> {
+        self.queries.iter().filter_map(|query| match query {
+            Query::Ccdb(query) => Some(query),
+            Query::Rcdb(_) => None,
+        })
+    }
+
+    /// Returns the recorded RCDB queries.
+    pub fn rcdb_queries(&self) -> impl Iterator<Item = &RcdbQuery> {
+        self.queries.iter().filter_map(|query| match query {
+            Query::Rcdb(query) => Some(query),
+            Query::Ccdb(_) => None,
+        })
+    }
+}
+
+/// A mismatch found while replaying a manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Discrepancy {
+    /// Index of the offending entry in [`Manifest::queries`], or `None` for file-level issues.
+    pub query: Option<usize>,
+    /// Description of the mismatch.
+    pub message: String,
+}
+
+/// Shared, thread-safe collector of queries; cloning yields another handle to the same manifest.
+#[derive(Debug, Clone, Default)]
+pub struct Recorder {
+    manifest: Arc<Mutex<Manifest>>,
+}
+
+impl Recorder {
+    /// Creates an empty recorder.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Notes that the database at `path` is in use.
+    pub fn register_database(&self, path: &str, kind: DatabaseKind) {
+        self.lock()
+            .databases
+            .entry(path.to_string())
+            .or_insert(DatabaseRecord { kind, sha256: None });
+    }
+
+    /// Appends a query to the manifest.
+    pub fn record(&self, query: Query) {
+        self.lock().queries.push(query);
+    }
+
+    /// Returns a copy of everything recorded so far.
+    #[must_use]
+    pub fn manifest(&self) -> Manifest {
+        self.lock().clone()
+    }
+
+    /// Hashes every registered database file and writes the manifest to `path` as JSON.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if a database file cannot be read or the manifest cannot be
+    /// written.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut manifest = self.manifest();
+        for (database, record) in &mut manifest.databases {
+            record.sha256 = Some(sha256_file(database)?);
+        }
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(writer, &manifest).map_err(io::Error::other)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Manifest> {
+        self.manifest.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Returns the hex-encoded SHA-256 hash of `bytes`.
+#[must_use]
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    to_hex(&Sha256::digest(bytes))
+}
+
+/// Returns the hex-encoded SHA-256 hash of the file at `path`.
+///
+/// # Errors
+///
+/// This function returns an error if the file cannot be read.
+pub fn sha256_file(path: impl AsRef<Path>) -> io::Result<String> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 1 << 16];
+    loop {
+        let n = reader.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+    }
+    Ok(to_hex(&hasher.finalize()))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut out, byte| {
+        let _ = write!(out, "{byte:02x}");
+        out
+    })
+}
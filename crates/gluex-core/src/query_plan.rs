@@ -0,0 +1,73 @@
+//! `SQLite` query plans of the statements behind CCDB and RCDB fetches.
+//!
+//! The `explain_*` methods of the database handles build the same SQL as the corresponding fetch
+//! and return the output of `EXPLAIN QUERY PLAN` for it as a [`QueryPlan`], which is the quickest
+//! way to find a missing index on a user-built snapshot (look for `SCAN` steps over large tables
+//! where a `SEARCH ... USING INDEX` is expected). The statements themselves are not executed.
+use std::fmt;
+
+/// One row of `EXPLAIN QUERY PLAN` output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryPlanStep {
+    /// Identifier of the step.
+    pub id: i64,
+    /// Identifier of the enclosing step, or 0 for top-level steps.
+    pub parent: i64,
+    /// Description of the step, such as `SEARCH c USING INDEX ... (run_number=?)`.
+    pub detail: String,
+}
+
+/// The plan `SQLite` chose for one statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryPlan {
+    /// SQL text with `?` placeholders.
+    pub sql: String,
+    /// Bound parameters, in placeholder order, as SQL literals.
+    pub params: Vec<String>,
+    /// Plan steps in the order reported by `SQLite`.
+    pub steps: Vec<QueryPlanStep>,
+}
+
+impl QueryPlan {
+    /// Returns true if any step scans a table without an index (a `SCAN` step that is not a
+    /// covering-index or subquery scan).
+    #[must_use]
+    pub fn has_full_scan(&self) -> bool {
+        self.steps.iter().any(|step| {
+            step.detail.starts_with("SCAN ")
+                && !step.detail.contains(" INDEX ")
+                && !step.detail.starts_with("SCAN CONSTANT ROW")
+                && !step.detail.contains("SUBQUERY")
+        })
+    }
+
+    fn fmt_children(&self, f: &mut fmt::Formatter<'_>, parent: i64, prefix: &str) -> fmt::Result {
+        let children: Vec<&QueryPlanStep> = self
+            .steps
+            .iter()
+            .filter(|step| step.parent == parent && step.id != parent)
+            .collect();
+        for (index, step) in children.iter().enumerate() {
+            let last = index + 1 == children.len();
+            writeln!(
+                f,
+                "{prefix}{}{}",
+                if last { "`--" } else { "|--" },
+                step.detail
+            )?;
+            let prefix = format!("{prefix}{}", if last { "   " } else { "|  " });
+            self.fmt_children(f, step.id, &prefix)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for QueryPlan {
+    /// Renders the SQL followed by the plan as a tree, in the format of the `sqlite3` shell.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sql = self.sql.split_whitespace().collect::<Vec<_>>().join(" ");
+        writeln!(f, "{sql} -- params: [{}]", self.params.join(", "))?;
+        writeln!(f, "QUERY PLAN")?;
+        self.fmt_children(f, 0, "")
+    }
+}
@@ -0,0 +1,212 @@
+//! Retries with exponential backoff for operations that can fail transiently.
+//!
+//! A [`RetryPolicy`] reruns an operation while it fails with an error its caller classifies as
+//! transient, sleeping between attempts for an exponentially growing, jittered delay, until the
+//! operation succeeds, the maximum number of attempts is reached, or the overall timeout would be
+//! exceeded. CCDB and RCDB handles retry opening a snapshot whose file is busy, locked, or on a
+//! flaky network filesystem, and the `gluex` CLI retries downloads and `MySQL` connections.
+//!
+//! Policies read [`RETRY_ATTEMPTS_ENV`], [`RETRY_BACKOFF_ENV`], [`RETRY_MAX_BACKOFF_ENV`], and
+//! [`RETRY_TIMEOUT_ENV`] so batch jobs can be tuned without code changes. Without them, database
+//! handles make a single attempt.
+use std::{
+    collections::hash_map::RandomState,
+    env,
+    hash::{BuildHasher, Hasher},
+    thread,
+    time::Duration,
+};
+
+use crate::Instant;
+
+/// Environment variable holding the maximum number of attempts, including the first one.
+pub const RETRY_ATTEMPTS_ENV: &str = "GLUEX_RETRY_ATTEMPTS";
+
+/// Environment variable holding the delay before the first retry, in (possibly fractional)
+/// seconds.
+pub const RETRY_BACKOFF_ENV: &str = "GLUEX_RETRY_BACKOFF";
+
+/// Environment variable holding the longest delay between two attempts, in (possibly fractional)
+/// seconds.
+pub const RETRY_MAX_BACKOFF_ENV: &str = "GLUEX_RETRY_MAX_BACKOFF";
+
+/// Environment variable holding the time after which no further attempt is started, in (possibly
+/// fractional) seconds.
+pub const RETRY_TIMEOUT_ENV: &str = "GLUEX_RETRY_TIMEOUT";
+
+/// How often and how patiently an operation is retried.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first one (at least one attempt is always made).
+    pub max_attempts: u32,
+    /// Delay before the first retry.
+    pub initial_backoff: Duration,
+    /// Longest delay between two attempts.
+    pub max_backoff: Duration,
+    /// Factor by which the delay grows after every retry.
+    pub multiplier: f64,
+    /// Fraction of each delay that is randomized, between 0 (fixed delays) and 1.
+    pub jitter: f64,
+    /// Time after the first attempt at which no further attempt is started.
+    pub timeout: Option<Duration>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            multiplier: 2.0,
+            jitter: 0.5,
+            timeout: None,
+        }
+    }
+}
+
+fn env_secs(name: &str) -> Option<Duration> {
+    Duration::try_from_secs_f64(env::var(name).ok()?.trim().parse().ok()?).ok()
+}
+
+/// A uniformly distributed number in `[0, 1)`, seeded differently in every call.
+fn random_unit() -> f64 {
+    let bits = RandomState::new().build_hasher().finish() >> 11;
+    #[allow(clippy::cast_precision_loss)]
+    let unit = bits as f64 / (1u64 << 53) as f64;
+    unit
+}
+
+impl RetryPolicy {
+    /// Creates a policy that makes a single attempt.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a policy suited to network transfers: five attempts starting one second apart.
+    #[must_use]
+    pub fn network() -> Self {
+        Self::default()
+            .with_max_attempts(5)
+            .with_backoff(Duration::from_secs(1), Duration::from_secs(60))
+    }
+
+    /// Reads a policy from the environment, starting from [`RetryPolicy::new`] (see
+    /// [`RetryPolicy::with_env`]).
+    #[must_use]
+    pub fn from_env() -> Self {
+        Self::new().with_env()
+    }
+
+    /// Overrides the settings given by [`RETRY_ATTEMPTS_ENV`], [`RETRY_BACKOFF_ENV`],
+    /// [`RETRY_MAX_BACKOFF_ENV`], and [`RETRY_TIMEOUT_ENV`]; unset or unparsable variables keep
+    /// the current setting.
+    #[must_use]
+    pub fn with_env(mut self) -> Self {
+        if let Some(max_attempts) = env::var(RETRY_ATTEMPTS_ENV)
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+        {
+            self.max_attempts = max_attempts;
+        }
+        if let Some(backoff) = env_secs(RETRY_BACKOFF_ENV) {
+            self.initial_backoff = backoff;
+        }
+        if let Some(max_backoff) = env_secs(RETRY_MAX_BACKOFF_ENV) {
+            self.max_backoff = max_backoff;
+        }
+        if let Some(timeout) = env_secs(RETRY_TIMEOUT_ENV) {
+            self.timeout = Some(timeout);
+        }
+        self
+    }
+
+    /// Sets the maximum number of attempts, including the first one.
+    #[must_use]
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Sets the delay before the first retry and the longest delay between two attempts.
+    #[must_use]
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Sets the factor by which the delay grows after every retry.
+    #[must_use]
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Sets the randomized fraction of each delay, clamped to `[0, 1]`.
+    #[must_use]
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the time after the first attempt at which no further attempt is started.
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Returns the delay before attempt `attempt + 1` without jitter, i.e. the initial backoff
+    /// grown `attempt - 1` times and capped at the maximum.
+    #[must_use]
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = i32::try_from(attempt.saturating_sub(1)).unwrap_or(i32::MAX);
+        let secs = self.initial_backoff.as_secs_f64() * self.multiplier.max(1.0).powi(exponent);
+        Duration::try_from_secs_f64(secs)
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff)
+    }
+
+    /// Returns the delay to sleep before attempt `attempt + 1`, with up to [`RetryPolicy::jitter`]
+    /// of [`RetryPolicy::backoff`] removed at random so that many clients failing together do not
+    /// retry in lockstep.
+    #[must_use]
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.backoff(attempt)
+            .mul_f64(1.0 - self.jitter.clamp(0.0, 1.0) * random_unit())
+    }
+
+    /// Runs `operation` until it succeeds or fails with an error for which `is_transient` returns
+    /// false, sleeping between attempts.
+    ///
+    /// `operation` receives the number of the attempt, starting at 1.
+    ///
+    /// # Errors
+    ///
+    /// Returns the last error once it is not transient, once [`RetryPolicy::max_attempts`]
+    /// attempts were made, or once the next attempt would start after the timeout.
+    pub fn run<T, E>(
+        &self,
+        mut operation: impl FnMut(u32) -> Result<T, E>,
+        is_transient: impl Fn(&E) -> bool,
+    ) -> Result<T, E> {
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        let mut attempt = 1;
+        loop {
+            let error = match operation(attempt) {
+                Ok(value) => return Ok(value),
+                Err(error) => error,
+            };
+            if attempt >= self.max_attempts || !is_transient(&error) {
+                return Err(error);
+            }
+            let delay = self.delay(attempt);
+            if deadline.is_some_and(|deadline| Instant::now() + delay > deadline) {
+                return Err(error);
+            }
+            thread::sleep(delay);
+            attempt += 1;
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! Routing of runs to the database file that covers them.
+//!
+//! Some run periods are calibrated in a dedicated database (for example a `PrimEx` CCDB file next
+//! to the standard one). A [`RunRouter`] maps inclusive run ranges to such targets, with an
+//! optional default for every run no range covers, and splits a [`RunSet`] into the part each
+//! target has to answer. The CCDB and RCDB routers use it to dispatch a single fetch across
+//! several snapshots.
+use std::ops::{Bound, RangeBounds, RangeInclusive};
+
+use crate::{
+    constants::{MAX_RUN_NUMBER, MIN_RUN_NUMBER},
+    run_set::RunSet,
+    RunNumber,
+};
+
+/// Run ranges mapped to targets, with an optional default target.
+///
+/// Routes are matched in the order they were added, so the first route containing a run wins when
+/// ranges overlap.
+#[derive(Debug, Clone)]
+pub struct RunRouter<T> {
+    routes: Vec<(RangeInclusive<RunNumber>, T)>,
+    default: Option<T>,
+}
+
+impl<T> Default for RunRouter<T> {
+    fn default() -> Self {
+        Self {
+            routes: Vec::new(),
+            default: None,
+        }
+    }
+}
+
+impl<T> RunRouter<T> {
+    /// Creates a router without routes or default.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sends the runs within `runs` to `target`, unless an earlier route already covers them.
+    ///
+    /// Exclusive and unbounded ends are normalized to inclusive bounds within
+    /// [`MIN_RUN_NUMBER`]..=[`MAX_RUN_NUMBER`]; an empty range never matches.
+    #[must_use]
+    pub fn with_route(mut self, runs: impl RangeBounds<RunNumber>, target: T) -> Self {
+        let start = match runs.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.saturating_add(1),
+            Bound::Unbounded => MIN_RUN_NUMBER,
+        };
+        let end = match runs.end_bound() {
+            Bound::Included(&end) => end,
+            Bound::Excluded(&end) => end.saturating_sub(1),
+            Bound::Unbounded => MAX_RUN_NUMBER,
+        };
+        self.routes.push((start..=end, target));
+        self
+    }
+
+    /// Sends every run that no route covers to `target`.
+    #[must_use]
+    pub fn with_default(mut self, target: T) -> Self {
+        self.default = Some(target);
+        self
+    }
+
+    /// Returns the routes in matching order.
+    #[must_use]
+    pub fn routes(&self) -> &[(RangeInclusive<RunNumber>, T)] {
+        &self.routes
+    }
+
+    /// Returns the target for runs that no route covers.
+    #[must_use]
+    pub fn default_target(&self) -> Option<&T> {
+        self.default.as_ref()
+    }
+
+    /// Returns the index of the route covering `run`, where [`RunRouter::routes`]`.len()` stands
+    /// for the default, or `None` if the run is not routed anywhere.
+    #[must_use]
+    pub fn index(&self, run: RunNumber) -> Option<usize> {
+        self.routes
+            .iter()
+            .position(|(range, _)| range.contains(&run))
+            .or_else(|| self.default.as_ref().map(|_| self.routes.len()))
+    }
+
+    /// Returns the target with the given index (see [`RunRouter::index`]).
+    #[must_use]
+    pub fn target(&self, index: usize) -> Option<&T> {
+        match self.routes.get(index) {
+            Some((_, target)) => Some(target),
+            None if index == self.routes.len() => self.default.as_ref(),
+            None => None,
+        }
+    }
+
+    /// Returns the target that answers `run`.
+    #[must_use]
+    pub fn route(&self, run: RunNumber) -> Option<&T> {
+        self.target(self.index(run)?)
+    }
+
+    /// Splits `runs` into the selection each target has to answer, as `(index, target, runs)`
+    /// triples with non-empty selections.
+    ///
+    /// Explicit run lists are split exactly. For ranges and [`RunSet::All`], a route receives the
+    /// intersection with its range and the default receives the whole selection, so results for
+    /// runs whose [`RunRouter::index`] differs from the target's index must be discarded.
+    pub fn plan(&self, runs: &RunSet) -> Vec<(usize, &T, RunSet)> {
+        let split = |index: usize, range: Option<&RangeInclusive<RunNumber>>| match runs {
+            RunSet::Runs(list) => RunSet::runs(
+                list.iter()
+                    .copied()
+                    .filter(|&run| self.index(run) == Some(index)),
+            ),
+            other => range.map_or_else(|| other.clone(), |range| other.intersect(range)),
+        };
+        self.routes
+            .iter()
+            .enumerate()
+            .map(|(index, (range, target))| (index, target, split(index, Some(range))))
+            .chain(
+                self.default
+                    .iter()
+                    .map(|target| (self.routes.len(), target, split(self.routes.len(), None))),
+            )
+            .filter(|(_, _, selection)| !selection.is_empty())
+            .collect()
+    }
+}
@@ -0,0 +1,321 @@
+//! Run selections shared by the CCDB and RCDB query contexts.
+//!
+//! A [`RunSet`] is built once (from explicit runs, a range, or a [`RunPeriod`]) and handed to any
+//! [`RunContext`], so both databases are queried for exactly the same runs. Ranges are always
+//! normalized to inclusive bounds.
+//!
+//! Run sets can also be parsed from a compact selection string (see [`RunSet::from_str`]), the
+//! syntax every command-line tool in the workspace accepts for choosing runs.
+use std::{
+    ops::{Bound, RangeBounds, RangeInclusive},
+    str::FromStr,
+};
+
+use crate::{
+    constants::{MAX_RUN_NUMBER, MIN_RUN_NUMBER},
+    errors::ParseRunSetError,
+    run_periods::RunPeriod,
+    RunNumber,
+};
+
+/// Largest number of runs a parsed selection may expand into when it cannot be represented as a
+/// single range.
+pub const MAX_EXPANDED_RUNS: u64 = 1_000_000;
+
+/// A selection of runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunSet {
+    /// Every run.
+    All,
+    /// Exactly these runs, sorted and without duplicates.
+    Runs(Vec<RunNumber>),
+    /// Every run in the inclusive range.
+    Range(RangeInclusive<RunNumber>),
+}
+
+impl RunSet {
+    /// Selects the given runs.
+    #[must_use]
+    pub fn runs(runs: impl IntoIterator<Item = RunNumber>) -> Self {
+        let mut runs: Vec<RunNumber> = runs.into_iter().collect();
+        runs.sort_unstable();
+        runs.dedup();
+        RunSet::Runs(runs)
+    }
+
+    /// Selects every run within `range`, converting exclusive and unbounded ends to inclusive
+    /// bounds within [`MIN_RUN_NUMBER`]..=[`MAX_RUN_NUMBER`]. An empty range selects no runs.
+    #[must_use]
+    pub fn range(range: impl RangeBounds<RunNumber>) -> Self {
+        let start = match range.start_bound() {
+            Bound::Included(&s) => s,
+            Bound::Excluded(&s) => s.saturating_add(1),
+            Bound::Unbounded => MIN_RUN_NUMBER,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&e) => e,
+            Bound::Excluded(&e) => e.saturating_sub(1),
+            Bound::Unbounded => MAX_RUN_NUMBER,
+        };
+        if start > end {
+            RunSet::Runs(Vec::new())
+        } else {
+            RunSet::Range(start..=end)
+        }
+    }
+
+    /// True when no runs are selected.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        match self {
+            RunSet::All => false,
+            RunSet::Runs(runs) => runs.is_empty(),
+            RunSet::Range(range) => range.is_empty(),
+        }
+    }
+
+    /// True when `run` is selected.
+    #[must_use]
+    pub fn contains(&self, run: RunNumber) -> bool {
+        match self {
+            RunSet::All => true,
+            RunSet::Runs(runs) => runs.binary_search(&run).is_ok(),
+            RunSet::Range(range) => range.contains(&run),
+        }
+    }
+
+    /// Smallest and largest run that can be selected.
+    #[must_use]
+    pub fn bounds(&self) -> Option<RangeInclusive<RunNumber>> {
+        match self {
+            RunSet::All => Some(MIN_RUN_NUMBER..=MAX_RUN_NUMBER),
+            RunSet::Runs(runs) => Some(*runs.first()?..=*runs.last()?),
+            RunSet::Range(range) => (!range.is_empty()).then(|| range.clone()),
+        }
+    }
+
+    /// Keeps only the selected runs that lie within `range`.
+    #[must_use]
+    pub fn intersect(&self, range: &RangeInclusive<RunNumber>) -> RunSet {
+        match self {
+            RunSet::All => RunSet::range(range.clone()),
+            RunSet::Runs(runs) => RunSet::Runs(
+                runs.iter()
+                    .copied()
+                    .filter(|run| range.contains(run))
+                    .collect(),
+            ),
+            RunSet::Range(own) => {
+                RunSet::range(*own.start().max(range.start())..=*own.end().min(range.end()))
+            }
+        }
+    }
+}
+
+impl From<RunPeriod> for RunSet {
+    fn from(run_period: RunPeriod) -> Self {
+        RunSet::Range(run_period.run_range())
+    }
+}
+
+impl From<RangeInclusive<RunNumber>> for RunSet {
+    fn from(range: RangeInclusive<RunNumber>) -> Self {
+        RunSet::range(range)
+    }
+}
+
+impl From<Vec<RunNumber>> for RunSet {
+    fn from(runs: Vec<RunNumber>) -> Self {
+        RunSet::runs(runs)
+    }
+}
+
+impl From<&[RunNumber]> for RunSet {
+    fn from(runs: &[RunNumber]) -> Self {
+        RunSet::runs(runs.iter().copied())
+    }
+}
+
+impl FromStr for RunSet {
+    type Err = ParseRunSetError;
+
+    /// Parses a run selection such as `S18+F18-[41050..41100]-41234`.
+    ///
+    /// A selection is a sequence of terms joined by `+` (add) or `,` (also add) and `-` (remove),
+    /// applied from left to right. Each term is one of
+    ///
+    /// - a run period name (`s18`, `F18`, `cpp/npp`, ...; case-insensitive),
+    /// - a run number (`41234`),
+    /// - an inclusive range (`41050..41100`, or equivalently `41050..=41100`),
+    /// - `all`, for every run,
+    /// - a bracketed, comma-separated list of the above (`[41050..41100, 41234]`).
+    ///
+    /// Whitespace is ignored. A selection that reduces to one contiguous range becomes
+    /// [`RunSet::Range`] (or [`RunSet::All`]); anything else is expanded into [`RunSet::Runs`].
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParseRunSetError`] for empty or unrecognized terms, unbalanced brackets,
+    /// inverted ranges, and selections that would expand into more than [`MAX_EXPANDED_RUNS`]
+    /// runs.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let text: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+        let mut selected = Intervals::default();
+        for (remove, term) in split_terms(&text, &['+', '-', ','])? {
+            for interval in parse_term(term)? {
+                if remove {
+                    selected.remove(interval);
+                } else {
+                    selected.add(interval);
+                }
+            }
+        }
+        selected.into_run_set()
+    }
+}
+
+/// Splits `text` at the top-level (unbracketed) `separators`, pairing each term with whether it
+/// follows a `-`.
+fn split_terms<'a>(
+    text: &'a str,
+    separators: &[char],
+) -> Result<Vec<(bool, &'a str)>, ParseRunSetError> {
+    let mut terms = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    let mut remove = false;
+    for (i, c) in text.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => {
+                depth = depth
+                    .checked_sub(1)
+                    .ok_or_else(|| ParseRunSetError::UnbalancedBrackets(text.to_string()))?;
+            }
+            c if depth == 0 && separators.contains(&c) => {
+                terms.push((remove, &text[start..i]));
+                remove = c == '-';
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    if depth != 0 {
+        return Err(ParseRunSetError::UnbalancedBrackets(text.to_string()));
+    }
+    terms.push((remove, &text[start..]));
+    if terms.iter().any(|(_, term)| term.is_empty()) {
+        return Err(ParseRunSetError::EmptyTerm(text.to_string()));
+    }
+    Ok(terms)
+}
+
+fn parse_term(term: &str) -> Result<Vec<(RunNumber, RunNumber)>, ParseRunSetError> {
+    if let Some(inner) = term.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+        let mut intervals = Vec::new();
+        for (_, item) in split_terms(inner, &[','])? {
+            intervals.extend(parse_term(item)?);
+        }
+        return Ok(intervals);
+    }
+    if term.eq_ignore_ascii_case("all") {
+        return Ok(vec![(MIN_RUN_NUMBER, MAX_RUN_NUMBER)]);
+    }
+    let run = |s: &str| {
+        s.parse::<RunNumber>()
+            .map_err(|_| ParseRunSetError::InvalidTerm(term.to_string()))
+    };
+    if let Some((start, end)) = term.split_once("..") {
+        let (start, end) = (run(start)?, run(end.strip_prefix('=').unwrap_or(end))?);
+        if start > end {
+            return Err(ParseRunSetError::EmptyRange(start, end));
+        }
+        return Ok(vec![(start, end)]);
+    }
+    if let Ok(run) = term.parse::<RunNumber>() {
+        return Ok(vec![(run, run)]);
+    }
+    term.parse::<RunPeriod>()
+        .map(|period| vec![(period.min_run(), period.max_run())])
+        .map_err(|_| ParseRunSetError::InvalidTerm(term.to_string()))
+}
+
+/// Sorted, disjoint, non-adjacent inclusive run intervals.
+#[derive(Default)]
+struct Intervals(Vec<(RunNumber, RunNumber)>);
+
+impl Intervals {
+    fn add(&mut self, (start, end): (RunNumber, RunNumber)) {
+        let (mut start, mut end) = (start, end);
+        self.0.retain(|&(s, e)| {
+            let touches = s <= end.saturating_add(1) && start <= e.saturating_add(1);
+            if touches {
+                start = start.min(s);
+                end = end.max(e);
+            }
+            !touches
+        });
+        let index = self.0.partition_point(|&(s, _)| s < start);
+        self.0.insert(index, (start, end));
+    }
+
+    fn remove(&mut self, (start, end): (RunNumber, RunNumber)) {
+        self.0 = self
+            .0
+            .iter()
+            .flat_map(|&(s, e)| {
+                if e < start || s > end {
+                    return vec![(s, e)];
+                }
+                let mut kept = Vec::new();
+                if s < start {
+                    kept.push((s, start - 1));
+                }
+                if e > end {
+                    kept.push((end + 1, e));
+                }
+                kept
+            })
+            .collect();
+    }
+
+    fn into_run_set(self) -> Result<RunSet, ParseRunSetError> {
+        match self.0.as_slice() {
+            [] => Ok(RunSet::Runs(Vec::new())),
+            [(MIN_RUN_NUMBER, MAX_RUN_NUMBER)] => Ok(RunSet::All),
+            [(start, end)] if start == end => Ok(RunSet::Runs(vec![*start])),
+            [(start, end)] => Ok(RunSet::Range(*start..=*end)),
+            intervals => {
+                let count = intervals
+                    .iter()
+                    .map(|(s, e)| (e - s).unsigned_abs() + 1)
+                    .sum();
+                if count > MAX_EXPANDED_RUNS {
+                    return Err(ParseRunSetError::TooManyRuns(count));
+                }
+                Ok(RunSet::Runs(
+                    intervals.iter().flat_map(|&(s, e)| s..=e).collect(),
+                ))
+            }
+        }
+    }
+}
+
+/// A query context whose run selection can be set from a [`RunSet`].
+///
+/// Implemented by `gluex_ccdb::context::Context` and `gluex_rcdb::context::Context`, so code that
+/// queries both databases (or is generic over either) can build them from the same input.
+pub trait RunContext: Sized {
+    /// Replaces the context's run selection.
+    #[must_use]
+    fn with_run_set(self, runs: RunSet) -> Self;
+
+    /// Returns the context's current run selection.
+    fn run_set(&self) -> RunSet;
+
+    /// Restricts the context to the runs of `run_period`.
+    #[must_use]
+    fn with_period(self, run_period: RunPeriod) -> Self {
+        self.with_run_set(run_period.into())
+    }
+}
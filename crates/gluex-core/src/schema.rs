@@ -0,0 +1,75 @@
+//! Handling of snapshots written with a newer database schema than this build supports.
+//!
+//! CCDB and RCDB snapshots record their schema version (`schemaVersions` and `schema_versions`).
+//! When a snapshot declares a newer version than the crate was written for, opening it fails with
+//! a [`NewerSchemaError`] that names both versions, rather than with whatever `SQLite` error the
+//! first query against a changed table happens to produce. In [`SchemaMode::BestEffort`] (opened
+//! with `open_with_schema_mode`, or for every handle when [`SCHEMA_MODE_ENV`] is `best-effort`)
+//! such snapshots open anyway: the handles only read the tables and columns they know, and skip
+//! metadata they cannot interpret instead of failing, so older binaries keep working as long as
+//! the tables they use did not change.
+use std::env;
+
+use thiserror::Error;
+
+/// Environment variable that selects the [`SchemaMode`] of every database opened by the process.
+///
+/// `best-effort` selects [`SchemaMode::BestEffort`]; anything else (or nothing) is strict.
+pub const SCHEMA_MODE_ENV: &str = "GLUEX_SCHEMA_COMPAT";
+
+/// How to treat snapshots that declare a newer schema version than this build supports.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SchemaMode {
+    /// Refuse to open them with a [`NewerSchemaError`].
+    #[default]
+    Strict,
+    /// Open them, ignoring unknown tables and columns and skipping metadata that cannot be
+    /// interpreted.
+    BestEffort,
+}
+
+impl SchemaMode {
+    /// Reads the mode from [`SCHEMA_MODE_ENV`].
+    #[must_use]
+    pub fn from_env() -> Self {
+        match env::var(SCHEMA_MODE_ENV).as_deref().map(str::trim) {
+            Ok("best-effort") => Self::BestEffort,
+            _ => Self::Strict,
+        }
+    }
+
+    /// Checks a snapshot's declared schema version against the newest version this build
+    /// supports, returning true if the snapshot is newer but the mode allows opening it.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`NewerSchemaError`] if `found` is newer than `supported` in strict mode.
+    pub fn check(
+        self,
+        database: &'static str,
+        found: i64,
+        supported: i64,
+    ) -> Result<bool, NewerSchemaError> {
+        match (found > supported, self) {
+            (false, _) => Ok(false),
+            (true, Self::BestEffort) => Ok(true),
+            (true, Self::Strict) => Err(NewerSchemaError {
+                database,
+                found,
+                supported,
+            }),
+        }
+    }
+}
+
+/// A snapshot declares a newer schema version than this build supports.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{database} snapshot uses schema version {found}, but this build supports up to version {supported}; upgrade gluex-rs, or set GLUEX_SCHEMA_COMPAT=best-effort to open it anyway")]
+pub struct NewerSchemaError {
+    /// Name of the database (`CCDB` or `RCDB`).
+    pub database: &'static str,
+    /// Schema version declared by the snapshot.
+    pub found: i64,
+    /// Newest schema version this build supports.
+    pub supported: i64,
+}
@@ -0,0 +1,140 @@
+//! Logging of the SQL statements issued by the CCDB and RCDB database handles.
+//!
+//! An [`SqlAudit`] is attached with `CCDB::with_sql_audit`/`RCDB::with_sql_audit`, or to every
+//! handle opened while the [`SQL_AUDIT_ENV`] environment variable is set. The handles then append
+//! each statement behind their fetches and lookups, with its bound parameters, to the audit (the
+//! metadata loaded once while opening a database is not logged). In
+//! [`AuditMode::DryRun`] the statements that read constants, conditions, runs, or files are only
+//! logged and behave as if they returned no rows; metadata lookups (variations, columns,
+//! condition types) still run, since the data queries cannot be built without them.
+use std::{
+    env, fmt,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+/// Environment variable that enables auditing for every database opened by the process.
+///
+/// `log` logs and executes statements; `dry-run` logs them without executing data queries. In
+/// both cases the statements are echoed to stderr.
+pub const SQL_AUDIT_ENV: &str = "GLUEX_SQL_AUDIT";
+
+/// Whether audited statements are executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditMode {
+    /// Log every statement and execute it as usual.
+    Log,
+    /// Log every statement but skip those that read data, which return no rows instead.
+    DryRun,
+}
+
+/// A statement issued (or, in a dry run, skipped) by a database handle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditedStatement {
+    /// Path of the database file the statement targets.
+    pub database: String,
+    /// SQL text with `?` placeholders.
+    pub sql: String,
+    /// Bound parameters, in placeholder order, as SQL literals.
+    pub params: Vec<String>,
+    /// False when the statement was skipped by a dry run.
+    pub executed: bool,
+}
+
+impl fmt::Display for AuditedStatement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sql = self.sql.split_whitespace().collect::<Vec<_>>().join(" ");
+        write!(
+            f,
+            "[{}{}] {sql} -- params: [{}]",
+            self.database,
+            if self.executed { "" } else { ", dry run" },
+            self.params.join(", ")
+        )
+    }
+}
+
+/// Formats `text` as a quoted SQL string literal.
+#[must_use]
+pub fn text_literal(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+/// Shared, thread-safe statement log; cloning yields another handle to the same log.
+#[derive(Debug, Clone)]
+pub struct SqlAudit {
+    mode: AuditMode,
+    echo: bool,
+    statements: Arc<Mutex<Vec<AuditedStatement>>>,
+}
+
+impl SqlAudit {
+    /// Creates an empty audit in the given mode.
+    #[must_use]
+    pub fn new(mode: AuditMode) -> Self {
+        Self {
+            mode,
+            echo: false,
+            statements: Arc::default(),
+        }
+    }
+
+    /// Builds an audit that echoes to stderr from [`SQL_AUDIT_ENV`], or `None` if the variable
+    /// is unset or not one of `log` and `dry-run`.
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        let mode = match env::var(SQL_AUDIT_ENV).ok()?.trim() {
+            "log" => AuditMode::Log,
+            "dry-run" => AuditMode::DryRun,
+            _ => return None,
+        };
+        Some(Self::new(mode).with_echo(true))
+    }
+
+    /// Also prints every statement to stderr as it is logged.
+    #[must_use]
+    pub fn with_echo(mut self, echo: bool) -> Self {
+        self.echo = echo;
+        self
+    }
+
+    /// Returns the audit mode.
+    #[must_use]
+    pub fn mode(&self) -> AuditMode {
+        self.mode
+    }
+
+    /// Logs a statement and returns whether the caller should execute it.
+    ///
+    /// `reads_data` marks statements that are skipped in [`AuditMode::DryRun`].
+    pub fn log(&self, database: &str, sql: &str, params: Vec<String>, reads_data: bool) -> bool {
+        let statement = AuditedStatement {
+            database: database.to_string(),
+            sql: sql.to_string(),
+            params,
+            executed: !(reads_data && self.mode == AuditMode::DryRun),
+        };
+        if self.echo {
+            eprintln!("{statement}");
+        }
+        let executed = statement.executed;
+        self.lock().push(statement);
+        executed
+    }
+
+    /// Returns a copy of every statement logged so far.
+    #[must_use]
+    pub fn statements(&self) -> Vec<AuditedStatement> {
+        self.lock().clone()
+    }
+
+    /// Clears the log.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<AuditedStatement>> {
+        self.statements
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
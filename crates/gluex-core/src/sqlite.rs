@@ -0,0 +1,242 @@
+//! `SQLite` storage shared by the CCDB and RCDB clients.
+//!
+//! Both crates define their own `StorageBackend` trait, with their own error type and SQL dialect
+//! hooks, and implement it for the [`SqliteBackend`] here. Rows, the connection pool, statement
+//! deadlines, and query plans are therefore handled the same way for both databases.
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use parking_lot::{Mutex, MutexGuard};
+use rusqlite::{
+    params_from_iter,
+    types::{FromSql, FromSqlError, ValueRef},
+    Connection, ErrorCode,
+};
+
+/// Dynamically typed value of a statement parameter or result column.
+pub use rusqlite::types::Value as SqlValue;
+
+use crate::{
+    limits::{LimitError, QueryBudget},
+    query_plan::QueryPlanStep,
+    Instant,
+};
+
+/// One result row handed out by a storage backend, holding a value for every selected column.
+#[derive(Debug, Clone, Copy)]
+pub struct Row<'a> {
+    values: &'a [SqlValue],
+}
+
+impl<'a> Row<'a> {
+    /// Wraps the column values of a row, in the order they were selected.
+    #[must_use]
+    pub fn new(values: &'a [SqlValue]) -> Self {
+        Self { values }
+    }
+
+    /// Returns the raw column values.
+    #[must_use]
+    pub fn values(&self) -> &'a [SqlValue] {
+        self.values
+    }
+
+    /// Converts the value of column `index`, with the same rules as [`rusqlite::Row::get`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the column does not exist or its value cannot be converted to `T`.
+    pub fn get<T: FromSql>(&self, index: usize) -> rusqlite::Result<T> {
+        let value = ValueRef::from(
+            self.values
+                .get(index)
+                .ok_or(rusqlite::Error::InvalidColumnIndex(index))?,
+        );
+        T::column_result(value).map_err(|err| match err {
+            FromSqlError::InvalidType => {
+                rusqlite::Error::InvalidColumnType(index, index.to_string(), value.data_type())
+            }
+            FromSqlError::OutOfRange(value) => {
+                rusqlite::Error::IntegralValueOutOfRange(index, value)
+            }
+            err => {
+                rusqlite::Error::FromSqlConversionFailure(index, value.data_type(), Box::new(err))
+            }
+        })
+    }
+}
+
+/// Storage backend reading a CCDB or RCDB `SQLite` file through a pool of connections.
+///
+/// Each statement runs on a connection no other thread is using, so clones of a handle can query
+/// concurrently up to the pool size. A backend made with [`SqliteBackend::new`] has a pool of
+/// one, which serializes its statements; [`SqliteBackend::with_connection`] grows the pool.
+pub struct SqliteBackend {
+    connections: Vec<Mutex<Connection>>,
+    next: AtomicUsize,
+    location: String,
+}
+
+impl SqliteBackend {
+    /// Wraps an open connection, reporting `location` as the database's location.
+    pub fn new(connection: Connection, location: impl Into<String>) -> Self {
+        Self {
+            connections: vec![Mutex::new(connection)],
+            next: AtomicUsize::new(0),
+            location: location.into(),
+        }
+    }
+
+    /// Adds another connection to the same database to the pool.
+    #[must_use]
+    pub fn with_connection(mut self, connection: Connection) -> Self {
+        self.connections.push(Mutex::new(connection));
+        self
+    }
+
+    /// Returns the number of pooled connections.
+    #[must_use]
+    pub fn pool_size(&self) -> usize {
+        self.connections.len()
+    }
+
+    /// Returns the location reported for the database, usually its file path.
+    #[must_use]
+    pub fn location(&self) -> &str {
+        &self.location
+    }
+
+    /// Returns an idle pooled [`rusqlite::Connection`], waiting for one if all are in use.
+    pub fn connection(&self) -> MutexGuard<'_, Connection> {
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        let size = self.connections.len();
+        (0..size)
+            .find_map(|offset| self.connections[(start + offset) % size].try_lock())
+            .unwrap_or_else(|| self.connections[start % size].lock())
+    }
+
+    /// Runs a read-only statement with positional parameters and calls `on_row` for every
+    /// result row, in order, stopping at the first error it returns.
+    ///
+    /// A statement still running once `budget`'s deadline passes is interrupted and reported as
+    /// the budget's timeout error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the statement fails, runs past the deadline, or `on_row` fails.
+    pub fn query<E>(
+        &self,
+        sql: &str,
+        params: &[SqlValue],
+        budget: Option<&QueryBudget>,
+        on_row: &mut dyn FnMut(Row<'_>) -> Result<(), E>,
+    ) -> Result<(), E>
+    where
+        E: From<rusqlite::Error> + From<LimitError>,
+    {
+        let connection = self.connection();
+        let _deadline = match budget {
+            Some(budget) => Deadline::install(&connection, budget)?,
+            None => None,
+        };
+        let mut stmt = connection.prepare_cached(sql)?;
+        let columns = stmt.column_count();
+        let mut rows = interrupted::<_, E>(stmt.query(params_from_iter(params)), budget)?;
+        let mut values = Vec::with_capacity(columns);
+        while let Some(row) = interrupted::<_, E>(rows.next(), budget)? {
+            values.clear();
+            for index in 0..columns {
+                values.push(row.get::<_, SqlValue>(index)?);
+            }
+            on_row(Row::new(&values))?;
+        }
+        Ok(())
+    }
+
+    /// Returns the output of `EXPLAIN QUERY PLAN` for a statement, without running it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the statement cannot be prepared.
+    pub fn explain(&self, sql: &str, params: &[SqlValue]) -> rusqlite::Result<Vec<QueryPlanStep>> {
+        let connection = self.connection();
+        let mut stmt = connection.prepare(&format!("EXPLAIN QUERY PLAN {sql}"))?;
+        let steps = stmt
+            .query_map(params_from_iter(params), |row| {
+                Ok(QueryPlanStep {
+                    id: row.get(0)?,
+                    parent: row.get(1)?,
+                    detail: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(steps)
+    }
+
+    /// Returns the name of the first index on `table` that covers `column`, if there is one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the indexes of `table` cannot be listed.
+    pub fn index_on(&self, table: &str, column: &str) -> rusqlite::Result<Option<String>> {
+        let connection = self.connection();
+        let mut stmt = connection.prepare(&format!("PRAGMA index_list('{table}')"))?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let index_name: String = row.get(1)?;
+            if index_has_column(&connection, &index_name, column)? {
+                return Ok(Some(index_name));
+            }
+        }
+        Ok(None)
+    }
+}
+
+fn index_has_column(
+    connection: &Connection,
+    index_name: &str,
+    column_name: &str,
+) -> rusqlite::Result<bool> {
+    let pragma = format!("PRAGMA index_info('{index_name}')");
+    let mut stmt = connection.prepare(&pragma)?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let col_name: String = row.get(2)?;
+        if col_name == column_name {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Interrupts the statements running on a connection once a fetch's deadline passes, until
+/// dropped.
+struct Deadline<'a> {
+    connection: &'a Connection,
+}
+
+impl<'a> Deadline<'a> {
+    fn install(connection: &'a Connection, budget: &QueryBudget) -> rusqlite::Result<Option<Self>> {
+        let Some(deadline) = budget.deadline() else {
+            return Ok(None);
+        };
+        connection.progress_handler(1000, Some(move || Instant::now() > deadline))?;
+        Ok(Some(Self { connection }))
+    }
+}
+
+impl Drop for Deadline<'_> {
+    fn drop(&mut self) {
+        let _ = self.connection.progress_handler(0, None::<fn() -> bool>);
+    }
+}
+
+/// Reports a statement interrupted by a [`Deadline`] as a timeout.
+fn interrupted<T, E>(result: rusqlite::Result<T>, budget: Option<&QueryBudget>) -> Result<T, E>
+where
+    E: From<rusqlite::Error> + From<LimitError>,
+{
+    result.map_err(|err| match (err.sqlite_error_code(), budget) {
+        (Some(ErrorCode::OperationInterrupted), Some(budget)) => budget.timeout_error().into(),
+        _ => err.into(),
+    })
+}
@@ -0,0 +1,149 @@
+//! Physical units of well-known RCDB conditions.
+//!
+//! RCDB stores numbers without units, and the conventions differ from condition to condition (the
+//! beam current is in nA, the solenoid current in A, beam energies in MeV). [`condition_unit`]
+//! looks up the unit a condition is stored in, and `gluex_rcdb` tags fetched values with it so
+//! that `Value::as_quantity` returns a [`Quantity`] that can be converted explicitly with
+//! [`Quantity::to`] instead of being guessed at.
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Physical dimension of a [`Unit`]; only units of the same dimension convert into each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dimension {
+    /// Electric current.
+    Current,
+    /// Energy.
+    Energy,
+    /// Plane angle.
+    Angle,
+}
+
+/// A unit of measurement used by RCDB conditions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Unit {
+    /// Ampere.
+    A,
+    /// Microampere.
+    #[serde(rename = "uA")]
+    MicroA,
+    /// Nanoampere.
+    #[serde(rename = "nA")]
+    NanoA,
+    /// Gigaelectronvolt.
+    GeV,
+    /// Megaelectronvolt.
+    MeV,
+    /// Degree of arc.
+    #[serde(rename = "deg")]
+    Degree,
+    /// Radian.
+    #[serde(rename = "rad")]
+    Radian,
+}
+
+impl Unit {
+    /// Returns the unit's symbol, such as `nA` or `GeV`.
+    #[must_use]
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::A => "A",
+            Self::MicroA => "uA",
+            Self::NanoA => "nA",
+            Self::GeV => "GeV",
+            Self::MeV => "MeV",
+            Self::Degree => "deg",
+            Self::Radian => "rad",
+        }
+    }
+
+    /// Returns the physical dimension of the unit.
+    #[must_use]
+    pub fn dimension(&self) -> Dimension {
+        match self {
+            Self::A | Self::MicroA | Self::NanoA => Dimension::Current,
+            Self::GeV | Self::MeV => Dimension::Energy,
+            Self::Degree | Self::Radian => Dimension::Angle,
+        }
+    }
+
+    /// Size of the unit in the reference unit of its dimension (A, GeV, or rad).
+    fn scale(self) -> f64 {
+        match self {
+            Self::A | Self::GeV | Self::Radian => 1.0,
+            Self::MeV => 1e-3,
+            Self::MicroA => 1e-6,
+            Self::NanoA => 1e-9,
+            Self::Degree => std::f64::consts::PI / 180.0,
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+/// A number tagged with the unit it is expressed in.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Quantity {
+    /// Numeric value in `unit`.
+    pub value: f64,
+    /// Unit of `value`.
+    pub unit: Unit,
+}
+
+impl Quantity {
+    /// Creates a quantity of `value` in `unit`.
+    #[must_use]
+    pub fn new(value: f64, unit: Unit) -> Self {
+        Self { value, unit }
+    }
+
+    /// Converts the quantity to `unit`, or returns `None` if the dimensions differ.
+    #[must_use]
+    pub fn to(&self, unit: Unit) -> Option<Self> {
+        (self.unit.dimension() == unit.dimension()).then(|| {
+            Self::new(
+                if self.unit == unit {
+                    self.value
+                } else {
+                    self.value * self.unit.scale() / unit.scale()
+                },
+                unit,
+            )
+        })
+    }
+
+    /// Returns the numeric value in `unit`, or `None` if the dimensions differ.
+    #[must_use]
+    pub fn value_in(&self, unit: Unit) -> Option<f64> {
+        self.to(unit).map(|quantity| quantity.value)
+    }
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.value, self.unit)
+    }
+}
+
+/// Well-known numeric RCDB conditions and the units they are stored in.
+pub const CONDITION_UNITS: &[(&str, Unit)] = &[
+    ("beam_current", Unit::NanoA),
+    ("beam_energy", Unit::MeV),
+    ("coherent_peak", Unit::MeV),
+    ("solenoid_current", Unit::A),
+    ("polarization_angle", Unit::Degree),
+];
+
+/// Returns the unit the RCDB condition `name` is stored in, if it is a well-known condition.
+#[must_use]
+pub fn condition_unit(name: &str) -> Option<Unit> {
+    CONDITION_UNITS
+        .iter()
+        .find(|(condition, _)| *condition == name)
+        .map(|(_, unit)| *unit)
+}
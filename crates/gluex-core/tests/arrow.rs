@@ -0,0 +1,146 @@
+#![cfg(feature = "arrow")]
+#![allow(missing_docs)]
+
+use std::{
+    ffi::{c_char, c_int, c_void, CStr},
+    mem::MaybeUninit,
+    slice,
+};
+
+use gluex_core::arrow::{ArrowArrayStream, ArrowColumn, ArrowValues};
+
+// Consumer-side mirrors of the Arrow C data interface structs, read the way a C consumer would.
+#[repr(C)]
+struct Schema {
+    format: *const c_char,
+    name: *const c_char,
+    metadata: *const c_char,
+    flags: i64,
+    n_children: i64,
+    children: *mut *mut Schema,
+    dictionary: *mut Schema,
+    release: Option<unsafe extern "C" fn(*mut Schema)>,
+    private_data: *mut c_void,
+}
+
+#[repr(C)]
+struct Array {
+    length: i64,
+    null_count: i64,
+    offset: i64,
+    n_buffers: i64,
+    n_children: i64,
+    buffers: *mut *const c_void,
+    children: *mut *mut Array,
+    dictionary: *mut Array,
+    release: Option<unsafe extern "C" fn(*mut Array)>,
+    private_data: *mut c_void,
+}
+
+#[repr(C)]
+struct Stream {
+    get_schema: Option<unsafe extern "C" fn(*mut Stream, *mut Schema) -> c_int>,
+    get_next: Option<unsafe extern "C" fn(*mut Stream, *mut Array) -> c_int>,
+    get_last_error: Option<unsafe extern "C" fn(*mut Stream) -> *const c_char>,
+    release: Option<unsafe extern "C" fn(*mut Stream)>,
+    private_data: *mut c_void,
+}
+
+fn cstr(ptr: *const c_char) -> String {
+    unsafe { CStr::from_ptr(ptr) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[test]
+fn stream_exports_one_struct_batch() {
+    let columns = vec![
+        ArrowColumn::new("run", ArrowValues::Int64(vec![10, 11, 12])),
+        ArrowColumn::new("current", ArrowValues::Float64(vec![1.5, 0.0, 2.5]))
+            .with_validity(vec![true, false, true]),
+        ArrowColumn::new("flag", ArrowValues::Boolean(vec![true, false, true])),
+        ArrowColumn::new(
+            "type",
+            ArrowValues::Utf8(vec!["a".into(), String::new(), "ccc".into()]),
+        ),
+    ];
+    let mut exported = ArrowArrayStream::new(columns);
+    let stream = (&raw mut exported).cast::<Stream>();
+
+    unsafe {
+        let mut schema = MaybeUninit::<Schema>::uninit();
+        assert_eq!(
+            ((*stream).get_schema.unwrap())(stream, schema.as_mut_ptr()),
+            0
+        );
+        let mut schema = schema.assume_init();
+        assert_eq!(cstr(schema.format), "+s");
+        assert_eq!(schema.n_children, 4);
+        let children = slice::from_raw_parts(schema.children, 4);
+        let fields: Vec<(String, String)> = children
+            .iter()
+            .map(|&child| (cstr((*child).name), cstr((*child).format)))
+            .collect();
+        assert_eq!(
+            fields,
+            [("run", "l"), ("current", "g"), ("flag", "b"), ("type", "u")]
+                .map(|(name, format)| (name.to_string(), format.to_string()))
+        );
+        (schema.release.unwrap())(&raw mut schema);
+        assert!(schema.release.is_none());
+
+        let mut batch = MaybeUninit::<Array>::uninit();
+        assert_eq!(((*stream).get_next.unwrap())(stream, batch.as_mut_ptr()), 0);
+        let mut batch = batch.assume_init();
+        assert_eq!((batch.length, batch.n_children), (3, 4));
+        let arrays = slice::from_raw_parts(batch.children, 4);
+
+        let run = &*arrays[0];
+        let buffers = slice::from_raw_parts(run.buffers, 2);
+        assert!(buffers[0].is_null());
+        assert_eq!(
+            slice::from_raw_parts(buffers[1].cast::<i64>(), 3),
+            [10, 11, 12]
+        );
+
+        let current = &*arrays[1];
+        assert_eq!(current.null_count, 1);
+        let buffers = slice::from_raw_parts(current.buffers, 2);
+        assert_eq!(*buffers[0].cast::<u8>(), 0b101);
+
+        let flag = &*arrays[2];
+        let buffers = slice::from_raw_parts(flag.buffers, 2);
+        assert_eq!(*buffers[1].cast::<u8>(), 0b101);
+
+        let text = &*arrays[3];
+        assert_eq!(text.n_buffers, 3);
+        let buffers = slice::from_raw_parts(text.buffers, 3);
+        assert_eq!(
+            slice::from_raw_parts(buffers[1].cast::<i32>(), 4),
+            [0, 1, 1, 4]
+        );
+        assert_eq!(slice::from_raw_parts(buffers[2].cast::<u8>(), 4), b"accc");
+
+        (batch.release.unwrap())(&raw mut batch);
+        assert!(batch.release.is_none());
+
+        let mut end = MaybeUninit::<Array>::uninit();
+        assert_eq!(((*stream).get_next.unwrap())(stream, end.as_mut_ptr()), 0);
+        assert!(end.assume_init().release.is_none());
+    }
+}
+
+#[test]
+fn moved_stream_is_not_released_twice() {
+    let mut exported =
+        ArrowArrayStream::new(vec![ArrowColumn::new("x", ArrowValues::Int32(vec![1, 2]))]);
+    let stream = (&raw mut exported).cast::<Stream>();
+    unsafe {
+        // Move the stream out the way `ArrowArrayStream_Move` does, then release the moved copy.
+        let mut moved = stream.read();
+        (*stream).release = None;
+        (moved.release.unwrap())(&raw mut moved);
+        assert!(moved.release.is_none());
+    }
+    drop(exported);
+}
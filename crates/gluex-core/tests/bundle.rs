@@ -0,0 +1,112 @@
+#![cfg(feature = "bundle")]
+#![allow(missing_docs)]
+
+use std::{
+    fs,
+    io::{Read, Write},
+    path::PathBuf,
+};
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use gluex_core::bundle::{
+    read_manifest, unbundled_path, verify, write_bundle, BundleError, BundleManifest, CCDB_FILE,
+    RCDB_FILE,
+};
+
+const FAKE_CCDB: &[u8] = b"SQLite format 3\0ccdb constants";
+const FAKE_RCDB: &[u8] = b"SQLite format 3\0rcdb conditions";
+
+fn temp_path(name: &str) -> PathBuf {
+    // every test points the cache at the same directory, so setting it concurrently is harmless
+    std::env::set_var(
+        "GLUEX_CACHE_DIR",
+        std::env::temp_dir().join(format!("gluex-bundle-cache-{}", std::process::id())),
+    );
+    std::env::temp_dir().join(format!("gluex-bundle-{}-{name}", std::process::id()))
+}
+
+fn bundle(name: &str, files: &[(&str, &[u8])]) -> (PathBuf, BundleManifest) {
+    let sources: Vec<(&str, PathBuf)> = files
+        .iter()
+        .map(|(file, content)| {
+            let source = temp_path(&format!("{name}-{file}"));
+            fs::write(&source, content).unwrap();
+            (*file, source)
+        })
+        .collect();
+    let path = temp_path(&format!("{name}.gluex"));
+    let manifest = write_bundle(
+        &path,
+        BundleManifest::new(30000, 30010, vec!["/PHOTON_BEAM".into()], Vec::new()),
+        &sources
+            .iter()
+            .map(|(file, source)| (*file, source.as_path()))
+            .collect::<Vec<_>>(),
+    )
+    .unwrap();
+    (path, manifest)
+}
+
+#[test]
+fn bundles_round_trip_through_the_cache() {
+    let (path, written) = bundle(
+        "round-trip",
+        &[(CCDB_FILE, FAKE_CCDB), (RCDB_FILE, FAKE_RCDB)],
+    );
+    assert_eq!(written.files.len(), 2);
+    let manifest = read_manifest(&path).unwrap();
+    assert_eq!(manifest, written);
+    assert_eq!((manifest.run_min, manifest.run_max), (30000, 30010));
+    assert_eq!(verify(&path).unwrap(), written);
+
+    let ccdb = unbundled_path(&path, CCDB_FILE).unwrap();
+    let rcdb = unbundled_path(&path, RCDB_FILE).unwrap();
+    assert_ne!(ccdb, rcdb);
+    assert_eq!(fs::read(&ccdb).unwrap(), FAKE_CCDB);
+    assert_eq!(fs::read(&rcdb).unwrap(), FAKE_RCDB);
+    assert_eq!(unbundled_path(&path, CCDB_FILE).unwrap(), ccdb);
+
+    let plain = temp_path("ccdb.sqlite");
+    assert_eq!(unbundled_path(&plain, CCDB_FILE).unwrap(), plain);
+}
+
+#[test]
+fn missing_databases_are_reported() {
+    let (path, _) = bundle("ccdb-only", &[(CCDB_FILE, FAKE_CCDB)]);
+    assert!(matches!(
+        unbundled_path(&path, RCDB_FILE),
+        Err(BundleError::MissingFile { file, .. }) if file == RCDB_FILE
+    ));
+}
+
+#[test]
+fn corrupted_bundles_are_rejected() {
+    let (path, _) = bundle("corrupt", &[(CCDB_FILE, FAKE_CCDB)]);
+    let mut archive = Vec::new();
+    GzDecoder::new(fs::File::open(&path).unwrap())
+        .read_to_end(&mut archive)
+        .unwrap();
+    let offset = archive
+        .windows(FAKE_CCDB.len())
+        .position(|window| window == FAKE_CCDB)
+        .unwrap();
+    archive[offset + FAKE_CCDB.len() - 1] ^= 1;
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&archive).unwrap();
+    fs::write(&path, encoder.finish().unwrap()).unwrap();
+    assert!(matches!(
+        unbundled_path(&path, CCDB_FILE),
+        Err(BundleError::ChecksumMismatch { .. })
+    ));
+    assert!(matches!(
+        verify(&path),
+        Err(BundleError::ChecksumMismatch { .. })
+    ));
+
+    let garbage = temp_path("garbage.gluex");
+    fs::write(&garbage, b"not a bundle").unwrap();
+    assert!(matches!(
+        read_manifest(&garbage),
+        Err(BundleError::InvalidArchive { .. })
+    ));
+}
@@ -0,0 +1,145 @@
+#![cfg(feature = "columnar")]
+#![allow(missing_docs)]
+
+use std::{fs, path::PathBuf};
+
+use gluex_core::{
+    arrow::{ArrowColumn, ArrowValues},
+    columnar::{
+        arrow_array::{
+            cast::AsArray,
+            types::{Float64Type, Int64Type, TimestampMicrosecondType},
+            Array,
+        },
+        write_ipc, CacheManifest, ColumnarCache, ColumnarError, ColumnarFile,
+    },
+};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("gluex-columnar-{}-{name}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn cache_round_trips_tables_and_conditions() -> Result<(), ColumnarError> {
+    let dir = temp_dir("round-trip");
+    let mut cache = ColumnarCache::create(
+        &dir,
+        CacheManifest {
+            runs: vec![30274, 30275],
+            variation: Some("default".to_string()),
+            ..CacheManifest::default()
+        },
+    )?;
+    cache.write_table(
+        "/PHOTON_BEAM/endpoint_energy",
+        vec![
+            ArrowColumn::new("run", ArrowValues::Int64(vec![30274, 30275])),
+            ArrowColumn::new("row", ArrowValues::UInt32(vec![0, 0])),
+            ArrowColumn::new(
+                "PSC_endpoint_energy",
+                ArrowValues::Float64(vec![11.6, 11.7]),
+            ),
+        ],
+    )?;
+    cache.write_conditions(vec![
+        ArrowColumn::new("run", ArrowValues::Int64(vec![30274, 30275])),
+        ArrowColumn::new("beam_current", ArrowValues::Float64(vec![150.0, 0.0]))
+            .with_validity(vec![true, false]),
+        ArrowColumn::new(
+            "run_type",
+            ArrowValues::Utf8(vec!["hd_all".into(), "".into()]),
+        ),
+        ArrowColumn::new(
+            "run_start_time",
+            ArrowValues::TimestampMicros(vec![1_000_000, 2_000_000]),
+        ),
+    ])?;
+    cache.save()?;
+
+    let cache = ColumnarCache::open(&dir)?;
+    assert_eq!(cache.manifest().runs, [30274, 30275]);
+    assert_eq!(
+        cache.manifest().conditions,
+        ["beam_current", "run_type", "run_start_time"]
+    );
+    assert!(dir.join("ccdb/PHOTON_BEAM/endpoint_energy.arrow").is_file());
+
+    let table = cache.table("/PHOTON_BEAM/endpoint_energy")?;
+    assert_eq!(table.num_rows(), 2);
+    let energy = table
+        .column("PSC_endpoint_energy")
+        .unwrap()
+        .as_primitive::<Float64Type>();
+    assert_eq!(energy.values(), &[11.6, 11.7]);
+
+    let conditions = cache.conditions()?;
+    let runs = conditions
+        .column("run")
+        .unwrap()
+        .as_primitive::<Int64Type>();
+    assert_eq!(runs.values(), &[30274, 30275]);
+    let current = conditions.column("beam_current").unwrap();
+    assert!(current.is_valid(0) && current.is_null(1));
+    assert_eq!(
+        conditions
+            .column("run_type")
+            .unwrap()
+            .as_string::<i32>()
+            .value(0),
+        "hd_all"
+    );
+    let start = conditions
+        .column("run_start_time")
+        .unwrap()
+        .as_primitive::<TimestampMicrosecondType>();
+    assert_eq!(start.value(1), 2_000_000);
+    assert_eq!(start.timezone(), Some("UTC"));
+
+    assert!(matches!(
+        cache.table("/PHOTON_BEAM/missing"),
+        Err(ColumnarError::TableNotMaterialized(_))
+    ));
+    fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[test]
+fn cache_rejects_escaping_table_paths_and_empty_conditions() -> Result<(), ColumnarError> {
+    let dir = temp_dir("invalid");
+    let mut cache = ColumnarCache::create(&dir, CacheManifest::default())?;
+    for path in ["/", "/../outside", "/a/./b"] {
+        assert!(matches!(
+            cache.write_table(path, Vec::new()),
+            Err(ColumnarError::InvalidTablePath(_))
+        ));
+    }
+    assert!(matches!(
+        cache.conditions(),
+        Err(ColumnarError::ConditionsNotMaterialized)
+    ));
+    fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[test]
+fn file_open_rejects_non_arrow_files() -> Result<(), ColumnarError> {
+    let dir = temp_dir("corrupt");
+    fs::create_dir_all(&dir)?;
+    let path = dir.join("bad.arrow");
+    fs::write(&path, b"not an arrow file")?;
+    assert!(matches!(
+        ColumnarFile::open(&path),
+        Err(ColumnarError::Arrow(_))
+    ));
+    fs::write(&path, b"")?;
+    assert!(ColumnarFile::open(&path).is_err());
+    write_ipc(
+        &path,
+        vec![ArrowColumn::new("flag", ArrowValues::Boolean(Vec::new()))],
+    )?;
+    assert_eq!(ColumnarFile::open(&path)?.num_rows(), 0);
+    fs::remove_dir_all(&dir)?;
+    Ok(())
+}
@@ -0,0 +1,127 @@
+#![cfg(feature = "compressed")]
+#![allow(missing_docs)]
+
+use std::{fs, io::Write, path::PathBuf};
+
+use flate2::{write::GzEncoder, Compression as Level};
+use gluex_core::compressed::{decompressed_path, CompressedSnapshotError};
+use ruzstd::encoding::{compress_to_vec, CompressionLevel};
+
+const FAKE_DB: &[u8] = b"SQLite format 3\0rest of the database";
+
+fn temp_path(name: &str) -> PathBuf {
+    // every test points the cache at the same directory, so setting it concurrently is harmless
+    std::env::set_var(
+        "GLUEX_CACHE_DIR",
+        std::env::temp_dir().join(format!("gluex-compressed-cache-{}", std::process::id())),
+    );
+    std::env::temp_dir().join(format!("gluex-compressed-{}-{name}", std::process::id()))
+}
+
+fn write_gz(path: &PathBuf, content: &[u8]) {
+    let mut encoder = GzEncoder::new(Vec::new(), Level::default());
+    encoder.write_all(content).unwrap();
+    fs::write(path, encoder.finish().unwrap()).unwrap();
+}
+
+#[test]
+fn gzip_snapshots_are_decompressed_once() {
+    let path = temp_path("ccdb.sqlite.gz");
+    write_gz(&path, FAKE_DB);
+    let cached = decompressed_path(&path).unwrap();
+    assert_ne!(cached, path);
+    assert!(cached
+        .file_name()
+        .unwrap()
+        .to_string_lossy()
+        .starts_with("gluex-compressed-"));
+    assert_eq!(fs::read(&cached).unwrap(), FAKE_DB);
+    fs::write(&cached, b"SQLite format 3\0reused").unwrap();
+    assert_eq!(decompressed_path(&path).unwrap(), cached);
+    assert_eq!(fs::read(&cached).unwrap(), b"SQLite format 3\0reused");
+}
+
+#[test]
+fn zstd_snapshots_round_trip() {
+    let path = temp_path("rcdb.sqlite.zst");
+    // a skippable frame between two data frames, as written by some archivers
+    let mut bytes = compress_to_vec(FAKE_DB, CompressionLevel::Fastest);
+    bytes.extend_from_slice(&[0x50, 0x2a, 0x4d, 0x18, 3, 0, 0, 0, 1, 2, 3]);
+    bytes.extend(compress_to_vec(
+        &FAKE_DB.repeat(1000)[..],
+        CompressionLevel::Fastest,
+    ));
+    fs::write(&path, &bytes).unwrap();
+    let cached = decompressed_path(&path).unwrap();
+    assert_ne!(cached, path);
+    let mut expected = FAKE_DB.to_vec();
+    expected.extend(FAKE_DB.repeat(1000));
+    assert_eq!(fs::read(&cached).unwrap(), expected);
+
+    let corrupt = temp_path("corrupt.sqlite.zst");
+    fs::write(&corrupt, &bytes[..bytes.len() / 2]).unwrap();
+    assert!(matches!(
+        decompressed_path(&corrupt),
+        Err(CompressedSnapshotError::Io { .. })
+    ));
+}
+
+#[test]
+fn concurrent_decompressions_do_not_share_temporary_files() {
+    let path = temp_path("concurrent.sqlite.gz");
+    let expected = FAKE_DB.repeat(10_000);
+    write_gz(&path, &expected);
+    let cached: Vec<PathBuf> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..8)
+            .map(|_| scope.spawn(|| decompressed_path(&path).unwrap()))
+            .collect();
+        workers.into_iter().map(|w| w.join().unwrap()).collect()
+    });
+    assert!(cached.windows(2).all(|w| w[0] == w[1]));
+    assert_eq!(fs::read(&cached[0]).unwrap(), expected);
+    let stem = cached[0].file_stem().unwrap().to_string_lossy().to_string();
+    let leftovers = fs::read_dir(cached[0].parent().unwrap())
+        .unwrap()
+        .filter_map(Result::ok)
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| name.starts_with(&stem) && name.ends_with(".part"))
+        .count();
+    assert_eq!(leftovers, 0);
+}
+
+#[test]
+fn plain_paths_are_returned_unchanged() {
+    let plain = temp_path("rcdb.sqlite");
+    assert_eq!(decompressed_path(&plain).unwrap(), plain);
+}
+
+#[test]
+fn integrity_checks_reject_bad_snapshots() {
+    let not_sqlite = temp_path("text.sqlite.gz");
+    write_gz(&not_sqlite, b"definitely not a database file");
+    assert!(matches!(
+        decompressed_path(&not_sqlite),
+        Err(CompressedSnapshotError::NotSqlite(_))
+    ));
+
+    let truncated = temp_path("truncated.sqlite.gz");
+    write_gz(&truncated, &FAKE_DB.repeat(100));
+    let bytes = fs::read(&truncated).unwrap();
+    fs::write(&truncated, &bytes[..bytes.len() - 6]).unwrap();
+    assert!(matches!(
+        decompressed_path(&truncated),
+        Err(CompressedSnapshotError::Io { .. })
+    ));
+
+    let checked = temp_path("checked.sqlite.gz");
+    write_gz(&checked, FAKE_DB);
+    fs::write(
+        temp_path("checked.sqlite.gz.sha256"),
+        "00ff  checked.sqlite.gz\n",
+    )
+    .unwrap();
+    assert!(matches!(
+        decompressed_path(&checked),
+        Err(CompressedSnapshotError::ChecksumMismatch { .. })
+    ));
+}
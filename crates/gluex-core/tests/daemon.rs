@@ -0,0 +1,60 @@
+#![cfg(all(feature = "daemon", unix))]
+#![allow(missing_docs)]
+
+use std::{io, os::unix::net::UnixListener, path::PathBuf, thread};
+
+use gluex_core::daemon::{read_frame, write_frame, Client, MAX_FRAME_LEN};
+use serde_json::{json, Value as JsonValue};
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("gluex-daemon-{}-{name}.sock", std::process::id()))
+}
+
+#[test]
+fn frames_round_trip_and_reject_oversized_lengths() {
+    let mut buffer = Vec::new();
+    write_frame(&mut buffer, b"{}").unwrap();
+    write_frame(&mut buffer, b"[1]").unwrap();
+    assert_eq!(&buffer[..4], &[0, 0, 0, 2]);
+    let mut reader = &buffer[..];
+    assert_eq!(read_frame(&mut reader).unwrap().unwrap(), b"{}");
+    assert_eq!(read_frame(&mut reader).unwrap().unwrap(), b"[1]");
+    assert!(read_frame(&mut reader).unwrap().is_none());
+
+    let oversized = u32::try_from(MAX_FRAME_LEN + 1).unwrap().to_be_bytes();
+    let err = read_frame(&mut &oversized[..]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    let truncated = [0, 0, 0, 8, b'{'];
+    assert!(read_frame(&mut &truncated[..]).is_err());
+}
+
+#[test]
+fn client_returns_results_and_daemon_errors() {
+    let socket = temp_path("client");
+    let _ = std::fs::remove_file(&socket);
+    let listener = UnixListener::bind(&socket).unwrap();
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        while let Some(body) = read_frame(&mut stream).unwrap() {
+            let request: JsonValue = serde_json::from_slice(&body).unwrap();
+            let response = match request["op"].as_str() {
+                Some("ping") => json!({ "result": "pong" }),
+                Some("stop") => json!({ "result": null }),
+                _ => json!({ "error": "unknown op" }),
+            };
+            write_frame(&mut stream, &serde_json::to_vec(&response).unwrap()).unwrap();
+        }
+    });
+
+    let mut client = Client::connect(&socket).unwrap();
+    assert_eq!(client.request(&json!({ "op": "ping" })).unwrap(), "pong");
+    assert_eq!(
+        client.request(&json!({ "op": "stop" })).unwrap(),
+        JsonValue::Null
+    );
+    let err = client.request(&json!({ "op": "nope" })).unwrap_err();
+    assert_eq!(err.to_string(), "unknown op");
+    drop(client);
+    server.join().unwrap();
+    std::fs::remove_file(&socket).unwrap();
+}
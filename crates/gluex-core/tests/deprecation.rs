@@ -0,0 +1,66 @@
+#![allow(missing_docs)]
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use gluex_core::deprecation::{
+    is_withdrawn, replacement_hint, DeprecatedItem, Deprecation, DeprecationWarnings,
+};
+
+#[test]
+fn replacements_are_read_from_comments() {
+    assert_eq!(
+        replacement_hint("Replaced by /PHOTON_BEAM/new_table."),
+        Some("/PHOTON_BEAM/new_table".to_string())
+    );
+    assert_eq!(
+        replacement_hint("do not use; use `calib-v2` instead"),
+        Some("calib-v2".to_string())
+    );
+    assert_eq!(
+        replacement_hint("Superseded by beam_current (2019)"),
+        Some("beam_current".to_string())
+    );
+    assert_eq!(replacement_hint("use with care"), None);
+    assert_eq!(replacement_hint("old calibration"), None);
+}
+
+#[test]
+fn withdrawn_descriptions_are_recognized() {
+    assert!(is_withdrawn("DEPRECATED: use beam_current"));
+    assert!(is_withdrawn("[Obsolete] trigger rate"));
+    assert!(is_withdrawn("withdrawn after run 40000"));
+    assert!(!is_withdrawn("Beam current, not deprecated"));
+    assert!(!is_withdrawn(""));
+}
+
+#[test]
+fn warnings_are_reported_once_per_item() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let warnings = DeprecationWarnings::new().with_callback(move |_| {
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+    let table = Deprecation::new(
+        "ccdb.sqlite",
+        DeprecatedItem::CcdbTable,
+        "/a/b",
+        "replaced by /a/c",
+    );
+    assert!(warnings.warn(table.clone()));
+    assert!(!warnings.clone().warn(table.clone()));
+    assert!(warnings.warn(Deprecation::new(
+        "ccdb.sqlite",
+        DeprecatedItem::CcdbVariation,
+        "/a/b",
+        ""
+    )));
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    assert_eq!(warnings.warnings()[0], table);
+    assert_eq!(
+        table.to_string(),
+        "CCDB table /a/b is deprecated; use /a/c instead [ccdb.sqlite]"
+    );
+}
@@ -0,0 +1,29 @@
+#![allow(missing_docs)]
+
+use std::time::Duration;
+
+use gluex_core::metrics::Metrics;
+
+#[test]
+fn report_renders_as_prometheus_text() {
+    let metrics = Metrics::new();
+    metrics.record("ccdb.fetch", Duration::from_millis(250), 3);
+    metrics.record("ccdb.fetch", Duration::from_millis(750), 1);
+    metrics.cache_hit("ccdb.column_layouts");
+    metrics.cache_hit("ccdb.column_layouts");
+    metrics.cache_miss("ccdb.column_layouts");
+    metrics.cache_miss("odd \"name\"");
+    let text = metrics.report().to_prometheus("gluex");
+    let lines: Vec<&str> = text.lines().collect();
+    assert!(lines.contains(&"# TYPE gluex_operations_total counter"));
+    assert!(lines.contains(&"gluex_operations_total{operation=\"ccdb.fetch\"} 2"));
+    assert!(lines.contains(&"gluex_operation_rows_total{operation=\"ccdb.fetch\"} 4"));
+    assert!(lines.contains(&"gluex_operation_seconds_total{operation=\"ccdb.fetch\"} 1"));
+    assert!(lines.contains(&"gluex_operation_seconds_max{operation=\"ccdb.fetch\"} 0.75"));
+    assert!(lines.contains(&"gluex_cache_hits_total{cache=\"ccdb.column_layouts\"} 2"));
+    assert!(lines.contains(&"gluex_cache_misses_total{cache=\"odd \\\"name\\\"\"} 1"));
+    assert!(lines
+        .iter()
+        .any(|line| line.starts_with("gluex_cache_hit_ratio{cache=\"ccdb.column_layouts\"} 0.66")));
+    assert!(lines.contains(&"gluex_cache_hit_ratio{cache=\"odd \\\"name\\\"\"} 0"));
+}
@@ -0,0 +1,67 @@
+#![cfg(feature = "provenance")]
+#![allow(missing_docs)]
+
+use std::collections::BTreeMap;
+
+use chrono::{TimeZone, Utc};
+use gluex_core::provenance::{
+    sha256_file, sha256_hex, CcdbQuery, DatabaseKind, Manifest, Query, RcdbQuery, Recorder,
+};
+
+fn temp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("gluex-provenance-{}-{name}", std::process::id()))
+}
+
+#[test]
+fn sha256_matches_known_digest() {
+    assert_eq!(
+        sha256_hex(b"abc"),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    let path = temp_path("abc.txt");
+    std::fs::write(&path, b"abc").unwrap();
+    assert_eq!(sha256_file(&path).unwrap(), sha256_hex(b"abc"));
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn recorder_round_trips_through_json() {
+    let database = temp_path("db.sqlite");
+    std::fs::write(&database, b"not really sqlite").unwrap();
+    let database_str = database.to_string_lossy().to_string();
+
+    let recorder = Recorder::new();
+    let shared = recorder.clone();
+    shared.register_database(&database_str, DatabaseKind::Ccdb);
+    shared.record(Query::Ccdb(CcdbQuery {
+        database: database_str.clone(),
+        table: "/test/demo/mytable".to_string(),
+        variation: "default".to_string(),
+        timestamp: Utc.with_ymd_and_hms(2020, 2, 1, 0, 0, 0).unwrap(),
+        runs: vec![1, 2],
+        assignments: BTreeMap::from([(1, 10), (2, 11)]),
+    }));
+    shared.record(Query::Rcdb(RcdbQuery {
+        database: database_str.clone(),
+        conditions: vec!["event_count".to_string()],
+        selection: "All".to_string(),
+        filters: Vec::new(),
+        runs: vec![2, 3],
+        digest: sha256_hex(b""),
+    }));
+    assert_eq!(recorder.manifest().queries.len(), 2);
+
+    let manifest_path = temp_path("manifest.json");
+    recorder.save(&manifest_path).unwrap();
+    let loaded = Manifest::load(&manifest_path).unwrap();
+    assert_eq!(loaded.queries, recorder.manifest().queries);
+    assert_eq!(loaded.ccdb_queries().count(), 1);
+    assert_eq!(loaded.rcdb_queries().count(), 1);
+    assert_eq!(
+        loaded.databases[&database_str].sha256.as_deref(),
+        Some(sha256_hex(b"not really sqlite").as_str())
+    );
+
+    std::fs::remove_file(&manifest_path).ok();
+    std::fs::remove_file(&database).ok();
+}
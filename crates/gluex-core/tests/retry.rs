@@ -0,0 +1,101 @@
+#![allow(missing_docs)]
+
+use std::{cell::Cell, time::Duration};
+
+use gluex_core::retry::RetryPolicy;
+
+fn fast(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy::new()
+        .with_max_attempts(max_attempts)
+        .with_backoff(Duration::from_millis(1), Duration::from_millis(4))
+}
+
+#[test]
+fn transient_errors_are_retried_until_success() {
+    let calls = Cell::new(0);
+    let result: Result<u32, &str> = fast(5).run(
+        |attempt| {
+            calls.set(calls.get() + 1);
+            if attempt < 3 {
+                Err("busy")
+            } else {
+                Ok(attempt)
+            }
+        },
+        |_| true,
+    );
+    assert_eq!(result, Ok(3));
+    assert_eq!(calls.get(), 3);
+}
+
+#[test]
+fn retries_stop_at_max_attempts_and_permanent_errors() {
+    let calls = Cell::new(0);
+    let result: Result<(), &str> = fast(4).run(
+        |_| {
+            calls.set(calls.get() + 1);
+            Err("busy")
+        },
+        |_| true,
+    );
+    assert_eq!(result, Err("busy"));
+    assert_eq!(calls.get(), 4);
+
+    calls.set(0);
+    let result: Result<(), &str> = fast(4).run(
+        |_| {
+            calls.set(calls.get() + 1);
+            Err("missing")
+        },
+        |error| *error != "missing",
+    );
+    assert_eq!(result, Err("missing"));
+    assert_eq!(calls.get(), 1);
+
+    calls.set(0);
+    let result: Result<(), &str> = RetryPolicy::new().run(
+        |_| {
+            calls.set(calls.get() + 1);
+            Err("busy")
+        },
+        |_| true,
+    );
+    assert_eq!(result, Err("busy"));
+    assert_eq!(calls.get(), 1);
+}
+
+#[test]
+fn timeout_prevents_attempts_past_the_deadline() {
+    let calls = Cell::new(0);
+    let result: Result<(), &str> = RetryPolicy::new()
+        .with_max_attempts(100)
+        .with_backoff(Duration::from_secs(10), Duration::from_secs(10))
+        .with_jitter(0.0)
+        .with_timeout(Duration::from_secs(1))
+        .run(
+            |_| {
+                calls.set(calls.get() + 1);
+                Err("busy")
+            },
+            |_| true,
+        );
+    assert_eq!(result, Err("busy"));
+    assert_eq!(calls.get(), 1);
+}
+
+#[test]
+fn backoff_grows_exponentially_up_to_the_cap_with_bounded_jitter() {
+    let policy = RetryPolicy::new()
+        .with_backoff(Duration::from_millis(100), Duration::from_millis(500))
+        .with_jitter(0.5);
+    assert_eq!(policy.backoff(1), Duration::from_millis(100));
+    assert_eq!(policy.backoff(2), Duration::from_millis(200));
+    assert_eq!(policy.backoff(3), Duration::from_millis(400));
+    assert_eq!(policy.backoff(4), Duration::from_millis(500));
+    assert_eq!(policy.backoff(u32::MAX), Duration::from_millis(500));
+    for _ in 0..100 {
+        let delay = policy.delay(2);
+        assert!(delay > Duration::from_millis(100) && delay <= Duration::from_millis(200));
+    }
+    assert_eq!(policy.with_jitter(0.0).delay(3), Duration::from_millis(400));
+}
@@ -0,0 +1,54 @@
+#![allow(missing_docs)]
+
+use gluex_core::{routing::RunRouter, run_set::RunSet};
+
+fn router() -> RunRouter<&'static str> {
+    RunRouter::new()
+        .with_route(10..=19, "primex")
+        .with_route(15..30, "late")
+        .with_default("standard")
+}
+
+#[test]
+fn first_matching_route_wins() {
+    let router = router();
+    assert_eq!(router.route(12), Some(&"primex"));
+    assert_eq!(router.route(19), Some(&"primex"));
+    assert_eq!(router.route(20), Some(&"late"));
+    assert_eq!(router.route(30), Some(&"standard"));
+    assert_eq!(router.index(30), Some(2));
+    assert_eq!(router.target(2), Some(&"standard"));
+    assert_eq!(router.target(3), None);
+    assert_eq!(
+        RunRouter::new().with_route(10..=19, "primex").route(5),
+        None
+    );
+}
+
+#[test]
+fn explicit_runs_are_split_exactly() {
+    let router = router();
+    let plan = router.plan(&RunSet::runs([5, 12, 18, 25, 40]));
+    assert_eq!(
+        plan,
+        vec![
+            (0, &"primex", RunSet::Runs(vec![12, 18])),
+            (1, &"late", RunSet::Runs(vec![25])),
+            (2, &"standard", RunSet::Runs(vec![5, 40])),
+        ]
+    );
+}
+
+#[test]
+fn ranges_are_intersected_with_each_route() {
+    let router = router();
+    let plan = router.plan(&RunSet::range(0..=12));
+    assert_eq!(
+        plan,
+        vec![
+            (0, &"primex", RunSet::Range(10..=12)),
+            (2, &"standard", RunSet::Range(0..=12)),
+        ]
+    );
+    assert!(RunRouter::<&str>::new().plan(&RunSet::All).is_empty());
+}
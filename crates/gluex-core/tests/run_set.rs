@@ -0,0 +1,110 @@
+use gluex_core::{
+    constants::{MAX_RUN_NUMBER, MIN_RUN_NUMBER},
+    errors::ParseRunSetError,
+    run_periods::RunPeriod,
+    run_set::RunSet,
+};
+
+#[test]
+fn ranges_are_normalized_to_inclusive_bounds() {
+    assert_eq!(RunSet::range(10..20), RunSet::Range(10..=19));
+    assert_eq!(RunSet::range(10..=20), RunSet::Range(10..=20));
+    assert_eq!(
+        RunSet::range(..),
+        RunSet::Range(MIN_RUN_NUMBER..=MAX_RUN_NUMBER)
+    );
+    let (start, end) = (20, 19);
+    assert!(RunSet::range(start..=end).is_empty());
+    assert!(RunSet::range(20..20).is_empty());
+}
+
+#[test]
+fn run_periods_include_their_last_run() {
+    let runs = RunSet::from(RunPeriod::RP2018_08);
+    assert!(runs.contains(RunPeriod::RP2018_08.max_run()));
+    assert!(runs.contains(RunPeriod::RP2018_08.min_run()));
+    assert!(!runs.contains(RunPeriod::RP2018_08.max_run() + 1));
+}
+
+#[test]
+fn explicit_runs_are_sorted_and_deduplicated() {
+    let runs = RunSet::from(vec![5, 3, 5, 1]);
+    assert_eq!(runs, RunSet::Runs(vec![1, 3, 5]));
+    assert_eq!(runs.bounds(), Some(1..=5));
+    assert!(runs.contains(3));
+    assert!(!runs.contains(4));
+    assert_eq!(RunSet::runs([]).bounds(), None);
+}
+
+#[test]
+fn selection_strings_combine_periods_ranges_and_exclusions() {
+    let runs: RunSet = "S18+F18-[41050..41100]-41234".parse().unwrap();
+    assert!(runs.contains(RunPeriod::RP2018_01.min_run()));
+    assert!(runs.contains(RunPeriod::RP2018_08.max_run()));
+    assert!(runs.contains(41049));
+    assert!(!runs.contains(41050));
+    assert!(!runs.contains(41100));
+    assert!(runs.contains(41101));
+    assert!(!runs.contains(41234));
+    assert!(!runs.contains(RunPeriod::RP2019_01.min_run()));
+}
+
+#[test]
+fn selection_strings_reduce_to_the_simplest_run_set() {
+    assert_eq!(
+        "f18".parse::<RunSet>().unwrap(),
+        RunPeriod::RP2018_08.into()
+    );
+    assert_eq!(
+        "[10..=20, 21..30]".parse::<RunSet>().unwrap(),
+        RunSet::Range(10..=30)
+    );
+    assert_eq!(
+        "30274, 30276 + 30275".parse::<RunSet>().unwrap(),
+        RunSet::Range(30274..=30276)
+    );
+    assert_eq!(
+        "1..5-3".parse::<RunSet>().unwrap(),
+        RunSet::Runs(vec![1, 2, 4, 5])
+    );
+    assert_eq!("all".parse::<RunSet>().unwrap(), RunSet::All);
+    assert_eq!("5-5".parse::<RunSet>().unwrap(), RunSet::runs([]));
+}
+
+#[test]
+fn invalid_selection_strings_are_rejected() {
+    assert!(matches!(
+        "s18+".parse::<RunSet>(),
+        Err(ParseRunSetError::EmptyTerm(_))
+    ));
+    assert!(matches!(
+        "s18-[1..5".parse::<RunSet>(),
+        Err(ParseRunSetError::UnbalancedBrackets(_))
+    ));
+    assert!(matches!(
+        "x99".parse::<RunSet>(),
+        Err(ParseRunSetError::InvalidTerm(_))
+    ));
+    assert!(matches!(
+        "20..10".parse::<RunSet>(),
+        Err(ParseRunSetError::EmptyRange(20, 10))
+    ));
+    assert!(matches!(
+        "all-5".parse::<RunSet>(),
+        Err(ParseRunSetError::TooManyRuns(_))
+    ));
+}
+
+#[test]
+fn intersections_keep_runs_within_the_range() {
+    assert_eq!(RunSet::All.intersect(&(5..=9)), RunSet::Range(5..=9));
+    assert_eq!(
+        RunSet::runs([1, 6, 12]).intersect(&(5..=9)),
+        RunSet::Runs(vec![6])
+    );
+    assert_eq!(
+        RunSet::range(1..=7).intersect(&(5..=9)),
+        RunSet::Range(5..=7)
+    );
+    assert!(RunSet::range(1..=4).intersect(&(5..=9)).is_empty());
+}
@@ -0,0 +1,32 @@
+#![allow(missing_docs)]
+
+use gluex_core::units::{condition_unit, Quantity, Unit};
+
+#[test]
+fn quantities_convert_within_a_dimension() {
+    let current = Quantity::new(150.0, Unit::NanoA);
+    let converted = current.to(Unit::MicroA).expect("currents should convert");
+    assert!((converted.value - 0.15).abs() < 1e-12);
+    assert_eq!(converted.unit, Unit::MicroA);
+    let energy = Quantity::new(11_600.0, Unit::MeV);
+    assert!((energy.value_in(Unit::GeV).unwrap() - 11.6).abs() < 1e-12);
+    assert!(
+        (Quantity::new(90.0, Unit::Degree)
+            .value_in(Unit::Radian)
+            .unwrap()
+            - std::f64::consts::FRAC_PI_2)
+            .abs()
+            < 1e-12
+    );
+    assert_eq!(energy.to(Unit::A), None);
+    assert_eq!(energy.to(Unit::MeV), Some(energy));
+    assert_eq!(current.to_string(), "150 nA");
+}
+
+#[test]
+fn well_known_conditions_have_units() {
+    assert_eq!(condition_unit("beam_current"), Some(Unit::NanoA));
+    assert_eq!(condition_unit("solenoid_current"), Some(Unit::A));
+    assert_eq!(condition_unit("beam_energy"), Some(Unit::MeV));
+    assert_eq!(condition_unit("event_count"), None);
+}
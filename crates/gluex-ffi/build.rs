@@ -0,0 +1,14 @@
+//! Generates the C header for the exported API with cbindgen.
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR")?;
+    let out_dir = std::env::var("OUT_DIR")?;
+    println!("cargo::rerun-if-changed=src");
+    println!("cargo::rerun-if-changed=cbindgen.toml");
+    cbindgen::generate_with_config(
+        &crate_dir,
+        cbindgen::Config::from_file(format!("{crate_dir}/cbindgen.toml"))?,
+    )?
+    .write_to_file(format!("{out_dir}/gluex.h"));
+    Ok(())
+}
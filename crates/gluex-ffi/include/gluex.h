@@ -0,0 +1,214 @@
+/* Generated by cbindgen from the gluex-ffi crate. Do not edit by hand. */
+
+#ifndef GLUEX_H
+#define GLUEX_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+// Result code of every fallible function.
+typedef enum GluexStatus {
+  // The call succeeded.
+  GLUEX_STATUS_OK = 0,
+  // A required pointer argument was null.
+  GLUEX_STATUS_NULL_ARGUMENT = 1,
+  // A string argument was not valid UTF-8.
+  GLUEX_STATUS_INVALID_STRING = 2,
+  // The requested table, directory, variation, condition, or value does not exist.
+  GLUEX_STATUS_NOT_FOUND = 3,
+  // An argument was malformed (e.g. an invalid request string or run selection).
+  GLUEX_STATUS_INVALID_ARGUMENT = 4,
+  // The output buffer was null or too small; the required size has been written.
+  GLUEX_STATUS_BUFFER_TOO_SMALL = 5,
+  // A value could not be converted to the requested type.
+  GLUEX_STATUS_TYPE_MISMATCH = 6,
+  // The database could not be opened or queried.
+  GLUEX_STATUS_DATABASE = 7,
+  // A Rust panic was caught at the API boundary.
+  GLUEX_STATUS_PANIC = 8,
+} GluexStatus;
+
+// An open CCDB snapshot.
+typedef struct GluexCcdb GluexCcdb;
+
+// An open RCDB snapshot.
+typedef struct GluexRcdb GluexRcdb;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Returns the message of the most recent failed call on this thread, or null if none failed.
+//
+// The string is owned by the library and stays valid until the next failing call on the same
+// thread.
+const char *gluex_last_error(void);
+
+// Opens the CCDB `SQLite` snapshot at `path` and stores the handle in `*out`.
+//
+// # Safety
+//
+// `path` must be a nul-terminated string and `out` must be valid for writes.
+enum GluexStatus gluex_ccdb_open(const char *path, struct GluexCcdb **out);
+
+// Closes a handle returned by [`gluex_ccdb_open`]. Passing null does nothing.
+//
+// # Safety
+//
+// `db` must be null or a handle from [`gluex_ccdb_open`] that has not been closed.
+void gluex_ccdb_close(struct GluexCcdb *db);
+
+// Writes the number of rows and columns of the constants selected by `request`.
+//
+// # Safety
+//
+// `db` must be a live handle, `request` a nul-terminated string, and `n_rows`/`n_columns` valid
+// for writes.
+enum GluexStatus gluex_ccdb_shape(const struct GluexCcdb *db,
+                                  const char *request,
+                                  size_t *n_rows,
+                                  size_t *n_columns);
+
+// Fills `buffer` with the constants selected by `request` as doubles, in row-major order.
+//
+// `*len` receives the number of values (rows times columns) even when the buffer is too small.
+// Integer and boolean columns are converted; string columns must hold numbers.
+//
+// # Safety
+//
+// `db` must be a live handle, `request` a nul-terminated string, `buffer` null or valid for
+// `capacity` writes, and `len` valid for writes.
+enum GluexStatus gluex_ccdb_fetch_doubles(const struct GluexCcdb *db,
+                                          const char *request,
+                                          double *buffer,
+                                          size_t capacity,
+                                          size_t *len);
+
+// Fills `buffer` with the constants selected by `request` as 64-bit integers, in row-major
+// order.
+//
+// `*len` receives the number of values even when the buffer is too small. Doubles must be whole
+// numbers and string columns must hold integers.
+//
+// # Safety
+//
+// `db` must be a live handle, `request` a nul-terminated string, `buffer` null or valid for
+// `capacity` writes, and `len` valid for writes.
+enum GluexStatus gluex_ccdb_fetch_longs(const struct GluexCcdb *db,
+                                        const char *request,
+                                        int64_t *buffer,
+                                        size_t capacity,
+                                        size_t *len);
+
+// Copies one cell of the constants selected by `request`, formatted as text, into `buffer` with
+// a terminating nul.
+//
+// `*len` receives the text length (without the nul) even when the buffer is too small.
+//
+// # Safety
+//
+// `db` must be a live handle, `request` a nul-terminated string, `buffer` null or valid for
+// `capacity` writes, and `len` valid for writes.
+enum GluexStatus gluex_ccdb_fetch_string(const struct GluexCcdb *db,
+                                         const char *request,
+                                         size_t row,
+                                         size_t column,
+                                         char *buffer,
+                                         size_t capacity,
+                                         size_t *len);
+
+// Copies the name of `column` of the table selected by `request` into `buffer` with a
+// terminating nul.
+//
+// # Safety
+//
+// `db` must be a live handle, `request` a nul-terminated string, `buffer` null or valid for
+// `capacity` writes, and `len` valid for writes.
+enum GluexStatus gluex_ccdb_column_name(const struct GluexCcdb *db,
+                                        const char *request,
+                                        size_t column,
+                                        char *buffer,
+                                        size_t capacity,
+                                        size_t *len);
+
+// Opens the RCDB `SQLite` snapshot at `path` and stores the handle in `*out`.
+//
+// # Safety
+//
+// `path` must be a nul-terminated string and `out` must be valid for writes.
+enum GluexStatus gluex_rcdb_open(const char *path, struct GluexRcdb **out);
+
+// Closes a handle returned by [`gluex_rcdb_open`]. Passing null does nothing.
+//
+// # Safety
+//
+// `db` must be null or a handle from [`gluex_rcdb_open`] that has not been closed.
+void gluex_rcdb_close(struct GluexRcdb *db);
+
+// Reads an `int` (or `bool`) condition of `run` into `*out`.
+//
+// # Safety
+//
+// `db` must be a live handle, `condition` a nul-terminated string, and `out` valid for writes.
+enum GluexStatus gluex_rcdb_fetch_long(const struct GluexRcdb *db,
+                                       const char *condition,
+                                       int64_t run,
+                                       int64_t *out);
+
+// Reads a `float` (or `int`) condition of `run` into `*out`.
+//
+// # Safety
+//
+// `db` must be a live handle, `condition` a nul-terminated string, and `out` valid for writes.
+enum GluexStatus gluex_rcdb_fetch_double(const struct GluexRcdb *db,
+                                         const char *condition,
+                                         int64_t run,
+                                         double *out);
+
+// Reads a `bool` condition of `run` into `*out`.
+//
+// # Safety
+//
+// `db` must be a live handle, `condition` a nul-terminated string, and `out` valid for writes.
+enum GluexStatus gluex_rcdb_fetch_bool(const struct GluexRcdb *db,
+                                       const char *condition,
+                                       int64_t run,
+                                       bool *out);
+
+// Copies a `string`, `json`, `blob`, or `time` condition of `run` into `buffer` with a
+// terminating nul (times as RFC 3339).
+//
+// `*len` receives the text length (without the nul) even when the buffer is too small.
+//
+// # Safety
+//
+// `db` must be a live handle, `condition` a nul-terminated string, `buffer` null or valid for
+// `capacity` writes, and `len` valid for writes.
+enum GluexStatus gluex_rcdb_fetch_string(const struct GluexRcdb *db,
+                                         const char *condition,
+                                         int64_t run,
+                                         char *buffer,
+                                         size_t capacity,
+                                         size_t *len);
+
+// Fills `buffer` with the runs stored in RCDB that match `selection`, a run selection string
+// such as `S18+F18-[41050..41100]-41234`, in ascending order.
+//
+// `*len` receives the number of runs even when the buffer is too small.
+//
+// # Safety
+//
+// `db` must be a live handle, `selection` a nul-terminated string, `buffer` null or valid for
+// `capacity` writes, and `len` valid for writes.
+enum GluexStatus gluex_rcdb_runs(const struct GluexRcdb *db,
+                                 const char *selection,
+                                 int64_t *buffer,
+                                 size_t capacity,
+                                 size_t *len);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* GLUEX_H */
@@ -0,0 +1,262 @@
+//! CCDB access: open a snapshot and fetch a table's constants for a request string
+//! (`<path>[:<run>[:<variation>[:<timestamp>]]]`, as used by `JCalibration`).
+use std::{ffi::c_char, panic::AssertUnwindSafe};
+
+use gluex_ccdb::{
+    data::{Data, Value},
+    database::CCDB,
+    CCDBError,
+};
+
+use crate::{
+    guard, handle_arg, str_arg, write_out, write_slice, write_string, Failure, FfiResult,
+    GluexStatus,
+};
+
+/// An open CCDB snapshot.
+pub struct GluexCcdb {
+    db: CCDB,
+}
+
+impl From<CCDBError> for Failure {
+    fn from(err: CCDBError) -> Self {
+        let status = match err {
+            CCDBError::DirectoryNotFoundError(_)
+            | CCDBError::TableNotFoundError(_)
+            | CCDBError::VariationNotFoundError(_) => GluexStatus::NotFound,
+            CCDBError::InvalidPathError(_)
+            | CCDBError::ParseRequestError(_)
+            | CCDBError::ParseTimestampError(_)
+            | CCDBError::RestVersionError(_)
+            | CCDBError::RunPeriodError(_) => GluexStatus::InvalidArgument,
+            _ => GluexStatus::Database,
+        };
+        Failure::new(status, err.to_string())
+    }
+}
+
+impl GluexCcdb {
+    /// Resolves `request` to the constants of its (single) run.
+    fn fetch(&self, request: &str) -> FfiResult<Data> {
+        self.db
+            .request(request)?
+            .into_values()
+            .next()
+            .ok_or_else(|| {
+                Failure::new(
+                    GluexStatus::NotFound,
+                    format!("no assignment matches {request}"),
+                )
+            })
+    }
+}
+
+/// Every cell of `data` in row-major order, converted with `convert`.
+fn cells<T>(data: &Data, convert: impl Fn(Value<'_>) -> Option<T>) -> FfiResult<Vec<T>> {
+    let mut cells = Vec::with_capacity(data.n_rows() * data.n_columns());
+    for row in 0..data.n_rows() {
+        for column in 0..data.n_columns() {
+            let value = data.value(column, row).ok_or_else(|| {
+                Failure::new(
+                    GluexStatus::NotFound,
+                    format!("missing value at row {row}, column {column}"),
+                )
+            })?;
+            cells.push(convert(value).ok_or_else(|| {
+                Failure::new(
+                    GluexStatus::TypeMismatch,
+                    format!("value at row {row}, column {column} has the wrong type"),
+                )
+            })?);
+        }
+    }
+    Ok(cells)
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn to_double(value: Value<'_>) -> Option<f64> {
+    match value {
+        Value::Int(v) => Some(f64::from(*v)),
+        Value::UInt(v) => Some(f64::from(*v)),
+        Value::Long(v) => Some(*v as f64),
+        Value::ULong(v) => Some(*v as f64),
+        Value::Double(v) => Some(*v),
+        Value::Bool(v) => Some(f64::from(u8::from(*v))),
+        Value::String(v) => v.trim().parse().ok(),
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn to_long(value: Value<'_>) -> Option<i64> {
+    match value {
+        Value::Int(v) => Some(i64::from(*v)),
+        Value::UInt(v) => Some(i64::from(*v)),
+        Value::Long(v) => Some(*v),
+        Value::ULong(v) => i64::try_from(*v).ok(),
+        Value::Double(v) => (v.fract() == 0.0 && v.abs() < 9.2e18).then_some(*v as i64),
+        Value::Bool(v) => Some(i64::from(*v)),
+        Value::String(v) => v.trim().parse().ok(),
+    }
+}
+
+/// Opens the CCDB `SQLite` snapshot at `path` and stores the handle in `*out`.
+///
+/// # Safety
+///
+/// `path` must be a nul-terminated string and `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn gluex_ccdb_open(
+    path: *const c_char,
+    out: *mut *mut GluexCcdb,
+) -> GluexStatus {
+    guard(AssertUnwindSafe(|| {
+        let path = str_arg(path, "path")?;
+        let db = CCDB::open(path)?;
+        write_out(out, Box::into_raw(Box::new(GluexCcdb { db })), "out")
+    }))
+}
+
+/// Closes a handle returned by [`gluex_ccdb_open`]. Passing null does nothing.
+///
+/// # Safety
+///
+/// `db` must be null or a handle from [`gluex_ccdb_open`] that has not been closed.
+#[no_mangle]
+pub unsafe extern "C" fn gluex_ccdb_close(db: *mut GluexCcdb) {
+    if !db.is_null() {
+        drop(Box::from_raw(db));
+    }
+}
+
+/// Writes the number of rows and columns of the constants selected by `request`.
+///
+/// # Safety
+///
+/// `db` must be a live handle, `request` a nul-terminated string, and `n_rows`/`n_columns` valid
+/// for writes.
+#[no_mangle]
+pub unsafe extern "C" fn gluex_ccdb_shape(
+    db: *const GluexCcdb,
+    request: *const c_char,
+    n_rows: *mut usize,
+    n_columns: *mut usize,
+) -> GluexStatus {
+    guard(AssertUnwindSafe(|| {
+        let data = handle_arg(db, "db")?.fetch(str_arg(request, "request")?)?;
+        write_out(n_rows, data.n_rows(), "n_rows")?;
+        write_out(n_columns, data.n_columns(), "n_columns")
+    }))
+}
+
+/// Fills `buffer` with the constants selected by `request` as doubles, in row-major order.
+///
+/// `*len` receives the number of values (rows times columns) even when the buffer is too small.
+/// Integer and boolean columns are converted; string columns must hold numbers.
+///
+/// # Safety
+///
+/// `db` must be a live handle, `request` a nul-terminated string, `buffer` null or valid for
+/// `capacity` writes, and `len` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn gluex_ccdb_fetch_doubles(
+    db: *const GluexCcdb,
+    request: *const c_char,
+    buffer: *mut f64,
+    capacity: usize,
+    len: *mut usize,
+) -> GluexStatus {
+    guard(AssertUnwindSafe(|| {
+        let data = handle_arg(db, "db")?.fetch(str_arg(request, "request")?)?;
+        write_slice(&cells(&data, to_double)?, buffer, capacity, len)
+    }))
+}
+
+/// Fills `buffer` with the constants selected by `request` as 64-bit integers, in row-major
+/// order.
+///
+/// `*len` receives the number of values even when the buffer is too small. Doubles must be whole
+/// numbers and string columns must hold integers.
+///
+/// # Safety
+///
+/// `db` must be a live handle, `request` a nul-terminated string, `buffer` null or valid for
+/// `capacity` writes, and `len` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn gluex_ccdb_fetch_longs(
+    db: *const GluexCcdb,
+    request: *const c_char,
+    buffer: *mut i64,
+    capacity: usize,
+    len: *mut usize,
+) -> GluexStatus {
+    guard(AssertUnwindSafe(|| {
+        let data = handle_arg(db, "db")?.fetch(str_arg(request, "request")?)?;
+        write_slice(&cells(&data, to_long)?, buffer, capacity, len)
+    }))
+}
+
+/// Copies one cell of the constants selected by `request`, formatted as text, into `buffer` with
+/// a terminating nul.
+///
+/// `*len` receives the text length (without the nul) even when the buffer is too small.
+///
+/// # Safety
+///
+/// `db` must be a live handle, `request` a nul-terminated string, `buffer` null or valid for
+/// `capacity` writes, and `len` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn gluex_ccdb_fetch_string(
+    db: *const GluexCcdb,
+    request: *const c_char,
+    row: usize,
+    column: usize,
+    buffer: *mut c_char,
+    capacity: usize,
+    len: *mut usize,
+) -> GluexStatus {
+    guard(AssertUnwindSafe(|| {
+        let data = handle_arg(db, "db")?.fetch(str_arg(request, "request")?)?;
+        let value = data.value(column, row).ok_or_else(|| {
+            Failure::new(
+                GluexStatus::NotFound,
+                format!("no value at row {row}, column {column}"),
+            )
+        })?;
+        let text = match value {
+            Value::Int(v) => v.to_string(),
+            Value::UInt(v) => v.to_string(),
+            Value::Long(v) => v.to_string(),
+            Value::ULong(v) => v.to_string(),
+            Value::Double(v) => v.to_string(),
+            Value::Bool(v) => v.to_string(),
+            Value::String(v) => v.to_string(),
+        };
+        write_string(&text, buffer, capacity, len)
+    }))
+}
+
+/// Copies the name of `column` of the table selected by `request` into `buffer` with a
+/// terminating nul.
+///
+/// # Safety
+///
+/// `db` must be a live handle, `request` a nul-terminated string, `buffer` null or valid for
+/// `capacity` writes, and `len` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn gluex_ccdb_column_name(
+    db: *const GluexCcdb,
+    request: *const c_char,
+    column: usize,
+    buffer: *mut c_char,
+    capacity: usize,
+    len: *mut usize,
+) -> GluexStatus {
+    guard(AssertUnwindSafe(|| {
+        let data = handle_arg(db, "db")?.fetch(str_arg(request, "request")?)?;
+        let name = data
+            .column_names()
+            .get(column)
+            .ok_or_else(|| Failure::new(GluexStatus::NotFound, format!("no column {column}")))?;
+        write_string(name, buffer, capacity, len)
+    }))
+}
@@ -0,0 +1,197 @@
+//! # gluex-ffi
+//!
+//! A C API over [`gluex_ccdb`] and [`gluex_rcdb`], so C and C++ code (such as `halld_recon` and
+//! JANA plugins) can read calibration constants and run conditions from local `SQLite` snapshots.
+//!
+//! Databases are opaque handles created by [`gluex_ccdb_open`]/[`gluex_rcdb_open`] and released
+//! with the matching `_close` function. Every fallible function returns a [`GluexStatus`]; the
+//! message of the most recent failure on the calling thread is available from
+//! [`gluex_last_error`]. Functions that fill caller-owned buffers report the size they need and
+//! return [`GluexStatus::BufferTooSmall`] when the buffer is too small (or null), so callers can
+//! query first and allocate exactly.
+//!
+//! The C header `gluex.h` is generated by cbindgen at build time and kept in `include/`.
+use std::{
+    any::Any,
+    cell::RefCell,
+    ffi::{c_char, CStr, CString},
+    panic::{self, UnwindSafe},
+    ptr,
+};
+
+pub mod ccdb;
+pub mod rcdb;
+
+/// Result code of every fallible function.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GluexStatus {
+    /// The call succeeded.
+    Ok = 0,
+    /// A required pointer argument was null.
+    NullArgument = 1,
+    /// A string argument was not valid UTF-8.
+    InvalidString = 2,
+    /// The requested table, directory, variation, condition, or value does not exist.
+    NotFound = 3,
+    /// An argument was malformed (e.g. an invalid request string or run selection).
+    InvalidArgument = 4,
+    /// The output buffer was null or too small; the required size has been written.
+    BufferTooSmall = 5,
+    /// A value could not be converted to the requested type.
+    TypeMismatch = 6,
+    /// The database could not be opened or queried.
+    Database = 7,
+    /// A Rust panic was caught at the API boundary.
+    Panic = 8,
+}
+
+/// A failure carrying its status code and message.
+pub(crate) struct Failure(GluexStatus, String);
+
+impl Failure {
+    pub(crate) fn new(status: GluexStatus, message: impl Into<String>) -> Self {
+        Self(status, message.into())
+    }
+}
+
+pub(crate) type FfiResult<T = ()> = Result<T, Failure>;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: &str) {
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+/// Returns the message of the most recent failed call on this thread, or null if none failed.
+///
+/// The string is owned by the library and stays valid until the next failing call on the same
+/// thread.
+#[no_mangle]
+pub extern "C" fn gluex_last_error() -> *const c_char {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(ptr::null(), |m| m.as_ptr()))
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(ToString::to_string)
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+/// Runs `f`, converting failures and panics into a status code and recording their message.
+pub(crate) fn guard(f: impl FnOnce() -> FfiResult + UnwindSafe) -> GluexStatus {
+    match panic::catch_unwind(f) {
+        Ok(Ok(())) => GluexStatus::Ok,
+        Ok(Err(Failure(status, message))) => {
+            set_last_error(&message);
+            status
+        }
+        Err(payload) => {
+            set_last_error(&format!("panic: {}", panic_message(payload.as_ref())));
+            GluexStatus::Panic
+        }
+    }
+}
+
+/// Borrows a C string argument as `&str`.
+///
+/// # Safety
+///
+/// `ptr` must be null or point to a nul-terminated string that outlives the returned slice.
+pub(crate) unsafe fn str_arg<'a>(ptr: *const c_char, name: &str) -> FfiResult<&'a str> {
+    if ptr.is_null() {
+        return Err(Failure::new(
+            GluexStatus::NullArgument,
+            format!("{name} is null"),
+        ));
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map_err(|_| Failure::new(GluexStatus::InvalidString, format!("{name} is not UTF-8")))
+}
+
+/// Dereferences a handle argument.
+///
+/// # Safety
+///
+/// `ptr` must be null or a live handle returned by the matching `_open` function.
+pub(crate) unsafe fn handle_arg<'a, T>(ptr: *const T, name: &str) -> FfiResult<&'a T> {
+    ptr.as_ref()
+        .ok_or_else(|| Failure::new(GluexStatus::NullArgument, format!("{name} is null")))
+}
+
+/// Writes `value` through an output pointer.
+///
+/// # Safety
+///
+/// `ptr` must be null or valid for writes of one `T`.
+pub(crate) unsafe fn write_out<T>(ptr: *mut T, value: T, name: &str) -> FfiResult {
+    if ptr.is_null() {
+        return Err(Failure::new(
+            GluexStatus::NullArgument,
+            format!("{name} is null"),
+        ));
+    }
+    ptr.write(value);
+    Ok(())
+}
+
+/// Copies `values` into a caller buffer of `capacity` elements after recording the required
+/// length in `len`.
+///
+/// # Safety
+///
+/// `buffer` must be null or valid for writes of `capacity` elements, and `len` must be valid for
+/// writes.
+pub(crate) unsafe fn write_slice<T: Copy>(
+    values: &[T],
+    buffer: *mut T,
+    capacity: usize,
+    len: *mut usize,
+) -> FfiResult {
+    write_out(len, values.len(), "len")?;
+    if buffer.is_null() || capacity < values.len() {
+        return Err(Failure::new(
+            GluexStatus::BufferTooSmall,
+            format!(
+                "buffer holds {capacity} elements but {} are needed",
+                values.len()
+            ),
+        ));
+    }
+    ptr::copy_nonoverlapping(values.as_ptr(), buffer, values.len());
+    Ok(())
+}
+
+/// Copies `text` with a terminating nul into a caller buffer of `capacity` bytes, recording the
+/// string length (without the nul) in `len`.
+///
+/// # Safety
+///
+/// `buffer` must be null or valid for writes of `capacity` bytes, and `len` must be valid for
+/// writes.
+pub(crate) unsafe fn write_string(
+    text: &str,
+    buffer: *mut c_char,
+    capacity: usize,
+    len: *mut usize,
+) -> FfiResult {
+    write_out(len, text.len(), "len")?;
+    if buffer.is_null() || capacity <= text.len() {
+        return Err(Failure::new(
+            GluexStatus::BufferTooSmall,
+            format!(
+                "buffer holds {capacity} bytes but {} are needed",
+                text.len() + 1
+            ),
+        ));
+    }
+    ptr::copy_nonoverlapping(text.as_ptr().cast::<c_char>(), buffer, text.len());
+    buffer.add(text.len()).write(0);
+    Ok(())
+}
@@ -0,0 +1,209 @@
+//! RCDB access: open a snapshot, read one condition of one run, and list runs matching a run
+//! selection string.
+use std::{ffi::c_char, panic::AssertUnwindSafe};
+
+use gluex_core::{run_set::RunSet, RunNumber};
+use gluex_rcdb::{context::Context, data::Value, database::RCDB, RCDBError};
+
+use crate::{
+    guard, handle_arg, str_arg, write_out, write_slice, write_string, Failure, FfiResult,
+    GluexStatus,
+};
+
+/// An open RCDB snapshot.
+pub struct GluexRcdb {
+    db: RCDB,
+}
+
+impl From<RCDBError> for Failure {
+    fn from(err: RCDBError) -> Self {
+        let status = match err {
+            RCDBError::ConditionTypeNotFound(_) => GluexStatus::NotFound,
+            RCDBError::ConditionTypeMismatch { .. } => GluexStatus::TypeMismatch,
+            RCDBError::EmptyConditionList | RCDBError::ParseTimestampError(_) => {
+                GluexStatus::InvalidArgument
+            }
+            _ => GluexStatus::Database,
+        };
+        Failure::new(status, err.to_string())
+    }
+}
+
+impl GluexRcdb {
+    /// The value of `condition` for `run`.
+    fn value(&self, condition: &str, run: RunNumber) -> FfiResult<Value> {
+        self.db
+            .fetch([condition], &Context::default().with_run(run))?
+            .remove(&run)
+            .and_then(|mut values| values.remove(condition))
+            .ok_or_else(|| {
+                Failure::new(
+                    GluexStatus::NotFound,
+                    format!("{condition} has no value for run {run}"),
+                )
+            })
+    }
+}
+
+fn mismatch(condition: &str, expected: &str) -> Failure {
+    Failure::new(
+        GluexStatus::TypeMismatch,
+        format!("{condition} is not {expected} condition"),
+    )
+}
+
+/// Opens the RCDB `SQLite` snapshot at `path` and stores the handle in `*out`.
+///
+/// # Safety
+///
+/// `path` must be a nul-terminated string and `out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn gluex_rcdb_open(
+    path: *const c_char,
+    out: *mut *mut GluexRcdb,
+) -> GluexStatus {
+    guard(AssertUnwindSafe(|| {
+        let path = str_arg(path, "path")?;
+        let db = RCDB::open(path)?;
+        write_out(out, Box::into_raw(Box::new(GluexRcdb { db })), "out")
+    }))
+}
+
+/// Closes a handle returned by [`gluex_rcdb_open`]. Passing null does nothing.
+///
+/// # Safety
+///
+/// `db` must be null or a handle from [`gluex_rcdb_open`] that has not been closed.
+#[no_mangle]
+pub unsafe extern "C" fn gluex_rcdb_close(db: *mut GluexRcdb) {
+    if !db.is_null() {
+        drop(Box::from_raw(db));
+    }
+}
+
+/// Reads an `int` (or `bool`) condition of `run` into `*out`.
+///
+/// # Safety
+///
+/// `db` must be a live handle, `condition` a nul-terminated string, and `out` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn gluex_rcdb_fetch_long(
+    db: *const GluexRcdb,
+    condition: *const c_char,
+    run: i64,
+    out: *mut i64,
+) -> GluexStatus {
+    guard(AssertUnwindSafe(|| {
+        let condition = str_arg(condition, "condition")?;
+        let value = handle_arg(db, "db")?.value(condition, run)?;
+        let value = value
+            .as_int()
+            .or_else(|| value.as_bool().map(i64::from))
+            .ok_or_else(|| mismatch(condition, "an int"))?;
+        write_out(out, value, "out")
+    }))
+}
+
+/// Reads a `float` (or `int`) condition of `run` into `*out`.
+///
+/// # Safety
+///
+/// `db` must be a live handle, `condition` a nul-terminated string, and `out` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn gluex_rcdb_fetch_double(
+    db: *const GluexRcdb,
+    condition: *const c_char,
+    run: i64,
+    out: *mut f64,
+) -> GluexStatus {
+    guard(AssertUnwindSafe(|| {
+        let condition = str_arg(condition, "condition")?;
+        let value = handle_arg(db, "db")?.value(condition, run)?;
+        #[allow(clippy::cast_precision_loss)]
+        let value = value
+            .as_float()
+            .or_else(|| value.as_int().map(|v| v as f64))
+            .ok_or_else(|| mismatch(condition, "a float"))?;
+        write_out(out, value, "out")
+    }))
+}
+
+/// Reads a `bool` condition of `run` into `*out`.
+///
+/// # Safety
+///
+/// `db` must be a live handle, `condition` a nul-terminated string, and `out` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn gluex_rcdb_fetch_bool(
+    db: *const GluexRcdb,
+    condition: *const c_char,
+    run: i64,
+    out: *mut bool,
+) -> GluexStatus {
+    guard(AssertUnwindSafe(|| {
+        let condition = str_arg(condition, "condition")?;
+        let value = handle_arg(db, "db")?
+            .value(condition, run)?
+            .as_bool()
+            .ok_or_else(|| mismatch(condition, "a bool"))?;
+        write_out(out, value, "out")
+    }))
+}
+
+/// Copies a `string`, `json`, `blob`, or `time` condition of `run` into `buffer` with a
+/// terminating nul (times as RFC 3339).
+///
+/// `*len` receives the text length (without the nul) even when the buffer is too small.
+///
+/// # Safety
+///
+/// `db` must be a live handle, `condition` a nul-terminated string, `buffer` null or valid for
+/// `capacity` writes, and `len` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn gluex_rcdb_fetch_string(
+    db: *const GluexRcdb,
+    condition: *const c_char,
+    run: i64,
+    buffer: *mut c_char,
+    capacity: usize,
+    len: *mut usize,
+) -> GluexStatus {
+    guard(AssertUnwindSafe(|| {
+        let condition = str_arg(condition, "condition")?;
+        let value = handle_arg(db, "db")?.value(condition, run)?;
+        let text = value
+            .as_string()
+            .map(str::to_string)
+            .or_else(|| value.as_time().map(|time| time.to_rfc3339()))
+            .ok_or_else(|| mismatch(condition, "a string"))?;
+        write_string(&text, buffer, capacity, len)
+    }))
+}
+
+/// Fills `buffer` with the runs stored in RCDB that match `selection`, a run selection string
+/// such as `S18+F18-[41050..41100]-41234`, in ascending order.
+///
+/// `*len` receives the number of runs even when the buffer is too small.
+///
+/// # Safety
+///
+/// `db` must be a live handle, `selection` a nul-terminated string, `buffer` null or valid for
+/// `capacity` writes, and `len` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn gluex_rcdb_runs(
+    db: *const GluexRcdb,
+    selection: *const c_char,
+    buffer: *mut i64,
+    capacity: usize,
+    len: *mut usize,
+) -> GluexStatus {
+    guard(AssertUnwindSafe(|| {
+        let runs: RunSet = str_arg(selection, "selection")?.parse().map_err(
+            |err: gluex_core::errors::ParseRunSetError| {
+                Failure::new(GluexStatus::InvalidArgument, err.to_string())
+            },
+        )?;
+        let runs = handle_arg(db, "db")?.db.fetch_runs(&Context::from(runs))?;
+        write_slice(&runs, buffer, capacity, len)
+    }))
+}
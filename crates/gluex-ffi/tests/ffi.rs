@@ -0,0 +1,278 @@
+#![allow(missing_docs)]
+
+use std::{
+    ffi::{c_char, CStr, CString},
+    path::PathBuf,
+    ptr,
+};
+
+use gluex_ffi::{
+    ccdb::{
+        gluex_ccdb_close, gluex_ccdb_column_name, gluex_ccdb_fetch_doubles, gluex_ccdb_fetch_longs,
+        gluex_ccdb_fetch_string, gluex_ccdb_open, gluex_ccdb_shape, GluexCcdb,
+    },
+    gluex_last_error,
+    rcdb::{
+        gluex_rcdb_close, gluex_rcdb_fetch_bool, gluex_rcdb_fetch_double, gluex_rcdb_fetch_long,
+        gluex_rcdb_fetch_string, gluex_rcdb_open, gluex_rcdb_runs, GluexRcdb,
+    },
+    GluexStatus,
+};
+use gluex_testdata::{
+    ccdb::{Assignment, CcdbBuilder},
+    rcdb::RcdbBuilder,
+};
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("gluex-ffi-{}-{name}.sqlite", std::process::id()))
+}
+
+fn c(text: &str) -> CString {
+    CString::new(text).unwrap()
+}
+
+fn open_ccdb(name: &str) -> *mut GluexCcdb {
+    let path = temp_path(name);
+    let _ = std::fs::remove_file(&path);
+    CcdbBuilder::new()
+        .table(
+            "/test/table",
+            2,
+            &[("x", "double"), ("n", "int"), ("label", "string")],
+        )
+        .assign(Assignment::new(
+            "/test/table",
+            0..=100,
+            [["1.5", "3", "a"], ["2.5", "4", "b"]],
+        ))
+        .write(&path)
+        .expect("failed to write synthetic CCDB");
+    let mut db = ptr::null_mut();
+    let path = c(path.to_str().unwrap());
+    assert_eq!(
+        unsafe { gluex_ccdb_open(path.as_ptr(), &raw mut db) },
+        GluexStatus::Ok
+    );
+    db
+}
+
+fn open_rcdb(name: &str) -> *mut GluexRcdb {
+    let path = temp_path(name);
+    let _ = std::fs::remove_file(&path);
+    RcdbBuilder::new()
+        .condition(10, "event_count", 500_i64)
+        .condition(10, "beam_current", 150.5)
+        .condition(10, "is_production", true)
+        .condition(10, "run_type", "hd_all.tsg")
+        .condition(11, "event_count", 600_i64)
+        .runs([12])
+        .write(&path)
+        .expect("failed to write synthetic RCDB");
+    let mut db = ptr::null_mut();
+    let path = c(path.to_str().unwrap());
+    assert_eq!(
+        unsafe { gluex_rcdb_open(path.as_ptr(), &raw mut db) },
+        GluexStatus::Ok
+    );
+    db
+}
+
+fn last_error() -> String {
+    unsafe { CStr::from_ptr(gluex_last_error()) }
+        .to_string_lossy()
+        .into_owned()
+}
+
+#[test]
+fn ccdb_constants_are_fetched_into_buffers() {
+    let db = open_ccdb("ccdb");
+    let request = c("/test/table:42");
+    let (mut rows, mut columns) = (0, 0);
+    unsafe {
+        assert_eq!(
+            gluex_ccdb_shape(db, request.as_ptr(), &raw mut rows, &raw mut columns),
+            GluexStatus::Ok
+        );
+        assert_eq!((rows, columns), (2, 3));
+
+        let mut len = 0;
+        let mut values = [0.0; 6];
+        assert_eq!(
+            gluex_ccdb_fetch_doubles(db, request.as_ptr(), values.as_mut_ptr(), 6, &raw mut len),
+            GluexStatus::TypeMismatch
+        );
+        assert!(last_error().contains("row 0, column 2"));
+
+        let mut text = [0 as c_char; 8];
+        assert_eq!(
+            gluex_ccdb_fetch_string(
+                db,
+                request.as_ptr(),
+                1,
+                2,
+                text.as_mut_ptr(),
+                8,
+                &raw mut len
+            ),
+            GluexStatus::Ok
+        );
+        assert_eq!(CStr::from_ptr(text.as_ptr()).to_str().unwrap(), "b");
+        assert_eq!(
+            gluex_ccdb_column_name(db, request.as_ptr(), 1, text.as_mut_ptr(), 8, &raw mut len),
+            GluexStatus::Ok
+        );
+        assert_eq!(CStr::from_ptr(text.as_ptr()).to_str().unwrap(), "n");
+        gluex_ccdb_close(db);
+    }
+}
+
+#[test]
+fn ccdb_numeric_columns_convert_to_doubles() {
+    let path = temp_path("numeric");
+    let _ = std::fs::remove_file(&path);
+    CcdbBuilder::new()
+        .table("/test/numbers", 2, &[("x", "double"), ("n", "int")])
+        .assign(Assignment::new(
+            "/test/numbers",
+            0..=10,
+            [["1.5", "3"], ["2.5", "4"]],
+        ))
+        .write(&path)
+        .unwrap();
+    let mut db = ptr::null_mut();
+    let mut values = [0.0; 4];
+    let mut longs = [0_i64; 4];
+    let mut len = 0;
+    unsafe {
+        assert_eq!(
+            gluex_ccdb_open(c(path.to_str().unwrap()).as_ptr(), &raw mut db),
+            GluexStatus::Ok
+        );
+        let request = c("/test/numbers:5:default");
+        assert_eq!(
+            gluex_ccdb_fetch_doubles(db, request.as_ptr(), ptr::null_mut(), 0, &raw mut len),
+            GluexStatus::BufferTooSmall
+        );
+        assert_eq!(len, 4);
+        assert_eq!(
+            gluex_ccdb_fetch_doubles(db, request.as_ptr(), values.as_mut_ptr(), 4, &raw mut len),
+            GluexStatus::Ok
+        );
+        assert!(values
+            .iter()
+            .zip([1.5, 3.0, 2.5, 4.0])
+            .all(|(value, expected)| (value - expected).abs() < f64::EPSILON));
+        assert_eq!(
+            gluex_ccdb_fetch_longs(db, request.as_ptr(), longs.as_mut_ptr(), 4, &raw mut len),
+            GluexStatus::TypeMismatch
+        );
+        assert_eq!(
+            gluex_ccdb_fetch_doubles(
+                db,
+                c("/test/missing:5").as_ptr(),
+                values.as_mut_ptr(),
+                4,
+                &raw mut len
+            ),
+            GluexStatus::NotFound
+        );
+        gluex_ccdb_close(db);
+    }
+}
+
+#[test]
+fn rcdb_conditions_are_read_by_type() {
+    let db = open_rcdb("rcdb");
+    let mut long = 0;
+    let mut double = 0.0;
+    let mut flag = false;
+    let mut text = [0 as c_char; 4];
+    let mut len = 0;
+    unsafe {
+        assert_eq!(
+            gluex_rcdb_fetch_long(db, c("event_count").as_ptr(), 10, &raw mut long),
+            GluexStatus::Ok
+        );
+        assert_eq!(long, 500);
+        assert_eq!(
+            gluex_rcdb_fetch_double(db, c("beam_current").as_ptr(), 10, &raw mut double),
+            GluexStatus::Ok
+        );
+        assert!((double - 150.5).abs() < f64::EPSILON);
+        assert_eq!(
+            gluex_rcdb_fetch_bool(db, c("is_production").as_ptr(), 10, &raw mut flag),
+            GluexStatus::Ok
+        );
+        assert!(flag);
+        assert_eq!(
+            gluex_rcdb_fetch_string(
+                db,
+                c("run_type").as_ptr(),
+                10,
+                text.as_mut_ptr(),
+                4,
+                &raw mut len
+            ),
+            GluexStatus::BufferTooSmall
+        );
+        assert_eq!(len, "hd_all.tsg".len());
+        assert_eq!(
+            gluex_rcdb_fetch_long(db, c("event_count").as_ptr(), 12, &raw mut long),
+            GluexStatus::NotFound
+        );
+        assert_eq!(
+            gluex_rcdb_fetch_long(db, c("run_type").as_ptr(), 10, &raw mut long),
+            GluexStatus::TypeMismatch
+        );
+        assert_eq!(
+            gluex_rcdb_fetch_long(db, c("missing").as_ptr(), 10, &raw mut long),
+            GluexStatus::NotFound
+        );
+        assert!(last_error().contains("missing"));
+        gluex_rcdb_close(db);
+    }
+}
+
+#[test]
+fn rcdb_runs_accept_selection_strings() {
+    let db = open_rcdb("runs");
+    let mut runs = [0_i64; 4];
+    let mut len = 0;
+    unsafe {
+        assert_eq!(
+            gluex_rcdb_runs(
+                db,
+                c("[0..20]-11").as_ptr(),
+                runs.as_mut_ptr(),
+                4,
+                &raw mut len
+            ),
+            GluexStatus::Ok
+        );
+        assert_eq!(&runs[..len], &[10, 12]);
+        assert_eq!(
+            gluex_rcdb_runs(db, c("s18+").as_ptr(), runs.as_mut_ptr(), 4, &raw mut len),
+            GluexStatus::InvalidArgument
+        );
+        assert_eq!(
+            gluex_rcdb_runs(
+                ptr::null(),
+                c("all").as_ptr(),
+                runs.as_mut_ptr(),
+                4,
+                &raw mut len
+            ),
+            GluexStatus::NullArgument
+        );
+        gluex_rcdb_close(db);
+    }
+}
+
+#[test]
+fn checked_in_header_matches_the_generated_one() {
+    assert_eq!(
+        include_str!("../include/gluex.h"),
+        include_str!(concat!(env!("OUT_DIR"), "/gluex.h")),
+        "include/gluex.h is stale; copy the header cbindgen wrote to OUT_DIR"
+    );
+}
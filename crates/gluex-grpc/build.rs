@@ -0,0 +1,9 @@
+//! Generates the gRPC types and service stubs from `proto/gluex.proto`.
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    if std::env::var_os("PROTOC").is_none() {
+        std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    }
+    tonic_prost_build::configure().compile_protos(&["proto/gluex.proto"], &["proto"])?;
+    Ok(())
+}
@@ -0,0 +1,185 @@
+use std::str::FromStr;
+
+use gluex_ccdb::{
+    context::Context,
+    data::{Data, Value},
+    database::CCDB,
+    CCDBError,
+};
+use gluex_core::run_periods::RunPeriod;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+
+use crate::{
+    join_error,
+    proto::{
+        ccdb_server::{Ccdb, CcdbServer},
+        cell::Kind,
+        CcdbFetchRequest, Cell, ListDirectoryRequest, ListDirectoryResponse, TableChunk, TableInfo,
+        TableRow,
+    },
+};
+
+/// Rows per streamed [`TableChunk`] when the request does not set `chunk_rows`.
+pub const DEFAULT_CHUNK_ROWS: usize = 1000;
+
+/// gRPC [`Ccdb`] service backed by a [`CCDB`] handle.
+#[derive(Clone)]
+pub struct CcdbService {
+    db: CCDB,
+}
+
+impl CcdbService {
+    /// Wraps an open [`CCDB`] handle.
+    #[must_use]
+    pub fn new(db: CCDB) -> Self {
+        Self { db }
+    }
+
+    /// Returns a [`CcdbServer`] that can be added to a [`tonic::transport::Server`].
+    #[must_use]
+    pub fn into_server(self) -> CcdbServer<Self> {
+        CcdbServer::new(self)
+    }
+}
+
+fn status(err: &CCDBError) -> Status {
+    match err {
+        CCDBError::DirectoryNotFoundError(_)
+        | CCDBError::TableNotFoundError(_)
+        | CCDBError::VariationNotFoundError(_) => Status::not_found(err.to_string()),
+        CCDBError::SqliteError(_) => Status::internal(err.to_string()),
+        _ => Status::invalid_argument(err.to_string()),
+    }
+}
+
+fn context(request: &CcdbFetchRequest) -> Result<Context, Status> {
+    let mut context = Context::default();
+    if let Some(variation) = &request.variation {
+        context = context.with_variation(variation);
+    }
+    if !request.runs.is_empty() {
+        context = context.with_runs(request.runs.iter().copied());
+    } else if let Some(run_period) = &request.run_period {
+        let run_period = RunPeriod::from_str(run_period)
+            .map_err(|err| Status::invalid_argument(err.to_string()))?;
+        context = context
+            .with_run_period(run_period, None)
+            .map_err(|err| status(&err))?;
+    }
+    if let Some(timestamp) = &request.timestamp {
+        context = context
+            .with_timestamp_string(timestamp)
+            .map_err(|err| Status::invalid_argument(err.to_string()))?;
+    }
+    Ok(context)
+}
+
+fn cell(value: Value<'_>) -> Cell {
+    let kind = match value {
+        Value::Int(v) => Kind::Int(i64::from(*v)),
+        Value::UInt(v) => Kind::Uint(u64::from(*v)),
+        Value::Long(v) => Kind::Int(*v),
+        Value::ULong(v) => Kind::Uint(*v),
+        Value::Double(v) => Kind::Double(*v),
+        Value::Bool(v) => Kind::Bool(*v),
+        Value::String(v) => Kind::String(v.to_string()),
+    };
+    Cell { kind: Some(kind) }
+}
+
+/// Splits one run's table into chunks of at most `chunk_rows` rows. The first chunk carries the
+/// column names and is sent even when the table has no rows.
+fn chunks(run: i64, data: &Data, chunk_rows: usize) -> impl Iterator<Item = TableChunk> + '_ {
+    let n_rows = data.n_rows();
+    (0..n_rows.max(1)).step_by(chunk_rows).map(move |start| {
+        let end = (start + chunk_rows).min(n_rows);
+        TableChunk {
+            run,
+            columns: if start == 0 {
+                data.column_names().to_vec()
+            } else {
+                Vec::new()
+            },
+            row_offset: start as u64,
+            rows: (start..end)
+                .map(|row| TableRow {
+                    cells: (0..data.n_columns())
+                        .filter_map(|column| data.value(column, row).map(cell))
+                        .collect(),
+                })
+                .collect(),
+        }
+    })
+}
+
+#[tonic::async_trait]
+impl Ccdb for CcdbService {
+    async fn list_directory(
+        &self,
+        request: Request<ListDirectoryRequest>,
+    ) -> Result<Response<ListDirectoryResponse>, Status> {
+        let db = self.db.clone();
+        let path = request.into_inner().path;
+        tokio::task::spawn_blocking(move || {
+            let dir = db
+                .dir(if path.is_empty() { "/" } else { &path })
+                .map_err(|err| status(&err))?;
+            let mut dirs: Vec<String> = dir
+                .dirs()
+                .iter()
+                .map(|d| d.meta().name().to_string())
+                .collect();
+            dirs.sort();
+            let mut tables = dir.tables();
+            tables.sort_by(|a, b| a.name().cmp(b.name()));
+            Ok(Response::new(ListDirectoryResponse {
+                path: dir.full_path(),
+                dirs,
+                tables: tables
+                    .iter()
+                    .map(|t| TableInfo {
+                        name: t.name().to_string(),
+                        path: t.full_path(),
+                        n_rows: t.meta().n_rows(),
+                        n_columns: t.meta().n_columns(),
+                        comment: t.meta().comment().to_string(),
+                    })
+                    .collect(),
+            }))
+        })
+        .await
+        .map_err(join_error)?
+    }
+
+    type FetchStream = ReceiverStream<Result<TableChunk, Status>>;
+
+    async fn fetch(
+        &self,
+        request: Request<CcdbFetchRequest>,
+    ) -> Result<Response<Self::FetchStream>, Status> {
+        let request = request.into_inner();
+        let context = context(&request)?;
+        let chunk_rows = match request.chunk_rows {
+            0 => DEFAULT_CHUNK_ROWS,
+            n => n as usize,
+        };
+        let db = self.db.clone();
+        let data = tokio::task::spawn_blocking(move || db.fetch(&request.table, &context))
+            .await
+            .map_err(join_error)?
+            .map_err(|err| status(&err))?;
+        let (tx, rx) = mpsc::channel(4);
+        tokio::spawn(async move {
+            for (run, data) in &data {
+                for chunk in chunks(*run, data, chunk_rows) {
+                    if tx.send(Ok(chunk)).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+}
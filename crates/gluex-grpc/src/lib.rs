@@ -0,0 +1,50 @@
+//! gRPC service serving `GlueX` CCDB constants and RCDB conditions.
+//!
+//! The service definition lives in `proto/gluex.proto`. A single server process keeps one
+//! open handle (and its metadata caches) per database, so farm jobs on a node can share it
+//! instead of each opening the `SQLite` files.
+
+use std::{future::Future, net::SocketAddr};
+
+use gluex_ccdb::database::CCDB;
+use gluex_rcdb::database::RCDB;
+use tonic::transport::Server;
+
+/// CCDB service implementation.
+pub mod ccdb;
+/// RCDB service implementation.
+pub mod rcdb;
+
+/// Types and service stubs generated from `proto/gluex.proto`.
+#[allow(missing_docs, clippy::pedantic)]
+pub mod proto {
+    tonic::include_proto!("gluex.v1");
+}
+
+pub use ccdb::CcdbService;
+pub use rcdb::RcdbService;
+
+/// Serves the given databases on `addr` until `shutdown` resolves.
+///
+/// Only the services for databases that are provided are registered.
+///
+/// # Errors
+///
+/// Returns an error if the server cannot bind to `addr` or fails while serving.
+pub async fn serve(
+    addr: SocketAddr,
+    ccdb: Option<CCDB>,
+    rcdb: Option<RCDB>,
+    shutdown: impl Future<Output = ()>,
+) -> Result<(), tonic::transport::Error> {
+    Server::builder()
+        .add_optional_service(ccdb.map(|db| CcdbService::new(db).into_server()))
+        .add_optional_service(rcdb.map(|db| RcdbService::new(db).into_server()))
+        .serve_with_shutdown(addr, shutdown)
+        .await
+}
+
+#[allow(clippy::needless_pass_by_value)]
+fn join_error(err: tokio::task::JoinError) -> tonic::Status {
+    tonic::Status::internal(err.to_string())
+}
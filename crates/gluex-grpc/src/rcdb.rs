@@ -0,0 +1,143 @@
+use std::{collections::HashMap, str::FromStr};
+
+use gluex_core::run_periods::RunPeriod;
+use gluex_rcdb::{context::Context, data::Value, database::RCDB, RCDBError};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status};
+
+use crate::{
+    join_error,
+    proto::{
+        condition_value::Kind,
+        rcdb_server::{Rcdb, RcdbServer},
+        ConditionInfo, ConditionValue, ListConditionsRequest, ListConditionsResponse,
+        RcdbFetchRequest, RunConditions, RunList, RunSelection,
+    },
+};
+
+/// gRPC [`Rcdb`] service backed by an [`RCDB`] handle.
+#[derive(Clone)]
+pub struct RcdbService {
+    db: RCDB,
+}
+
+impl RcdbService {
+    /// Wraps an open [`RCDB`] handle.
+    #[must_use]
+    pub fn new(db: RCDB) -> Self {
+        Self { db }
+    }
+
+    /// Returns an [`RcdbServer`] that can be added to a [`tonic::transport::Server`].
+    #[must_use]
+    pub fn into_server(self) -> RcdbServer<Self> {
+        RcdbServer::new(self)
+    }
+}
+
+fn status(err: &RCDBError) -> Status {
+    match err {
+        RCDBError::ConditionTypeNotFound(_) => Status::not_found(err.to_string()),
+        RCDBError::SqliteError(_) => Status::internal(err.to_string()),
+        _ => Status::invalid_argument(err.to_string()),
+    }
+}
+
+fn context(selection: Option<&RunSelection>) -> Result<Context, Status> {
+    let context = Context::default();
+    let Some(selection) = selection else {
+        return Ok(context);
+    };
+    if let Some(run_period) = &selection.run_period {
+        let run_period = RunPeriod::from_str(run_period)
+            .map_err(|err| Status::invalid_argument(err.to_string()))?;
+        return Ok(context.with_run_period(run_period));
+    }
+    if !selection.runs.is_empty() {
+        return Ok(context.with_runs(selection.runs.iter().copied()));
+    }
+    Ok(match (selection.run_min, selection.run_max) {
+        (None, None) => context,
+        (Some(min), None) => context.with_run_range(min..),
+        (None, Some(max)) => context.with_run_range(..=max),
+        (Some(min), Some(max)) => context.with_run_range(min..=max),
+    })
+}
+
+fn condition_value(value: &Value) -> Option<ConditionValue> {
+    let kind = if let Some(text) = value.as_string() {
+        Kind::Text(text.to_string())
+    } else if let Some(int) = value.as_int() {
+        Kind::Int(int)
+    } else if let Some(float) = value.as_float() {
+        Kind::Float(float)
+    } else if let Some(flag) = value.as_bool() {
+        Kind::Bool(flag)
+    } else {
+        Kind::Time(value.as_time()?.to_rfc3339())
+    };
+    Some(ConditionValue { kind: Some(kind) })
+}
+
+#[tonic::async_trait]
+impl Rcdb for RcdbService {
+    async fn list_conditions(
+        &self,
+        _request: Request<ListConditionsRequest>,
+    ) -> Result<Response<ListConditionsResponse>, Status> {
+        let mut types = self.db.condition_types();
+        types.sort_by(|a, b| a.name().cmp(b.name()));
+        Ok(Response::new(ListConditionsResponse {
+            conditions: types
+                .iter()
+                .map(|t| ConditionInfo {
+                    name: t.name().to_string(),
+                    value_type: t.value_type().as_str().to_string(),
+                    description: t.description().to_string(),
+                })
+                .collect(),
+        }))
+    }
+
+    async fn fetch_runs(
+        &self,
+        request: Request<RunSelection>,
+    ) -> Result<Response<RunList>, Status> {
+        let context = context(Some(request.get_ref()))?;
+        let db = self.db.clone();
+        let runs = tokio::task::spawn_blocking(move || db.fetch_runs(&context))
+            .await
+            .map_err(join_error)?
+            .map_err(|err| status(&err))?;
+        Ok(Response::new(RunList { runs }))
+    }
+
+    type FetchStream = ReceiverStream<Result<RunConditions, Status>>;
+
+    async fn fetch(
+        &self,
+        request: Request<RcdbFetchRequest>,
+    ) -> Result<Response<Self::FetchStream>, Status> {
+        let request = request.into_inner();
+        let context = context(request.selection.as_ref())?;
+        let db = self.db.clone();
+        let rows = tokio::task::spawn_blocking(move || db.fetch(&request.conditions, &context))
+            .await
+            .map_err(join_error)?
+            .map_err(|err| status(&err))?;
+        let (tx, rx) = mpsc::channel(64);
+        tokio::spawn(async move {
+            for (run, values) in rows {
+                let values: HashMap<String, ConditionValue> = values
+                    .iter()
+                    .filter_map(|(name, value)| Some((name.clone(), condition_value(value)?)))
+                    .collect();
+                if tx.send(Ok(RunConditions { run, values })).await.is_err() {
+                    return;
+                }
+            }
+        });
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+}
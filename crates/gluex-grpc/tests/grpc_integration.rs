@@ -0,0 +1,424 @@
+#![allow(missing_docs)]
+
+use std::path::PathBuf;
+
+use chrono::Duration;
+use gluex_ccdb::database::CCDB;
+use gluex_grpc::{
+    proto::{
+        ccdb_server::Ccdb, cell::Kind, condition_value, rcdb_server::Rcdb, CcdbFetchRequest,
+        ListConditionsRequest, ListDirectoryRequest, RcdbFetchRequest, RunConditions, RunSelection,
+        TableChunk,
+    },
+    CcdbService, RcdbService,
+};
+use gluex_rcdb::database::RCDB;
+use gluex_testdata::{
+    ccdb::{Assignment, CcdbBuilder},
+    rcdb::RcdbBuilder,
+};
+use tokio_stream::{Stream, StreamExt};
+use tonic::{Code, Request, Status};
+
+const TABLE_PATH: &str = "/test/demo/mytable";
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "gluex-grpc-synthetic-{}-{name}.sqlite",
+        std::process::id()
+    ))
+}
+
+fn ccdb_service(name: &str) -> CcdbService {
+    let path = temp_path(name);
+    let _ = std::fs::remove_file(&path);
+    let epoch = CcdbBuilder::epoch();
+    CcdbBuilder::new()
+        .variation("mc", "default")
+        .table(
+            TABLE_PATH,
+            2,
+            &[("x", "double"), ("label", "string"), ("n", "int")],
+        )
+        .comment("synthetic table")
+        .table("/test/counts", 1, &[("n", "int")])
+        .assign(
+            Assignment::new(TABLE_PATH, 0..=100, [["1.5", "a", "1"], ["2.5", "b", "2"]])
+                .with_created(epoch + Duration::days(1)),
+        )
+        .assign(
+            Assignment::new(TABLE_PATH, 50..=60, [["9", "x", "3"], ["8", "y", "4"]])
+                .with_created(epoch + Duration::days(3)),
+        )
+        .assign(
+            Assignment::new(TABLE_PATH, 0..=100, [["7", "mc", "5"], ["6", "mc", "6"]])
+                .with_variation("mc")
+                .with_created(epoch + Duration::days(1)),
+        )
+        .write(&path)
+        .expect("failed to write synthetic CCDB");
+    CcdbService::new(CCDB::open(&path).expect("failed to open synthetic CCDB"))
+}
+
+fn rcdb_service(name: &str) -> RcdbService {
+    let path = temp_path(name);
+    let _ = std::fs::remove_file(&path);
+    let mut builder = RcdbBuilder::new().describe("event_count", "Number of events");
+    for run in 1..=5 {
+        builder = builder
+            .condition(run, "event_count", run * 1000)
+            .condition(
+                run,
+                "run_type",
+                if run % 2 == 0 { "physics" } else { "cosmic" },
+            )
+            .condition(run, "is_valid_run_end", run != 4);
+    }
+    builder
+        .runs([6])
+        .write(&path)
+        .expect("failed to write synthetic RCDB");
+    RcdbService::new(RCDB::open(&path).expect("failed to open synthetic RCDB"))
+}
+
+async fn collect<T>(stream: impl Stream<Item = Result<T, Status>>) -> Vec<T> {
+    stream
+        .map(|item| item.expect("stream error"))
+        .collect()
+        .await
+}
+
+async fn fetch_chunks(service: &CcdbService, request: CcdbFetchRequest) -> Vec<TableChunk> {
+    collect(
+        service
+            .fetch(Request::new(request))
+            .await
+            .expect("fetch failed")
+            .into_inner(),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn ccdb_fetch_streams_row_chunks_per_run() {
+    let service = ccdb_service("ccdb_chunks");
+    let chunks = fetch_chunks(
+        &service,
+        CcdbFetchRequest {
+            table: TABLE_PATH.to_string(),
+            runs: vec![5, 55],
+            chunk_rows: 1,
+            ..Default::default()
+        },
+    )
+    .await;
+    assert_eq!(
+        chunks
+            .iter()
+            .map(|c| (c.run, c.row_offset, c.rows.len()))
+            .collect::<Vec<_>>(),
+        [(5, 0, 1), (5, 1, 1), (55, 0, 1), (55, 1, 1)]
+    );
+    assert_eq!(chunks[0].columns, ["x", "label", "n"]);
+    assert!(chunks[1].columns.is_empty());
+    let cells: Vec<_> = chunks[1].rows[0]
+        .cells
+        .iter()
+        .map(|cell| cell.kind.clone())
+        .collect();
+    assert_eq!(
+        cells,
+        [
+            Some(Kind::Double(2.5)),
+            Some(Kind::String("b".to_string())),
+            Some(Kind::Int(2)),
+        ]
+    );
+    assert_eq!(chunks[2].rows[0].cells[0].kind, Some(Kind::Double(9.0)));
+
+    let whole = fetch_chunks(
+        &service,
+        CcdbFetchRequest {
+            table: TABLE_PATH.to_string(),
+            runs: vec![5],
+            ..Default::default()
+        },
+    )
+    .await;
+    assert_eq!(whole.len(), 1);
+    assert_eq!(whole[0].rows.len(), 2);
+}
+
+#[tokio::test]
+async fn ccdb_fetch_honors_variation_and_timestamp() {
+    let service = ccdb_service("ccdb_context");
+    let first_x = |chunks: Vec<TableChunk>| chunks[0].rows[0].cells[0].kind.clone();
+    let mc = fetch_chunks(
+        &service,
+        CcdbFetchRequest {
+            table: TABLE_PATH.to_string(),
+            runs: vec![55],
+            variation: Some("mc".to_string()),
+            ..Default::default()
+        },
+    )
+    .await;
+    assert_eq!(first_x(mc), Some(Kind::Double(7.0)));
+
+    let before_override = (CcdbBuilder::epoch() + Duration::days(2))
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string();
+    let older = fetch_chunks(
+        &service,
+        CcdbFetchRequest {
+            table: TABLE_PATH.to_string(),
+            runs: vec![55],
+            timestamp: Some(before_override),
+            ..Default::default()
+        },
+    )
+    .await;
+    assert_eq!(first_x(older), Some(Kind::Double(1.5)));
+}
+
+#[tokio::test]
+async fn ccdb_directories_list_subdirectories_and_tables() {
+    let service = ccdb_service("ccdb_list");
+    let root = service
+        .list_directory(Request::new(ListDirectoryRequest::default()))
+        .await
+        .expect("listing the root failed")
+        .into_inner();
+    assert_eq!(root.path, "/");
+    assert_eq!(root.dirs, ["test"]);
+
+    let test = service
+        .list_directory(Request::new(ListDirectoryRequest {
+            path: "/test".to_string(),
+        }))
+        .await
+        .expect("listing /test failed")
+        .into_inner();
+    assert_eq!(test.dirs, ["demo"]);
+    assert_eq!(test.tables.len(), 1);
+    assert_eq!(test.tables[0].path, "/test/counts");
+
+    let demo = service
+        .list_directory(Request::new(ListDirectoryRequest {
+            path: "/test/demo".to_string(),
+        }))
+        .await
+        .expect("listing /test/demo failed")
+        .into_inner();
+    let table = &demo.tables[0];
+    assert_eq!(
+        (table.name.as_str(), table.n_rows, table.n_columns),
+        ("mytable", 2, 3)
+    );
+    assert_eq!(table.comment, "synthetic table");
+}
+
+#[tokio::test]
+async fn ccdb_errors_map_to_status_codes() {
+    let service = ccdb_service("ccdb_errors");
+    let missing_dir = service
+        .list_directory(Request::new(ListDirectoryRequest {
+            path: "/definitely/not/here".to_string(),
+        }))
+        .await
+        .unwrap_err();
+    assert_eq!(missing_dir.code(), Code::NotFound);
+
+    let fetch_error = |request: CcdbFetchRequest| {
+        let service = service.clone();
+        async move {
+            service
+                .fetch(Request::new(request))
+                .await
+                .unwrap_err()
+                .code()
+        }
+    };
+    assert_eq!(
+        fetch_error(CcdbFetchRequest {
+            table: "/test/missing".to_string(),
+            ..Default::default()
+        })
+        .await,
+        Code::NotFound
+    );
+    assert_eq!(
+        fetch_error(CcdbFetchRequest {
+            table: TABLE_PATH.to_string(),
+            variation: Some("nope".to_string()),
+            ..Default::default()
+        })
+        .await,
+        Code::NotFound
+    );
+    assert_eq!(
+        fetch_error(CcdbFetchRequest {
+            table: TABLE_PATH.to_string(),
+            timestamp: Some("not a timestamp".to_string()),
+            ..Default::default()
+        })
+        .await,
+        Code::InvalidArgument
+    );
+    assert_eq!(
+        fetch_error(CcdbFetchRequest {
+            table: TABLE_PATH.to_string(),
+            run_period: Some("not a run period".to_string()),
+            ..Default::default()
+        })
+        .await,
+        Code::InvalidArgument
+    );
+}
+
+#[tokio::test]
+async fn rcdb_conditions_are_listed_by_name() {
+    let service = rcdb_service("rcdb_list");
+    let conditions = service
+        .list_conditions(Request::new(ListConditionsRequest {}))
+        .await
+        .expect("listing conditions failed")
+        .into_inner()
+        .conditions;
+    assert_eq!(
+        conditions
+            .iter()
+            .map(|c| (c.name.as_str(), c.value_type.as_str()))
+            .collect::<Vec<_>>(),
+        [
+            ("event_count", "int"),
+            ("is_valid_run_end", "bool"),
+            ("run_type", "string"),
+        ]
+    );
+    assert_eq!(conditions[0].description, "Number of events");
+}
+
+#[tokio::test]
+async fn rcdb_fetch_runs_follows_the_selection() {
+    let service = rcdb_service("rcdb_runs");
+    let runs = |selection: RunSelection| {
+        let service = service.clone();
+        async move {
+            service
+                .fetch_runs(Request::new(selection))
+                .await
+                .expect("fetch_runs failed")
+                .into_inner()
+                .runs
+        }
+    };
+    assert_eq!(
+        runs(RunSelection {
+            runs: vec![2, 4, 9],
+            ..Default::default()
+        })
+        .await,
+        [2, 4]
+    );
+    assert_eq!(
+        runs(RunSelection {
+            run_min: Some(2),
+            run_max: Some(4),
+            ..Default::default()
+        })
+        .await,
+        [2, 3, 4]
+    );
+    assert_eq!(
+        runs(RunSelection {
+            run_min: Some(5),
+            ..Default::default()
+        })
+        .await,
+        [5, 6]
+    );
+}
+
+#[tokio::test]
+async fn rcdb_fetch_streams_one_message_per_run() {
+    let service = rcdb_service("rcdb_fetch");
+    let messages: Vec<RunConditions> = collect(
+        service
+            .fetch(Request::new(RcdbFetchRequest {
+                conditions: vec![
+                    "event_count".to_string(),
+                    "run_type".to_string(),
+                    "is_valid_run_end".to_string(),
+                ],
+                selection: Some(RunSelection {
+                    run_min: Some(3),
+                    run_max: Some(4),
+                    ..Default::default()
+                }),
+            }))
+            .await
+            .expect("fetch failed")
+            .into_inner(),
+    )
+    .await;
+    assert_eq!(messages.iter().map(|m| m.run).collect::<Vec<_>>(), [3, 4]);
+    let kind = |message: &RunConditions, name: &str| {
+        message
+            .values
+            .get(name)
+            .and_then(|value| value.kind.clone())
+    };
+    assert_eq!(
+        kind(&messages[1], "event_count"),
+        Some(condition_value::Kind::Int(4000))
+    );
+    assert_eq!(
+        kind(&messages[1], "run_type"),
+        Some(condition_value::Kind::Text("physics".to_string()))
+    );
+    assert_eq!(
+        kind(&messages[1], "is_valid_run_end"),
+        Some(condition_value::Kind::Bool(false))
+    );
+    assert_eq!(
+        kind(&messages[0], "is_valid_run_end"),
+        Some(condition_value::Kind::Bool(true))
+    );
+}
+
+#[tokio::test]
+async fn rcdb_errors_map_to_status_codes() {
+    let service = rcdb_service("rcdb_errors");
+    let unknown = service
+        .fetch(Request::new(RcdbFetchRequest {
+            conditions: vec!["missing".to_string()],
+            selection: None,
+        }))
+        .await
+        .unwrap_err();
+    assert_eq!(unknown.code(), Code::NotFound);
+
+    let bad_period = service
+        .fetch_runs(Request::new(RunSelection {
+            run_period: Some("not a run period".to_string()),
+            ..Default::default()
+        }))
+        .await
+        .unwrap_err();
+    assert_eq!(bad_period.code(), Code::InvalidArgument);
+}
+
+#[tokio::test]
+#[ignore = "needs CCDB_TEST_SQLITE_CONNECTION to point at a CCDB snapshot"]
+async fn ccdb_snapshot_lists_its_root() {
+    let path = std::env::var("CCDB_TEST_SQLITE_CONNECTION")
+        .expect("set CCDB_TEST_SQLITE_CONNECTION to a CCDB SQLite file");
+    let service = CcdbService::new(CCDB::open(&path).expect("failed to open CCDB test database"));
+    let root = service
+        .list_directory(Request::new(ListDirectoryRequest::default()))
+        .await
+        .expect("listing the root failed")
+        .into_inner();
+    assert!(!root.dirs.is_empty() || !root.tables.is_empty());
+}
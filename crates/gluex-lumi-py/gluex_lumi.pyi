@@ -2,17 +2,29 @@
 
 from __future__ import annotations
 
+import os
 from collections.abc import Mapping, Sequence
 
-class Histogram:
-    counts: list[float]
-    edges: list[float]
-    errors: list[float]
+import numpy as np
+import numpy.typing as npt
 
+class Histogram:
     def __init__(
-        self, counts: list[float], edges: list[float], errors: list[float]
+        self, counts: Sequence[float], edges: Sequence[float], errors: Sequence[float]
     ) -> None: ...
+    @property
+    def counts(self) -> npt.NDArray[np.float64]: ...
+    @property
+    def edges(self) -> npt.NDArray[np.float64]: ...
+    @property
+    def errors(self) -> npt.NDArray[np.float64]: ...
+    def to_mpl(
+        self,
+    ) -> tuple[
+        npt.NDArray[np.float64], npt.NDArray[np.float64], npt.NDArray[np.float64]
+    ]: ...
     def as_dict(self) -> dict[str, list[float]]: ...
+    def __len__(self) -> int: ...
 
 class FluxHistograms:
     tagged_flux: Histogram
@@ -35,8 +47,8 @@ def get_flux_histograms(
     *,
     coherent_peak: bool = False,
     polarized: bool = False,
-    rcdb: str | None = None,
-    ccdb: str | None = None,
+    rcdb: str | os.PathLike[str] | None = None,
+    ccdb: str | os.PathLike[str] | None = None,
     exclude_runs: Sequence[int] | None = None,
 ) -> FluxHistograms: ...
 def cli() -> None: ...
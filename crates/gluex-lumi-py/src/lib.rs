@@ -1,4 +1,4 @@
-use std::{collections::HashMap, env, error::Error, ffi::CString, io, str::FromStr};
+use std::{collections::HashMap, env, error::Error, ffi::CString, io, path::PathBuf, str::FromStr};
 
 use ::gluex_lumi as lumi_crate;
 use gluex_core::{histograms::Histogram, run_periods::RunPeriod, RestVersion, RunNumber};
@@ -6,6 +6,7 @@ use lumi_crate::{
     get_flux_histograms as compute_flux_histograms, FluxHistograms as RustFluxHistograms,
     GlueXLumiError, RestSelection,
 };
+use numpy::PyArray1;
 use pyo3::{
     exceptions::PyRuntimeError,
     prelude::*,
@@ -55,22 +56,27 @@ def plot_histograms(data, output_path):
     plt.close(fig)
 "#;
 
-#[pyclass(module = "gluex_lumi", name = "Histogram")]
+/// Binned histogram with per-bin uncertainties.
+///
+/// Parameters
+/// ----------
+/// counts : Sequence[float]
+///     Bin contents.
+/// edges : Sequence[float]
+///     Monotonically increasing bin edges (one more than the number of bins).
+/// errors : Sequence[float]
+///     Per-bin uncertainties.
+#[pyclass(module = "gluex_lumi", name = "Histogram", frozen)]
 pub struct PyHistogram {
-    #[pyo3(get)]
-    counts: Vec<f64>,
-    #[pyo3(get)]
-    edges: Vec<f64>,
-    #[pyo3(get)]
-    errors: Vec<f64>,
+    inner: Histogram,
 }
 
 impl PyHistogram {
     fn to_dict(&self, py: Python<'_>) -> PyResult<Py<PyDict>> {
         let dict = PyDict::new(py);
-        dict.set_item("counts", self.counts.clone())?;
-        dict.set_item("edges", self.edges.clone())?;
-        dict.set_item("errors", self.errors.clone())?;
+        dict.set_item("counts", self.inner.counts.clone())?;
+        dict.set_item("edges", self.inner.edges.clone())?;
+        dict.set_item("errors", self.inner.errors.clone())?;
         Ok(dict.unbind())
     }
 }
@@ -78,17 +84,87 @@ impl PyHistogram {
 #[pymethods]
 impl PyHistogram {
     #[new]
-    fn new(counts: Vec<f64>, edges: Vec<f64>, errors: Vec<f64>) -> Self {
-        Self {
-            counts,
-            edges,
-            errors,
+    fn new(counts: Vec<f64>, edges: Vec<f64>, errors: Vec<f64>) -> PyResult<Self> {
+        if edges.len() != counts.len() + 1 || errors.len() != counts.len() {
+            return Err(PyRuntimeError::new_err(format!(
+                "expected len(edges) == len(counts) + 1 == len(errors) + 1, got {} edges, {} counts, and {} errors",
+                edges.len(),
+                counts.len(),
+                errors.len()
+            )));
         }
+        Ok(Self {
+            inner: Histogram {
+                counts,
+                edges,
+                errors,
+            },
+        })
+    }
+
+    /// numpy.ndarray: Bin contents as a float64 array.
+    #[getter]
+    fn counts<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+        PyArray1::from_slice(py, &self.inner.counts)
+    }
+
+    /// numpy.ndarray: Bin edges as a float64 array with ``len(counts) + 1`` entries.
+    #[getter]
+    fn edges<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+        PyArray1::from_slice(py, &self.inner.edges)
+    }
+
+    /// numpy.ndarray: Per-bin uncertainties as a float64 array.
+    #[getter]
+    fn errors<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<f64>> {
+        PyArray1::from_slice(py, &self.inner.errors)
+    }
+
+    /// to_mpl(self)
+    ///
+    /// Returns
+    /// -------
+    /// tuple[numpy.ndarray, numpy.ndarray, numpy.ndarray]
+    ///     ``(edges, counts, errors)``, ready for ``plt.stairs(counts, edges)`` and
+    ///     ``plt.errorbar(centers, counts, yerr=errors)``.
+    ///
+    /// Examples
+    /// --------
+    /// >>> edges, counts, errors = hist.to_mpl()
+    /// >>> plt.stairs(counts, edges)
+    #[allow(clippy::type_complexity)]
+    fn to_mpl<'py>(
+        &self,
+        py: Python<'py>,
+    ) -> (
+        Bound<'py, PyArray1<f64>>,
+        Bound<'py, PyArray1<f64>>,
+        Bound<'py, PyArray1<f64>>,
+    ) {
+        (self.edges(py), self.counts(py), self.errors(py))
     }
 
+    /// as_dict(self)
+    ///
+    /// Returns
+    /// -------
+    /// dict[str, list[float]]
+    ///     ``counts``, ``edges``, and ``errors`` as plain Python lists.
     pub fn as_dict(&self, py: Python<'_>) -> PyResult<Py<PyDict>> {
         self.to_dict(py)
     }
+
+    fn __len__(&self) -> usize {
+        self.inner.counts.len()
+    }
+
+    fn __repr__(&self) -> String {
+        let (low, high) = self.inner.limits();
+        format!(
+            "Histogram(bins={}, range=({low}, {high}))",
+            self.inner.counts.len()
+        )
+    }
 }
 
 #[pyclass(module = "gluex_lumi", name = "FluxHistograms")]
@@ -161,10 +237,10 @@ fn parse_run_periods(obj: &Bound<'_, PyAny>) -> PyResult<HashMap<RunPeriod, Rest
     Ok(selection)
 }
 
-fn resolve_connection_path(value: Option<String>, env_var: &str) -> PyResult<String> {
+fn resolve_connection_path(value: Option<PathBuf>, env_var: &str) -> PyResult<PathBuf> {
     match value {
-        Some(path) if !path.is_empty() => Ok(path),
-        _ => env::var(env_var).map_err(|_| {
+        Some(path) if !path.as_os_str().is_empty() => Ok(path),
+        _ => env::var_os(env_var).map(PathBuf::from).ok_or_else(|| {
             PyRuntimeError::new_err(format!("{env_var} is not set and no path was provided"))
         }),
     }
@@ -174,9 +250,7 @@ fn histogram_to_py(py: Python<'_>, hist: &Histogram) -> PyResult<Py<PyHistogram>
     Py::new(
         py,
         PyHistogram {
-            counts: hist.counts.clone(),
-            edges: hist.edges.clone(),
-            errors: hist.errors.clone(),
+            inner: hist.clone(),
         },
     )
 }
@@ -227,8 +301,8 @@ struct ParsedCliArgs {
     max_edge: f64,
     coherent_peak: bool,
     polarized: bool,
-    rcdb: String,
-    ccdb: String,
+    rcdb: PathBuf,
+    ccdb: PathBuf,
     exclude_runs: Option<Vec<RunNumber>>,
     plot_path: String,
 }
@@ -276,8 +350,8 @@ fn parse_plot_cli_args(argv: &[String], plot_path: String) -> PyResult<ParsedCli
     let mut bins: Option<usize> = None;
     let mut min_edge: Option<f64> = None;
     let mut max_edge: Option<f64> = None;
-    let mut rcdb_path: Option<String> = None;
-    let mut ccdb_path: Option<String> = None;
+    let mut rcdb_path: Option<PathBuf> = None;
+    let mut ccdb_path: Option<PathBuf> = None;
     let mut exclude_runs: Option<Vec<RunNumber>> = None;
     let mut coherent_peak = false;
     let mut polarized = false;
@@ -320,9 +394,9 @@ fn parse_plot_cli_args(argv: &[String], plot_path: String) -> PyResult<ParsedCli
                     PyRuntimeError::new_err("--max expects a floating point value")
                 })?);
         } else if let Some(value) = take_value("--rcdb", &mut i, argv)? {
-            rcdb_path = Some(value);
+            rcdb_path = Some(value.into());
         } else if let Some(value) = take_value("--ccdb", &mut i, argv)? {
-            ccdb_path = Some(value);
+            ccdb_path = Some(value.into());
         } else if let Some(value) = take_value("--exclude-runs", &mut i, argv)? {
             let parsed = parse_exclude_runs_arg(&value)?;
             if let Some(existing) = exclude_runs.as_mut() {
@@ -390,9 +464,9 @@ fn uniform_edges(bins: usize, min_edge: f64, max_edge: f64) -> Vec<f64> {
 ///     If true, only retain photons in the coherent peak for each run.
 /// polarized : bool, optional
 ///     Use the polarized flux calibration constants when true.
-/// rcdb : str, optional
+/// rcdb : str or os.PathLike, optional
 ///     Path to the RCDB SQLite database. Defaults to the ``RCDB_CONNECTION`` env var.
-/// ccdb : str, optional
+/// ccdb : str or os.PathLike, optional
 ///     Path to the CCDB SQLite database. Defaults to the ``CCDB_CONNECTION`` env var.
 /// exclude_runs : Sequence[int], optional
 ///     Run numbers to skip when computing the histograms.
@@ -403,6 +477,7 @@ fn uniform_edges(bins: usize, min_edge: f64, max_edge: f64) -> Vec<f64> {
 ///     Object exposing ``tagged_flux``, ``tagm_flux``, ``tagh_flux``, and
 ///     ``tagged_luminosity`` histograms.
 #[pyfunction(name = "get_flux_histograms")]
+#[allow(clippy::too_many_arguments)]
 #[pyo3(signature = (run_periods, edges, *, coherent_peak=false, polarized=false, rcdb=None, ccdb=None, exclude_runs=None))]
 pub fn py_get_flux_histograms(
     py: Python<'_>,
@@ -410,8 +485,8 @@ pub fn py_get_flux_histograms(
     edges: Vec<f64>,
     coherent_peak: bool,
     polarized: bool,
-    rcdb: Option<String>,
-    ccdb: Option<String>,
+    rcdb: Option<PathBuf>,
+    ccdb: Option<PathBuf>,
     exclude_runs: Option<Vec<RunNumber>>,
 ) -> PyResult<Py<PyFluxHistograms>> {
     if edges.len() < 2 {
@@ -422,16 +497,19 @@ pub fn py_get_flux_histograms(
     let run_selection = parse_run_periods(&run_periods)?;
     let rcdb_path = resolve_connection_path(rcdb, "RCDB_CONNECTION")?;
     let ccdb_path = resolve_connection_path(ccdb, "CCDB_CONNECTION")?;
-    let histograms = compute_flux_histograms(
-        run_selection,
-        &edges,
-        coherent_peak,
-        polarized,
-        rcdb_path,
-        ccdb_path,
-        exclude_runs,
-    )
-    .map_err(py_lumi_error)?;
+    let histograms = py
+        .detach(|| {
+            compute_flux_histograms(
+                run_selection,
+                &edges,
+                coherent_peak,
+                polarized,
+                rcdb_path,
+                ccdb_path,
+                exclude_runs,
+            )
+        })
+        .map_err(py_lumi_error)?;
     flux_histograms_to_py(py, &histograms)
 }
 
@@ -468,16 +546,19 @@ pub fn py_cli(py: Python<'_>) -> PyResult<()> {
     if let Some(plot_path) = plot_path {
         let parsed = parse_plot_cli_args(&filtered_args, plot_path)?;
         let edges = uniform_edges(parsed.bins, parsed.min_edge, parsed.max_edge);
-        let hist = compute_flux_histograms(
-            parsed.run_selection,
-            &edges,
-            parsed.coherent_peak,
-            parsed.polarized,
-            parsed.rcdb,
-            parsed.ccdb,
-            parsed.exclude_runs,
-        )
-        .map_err(py_lumi_error)?;
+        let hist = py
+            .detach(|| {
+                compute_flux_histograms(
+                    parsed.run_selection,
+                    &edges,
+                    parsed.coherent_peak,
+                    parsed.polarized,
+                    parsed.rcdb,
+                    parsed.ccdb,
+                    parsed.exclude_runs,
+                )
+            })
+            .map_err(py_lumi_error)?;
         to_writer_pretty(io::stdout(), &hist)
             .map_err(|err| PyRuntimeError::new_err(err.to_string()))?;
         println!();
@@ -485,15 +566,18 @@ pub fn py_cli(py: Python<'_>) -> PyResult<()> {
         let flux_bound = py_flux.bind(py);
         let dict = flux_bound.borrow().to_dict(py)?;
         let bound = dict.bind(py);
-        plot_histograms(py, &bound, &parsed.plot_path)?;
+        plot_histograms(py, bound, &parsed.plot_path)?;
         Ok(())
     } else {
-        lumi_crate::cli::run_with_args(filtered_args)
-            .map_err(|err: Box<dyn Error>| PyRuntimeError::new_err(err.to_string()))
+        py.detach(|| {
+            lumi_crate::cli::run_with_args(filtered_args)
+                .map_err(|err: Box<dyn Error>| err.to_string())
+        })
+        .map_err(PyRuntimeError::new_err)
     }
 }
 
-#[pymodule]
+#[pymodule(gil_used = false)]
 /// gluex_lumi
 ///
 /// Python bindings for the GlueX luminosity utilities.
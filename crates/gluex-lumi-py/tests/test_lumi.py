@@ -63,3 +63,22 @@ def test_get_flux_histograms_smoke() -> None:
         assert len(hist.edges) == 3
         assert len(hist.counts) == 2
         assert len(hist.errors) == 2
+
+
+def test_histogram_numpy_and_mpl() -> None:
+    np = pytest.importorskip("numpy")
+    hist = gluex_lumi.Histogram([1.0, 4.0], [0.0, 0.5, 1.0], [1.0, 2.0])
+    assert len(hist) == 2
+    assert hist.counts.dtype == np.float64
+    np.testing.assert_array_equal(hist.edges, [0.0, 0.5, 1.0])
+    edges, counts, errors = hist.to_mpl()
+    np.testing.assert_array_equal(edges, hist.edges)
+    np.testing.assert_array_equal(counts, [1.0, 4.0])
+    np.testing.assert_array_equal(errors, [1.0, 2.0])
+    assert hist.as_dict() == {
+        "counts": [1.0, 4.0],
+        "edges": [0.0, 0.5, 1.0],
+        "errors": [1.0, 2.0],
+    }
+    with pytest.raises(RuntimeError):
+        gluex_lumi.Histogram([1.0], [0.0, 1.0, 2.0], [1.0])
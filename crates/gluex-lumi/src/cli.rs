@@ -1,18 +1,99 @@
-use std::{collections::HashMap, env, ffi::OsString, io, path::PathBuf, str::FromStr};
+use std::{
+    collections::HashMap,
+    env,
+    ffi::OsString,
+    fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 use clap::{Args, CommandFactory, Parser, Subcommand};
 use gluex_core::{
     run_periods::{rest_versions_for, RunPeriod},
     RunNumber,
 };
-use serde_json::to_writer_pretty;
+use serde_json::{from_str, json, to_writer_pretty};
 use strum::IntoEnumIterator;
 
-use crate::{get_flux_histograms, RestSelection};
+use crate::{
+    get_coverage_report, get_flux_histograms, get_flux_histograms_with_diagnostics,
+    CoherentPeakSelection, CoherentPeakWindow, CoherentPeakWindows, RestSelection,
+};
 
 #[derive(Parser)]
 #[command(name = "gluex-lumi", version)]
 struct Cli {
+    #[command(flatten)]
+    connections: ConnectionArgs,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    #[command(flatten)]
+    lumi: LumiArgs,
+}
+
+/// Database connection flags shared by every command that reads RCDB or CCDB.
+///
+/// Both flags are global, so they may be given before or after a subcommand, and fall back to the
+/// `RCDB_CONNECTION` and `CCDB_CONNECTION` environment variables.
+#[derive(Args, Debug, Clone, Default)]
+#[command(about = None, long_about = None)]
+pub struct ConnectionArgs {
+    /// RCDB path
+    #[arg(long, global = true, env = "RCDB_CONNECTION")]
+    pub rcdb: Option<PathBuf>,
+
+    /// CCDB path
+    #[arg(long, global = true, env = "CCDB_CONNECTION")]
+    pub ccdb: Option<PathBuf>,
+}
+
+impl ConnectionArgs {
+    /// Returns the RCDB path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`io::ErrorKind::InvalidInput`] error if neither `--rcdb` nor `RCDB_CONNECTION`
+    /// was given.
+    pub fn rcdb_path(&self) -> io::Result<&Path> {
+        self.rcdb.as_deref().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--rcdb is required (or set RCDB_CONNECTION)",
+            )
+        })
+    }
+
+    /// Returns the CCDB path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`io::ErrorKind::InvalidInput`] error if neither `--ccdb` nor `CCDB_CONNECTION`
+    /// was given.
+    pub fn ccdb_path(&self) -> io::Result<&Path> {
+        self.ccdb.as_deref().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "--ccdb is required (or set CCDB_CONNECTION)",
+            )
+        })
+    }
+}
+
+/// Output format flags shared by every command that prints results.
+#[derive(Args, Debug, Clone, Default)]
+#[command(about = None, long_about = None)]
+pub struct OutputArgs {
+    /// Print results as JSON instead of human-readable text
+    #[arg(long, global = true)]
+    pub json: bool,
+}
+
+/// Arguments for the luminosity commands, usable as a subcommand tree in other CLIs.
+#[derive(Args, Debug, Clone)]
+#[command(about = None, long_about = None)]
+pub struct LumiArgs {
     #[command(flatten)]
     flux: FluxArgs,
 
@@ -20,12 +101,26 @@ struct Cli {
     command: Option<Command>,
 }
 
-#[derive(Subcommand)]
+#[derive(Subcommand, Debug, Clone)]
 enum Command {
     /// List known REST versions for one or all run periods.
     List { run_period: Option<RunPeriod> },
     /// Run the flux calculation (alias for no subcommand).
     Plot(FluxArgs),
+    /// Cross-check approved RCDB runs against the CCDB tables the flux calculation needs.
+    Coverage(CoverageArgs),
+}
+
+#[derive(Args, Debug, Clone)]
+struct CoverageArgs {
+    /// Run period selection: <run>[=<rest>]
+    /// Example: f18=0, s19=2, s23
+    #[arg(long = "run", value_parser = parse_run_pair)]
+    runs: Vec<(RunPeriod, RestSelection)>,
+
+    /// Apply the polarized (coherent beam) run selection
+    #[arg(long)]
+    polarized: bool,
 }
 
 #[derive(Args, Debug, Clone)]
@@ -51,21 +146,27 @@ struct FluxArgs {
     #[arg(long)]
     coherent_peak: bool,
 
+    /// Override the coherent peak window for a run range: <run_min>-<run_max>=<low>:<high>
+    /// Example: 30000-39999=8.2:8.8 (implies --coherent-peak)
+    #[arg(long = "coherent-peak-window")]
+    coherent_peak_windows: Vec<CoherentPeakWindow>,
+
+    /// JSON file containing a list of coherent peak windows, each with "run_min", "run_max",
+    /// "low", and "high" keys (implies --coherent-peak; --coherent-peak-window takes precedence)
+    #[arg(long)]
+    coherent_peak_config: Option<PathBuf>,
+
     /// Use polarized flux
     #[arg(long)]
     polarized: bool,
 
-    /// RCDB path
-    #[arg(long, env = "RCDB_CONNECTION")]
-    rcdb: Option<PathBuf>,
-
-    /// CCDB path
-    #[arg(long, env = "CCDB_CONNECTION")]
-    ccdb: Option<PathBuf>,
-
     /// Comma-separated run numbers to exclude (e.g. 10,20,30)
     #[arg(long = "exclude-runs", value_delimiter = ',')]
     exclude_runs: Option<Vec<RunNumber>>,
+
+    /// Report why each dropped run was excluded from the flux sum (printed to stderr)
+    #[arg(long)]
+    diagnostics: bool,
 }
 
 struct FluxConfig {
@@ -73,11 +174,12 @@ struct FluxConfig {
     bins: usize,
     min_edge: f64,
     max_edge: f64,
-    coherent_peak: bool,
+    coherent_peak: CoherentPeakSelection,
     polarized: bool,
     rcdb: PathBuf,
     ccdb: PathBuf,
     exclude_runs: Option<Vec<RunNumber>>,
+    diagnostics: bool,
 }
 
 fn parse_run_pair(s: &str) -> Result<(RunPeriod, RestSelection), String> {
@@ -116,6 +218,22 @@ fn print_rest_versions(run_period: RunPeriod) {
     }
 }
 
+fn rest_versions_json(run_period: RunPeriod) -> serde_json::Value {
+    let versions: Vec<serde_json::Value> = rest_versions_for(run_period)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(version, timestamp)| {
+            json!({ "version": version, "timestamp": timestamp.to_rfc3339() })
+        })
+        .collect();
+    json!({
+        "run_period": run_period.short_name(),
+        "min_run": run_period.min_run(),
+        "max_run": run_period.max_run(),
+        "rest_versions": versions,
+    })
+}
+
 fn uniform_edges(bins: usize, min: f64, max: f64) -> Vec<f64> {
     let width = (max - min) / bins as f64;
     (0..=bins).map(|i| min + i as f64 * width).collect()
@@ -134,13 +252,46 @@ where
         return Ok(());
     }
     let cli = Cli::parse_from(args_vec);
+    run_with_output(cli.lumi, &cli.connections, &cli.output)
+}
+
+/// Execute parsed luminosity arguments against the given database connections.
+///
+/// # Errors
+///
+/// Returns an error if a required argument or connection is missing, if the coherent peak
+/// configuration cannot be read, or if the flux calculation fails.
+pub fn run(args: LumiArgs, connections: &ConnectionArgs) -> Result<(), Box<dyn std::error::Error>> {
+    run_with_output(args, connections, &OutputArgs::default())
+}
 
-    match cli.command {
+/// Execute parsed luminosity arguments, formatting results as requested by `output`.
+///
+/// The flux histograms are always printed as JSON; `--json` additionally switches `list` to JSON
+/// and prints the `--diagnostics` report to stderr as a JSON [`FluxDiagnostics`](crate::FluxDiagnostics).
+///
+/// # Errors
+///
+/// Returns an error if a required argument or connection is missing, if the coherent peak
+/// configuration cannot be read, or if the flux calculation fails.
+pub fn run_with_output(
+    args: LumiArgs,
+    connections: &ConnectionArgs,
+    output: &OutputArgs,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match args.command {
         Some(Command::List { run_period }) => {
-            if let Some(period) = run_period {
-                print_rest_versions(period);
+            let periods: Vec<RunPeriod> = match run_period {
+                Some(period) => vec![period],
+                None => RunPeriod::iter().collect(),
+            };
+            if output.json {
+                let periods: Vec<serde_json::Value> =
+                    periods.into_iter().map(rest_versions_json).collect();
+                to_writer_pretty(std::io::stdout(), &periods)?;
+                println!();
             } else {
-                for (idx, period) in RunPeriod::iter().enumerate() {
+                for (idx, period) in periods.into_iter().enumerate() {
                     if idx > 0 {
                         println!();
                     }
@@ -149,8 +300,9 @@ where
             }
             Ok(())
         }
-        Some(Command::Plot(args)) => run_flux(args),
-        None => run_flux(cli.flux),
+        Some(Command::Plot(flux)) => run_flux(flux, connections, output),
+        Some(Command::Coverage(coverage)) => run_coverage(coverage, connections, output),
+        None => run_flux(args.flux, connections, output),
     }
 }
 
@@ -159,7 +311,10 @@ pub fn cli() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 impl FluxArgs {
-    fn into_config(self) -> Result<FluxConfig, Box<dyn std::error::Error>> {
+    fn into_config(
+        self,
+        connections: &ConnectionArgs,
+    ) -> Result<FluxConfig, Box<dyn std::error::Error>> {
         let run_selection: HashMap<RunPeriod, RestSelection> = self.runs.into_iter().collect();
         if run_selection.is_empty() {
             return Err(io::Error::new(
@@ -191,35 +346,74 @@ impl FluxArgs {
             )
             .into());
         }
-        let rcdb = self.rcdb.ok_or_else(|| {
-            io::Error::new(
-                io::ErrorKind::InvalidInput,
-                "--rcdb is required (or set RCDB_CONNECTION)",
-            )
-        })?;
-        let ccdb = self.ccdb.ok_or_else(|| {
-            io::Error::new(
-                io::ErrorKind::InvalidInput,
-                "--ccdb is required (or set CCDB_CONNECTION)",
-            )
-        })?;
+        let rcdb = connections.rcdb_path()?.to_path_buf();
+        let ccdb = connections.ccdb_path()?.to_path_buf();
+        let mut windows: Vec<CoherentPeakWindow> = Vec::new();
+        if let Some(path) = &self.coherent_peak_config {
+            windows.extend(from_str::<Vec<CoherentPeakWindow>>(&fs::read_to_string(
+                path,
+            )?)?);
+        }
+        windows.extend(self.coherent_peak_windows);
+        let coherent_peak = if windows.is_empty() {
+            CoherentPeakSelection::from(self.coherent_peak)
+        } else {
+            CoherentPeakSelection::from(CoherentPeakWindows::new(windows))
+        };
 
         Ok(FluxConfig {
             run_selection,
             bins,
             min_edge,
             max_edge,
-            coherent_peak: self.coherent_peak,
+            coherent_peak,
             polarized: self.polarized,
             rcdb,
             ccdb,
             exclude_runs: self.exclude_runs,
+            diagnostics: self.diagnostics,
         })
     }
 }
 
-fn run_flux(args: FluxArgs) -> Result<(), Box<dyn std::error::Error>> {
-    let config = args.into_config()?;
+fn run_coverage(
+    args: CoverageArgs,
+    connections: &ConnectionArgs,
+    output: &OutputArgs,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let run_selection: HashMap<RunPeriod, RestSelection> = args.runs.into_iter().collect();
+    if run_selection.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "at least one --run=<period>=<rest> argument is required",
+        )
+        .into());
+    }
+    let report = get_coverage_report(
+        run_selection,
+        args.polarized,
+        connections.rcdb_path()?,
+        connections.ccdb_path()?,
+    )?;
+    if output.json {
+        to_writer_pretty(std::io::stdout(), &report)?;
+        println!();
+    } else {
+        print!("{report}");
+    }
+    if report.is_complete() {
+        Ok(())
+    } else {
+        Err(io::Error::other("some approved runs are missing CCDB constants").into())
+    }
+}
+
+fn run_flux(
+    args: FluxArgs,
+    connections: &ConnectionArgs,
+    output: &OutputArgs,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let config = args.into_config(connections)?;
     let FluxConfig {
         run_selection,
         bins,
@@ -230,20 +424,41 @@ fn run_flux(args: FluxArgs) -> Result<(), Box<dyn std::error::Error>> {
         rcdb,
         ccdb,
         exclude_runs,
+        diagnostics,
     } = config;
 
     let edges = uniform_edges(bins, min_edge, max_edge);
 
-    let histos = get_flux_histograms(
-        run_selection,
-        &edges,
-        coherent_peak,
-        polarized,
-        &rcdb,
-        &ccdb,
-        exclude_runs,
-    )?;
+    let histos = if diagnostics {
+        let (histos, diagnostics) = get_flux_histograms_with_diagnostics(
+            run_selection,
+            &edges,
+            coherent_peak,
+            polarized,
+            &rcdb,
+            &ccdb,
+            exclude_runs,
+        )?;
+        if output.json {
+            to_writer_pretty(std::io::stderr(), &diagnostics)?;
+            eprintln!();
+        } else {
+            eprint!("{diagnostics}");
+        }
+        histos
+    } else {
+        get_flux_histograms(
+            run_selection,
+            &edges,
+            coherent_peak,
+            polarized,
+            &rcdb,
+            &ccdb,
+            exclude_runs,
+        )?
+    };
 
     to_writer_pretty(std::io::stdout(), &histos)?;
+    println!();
     Ok(())
 }
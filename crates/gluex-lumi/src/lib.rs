@@ -1,16 +1,22 @@
 use chrono::{DateTime, TimeZone, Utc};
 use gluex_ccdb::{
-    context::Context as CCDBContext,
+    data::Data,
     prelude::{CCDBError, CCDB},
 };
 use gluex_core::{
     histograms::Histogram,
     run_periods::{resolve_rest_version, RestVersionError, RunPeriod},
+    run_set::RunContext,
     RestVersion, RunNumber,
 };
 use gluex_rcdb::prelude::{RCDBError, RCDB};
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, path::Path, str::FromStr};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt,
+    path::Path,
+    str::FromStr,
+};
 use thiserror::Error;
 
 pub mod cli;
@@ -21,7 +27,7 @@ pub const BERILLIUM_RADIATION_LENGTH_METERS: f64 = 35.28e-2;
 #[error("Unknown radiator: {0}")]
 pub struct ConverterParseError(String);
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Converter {
     Retracted,
     Unknown,
@@ -61,6 +67,15 @@ impl Converter {
 
 pub const TARGET_LENGTH_CM: f64 = 29.5;
 pub const AVOGADRO_CONSTANT: f64 = 6.02214076e23;
+const LIVETIME_TABLE: &str = "/PHOTON_BEAM/pair_spectrometer/lumi/trig_live";
+const PS_ACCEPT_TABLE: &str = "/PHOTON_BEAM/pair_spectrometer/lumi/PS_accept";
+const ENDPOINT_ENERGY_TABLE: &str = "/PHOTON_BEAM/endpoint_energy";
+const TAGM_TAGGED_TABLE: &str = "/PHOTON_BEAM/pair_spectrometer/lumi/tagm/tagged";
+const TAGM_SCALED_ENERGY_TABLE: &str = "/PHOTON_BEAM/microscope/scaled_energy_range";
+const TAGH_TAGGED_TABLE: &str = "/PHOTON_BEAM/pair_spectrometer/lumi/tagh/tagged";
+const TAGH_SCALED_ENERGY_TABLE: &str = "/PHOTON_BEAM/hodoscope/scaled_energy_range";
+const ENDPOINT_CALIB_TABLE: &str = "/PHOTON_BEAM/hodoscope/endpoint_calib";
+const TARGET_DENSITY_TABLE: &str = "/TARGET/density";
 const RP2019_11_OVERRIDE_START: RunNumber = 72436;
 fn rp2019_11_override_timestamp() -> DateTime<Utc> {
     Utc.with_ymd_and_hms(2021, 4, 23, 0, 0, 1).unwrap()
@@ -72,19 +87,259 @@ pub enum RestSelection {
     Version(RestVersion),
 }
 
-#[derive(Debug)]
+/// Error raised when a coherent-peak window override cannot be parsed.
+#[derive(Error, Debug)]
+#[error("Invalid coherent peak window '{0}' (expected <run_min>-<run_max>=<low>:<high>)")]
+pub struct CoherentPeakWindowParseError(String);
+
+/// Coherent-peak energy window (in GeV) applied to an inclusive range of runs.
+///
+/// Deserialized windows are checked like parsed ones: `run_min` may not exceed `run_max` and
+/// `low` must be below `high`.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "UncheckedCoherentPeakWindow")]
+pub struct CoherentPeakWindow {
+    /// First run (inclusive) to which the window applies.
+    pub run_min: RunNumber,
+    /// Last run (inclusive) to which the window applies.
+    pub run_max: RunNumber,
+    /// Lower edge of the coherent peak in GeV.
+    pub low: f64,
+    /// Upper edge of the coherent peak in GeV.
+    pub high: f64,
+}
+impl CoherentPeakWindow {
+    /// True when `run` lies within the window's run range.
+    #[must_use]
+    pub fn contains(&self, run: RunNumber) -> bool {
+        run >= self.run_min && run <= self.run_max
+    }
+}
+/// Fields of a [`CoherentPeakWindow`] as read from a configuration file, before validation.
+#[derive(Deserialize)]
+struct UncheckedCoherentPeakWindow {
+    run_min: RunNumber,
+    run_max: RunNumber,
+    low: f64,
+    high: f64,
+}
+impl TryFrom<UncheckedCoherentPeakWindow> for CoherentPeakWindow {
+    type Error = CoherentPeakWindowParseError;
+
+    fn try_from(window: UncheckedCoherentPeakWindow) -> Result<Self, Self::Error> {
+        let UncheckedCoherentPeakWindow {
+            run_min,
+            run_max,
+            low,
+            high,
+        } = window;
+        if run_min > run_max || low >= high || low.is_nan() || high.is_nan() {
+            return Err(CoherentPeakWindowParseError(format!(
+                "{run_min}-{run_max}={low}:{high}"
+            )));
+        }
+        Ok(Self {
+            run_min,
+            run_max,
+            low,
+            high,
+        })
+    }
+}
+impl FromStr for CoherentPeakWindow {
+    type Err = CoherentPeakWindowParseError;
+
+    /// Parses `<run_min>-<run_max>=<low>:<high>` or `<run>=<low>:<high>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || CoherentPeakWindowParseError(s.to_string());
+        let (runs, energies) = s.split_once('=').ok_or_else(err)?;
+        let (run_min, run_max) = match runs.split_once('-') {
+            Some((min, max)) => (min.trim().parse(), max.trim().parse()),
+            None => (runs.trim().parse(), runs.trim().parse()),
+        };
+        let (low, high) = energies.split_once(':').ok_or_else(err)?;
+        CoherentPeakWindow::try_from(UncheckedCoherentPeakWindow {
+            run_min: run_min.map_err(|_| err())?,
+            run_max: run_max.map_err(|_| err())?,
+            low: low.trim().parse().map_err(|_| err())?,
+            high: high.trim().parse().map_err(|_| err())?,
+        })
+        .map_err(|_| err())
+    }
+}
+
+/// Coherent-peak windows used to select photons, falling back to
+/// [`gluex_core::run_periods::coherent_peak`] for runs without an override.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CoherentPeakWindows {
+    overrides: Vec<CoherentPeakWindow>,
+}
+impl CoherentPeakWindows {
+    /// Builds a set of windows from the given overrides. Later entries take precedence over
+    /// earlier ones when their run ranges overlap.
+    pub fn new(overrides: impl IntoIterator<Item = CoherentPeakWindow>) -> Self {
+        Self {
+            overrides: overrides.into_iter().collect(),
+        }
+    }
+    /// The override windows in order of increasing precedence.
+    #[must_use]
+    pub fn overrides(&self) -> &[CoherentPeakWindow] {
+        &self.overrides
+    }
+    /// Returns the `(low, high)` coherent-peak window in GeV for the given run.
+    #[must_use]
+    pub fn window(&self, run: RunNumber) -> (f64, f64) {
+        self.overrides
+            .iter()
+            .rev()
+            .find(|window| window.contains(run))
+            .map_or_else(
+                || gluex_core::run_periods::coherent_peak(run),
+                |window| (window.low, window.high),
+            )
+    }
+}
+
+/// Whether (and how) photons are restricted to the coherent peak.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum CoherentPeakSelection {
+    /// All photon energies contribute.
+    #[default]
+    Disabled,
+    /// Only photons inside the per-run window contribute.
+    Enabled(CoherentPeakWindows),
+}
+impl From<bool> for CoherentPeakSelection {
+    fn from(enabled: bool) -> Self {
+        if enabled {
+            Self::Enabled(CoherentPeakWindows::default())
+        } else {
+            Self::Disabled
+        }
+    }
+}
+impl From<CoherentPeakWindows> for CoherentPeakSelection {
+    fn from(windows: CoherentPeakWindows) -> Self {
+        Self::Enabled(windows)
+    }
+}
+impl CoherentPeakSelection {
+    fn windows(&self) -> Option<&CoherentPeakWindows> {
+        match self {
+            Self::Disabled => None,
+            Self::Enabled(windows) => Some(windows),
+        }
+    }
+}
+
+/// Per-run calibration inputs needed to compute the tagged photon flux.
+///
+/// Each entry collects the RCDB and CCDB values for a single run so downstream tools can reuse
+/// them without repeating the database queries performed by [`get_flux_cache`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FluxCache {
+    /// Livetime ratio from `/PHOTON_BEAM/pair_spectrometer/lumi/trig_live` divided by the
+    /// converter thickness in units of `7/9` radiation lengths.
     pub livetime_scaling: f64,
+    /// Pair spectrometer acceptance parameters `(p0, p1, p2)` from
+    /// `/PHOTON_BEAM/pair_spectrometer/lumi/PS_accept`.
     pub pair_spectrometer_parameters: (f64, f64, f64),
+    /// Electron beam endpoint energy in GeV from `/PHOTON_BEAM/endpoint_energy`.
     pub photon_endpoint_energy: f64,
+    /// Microscope (TAGM) `(column, flux, error)` rows from
+    /// `/PHOTON_BEAM/pair_spectrometer/lumi/tagm/tagged`.
     pub tagm_tagged_flux: Vec<(f64, f64, f64)>,
+    /// Microscope (TAGM) `(low, high)` energy fractions from
+    /// `/PHOTON_BEAM/microscope/scaled_energy_range`.
     pub tagm_scaled_energy_range: Vec<(f64, f64)>,
+    /// Hodoscope (TAGH) `(counter, flux, error)` rows from
+    /// `/PHOTON_BEAM/pair_spectrometer/lumi/tagh/tagged`.
     pub tagh_tagged_flux: Vec<(f64, f64, f64)>,
+    /// Hodoscope (TAGH) `(low, high)` energy fractions from
+    /// `/PHOTON_BEAM/hodoscope/scaled_energy_range`.
     pub tagh_scaled_energy_range: Vec<(f64, f64)>,
+    /// Endpoint energy used when the tagger energies were calibrated, from
+    /// `/PHOTON_BEAM/hodoscope/endpoint_calib` (absent for older runs).
     pub photon_endpoint_calibration: Option<f64>,
+    /// Target scattering centers and their uncertainty in protons/barn, derived from
+    /// `/TARGET/density`.
     pub target_scattering_centers: (f64, f64),
 }
 
+/// Reason a run was dropped from the flux sum.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DropReason {
+    /// The run was listed in `exclude_runs`.
+    Excluded,
+    /// The run did not pass the named RCDB selection (e.g. `approved_production`).
+    RCDBSelection(String),
+    /// The named RCDB condition has no value for the run.
+    MissingCondition(String),
+    /// The run used a [`Converter`] with no known thickness (retracted or unknown).
+    UnusableConverter(Converter),
+    /// The CCDB table at the given path has no usable data for the run.
+    MissingTable(String),
+    /// No tagger channel fell inside the coherent-peak window.
+    OutsideCoherentPeak,
+    /// Every remaining tagger channel had zero pair-spectrometer acceptance.
+    ZeroAcceptance,
+    /// Every remaining tagger channel fell outside the requested histogram edges.
+    OutsideBinning,
+}
+impl fmt::Display for DropReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DropReason::Excluded => write!(f, "excluded by request"),
+            DropReason::RCDBSelection(name) => write!(f, "failed RCDB selection '{name}'"),
+            DropReason::MissingCondition(name) => write!(f, "missing RCDB condition '{name}'"),
+            DropReason::UnusableConverter(converter) => {
+                write!(
+                    f,
+                    "polarimeter converter {converter:?} has no known thickness"
+                )
+            }
+            DropReason::MissingTable(path) => write!(f, "missing CCDB table '{path}'"),
+            DropReason::OutsideCoherentPeak => write!(f, "no tagger channels in coherent peak"),
+            DropReason::ZeroAcceptance => write!(f, "zero pair-spectrometer acceptance"),
+            DropReason::OutsideBinning => write!(f, "no tagger channels inside histogram edges"),
+        }
+    }
+}
+
+/// Per-run account of which runs contributed to a flux calculation and why others did not.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FluxDiagnostics {
+    /// Runs that contributed to the flux sum.
+    pub included: BTreeSet<RunNumber>,
+    /// Runs that were dropped, mapped to every [`DropReason`] that applies to them.
+    pub dropped: BTreeMap<RunNumber, Vec<DropReason>>,
+}
+impl FluxDiagnostics {
+    fn drop_run(&mut self, run: RunNumber, reason: DropReason) {
+        self.dropped.entry(run).or_default().push(reason);
+    }
+}
+impl fmt::Display for FluxDiagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} runs included, {} runs dropped",
+            self.included.len(),
+            self.dropped.len()
+        )?;
+        for (run, reasons) in &self.dropped {
+            let reasons = reasons
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("; ");
+            writeln!(f, "  {run}: {reasons}")?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum GlueXLumiError {
     #[error("{0}")]
@@ -99,54 +354,128 @@ pub enum GlueXLumiError {
     RestVersionError(#[from] RestVersionError),
 }
 
-fn get_flux_cache(
+/// Collect the per-run flux inputs for a single run period.
+///
+/// # Arguments
+/// * `run_period` - [`RunPeriod`] whose approved production runs should be loaded.
+/// * `polarized` - Restricts the selection to runs with a coherent (polarized) beam when true.
+/// * `timestamp` - CCDB timestamp used for the REST-dependent energy calibrations.
+/// * `rcdb_path` - Filesystem path to the RCDB SQLite database (any type implementing
+///   `AsRef<Path>`).
+/// * `ccdb_path` - Filesystem path to the CCDB SQLite database (any type implementing
+///   `AsRef<Path>`).
+///
+/// # Returns
+/// [`HashMap`] from run number to [`FluxCache`] for every run with a complete set of inputs.
+pub fn get_flux_cache(
     run_period: RunPeriod,
     polarized: bool,
     timestamp: DateTime<Utc>,
     rcdb_path: impl AsRef<Path>,
     ccdb_path: impl AsRef<Path>,
 ) -> Result<HashMap<RunNumber, FluxCache>, GlueXLumiError> {
+    build_flux_cache(run_period, polarized, timestamp, rcdb_path, ccdb_path, None)
+}
+
+#[allow(clippy::too_many_lines)]
+fn build_flux_cache(
+    run_period: RunPeriod,
+    polarized: bool,
+    timestamp: DateTime<Utc>,
+    rcdb_path: impl AsRef<Path>,
+    ccdb_path: impl AsRef<Path>,
+    diagnostics: Option<&mut FluxDiagnostics>,
+) -> Result<HashMap<RunNumber, FluxCache>, GlueXLumiError> {
+    let check_selection = diagnostics.is_some();
+    let mut scratch = FluxDiagnostics::default();
+    let diagnostics = diagnostics.unwrap_or(&mut scratch);
     let rcdb = RCDB::open(rcdb_path)?;
-    let mut rcdb_filters = gluex_rcdb::conditions::aliases::approved_production(run_period);
+    let rcdb_context = gluex_rcdb::context::Context::default().with_period(run_period);
+    let production_filter = gluex_rcdb::conditions::aliases::approved_production(run_period);
+    let mut rcdb_filters = production_filter.clone();
     if polarized {
         rcdb_filters = gluex_rcdb::conditions::all([
             rcdb_filters,
             gluex_rcdb::conditions::aliases::is_coherent_beam(),
         ]);
     }
-    let polarimeter_converter: HashMap<RunNumber, Converter> = rcdb
-        .fetch(
-            ["polarimeter_converter"],
-            &gluex_rcdb::context::Context::default()
-                .with_run_range(run_period.min_run()..=run_period.max_run())
-                .filter(rcdb_filters),
-        )?
-        .into_iter()
-        .map(|(r, pc_map)| {
-            let mut converter = pc_map["polarimeter_converter"]
-                .as_string()
-                .unwrap()
-                .parse()?;
-            if !matches!(
-                converter,
-                Converter::Be75um | Converter::Be750um | Converter::Be50um,
-            ) && r > 10633
-                && r < 10694
+    if check_selection {
+        let production_runs: HashSet<RunNumber> = rcdb
+            .fetch_runs(&rcdb_context.clone().filter(production_filter))?
+            .into_iter()
+            .collect();
+        let coherent_runs: Option<HashSet<RunNumber>> = if polarized {
+            Some(
+                rcdb.fetch_runs(
+                    &rcdb_context
+                        .clone()
+                        .filter(gluex_rcdb::conditions::aliases::is_coherent_beam()),
+                )?
+                .into_iter()
+                .collect(),
+            )
+        } else {
+            None
+        };
+        for run in rcdb.fetch_runs(&rcdb_context)? {
+            if !production_runs.contains(&run) {
+                diagnostics.drop_run(
+                    run,
+                    DropReason::RCDBSelection("approved_production".to_string()),
+                );
+            }
+            if coherent_runs
+                .as_ref()
+                .is_some_and(|coherent_runs| !coherent_runs.contains(&run))
             {
-                converter = Converter::Be75um; // no converter in RCDB but 75um found in logbook
+                diagnostics.drop_run(
+                    run,
+                    DropReason::RCDBSelection("is_coherent_beam".to_string()),
+                );
             }
-            Ok((r, converter))
-        })
-        .collect::<Result<HashMap<RunNumber, Converter>, ConverterParseError>>()?;
+        }
+    }
+    let mut polarimeter_converter: HashMap<RunNumber, Converter> = HashMap::new();
+    for (r, pc_map) in rcdb.fetch(
+        ["polarimeter_converter"],
+        &rcdb_context.filter(rcdb_filters),
+    )? {
+        let Some(converter) = pc_map
+            .get("polarimeter_converter")
+            .and_then(|value| value.as_string())
+        else {
+            diagnostics.drop_run(
+                r,
+                DropReason::MissingCondition("polarimeter_converter".to_string()),
+            );
+            continue;
+        };
+        let mut converter: Converter = converter.parse()?;
+        if !matches!(
+            converter,
+            Converter::Be75um | Converter::Be750um | Converter::Be50um,
+        ) && r > 10633
+            && r < 10694
+        {
+            converter = Converter::Be75um; // no converter in RCDB but 75um found in logbook
+        }
+        polarimeter_converter.insert(r, converter);
+    }
     let ccdb = CCDB::open(ccdb_path)?;
-    let ccdb_context = gluex_ccdb::context::Context::default()
-        .with_run_range(run_period.min_run()..run_period.max_run());
+    let ccdb_context = gluex_ccdb::context::Context::default().with_period(run_period);
     let ccdb_context_restver = ccdb_context.clone().with_timestamp(timestamp);
-    let livetime_ratio: HashMap<RunNumber, f64> = ccdb
-        .fetch(
-            "/PHOTON_BEAM/pair_spectrometer/lumi/trig_live",
-            &ccdb_context,
-        )?
+    let mut tables = ccdb.fetch_many(
+        [
+            LIVETIME_TABLE,
+            PS_ACCEPT_TABLE,
+            TAGM_TAGGED_TABLE,
+            TAGH_TAGGED_TABLE,
+            TARGET_DENSITY_TABLE,
+        ],
+        &ccdb_context,
+    )?;
+    let mut rest_tables = ccdb.fetch_many(REST_DEPENDENT_TABLES, &ccdb_context_restver)?;
+    let livetime_ratio: HashMap<RunNumber, f64> = take_table(&mut tables, LIVETIME_TABLE)
         .into_iter()
         .filter_map(|(r, d)| {
             let livetime = d.column(1)?;
@@ -155,93 +484,355 @@ fn get_flux_cache(
             Some((r, if total > 0.0 { live / total } else { 1.0 }))
         })
         .collect::<HashMap<_, _>>();
-    let livetime_scaling: HashMap<RunNumber, f64> = polarimeter_converter
-        .into_iter()
-        .filter_map(|(r, c)| {
-            // See https://doi.org/10.1103/RevModPhys.46.815 Section IV parts B, C, and D
-            Some((
-                r,
-                livetime_ratio.get(&r).unwrap_or(&1.0) * 9.0 / (7.0 * c.radiation_lengths()?),
-            ))
-        })
-        .collect();
-    let pair_spectrometer_parameters = fetch_pair_spectrometer_parameters(&ccdb, &ccdb_context)?;
-    let mut photon_endpoint_energy = fetch_photon_endpoint_energy(&ccdb, &ccdb_context_restver)?;
-    let tagm_tagged_flux = fetch_tagm_tagged_flux(&ccdb, &ccdb_context)?;
+    let mut livetime_scaling: HashMap<RunNumber, f64> = HashMap::new();
+    for (r, c) in polarimeter_converter {
+        let Some(radiation_lengths) = c.radiation_lengths() else {
+            diagnostics.drop_run(r, DropReason::UnusableConverter(c));
+            continue;
+        };
+        // See https://doi.org/10.1103/RevModPhys.46.815 Section IV parts B, C, and D
+        livetime_scaling.insert(
+            r,
+            livetime_ratio.get(&r).unwrap_or(&1.0) * 9.0 / (7.0 * radiation_lengths),
+        );
+    }
+    let pair_spectrometer_parameters =
+        parse_pair_spectrometer_parameters(take_table(&mut tables, PS_ACCEPT_TABLE));
+    let mut photon_endpoint_energy =
+        parse_photon_endpoint_energy(take_table(&mut rest_tables, ENDPOINT_ENERGY_TABLE));
+    let tagm_tagged_flux = parse_tagged_flux(take_table(&mut tables, TAGM_TAGGED_TABLE));
     let mut tagm_scaled_energy_range =
-        fetch_tagm_scaled_energy_range(&ccdb, &ccdb_context_restver)?;
-    let tagh_tagged_flux = fetch_tagh_tagged_flux(&ccdb, &ccdb_context)?;
+        parse_scaled_energy_range(take_table(&mut rest_tables, TAGM_SCALED_ENERGY_TABLE));
+    let tagh_tagged_flux = parse_tagged_flux(take_table(&mut tables, TAGH_TAGGED_TABLE));
     let mut tagh_scaled_energy_range =
-        fetch_tagh_scaled_energy_range(&ccdb, &ccdb_context_restver)?;
+        parse_scaled_energy_range(take_table(&mut rest_tables, TAGH_SCALED_ENERGY_TABLE));
     let mut photon_endpoint_calibration =
-        fetch_photon_endpoint_calibration(&ccdb, &ccdb_context_restver)?;
+        parse_photon_endpoint_calibration(take_table(&mut rest_tables, ENDPOINT_CALIB_TABLE));
     // Density is in mg/cm^3, so to get the number of scattering centers, we multiply density by
     // the target length to get mg/cm^2, then we multiply by 1e-3 to get g/cm^2. We then multiply
     // by 1e-24 cm^2/barn to get g/barn, and finally by Avogadro's constant to get g/(mol * barn).
     // Finally, we divide by 1 g/mol (proton molar mass) to get protons/barn
     let factor = 1e-24 * AVOGADRO_CONSTANT * 1e-3 * TARGET_LENGTH_CM;
-    let target_scattering_centers: HashMap<RunNumber, (f64, f64)> = ccdb
-        .fetch("/TARGET/density", &ccdb_context)?
-        .into_iter()
-        .filter_map(|(r, d)| Some((r, (d.double(0, 0)? * factor, d.double(1, 0)? * factor))))
-        .collect();
+    let target_scattering_centers: HashMap<RunNumber, (f64, f64)> =
+        take_table(&mut tables, TARGET_DENSITY_TABLE)
+            .into_iter()
+            .filter_map(|(r, d)| Some((r, (d.double(0, 0)? * factor, d.double(1, 0)? * factor))))
+            .collect();
 
     if run_period == RunPeriod::RP2019_11 {
         let override_context = ccdb_context
             .clone()
             .with_timestamp(rp2019_11_override_timestamp());
+        let mut overrides = ccdb.fetch_many(REST_DEPENDENT_TABLES, &override_context)?;
         apply_run_override(
             &mut photon_endpoint_energy,
-            fetch_photon_endpoint_energy(&ccdb, &override_context)?,
+            parse_photon_endpoint_energy(take_table(&mut overrides, ENDPOINT_ENERGY_TABLE)),
             RP2019_11_OVERRIDE_START,
             run_period.max_run(),
         );
         apply_run_override(
             &mut tagm_scaled_energy_range,
-            fetch_tagm_scaled_energy_range(&ccdb, &override_context)?,
+            parse_scaled_energy_range(take_table(&mut overrides, TAGM_SCALED_ENERGY_TABLE)),
             RP2019_11_OVERRIDE_START,
             run_period.max_run(),
         );
         apply_run_override(
             &mut tagh_scaled_energy_range,
-            fetch_tagh_scaled_energy_range(&ccdb, &override_context)?,
+            parse_scaled_energy_range(take_table(&mut overrides, TAGH_SCALED_ENERGY_TABLE)),
             RP2019_11_OVERRIDE_START,
             run_period.max_run(),
         );
         apply_run_override(
             &mut photon_endpoint_calibration,
-            fetch_photon_endpoint_calibration(&ccdb, &override_context)?,
+            parse_photon_endpoint_calibration(take_table(&mut overrides, ENDPOINT_CALIB_TABLE)),
             RP2019_11_OVERRIDE_START,
             run_period.max_run(),
         );
     }
-    Ok(livetime_scaling
+    let mut cache = HashMap::new();
+    for (r, livetime_scaling) in livetime_scaling {
+        let pair_spectrometer_parameters = pair_spectrometer_parameters.get(&r).copied();
+        let photon_endpoint_energy = photon_endpoint_energy.get(&r).copied();
+        let tagm_tagged_flux = tagm_tagged_flux.get(&r);
+        let tagm_scaled_energy_range = tagm_scaled_energy_range.get(&r);
+        let tagh_tagged_flux = tagh_tagged_flux.get(&r);
+        let tagh_scaled_energy_range = tagh_scaled_energy_range.get(&r);
+        let target_scattering_centers = target_scattering_centers.get(&r).copied();
+        for (table, present) in [
+            (PS_ACCEPT_TABLE, pair_spectrometer_parameters.is_some()),
+            (ENDPOINT_ENERGY_TABLE, photon_endpoint_energy.is_some()),
+            (TAGM_TAGGED_TABLE, tagm_tagged_flux.is_some()),
+            (TAGM_SCALED_ENERGY_TABLE, tagm_scaled_energy_range.is_some()),
+            (TAGH_TAGGED_TABLE, tagh_tagged_flux.is_some()),
+            (TAGH_SCALED_ENERGY_TABLE, tagh_scaled_energy_range.is_some()),
+            (TARGET_DENSITY_TABLE, target_scattering_centers.is_some()),
+        ] {
+            if !present {
+                diagnostics.drop_run(r, DropReason::MissingTable(table.to_string()));
+            }
+        }
+        let (
+            Some(pair_spectrometer_parameters),
+            Some(photon_endpoint_energy),
+            Some(tagm_tagged_flux),
+            Some(tagm_scaled_energy_range),
+            Some(tagh_tagged_flux),
+            Some(tagh_scaled_energy_range),
+            Some(target_scattering_centers),
+        ) = (
+            pair_spectrometer_parameters,
+            photon_endpoint_energy,
+            tagm_tagged_flux,
+            tagm_scaled_energy_range,
+            tagh_tagged_flux,
+            tagh_scaled_energy_range,
+            target_scattering_centers,
+        )
+        else {
+            continue;
+        };
+        cache.insert(
+            r,
+            FluxCache {
+                livetime_scaling,
+                pair_spectrometer_parameters,
+                photon_endpoint_energy,
+                tagm_tagged_flux: tagm_tagged_flux.clone(),
+                tagm_scaled_energy_range: tagm_scaled_energy_range.clone(),
+                tagh_tagged_flux: tagh_tagged_flux.clone(),
+                tagh_scaled_energy_range: tagh_scaled_energy_range.clone(),
+                photon_endpoint_calibration: photon_endpoint_calibration.get(&r).copied(),
+                target_scattering_centers,
+            },
+        );
+    }
+    Ok(cache)
+}
+
+/// CCDB timestamp for the REST selection of a run period, warning on stderr when the requested
+/// REST version had to be replaced.
+fn rest_timestamp(
+    run_period: RunPeriod,
+    selection: RestSelection,
+) -> Result<DateTime<Utc>, RestVersionError> {
+    Ok(match selection {
+        RestSelection::Current => Utc::now(),
+        RestSelection::Version(rest_version) => {
+            let resolved = resolve_rest_version(run_period, rest_version)?;
+            if resolved.requested != resolved.used {
+                eprintln!(
+                    "Warning: REST ver{req:02} was not found for run period {} so ver{used:02} was used instead.",
+                    run_period.short_name(),
+                    req = resolved.requested,
+                    used = resolved.used
+                );
+            }
+            resolved.timestamp
+        }
+    })
+}
+
+/// CCDB tables every run needs for the flux calculation, and whether each is read at the REST
+/// timestamp. Runs missing any of them are dropped from the flux sum.
+const REQUIRED_TABLES: [(&str, bool); 7] = [
+    (PS_ACCEPT_TABLE, false),
+    (ENDPOINT_ENERGY_TABLE, true),
+    (TAGM_TAGGED_TABLE, false),
+    (TAGM_SCALED_ENERGY_TABLE, true),
+    (TAGH_TAGGED_TABLE, false),
+    (TAGH_SCALED_ENERGY_TABLE, true),
+    (TARGET_DENSITY_TABLE, false),
+];
+
+/// Tables whose constants are read at the REST version's timestamp.
+const REST_DEPENDENT_TABLES: [&str; 4] = [
+    ENDPOINT_ENERGY_TABLE,
+    TAGM_SCALED_ENERGY_TABLE,
+    TAGH_SCALED_ENERGY_TABLE,
+    ENDPOINT_CALIB_TABLE,
+];
+
+/// Coverage of one CCDB table over the runs of a [`CoverageReport`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableCoverage {
+    /// Approved runs with constants in the table.
+    pub covered: BTreeSet<RunNumber>,
+    /// Approved runs without constants in the table, which the flux calculation drops.
+    pub missing: BTreeSet<RunNumber>,
+    /// Runs recorded in RCDB that have constants in the table but fail the approved selection.
+    pub unapproved: BTreeSet<RunNumber>,
+}
+
+/// Cross-check of the RCDB run selection against the CCDB tables the flux calculation needs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CoverageReport {
+    /// Runs passing the `approved_production` (and, if requested, `is_coherent_beam`) selection.
+    pub approved: BTreeSet<RunNumber>,
+    /// Coverage of each required CCDB table, keyed by table path.
+    pub tables: BTreeMap<String, TableCoverage>,
+}
+impl CoverageReport {
+    /// Approved runs missing at least one required table.
+    #[must_use]
+    pub fn incomplete(&self) -> BTreeSet<RunNumber> {
+        self.tables
+            .values()
+            .flat_map(|coverage| coverage.missing.iter().copied())
+            .collect()
+    }
+
+    /// True when every approved run has constants in every required table.
+    #[must_use]
+    pub fn is_complete(&self) -> bool {
+        self.tables
+            .values()
+            .all(|coverage| coverage.missing.is_empty())
+    }
+}
+impl fmt::Display for CoverageReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{} approved runs, {} missing CCDB constants",
+            self.approved.len(),
+            self.incomplete().len()
+        )?;
+        for (table, coverage) in &self.tables {
+            writeln!(
+                f,
+                "  {table}: {}/{} approved runs covered",
+                coverage.covered.len(),
+                self.approved.len()
+            )?;
+            if !coverage.missing.is_empty() {
+                writeln!(f, "    missing: {}", format_runs(&coverage.missing))?;
+            }
+            if !coverage.unapproved.is_empty() {
+                writeln!(f, "    unapproved: {}", format_runs(&coverage.unapproved))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Formats runs as comma-separated numbers and inclusive ranges (`10-12, 15`).
+fn format_runs(runs: &BTreeSet<RunNumber>) -> String {
+    let mut ranges: Vec<(RunNumber, RunNumber)> = Vec::new();
+    for &run in runs {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == run => *end = run,
+            _ => ranges.push((run, run)),
+        }
+    }
+    ranges
         .into_iter()
-        .filter_map(|(r, livetime_scaling)| {
-            let pair_spectrometer_parameters = *pair_spectrometer_parameters.get(&r)?;
-            let photon_endpoint_energy = *photon_endpoint_energy.get(&r)?;
-            let tagm_tagged_flux = tagm_tagged_flux.get(&r)?.to_vec();
-            let tagm_scaled_energy_range = tagm_scaled_energy_range.get(&r)?.to_vec();
-            let tagh_tagged_flux = tagh_tagged_flux.get(&r)?.to_vec();
-            let tagh_scaled_energy_range = tagh_scaled_energy_range.get(&r)?.to_vec();
-            let photon_endpoint_calibration = photon_endpoint_calibration.get(&r).copied();
-            let target_scattering_centers = *target_scattering_centers.get(&r)?;
-            Some((
-                r,
-                FluxCache {
-                    livetime_scaling,
-                    pair_spectrometer_parameters,
-                    photon_endpoint_energy,
-                    tagm_tagged_flux,
-                    tagm_scaled_energy_range,
-                    tagh_tagged_flux,
-                    tagh_scaled_energy_range,
-                    photon_endpoint_calibration,
-                    target_scattering_centers,
-                },
-            ))
+        .map(|(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{start}-{end}")
+            }
         })
-        .collect())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Cross-check RCDB and CCDB for the runs the flux calculation would use.
+///
+/// For every run period, the runs passing the same RCDB selection as [`get_flux_histograms`] are
+/// compared with the runs that have constants in each CCDB table the calculation requires
+/// (endpoint energy, pair-spectrometer acceptance, tagger fluxes and energies, target density).
+/// Approved runs without constants are exactly the runs the flux sum silently loses; runs with
+/// constants that fail the selection may point at a missing RCDB flag instead.
+///
+/// # Arguments
+/// See [`get_flux_histograms`].
+///
+/// # Returns
+/// A [`CoverageReport`] over every requested run period.
+pub fn get_coverage_report(
+    run_period_selection: HashMap<RunPeriod, RestSelection>,
+    polarized: bool,
+    rcdb_path: impl AsRef<Path>,
+    ccdb_path: impl AsRef<Path>,
+) -> Result<CoverageReport, GlueXLumiError> {
+    let rcdb = RCDB::open(rcdb_path)?;
+    let ccdb = CCDB::open(ccdb_path)?;
+    let mut run_periods: Vec<(RunPeriod, RestSelection)> =
+        run_period_selection.into_iter().collect();
+    run_periods.sort_unstable_by_key(|(rp, _)| *rp);
+    let mut report = CoverageReport::default();
+    for (rp, selection) in run_periods {
+        let timestamp = rest_timestamp(rp, selection)?;
+        let rcdb_context = gluex_rcdb::context::Context::default().with_period(rp);
+        let mut filter = gluex_rcdb::conditions::aliases::approved_production(rp);
+        if polarized {
+            filter = gluex_rcdb::conditions::all([
+                filter,
+                gluex_rcdb::conditions::aliases::is_coherent_beam(),
+            ]);
+        }
+        let recorded: BTreeSet<RunNumber> = rcdb.fetch_runs(&rcdb_context)?.into_iter().collect();
+        let approved: BTreeSet<RunNumber> = rcdb
+            .fetch_runs(&rcdb_context.filter(filter))?
+            .into_iter()
+            .collect();
+        let ccdb_context = gluex_ccdb::context::Context::default().with_period(rp);
+        let required = |rest: bool| {
+            REQUIRED_TABLES
+                .iter()
+                .filter(move |(_, rest_dependent)| *rest_dependent == rest)
+                .map(|(table, _)| *table)
+        };
+        let mut tables = ccdb.fetch_many(required(false), &ccdb_context)?;
+        let mut rest_tables = ccdb.fetch_many(
+            required(true),
+            &ccdb_context.clone().with_timestamp(timestamp),
+        )?;
+        let mut overrides = if rp == RunPeriod::RP2019_11 {
+            ccdb.fetch_many(
+                required(true),
+                &ccdb_context
+                    .clone()
+                    .with_timestamp(rp2019_11_override_timestamp()),
+            )?
+        } else {
+            BTreeMap::new()
+        };
+        for (table, rest_dependent) in REQUIRED_TABLES {
+            let mut present: BTreeSet<RunNumber> = if rest_dependent {
+                take_table(&mut rest_tables, table)
+            } else {
+                take_table(&mut tables, table)
+            }
+            .into_keys()
+            .collect();
+            if rest_dependent && rp == RunPeriod::RP2019_11 {
+                // mirror the calibration override applied by the flux calculation
+                present.retain(|run| *run < RP2019_11_OVERRIDE_START);
+                present.extend(
+                    take_table(&mut overrides, table)
+                        .into_keys()
+                        .filter(|run| *run >= RP2019_11_OVERRIDE_START),
+                );
+            }
+            let coverage = report.tables.entry(table.to_string()).or_default();
+            for &run in &approved {
+                if present.contains(&run) {
+                    coverage.covered.insert(run);
+                } else {
+                    coverage.missing.insert(run);
+                }
+            }
+            coverage.unapproved.extend(
+                present
+                    .iter()
+                    .filter(|run| recorded.contains(run) && !approved.contains(run)),
+            );
+        }
+        report.approved.extend(approved);
+    }
+    Ok(report)
 }
 
 /// Photon flux and luminosity histograms aggregated across TAGM and TAGH detectors.
@@ -268,76 +859,34 @@ fn pair_spectrometer_acceptance(x: f64, args: (f64, f64, f64)) -> f64 {
     0.0
 }
 
-fn fetch_pair_spectrometer_parameters(
-    ccdb: &CCDB,
-    context: &CCDBContext,
-) -> Result<HashMap<RunNumber, (f64, f64, f64)>, CCDBError> {
-    Ok(ccdb
-        .fetch("/PHOTON_BEAM/pair_spectrometer/lumi/PS_accept", context)?
-        .into_iter()
+/// Removes the constants of `path` from a [`CCDB::fetch_many`] result.
+fn take_table(
+    tables: &mut BTreeMap<String, BTreeMap<RunNumber, Data>>,
+    path: &str,
+) -> BTreeMap<RunNumber, Data> {
+    tables.remove(path).unwrap_or_default()
+}
+
+fn parse_pair_spectrometer_parameters(
+    data: BTreeMap<RunNumber, Data>,
+) -> HashMap<RunNumber, (f64, f64, f64)> {
+    data.into_iter()
         .filter_map(|(r, d)| {
             let row = d.row(0).ok()?;
             Some((r, (row.double(0)?, row.double(1)?, row.double(2)?)))
         })
-        .collect())
+        .collect()
 }
 
-fn fetch_photon_endpoint_energy(
-    ccdb: &CCDB,
-    context: &CCDBContext,
-) -> Result<HashMap<RunNumber, f64>, CCDBError> {
-    Ok(ccdb
-        .fetch("/PHOTON_BEAM/endpoint_energy", context)?
-        .into_iter()
+fn parse_photon_endpoint_energy(data: BTreeMap<RunNumber, Data>) -> HashMap<RunNumber, f64> {
+    data.into_iter()
         .filter_map(|(r, d)| Some((r, d.value(0, 0)?.as_double()?)))
-        .collect())
-}
-
-#[allow(clippy::type_complexity)]
-fn fetch_tagm_tagged_flux(
-    ccdb: &CCDB,
-    context: &CCDBContext,
-) -> Result<HashMap<RunNumber, Vec<(f64, f64, f64)>>, CCDBError> {
-    Ok(ccdb
-        .fetch("/PHOTON_BEAM/pair_spectrometer/lumi/tagm/tagged", context)?
-        .into_iter()
-        .map(|(r, d)| {
-            (
-                r,
-                d.iter_rows()
-                    .filter_map(|row| Some((row.double(0)?, row.double(1)?, row.double(2)?)))
-                    .collect::<Vec<_>>(),
-            )
-        })
-        .collect())
+        .collect()
 }
 
-fn fetch_tagm_scaled_energy_range(
-    ccdb: &CCDB,
-    context: &CCDBContext,
-) -> Result<HashMap<RunNumber, Vec<(f64, f64)>>, CCDBError> {
-    Ok(ccdb
-        .fetch("/PHOTON_BEAM/microscope/scaled_energy_range", context)?
-        .into_iter()
-        .map(|(r, d)| {
-            (
-                r,
-                d.iter_rows()
-                    .filter_map(|row| Some((row.double(1)?, row.double(2)?)))
-                    .collect::<Vec<_>>(),
-            )
-        })
-        .collect())
-}
-
-#[allow(clippy::type_complexity)]
-fn fetch_tagh_tagged_flux(
-    ccdb: &CCDB,
-    context: &CCDBContext,
-) -> Result<HashMap<RunNumber, Vec<(f64, f64, f64)>>, CCDBError> {
-    Ok(ccdb
-        .fetch("/PHOTON_BEAM/pair_spectrometer/lumi/tagh/tagged", context)?
-        .into_iter()
+/// Reads the TAGM or TAGH tagged-flux tables.
+fn parse_tagged_flux(data: BTreeMap<RunNumber, Data>) -> HashMap<RunNumber, Vec<(f64, f64, f64)>> {
+    data.into_iter()
         .map(|(r, d)| {
             (
                 r,
@@ -346,16 +895,14 @@ fn fetch_tagh_tagged_flux(
                     .collect::<Vec<_>>(),
             )
         })
-        .collect())
+        .collect()
 }
 
-fn fetch_tagh_scaled_energy_range(
-    ccdb: &CCDB,
-    context: &CCDBContext,
-) -> Result<HashMap<RunNumber, Vec<(f64, f64)>>, CCDBError> {
-    Ok(ccdb
-        .fetch("/PHOTON_BEAM/hodoscope/scaled_energy_range", context)?
-        .into_iter()
+/// Reads the TAGM or TAGH scaled energy range tables.
+fn parse_scaled_energy_range(
+    data: BTreeMap<RunNumber, Data>,
+) -> HashMap<RunNumber, Vec<(f64, f64)>> {
+    data.into_iter()
         .map(|(r, d)| {
             (
                 r,
@@ -364,18 +911,13 @@ fn fetch_tagh_scaled_energy_range(
                     .collect::<Vec<_>>(),
             )
         })
-        .collect())
+        .collect()
 }
 
-fn fetch_photon_endpoint_calibration(
-    ccdb: &CCDB,
-    context: &CCDBContext,
-) -> Result<HashMap<RunNumber, f64>, CCDBError> {
-    Ok(ccdb
-        .fetch("/PHOTON_BEAM/hodoscope/endpoint_calib", context)?
-        .into_iter()
+fn parse_photon_endpoint_calibration(data: BTreeMap<RunNumber, Data>) -> HashMap<RunNumber, f64> {
+    data.into_iter()
         .filter_map(|(r, d)| Some((r, d.double(0, 0)?)))
-        .collect())
+        .collect()
 }
 
 fn apply_run_override<T>(
@@ -397,7 +939,8 @@ fn apply_run_override<T>(
 /// * `run_period_selection` - [`HashMap`] mapping [`RunPeriod`] values to [`RestSelection`] entries
 ///   that define the timestamp to use.
 /// * `edges` - Photon-energy bin edges used to construct output [`Histogram`]s.
-/// * `coherent_peak` - When true, only photons inside the per-run coherent peak contribute.
+/// * `coherent_peak` - When true (or given [`CoherentPeakWindows`]), only photons inside the
+///   per-run coherent peak contribute. See [`CoherentPeakSelection`].
 /// * `polarized` - Selects the polarized-flux calibration set when true.
 /// * `rcdb_path` - Filesystem path to the RCDB SQLite database (any type implementing
 ///   `AsRef<Path>`).
@@ -410,12 +953,80 @@ fn apply_run_override<T>(
 pub fn get_flux_histograms(
     run_period_selection: HashMap<RunPeriod, RestSelection>,
     edges: &[f64],
-    coherent_peak: bool,
+    coherent_peak: impl Into<CoherentPeakSelection>,
+    polarized: bool,
+    rcdb_path: impl AsRef<Path>,
+    ccdb_path: impl AsRef<Path>,
+    exclude_runs: Option<Vec<RunNumber>>,
+) -> Result<FluxHistograms, GlueXLumiError> {
+    compute_flux_histograms(
+        run_period_selection,
+        edges,
+        coherent_peak,
+        polarized,
+        rcdb_path,
+        ccdb_path,
+        exclude_runs,
+        None,
+    )
+}
+
+/// Construct tagged photon-flux and luminosity histograms along with a per-run account of which
+/// runs were dropped from the sum and why.
+///
+/// This performs a few additional RCDB queries to attribute runs which fail the production (and,
+/// if requested, coherent-beam) selections.
+///
+/// # Arguments
+/// See [`get_flux_histograms`].
+///
+/// # Returns
+/// [`FluxHistograms`] as returned by [`get_flux_histograms`] and the corresponding
+/// [`FluxDiagnostics`].
+pub fn get_flux_histograms_with_diagnostics(
+    run_period_selection: HashMap<RunPeriod, RestSelection>,
+    edges: &[f64],
+    coherent_peak: impl Into<CoherentPeakSelection>,
     polarized: bool,
     rcdb_path: impl AsRef<Path>,
     ccdb_path: impl AsRef<Path>,
     exclude_runs: Option<Vec<RunNumber>>,
+) -> Result<(FluxHistograms, FluxDiagnostics), GlueXLumiError> {
+    let mut diagnostics = FluxDiagnostics::default();
+    let histograms = compute_flux_histograms(
+        run_period_selection,
+        edges,
+        coherent_peak,
+        polarized,
+        rcdb_path,
+        ccdb_path,
+        exclude_runs,
+        Some(&mut diagnostics),
+    )?;
+    Ok((histograms, diagnostics))
+}
+
+/// Number of tagger channels for a run that survived each stage of the flux selection.
+#[derive(Default)]
+struct ChannelTally {
+    in_peak: usize,
+    accepted: usize,
+    binned: usize,
+}
+
+#[allow(clippy::too_many_arguments)]
+fn compute_flux_histograms(
+    run_period_selection: HashMap<RunPeriod, RestSelection>,
+    edges: &[f64],
+    coherent_peak: impl Into<CoherentPeakSelection>,
+    polarized: bool,
+    rcdb_path: impl AsRef<Path>,
+    ccdb_path: impl AsRef<Path>,
+    exclude_runs: Option<Vec<RunNumber>>,
+    mut diagnostics: Option<&mut FluxDiagnostics>,
 ) -> Result<FluxHistograms, GlueXLumiError> {
+    let coherent_peak = coherent_peak.into();
+    let coherent_peak_windows = coherent_peak.windows();
     let mut cache: HashMap<RunNumber, FluxCache> = HashMap::new();
     let mut tagged_flux_hist = Histogram::empty(edges);
     let mut tagm_flux_hist = Histogram::empty(edges);
@@ -430,35 +1041,27 @@ pub fn get_flux_histograms(
         .iter()
         .flat_map(|(rp, _)| rp.min_run()..=rp.max_run())
         .collect();
-    let run_numbers = if let Some(exclude_runs) = exclude_runs {
-        run_numbers
-            .into_iter()
-            .filter(|run| !exclude_runs.contains(run))
-            .collect()
-    } else {
-        run_numbers
-    };
+    let exclude_runs: HashSet<RunNumber> = exclude_runs.unwrap_or_default().into_iter().collect();
     for (rp, selection) in run_periods.iter() {
-        let timestamp = match selection {
-            RestSelection::Current => Utc::now(),
-            RestSelection::Version(rest_version) => {
-                let resolved = resolve_rest_version(*rp, *rest_version)?;
-                if resolved.requested != resolved.used {
-                    eprintln!(
-                        "Warning: REST ver{req:02} was not found for run period {} so ver{used:02} was used instead.",
-                        rp.short_name(),
-                        req = resolved.requested,
-                        used = resolved.used
-                    );
-                }
-                resolved.timestamp
-            }
-        };
-        cache.extend(get_flux_cache(
-            *rp, polarized, timestamp, &rcdb_path, &ccdb_path,
+        let timestamp = rest_timestamp(*rp, *selection)?;
+        cache.extend(build_flux_cache(
+            *rp,
+            polarized,
+            timestamp,
+            &rcdb_path,
+            &ccdb_path,
+            diagnostics.as_deref_mut(),
         )?);
     }
     for run in run_numbers {
+        if exclude_runs.contains(&run) {
+            if let Some(diagnostics) = diagnostics.as_deref_mut() {
+                if cache.contains_key(&run) || diagnostics.dropped.contains_key(&run) {
+                    diagnostics.drop_run(run, DropReason::Excluded);
+                }
+            }
+            continue;
+        }
         if let Some(data) = cache.get(&run) {
             let delta_e = match data.photon_endpoint_calibration {
                 Some(calibration) => data.photon_endpoint_energy - calibration,
@@ -467,6 +1070,7 @@ pub fn get_flux_histograms(
                 }
                 None => 0.0,
             };
+            let mut tally = ChannelTally::default();
             // Fill microscope
             for (tagged_flux, e_range) in data
                 .tagm_tagged_flux
@@ -475,19 +1079,21 @@ pub fn get_flux_histograms(
             {
                 let energy = data.photon_endpoint_energy * (e_range.0 + e_range.1) * 0.5 + delta_e;
 
-                if coherent_peak {
-                    let (coherent_peak_low, coherent_peak_high) =
-                        gluex_core::run_periods::coherent_peak(run);
+                if let Some(windows) = coherent_peak_windows {
+                    let (coherent_peak_low, coherent_peak_high) = windows.window(run);
                     if energy < coherent_peak_low || energy > coherent_peak_high {
                         continue;
                     }
                 }
+                tally.in_peak += 1;
                 let acceptance =
                     pair_spectrometer_acceptance(energy, data.pair_spectrometer_parameters);
                 if acceptance <= 0.0 {
                     continue;
                 }
+                tally.accepted += 1;
                 if let Some(ibin) = tagged_flux_hist.get_index(energy) {
+                    tally.binned += 1;
                     let count = tagged_flux.1 * data.livetime_scaling / acceptance;
                     let error = tagged_flux.2 * data.livetime_scaling / acceptance;
                     tagged_flux_hist.counts[ibin] += count;
@@ -504,19 +1110,21 @@ pub fn get_flux_histograms(
             {
                 let energy = data.photon_endpoint_energy * (e_range.0 + e_range.1) * 0.5 + delta_e;
 
-                if coherent_peak {
-                    let (coherent_peak_low, coherent_peak_high) =
-                        gluex_core::run_periods::coherent_peak(run);
+                if let Some(windows) = coherent_peak_windows {
+                    let (coherent_peak_low, coherent_peak_high) = windows.window(run);
                     if energy < coherent_peak_low || energy > coherent_peak_high {
                         continue;
                     }
                 }
+                tally.in_peak += 1;
                 let acceptance =
                     pair_spectrometer_acceptance(energy, data.pair_spectrometer_parameters);
                 if acceptance <= 0.0 {
                     continue;
                 }
+                tally.accepted += 1;
                 if let Some(ibin) = tagged_flux_hist.get_index(energy) {
+                    tally.binned += 1;
                     let count = tagged_flux.1 * data.livetime_scaling / acceptance;
                     let error = tagged_flux.2 * data.livetime_scaling / acceptance;
                     tagged_flux_hist.counts[ibin] += count;
@@ -525,6 +1133,17 @@ pub fn get_flux_histograms(
                     tagh_flux_hist.errors[ibin] = tagh_flux_hist.errors[ibin].hypot(error);
                 }
             }
+            if let Some(diagnostics) = diagnostics.as_deref_mut() {
+                if coherent_peak_windows.is_some() && tally.in_peak == 0 {
+                    diagnostics.drop_run(run, DropReason::OutsideCoherentPeak);
+                } else if tally.accepted == 0 {
+                    diagnostics.drop_run(run, DropReason::ZeroAcceptance);
+                } else if tally.binned == 0 {
+                    diagnostics.drop_run(run, DropReason::OutsideBinning);
+                } else {
+                    diagnostics.included.insert(run);
+                }
+            }
             let (n_scattering_centers, n_scattering_centers_error) = data.target_scattering_centers;
             for ibin in 0..tagged_flux_hist.bins() {
                 let count = tagged_flux_hist.counts[ibin];
@@ -0,0 +1,76 @@
+use gluex_lumi::{CoherentPeakWindow, CoherentPeakWindows};
+
+fn window(run_min: i64, run_max: i64, low: f64, high: f64) -> CoherentPeakWindow {
+    CoherentPeakWindow {
+        run_min,
+        run_max,
+        low,
+        high,
+    }
+}
+
+#[test]
+fn windows_parse_from_run_ranges_and_single_runs() {
+    assert_eq!(
+        "30274-30300=8.2:8.8".parse::<CoherentPeakWindow>().unwrap(),
+        window(30274, 30300, 8.2, 8.8)
+    );
+    assert_eq!(
+        " 72436 = 8.0 : 8.6 ".parse::<CoherentPeakWindow>().unwrap(),
+        window(72436, 72436, 8.0, 8.6)
+    );
+    for bad in [
+        "30274-30300",
+        "30274-30300=8.2",
+        "a-30300=8.2:8.8",
+        "30300-30274=8.2:8.8",
+        "30274=8.8:8.2",
+        "30274=8.2:8.2",
+        "30274=nan:8.8",
+    ] {
+        assert!(bad.parse::<CoherentPeakWindow>().is_err(), "{bad}");
+    }
+}
+
+#[test]
+fn deserialized_windows_are_validated() {
+    let windows: Vec<CoherentPeakWindow> =
+        serde_json::from_str(r#"[{"run_min": 30274, "run_max": 30300, "low": 8.2, "high": 8.8}]"#)
+            .unwrap();
+    assert_eq!(windows, [window(30274, 30300, 8.2, 8.8)]);
+    for bad in [
+        r#"{"run_min": 30300, "run_max": 30274, "low": 8.2, "high": 8.8}"#,
+        r#"{"run_min": 30274, "run_max": 30300, "low": 8.8, "high": 8.2}"#,
+    ] {
+        assert!(
+            serde_json::from_str::<CoherentPeakWindow>(bad).is_err(),
+            "{bad}"
+        );
+    }
+    let round_trip = serde_json::to_string(&windows[0]).unwrap();
+    assert_eq!(
+        serde_json::from_str::<CoherentPeakWindow>(&round_trip).unwrap(),
+        windows[0]
+    );
+}
+
+#[test]
+fn later_overrides_take_precedence() {
+    let windows = CoherentPeakWindows::new([
+        window(30000, 30999, 8.1, 8.7),
+        window(30500, 30599, 8.3, 8.9),
+    ]);
+    assert_eq!(windows.window(30100), (8.1, 8.7));
+    assert_eq!(windows.window(30550), (8.3, 8.9));
+    assert_eq!(windows.window(30999), (8.1, 8.7));
+    assert_eq!(
+        windows.window(31000),
+        gluex_core::run_periods::coherent_peak(31000)
+    );
+    let reversed = CoherentPeakWindows::new(windows.overrides().iter().rev().copied());
+    assert_eq!(reversed.window(30550), (8.1, 8.7));
+    assert_eq!(
+        CoherentPeakWindows::default().window(30550),
+        gluex_core::run_periods::coherent_peak(30550)
+    );
+}
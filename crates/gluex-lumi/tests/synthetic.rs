@@ -0,0 +1,175 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use gluex_core::run_periods::RunPeriod;
+use gluex_lumi::{get_flux_histograms_with_diagnostics, Converter, DropReason, RestSelection};
+use gluex_testdata::{
+    ccdb::{Assignment, CcdbBuilder},
+    rcdb::RcdbBuilder,
+};
+
+const TARGET_DENSITY_TABLE: &str = "/TARGET/density";
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "gluex-lumi-synthetic-{}-{name}.sqlite",
+        std::process::id()
+    ))
+}
+
+/// RCDB with four Spring 2017 runs: 30001-30003 pass the production selection (30002 with a
+/// retracted converter) and 30004 is not approved.
+fn synthetic_rcdb(name: &str) -> PathBuf {
+    let path = temp_path(name);
+    let _ = std::fs::remove_file(&path);
+    let mut builder = RcdbBuilder::new();
+    for run in 30001..=30004 {
+        builder = builder
+            .condition(run, "run_type", "hd_all.tsg")
+            .condition(run, "beam_current", 100.0)
+            .condition(run, "event_count", 1_000_000)
+            .condition(run, "solenoid_current", 1200.0)
+            .condition(run, "collimator_diameter", "5.0mm hole")
+            .condition(run, "status", if run == 30004 { 0 } else { 1 })
+            .condition(
+                run,
+                "polarimeter_converter",
+                if run == 30002 { "Retracted" } else { "Be 75um" },
+            );
+    }
+    builder
+        .write(&path)
+        .expect("failed to write synthetic RCDB");
+    path
+}
+
+/// CCDB with one tagger channel per detector at 7.8 GeV for runs 30001-30004, but no target
+/// density for run 30003.
+fn synthetic_ccdb(name: &str) -> PathBuf {
+    let path = temp_path(name);
+    let _ = std::fs::remove_file(&path);
+    let tagged = "/PHOTON_BEAM/pair_spectrometer/lumi";
+    let mut builder = CcdbBuilder::new()
+        .table(
+            &format!("{tagged}/trig_live"),
+            4,
+            &[("id", "int"), ("value", "double")],
+        )
+        .table(
+            &format!("{tagged}/PS_accept"),
+            1,
+            &[("p0", "double"), ("p1", "double"), ("p2", "double")],
+        )
+        .table("/PHOTON_BEAM/endpoint_energy", 1, &[("energy", "double")])
+        .table(
+            "/PHOTON_BEAM/hodoscope/endpoint_calib",
+            1,
+            &[("energy", "double")],
+        )
+        .table(
+            TARGET_DENSITY_TABLE,
+            1,
+            &[("density", "double"), ("error", "double")],
+        );
+    for detector in ["tagm", "tagh"] {
+        builder = builder.table(
+            &format!("{tagged}/{detector}/tagged"),
+            1,
+            &[
+                ("channel", "double"),
+                ("flux", "double"),
+                ("error", "double"),
+            ],
+        );
+    }
+    for detector in ["microscope", "hodoscope"] {
+        builder = builder.table(
+            &format!("/PHOTON_BEAM/{detector}/scaled_energy_range"),
+            1,
+            &[("channel", "int"), ("low", "double"), ("high", "double")],
+        );
+    }
+    builder
+        .assign(Assignment::new(
+            &format!("{tagged}/trig_live"),
+            30001..=30004,
+            [[0.0, 90.0], [1.0, 0.0], [2.0, 0.0], [3.0, 100.0]],
+        ))
+        .assign(Assignment::new(
+            &format!("{tagged}/PS_accept"),
+            30001..=30004,
+            [[1.0, 1.0, 10.0]],
+        ))
+        .assign(Assignment::new(
+            "/PHOTON_BEAM/endpoint_energy",
+            30001..=30004,
+            [[12.0]],
+        ))
+        .assign(Assignment::new(
+            &format!("{tagged}/tagm/tagged"),
+            30001..=30004,
+            [[1.0, 1000.0, 10.0]],
+        ))
+        .assign(Assignment::new(
+            &format!("{tagged}/tagh/tagged"),
+            30001..=30004,
+            [[1.0, 2000.0, 20.0]],
+        ))
+        .assign(Assignment::new(
+            "/PHOTON_BEAM/microscope/scaled_energy_range",
+            30001..=30004,
+            [[1.0, 0.6, 0.7]],
+        ))
+        .assign(Assignment::new(
+            "/PHOTON_BEAM/hodoscope/scaled_energy_range",
+            30001..=30004,
+            [[1.0, 0.6, 0.7]],
+        ))
+        .assign(Assignment::new(
+            TARGET_DENSITY_TABLE,
+            30001..=30002,
+            [[70.0, 0.1]],
+        ))
+        .assign(Assignment::new(
+            TARGET_DENSITY_TABLE,
+            30004..=30004,
+            [[70.0, 0.1]],
+        ))
+        .write(&path)
+        .expect("failed to write synthetic CCDB");
+    path
+}
+
+#[test]
+fn synthetic_dropped_runs_are_attributed() {
+    let rcdb = synthetic_rcdb("drop_reasons_rcdb");
+    let ccdb = synthetic_ccdb("drop_reasons_ccdb");
+    let (histograms, diagnostics) = get_flux_histograms_with_diagnostics(
+        HashMap::from([(RunPeriod::RP2017_01, RestSelection::Current)]),
+        &[7.0, 8.0, 9.0],
+        false,
+        false,
+        &rcdb,
+        &ccdb,
+        None,
+    )
+    .expect("flux calculation failed");
+    assert_eq!(
+        diagnostics.included.iter().copied().collect::<Vec<_>>(),
+        [30001]
+    );
+    assert_eq!(
+        diagnostics.dropped[&30002],
+        [DropReason::UnusableConverter(Converter::Retracted)]
+    );
+    assert_eq!(
+        diagnostics.dropped[&30003],
+        [DropReason::MissingTable(TARGET_DENSITY_TABLE.to_string())]
+    );
+    assert_eq!(
+        diagnostics.dropped[&30004],
+        [DropReason::RCDBSelection("approved_production".to_string())]
+    );
+    assert_eq!(diagnostics.dropped.len(), 3);
+    assert!(histograms.tagged_flux.counts[0] > 0.0);
+    assert!(histograms.tagged_flux.counts[1].abs() < f64::EPSILON);
+}
@@ -0,0 +1,69 @@
+"""Typed interface for the unified gluex Python bindings."""
+
+from __future__ import annotations
+
+import datetime
+from types import ModuleType
+
+ccdb: ModuleType
+rcdb: ModuleType
+lumi: ModuleType
+
+__version__: str
+
+class RunPeriod:
+    RP2016_02: RunPeriod
+    RP2017_01: RunPeriod
+    RP2018_01: RunPeriod
+    RP2018_08: RunPeriod
+    RP2019_01: RunPeriod
+    RP2019_11: RunPeriod
+    RP2021_08: RunPeriod
+    RP2021_11: RunPeriod
+    RP2022_05: RunPeriod
+    RP2022_08: RunPeriod
+    RP2023_01: RunPeriod
+    RP2025_01: RunPeriod
+
+    @staticmethod
+    def from_run(run: int) -> RunPeriod: ...
+    @staticmethod
+    def from_name(name: str) -> RunPeriod: ...
+    @property
+    def min_run(self) -> int: ...
+    @property
+    def max_run(self) -> int: ...
+    @property
+    def short_name(self) -> str: ...
+    def contains(self, run: int) -> bool: ...
+    def coherent_peak(self) -> tuple[float, float]: ...
+    def rest_versions(self) -> dict[int, datetime.datetime]: ...
+    def __int__(self) -> int: ...
+    def __lt__(self, other: RunPeriod) -> bool: ...
+    def __le__(self, other: RunPeriod) -> bool: ...
+    def __gt__(self, other: RunPeriod) -> bool: ...
+    def __ge__(self, other: RunPeriod) -> bool: ...
+
+def run_period(run: int) -> str: ...
+def run_period_for(run: int) -> RunPeriod: ...
+def run_range(run_period: str) -> tuple[int, int]: ...
+def run_periods() -> list[str]: ...
+def coherent_peak(run: int) -> tuple[float, float]: ...
+def rest_versions(run_period: str) -> dict[int, datetime.datetime]: ...
+def resolve_rest_version(run_period: str, version: int) -> tuple[int, datetime.datetime]: ...
+def parse_timestamp(s: str) -> datetime.datetime: ...
+
+__all__ = [
+    "RunPeriod",
+    "ccdb",
+    "coherent_peak",
+    "lumi",
+    "parse_timestamp",
+    "rcdb",
+    "resolve_rest_version",
+    "rest_versions",
+    "run_period",
+    "run_period_for",
+    "run_periods",
+    "run_range",
+]
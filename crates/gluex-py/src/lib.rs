@@ -0,0 +1,412 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use gluex_core::{
+    parsers,
+    run_periods::{self, RunPeriod},
+    RestVersion, RunNumber,
+};
+use pyo3::{exceptions::PyRuntimeError, prelude::*, types::PyModule};
+use strum::IntoEnumIterator;
+
+fn parse_run_period(run_period: &str) -> PyResult<RunPeriod> {
+    RunPeriod::from_str(run_period).map_err(|err| PyRuntimeError::new_err(err.to_string()))
+}
+
+/// Known GlueX run periods.
+///
+/// Variants mirror ``gluex_core::run_periods::RunPeriod`` (for example ``RunPeriod.RP2018_08``
+/// is the Fall 2018 period, short name ``"F18"``).
+///
+/// Examples
+/// --------
+/// >>> import gluex
+/// >>> rp = gluex.RunPeriod.from_run(51_000)
+/// >>> rp.short_name, rp.min_run, rp.max_run
+/// ('F18', 50000, 59999)
+#[pyclass(name = "RunPeriod", module = "gluex", eq, eq_int, hash, frozen, ord)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[allow(non_camel_case_types)]
+pub enum PyRunPeriod {
+    /// Commisioning, 12 GeV
+    RP2016_02,
+    /// GlueX Phase I, 12 GeV
+    RP2017_01,
+    /// GlueX Phase I, 12 GeV
+    RP2018_01,
+    /// GlueX Phase I, 12 GeV / PrimEx Commissioning
+    RP2018_08,
+    /// DIRC Commissioning/PrimEx
+    RP2019_01,
+    /// DIRC Commissioning/GlueX Phase II
+    RP2019_11,
+    /// PrimEx
+    RP2021_08,
+    /// SRC
+    RP2021_11,
+    /// CPP/NPP
+    RP2022_05,
+    /// PrimEx
+    RP2022_08,
+    /// GlueX Phase II
+    RP2023_01,
+    /// ECAL Commissioning/GlueX Phase II
+    RP2025_01,
+}
+
+impl From<RunPeriod> for PyRunPeriod {
+    fn from(value: RunPeriod) -> Self {
+        match value {
+            RunPeriod::RP2016_02 => Self::RP2016_02,
+            RunPeriod::RP2017_01 => Self::RP2017_01,
+            RunPeriod::RP2018_01 => Self::RP2018_01,
+            RunPeriod::RP2018_08 => Self::RP2018_08,
+            RunPeriod::RP2019_01 => Self::RP2019_01,
+            RunPeriod::RP2019_11 => Self::RP2019_11,
+            RunPeriod::RP2021_08 => Self::RP2021_08,
+            RunPeriod::RP2021_11 => Self::RP2021_11,
+            RunPeriod::RP2022_05 => Self::RP2022_05,
+            RunPeriod::RP2022_08 => Self::RP2022_08,
+            RunPeriod::RP2023_01 => Self::RP2023_01,
+            RunPeriod::RP2025_01 => Self::RP2025_01,
+        }
+    }
+}
+
+impl From<PyRunPeriod> for RunPeriod {
+    fn from(value: PyRunPeriod) -> Self {
+        match value {
+            PyRunPeriod::RP2016_02 => Self::RP2016_02,
+            PyRunPeriod::RP2017_01 => Self::RP2017_01,
+            PyRunPeriod::RP2018_01 => Self::RP2018_01,
+            PyRunPeriod::RP2018_08 => Self::RP2018_08,
+            PyRunPeriod::RP2019_01 => Self::RP2019_01,
+            PyRunPeriod::RP2019_11 => Self::RP2019_11,
+            PyRunPeriod::RP2021_08 => Self::RP2021_08,
+            PyRunPeriod::RP2021_11 => Self::RP2021_11,
+            PyRunPeriod::RP2022_05 => Self::RP2022_05,
+            PyRunPeriod::RP2022_08 => Self::RP2022_08,
+            PyRunPeriod::RP2023_01 => Self::RP2023_01,
+            PyRunPeriod::RP2025_01 => Self::RP2025_01,
+        }
+    }
+}
+
+#[pymethods]
+impl PyRunPeriod {
+    /// from_run(run)
+    ///
+    /// Parameters
+    /// ----------
+    /// run : int
+    ///     Run number to classify.
+    ///
+    /// Returns
+    /// -------
+    /// RunPeriod
+    ///     The run period containing ``run``.
+    ///
+    /// Raises
+    /// ------
+    /// RuntimeError
+    ///     If the run does not belong to any known run period.
+    #[staticmethod]
+    fn from_run(run: RunNumber) -> PyResult<Self> {
+        RunPeriod::try_from(run)
+            .map(Self::from)
+            .map_err(|err| PyRuntimeError::new_err(err.to_string()))
+    }
+
+    /// from_name(name)
+    ///
+    /// Parameters
+    /// ----------
+    /// name : str
+    ///     Run period short name (case-insensitive, for example ``"s17"`` or ``"F18"``).
+    ///
+    /// Returns
+    /// -------
+    /// RunPeriod
+    #[staticmethod]
+    fn from_name(name: &str) -> PyResult<Self> {
+        parse_run_period(name).map(Self::from)
+    }
+
+    /// int: First run number in the period.
+    #[getter]
+    fn min_run(&self) -> RunNumber {
+        RunPeriod::from(*self).min_run()
+    }
+
+    /// int: Last run number in the period.
+    #[getter]
+    fn max_run(&self) -> RunNumber {
+        RunPeriod::from(*self).max_run()
+    }
+
+    /// str: Short name of the period (for example ``"F18"``).
+    #[getter]
+    fn short_name(&self) -> String {
+        RunPeriod::from(*self).short_name().to_string()
+    }
+
+    /// contains(run)
+    ///
+    /// Parameters
+    /// ----------
+    /// run : int
+    ///
+    /// Returns
+    /// -------
+    /// bool
+    ///     True if ``run`` lies within the period's run range.
+    fn contains(&self, run: RunNumber) -> bool {
+        RunPeriod::from(*self).contains(run)
+    }
+
+    /// coherent_peak(self)
+    ///
+    /// Returns
+    /// -------
+    /// tuple[float, float]
+    ///     Default ``(low, high)`` coherent-peak beam-energy window in GeV for runs in this period.
+    fn coherent_peak(&self) -> (f64, f64) {
+        run_periods::coherent_peak(self.min_run())
+    }
+
+    /// rest_versions(self)
+    ///
+    /// Returns
+    /// -------
+    /// dict[int, datetime.datetime]
+    ///     Mapping of REST version to the CCDB timestamp used for that version.
+    fn rest_versions(&self) -> BTreeMap<RestVersion, DateTime<Utc>> {
+        run_periods::rest_versions_for(RunPeriod::from(*self))
+            .unwrap_or_default()
+            .into_iter()
+            .collect()
+    }
+
+    fn __str__(&self) -> String {
+        self.short_name()
+    }
+}
+
+/// run_period(run)
+///
+/// Look up the run period containing a run number.
+///
+/// Parameters
+/// ----------
+/// run : int
+///     Run number to classify.
+///
+/// Returns
+/// -------
+/// str
+///     Short name of the run period (for example ``"F18"``).
+///
+/// Raises
+/// ------
+/// RuntimeError
+///     If the run does not belong to any known run period.
+#[pyfunction]
+pub fn run_period(run: RunNumber) -> PyResult<String> {
+    RunPeriod::try_from(run)
+        .map(|rp| rp.short_name().to_string())
+        .map_err(|err| PyRuntimeError::new_err(err.to_string()))
+}
+
+/// run_period_for(run)
+///
+/// Look up the run period containing a run number.
+///
+/// Parameters
+/// ----------
+/// run : int
+///     Run number to classify.
+///
+/// Returns
+/// -------
+/// RunPeriod
+///     The run period containing ``run``.
+///
+/// Raises
+/// ------
+/// RuntimeError
+///     If the run does not belong to any known run period.
+#[pyfunction]
+pub fn run_period_for(run: RunNumber) -> PyResult<PyRunPeriod> {
+    PyRunPeriod::from_run(run)
+}
+
+/// run_range(run_period)
+///
+/// Return the inclusive run-number bounds of a run period.
+///
+/// Parameters
+/// ----------
+/// run_period : str
+///     Run period short name (for example ``"s17"`` or ``"f18"``).
+///
+/// Returns
+/// -------
+/// tuple[int, int]
+///     ``(min_run, max_run)`` for the run period.
+#[pyfunction]
+pub fn run_range(run_period: &str) -> PyResult<(RunNumber, RunNumber)> {
+    let rp = parse_run_period(run_period)?;
+    Ok((rp.min_run(), rp.max_run()))
+}
+
+/// run_periods()
+///
+/// List the short names of every known run period in chronological order.
+///
+/// Returns
+/// -------
+/// list[str]
+#[pyfunction(name = "run_periods")]
+pub fn py_run_periods() -> Vec<String> {
+    RunPeriod::iter()
+        .map(|rp| rp.short_name().to_string())
+        .collect()
+}
+
+/// coherent_peak(run)
+///
+/// Return the default coherent-peak energy window for a run.
+///
+/// Parameters
+/// ----------
+/// run : int
+///     Run number to look up.
+///
+/// Returns
+/// -------
+/// tuple[float, float]
+///     ``(low, high)`` beam-energy bounds in GeV.
+#[pyfunction]
+pub fn coherent_peak(run: RunNumber) -> (f64, f64) {
+    run_periods::coherent_peak(run)
+}
+
+/// rest_versions(run_period)
+///
+/// Return the known REST versions for a run period.
+///
+/// Parameters
+/// ----------
+/// run_period : str
+///     Run period short name.
+///
+/// Returns
+/// -------
+/// dict[int, datetime.datetime]
+///     Mapping of REST version to the CCDB timestamp used for that version. Empty when no
+///     REST versions are recorded for the run period.
+#[pyfunction]
+pub fn rest_versions(run_period: &str) -> PyResult<BTreeMap<RestVersion, DateTime<Utc>>> {
+    let rp = parse_run_period(run_period)?;
+    Ok(run_periods::rest_versions_for(rp)
+        .unwrap_or_default()
+        .into_iter()
+        .collect())
+}
+
+/// resolve_rest_version(run_period, version)
+///
+/// Resolve the CCDB timestamp for a REST version of a run period.
+///
+/// If ``version`` has no recorded timestamp, the closest lower REST version is used instead.
+///
+/// Parameters
+/// ----------
+/// run_period : str
+///     Run period short name.
+/// version : int
+///     Requested REST version.
+///
+/// Returns
+/// -------
+/// tuple[int, datetime.datetime]
+///     ``(used_version, timestamp)`` where ``used_version`` is the REST version actually used.
+///
+/// Raises
+/// ------
+/// RuntimeError
+///     If the run period has no REST versions or none at or below ``version``.
+#[pyfunction]
+pub fn resolve_rest_version(
+    run_period: &str,
+    version: RestVersion,
+) -> PyResult<(RestVersion, DateTime<Utc>)> {
+    let rp = parse_run_period(run_period)?;
+    run_periods::resolve_rest_version(rp, version)
+        .map(|resolved| (resolved.used, resolved.timestamp))
+        .map_err(|err| PyRuntimeError::new_err(err.to_string()))
+}
+
+/// parse_timestamp(s)
+///
+/// Parse a CCDB-style timestamp string, inferring missing fields.
+///
+/// Missing trailing fields are filled with their latest value, so ``"2018"`` is the last second
+/// of 2018 and ``"2018-08"`` is the last second of August 2018.
+///
+/// Parameters
+/// ----------
+/// s : str
+///     Timestamp string such as ``"2018-08-15 12:00:00"``. Any non-digit characters separate fields.
+///
+/// Returns
+/// -------
+/// datetime.datetime
+///     Timezone-aware UTC datetime.
+///
+/// Raises
+/// ------
+/// RuntimeError
+///     If the string cannot be interpreted as a timestamp.
+#[pyfunction]
+pub fn parse_timestamp(s: &str) -> PyResult<DateTime<Utc>> {
+    parsers::parse_timestamp(s).map_err(|err| PyRuntimeError::new_err(err.to_string()))
+}
+
+fn add_submodule<'py>(
+    py: Python<'py>,
+    parent: &Bound<'py, PyModule>,
+    name: &str,
+    init: impl FnOnce(Python<'py>, &Bound<'py, PyModule>) -> PyResult<()>,
+) -> PyResult<()> {
+    let module = PyModule::new(py, name)?;
+    init(py, &module)?;
+    parent.add_submodule(&module)?;
+    // Register the submodule so `import gluex.<name>` and `from gluex.<name> import ...` work.
+    py.import("sys")?
+        .getattr("modules")?
+        .set_item(format!("gluex.{name}"), &module)?;
+    Ok(())
+}
+
+#[pymodule(gil_used = false)]
+/// gluex
+///
+/// Unified Python bindings for the GlueX CCDB, RCDB, and luminosity crates.
+pub fn gluex(py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
+    add_submodule(py, m, "ccdb", ::gluex_ccdb::gluex_ccdb)?;
+    add_submodule(py, m, "rcdb", ::gluex_rcdb::gluex_rcdb)?;
+    add_submodule(py, m, "lumi", ::gluex_lumi::gluex_lumi)?;
+    m.add_class::<PyRunPeriod>()?;
+    m.add_function(wrap_pyfunction!(run_period, m)?)?;
+    m.add_function(wrap_pyfunction!(run_period_for, m)?)?;
+    m.add_function(wrap_pyfunction!(run_range, m)?)?;
+    m.add_function(wrap_pyfunction!(py_run_periods, m)?)?;
+    m.add_function(wrap_pyfunction!(coherent_peak, m)?)?;
+    m.add_function(wrap_pyfunction!(rest_versions, m)?)?;
+    m.add_function(wrap_pyfunction!(resolve_rest_version, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_timestamp, m)?)?;
+    let version = env!("CARGO_PKG_VERSION");
+    m.add("__version__", version)?;
+    Ok(())
+}
@@ -0,0 +1,79 @@
+"""Tests for the unified gluex Python package."""
+
+from __future__ import annotations
+
+import datetime
+
+import pytest
+
+import gluex
+
+
+def test_submodules_are_importable() -> None:
+    from gluex import ccdb, lumi, rcdb
+
+    assert hasattr(ccdb, "CCDB")
+    assert hasattr(rcdb, "RCDB")
+    assert hasattr(lumi, "get_flux_histograms")
+
+    import gluex.rcdb
+
+    assert gluex.rcdb is rcdb
+
+
+def test_run_period_helpers() -> None:
+    assert gluex.run_period(51_000) == "F18"
+    assert gluex.run_range("s17") == (30_000, 39_999)
+    assert "F18" in gluex.run_periods()
+    low, high = gluex.coherent_peak(51_000)
+    assert low < high
+    with pytest.raises(RuntimeError):
+        gluex.run_period(1)
+    with pytest.raises(RuntimeError):
+        gluex.run_range("not-a-period")
+
+
+def test_rest_versions() -> None:
+    versions = gluex.rest_versions("f18")
+    assert versions
+    for version, timestamp in versions.items():
+        assert isinstance(version, int)
+        assert isinstance(timestamp, datetime.datetime)
+
+
+def test_resolve_rest_version() -> None:
+    versions = gluex.rest_versions("f18")
+    version, timestamp = max(versions.items())
+    assert gluex.resolve_rest_version("f18", version) == (version, timestamp)
+    assert gluex.resolve_rest_version("f18", version + 100) == (version, timestamp)
+    with pytest.raises(RuntimeError):
+        gluex.resolve_rest_version("s19", 0)
+
+
+def test_parse_timestamp() -> None:
+    utc = datetime.timezone.utc
+    assert gluex.parse_timestamp("2018-08-15 12:30:00") == datetime.datetime(
+        2018, 8, 15, 12, 30, 0, tzinfo=utc
+    )
+    assert gluex.parse_timestamp("2018-08") == datetime.datetime(
+        2018, 8, 31, 23, 59, 59, tzinfo=utc
+    )
+    with pytest.raises(RuntimeError):
+        gluex.parse_timestamp("not a timestamp")
+
+
+def test_run_period_class() -> None:
+    rp = gluex.RunPeriod.from_run(51_000)
+    assert rp == gluex.RunPeriod.RP2018_08
+    assert gluex.run_period_for(51_000) == rp
+    assert rp == gluex.RunPeriod.from_name("f18")
+    assert rp.short_name == str(rp) == "F18"
+    assert (rp.min_run, rp.max_run) == gluex.run_range("f18")
+    assert rp.contains(51_000)
+    assert not rp.contains(40_000)
+    assert rp.coherent_peak() == gluex.coherent_peak(rp.min_run)
+    assert rp.rest_versions() == gluex.rest_versions("f18")
+    assert gluex.RunPeriod.RP2017_01 < rp
+    assert len({rp, gluex.RunPeriod.RP2018_08}) == 1
+    with pytest.raises(RuntimeError):
+        gluex.RunPeriod.from_name("not-a-period")
@@ -0,0 +1,84 @@
+//! Derive macro for `gluex_rcdb::typed::RcdbConditions`.
+//!
+//! This crate is re-exported by `gluex-rcdb` when its `derive` feature is enabled and is not meant
+//! to be used directly.
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, LitStr};
+
+/// Implements `gluex_rcdb::typed::RcdbConditions` for a struct with named fields.
+///
+/// Each field is read from the condition with the same name; `#[rcdb(condition = "name")]` reads
+/// it from another condition instead. Field types must implement
+/// `gluex_rcdb::typed::ConditionField`; runs missing a condition are skipped unless its field is
+/// an `Option`.
+#[proc_macro_derive(RcdbConditions, attributes(rcdb))]
+pub fn derive_rcdb_conditions(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(
+            input,
+            "RcdbConditions can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(Error::new_spanned(
+            &data.fields,
+            "RcdbConditions can only be derived for structs with named fields",
+        ));
+    };
+    let mut idents = Vec::new();
+    let mut conditions = Vec::new();
+    for field in &fields.named {
+        let ident = field.ident.clone().expect("named fields have identifiers");
+        conditions.push(condition_name(field)?.unwrap_or_else(|| ident.to_string()));
+        idents.push(ident);
+    }
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::gluex_rcdb::typed::RcdbConditions for #name #ty_generics #where_clause {
+            const CONDITIONS: &'static [&'static str] = &[#(#conditions),*];
+
+            fn from_conditions(
+                values: &::std::collections::HashMap<::std::string::String, ::gluex_rcdb::data::Value>,
+            ) -> ::gluex_rcdb::RCDBResult<::core::option::Option<Self>> {
+                #(
+                    let ::core::option::Option::Some(#idents) =
+                        ::gluex_rcdb::typed::condition(values, #conditions)?
+                    else {
+                        return ::core::result::Result::Ok(::core::option::Option::None);
+                    };
+                )*
+                ::core::result::Result::Ok(::core::option::Option::Some(Self { #(#idents),* }))
+            }
+        }
+    })
+}
+
+/// Reads the condition name from a field's `#[rcdb(condition = "...")]` attribute, if it has one.
+fn condition_name(field: &syn::Field) -> syn::Result<Option<String>> {
+    let mut condition = None;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("rcdb"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("condition") {
+                condition = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            } else {
+                Err(meta.error("unsupported rcdb attribute, expected `condition = \"...\"`"))
+            }
+        })?;
+    }
+    Ok(condition)
+}
@@ -1,32 +1,184 @@
-from typing import Any, Sequence
+import asyncio
+import os
+from collections.abc import Iterator
+from typing import Any, Literal, Sequence, overload
+
+import awkward as ak
+import numpy as np
+import numpy.typing as npt
 from datetime import datetime
+from types import TracebackType
+
+class RCDBError(RuntimeError): ...
+class SqliteError(RCDBError): ...
+class SchemaError(RCDBError): ...
+class ConditionNotFoundError(RCDBError): ...
+class ConditionTypeMismatchError(RCDBError): ...
+class EmptyConditionListError(RCDBError): ...
+class DataError(RCDBError): ...
+class LimitError(RCDBError): ...
+class RunPeriodError(RCDBError): ...
 
 class Expr:
+    @property
+    def sql(self) -> str: ...
     def __invert__(self) -> Expr: ...
+    def __and__(self, other: Expr) -> Expr: ...
+    def __or__(self, other: Expr) -> Expr: ...
+
+class ArrowTable:
+    @property
+    def column_names(self) -> list[str]: ...
+    def __arrow_c_stream__(self, requested_schema: object | None = None) -> object: ...
+    def __len__(self) -> int: ...
+
+class FetchIter(Iterator[tuple[int, dict[str, Any]]]):
+    def __iter__(self) -> FetchIter: ...
+    def __next__(self) -> tuple[int, dict[str, Any]]: ...
+
+class ConditionType:
+    @property
+    def id(self) -> int: ...
+    @property
+    def name(self) -> str: ...
+    @property
+    def value_type(self) -> str: ...
+    @property
+    def description(self) -> str: ...
+    @property
+    def created(self) -> str: ...
 
 class RCDB:
-    def __init__(self, path: str) -> None: ...
+    def __init__(self, path: str | os.PathLike[str]) -> None: ...
     @property
     def connection_path(self) -> str: ...
+    @property
+    def closed(self) -> bool: ...
+    def close(self) -> None: ...
+    def __enter__(self) -> RCDB: ...
+    def __exit__(
+        self,
+        exc_type: type[BaseException] | None,
+        exc_value: BaseException | None,
+        traceback: TracebackType | None,
+    ) -> bool: ...
+    def condition_types(self) -> list[ConditionType]: ...
+    def condition_type(self, name: str) -> ConditionType | None: ...
+    def has_condition(self, name: str) -> bool: ...
     def fetch(
         self,
         condition_names: Sequence[str],
         *,
         run_period: str | None = None,
-        runs: Sequence[int] | None = None,
+        runs: Sequence[int] | npt.NDArray[np.integer] | None = None,
         run_min: int | None = None,
         run_max: int | None = None,
         filters: Expr | Sequence[Expr] | None = None,
+        time_as_str: bool = False,
     ) -> dict[int, dict[str, Any]]: ...
+    def fetch_async(
+        self,
+        condition_names: Sequence[str],
+        *,
+        run_period: str | None = None,
+        runs: Sequence[int] | npt.NDArray[np.integer] | None = None,
+        run_min: int | None = None,
+        run_max: int | None = None,
+        filters: Expr | Sequence[Expr] | None = None,
+        time_as_str: bool = False,
+    ) -> asyncio.Future[dict[int, dict[str, Any]]]: ...
+    def fetch_dataframe(
+        self,
+        condition_names: Sequence[str],
+        *,
+        run_period: str | None = None,
+        runs: Sequence[int] | npt.NDArray[np.integer] | None = None,
+        run_min: int | None = None,
+        run_max: int | None = None,
+        filters: Expr | Sequence[Expr] | None = None,
+        backend: Literal["pandas", "polars"] = "pandas",
+        time_as_str: bool = False,
+        index_by_run: bool = False,
+        dtype_backend: Literal["numpy", "numpy_nullable"] = "numpy",
+    ) -> Any: ...
+    def fetch_awkward(
+        self,
+        condition_names: Sequence[str],
+        *,
+        run_period: str | None = None,
+        runs: Sequence[int] | npt.NDArray[np.integer] | None = None,
+        run_min: int | None = None,
+        run_max: int | None = None,
+        filters: Expr | Sequence[Expr] | None = None,
+        time_as_str: bool = False,
+    ) -> ak.Array: ...
+    def fetch_arrays(
+        self,
+        condition_names: Sequence[str],
+        *,
+        run_period: str | None = None,
+        runs: Sequence[int] | npt.NDArray[np.integer] | None = None,
+        run_min: int | None = None,
+        run_max: int | None = None,
+        filters: Expr | Sequence[Expr] | None = None,
+        int_fill: int = -1,
+    ) -> dict[str, np.ndarray]: ...
+    def fetch_arrow(
+        self,
+        condition_names: Sequence[str],
+        *,
+        run_period: str | None = None,
+        runs: Sequence[int] | npt.NDArray[np.integer] | None = None,
+        run_min: int | None = None,
+        run_max: int | None = None,
+        filters: Expr | Sequence[Expr] | None = None,
+    ) -> ArrowTable: ...
+    def fetch_iter(
+        self,
+        condition_names: Sequence[str],
+        *,
+        run_period: str | None = None,
+        runs: Sequence[int] | npt.NDArray[np.integer] | None = None,
+        run_min: int | None = None,
+        run_max: int | None = None,
+        filters: Expr | Sequence[Expr] | None = None,
+        chunk_size: int = 1000,
+        time_as_str: bool = False,
+    ) -> FetchIter: ...
+    @overload
     def fetch_runs(
         self,
         *,
         run_period: str | None = None,
-        runs: Sequence[int] | None = None,
+        runs: Sequence[int] | npt.NDArray[np.integer] | None = None,
         run_min: int | None = None,
         run_max: int | None = None,
         filters: Expr | Sequence[Expr] | None = None,
+        as_numpy: Literal[False] = False,
     ) -> list[int]: ...
+    @overload
+    def fetch_runs(
+        self,
+        *,
+        run_period: str | None = None,
+        runs: Sequence[int] | npt.NDArray[np.integer] | None = None,
+        run_min: int | None = None,
+        run_max: int | None = None,
+        filters: Expr | Sequence[Expr] | None = None,
+        as_numpy: Literal[True],
+    ) -> npt.NDArray[np.int64]: ...
+    def fetch_runs_async(
+        self,
+        *,
+        run_period: str | None = None,
+        runs: Sequence[int] | npt.NDArray[np.integer] | None = None,
+        run_min: int | None = None,
+        run_max: int | None = None,
+        filters: Expr | Sequence[Expr] | None = None,
+        as_numpy: bool = False,
+    ) -> asyncio.Future[list[int] | npt.NDArray[np.int64]]: ...
+    def files(self, run: int) -> dict[str, bytes]: ...
+    def select(self, **lookups: Any) -> Expr: ...
 
 class IntCondition:
     def eq(self, value: int) -> Expr: ...
@@ -107,17 +259,34 @@ class Aliases:
     @property
     def status_reject(self) -> Expr: ...
     def approved_production(self, run_period: str) -> Expr: ...
+    def names(self) -> list[str]: ...
+    def __getitem__(self, name: str) -> Expr: ...
+    def __contains__(self, name: str) -> bool: ...
 
 aliases: Aliases
 
+def alias(name: str) -> Expr: ...
+
 __all__ = [
     "RCDB",
+    "RCDBError",
+    "SqliteError",
+    "SchemaError",
+    "ConditionNotFoundError",
+    "ConditionTypeMismatchError",
+    "EmptyConditionListError",
+    "DataError",
+    "LimitError",
+    "RunPeriodError",
+    "ConditionType",
     "IntCondition",
     "FloatCondition",
     "StringCondition",
     "BoolCondition",
     "TimeCondition",
     "Expr",
+    "ArrowTable",
+    "FetchIter",
     "int_cond",
     "float_cond",
     "string_cond",
@@ -125,5 +294,7 @@ __all__ = [
     "time_cond",
     "all",
     "any",
+    "Aliases",
     "aliases",
+    "alias",
 ]
@@ -0,0 +1,107 @@
+//! Python exception hierarchy raised by the `gluex_rcdb` bindings.
+//!
+//! Every exception derives from [`RCDBError`], which itself subclasses `RuntimeError` so that
+//! existing `except RuntimeError` handlers keep working.
+
+use ::gluex_rcdb::RCDBError as RustRCDBError;
+use pyo3::{create_exception, exceptions::PyRuntimeError, prelude::*, PyErr};
+
+create_exception!(
+    gluex_rcdb,
+    RCDBError,
+    PyRuntimeError,
+    "Base class for all errors raised by gluex_rcdb."
+);
+create_exception!(
+    gluex_rcdb,
+    SqliteError,
+    RCDBError,
+    "The underlying SQLite database reported an error."
+);
+create_exception!(
+    gluex_rcdb,
+    SchemaError,
+    RCDBError,
+    "The database schema is missing or uses an unsupported layout or value type."
+);
+create_exception!(
+    gluex_rcdb,
+    ConditionNotFoundError,
+    RCDBError,
+    "The requested condition type does not exist."
+);
+create_exception!(
+    gluex_rcdb,
+    ConditionTypeMismatchError,
+    RCDBError,
+    "A filter used a condition with a different value type than the one stored in RCDB."
+);
+create_exception!(
+    gluex_rcdb,
+    EmptyConditionListError,
+    RCDBError,
+    "A fetch was requested without any condition names."
+);
+create_exception!(
+    gluex_rcdb,
+    DataError,
+    RCDBError,
+    "A stored condition value could not be decoded."
+);
+create_exception!(
+    gluex_rcdb,
+    LimitError,
+    RCDBError,
+    "A fetch exceeded the handle's run, memory, or time limits."
+);
+create_exception!(
+    gluex_rcdb,
+    RunPeriodError,
+    RCDBError,
+    "A run period name could not be resolved."
+);
+
+/// Converts a Rust [`RustRCDBError`] into the matching Python exception.
+pub(crate) fn to_py_err(err: RustRCDBError) -> PyErr {
+    let message = err.to_string();
+    match err {
+        RustRCDBError::SqliteError(_) => SqliteError::new_err(message),
+        RustRCDBError::CompressedSnapshotError(_) | RustRCDBError::BundleError(_) => {
+            RCDBError::new_err(message)
+        }
+        RustRCDBError::ConditionTypeNotFound(_) => ConditionNotFoundError::new_err(message),
+        RustRCDBError::MissingSchemaVersion
+        | RustRCDBError::UnknownValueType(_)
+        | RustRCDBError::NewerSchemaError(_) => SchemaError::new_err(message),
+        RustRCDBError::EmptyConditionList => EmptyConditionListError::new_err(message),
+        RustRCDBError::ConditionTypeMismatch { .. } => ConditionTypeMismatchError::new_err(message),
+        RustRCDBError::ParseTimestampError(_) | RustRCDBError::MissingTimeValue { .. } => {
+            DataError::new_err(message)
+        }
+        RustRCDBError::LimitError(_) => LimitError::new_err(message),
+    }
+}
+
+/// Registers every exception class on the `gluex_rcdb` module.
+pub(crate) fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add("RCDBError", py.get_type::<RCDBError>())?;
+    m.add("SqliteError", py.get_type::<SqliteError>())?;
+    m.add("SchemaError", py.get_type::<SchemaError>())?;
+    m.add(
+        "ConditionNotFoundError",
+        py.get_type::<ConditionNotFoundError>(),
+    )?;
+    m.add(
+        "ConditionTypeMismatchError",
+        py.get_type::<ConditionTypeMismatchError>(),
+    )?;
+    m.add(
+        "EmptyConditionListError",
+        py.get_type::<EmptyConditionListError>(),
+    )?;
+    m.add("DataError", py.get_type::<DataError>())?;
+    m.add("LimitError", py.get_type::<LimitError>())?;
+    m.add("RunPeriodError", py.get_type::<RunPeriodError>())?;
+    Ok(())
+}
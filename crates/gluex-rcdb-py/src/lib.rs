@@ -3,24 +3,44 @@ use ::gluex_rcdb::{
     context::Context,
     data::Value,
     database::RCDB,
-    models::ValueType,
+    models::{ConditionTypeMeta, ValueType},
+    stream::FetchIter,
     RCDBError,
 };
 use chrono::{DateTime, Utc};
+#[cfg(feature = "arrow")]
+use gluex_core::arrow::{ArrowArrayStream, ArrowColumn, ArrowValues};
 use gluex_core::{
     constants::{MAX_RUN_NUMBER, MIN_RUN_NUMBER},
     run_periods::RunPeriodError,
     RunNumber,
 };
+use numpy::{
+    datetime::{units::Microseconds, Datetime},
+    PyArray1, PyArrayMethods,
+};
+#[cfg(feature = "arrow")]
+use pyo3::types::PyCapsule;
 use pyo3::{
-    exceptions::PyRuntimeError,
+    exceptions::{PyKeyError, PyRuntimeError},
     prelude::*,
-    types::{PyDict, PyFloat, PyInt, PyList, PyModule, PyString, PyTuple},
+    types::{PyBytes, PyDict, PyFloat, PyInt, PyList, PyModule, PyString, PyTuple, PyType},
     Bound, IntoPyObject,
 };
+use std::{
+    collections::HashSet,
+    iter,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+mod exceptions;
 
 fn py_rcdb_error(err: RCDBError) -> PyErr {
-    PyRuntimeError::new_err(err.to_string())
+    exceptions::to_py_err(err)
 }
 
 /// Boolean expression used to filter RCDB queries.
@@ -29,7 +49,9 @@ fn py_rcdb_error(err: RCDBError) -> PyErr {
 /// --------
 /// >>> import gluex_rcdb as rcdb
 /// >>> expr = rcdb.int_cond("event_count").gt(1000)
-/// >>> ctx = rcdb.Context(filters=expr)
+/// >>> expr = expr & ~(rcdb.float_cond("beam_current").lt(2.0) | rcdb.aliases.is_cosmic)
+/// >>> print(expr.sql)
+/// (cond_0.int_value > 1000 AND NOT ((cond_1.float_value < 2.0 OR ...)))
 #[pyclass(name = "Expr", module = "gluex_rcdb")]
 #[derive(Clone)]
 pub struct PyExpr {
@@ -48,8 +70,21 @@ impl PyExpr {
 
 #[pymethods]
 impl PyExpr {
+    /// str: SQL predicate generated for the expression, with parameters inlined.
+    ///
+    /// Conditions are shown with the placeholder aliases ``cond_0``, ``cond_1``, ... and the
+    /// types implied by their builders. Raises ``ConditionTypeMismatchError`` if one condition is
+    /// compared as two different types.
+    #[getter]
+    fn sql(&self) -> PyResult<String> {
+        self.expr.to_sql_preview().map_err(py_rcdb_error)
+    }
+
     fn __repr__(&self) -> String {
-        format!("Expr({})", self.expr)
+        match self.expr.to_sql_preview() {
+            Ok(sql) => format!("Expr({}, sql={sql:?})", self.expr),
+            Err(err) => format!("Expr({}, sql=<{err}>)", self.expr),
+        }
     }
 
     fn __str__(&self) -> String {
@@ -59,12 +94,70 @@ impl PyExpr {
     fn __invert__(&self) -> PyExpr {
         PyExpr::new(self.inner().negate())
     }
+
+    fn __and__(&self, other: &PyExpr) -> PyExpr {
+        PyExpr::new(conditions::all([self.inner(), other.inner()]))
+    }
+
+    fn __or__(&self, other: &PyExpr) -> PyExpr {
+        PyExpr::new(conditions::any([self.inner(), other.inner()]))
+    }
+
+    fn __reduce__<'py>(slf: &Bound<'py, Self>) -> PyResult<(Bound<'py, PyAny>, (String,))> {
+        let state = serde_json::to_string(&slf.borrow().expr)
+            .map_err(|err| PyRuntimeError::new_err(err.to_string()))?;
+        Ok((slf.get_type().getattr("_from_state")?, (state,)))
+    }
+
+    #[classmethod]
+    fn _from_state(_cls: &Bound<'_, PyType>, state: &str) -> PyResult<Self> {
+        serde_json::from_str(state)
+            .map(PyExpr::new)
+            .map_err(|err| PyRuntimeError::new_err(err.to_string()))
+    }
+}
+
+/// Run numbers passed from Python.
+///
+/// One-dimensional numpy integer arrays are copied directly from their buffer; any other
+/// sequence of integers is extracted element by element.
+pub struct RunList(pub Vec<RunNumber>);
+
+impl<'a, 'py> FromPyObject<'a, 'py> for RunList {
+    type Error = PyErr;
+
+    fn extract(obj: Borrowed<'a, 'py, PyAny>) -> PyResult<Self> {
+        if let Ok(array) = obj.cast::<PyArray1<i64>>() {
+            return Ok(Self(array.readonly().as_array().to_vec()));
+        }
+        if let Ok(array) = obj.cast::<PyArray1<i32>>() {
+            return Ok(Self(
+                array
+                    .readonly()
+                    .as_array()
+                    .iter()
+                    .map(|&run| RunNumber::from(run))
+                    .collect(),
+            ));
+        }
+        if let Ok(array) = obj.cast::<PyArray1<u32>>() {
+            return Ok(Self(
+                array
+                    .readonly()
+                    .as_array()
+                    .iter()
+                    .map(|&run| RunNumber::from(run))
+                    .collect(),
+            ));
+        }
+        obj.extract().map(Self)
+    }
 }
 
 pub fn parse_context(
     py: Python<'_>,
     run_period: Option<String>,
-    runs: Option<Vec<RunNumber>>,
+    runs: Option<RunList>,
     run_min: Option<RunNumber>,
     run_max: Option<RunNumber>,
     filters: Option<Py<PyAny>>,
@@ -90,9 +183,9 @@ pub fn parse_context(
         ctx = ctx.with_run_period(
             run_period
                 .parse()
-                .map_err(|e: RunPeriodError| PyRuntimeError::new_err(e.to_string()))?,
+                .map_err(|e: RunPeriodError| exceptions::RunPeriodError::new_err(e.to_string()))?,
         );
-    } else if let Some(run_list) = runs {
+    } else if let Some(RunList(run_list)) = runs {
         ctx = ctx.with_runs(run_list);
     } else if run_min.is_some() || run_max.is_some() {
         let start = run_min.unwrap_or(MIN_RUN_NUMBER);
@@ -109,15 +202,131 @@ pub fn parse_context(
     Ok(ctx)
 }
 
+/// Metadata describing an RCDB condition type.
+///
+/// Attributes
+/// ----------
+/// id : int
+///     Database identifier of the condition type.
+/// name : str
+///     Condition name used in queries.
+/// value_type : str
+///     Storage type (``"string"``, ``"int"``, ``"bool"``, ``"float"``, ``"json"``, ``"blob"``, or ``"time"``).
+/// description : str
+///     Free-form description stored in RCDB.
+/// created : str
+///     Creation timestamp as stored in RCDB.
+#[pyclass(name = "ConditionType", module = "gluex_rcdb")]
+#[derive(Clone)]
+pub struct PyConditionType {
+    inner: ConditionTypeMeta,
+}
+
+#[pymethods]
+impl PyConditionType {
+    #[getter]
+    fn id(&self) -> i64 {
+        self.inner.id()
+    }
+    #[getter]
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+    #[getter]
+    fn value_type(&self) -> &'static str {
+        self.inner.value_type().as_str()
+    }
+    #[getter]
+    fn description(&self) -> &str {
+        self.inner.description()
+    }
+    #[getter]
+    fn created(&self) -> String {
+        self.inner.created()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ConditionType(name='{}', value_type='{}')",
+            self.inner.name(),
+            self.inner.value_type().as_str()
+        )
+    }
+}
+
 /// Read-only RCDB client.
 ///
 /// Parameters
 /// ----------
-/// path : str
+/// path : str or os.PathLike
 ///     Filesystem path to an RCDB SQLite database.
-#[pyclass(name = "RCDB", module = "gluex_rcdb", unsendable)]
+#[pyclass(name = "RCDB", module = "gluex_rcdb")]
 pub struct PyRCDB {
-    inner: RCDB,
+    inner: Option<RCDB>,
+    connection_path: String,
+    closed: Closed,
+}
+
+/// Flag set by [`PyRCDB::close`] and shared with the iterators returned by `fetch_iter`, so they
+/// stop querying once the database is closed.
+#[derive(Clone, Default)]
+struct Closed(Arc<AtomicBool>);
+
+impl Closed {
+    fn close(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+
+    fn check(&self) -> PyResult<()> {
+        if self.0.load(Ordering::Acquire) {
+            Err(exceptions::RCDBError::new_err("RCDB handle is closed"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl PyRCDB {
+    /// Returns the open database, or an error if the handle has been closed.
+    fn db(&self) -> PyResult<&RCDB> {
+        self.inner
+            .as_ref()
+            .ok_or_else(|| exceptions::RCDBError::new_err("RCDB handle is closed"))
+    }
+
+    /// Fetches conditions into a `{"run": [...], name: [...]}` column dictionary, using `None`
+    /// for conditions missing from a run.
+    fn fetch_columns<'py>(
+        &self,
+        py: Python<'py>,
+        condition_names: &Bound<'py, PyAny>,
+        ctx: &Context,
+        time_as_str: bool,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let mut names = extract_name_list(condition_names)?;
+        let mut seen = HashSet::new();
+        names.retain(|name| seen.insert(name.clone()));
+        let db = self.db()?;
+        let data = py.detach(|| db.fetch(&names, ctx)).map_err(py_rcdb_error)?;
+        let run_column: Vec<RunNumber> = data.keys().copied().collect();
+        let mut columns: Vec<Vec<Py<PyAny>>> = (0..names.len())
+            .map(|_| Vec::with_capacity(data.len()))
+            .collect();
+        for values in data.values() {
+            for (name, column) in names.iter().zip(columns.iter_mut()) {
+                column.push(match values.get(name) {
+                    Some(value) => value_to_python(py, value, time_as_str)?,
+                    None => py.None(),
+                });
+            }
+        }
+        let frame = PyDict::new(py);
+        frame.set_item("run", run_column)?;
+        for (name, column) in names.iter().zip(columns) {
+            frame.set_item(name, column)?;
+        }
+        Ok(frame)
+    }
 }
 
 #[pymethods]
@@ -128,18 +337,102 @@ impl PyRCDB {
     ///
     /// Parameters
     /// ----------
-    /// path : str
+    /// path : str or os.PathLike
     ///     Path to the RCDB SQLite database file.
-    fn new(path: &str) -> PyResult<Self> {
+    fn new(py: Python<'_>, path: PathBuf) -> PyResult<Self> {
+        let inner = py.detach(|| RCDB::open(path)).map_err(py_rcdb_error)?;
         Ok(Self {
-            inner: RCDB::open(path).map_err(py_rcdb_error)?,
+            connection_path: inner.connection_path().to_string(),
+            inner: Some(inner),
+            closed: Closed::default(),
         })
     }
 
     /// str: Filesystem path that was used to open the database.
     #[getter]
     pub fn connection_path(&self) -> &str {
-        self.inner.connection_path()
+        &self.connection_path
+    }
+
+    /// bool: Whether :meth:`RCDB.close` has been called on this handle.
+    #[getter]
+    pub fn closed(&self) -> bool {
+        self.inner.is_none()
+    }
+
+    /// close(self)
+    ///
+    /// Release this handle's SQLite connection. Further queries through the handle, or through
+    /// iterators returned by :meth:`RCDB.fetch_iter`, raise ``RCDBError`` (a ``RuntimeError``).
+    /// The connection itself is freed once those iterators are garbage collected. Calling
+    /// ``close`` more than once is a no-op.
+    pub fn close(&mut self) {
+        self.inner = None;
+        self.closed.close();
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyResult<PyRef<'_, Self>> {
+        slf.db()?;
+        Ok(slf)
+    }
+
+    #[pyo3(signature = (_exc_type=None, _exc_value=None, _traceback=None))]
+    fn __exit__(
+        &mut self,
+        _exc_type: Option<&Bound<'_, PyAny>>,
+        _exc_value: Option<&Bound<'_, PyAny>>,
+        _traceback: Option<&Bound<'_, PyAny>>,
+    ) -> bool {
+        self.close();
+        false
+    }
+
+    /// condition_types(self)
+    ///
+    /// Returns
+    /// -------
+    /// list[ConditionType]
+    ///     Metadata for every condition type in the database, sorted by name.
+    pub fn condition_types(&self) -> PyResult<Vec<PyConditionType>> {
+        Ok(self
+            .db()?
+            .condition_types()
+            .into_iter()
+            .map(|inner| PyConditionType { inner })
+            .collect())
+    }
+
+    /// condition_type(self, name)
+    ///
+    /// Parameters
+    /// ----------
+    /// name : str
+    ///     Condition name to look up.
+    ///
+    /// Returns
+    /// -------
+    /// ConditionType | None
+    ///     Metadata for the condition, or ``None`` if it does not exist.
+    pub fn condition_type(&self, name: &str) -> PyResult<Option<PyConditionType>> {
+        Ok(self
+            .db()?
+            .condition_type(name)
+            .map(|inner| PyConditionType { inner }))
+    }
+
+    /// has_condition(self, name)
+    ///
+    /// Parameters
+    /// ----------
+    /// name : str
+    ///     Condition name to look up.
+    ///
+    /// Returns
+    /// -------
+    /// bool
+    ///     True if the database defines a condition type with this name.
+    pub fn has_condition(&self, name: &str) -> PyResult<bool> {
+        Ok(self.db()?.condition_type(name).is_some())
     }
 
     /// fetch(self, condition_names, context=None)
@@ -150,7 +443,7 @@ impl PyRCDB {
     ///     Condition names to retrieve per run.
     /// run_period : str, optional
     ///     The run period to use (short name, e.g. "S17", "F18").
-    /// runs : Sequence[int], optional
+    /// runs : Sequence[int] or numpy.ndarray, optional
     ///     Explicit list of run numbers. Duplicates are ignored.
     /// run_min : int, optional
     ///     Inclusive start of the run range. Defaults to the first run in RCDB
@@ -160,6 +453,9 @@ impl PyRCDB {
     ///     only ``run_min`` is provided.
     /// filters : Expr or Sequence[Expr], optional
     ///     One or more expressions that must evaluate to true.
+    /// time_as_str : bool, default False
+    ///     Return ``time`` conditions as RFC 3339 strings (the previous behaviour) instead of
+    ///     timezone-aware UTC ``datetime.datetime`` objects.
     ///
     /// Returns
     /// -------
@@ -170,26 +466,29 @@ impl PyRCDB {
     /// Notes
     /// -----
     /// The run_period, runs, and (run_min, run_max) arguments are mutually exclusive.
-    #[pyo3(signature = (condition_names, *, run_period=None, runs=None, run_min=None, run_max=None, filters=None))]
+    #[pyo3(signature = (condition_names, *, run_period=None, runs=None, run_min=None, run_max=None, filters=None, time_as_str=false))]
+    #[allow(clippy::too_many_arguments)]
     pub fn fetch(
         &self,
         py: Python<'_>,
         condition_names: &Bound<'_, PyAny>,
         run_period: Option<String>,
-        runs: Option<Vec<RunNumber>>,
+        runs: Option<RunList>,
         run_min: Option<RunNumber>,
         run_max: Option<RunNumber>,
         filters: Option<Py<PyAny>>,
+        time_as_str: bool,
     ) -> PyResult<Py<PyDict>> {
         let names = extract_name_list(condition_names)?;
         let ctx =
             parse_context(py, run_period, runs, run_min, run_max, filters).unwrap_or_default();
-        let data = self.inner.fetch(names, &ctx).map_err(py_rcdb_error)?;
+        let db = self.db()?;
+        let data = py.detach(|| db.fetch(names, &ctx)).map_err(py_rcdb_error)?;
         let runs_dict = PyDict::new(py);
         for (run, values) in data {
             let value_dict = PyDict::new(py);
             for (name, value) in values {
-                let py_value = value_to_python(py, &value)?;
+                let py_value = value_to_python(py, &value, time_as_str)?;
                 value_dict.set_item(name, py_value)?;
             }
             runs_dict.set_item(run, value_dict)?;
@@ -197,13 +496,41 @@ impl PyRCDB {
         Ok(runs_dict.unbind())
     }
 
-    /// fetch_runs(self, context=None)
+    /// fetch_async(self, condition_names, *, run_period=None, runs=None, run_min=None, run_max=None, filters=None, time_as_str=False)
+    ///
+    /// Awaitable variant of :meth:`RCDB.fetch`.
     ///
     /// Parameters
     /// ----------
+    /// Same as :meth:`RCDB.fetch`.
+    ///
+    /// Returns
+    /// -------
+    /// asyncio.Future[dict[int, dict[str, object]]]
+    ///     Future resolving to the same mapping returned by :meth:`RCDB.fetch`.
+    ///
+    /// Notes
+    /// -----
+    /// The query runs on the event loop's default executor with the GIL released, so the
+    /// event loop is not blocked. Must be called while an event loop is running.
+    #[pyo3(signature = (*args, **kwargs))]
+    pub fn fetch_async<'py>(
+        slf: &Bound<'py, Self>,
+        args: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        run_in_executor(slf.py(), slf.getattr("fetch")?, args, kwargs)
+    }
+
+    /// fetch_dataframe(self, condition_names, *, run_period=None, runs=None, run_min=None, run_max=None, filters=None, backend="pandas", time_as_str=False, index_by_run=False, dtype_backend="numpy")
+    ///
+    /// Parameters
+    /// ----------
+    /// condition_names : Sequence[str]
+    ///     Condition names to retrieve per run.
     /// run_period : str, optional
     ///     The run period to use (short name, e.g. "S17", "F18").
-    /// runs : Sequence[int], optional
+    /// runs : Sequence[int] or numpy.ndarray, optional
     ///     Explicit list of run numbers. Duplicates are ignored.
     /// run_min : int, optional
     ///     Inclusive start of the run range. Defaults to the first run in RCDB
@@ -213,32 +540,585 @@ impl PyRCDB {
     ///     only ``run_min`` is provided.
     /// filters : Expr or Sequence[Expr], optional
     ///     One or more expressions that must evaluate to true.
+    /// backend : {"pandas", "polars"}, default "pandas"
+    ///     DataFrame library used to build the result.
+    /// time_as_str : bool, default False
+    ///     Return ``time`` conditions as RFC 3339 strings (the previous behaviour) instead of
+    ///     timezone-aware UTC ``datetime.datetime`` objects.
+    /// index_by_run : bool, default False
+    ///     Use the run numbers as the DataFrame index instead of a ``run`` column. Only
+    ///     supported by the pandas backend.
+    /// dtype_backend : {"numpy", "numpy_nullable"}, default "numpy"
+    ///     With ``"numpy_nullable"``, pandas columns get nullable dtypes from the condition
+    ///     types (``Int64``, ``Float64``, ``boolean``, ``string``, and
+    ///     ``datetime64[us, UTC]``), so missing values are ``pd.NA``/``NaT`` and integer
+    ///     columns stay integers. Ignored by the polars backend, which always infers typed
+    ///     columns.
     ///
     /// Returns
     /// -------
-    /// list[int]
-    ///     Sorted run numbers satisfying the run number specifications and filters.
+    /// pandas.DataFrame | polars.DataFrame
+    ///     One row per run (sorted by run number) with a ``run`` column (or index, with
+    ///     ``index_by_run``) followed by one column per requested condition. Missing values are
+    ///     ``None`` unless ``dtype_backend="numpy_nullable"``.
     ///
     /// Notes
     /// -----
     /// The run_period, runs, and (run_min, run_max) arguments are mutually exclusive.
-    #[pyo3(signature = (*, run_period=None, runs=None, run_min=None, run_max=None, filters=None))]
-    pub fn fetch_runs(
+    #[pyo3(signature = (condition_names, *, run_period=None, runs=None, run_min=None, run_max=None, filters=None, backend="pandas", time_as_str=false, index_by_run=false, dtype_backend="numpy"))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn fetch_dataframe<'py>(
+        &self,
+        py: Python<'py>,
+        condition_names: &Bound<'py, PyAny>,
+        run_period: Option<String>,
+        runs: Option<RunList>,
+        run_min: Option<RunNumber>,
+        run_max: Option<RunNumber>,
+        filters: Option<Py<PyAny>>,
+        backend: &str,
+        time_as_str: bool,
+        index_by_run: bool,
+        dtype_backend: &str,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        if !matches!(backend, "pandas" | "polars") {
+            return Err(PyRuntimeError::new_err(format!(
+                "unsupported DataFrame backend '{backend}' (expected 'pandas' or 'polars')"
+            )));
+        }
+        if !matches!(dtype_backend, "numpy" | "numpy_nullable") {
+            return Err(PyRuntimeError::new_err(format!(
+                "unsupported dtype backend '{dtype_backend}' (expected 'numpy' or 'numpy_nullable')"
+            )));
+        }
+        if index_by_run && backend == "polars" {
+            return Err(PyRuntimeError::new_err(
+                "polars DataFrames have no index; use index_by_run=False",
+            ));
+        }
+        let ctx =
+            parse_context(py, run_period, runs, run_min, run_max, filters).unwrap_or_default();
+        let frame = self.fetch_columns(py, condition_names, &ctx, time_as_str)?;
+        let mut df = py.import(backend)?.call_method1("DataFrame", (&frame,))?;
+        if backend == "pandas" {
+            if dtype_backend == "numpy_nullable" {
+                let db = self.db()?;
+                let dtypes = PyDict::new(py);
+                for name in frame.keys().iter().skip(1) {
+                    let name: String = name.extract()?;
+                    let value_type = db
+                        .condition_type(&name)
+                        .map(|ct| ct.value_type())
+                        .ok_or_else(|| {
+                            py_rcdb_error(RCDBError::ConditionTypeNotFound(name.clone()))
+                        })?;
+                    dtypes.set_item(name, nullable_dtype(value_type, time_as_str))?;
+                }
+                df = df.call_method1("astype", (dtypes,))?;
+            }
+            if index_by_run {
+                df = df.call_method1("set_index", ("run",))?;
+            }
+        }
+        Ok(df)
+    }
+
+    /// fetch_awkward(self, condition_names, *, run_period=None, runs=None, run_min=None, run_max=None, filters=None, time_as_str=False)
+    ///
+    /// Parameters
+    /// ----------
+    /// condition_names : Sequence[str]
+    ///     Condition names to retrieve per run.
+    /// run_period : str, optional
+    ///     The run period to use (short name, e.g. "S17", "F18").
+    /// runs : Sequence[int] or numpy.ndarray, optional
+    ///     Explicit list of run numbers. Duplicates are ignored.
+    /// run_min : int, optional
+    ///     Inclusive start of the run range. Defaults to the first run in RCDB
+    ///     when only ``run_max`` is provided.
+    /// run_max : int, optional
+    ///     Inclusive end of the run range. Defaults to the last run in RCDB when
+    ///     only ``run_min`` is provided.
+    /// filters : Expr or Sequence[Expr], optional
+    ///     One or more expressions that must evaluate to true.
+    /// time_as_str : bool, default False
+    ///     Return ``time`` conditions as RFC 3339 strings (the previous behaviour) instead of
+    ///     timezone-aware UTC ``datetime.datetime`` objects.
+    ///
+    /// Returns
+    /// -------
+    /// awkward.Array
+    ///     Record array with one entry per run (sorted by run number) and fields ``run``
+    ///     followed by one option-typed field per requested condition.
+    ///
+    /// Notes
+    /// -----
+    /// Requires the ``awkward`` extra. The run_period, runs, and (run_min, run_max)
+    /// arguments are mutually exclusive.
+    #[pyo3(signature = (condition_names, *, run_period=None, runs=None, run_min=None, run_max=None, filters=None, time_as_str=false))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn fetch_awkward<'py>(
+        &self,
+        py: Python<'py>,
+        condition_names: &Bound<'py, PyAny>,
+        run_period: Option<String>,
+        runs: Option<RunList>,
+        run_min: Option<RunNumber>,
+        run_max: Option<RunNumber>,
+        filters: Option<Py<PyAny>>,
+        time_as_str: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx =
+            parse_context(py, run_period, runs, run_min, run_max, filters).unwrap_or_default();
+        let frame = self.fetch_columns(py, condition_names, &ctx, time_as_str)?;
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("depth_limit", 1)?;
+        py.import("awkward")?
+            .call_method("zip", (frame,), Some(&kwargs))
+    }
+
+    /// fetch_arrays(self, condition_names, *, run_period=None, runs=None, run_min=None, run_max=None, filters=None, int_fill=-1)
+    ///
+    /// Parameters
+    /// ----------
+    /// condition_names : Sequence[str]
+    ///     Condition names to retrieve per run.
+    /// run_period : str, optional
+    ///     The run period to use (short name, e.g. "S17", "F18").
+    /// runs : Sequence[int] or numpy.ndarray, optional
+    ///     Explicit list of run numbers. Duplicates are ignored.
+    /// run_min : int, optional
+    ///     Inclusive start of the run range. Defaults to the first run in RCDB
+    ///     when only ``run_max`` is provided.
+    /// run_max : int, optional
+    ///     Inclusive end of the run range. Defaults to the last run in RCDB when
+    ///     only ``run_min`` is provided.
+    /// filters : Expr or Sequence[Expr], optional
+    ///     One or more expressions that must evaluate to true.
+    /// int_fill : int, default -1
+    ///     Sentinel stored in ``int`` columns for runs without a value.
+    ///
+    /// Returns
+    /// -------
+    /// dict[str, numpy.ndarray]
+    ///     ``"run"`` (int64) followed by one array per condition, aligned by run. ``int``
+    ///     conditions are int64 (missing values set to ``int_fill``), ``float`` conditions are
+    ///     float64 (missing values are NaN), ``bool`` conditions are bool (missing values are
+    ///     False), ``time`` conditions are ``datetime64[us]`` in UTC (missing values are NaT),
+    ///     and textual conditions are object arrays of ``str`` (missing values are ``None``).
+    ///
+    /// Notes
+    /// -----
+    /// Requires the ``numpy`` extra. Arrays are filled in Rust, so no Python object is
+    /// created per value for numeric, boolean, or time conditions. The run_period, runs,
+    /// and (run_min, run_max) arguments are mutually exclusive.
+    #[pyo3(signature = (condition_names, *, run_period=None, runs=None, run_min=None, run_max=None, filters=None, int_fill=-1))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn fetch_arrays<'py>(
+        &self,
+        py: Python<'py>,
+        condition_names: &Bound<'py, PyAny>,
+        run_period: Option<String>,
+        runs: Option<RunList>,
+        run_min: Option<RunNumber>,
+        run_max: Option<RunNumber>,
+        filters: Option<Py<PyAny>>,
+        int_fill: i64,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let mut names = extract_name_list(condition_names)?;
+        let mut seen = HashSet::new();
+        names.retain(|name| seen.insert(name.clone()));
+        let db = self.db()?;
+        let value_types = names
+            .iter()
+            .map(|name| {
+                db.condition_type(name)
+                    .map(|ct| ct.value_type())
+                    .ok_or_else(|| py_rcdb_error(RCDBError::ConditionTypeNotFound(name.clone())))
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        let ctx =
+            parse_context(py, run_period, runs, run_min, run_max, filters).unwrap_or_default();
+        let data = py
+            .detach(|| db.fetch(&names, &ctx))
+            .map_err(py_rcdb_error)?;
+        let arrays = PyDict::new(py);
+        let run_column: Vec<RunNumber> = data.keys().copied().collect();
+        arrays.set_item("run", PyArray1::from_vec(py, run_column))?;
+        for (name, value_type) in names.iter().zip(value_types) {
+            let column = data.values().map(|values| values.get(name));
+            let array = match value_type {
+                ValueType::Int => PyArray1::from_iter(
+                    py,
+                    column.map(|value| value.and_then(Value::as_int).unwrap_or(int_fill)),
+                )
+                .into_any(),
+                ValueType::Float => PyArray1::from_iter(
+                    py,
+                    column.map(|value| value.and_then(Value::as_float).unwrap_or(f64::NAN)),
+                )
+                .into_any(),
+                ValueType::Bool => PyArray1::from_iter(
+                    py,
+                    column.map(|value| value.and_then(Value::as_bool).unwrap_or(false)),
+                )
+                .into_any(),
+                ValueType::Time => PyArray1::<Datetime<Microseconds>>::from_iter(
+                    py,
+                    column.map(|value| {
+                        Datetime::from(
+                            value
+                                .and_then(Value::as_time)
+                                .map_or(i64::MIN, |dt| dt.timestamp_micros()),
+                        )
+                    }),
+                )
+                .into_any(),
+                ValueType::String | ValueType::Json | ValueType::Blob => PyArray1::from_vec(
+                    py,
+                    column
+                        .map(|value| match value.and_then(Value::as_string) {
+                            Some(text) => PyString::new(py, text).into_any().unbind(),
+                            None => py.None(),
+                        })
+                        .collect(),
+                )
+                .into_any(),
+            };
+            arrays.set_item(name, array)?;
+        }
+        Ok(arrays)
+    }
+
+    /// fetch_arrow(self, condition_names, *, run_period=None, runs=None, run_min=None, run_max=None, filters=None)
+    ///
+    /// Parameters
+    /// ----------
+    /// condition_names : Sequence[str]
+    ///     Condition names to retrieve per run.
+    /// run_period : str, optional
+    ///     The run period to use (short name, e.g. "S17", "F18").
+    /// runs : Sequence[int] or numpy.ndarray, optional
+    ///     Explicit list of run numbers. Duplicates are ignored.
+    /// run_min : int, optional
+    ///     Inclusive start of the run range. Defaults to the first run in RCDB
+    ///     when only ``run_max`` is provided.
+    /// run_max : int, optional
+    ///     Inclusive end of the run range. Defaults to the last run in RCDB when
+    ///     only ``run_min`` is provided.
+    /// filters : Expr or Sequence[Expr], optional
+    ///     One or more expressions that must evaluate to true.
+    ///
+    /// Returns
+    /// -------
+    /// ArrowTable
+    ///     Table with an ``int64`` ``run`` column followed by one nullable column per
+    ///     condition (``int64``, ``float64``, ``bool``, ``timestamp[us, UTC]``, or ``utf8``),
+    ///     consumable by any library implementing the Arrow PyCapsule interface.
+    ///
+    /// Notes
+    /// -----
+    /// Only available when built with the ``arrow`` feature. The run_period, runs, and
+    /// (run_min, run_max) arguments are mutually exclusive.
+    #[cfg(feature = "arrow")]
+    #[pyo3(signature = (condition_names, *, run_period=None, runs=None, run_min=None, run_max=None, filters=None))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn fetch_arrow(
+        &self,
+        py: Python<'_>,
+        condition_names: &Bound<'_, PyAny>,
+        run_period: Option<String>,
+        runs: Option<RunList>,
+        run_min: Option<RunNumber>,
+        run_max: Option<RunNumber>,
+        filters: Option<Py<PyAny>>,
+    ) -> PyResult<PyArrowTable> {
+        let mut names = extract_name_list(condition_names)?;
+        let mut seen = HashSet::new();
+        names.retain(|name| seen.insert(name.clone()));
+        let db = self.db()?;
+        let value_types = names
+            .iter()
+            .map(|name| {
+                db.condition_type(name)
+                    .map(|ct| ct.value_type())
+                    .ok_or_else(|| py_rcdb_error(RCDBError::ConditionTypeNotFound(name.clone())))
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        let ctx =
+            parse_context(py, run_period, runs, run_min, run_max, filters).unwrap_or_default();
+        let data = py
+            .detach(|| db.fetch(&names, &ctx))
+            .map_err(py_rcdb_error)?;
+        let mut columns = vec![ArrowColumn::new(
+            "run",
+            ArrowValues::Int64(data.keys().copied().collect()),
+        )];
+        for (name, value_type) in names.iter().zip(value_types) {
+            let validity = data
+                .values()
+                .map(|values| values.contains_key(name))
+                .collect();
+            let column = data.values().map(|values| values.get(name));
+            let values = match value_type {
+                ValueType::Int => ArrowValues::Int64(
+                    column
+                        .map(|value| value.and_then(Value::as_int).unwrap_or_default())
+                        .collect(),
+                ),
+                ValueType::Float => ArrowValues::Float64(
+                    column
+                        .map(|value| value.and_then(Value::as_float).unwrap_or_default())
+                        .collect(),
+                ),
+                ValueType::Bool => ArrowValues::Boolean(
+                    column
+                        .map(|value| value.and_then(Value::as_bool).unwrap_or_default())
+                        .collect(),
+                ),
+                ValueType::Time => ArrowValues::TimestampMicros(
+                    column
+                        .map(|value| {
+                            value
+                                .and_then(Value::as_time)
+                                .map_or(0, |dt| dt.timestamp_micros())
+                        })
+                        .collect(),
+                ),
+                ValueType::String | ValueType::Json | ValueType::Blob => ArrowValues::Utf8(
+                    column
+                        .map(|value| {
+                            value
+                                .and_then(Value::as_string)
+                                .unwrap_or_default()
+                                .to_string()
+                        })
+                        .collect(),
+                ),
+            };
+            columns.push(ArrowColumn::new(name, values).with_validity(validity));
+        }
+        Ok(PyArrowTable { columns })
+    }
+
+    /// fetch_iter(self, condition_names, *, run_period=None, runs=None, run_min=None, run_max=None, filters=None, chunk_size=1000, time_as_str=False)
+    ///
+    /// Parameters
+    /// ----------
+    /// condition_names : Sequence[str]
+    ///     Condition names to retrieve per run.
+    /// run_period : str, optional
+    ///     The run period to use (short name, e.g. "S17", "F18").
+    /// runs : Sequence[int] or numpy.ndarray, optional
+    ///     Explicit list of run numbers. Duplicates are ignored.
+    /// run_min : int, optional
+    ///     Inclusive start of the run range. Defaults to the first run in RCDB
+    ///     when only ``run_max`` is provided.
+    /// run_max : int, optional
+    ///     Inclusive end of the run range. Defaults to the last run in RCDB when
+    ///     only ``run_min`` is provided.
+    /// filters : Expr or Sequence[Expr], optional
+    ///     One or more expressions that must evaluate to true.
+    /// chunk_size : int, default 1000
+    ///     Number of runs loaded from the database at a time.
+    /// time_as_str : bool, default False
+    ///     Return ``time`` conditions as RFC 3339 strings (the previous behaviour) instead of
+    ///     timezone-aware UTC ``datetime.datetime`` objects.
+    ///
+    /// Returns
+    /// -------
+    /// FetchIter
+    ///     Iterator yielding ``(run, dict[str, object])`` pairs in run order.
+    ///
+    /// Notes
+    /// -----
+    /// The matching run numbers are resolved up front, but condition values are only
+    /// loaded ``chunk_size`` runs at a time (with the GIL released) as the iterator
+    /// advances. The run_period, runs, and (run_min, run_max) arguments are mutually
+    /// exclusive.
+    #[pyo3(signature = (condition_names, *, run_period=None, runs=None, run_min=None, run_max=None, filters=None, chunk_size=1000, time_as_str=false))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn fetch_iter(
         &self,
         py: Python<'_>,
+        condition_names: &Bound<'_, PyAny>,
         run_period: Option<String>,
-        runs: Option<Vec<RunNumber>>,
+        runs: Option<RunList>,
         run_min: Option<RunNumber>,
         run_max: Option<RunNumber>,
         filters: Option<Py<PyAny>>,
-    ) -> PyResult<Vec<RunNumber>> {
+        chunk_size: usize,
+        time_as_str: bool,
+    ) -> PyResult<PyFetchIter> {
+        if chunk_size == 0 {
+            return Err(PyRuntimeError::new_err("chunk_size must be positive"));
+        }
+        let names = extract_name_list(condition_names)?;
         let ctx =
             parse_context(py, run_period, runs, run_min, run_max, filters).unwrap_or_default();
-        self.inner.fetch_runs(&ctx).map_err(py_rcdb_error)
+        let db = self.db()?;
+        let inner = py
+            .detach(|| db.fetch_iter(&names, &ctx))
+            .map_err(py_rcdb_error)?
+            .with_chunk_size(chunk_size);
+        Ok(PyFetchIter {
+            inner,
+            time_as_str,
+            closed: self.closed.clone(),
+        })
+    }
+
+    /// fetch_runs(self, *, run_period=None, runs=None, run_min=None, run_max=None, filters=None, as_numpy=False)
+    ///
+    /// Parameters
+    /// ----------
+    /// run_period : str, optional
+    ///     The run period to use (short name, e.g. "S17", "F18").
+    /// runs : Sequence[int] or numpy.ndarray, optional
+    ///     Explicit list of run numbers. Duplicates are ignored.
+    /// run_min : int, optional
+    ///     Inclusive start of the run range. Defaults to the first run in RCDB
+    ///     when only ``run_max`` is provided.
+    /// run_max : int, optional
+    ///     Inclusive end of the run range. Defaults to the last run in RCDB when
+    ///     only ``run_min`` is provided.
+    /// filters : Expr or Sequence[Expr], optional
+    ///     One or more expressions that must evaluate to true.
+    ///
+    /// as_numpy : bool, default False
+    ///     Return the run numbers as a numpy ``int64`` array instead of a list.
+    ///
+    /// Returns
+    /// -------
+    /// list[int] or numpy.ndarray
+    ///     Sorted run numbers satisfying the run number specifications and filters.
+    ///
+    /// Notes
+    /// -----
+    /// The run_period, runs, and (run_min, run_max) arguments are mutually exclusive. ``runs``
+    /// may be a numpy integer array.
+    #[pyo3(signature = (*, run_period=None, runs=None, run_min=None, run_max=None, filters=None, as_numpy=false))]
+    #[allow(clippy::too_many_arguments)]
+    pub fn fetch_runs<'py>(
+        &self,
+        py: Python<'py>,
+        run_period: Option<String>,
+        runs: Option<RunList>,
+        run_min: Option<RunNumber>,
+        run_max: Option<RunNumber>,
+        filters: Option<Py<PyAny>>,
+        as_numpy: bool,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let ctx =
+            parse_context(py, run_period, runs, run_min, run_max, filters).unwrap_or_default();
+        let db = self.db()?;
+        let run_numbers = py.detach(|| db.fetch_runs(&ctx)).map_err(py_rcdb_error)?;
+        if as_numpy {
+            Ok(PyArray1::from_vec(py, run_numbers).into_any())
+        } else {
+            run_numbers.into_pyobject(py)
+        }
+    }
+
+    /// fetch_runs_async(self, *, run_period=None, runs=None, run_min=None, run_max=None, filters=None)
+    ///
+    /// Awaitable variant of :meth:`RCDB.fetch_runs`.
+    ///
+    /// Parameters
+    /// ----------
+    /// Same as :meth:`RCDB.fetch_runs`.
+    ///
+    /// Returns
+    /// -------
+    /// asyncio.Future[list[int]]
+    ///     Future resolving to the sorted run numbers returned by :meth:`RCDB.fetch_runs`.
+    #[pyo3(signature = (*args, **kwargs))]
+    pub fn fetch_runs_async<'py>(
+        slf: &Bound<'py, Self>,
+        args: &Bound<'py, PyTuple>,
+        kwargs: Option<&Bound<'py, PyDict>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        run_in_executor(slf.py(), slf.getattr("fetch_runs")?, args, kwargs)
+    }
+
+    /// files(self, run)
+    ///
+    /// Parameters
+    /// ----------
+    /// run : int
+    ///     Run number whose attached configuration files should be loaded.
+    ///
+    /// Returns
+    /// -------
+    /// dict[str, bytes]
+    ///     Raw file content keyed by the file's recorded path, ordered by path. Empty when no
+    ///     files are attached to the run.
+    ///
+    /// Examples
+    /// --------
+    /// >>> for path, content in db.files(30_274).items():
+    /// ...     print(path, len(content))
+    pub fn files<'py>(&self, py: Python<'py>, run: RunNumber) -> PyResult<Bound<'py, PyDict>> {
+        let db = self.db()?;
+        let files = py.detach(|| db.files(run)).map_err(py_rcdb_error)?;
+        let out = PyDict::new(py);
+        for file in files {
+            out.set_item(file.path(), PyBytes::new(py, file.content()))?;
+        }
+        Ok(out)
+    }
+
+    /// select(self, **lookups)
+    ///
+    /// Build a filter expression from Django-style keyword lookups.
+    ///
+    /// Parameters
+    /// ----------
+    /// **lookups
+    ///     ``name=value`` or ``name__<lookup>=value`` pairs. Supported lookups are ``eq``
+    ///     (the default), ``ne``, ``gt``, ``ge``, ``lt``, ``le``, and ``in`` for numeric and time
+    ///     conditions, ``eq``, ``ne``, ``in``, and ``contains`` for string conditions, and
+    ///     ``eq``, ``ne``, and ``exists`` for boolean conditions. The condition builder is chosen
+    ///     from the condition's type in the database.
+    ///
+    /// Returns
+    /// -------
+    /// Expr
+    ///     Expression requiring every lookup to hold.
+    ///
+    /// Raises
+    /// ------
+    /// ConditionNotFoundError
+    ///     If a lookup names a condition that does not exist.
+    /// RuntimeError
+    ///     If a lookup is not supported for the condition's type or the value has the wrong type.
+    ///
+    /// Examples
+    /// --------
+    /// >>> expr = db.select(run_type="hd_all.tsg", beam_current__gt=2.0, event_count__gt=500_000)
+    /// >>> runs = db.fetch_runs(run_period="s17", filters=expr)
+    #[pyo3(signature = (**lookups))]
+    pub fn select(&self, lookups: Option<&Bound<'_, PyDict>>) -> PyResult<PyExpr> {
+        let db = self.db()?;
+        let mut exprs = Vec::new();
+        for (key, value) in lookups.into_iter().flat_map(|lookups| lookups.iter()) {
+            let key: String = key.extract()?;
+            let (name, lookup) = match key.rsplit_once("__") {
+                Some((name, lookup)) if SELECT_LOOKUPS.contains(&lookup) => (name, lookup),
+                _ => (key.as_str(), "eq"),
+            };
+            let value_type = db
+                .condition_type(name)
+                .ok_or_else(|| py_rcdb_error(RCDBError::ConditionTypeNotFound(name.to_string())))?
+                .value_type();
+            exprs.push(lookup_expr(name, value_type, lookup, &value)?);
+        }
+        Ok(PyExpr::new(conditions::all(exprs)))
     }
 
     fn __repr__(&self) -> String {
-        format!("RCDB(path='{}')", self.inner.connection_path())
+        let state = if self.closed() { ", closed" } else { "" };
+        format!("RCDB(path='{}'{state})", self.connection_path)
     }
 
     fn __str__(&self) -> String {
@@ -246,6 +1126,69 @@ impl PyRCDB {
     }
 }
 
+/// Columnar RCDB fetch result exported through the Arrow PyCapsule interface.
+///
+/// Pass it to ``pyarrow.table``, ``polars.DataFrame``, ``duckdb.arrow``, or any other consumer of
+/// ``__arrow_c_stream__``; no pandas installation is needed.
+#[cfg(feature = "arrow")]
+#[pyclass(name = "ArrowTable", module = "gluex_rcdb", frozen)]
+pub struct PyArrowTable {
+    columns: Vec<ArrowColumn>,
+}
+
+#[cfg(feature = "arrow")]
+#[pymethods]
+impl PyArrowTable {
+    /// list[str]: Column names, starting with ``"run"``.
+    #[getter]
+    fn column_names(&self) -> Vec<String> {
+        self.columns
+            .iter()
+            .map(|column| column.name().to_string())
+            .collect()
+    }
+
+    /// __arrow_c_stream__(self, requested_schema=None)
+    ///
+    /// Parameters
+    /// ----------
+    /// requested_schema : object, optional
+    ///     Ignored; columns are always exported with their native types.
+    ///
+    /// Returns
+    /// -------
+    /// PyCapsule
+    ///     ``arrow_array_stream`` capsule yielding the table as a single record batch.
+    #[pyo3(signature = (requested_schema=None))]
+    fn __arrow_c_stream__<'py>(
+        &self,
+        py: Python<'py>,
+        requested_schema: Option<Bound<'py, PyAny>>,
+    ) -> PyResult<Bound<'py, PyCapsule>> {
+        let _ = requested_schema;
+        PyCapsule::new_with_destructor(
+            py,
+            ArrowArrayStream::new(self.columns.clone()),
+            Some(c"arrow_array_stream".to_owned()),
+            |_, _| {},
+        )
+    }
+
+    fn __len__(&self) -> usize {
+        self.columns
+            .first()
+            .map_or(0, |column| column.values().len())
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ArrowTable(rows={}, columns={:?})",
+            self.__len__(),
+            self.column_names()
+        )
+    }
+}
+
 /// Builder used to construct integer condition expressions.
 #[pyclass(name = "IntCondition", module = "gluex_rcdb")]
 #[derive(Clone)]
@@ -750,8 +1693,43 @@ fn any(exprs: &Bound<'_, PyTuple>) -> PyResult<PyExpr> {
     Ok(PyExpr::new(conditions::any(tuple_to_exprs(exprs)?)))
 }
 
+/// Lazy iterator over per-run RCDB condition values.
+///
+/// Returned by :meth:`RCDB.fetch_iter`; yields ``(run, dict[str, object])`` pairs.
+#[pyclass(name = "FetchIter", module = "gluex_rcdb")]
+pub struct PyFetchIter {
+    inner: FetchIter,
+    time_as_str: bool,
+    closed: Closed,
+}
+
+#[pymethods]
+impl PyFetchIter {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<(RunNumber, Py<PyDict>)>> {
+        self.closed.check()?;
+        let inner = &mut self.inner;
+        let Some(entry) = py.detach(|| inner.next()) else {
+            return Ok(None);
+        };
+        let (run, values) = entry.map_err(py_rcdb_error)?;
+        let value_dict = PyDict::new(py);
+        for (name, value) in values {
+            value_dict.set_item(name, value_to_python(py, &value, self.time_as_str)?)?;
+        }
+        Ok(Some((run, value_dict.unbind())))
+    }
+}
+
 /// Common aliases for expressions used in RCDB filters.
-#[pyclass(name = "aliases", module = "gluex_rcdb")]
+///
+/// The module-level ``aliases`` instance exposes each alias as an attribute returning a fresh
+/// ``Expr``, so aliases are discoverable by IDE autocompletion. Aliases can also be looked up
+/// by name with ``aliases["is_production"]`` or :func:`alias`.
+#[pyclass(name = "Aliases", module = "gluex_rcdb", frozen)]
 #[derive(Clone)]
 pub struct Aliases;
 
@@ -851,9 +1829,51 @@ impl Aliases {
         Ok(PyExpr::new(conditions::aliases::approved_production(
             run_period
                 .parse()
-                .map_err(|e: RunPeriodError| PyRuntimeError::new_err(e.to_string()))?,
+                .map_err(|e: RunPeriodError| exceptions::RunPeriodError::new_err(e.to_string()))?,
         )))
     }
+    /// names(self)
+    ///
+    /// Returns
+    /// -------
+    /// list[str]
+    ///     Names of every attribute alias, in definition order.
+    pub fn names(&self) -> Vec<&'static str> {
+        conditions::aliases::NAMES.to_vec()
+    }
+    fn __getitem__(&self, name: &str) -> PyResult<PyExpr> {
+        alias(name)
+    }
+    fn __contains__(&self, name: &str) -> bool {
+        conditions::aliases::NAMES.contains(&name)
+    }
+    fn __repr__(&self) -> String {
+        format!("Aliases({})", conditions::aliases::NAMES.join(", "))
+    }
+}
+
+#[pyfunction(name = "alias", text_signature = "(name)")]
+/// alias(name)
+///
+/// Parameters
+/// ----------
+/// name : str
+///     Alias name, matching one of the attributes of ``aliases`` (for example
+///     ``"is_production"``).
+///
+/// Returns
+/// -------
+/// Expr
+///     A fresh expression for the named alias.
+///
+/// Raises
+/// ------
+/// KeyError
+///     If no alias with this name exists.
+fn alias(name: &str) -> PyResult<PyExpr> {
+    conditions::aliases::get(name)
+        .map(PyExpr::new)
+        .ok_or_else(|| PyKeyError::new_err(format!("unknown RCDB alias '{name}'")))
 }
 
 fn tuple_to_exprs(exprs: &Bound<'_, PyTuple>) -> PyResult<Vec<Expr>> {
@@ -877,13 +1897,127 @@ fn exprs_from_object(obj: Bound<'_, PyAny>) -> PyResult<Vec<Expr>> {
     ))
 }
 
+/// Lookup suffixes recognised by `RCDB.select`.
+const SELECT_LOOKUPS: [&str; 9] = [
+    "eq", "ne", "gt", "ge", "lt", "le", "in", "contains", "exists",
+];
+
+/// Builds the expression for a single `RCDB.select` lookup on a condition of `value_type`.
+fn lookup_expr(
+    name: &str,
+    value_type: ValueType,
+    lookup: &str,
+    value: &Bound<'_, PyAny>,
+) -> PyResult<Expr> {
+    let expr = match (value_type, lookup) {
+        (ValueType::Int, "eq") => conditions::int_cond(name).eq(value.extract()?),
+        (ValueType::Int, "ne") => conditions::int_cond(name).ne(value.extract()?),
+        (ValueType::Int, "gt") => conditions::int_cond(name).gt(value.extract()?),
+        (ValueType::Int, "ge") => conditions::int_cond(name).ge(value.extract()?),
+        (ValueType::Int, "lt") => conditions::int_cond(name).lt(value.extract()?),
+        (ValueType::Int, "le") => conditions::int_cond(name).le(value.extract()?),
+        (ValueType::Int, "in") => conditions::any(
+            value
+                .extract::<Vec<i64>>()?
+                .into_iter()
+                .map(|v| conditions::int_cond(name).eq(v)),
+        ),
+        (ValueType::Float, "eq") => conditions::float_cond(name).eq(value.extract()?),
+        (ValueType::Float, "ne") => conditions::float_cond(name).eq(value.extract()?).negate(),
+        (ValueType::Float, "gt") => conditions::float_cond(name).gt(value.extract()?),
+        (ValueType::Float, "ge") => conditions::float_cond(name).ge(value.extract()?),
+        (ValueType::Float, "lt") => conditions::float_cond(name).lt(value.extract()?),
+        (ValueType::Float, "le") => conditions::float_cond(name).le(value.extract()?),
+        (ValueType::Float, "in") => conditions::any(
+            value
+                .extract::<Vec<f64>>()?
+                .into_iter()
+                .map(|v| conditions::float_cond(name).eq(v)),
+        ),
+        (ValueType::String | ValueType::Json | ValueType::Blob, "eq") => {
+            conditions::string_cond(name).eq(value.extract::<String>()?)
+        }
+        (ValueType::String | ValueType::Json | ValueType::Blob, "ne") => {
+            conditions::string_cond(name).ne(value.extract::<String>()?)
+        }
+        (ValueType::String | ValueType::Json | ValueType::Blob, "in") => {
+            conditions::string_cond(name).isin(value.extract::<Vec<String>>()?)
+        }
+        (ValueType::String | ValueType::Json | ValueType::Blob, "contains") => {
+            conditions::string_cond(name).contains(value.extract::<String>()?)
+        }
+        (ValueType::Bool, "eq" | "ne") => {
+            let wanted = value.extract::<bool>()? == (lookup == "eq");
+            if wanted {
+                conditions::bool_cond(name).is_true()
+            } else {
+                conditions::bool_cond(name).is_false()
+            }
+        }
+        (ValueType::Bool, "exists") => {
+            if value.extract::<bool>()? {
+                conditions::bool_cond(name).exists()
+            } else {
+                conditions::bool_cond(name).exists().negate()
+            }
+        }
+        (ValueType::Time, "eq") => conditions::time_cond(name).eq(value.extract()?),
+        (ValueType::Time, "gt") => conditions::time_cond(name).gt(value.extract()?),
+        (ValueType::Time, "ge") => conditions::time_cond(name).ge(value.extract()?),
+        (ValueType::Time, "lt") => conditions::time_cond(name).lt(value.extract()?),
+        (ValueType::Time, "le") => conditions::time_cond(name).le(value.extract()?),
+        (ValueType::Time, "ne") => conditions::time_cond(name).eq(value.extract()?).negate(),
+        (ValueType::Time, "in") => conditions::any(
+            value
+                .extract::<Vec<DateTime<Utc>>>()?
+                .into_iter()
+                .map(|v| conditions::time_cond(name).eq(v)),
+        ),
+        _ => {
+            return Err(PyRuntimeError::new_err(format!(
+                "lookup '{lookup}' is not supported for {} condition '{name}'",
+                value_type.as_str()
+            )))
+        }
+    };
+    Ok(expr)
+}
+
 fn extract_expr(obj: &Bound<'_, PyAny>) -> PyResult<Expr> {
     let expr: Py<PyExpr> = obj.extract()?;
     let borrowed = expr.borrow(obj.py());
     Ok(borrowed.inner())
 }
 
-fn value_to_python(py: Python<'_>, value: &Value) -> PyResult<Py<PyAny>> {
+/// Schedules `func(*args, **kwargs)` on the running event loop's default executor.
+fn run_in_executor<'py>(
+    py: Python<'py>,
+    func: Bound<'py, PyAny>,
+    args: &Bound<'py, PyTuple>,
+    kwargs: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let partial_args: Vec<Bound<'py, PyAny>> = iter::once(func).chain(args.iter()).collect();
+    let call = py
+        .import("functools")?
+        .getattr("partial")?
+        .call(PyTuple::new(py, partial_args)?, kwargs)?;
+    py.import("asyncio")?
+        .call_method0("get_running_loop")?
+        .call_method1("run_in_executor", (py.None(), call))
+}
+
+/// Pandas nullable extension dtype used for a condition type by `fetch_dataframe`.
+fn nullable_dtype(value_type: ValueType, time_as_str: bool) -> &'static str {
+    match value_type {
+        ValueType::Int => "Int64",
+        ValueType::Float => "Float64",
+        ValueType::Bool => "boolean",
+        ValueType::Time if !time_as_str => "datetime64[us, UTC]",
+        ValueType::Time | ValueType::String | ValueType::Json | ValueType::Blob => "string",
+    }
+}
+
+fn value_to_python(py: Python<'_>, value: &Value, time_as_str: bool) -> PyResult<Py<PyAny>> {
     let obj = match value.value_type() {
         ValueType::String | ValueType::Json | ValueType::Blob => value
             .as_string()
@@ -913,7 +2047,11 @@ fn value_to_python(py: Python<'_>, value: &Value) -> PyResult<Py<PyAny>> {
         }
         ValueType::Time => {
             if let Some(dt) = value.as_time() {
-                PyString::new(py, &dt.to_rfc3339()).into_any().unbind()
+                if time_as_str {
+                    PyString::new(py, &dt.to_rfc3339()).into_any().unbind()
+                } else {
+                    dt.into_pyobject(py)?.into_any().unbind()
+                }
             } else {
                 py.None()
             }
@@ -928,11 +2066,16 @@ fn extract_name_list(names: &Bound<'_, PyAny>) -> PyResult<Vec<String>> {
         .map_err(|_| PyRuntimeError::new_err("condition_names must be a sequence of strings"))
 }
 
-#[pymodule]
+#[pymodule(gil_used = false)]
 /// Python module initializer for gluex_rcdb bindings.
 pub fn gluex_rcdb(py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
+    exceptions::register(m)?;
     m.add_class::<PyRCDB>()?;
+    m.add_class::<PyConditionType>()?;
     m.add_class::<PyExpr>()?;
+    #[cfg(feature = "arrow")]
+    m.add_class::<PyArrowTable>()?;
+    m.add_class::<PyFetchIter>()?;
     m.add_class::<PyIntField>()?;
     m.add_class::<PyFloatField>()?;
     m.add_class::<PyStringField>()?;
@@ -945,6 +2088,8 @@ pub fn gluex_rcdb(py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(time_cond, m)?)?;
     m.add_function(wrap_pyfunction!(all, m)?)?;
     m.add_function(wrap_pyfunction!(any, m)?)?;
+    m.add_function(wrap_pyfunction!(alias, m)?)?;
+    m.add_class::<Aliases>()?;
     let aliases = Py::new(py, Aliases)?;
     m.add("aliases", aliases)?;
     Ok(())
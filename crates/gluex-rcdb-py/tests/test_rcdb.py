@@ -2,7 +2,11 @@
 
 from __future__ import annotations
 
+import asyncio
 import os
+import pickle
+from concurrent.futures import ThreadPoolExecutor
+from datetime import datetime, timezone
 from pathlib import Path
 
 import pytest
@@ -44,6 +48,54 @@ def test_fetch_single_run_int_condition() -> None:
     assert value == 2
 
 
+def test_time_conditions_are_datetimes() -> None:
+    db = _open_db()
+    value = db.fetch(["run_start_time"], runs=[2])[2]["run_start_time"]
+    assert value == datetime(2015, 12, 8, 15, 47, 20, tzinfo=timezone.utc)
+    assert value.tzinfo is not None
+    legacy = db.fetch(["run_start_time"], runs=[2], time_as_str=True)[2]["run_start_time"]
+    assert legacy == value.isoformat()
+
+
+def test_open_accepts_pathlike() -> None:
+    db = rcdb.RCDB(_rcdb_path())
+    assert db.connection_path == str(_rcdb_path())
+    assert db.fetch_runs(runs=[2]) == [2]
+
+
+def test_context_manager_closes_connection() -> None:
+    with _open_db() as db:
+        assert not db.closed
+        assert db.fetch(["event_count"], runs=[2])[2]["event_count"] == 2
+    assert db.closed
+    assert "closed" in repr(db)
+    with pytest.raises(RuntimeError, match="closed"):
+        db.fetch_runs(runs=[2])
+    db.close()
+
+
+def test_fetch_iter_fails_after_close() -> None:
+    db = _open_db()
+    rows = db.fetch_iter(["event_count"], runs=[2])
+    db.close()
+    with pytest.raises(rcdb.RCDBError, match="closed"):
+        next(rows)
+
+
+def test_condition_type_introspection() -> None:
+    db = _open_db()
+    types = db.condition_types()
+    names = [ct.name for ct in types]
+    assert names == sorted(names)
+    assert "event_count" in names
+    assert db.has_condition("event_count")
+    assert not db.has_condition("definitely_not_a_condition")
+    event_count = db.condition_type("event_count")
+    assert event_count is not None
+    assert event_count.value_type == "int"
+    assert db.condition_type("definitely_not_a_condition") is None
+
+
 def test_fetch_with_filters() -> None:
     db = _open_db()
     data = db.fetch(
@@ -65,6 +117,33 @@ def test_fetch_with_filters() -> None:
         assert values["event_count"] > 50
 
 
+def test_select_matches_explicit_conditions() -> None:
+    db = _open_db()
+    explicit = db.fetch_runs(
+        run_min=1000,
+        run_max=1100,
+        filters=rcdb.all(
+            rcdb.string_cond("run_type").isin(["hd_all.tsg", "hd_all.tsg-m8"]),
+            rcdb.float_cond("beam_current").gt(0.1),
+            rcdb.int_cond("event_count").gt(50),
+        ),
+    )
+    selected = db.fetch_runs(
+        run_min=1000,
+        run_max=1100,
+        filters=db.select(
+            run_type__in=["hd_all.tsg", "hd_all.tsg-m8"],
+            beam_current__gt=0.1,
+            event_count__gt=50,
+        ),
+    )
+    assert selected == explicit
+    with pytest.raises(rcdb.ConditionNotFoundError):
+        db.select(definitely_not_a_condition=1)
+    with pytest.raises(RuntimeError, match="not supported"):
+        db.select(run_type__gt="hd_all.tsg")
+
+
 def test_fetch_runs_with_alias() -> None:
     db = _open_db()
     runs = db.fetch_runs(
@@ -74,3 +153,222 @@ def test_fetch_runs_with_alias() -> None:
     )
     assert runs
     assert all(10000 <= run <= 10300 for run in runs)
+
+
+def test_files_for_run() -> None:
+    db = _open_db()
+    assert db.files(-1) == {}
+    files = db.files(2)
+    assert list(files) == sorted(files)
+    assert all(isinstance(content, bytes) for content in files.values())
+
+
+def test_errors_use_exception_hierarchy() -> None:
+    db = _open_db()
+    with pytest.raises(rcdb.ConditionNotFoundError):
+        db.fetch(["definitely_not_a_condition"], runs=[2])
+    with pytest.raises(rcdb.EmptyConditionListError):
+        db.fetch_iter([], runs=[2])
+    assert issubclass(rcdb.ConditionNotFoundError, rcdb.RCDBError)
+    assert issubclass(rcdb.RCDBError, RuntimeError)
+
+
+def test_alias_namespace_and_lookup() -> None:
+    names = rcdb.aliases.names()
+    assert "is_production" in names
+    assert "is_production" in rcdb.aliases
+    assert "not_an_alias" not in rcdb.aliases
+    assert isinstance(rcdb.aliases.is_cosmic, rcdb.Expr)
+    assert rcdb.aliases.is_cosmic is not rcdb.aliases.is_cosmic
+    for name in names:
+        assert isinstance(getattr(rcdb.aliases, name), rcdb.Expr)
+    db = _open_db()
+    assert db.fetch_runs(run_min=1000, run_max=1100, filters=rcdb.alias("is_cosmic")) == (
+        db.fetch_runs(run_min=1000, run_max=1100, filters=rcdb.aliases["is_cosmic"])
+    )
+    with pytest.raises(KeyError):
+        rcdb.alias("not_an_alias")
+
+
+def test_expr_operators() -> None:
+    current = rcdb.float_cond("beam_current").gt(0.1)
+    events = rcdb.int_cond("event_count").gt(50)
+    assert str(current & events) == str(rcdb.all(current, events))
+    assert str(current | events) == str(rcdb.any(current, events))
+    assert str(~current).startswith("NOT")
+
+    db = _open_db()
+    combined = db.fetch_runs(run_min=1000, run_max=1100, filters=current & events)
+    nested = db.fetch_runs(
+        run_min=1000, run_max=1100, filters=rcdb.all(current, events)
+    )
+    assert combined == nested
+
+
+def test_expr_repr_shows_sql() -> None:
+    expr = rcdb.float_cond("beam_current").gt(2.0) & ~rcdb.int_cond("event_count").lt(10)
+    assert expr.sql == "(cond_0.float_value > 2.0 AND NOT (cond_1.int_value < 10))"
+    assert str(expr) in repr(expr)
+    assert expr.sql in repr(expr)
+    mismatched = rcdb.int_cond("x").eq(1) & rcdb.float_cond("x").gt(1.0)
+    with pytest.raises(rcdb.ConditionTypeMismatchError):
+        mismatched.sql
+    assert repr(mismatched).startswith("Expr(")
+
+
+def test_expr_pickle_round_trip() -> None:
+    expr = (
+        rcdb.string_cond("run_type").isin(["hd_all.tsg", "hd_all.tsg-m8"])
+        & ~rcdb.float_cond("beam_current").lt(0.1)
+    ) | rcdb.time_cond("run_start_time").ge(
+        datetime(2018, 8, 1, tzinfo=timezone.utc)
+    )
+    restored = pickle.loads(pickle.dumps(expr))
+    assert isinstance(restored, rcdb.Expr)
+    assert str(restored) == str(expr)
+    assert str(pickle.loads(pickle.dumps(rcdb.aliases.is_production))) == str(
+        rcdb.aliases.is_production
+    )
+
+
+def test_fetch_results_pickle_round_trip() -> None:
+    db = _open_db()
+    data = db.fetch(["event_count", "run_start_time"], run_min=1, run_max=2)
+    assert data
+    assert pickle.loads(pickle.dumps(data)) == data
+
+
+def test_fetch_iter_matches_fetch() -> None:
+    db = _open_db()
+    eager = db.fetch(["event_count"], run_min=1000, run_max=1100)
+    lazy = db.fetch_iter(["event_count"], run_min=1000, run_max=1100, chunk_size=7)
+    assert dict(lazy) == eager
+
+
+def test_fetch_async_matches_fetch() -> None:
+    db = _open_db()
+
+    async def query() -> tuple[dict, list[int]]:
+        return await asyncio.gather(
+            db.fetch_async(["event_count"], run_min=1000, run_max=1100),
+            db.fetch_runs_async(run_min=1000, run_max=1100),
+        )
+
+    values, runs = asyncio.run(query())
+    assert values == db.fetch(["event_count"], run_min=1000, run_max=1100)
+    assert runs == db.fetch_runs(run_min=1000, run_max=1100)
+
+
+def test_fetch_from_threads_matches_sequential() -> None:
+    db = _open_db()
+    run_ranges = [(1000, 1025), (1025, 1050), (1050, 1075), (1075, 1100)]
+
+    def query(bounds: tuple[int, int]) -> dict:
+        return db.fetch(["event_count"], run_min=bounds[0], run_max=bounds[1])
+
+    with ThreadPoolExecutor(max_workers=4) as pool:
+        threaded = list(pool.map(query, run_ranges))
+    assert threaded == [query(bounds) for bounds in run_ranges]
+
+
+def test_fetch_dataframe() -> None:
+    pytest.importorskip("pandas")
+    db = _open_db()
+    df = db.fetch_dataframe(["event_count", "beam_current"], run_min=1000, run_max=1100)
+    expected = db.fetch(["event_count", "beam_current"], run_min=1000, run_max=1100)
+    assert list(df.columns) == ["run", "event_count", "beam_current"]
+    assert df["run"].tolist() == sorted(expected)
+    for row in df.itertuples(index=False):
+        assert row.event_count == expected[row.run].get("event_count")
+
+
+def test_fetch_dataframe_indexed_with_nullable_dtypes() -> None:
+    pd = pytest.importorskip("pandas")
+    db = _open_db()
+    names = ["event_count", "beam_current", "run_type", "run_start_time"]
+    df = db.fetch_dataframe(
+        names, runs=[2, 3], index_by_run=True, dtype_backend="numpy_nullable"
+    )
+    expected = db.fetch(names, runs=[2, 3])
+    assert df.index.name == "run"
+    assert df.index.tolist() == sorted(expected)
+    assert list(df.columns) == names
+    assert df["event_count"].dtype == pd.Int64Dtype()
+    assert df["beam_current"].dtype == pd.Float64Dtype()
+    assert df["run_type"].dtype == pd.StringDtype()
+    assert str(df["run_start_time"].dtype) == "datetime64[us, UTC]"
+    for run, values in expected.items():
+        count = df.at[run, "event_count"]
+        if "event_count" in values:
+            assert count == values["event_count"]
+        else:
+            assert count is pd.NA
+    with pytest.raises(RuntimeError, match="no index"):
+        db.fetch_dataframe(names, runs=[2], backend="polars", index_by_run=True)
+
+
+def test_fetch_arrays() -> None:
+    np = pytest.importorskip("numpy")
+    db = _open_db()
+    names = ["event_count", "run_start_time"]
+    arrays = db.fetch_arrays(names, runs=[2, 3], int_fill=-999)
+    values = db.fetch(names, runs=[2, 3])
+    assert list(arrays) == ["run", *names]
+    assert arrays["run"].dtype == np.int64
+    assert arrays["run"].tolist() == sorted(values)
+    assert arrays["event_count"].dtype == np.int64
+    for run, count in zip(arrays["run"].tolist(), arrays["event_count"].tolist()):
+        assert count == values[run].get("event_count", -999)
+    assert arrays["run_start_time"].dtype == np.dtype("datetime64[us]")
+    assert arrays["run_start_time"][0] == np.datetime64("2015-12-08T15:47:20")
+    with pytest.raises(RuntimeError, match="not found"):
+        db.fetch_arrays(["definitely_not_a_condition"], runs=[2])
+
+
+def test_numpy_run_selections() -> None:
+    np = pytest.importorskip("numpy")
+    db = _open_db()
+    expected = db.fetch_runs(run_min=1000, run_max=1100)
+    for dtype in (np.int64, np.int32, np.uint32, np.int16):
+        runs = np.arange(1000, 1101, dtype=dtype)
+        assert db.fetch_runs(runs=runs) == expected
+    as_array = db.fetch_runs(run_min=1000, run_max=1100, as_numpy=True)
+    assert isinstance(as_array, np.ndarray)
+    assert as_array.dtype == np.int64
+    assert as_array.tolist() == expected
+    sliced = np.arange(1000, 1101, dtype=np.int64)[::2]
+    assert db.fetch_runs(runs=sliced) == [run for run in expected if run % 2 == 0]
+
+
+def test_fetch_arrow() -> None:
+    pa = pytest.importorskip("pyarrow")
+    db = _open_db()
+    names = ["event_count", "run_start_time"]
+    table = db.fetch_arrow(names, runs=[2, 3])
+    assert table.column_names == ["run", *names]
+    arrow_table = pa.table(table)
+    assert len(arrow_table) == len(table)
+    assert arrow_table.schema.field("run").type == pa.int64()
+    assert arrow_table.schema.field("event_count").type == pa.int64()
+    assert arrow_table.schema.field("run_start_time").type == pa.timestamp("us", tz="UTC")
+    values = db.fetch(names, runs=[2, 3])
+    rows = arrow_table.to_pylist()
+    assert [row["run"] for row in rows] == sorted(values)
+    for row in rows:
+        assert row["event_count"] == values[row["run"]].get("event_count")
+        assert row["run_start_time"] == values[row["run"]].get("run_start_time")
+
+
+def test_fetch_awkward() -> None:
+    ak = pytest.importorskip("awkward")
+    db = _open_db()
+    data = db.fetch(["event_count", "beam_current"], run_min=1000, run_max=1100)
+
+    records = db.fetch_awkward(
+        ["event_count", "beam_current", "event_count"], run_min=1000, run_max=1100
+    )
+    assert records.fields == ["run", "event_count", "beam_current"]
+    assert ak.to_list(records["run"]) == sorted(data)
+    assert ak.to_list(records["event_count"]) == [
+        data[run].get("event_count") for run in sorted(data)
+    ]
@@ -0,0 +1,92 @@
+use gluex_core::{limits::QueryBudget, query_plan::QueryPlanStep};
+
+pub use gluex_core::sqlite::{Row, SqlValue, SqliteBackend};
+
+use crate::RCDBResult;
+
+/// Storage behind a [`crate::database::RCDB`] handle.
+///
+/// The query layer (run selection, filter compilation, and the joins that collect condition
+/// values) is written once against this trait and issues plain `SELECT` statements over the RCDB
+/// schema; a backend only has to execute them. [`SqliteBackend`] serves `SQLite` snapshots, and
+/// other stores (a `MySQL` server, an HTTP service, an in-memory fixture) can be plugged in with
+/// [`crate::database::RCDB::from_backend`].
+pub trait StorageBackend: Send + Sync {
+    /// Returns the location reported for the database, such as a file path or URL.
+    fn location(&self) -> &str;
+
+    /// Runs a read-only statement with positional parameters and calls `on_row` for every
+    /// result row, in order, stopping at the first error it returns.
+    ///
+    /// Backends should stop a statement that is still running once `budget`'s deadline passes and
+    /// report it as the budget's timeout error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the statement fails, runs past the deadline, or `on_row` fails.
+    fn query(
+        &self,
+        sql: &str,
+        params: &[SqlValue],
+        budget: Option<&QueryBudget>,
+        on_row: &mut dyn FnMut(Row<'_>) -> RCDBResult<()>,
+    ) -> RCDBResult<()>;
+
+    /// Returns the plan the backend would use to run a statement, without running it.
+    /// Backends that cannot explain their statements return no steps.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the statement cannot be prepared.
+    fn explain(&self, sql: &str, params: &[SqlValue]) -> RCDBResult<Vec<QueryPlanStep>> {
+        let _ = (sql, params);
+        Ok(Vec::new())
+    }
+
+    /// Returns the index hint to place after `conditions AS alias` when joining conditions by
+    /// run number (such as `INDEXED BY name`), or `None` to let the backend choose.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the backend's indexes cannot be listed.
+    fn conditions_index_hint(&self) -> RCDBResult<Option<String>> {
+        Ok(None)
+    }
+
+    /// Returns the backend as a [`SqliteBackend`], if it is one.
+    fn sqlite(&self) -> Option<&SqliteBackend> {
+        None
+    }
+}
+
+impl StorageBackend for SqliteBackend {
+    fn location(&self) -> &str {
+        SqliteBackend::location(self)
+    }
+
+    fn query(
+        &self,
+        sql: &str,
+        params: &[SqlValue],
+        budget: Option<&QueryBudget>,
+        on_row: &mut dyn FnMut(Row<'_>) -> RCDBResult<()>,
+    ) -> RCDBResult<()> {
+        SqliteBackend::query(self, sql, params, budget, on_row)
+    }
+
+    fn explain(&self, sql: &str, params: &[SqlValue]) -> RCDBResult<Vec<QueryPlanStep>> {
+        Ok(SqliteBackend::explain(self, sql, params)?)
+    }
+
+    /// Hints the first index on `conditions` that covers `run_number`, which `SQLite` would
+    /// otherwise often skip for these joins.
+    fn conditions_index_hint(&self) -> RCDBResult<Option<String>> {
+        Ok(self
+            .index_on("conditions", "run_number")?
+            .map(|index_name| format!("INDEXED BY {index_name}")))
+    }
+
+    fn sqlite(&self) -> Option<&SqliteBackend> {
+        Some(self)
+    }
+}
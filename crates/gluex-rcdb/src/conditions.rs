@@ -1,15 +1,19 @@
-use std::{fmt, sync::Arc};
+use std::{collections::HashMap, fmt, sync::Arc};
 
 use chrono::{DateTime, Utc};
 use rusqlite::types::Value;
 
-use crate::{models::ValueType, RCDBError};
+use crate::{data::Value as ConditionValue, models::ValueType, RCDBError};
 
 /// Condition expression used to filter RCDB queries.
+///
+/// With the `serde` feature enabled, expressions can be serialized and deserialized so filters
+/// can be stored or sent to other processes.
 #[derive(Debug, Clone)]
 pub struct Expr(Arc<ExprInner>);
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 enum ExprInner {
     True,
     Comparison(Comparison),
@@ -18,6 +22,7 @@ enum ExprInner {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) struct Comparison {
     field: String,
     value_type: ValueType,
@@ -25,12 +30,14 @@ pub(crate) struct Comparison {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 enum GroupKind {
     And,
     Or,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 enum Operator {
     Bool(bool),
     IntEquals(i64),
@@ -74,6 +81,58 @@ impl Expr {
         }
     }
 
+    fn referenced_fields(&self, out: &mut Vec<(String, ValueType)>) {
+        match self.0.as_ref() {
+            ExprInner::True => {}
+            ExprInner::Comparison(cmp) => {
+                if !out.iter().any(|(name, _)| name == &cmp.field) {
+                    out.push((cmp.field.clone(), cmp.value_type));
+                }
+            }
+            ExprInner::Group { clauses, .. } => {
+                for clause in clauses {
+                    clause.referenced_fields(out);
+                }
+            }
+            ExprInner::Not(inner) => inner.referenced_fields(out),
+        }
+    }
+
+    /// Renders the SQL predicate generated for this expression, with parameters inlined.
+    ///
+    /// Conditions are given the placeholder aliases `cond_0`, `cond_1`, ... in order of first
+    /// reference and are assumed to have the type implied by their builder, so no database is
+    /// needed. The output is meant for debugging; real queries always bind parameters.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RCDBError::ConditionTypeMismatch`] if the same condition is compared as two
+    /// different types.
+    pub fn to_sql_preview(&self) -> Result<String, RCDBError> {
+        let mut fields = Vec::new();
+        self.referenced_fields(&mut fields);
+        let alias_lookup = |name: &str| -> Option<(String, ValueType)> {
+            fields
+                .iter()
+                .position(|(field, _)| field == name)
+                .map(|index| (format!("cond_{index}"), fields[index].1))
+        };
+        let mut params = Vec::new();
+        let template = self.to_sql(&alias_lookup, &mut params)?;
+        let mut params = params.into_iter();
+        let mut sql = String::with_capacity(template.len());
+        for ch in template.chars() {
+            if ch == '?' {
+                if let Some(param) = params.next() {
+                    sql.push_str(&format_sql_literal(&param));
+                    continue;
+                }
+            }
+            sql.push(ch);
+        }
+        Ok(sql)
+    }
+
     pub(crate) fn to_sql(
         &self,
         alias_lookup: &dyn Fn(&str) -> Option<(String, ValueType)>,
@@ -100,6 +159,50 @@ impl Expr {
         }
     }
 
+    /// Evaluates the expression against one run's condition values with the three-valued logic
+    /// of the generated SQL: `None` when the result depends on a condition the run has no value
+    /// for (a run is only selected when this is `Some(true)`).
+    pub(crate) fn evaluate(&self, values: &HashMap<String, ConditionValue>) -> Option<bool> {
+        match self.0.as_ref() {
+            ExprInner::True => Some(true),
+            ExprInner::Comparison(cmp) => cmp.evaluate(values.get(&cmp.field)),
+            ExprInner::Group { clauses, .. } if clauses.is_empty() => Some(true),
+            ExprInner::Group { kind, clauses } => {
+                let mut unknown = false;
+                for clause in clauses {
+                    match (kind, clause.evaluate(values)) {
+                        (GroupKind::And, Some(false)) => return Some(false),
+                        (GroupKind::Or, Some(true)) => return Some(true),
+                        (_, None) => unknown = true,
+                        _ => {}
+                    }
+                }
+                (!unknown).then_some(*kind == GroupKind::And)
+            }
+            ExprInner::Not(inner) => inner.evaluate(values).map(|result| !result),
+        }
+    }
+
+    /// Returns the innermost clause responsible for this expression not holding for `values`.
+    ///
+    /// `AND` groups are searched for their first clause that does not hold; comparisons, `OR`
+    /// groups, and negations are returned as they are.
+    pub(crate) fn failing_clause(&self, values: &HashMap<String, ConditionValue>) -> Expr {
+        if let ExprInner::Group {
+            kind: GroupKind::And,
+            clauses,
+        } = self.0.as_ref()
+        {
+            if let Some(clause) = clauses
+                .iter()
+                .find(|clause| clause.evaluate(values) != Some(true))
+            {
+                return clause.failing_clause(values);
+            }
+        }
+        self.clone()
+    }
+
     /// Negates the expression.
     #[must_use]
     pub fn negate(self) -> Expr {
@@ -128,6 +231,26 @@ impl Expr {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for Expr {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.0.as_ref().serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Expr {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        ExprInner::deserialize(deserializer).map(Expr::new)
+    }
+}
+
 impl fmt::Display for Expr {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.fmt_with(f)
@@ -203,6 +326,47 @@ impl Comparison {
         })
     }
 
+    /// Evaluates the comparison for one value, mirroring [`Comparison::to_sql`]: `None` when the
+    /// value is missing, since SQL compares against `NULL` as unknown.
+    // SQL compares REAL values exactly, so the float comparisons do as well.
+    #[allow(clippy::float_cmp)]
+    fn evaluate(&self, value: Option<&ConditionValue>) -> Option<bool> {
+        match (&self.operator, value) {
+            (Operator::Exists, value) => return Some(value.is_some()),
+            (Operator::StringIn(values), _) if values.is_empty() => return Some(false),
+            (_, None) => return None,
+            _ => {}
+        }
+        let value = value?;
+        Some(match &self.operator {
+            Operator::Bool(expected) => value.as_bool()? == *expected,
+            Operator::IntEquals(v) => value.as_int()? == *v,
+            Operator::IntNotEquals(v) => value.as_int()? != *v,
+            Operator::IntGt(v) => value.as_int()? > *v,
+            Operator::IntGe(v) => value.as_int()? >= *v,
+            Operator::IntLt(v) => value.as_int()? < *v,
+            Operator::IntLe(v) => value.as_int()? <= *v,
+            Operator::FloatEquals(v) => value.as_float()? == *v,
+            Operator::FloatGt(v) => value.as_float()? > *v,
+            Operator::FloatGe(v) => value.as_float()? >= *v,
+            Operator::FloatLt(v) => value.as_float()? < *v,
+            Operator::FloatLe(v) => value.as_float()? <= *v,
+            Operator::StringEquals(v) => value.as_string()? == v,
+            Operator::StringNotEquals(v) => value.as_string()? != v,
+            Operator::StringIn(values) => {
+                let text = value.as_string()?;
+                values.iter().any(|v| v == text)
+            }
+            Operator::StringContains(v) => value.as_string()?.contains(v.as_str()),
+            Operator::TimeEquals(v) => value.as_time()? == *v,
+            Operator::TimeGt(v) => value.as_time()? > *v,
+            Operator::TimeGe(v) => value.as_time()? >= *v,
+            Operator::TimeLt(v) => value.as_time()? < *v,
+            Operator::TimeLe(v) => value.as_time()? <= *v,
+            Operator::Exists => true,
+        })
+    }
+
     fn fmt_operator(&self) -> String {
         match &self.operator {
             Operator::Bool(v) => format!("{v}"),
@@ -289,6 +453,16 @@ fn push_time(params: &mut Vec<Value>, alias: &str, op: &str, value: &DateTime<Ut
     format!("{alias}.time_value {op} ?")
 }
 
+fn format_sql_literal(value: &Value) -> String {
+    match value {
+        Value::Null => "NULL".to_string(),
+        Value::Integer(v) => v.to_string(),
+        Value::Real(v) => format!("{v:?}"),
+        Value::Text(v) => format!("'{}'", v.replace('\'', "''")),
+        Value::Blob(v) => format!("<{} byte blob>", v.len()),
+    }
+}
+
 fn format_time(value: &DateTime<Utc>) -> String {
     value.format("%Y-%m-%d %H:%M:%S").to_string()
 }
@@ -796,6 +970,54 @@ pub mod aliases {
         int_cond("status").eq(0)
     }
 
+    /// Names of every parameterless alias, in the order they are defined in this module.
+    pub const NAMES: [&str; 18] = [
+        "is_production",
+        "is_2018production",
+        "is_primex_production",
+        "is_dirc_production",
+        "is_src_production",
+        "is_cpp_production",
+        "is_production_long",
+        "is_cosmic",
+        "is_empty_target",
+        "is_amorph_radiator",
+        "is_coherent_beam",
+        "is_field_off",
+        "is_field_on",
+        "status_calibration",
+        "status_approved_long",
+        "status_approved",
+        "status_unchecked",
+        "status_reject",
+    ];
+
+    /// Looks up a parameterless alias by name (see [`NAMES`]), returning `None` for unknown names.
+    #[must_use]
+    pub fn get(name: &str) -> Option<Expr> {
+        Some(match name {
+            "is_production" => is_production(),
+            "is_2018production" => is_2018production(),
+            "is_primex_production" => is_primex_production(),
+            "is_dirc_production" => is_dirc_production(),
+            "is_src_production" => is_src_production(),
+            "is_cpp_production" => is_cpp_production(),
+            "is_production_long" => is_production_long(),
+            "is_cosmic" => is_cosmic(),
+            "is_empty_target" => is_empty_target(),
+            "is_amorph_radiator" => is_amorph_radiator(),
+            "is_coherent_beam" => is_coherent_beam(),
+            "is_field_off" => is_field_off(),
+            "is_field_on" => is_field_on(),
+            "status_calibration" => status_calibration(),
+            "status_approved_long" => status_approved_long(),
+            "status_approved" => status_approved(),
+            "status_unchecked" => status_unchecked(),
+            "status_reject" => status_reject(),
+            _ => return None,
+        })
+    }
+
     /// Returns an expression which matches approved production runs for the given [`RunPeriod`].
     #[must_use]
     pub fn approved_production(run_period: RunPeriod) -> Expr {
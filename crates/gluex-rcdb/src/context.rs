@@ -1,8 +1,8 @@
-use std::ops::{Bound, RangeBounds};
+use std::ops::RangeBounds;
 
 use gluex_core::{
-    constants::{MAX_RUN_NUMBER, MIN_RUN_NUMBER},
     run_periods::RunPeriod,
+    run_set::{RunContext, RunSet},
     RunNumber,
 };
 
@@ -57,12 +57,8 @@ impl Context {
 
     /// Restricts the context to a single run period.
     #[must_use]
-    pub fn with_run_period(mut self, run_period: RunPeriod) -> Self {
-        self.selection = RunSelection::Range {
-            start: run_period.min_run(),
-            end: run_period.max_run(),
-        };
-        self
+    pub fn with_run_period(self, run_period: RunPeriod) -> Self {
+        self.with_run_set(run_period.into())
     }
 
     /// Restricts the context to a single run number.
@@ -74,33 +70,14 @@ impl Context {
 
     /// Restricts the context to the provided run numbers.
     #[must_use]
-    pub fn with_runs(mut self, runs: impl IntoIterator<Item = RunNumber>) -> Self {
-        let mut run_list: Vec<RunNumber> = runs.into_iter().collect();
-        run_list.sort_unstable();
-        run_list.dedup();
-        self.selection = RunSelection::Runs(run_list);
-        self
+    pub fn with_runs(self, runs: impl IntoIterator<Item = RunNumber>) -> Self {
+        self.with_run_set(RunSet::runs(runs))
     }
 
     /// Restricts the context to the inclusive range described by the [`RangeBounds`] passed as `run_range`.
     #[must_use]
-    pub fn with_run_range(mut self, run_range: impl RangeBounds<RunNumber>) -> Self {
-        let start = match run_range.start_bound() {
-            Bound::Included(&s) => s,
-            Bound::Excluded(&s) => s.saturating_add(1),
-            Bound::Unbounded => MIN_RUN_NUMBER,
-        };
-        let end = match run_range.end_bound() {
-            Bound::Included(&e) => e,
-            Bound::Excluded(&e) => e.saturating_sub(1),
-            Bound::Unbounded => MAX_RUN_NUMBER,
-        };
-        if start > end {
-            self.selection = RunSelection::Runs(Vec::new());
-        } else {
-            self.selection = RunSelection::Range { start, end };
-        }
-        self
+    pub fn with_run_range(self, run_range: impl RangeBounds<RunNumber>) -> Self {
+        self.with_run_set(RunSet::range(run_range))
     }
 
     /// Adds one or more predicate expressions that must all evaluate to true.
@@ -132,3 +109,45 @@ impl Context {
         &self.filters
     }
 }
+
+/// Builds an unfiltered context for the runs in `runs`, so a parsed selection string can be used
+/// directly (`"S18-41234".parse::<RunSet>()?.into()`).
+impl From<RunSet> for Context {
+    fn from(runs: RunSet) -> Self {
+        Context::default().with_run_set(runs)
+    }
+}
+
+impl RunContext for Context {
+    fn with_run_set(mut self, runs: RunSet) -> Self {
+        self.selection = runs.into();
+        self
+    }
+
+    fn run_set(&self) -> RunSet {
+        self.selection.clone().into()
+    }
+}
+
+impl From<RunSet> for RunSelection {
+    fn from(runs: RunSet) -> Self {
+        match runs {
+            RunSet::All => RunSelection::All,
+            RunSet::Runs(runs) => RunSelection::Runs(runs),
+            RunSet::Range(range) => RunSelection::Range {
+                start: *range.start(),
+                end: *range.end(),
+            },
+        }
+    }
+}
+
+impl From<RunSelection> for RunSet {
+    fn from(selection: RunSelection) -> Self {
+        match selection {
+            RunSelection::All => RunSet::All,
+            RunSelection::Runs(runs) => RunSet::Runs(runs),
+            RunSelection::Range { start, end } => RunSet::Range(start..=end),
+        }
+    }
+}
@@ -1,8 +1,10 @@
 use chrono::{DateTime, Utc};
+use gluex_core::units::{Quantity, Unit};
 
 use crate::models::ValueType;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 enum Repr {
     Text(String),
     Int(i64),
@@ -13,14 +15,30 @@ enum Repr {
 
 /// Typed wrapper for an individual RCDB condition value.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[allow(clippy::struct_field_names)]
 pub struct Value {
     value_type: ValueType,
     repr: Repr,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    unit: Option<Unit>,
 }
 
 impl Value {
     fn new(value_type: ValueType, repr: Repr) -> Self {
-        Self { value_type, repr }
+        Self {
+            value_type,
+            repr,
+            unit: None,
+        }
+    }
+
+    pub(crate) fn with_unit(mut self, unit: Option<Unit>) -> Self {
+        self.unit = unit;
+        self
     }
 
     pub(crate) fn text(value_type: ValueType, value: Option<String>) -> Self {
@@ -87,6 +105,25 @@ impl Value {
         }
     }
 
+    /// Returns the unit of a well-known numeric condition (see [`gluex_core::units`]).
+    #[must_use]
+    pub fn unit(&self) -> Option<Unit> {
+        self.unit
+    }
+
+    /// Returns the numeric payload tagged with its unit, when the value is an `int` or `float`
+    /// of a condition with a known unit.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn as_quantity(&self) -> Option<Quantity> {
+        let value = match &self.repr {
+            Repr::Int(value) => *value as f64,
+            Repr::Float(value) => *value,
+            _ => return None,
+        };
+        Some(Quantity::new(value, self.unit?))
+    }
+
     /// Returns the timestamp payload when the value type is `time`.
     #[must_use]
     pub fn as_time(&self) -> Option<DateTime<Utc>> {
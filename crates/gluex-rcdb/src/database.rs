@@ -1,54 +1,239 @@
 use std::{
     collections::{BTreeMap, HashMap, HashSet},
+    fmt::Write as _,
+    mem,
     path::Path,
     sync::Arc,
 };
 
-use gluex_core::{parsers::parse_timestamp, Id, RunNumber};
-use parking_lot::{Mutex, MutexGuard, RwLock};
-use rusqlite::types::Value as SqlValue;
-use rusqlite::{params_from_iter, Connection, OpenFlags, ToSql};
+#[cfg(feature = "provenance")]
+use gluex_core::provenance::{DatabaseKind, Recorder};
+use gluex_core::{
+    deprecation::{is_withdrawn, DeprecatedItem, Deprecation, DeprecationWarnings},
+    limits::QueryLimits,
+    metrics::{Metrics, MetricsReport},
+    parsers::parse_timestamp,
+    query_plan::QueryPlan,
+    retry::RetryPolicy,
+    run_set::RunContext,
+    schema::SchemaMode,
+    sql_audit::{text_literal, SqlAudit},
+    units::{condition_unit, Unit},
+    Id, RunNumber,
+};
+use parking_lot::{MutexGuard, RwLock};
+use rusqlite::{Connection, OpenFlags, MAIN_DB};
 
 use crate::{
+    backend::{Row, SqlValue, SqliteBackend, StorageBackend},
     context::{Context, RunSelection},
     data::Value,
-    models::{ConditionTypeMeta, ValueType},
+    explain::{RunExplanation, RunVerdict},
+    models::{ConditionTypeMeta, ConfigurationFile, ValueType},
+    typed::RcdbConditions,
     RCDBError, RCDBResult,
 };
 
+/// Path reported for databases opened with [`RCDB::open_from_bytes`].
+const MEMORY_PATH: &str = ":memory:";
+
+/// Newest `schema_versions` entry this crate knows how to read.
+pub const SUPPORTED_SCHEMA_VERSION: i64 = 2;
+
+const FILES_SQL: &str =
+    "SELECT files.id, files.path, files.sha256, files.content, files.description,
+            files.importance
+     FROM files
+     JOIN files_have_runs ON files_have_runs.files_id = files.id
+     WHERE files_have_runs.run_number = ?
+     ORDER BY files.path, files.id";
+
 /// Primary entry point for interacting with an RCDB `SQLite` file.
 #[derive(Clone)]
 pub struct RCDB {
-    connection: Arc<Mutex<Connection>>,
+    backend: Arc<dyn StorageBackend>,
     connection_path: String,
     condition_types: Arc<RwLock<HashMap<String, ConditionTypeMeta>>>,
-    conditions_run_number_index: Option<String>,
+    conditions_index_hint: Option<String>,
+    #[cfg(feature = "provenance")]
+    recorder: Option<Recorder>,
+    metrics: Option<Metrics>,
+    sql_audit: Option<SqlAudit>,
+    deprecations: Option<DeprecationWarnings>,
+    limits: QueryLimits,
+    schema_version: i64,
+    best_effort: bool,
+}
+
+fn read_only_connection(path: &Path) -> RCDBResult<Connection> {
+    let connection = Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+    connection.pragma_update(None, "foreign_keys", "ON")?;
+    Ok(connection)
 }
 
 impl RCDB {
     /// Opens a read-only handle to the supplied RCDB `SQLite` database file.
     ///
+    /// With the `compressed` feature, `.gz` and `.zst` files are decompressed into a cache
+    /// directory on first use and the cached copy is opened (see `gluex_core::compressed`).
+    /// With the `bundle` feature, the RCDB snapshot of an offline bundle (`.gluex`) is extracted
+    /// into the same directory and opened (see `gluex_core::bundle`).
+    ///
+    /// Opening is retried while the file is busy, locked, or fails with an I/O error, as configured
+    /// by the `GLUEX_RETRY_*` environment variables (see `gluex_core::retry`); by default a single
+    /// attempt is made.
+    ///
+    /// Snapshots with a newer schema than [`SUPPORTED_SCHEMA_VERSION`] are handled according to
+    /// the `GLUEX_SCHEMA_COMPAT` environment variable (see [`RCDB::open_with_schema_mode`]).
+    ///
     /// # Errors
     ///
-    /// This method returns an error if the database cannot be opened.
+    /// This method returns an error if the database cannot be opened, if a compressed file
+    /// cannot be decompressed or a bundle cannot be extracted, or if the snapshot's schema is missing or too new (see
+    /// [`gluex_core::schema`]).
     pub fn open(path: impl AsRef<Path>) -> RCDBResult<Self> {
-        let path_str = path.as_ref().to_string_lossy().to_string();
-        let connection = Connection::open_with_flags(
-            path,
-            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        Self::open_with_schema_mode(path, SchemaMode::from_env())
+    }
+
+    /// Opens an RCDB `SQLite` database file, choosing how to treat a snapshot whose schema is
+    /// newer than [`SUPPORTED_SCHEMA_VERSION`].
+    ///
+    /// In [`SchemaMode::Strict`] such snapshots are rejected with
+    /// [`RCDBError::NewerSchemaError`]. In [`SchemaMode::BestEffort`] they open, only the tables
+    /// and columns this crate knows are read, and condition types with value types it does not
+    /// know are skipped instead of failing with [`RCDBError::UnknownValueType`].
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the database cannot be opened, if a compressed file
+    /// cannot be decompressed or a bundle cannot be extracted, or if the snapshot's schema is missing, or too new in strict mode.
+    pub fn open_with_schema_mode(path: impl AsRef<Path>, mode: SchemaMode) -> RCDBResult<Self> {
+        Self::open_pool(path.as_ref(), mode, 1)
+    }
+
+    /// Opens an RCDB `SQLite` database file like [`RCDB::open`], but with a pool of `pool_size`
+    /// read-only connections (at least one) shared by the handle and all its clones.
+    ///
+    /// A handle normally runs its statements on a single connection, so threads querying through
+    /// clones of it wait for each other. With a pool, each statement takes an idle connection,
+    /// letting up to `pool_size` threads (for example, the workers of a web server) query at once.
+    ///
+    /// ```no_run
+    /// use gluex_rcdb::{context::Context, database::RCDB};
+    ///
+    /// let db = RCDB::open_pooled("rcdb.sqlite", 4)?;
+    /// std::thread::scope(|scope| {
+    ///     for run in [30_000, 30_500, 31_000, 31_500] {
+    ///         let db = db.clone();
+    ///         scope.spawn(move || db.fetch(["event_count"], &Context::default().with_run(run)));
+    ///     }
+    /// });
+    /// # Ok::<(), gluex_rcdb::RCDBError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This method returns the same errors as [`RCDB::open`].
+    pub fn open_pooled(path: impl AsRef<Path>, pool_size: usize) -> RCDBResult<Self> {
+        Self::open_pool(path.as_ref(), SchemaMode::from_env(), pool_size)
+    }
+
+    fn open_pool(path: &Path, mode: SchemaMode, pool_size: usize) -> RCDBResult<Self> {
+        #[cfg(feature = "bundle")]
+        let path = &gluex_core::bundle::unbundled_path(path, gluex_core::bundle::RCDB_FILE)?;
+        #[cfg(feature = "compressed")]
+        let path = &gluex_core::compressed::decompressed_path(path)?;
+        let path_str = path.to_string_lossy().to_string();
+        RetryPolicy::from_env().run(
+            |_| {
+                let mut backend = SqliteBackend::new(read_only_connection(path)?, path_str.clone());
+                for _ in 1..pool_size {
+                    backend = backend.with_connection(read_only_connection(path)?);
+                }
+                Self::from_backend(Arc::new(backend), mode)
+            },
+            RCDBError::is_transient,
+        )
+    }
+
+    /// Opens a read-only RCDB database from the bytes of an `SQLite` file held in memory.
+    ///
+    /// The bytes are copied into an in-memory database (`SQLite`'s deserialize API), so snapshots
+    /// embedded with `include_bytes!`, downloaded over HTTP, or read from an object store can be
+    /// queried without writing a temporary file. Unlike `RCDB::open_memvfs`, this works on
+    /// every target. [`RCDB::connection_path`] reports `:memory:` for such handles.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if `bytes` is not an RCDB `SQLite` database.
+    pub fn open_from_bytes(bytes: &[u8]) -> RCDBResult<Self> {
+        let mut connection = Connection::open_in_memory_with_flags(
+            OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_NO_MUTEX,
         )?;
+        connection.deserialize_read_exact(MAIN_DB, bytes, bytes.len(), true)?;
+        Self::from_connection(connection, MEMORY_PATH.to_string(), SchemaMode::from_env())
+    }
+
+    fn from_connection(
+        connection: Connection,
+        path_str: String,
+        mode: SchemaMode,
+    ) -> RCDBResult<Self> {
         connection.pragma_update(None, "foreign_keys", "ON")?;
-        ensure_schema_version(&connection)?;
-        let run_number_index = lookup_conditions_run_number_index(&connection)?;
+        Self::from_backend(Arc::new(SqliteBackend::new(connection, path_str)), mode)
+    }
+
+    /// Builds a client on top of any [`StorageBackend`], such as a server connection or an
+    /// in-memory store, treating a schema newer than [`SUPPORTED_SCHEMA_VERSION`] according to
+    /// `mode` (see [`RCDB::open_with_schema_mode`]).
+    ///
+    /// Condition types are loaded immediately; everything else is read through the backend on
+    /// demand.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the schema version is missing, or too new in strict mode,
+    /// or if the condition types cannot be loaded.
+    pub fn from_backend(backend: Arc<dyn StorageBackend>, mode: SchemaMode) -> RCDBResult<Self> {
+        let schema_version = ensure_schema_version(backend.as_ref())?;
+        let best_effort = mode.check("RCDB", schema_version, SUPPORTED_SCHEMA_VERSION)?;
+        let conditions_index_hint = backend.conditions_index_hint()?;
         let db = Self {
-            connection: Arc::new(Mutex::new(connection)),
-            connection_path: path_str,
+            connection_path: backend.location().to_string(),
+            backend,
             condition_types: Arc::new(RwLock::new(HashMap::new())),
-            conditions_run_number_index: run_number_index,
+            conditions_index_hint,
+            #[cfg(feature = "provenance")]
+            recorder: None,
+            metrics: None,
+            sql_audit: SqlAudit::from_env(),
+            deprecations: DeprecationWarnings::from_env(),
+            limits: QueryLimits::from_env(),
+            schema_version,
+            best_effort,
         };
         db.load_condition_types()?;
         Ok(db)
     }
+    /// Loads an RCDB `SQLite` snapshot held in memory into the in-memory VFS that backs `SQLite`
+    /// on `wasm32-unknown-unknown`, then opens it.
+    ///
+    /// This lets browser applications use a snapshot fetched over HTTP or picked by the user
+    /// without a server. `name` identifies the snapshot inside the VFS.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if `bytes` is not an `SQLite` database, if a snapshot named
+    /// `name` was already imported, or if the database cannot be opened.
+    #[cfg(all(feature = "wasm", target_family = "wasm", target_os = "unknown"))]
+    pub fn open_memvfs(name: &str, bytes: &[u8]) -> RCDBResult<Self> {
+        sqlite_wasm_rs::MemVfsUtil::<sqlite_wasm_rs::WasmOsCallback>::new()
+            .import_db(name, bytes)?;
+        Self::open(name)
+    }
 
     /// Returns the filesystem path used to open this connection.
     #[must_use]
@@ -57,8 +242,160 @@ impl RCDB {
     }
 
     /// Returns the underlying [`rusqlite::Connection`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the handle is not backed by a [`SqliteBackend`], such as one built with
+    /// [`RCDB::from_backend`] over another store. Use [`RCDB::try_connection`] instead.
+    #[deprecated(note = "panics on handles that are not backed by SQLite; use `try_connection`")]
     pub fn connection(&self) -> MutexGuard<'_, Connection> {
-        self.connection.lock()
+        self.try_connection()
+            .expect("RCDB handle is not backed by an SQLite database")
+    }
+
+    /// Returns the underlying [`rusqlite::Connection`], or `None` if the handle is not backed by
+    /// a [`SqliteBackend`].
+    pub fn try_connection(&self) -> Option<MutexGuard<'_, Connection>> {
+        self.backend.sqlite().map(SqliteBackend::connection)
+    }
+
+    /// Returns the storage backend this handle reads from.
+    #[must_use]
+    pub fn backend(&self) -> &dyn StorageBackend {
+        self.backend.as_ref()
+    }
+
+    /// Returns a handle that records every condition and run fetch into `recorder`.
+    ///
+    /// The original client is unaffected.
+    #[cfg(feature = "provenance")]
+    #[must_use]
+    pub fn with_recorder(mut self, recorder: Recorder) -> Self {
+        recorder.register_database(&self.connection_path, DatabaseKind::Rcdb);
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Returns a handle that counts and times every query into `metrics`.
+    ///
+    /// The original client is unaffected.
+    #[must_use]
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Returns the totals recorded so far, or `None` if no collector was attached with
+    /// [`RCDB::with_metrics`].
+    #[must_use]
+    pub fn metrics(&self) -> Option<MetricsReport> {
+        self.metrics.as_ref().map(Metrics::report)
+    }
+
+    /// Returns a handle that logs every SQL statement it issues, with bound parameters, to
+    /// `audit` (in a dry run, condition, run, and file queries are logged but not executed).
+    ///
+    /// The original client is unaffected. Handles opened while `GLUEX_SQL_AUDIT` is set start with
+    /// an audit that echoes to stderr (see [`gluex_core::sql_audit`]).
+    #[must_use]
+    pub fn with_sql_audit(mut self, audit: SqlAudit) -> Self {
+        self.sql_audit = Some(audit);
+        self
+    }
+
+    /// Returns the attached SQL audit, if any.
+    #[must_use]
+    pub fn sql_audit(&self) -> Option<&SqlAudit> {
+        self.sql_audit.as_ref()
+    }
+
+    /// Returns a handle whose [`RCDB::fetch`] reports withdrawn conditions to `warnings`, or that
+    /// stays silent if `warnings` is `None`.
+    ///
+    /// RCDB has no deprecation flag, so a condition counts as withdrawn when its description
+    /// starts with "deprecated", "withdrawn", or "obsolete". The original client is unaffected.
+    /// Handles start with a channel that only collects warnings, or with none if
+    /// `GLUEX_DEPRECATION_WARNINGS=off` (see [`gluex_core::deprecation`]).
+    #[must_use]
+    pub fn with_deprecation_warnings(mut self, warnings: Option<DeprecationWarnings>) -> Self {
+        self.deprecations = warnings;
+        self
+    }
+
+    /// Returns the attached deprecation warning channel, if any.
+    #[must_use]
+    pub fn deprecation_warnings(&self) -> Option<&DeprecationWarnings> {
+        self.deprecations.as_ref()
+    }
+
+    /// Reports the requested conditions whose descriptions mark them as withdrawn.
+    fn report_withdrawn(&self, names: &[String]) {
+        let Some(warnings) = &self.deprecations else {
+            return;
+        };
+        let condition_types = self.condition_types.read();
+        for name in names {
+            let Some(meta) = condition_types.get(name) else {
+                continue;
+            };
+            if !is_withdrawn(&meta.description) {
+                continue;
+            }
+            let deprecation = Deprecation::new(
+                self.connection_path.clone(),
+                DeprecatedItem::RcdbCondition,
+                name.clone(),
+                meta.description.clone(),
+            );
+            #[cfg(feature = "tracing")]
+            let event = deprecation.clone();
+            if warnings.warn(deprecation) {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(
+                    database = %event.database,
+                    item = %event.item,
+                    name = %event.name,
+                    replacement = ?event.replacement,
+                    "{event}"
+                );
+            }
+        }
+    }
+
+    /// Returns a handle whose [`RCDB::fetch`] and [`RCDB::fetch_runs`] fail with
+    /// [`RCDBError::LimitError`] when they exceed `limits`.
+    ///
+    /// Runs and values are counted as rows are read, so a runaway query stops as soon as it
+    /// crosses a limit. The original client is unaffected. Handles start with the limits set in
+    /// the environment (see [`gluex_core::limits`]).
+    #[must_use]
+    pub fn with_limits(mut self, limits: QueryLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Returns the limits applied to every fetch.
+    #[must_use]
+    pub fn limits(&self) -> QueryLimits {
+        self.limits
+    }
+
+    /// Returns the newest schema version recorded in the snapshot's `schema_versions` table.
+    #[must_use]
+    pub fn schema_version(&self) -> i64 {
+        self.schema_version
+    }
+
+    /// Logs a data query to the attached audit and returns whether it should be executed.
+    fn audit(&self, sql: &str, params: &[SqlValue]) -> bool {
+        self.sql_audit.as_ref().is_none_or(|audit| {
+            audit.log(
+                &self.connection_path,
+                sql,
+                params.iter().map(sql_literal).collect(),
+                true,
+            )
+        })
     }
 
     /// Reloads the `condition_types` table into memory.
@@ -67,36 +404,61 @@ impl RCDB {
     ///
     /// This method will fail if there are any problems parsing the `condition_types` table.
     pub fn load_condition_types(&self) -> RCDBResult<()> {
-        let connection = self.connection();
-        let mut stmt = connection
-            .prepare("SELECT id, name, value_type, created, description FROM condition_types")?;
-        let mut rows = stmt.query([])?;
         let mut loaded: HashMap<String, ConditionTypeMeta> = HashMap::new();
-        while let Some(row) = rows.next()? {
-            let id: Id = row.get(0)?;
-            let name: String = row.get(1)?;
-            let value_type_name: String = row.get(2)?;
-            let value_type = ValueType::from_identifier(&value_type_name)
-                .ok_or_else(|| RCDBError::UnknownValueType(value_type_name.clone()))?;
-            let created: Option<String> = row.get(3)?;
-            let description: Option<String> = row.get(4)?;
-            loaded.insert(
-                name.clone(),
-                ConditionTypeMeta {
-                    id,
-                    name,
-                    value_type,
-                    created: created.unwrap_or_default(),
-                    description: description.unwrap_or_default(),
-                },
-            );
-        }
+        self.backend.query(
+            "SELECT id, name, value_type, created, description FROM condition_types",
+            &[],
+            None,
+            &mut |row| {
+                let id: Id = row.get(0)?;
+                let name: String = row.get(1)?;
+                let value_type_name: String = row.get(2)?;
+                let Some(value_type) = ValueType::from_identifier(&value_type_name) else {
+                    if self.best_effort {
+                        return Ok(());
+                    }
+                    return Err(RCDBError::UnknownValueType(value_type_name));
+                };
+                let created: Option<String> = row.get(3)?;
+                let description: Option<String> = row.get(4)?;
+                loaded.insert(
+                    name.clone(),
+                    ConditionTypeMeta {
+                        id,
+                        name,
+                        value_type,
+                        created: created.unwrap_or_default(),
+                        description: description.unwrap_or_default(),
+                    },
+                );
+                Ok(())
+            },
+        )?;
         *self.condition_types.write() = loaded;
         Ok(())
     }
 
-    fn condition_type(&self, name: &str) -> Option<ConditionTypeMeta> {
-        self.condition_types.read().get(name).cloned()
+    /// Returns the metadata for the named condition type, if it exists.
+    #[must_use]
+    pub fn condition_type(&self, name: &str) -> Option<ConditionTypeMeta> {
+        let meta = self.condition_types.read().get(name).cloned();
+        if let Some(metrics) = &self.metrics {
+            if meta.is_some() {
+                metrics.cache_hit("rcdb.condition_types");
+            } else {
+                metrics.cache_miss("rcdb.condition_types");
+            }
+        }
+        meta
+    }
+
+    /// Returns metadata for every known condition type, sorted by name.
+    #[must_use]
+    pub fn condition_types(&self) -> Vec<ConditionTypeMeta> {
+        let mut types: Vec<ConditionTypeMeta> =
+            self.condition_types.read().values().cloned().collect();
+        types.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+        types
     }
 
     /// Fetches multiple condition values for the supplied names and context.
@@ -105,7 +467,15 @@ impl RCDB {
     ///
     /// This method will return an error if any of the requested conditions cannot be found, if the
     /// conditions list is empty (use [`RCDB::fetch_runs`] instead), or if the SQL query fails.
-    #[allow(clippy::too_many_lines)]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            name = "rcdb.fetch",
+            skip_all,
+            fields(selection = ?context.selection(), filters = context.filters().len())
+        )
+    )]
     pub fn fetch<S>(
         &self,
         condition_names: S,
@@ -115,41 +485,92 @@ impl RCDB {
         S: IntoIterator,
         S::Item: AsRef<str>,
     {
-        let mut requested: Vec<String> = Vec::new();
-        let mut seen: HashSet<String> = HashSet::new();
-        for name in condition_names {
-            let name_ref = name.as_ref();
-            if seen.insert(name_ref.to_string()) {
-                requested.push(name_ref.to_string());
-            }
+        let names: Vec<String> = condition_names
+            .into_iter()
+            .map(|name| name.as_ref().to_string())
+            .collect();
+        let timer = self
+            .metrics
+            .as_ref()
+            .map(|metrics| metrics.timer("rcdb.fetch"));
+        let values = self.fetch_values(&names, context)?;
+        if let Some(timer) = timer {
+            timer.finish(values.len());
         }
-        if requested.is_empty() {
-            return Err(RCDBError::EmptyConditionList);
+        self.report_withdrawn(&names);
+        #[cfg(feature = "provenance")]
+        if let Some(recorder) = &self.recorder {
+            recorder.record(crate::provenance::fetch_query(
+                &self.connection_path,
+                &names,
+                context,
+                &values,
+            ));
         }
-        if matches!(context.selection(), RunSelection::Runs(runs) if runs.is_empty()) {
-            return Ok(BTreeMap::new());
+        Ok(values)
+    }
+
+    /// Fetches the conditions `T` reads (see [`RcdbConditions::CONDITIONS`]) and reads each run's
+    /// values into `T`. Runs that lack a condition a non-[`Option`] field of `T` requires are left
+    /// out.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if `T` reads no conditions, if fetching fails (see
+    /// [`RCDB::fetch`]), or if a condition stores a different type than its field.
+    pub fn fetch_typed<T: RcdbConditions>(
+        &self,
+        context: &Context,
+    ) -> RCDBResult<BTreeMap<RunNumber, T>> {
+        let mut typed = BTreeMap::new();
+        for (run, values) in self.fetch(T::CONDITIONS, context)? {
+            if let Some(row) = T::from_conditions(&values)? {
+                typed.insert(run, row);
+            }
         }
-        let (matched_runs_sql, mut params) = self.build_matched_runs_query(context)?;
+        Ok(typed)
+    }
+
+    /// Resolves the requested condition names (in order, without duplicates) to their types.
+    fn requested_conditions(
+        &self,
+        condition_names: &[String],
+    ) -> RCDBResult<Vec<RequestedCondition>> {
         let mut requested_conditions: Vec<RequestedCondition> = Vec::new();
-        let mut requested_index_by_id: HashMap<Id, usize> = HashMap::new();
-        for name in &requested {
+        let mut seen: HashSet<&str> = HashSet::new();
+        for name in condition_names {
+            if !seen.insert(name.as_str()) {
+                continue;
+            }
             let meta = self
                 .condition_type(name)
                 .ok_or_else(|| RCDBError::ConditionTypeNotFound(name.clone()))?;
-            let idx = requested_conditions.len();
-            requested_index_by_id.insert(meta.id(), idx);
             requested_conditions.push(RequestedCondition {
                 name: name.clone(),
                 id: meta.id(),
                 value_type: meta.value_type(),
+                unit: condition_unit(name),
             });
         }
+        if requested_conditions.is_empty() {
+            return Err(RCDBError::EmptyConditionList);
+        }
+        Ok(requested_conditions)
+    }
+
+    /// Builds the statement behind [`RCDB::fetch`].
+    fn build_fetch_query(
+        &self,
+        requested_conditions: &[RequestedCondition],
+        context: &Context,
+    ) -> RCDBResult<(String, Vec<SqlValue>)> {
+        let (matched_runs_sql, mut params) = self.build_matched_runs_query(context)?;
         let mut sql = String::from("WITH matched_runs AS (");
         sql.push_str(&matched_runs_sql);
         let index_hint = self
-            .conditions_run_number_index
+            .conditions_index_hint
             .as_deref()
-            .map(|name| format!("INDEXED BY {name} "))
+            .map(|hint| format!("{hint} "))
             .unwrap_or_default();
         sql.push_str(
             ") SELECT matched_runs.number, c.condition_type_id, c.text_value, c.int_value, c.float_value, c.bool_value, c.time_value FROM matched_runs LEFT JOIN conditions AS c ",
@@ -161,79 +582,90 @@ impl RCDB {
         sql.push_str(&format!(
             " AND c.condition_type_id IN ({cond_placeholders})"
         ));
-        for cond in &requested_conditions {
+        for cond in requested_conditions {
             params.push(SqlValue::Integer(cond.id));
         }
         sql.push_str(" ORDER BY matched_runs.number");
-        let connection = self.connection();
-        let mut stmt = connection.prepare(&sql)?;
-        let mut rows = if params.is_empty() {
-            stmt.query([])?
-        } else {
-            let param_refs: Vec<&dyn ToSql> = params.iter().map(|v| v as &dyn ToSql).collect();
-            stmt.query(params_from_iter(param_refs))?
-        };
+        Ok((sql, params))
+    }
 
+    fn fetch_values(
+        &self,
+        condition_names: &[String],
+        context: &Context,
+    ) -> RCDBResult<BTreeMap<RunNumber, HashMap<String, Value>>> {
+        if condition_names.is_empty() {
+            return Err(RCDBError::EmptyConditionList);
+        }
+        if matches!(context.selection(), RunSelection::Runs(runs) if runs.is_empty()) {
+            return Ok(BTreeMap::new());
+        }
+        let mut budget = self.limits.start();
+        if let RunSelection::Runs(runs) = context.selection() {
+            budget.check_runs(runs.len())?;
+        }
+        let requested_conditions = self.requested_conditions(condition_names)?;
+        let requested_index_by_id: HashMap<Id, usize> = requested_conditions
+            .iter()
+            .enumerate()
+            .map(|(index, cond)| (cond.id, index))
+            .collect();
+        let (sql, params) = self.build_fetch_query(&requested_conditions, context)?;
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!(
+            "rcdb.query",
+            sql = %sql,
+            params = params.len(),
+            rows = tracing::field::Empty
+        )
+        .entered();
+        if !self.audit(&sql, &params) {
+            return Ok(BTreeMap::new());
+        }
         let run_filter = match context.selection() {
             RunSelection::Runs(runs) => Some(runs.iter().copied().collect::<HashSet<_>>()),
             _ => None,
         };
 
         let mut results: BTreeMap<RunNumber, HashMap<String, Value>> = BTreeMap::new();
-        while let Some(row) = rows.next()? {
-            let run_number: RunNumber = row.get(0)?;
-            if let Some(filter) = &run_filter {
-                if !filter.contains(&run_number) {
-                    continue;
-                }
-            }
-
-            let entry = results.entry(run_number).or_default();
-            let cond_type_id: Option<Id> = row.get(1)?;
-            let Some(cond_type_id) = cond_type_id else {
-                continue;
-            };
-            let Some(&index) = requested_index_by_id.get(&cond_type_id) else {
-                continue;
-            };
-            let requested = &requested_conditions[index];
-            match requested.value_type {
-                ValueType::String | ValueType::Json | ValueType::Blob => {
-                    let value: Option<String> = row.get(2)?;
-                    if let Some(text) = value {
-                        entry.insert(
-                            requested.name.clone(),
-                            Value::text(requested.value_type, Some(text)),
-                        );
-                    }
-                }
-                ValueType::Int => {
-                    let value: Option<i64> = row.get(3)?;
-                    if let Some(v) = value {
-                        entry.insert(requested.name.clone(), Value::int(v));
-                    }
-                }
-                ValueType::Float => {
-                    let value: Option<f64> = row.get(4)?;
-                    if let Some(v) = value {
-                        entry.insert(requested.name.clone(), Value::float(v));
+        let deadline = budget.clone();
+        self.backend
+            .query(&sql, &params, Some(&deadline), &mut |row| {
+                let run_number: RunNumber = row.get(0)?;
+                if let Some(filter) = &run_filter {
+                    if !filter.contains(&run_number) {
+                        return Ok(());
                     }
                 }
-                ValueType::Bool => {
-                    let value: Option<i64> = row.get(5)?;
-                    if let Some(v) = value {
-                        entry.insert(requested.name.clone(), Value::bool(v != 0));
-                    }
+
+                if !results.contains_key(&run_number) {
+                    budget.check_runs(results.len() + 1)?;
+                    budget.add_bytes(
+                        mem::size_of::<RunNumber>() + mem::size_of::<HashMap<String, Value>>(),
+                    )?;
                 }
-                ValueType::Time => {
-                    let value: Option<String> = row.get(6)?;
-                    if let Some(raw) = value {
-                        let parsed = parse_timestamp(&raw)?;
-                        entry.insert(requested.name.clone(), Value::time(parsed));
-                    }
+                let entry = results.entry(run_number).or_default();
+                let cond_type_id: Option<Id> = row.get(1)?;
+                let Some(cond_type_id) = cond_type_id else {
+                    return Ok(());
+                };
+                let Some(&index) = requested_index_by_id.get(&cond_type_id) else {
+                    return Ok(());
+                };
+                let requested = &requested_conditions[index];
+                if let Some(value) = read_value(&row, requested)? {
+                    budget.add_bytes(
+                        mem::size_of::<String>()
+                            + requested.name.len()
+                            + mem::size_of::<Value>()
+                            + value.as_string().map_or(0, str::len),
+                    )?;
+                    entry.insert(requested.name.clone(), value);
                 }
-            }
-        }
+                Ok(())
+            })?;
+        #[cfg(feature = "tracing")]
+        span.record("rows", results.len());
         Ok(results)
     }
 
@@ -242,40 +674,239 @@ impl RCDB {
     /// # Errors
     ///
     /// This method will return an error if the SQL query fails.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            name = "rcdb.fetch_runs",
+            skip_all,
+            fields(selection = ?context.selection(), filters = context.filters().len())
+        )
+    )]
     pub fn fetch_runs(&self, context: &Context) -> RCDBResult<Vec<RunNumber>> {
         if matches!(context.selection(), RunSelection::Runs(runs) if runs.is_empty()) {
             return Ok(Vec::new());
         }
 
+        let timer = self
+            .metrics
+            .as_ref()
+            .map(|metrics| metrics.timer("rcdb.fetch_runs"));
+        let mut budget = self.limits.start();
+        if let RunSelection::Runs(runs) = context.selection() {
+            budget.check_runs(runs.len())?;
+        }
         let (sql, params) = self.build_matched_runs_query(context)?;
 
-        let connection = self.connection();
-        let mut stmt = connection.prepare(&sql)?;
-        let mut rows = if params.is_empty() {
-            stmt.query([])?
-        } else {
-            let param_refs: Vec<&dyn ToSql> = params.iter().map(|v| v as &dyn ToSql).collect();
-            stmt.query(params_from_iter(param_refs))?
-        };
-
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!(
+            "rcdb.query",
+            sql = %sql,
+            params = params.len(),
+            rows = tracing::field::Empty
+        )
+        .entered();
+        if !self.audit(&sql, &params) {
+            return Ok(Vec::new());
+        }
         let run_filter = match context.selection() {
             RunSelection::Runs(runs) => Some(runs.iter().copied().collect::<HashSet<_>>()),
             _ => None,
         };
 
         let mut runs = Vec::new();
-        while let Some(row) = rows.next()? {
-            let run_number: RunNumber = row.get(0)?;
-            if let Some(filter) = &run_filter {
-                if !filter.contains(&run_number) {
-                    continue;
+        let deadline = budget.clone();
+        self.backend
+            .query(&sql, &params, Some(&deadline), &mut |row| {
+                let run_number: RunNumber = row.get(0)?;
+                if let Some(filter) = &run_filter {
+                    if !filter.contains(&run_number) {
+                        return Ok(());
+                    }
                 }
-            }
-            runs.push(run_number);
+                budget.check_runs(runs.len() + 1)?;
+                budget.add_bytes(mem::size_of::<RunNumber>())?;
+                runs.push(run_number);
+                Ok(())
+            })?;
+        #[cfg(feature = "tracing")]
+        span.record("rows", runs.len());
+        if let Some(timer) = timer {
+            timer.finish(runs.len());
+        }
+        #[cfg(feature = "provenance")]
+        if let Some(recorder) = &self.recorder {
+            recorder.record(crate::provenance::runs_query(
+                &self.connection_path,
+                context,
+                &runs,
+            ));
         }
         Ok(runs)
     }
 
+    /// Returns the `SQLite` query plan of the statement [`RCDB::fetch`] would run for the same
+    /// arguments, without running it.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if the condition list is empty, a condition is unknown,
+    /// or `SQLite` cannot prepare the statement.
+    pub fn explain_fetch<S>(&self, condition_names: S, context: &Context) -> RCDBResult<QueryPlan>
+    where
+        S: IntoIterator,
+        S::Item: AsRef<str>,
+    {
+        let names: Vec<String> = condition_names
+            .into_iter()
+            .map(|name| name.as_ref().to_string())
+            .collect();
+        let requested_conditions = self.requested_conditions(&names)?;
+        let (sql, params) = self.build_fetch_query(&requested_conditions, context)?;
+        explain(self.backend(), sql, &params)
+    }
+
+    /// Returns the `SQLite` query plan of the statement [`RCDB::fetch_runs`] would run for the
+    /// same context, without running it.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if a filter references an unknown condition or `SQLite`
+    /// cannot prepare the statement.
+    pub fn explain_fetch_runs(&self, context: &Context) -> RCDBResult<QueryPlan> {
+        let (sql, params) = self.build_matched_runs_query(context)?;
+        explain(self.backend(), sql, &params)
+    }
+
+    /// Explains, run by run, which runs of the context's selection its filters keep and which
+    /// filter clause drops each of the others.
+    ///
+    /// Every run of the selection that exists in the database is checked against the filters one
+    /// at a time, with the same three-valued logic as the SQL behind [`RCDB::fetch_runs`] (a
+    /// comparison with a condition the run has no value for never holds). An excluded run is
+    /// reported with the first filter that does not hold, the innermost clause responsible (the
+    /// first failing clause of an `AND` group, recursively), and the run's values of the
+    /// conditions that clause compares. Explicitly requested runs that are not in the database
+    /// are reported as such. Explanations are ordered by run number.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if a filter references an unknown condition or compares a
+    /// condition as the wrong type, if the SQL queries fail, or if the selection exceeds the
+    /// handle's limits.
+    pub fn explain_selection(&self, context: &Context) -> RCDBResult<Vec<RunExplanation>> {
+        // Reject unknown conditions and type mismatches exactly as a fetch would.
+        self.build_matched_runs_query(context)?;
+        let runs = self.fetch_runs(&Context::default().with_run_set(context.run_set()))?;
+        let mut names = Vec::new();
+        for filter in context.filters() {
+            filter.referenced_conditions(&mut names);
+        }
+        names.sort_unstable();
+        names.dedup();
+        let values = if names.is_empty() || runs.is_empty() {
+            BTreeMap::new()
+        } else {
+            self.fetch_values(&names, &Context::default().with_runs(runs.iter().copied()))?
+        };
+        let no_values = HashMap::new();
+        let mut explanations: Vec<RunExplanation> = runs
+            .iter()
+            .map(|&run| {
+                let run_values = values.get(&run).unwrap_or(&no_values);
+                let verdict = context
+                    .filters()
+                    .iter()
+                    .enumerate()
+                    .find(|(_, filter)| filter.evaluate(run_values) != Some(true))
+                    .map_or(RunVerdict::Passed, |(index, filter)| {
+                        let clause = filter.failing_clause(run_values);
+                        let mut referenced = Vec::new();
+                        clause.referenced_conditions(&mut referenced);
+                        let mut seen = HashSet::new();
+                        referenced.retain(|name| seen.insert(name.clone()));
+                        RunVerdict::Excluded {
+                            filter: index,
+                            values: referenced
+                                .into_iter()
+                                .map(|name| {
+                                    let value = run_values.get(&name).cloned();
+                                    (name, value)
+                                })
+                                .collect(),
+                            clause,
+                        }
+                    });
+                RunExplanation { run, verdict }
+            })
+            .collect();
+        if let Some(requested) = context.runs() {
+            let found: HashSet<RunNumber> = runs.iter().copied().collect();
+            explanations.extend(
+                requested
+                    .iter()
+                    .filter(|run| !found.contains(run))
+                    .map(|&run| RunExplanation {
+                        run,
+                        verdict: RunVerdict::NotInDatabase,
+                    }),
+            );
+            explanations.sort_by_key(|explanation| explanation.run);
+            explanations.dedup_by_key(|explanation| explanation.run);
+        }
+        Ok(explanations)
+    }
+
+    /// Returns the configuration files attached to `run`, ordered by path.
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if the SQL query fails.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            name = "rcdb.query",
+            skip(self),
+            fields(sql = FILES_SQL, rows = tracing::field::Empty)
+        )
+    )]
+    pub fn files(&self, run: RunNumber) -> RCDBResult<Vec<ConfigurationFile>> {
+        let timer = self
+            .metrics
+            .as_ref()
+            .map(|metrics| metrics.timer("rcdb.files"));
+        if !self.audit(FILES_SQL, &[SqlValue::Integer(run)]) {
+            return Ok(Vec::new());
+        }
+        let mut files = Vec::new();
+        self.backend
+            .query(FILES_SQL, &[SqlValue::Integer(run)], None, &mut |row| {
+                let content = match row.get::<SqlValue>(3)? {
+                    SqlValue::Text(text) => text.into_bytes(),
+                    SqlValue::Blob(bytes) => bytes,
+                    _ => Vec::new(),
+                };
+                let description: Option<String> = row.get(4)?;
+                let importance: Option<i64> = row.get(5)?;
+                files.push(ConfigurationFile {
+                    id: row.get(0)?,
+                    path: row.get(1)?,
+                    sha256: row.get(2)?,
+                    content,
+                    description: description.unwrap_or_default(),
+                    importance: importance.unwrap_or_default(),
+                });
+                Ok(())
+            })?;
+        #[cfg(feature = "tracing")]
+        tracing::Span::current().record("rows", files.len());
+        if let Some(timer) = timer {
+            timer.finish(files.len());
+        }
+        Ok(files)
+    }
+
     fn ensure_query_entry(
         &self,
         name: &str,
@@ -315,9 +946,9 @@ impl RCDB {
 
         let mut sql = String::from("SELECT runs.number FROM runs ");
         let join_hint = self
-            .conditions_run_number_index
+            .conditions_index_hint
             .as_deref()
-            .map(|name| format!("INDEXED BY {name} "))
+            .map(|hint| format!("{hint} "))
             .unwrap_or_default();
         for entry in &entries {
             #[allow(clippy::format_push_string)]
@@ -368,43 +999,53 @@ impl RCDB {
     }
 }
 
-fn ensure_schema_version(connection: &Connection) -> RCDBResult<()> {
-    let mut stmt = connection.prepare("SELECT 1 FROM schema_versions WHERE version = 2 LIMIT 1")?;
-    let exists = stmt.exists([])?;
-    if exists {
-        Ok(())
-    } else {
-        Err(RCDBError::MissingSchemaVersion)
-    }
+/// Asks the backend for the plan of `sql` (bypassing any audit, since nothing is read).
+fn explain(
+    backend: &dyn StorageBackend,
+    sql: String,
+    params: &[SqlValue],
+) -> RCDBResult<QueryPlan> {
+    let steps = backend.explain(&sql, params)?;
+    Ok(QueryPlan {
+        sql,
+        params: params.iter().map(sql_literal).collect(),
+        steps,
+    })
 }
 
-fn lookup_conditions_run_number_index(connection: &Connection) -> RCDBResult<Option<String>> {
-    let mut stmt = connection.prepare("PRAGMA index_list('conditions')")?;
-    let mut rows = stmt.query([])?;
-    while let Some(row) = rows.next()? {
-        let index_name: String = row.get(1)?;
-        if index_has_column(connection, &index_name, "run_number")? {
-            return Ok(Some(index_name));
+fn sql_literal(value: &SqlValue) -> String {
+    match value {
+        SqlValue::Null => "NULL".to_string(),
+        SqlValue::Integer(value) => value.to_string(),
+        SqlValue::Real(value) => value.to_string(),
+        SqlValue::Text(text) => text_literal(text),
+        SqlValue::Blob(bytes) => {
+            let mut literal = bytes.iter().fold(String::from("X'"), |mut out, byte| {
+                let _ = write!(out, "{byte:02X}");
+                out
+            });
+            literal.push('\'');
+            literal
         }
     }
-    Ok(None)
 }
 
-fn index_has_column(
-    connection: &Connection,
-    index_name: &str,
-    column_name: &str,
-) -> RCDBResult<bool> {
-    let pragma = format!("PRAGMA index_info('{index_name}')");
-    let mut stmt = connection.prepare(&pragma)?;
-    let mut rows = stmt.query([])?;
-    while let Some(row) = rows.next()? {
-        let col_name: String = row.get(2)?;
-        if col_name == column_name {
-            return Ok(true);
-        }
-    }
-    Ok(false)
+/// Returns the newest version in `schema_versions`, which must be at least version 2.
+fn ensure_schema_version(backend: &dyn StorageBackend) -> RCDBResult<i64> {
+    let mut version: Option<i64> = None;
+    backend.query(
+        "SELECT MAX(version) FROM schema_versions",
+        &[],
+        None,
+        &mut |row| {
+            version = row.get(0)?;
+            Ok(())
+        },
+    )?;
+    match version {
+        Some(version) if version >= SUPPORTED_SCHEMA_VERSION => Ok(version),
+        _ => Err(RCDBError::MissingSchemaVersion),
+    }
 }
 
 struct ConditionQueryEntry {
@@ -422,6 +1063,25 @@ struct RequestedCondition {
     name: String,
     id: Id,
     value_type: ValueType,
+    unit: Option<Unit>,
+}
+
+/// Decodes the value column of a fetch row that matches `requested`.
+fn read_value(row: &Row<'_>, requested: &RequestedCondition) -> RCDBResult<Option<Value>> {
+    let value_type = requested.value_type;
+    let value = match value_type {
+        ValueType::String | ValueType::Json | ValueType::Blob => row
+            .get::<Option<String>>(2)?
+            .map(|text| Value::text(value_type, Some(text))),
+        ValueType::Int => row.get::<Option<i64>>(3)?.map(Value::int),
+        ValueType::Float => row.get::<Option<f64>>(4)?.map(Value::float),
+        ValueType::Bool => row.get::<Option<i64>>(5)?.map(|v| Value::bool(v != 0)),
+        ValueType::Time => match row.get::<Option<String>>(6)? {
+            Some(raw) => Some(Value::time(parse_timestamp(&raw)?)),
+            None => None,
+        },
+    };
+    Ok(value.map(|value| value.with_unit(requested.unit)))
 }
 
 const MAX_RUN_RANGE_CLAUSES: usize = 400;
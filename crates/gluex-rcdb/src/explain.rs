@@ -0,0 +1,84 @@
+use std::fmt;
+
+use gluex_core::RunNumber;
+
+use crate::{conditions::Expr, data::Value};
+
+/// Why a run was kept in or dropped from a selection by [`crate::database::RCDB::explain_selection`].
+#[derive(Debug, Clone)]
+pub enum RunVerdict {
+    /// The run satisfies every filter and is part of the selection.
+    Passed,
+    /// The run was requested explicitly but is not in the database's `runs` table.
+    NotInDatabase,
+    /// The run is in the database but one of the filters does not hold for it.
+    Excluded {
+        /// Index of the first failing filter in [`crate::context::Context::filters`].
+        filter: usize,
+        /// Innermost clause of that filter that does not hold.
+        clause: Expr,
+        /// The run's values of the conditions the clause compares, with `None` for conditions the
+        /// run has no value for (comparisons with a missing value never hold).
+        values: Vec<(String, Option<Value>)>,
+    },
+}
+
+/// The verdict of [`crate::database::RCDB::explain_selection`] for one run.
+#[derive(Debug, Clone)]
+pub struct RunExplanation {
+    /// Run number.
+    pub run: RunNumber,
+    /// Whether and why the run was kept or dropped.
+    pub verdict: RunVerdict,
+}
+
+impl RunExplanation {
+    /// Returns true if the run is part of the selection.
+    #[must_use]
+    pub fn passed(&self) -> bool {
+        matches!(self.verdict, RunVerdict::Passed)
+    }
+}
+
+impl fmt::Display for RunExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.verdict {
+            RunVerdict::Passed => write!(f, "{}: passed", self.run),
+            RunVerdict::NotInDatabase => write!(f, "{}: not in the database", self.run),
+            RunVerdict::Excluded {
+                filter,
+                clause,
+                values,
+            } => {
+                write!(f, "{}: excluded by filter {filter}: {clause}", self.run)?;
+                let values: Vec<String> = values
+                    .iter()
+                    .map(|(name, value)| match value {
+                        Some(value) => format!("{name} = {}", display_value(value)),
+                        None => format!("{name} is missing"),
+                    })
+                    .collect();
+                if !values.is_empty() {
+                    write!(f, " ({})", values.join(", "))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn display_value(value: &Value) -> String {
+    if let Some(text) = value.as_string() {
+        format!("{text:?}")
+    } else if let Some(int) = value.as_int() {
+        int.to_string()
+    } else if let Some(float) = value.as_float() {
+        float.to_string()
+    } else if let Some(flag) = value.as_bool() {
+        flag.to_string()
+    } else if let Some(time) = value.as_time() {
+        time.to_rfc3339()
+    } else {
+        String::new()
+    }
+}
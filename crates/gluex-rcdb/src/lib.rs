@@ -1,5 +1,7 @@
 //! `GlueX` RCDB access library with optional Python bindings.
 
+/// Storage backends the query layer reads through, with `SQLite` as the built-in one.
+pub mod backend;
 /// Condition expression builders and helpers.
 pub mod conditions;
 /// Run-selection context utilities.
@@ -8,8 +10,29 @@ pub mod context;
 pub mod data;
 /// High-level database accessors.
 pub mod database;
+/// Per-run diagnostics of which filters keep runs in or drop them from a selection.
+pub mod explain;
 /// Lightweight structs that mirror RCDB tables.
 pub mod models;
+/// Stacks of RCDB files where lookups fall through from override snapshots to the official one.
+pub mod overlay;
+/// Conversion of fetched conditions to polars data frames.
+#[cfg(feature = "polars")]
+pub mod polars;
+/// Query recording and manifest verification.
+#[cfg(feature = "provenance")]
+pub mod provenance;
+/// Text export of fetched conditions for ROOT's `TTree::ReadFile`.
+#[cfg(feature = "readfile")]
+pub mod readfile;
+/// Routing of fetches to the RCDB file that serves each run.
+pub mod router;
+/// Extraction and merging of standalone RCDB snapshot files.
+pub mod snapshot;
+/// Lazy, chunked iteration over fetched conditions.
+pub mod stream;
+/// Reading the conditions of each run into user-defined structs.
+pub mod typed;
 
 use gluex_core::errors::ParseTimestampError;
 use gluex_core::RunNumber;
@@ -26,6 +49,18 @@ pub enum RCDBError {
     /// Wrapper around [`rusqlite::Error`].
     #[error("{0}")]
     SqliteError(#[from] rusqlite::Error),
+    /// A compressed snapshot could not be decompressed.
+    #[cfg(feature = "compressed")]
+    #[error("{0}")]
+    CompressedSnapshotError(#[from] gluex_core::compressed::CompressedSnapshotError),
+    /// A database could not be extracted from an offline bundle.
+    #[cfg(feature = "bundle")]
+    #[error("{0}")]
+    BundleError(#[from] gluex_core::bundle::BundleError),
+    /// Snapshot bytes could not be loaded into the in-memory `SQLite` VFS used on WebAssembly.
+    #[cfg(all(feature = "wasm", target_family = "wasm", target_os = "unknown"))]
+    #[error("{0}")]
+    WasmVfsError(#[from] sqlite_wasm_rs::MemVfsError),
     /// Requested condition name does not exist.
     #[error("condition type not found: {0}")]
     ConditionTypeNotFound(String),
@@ -59,6 +94,30 @@ pub enum RCDBError {
         /// Run number missing the time value.
         run_number: RunNumber,
     },
+    /// A fetch exceeded the handle's resource limits.
+    #[error("{0}")]
+    LimitError(#[from] gluex_core::limits::LimitError),
+    /// The snapshot uses a newer schema than this build supports.
+    #[error("{0}")]
+    NewerSchemaError(#[from] gluex_core::schema::NewerSchemaError),
+}
+
+impl RCDBError {
+    /// Returns true for errors that may go away on a later attempt: `SQLite` reporting the file
+    /// as busy or locked, or an I/O error such as a network filesystem timing out.
+    #[must_use]
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            Self::SqliteError(rusqlite::Error::SqliteFailure(error, _))
+                if matches!(
+                    error.code,
+                    rusqlite::ErrorCode::DatabaseBusy
+                        | rusqlite::ErrorCode::DatabaseLocked
+                        | rusqlite::ErrorCode::SystemIoFailure
+                )
+        )
+    }
 }
 
 /// Re-exports for the most common types.
@@ -69,7 +128,12 @@ pub mod prelude {
         data::Value,
         database::RCDB,
         models::ValueType,
+        overlay::RcdbOverlay,
+        router::RcdbRouter,
         RCDBError, RCDBResult,
     };
-    pub use gluex_core::RunNumber;
+    pub use gluex_core::{
+        run_set::{RunContext, RunSet},
+        RunNumber,
+    };
 }
@@ -3,6 +3,7 @@ use gluex_core::{errors::ParseTimestampError, parsers::parse_timestamp, Id, RunN
 
 /// Typed representation of a condition value column.
 #[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ValueType {
     /// Human readable UTF-8 string payload.
     #[default]
@@ -70,6 +71,7 @@ impl ValueType {
 }
 /// Metadata record for a condition type entry.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ConditionTypeMeta {
     pub(crate) id: Id,
     pub(crate) name: String,
@@ -106,6 +108,7 @@ impl ConditionTypeMeta {
 }
 
 /// Raw metadata row for an individual condition value.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ConditionMeta {
     pub(crate) id: Id,
     pub(crate) text_value: String,
@@ -172,6 +175,7 @@ impl ConditionMeta {
 }
 
 /// Metadata describing a named RCDB run period.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RunPeriodMeta {
     pub(crate) id: Id,
     pub(crate) name: String,
@@ -226,6 +230,7 @@ impl RunPeriodMeta {
 }
 
 /// Metadata describing a single run record.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RunMeta {
     pub(crate) number: RunNumber,
     pub(crate) started: String,
@@ -254,3 +259,47 @@ impl RunMeta {
         parse_timestamp(&self.finished)
     }
 }
+
+/// Configuration file (trigger, DAQ, or other run-control setup) attached to one or more runs.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConfigurationFile {
+    pub(crate) id: Id,
+    pub(crate) path: String,
+    pub(crate) sha256: String,
+    pub(crate) content: Vec<u8>,
+    pub(crate) description: String,
+    pub(crate) importance: i64,
+}
+impl ConfigurationFile {
+    /// Identifier of the file record.
+    #[must_use]
+    pub fn id(&self) -> Id {
+        self.id
+    }
+    /// Original path of the file on the machine that recorded it.
+    #[must_use]
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+    /// SHA-256 digest of the file content as stored in RCDB.
+    #[must_use]
+    pub fn sha256(&self) -> &str {
+        &self.sha256
+    }
+    /// Raw file content.
+    #[must_use]
+    pub fn content(&self) -> &[u8] {
+        &self.content
+    }
+    /// Optional descriptive text for the file.
+    #[must_use]
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+    /// Importance flag assigned when the file was recorded.
+    #[must_use]
+    pub fn importance(&self) -> i64 {
+        self.importance
+    }
+}
@@ -0,0 +1,138 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+};
+
+use gluex_core::RunNumber;
+
+use crate::{
+    context::Context, data::Value, database::RCDB, models::ConfigurationFile, RCDBError, RCDBResult,
+};
+
+/// A condition value together with the overlay layer it was read from.
+#[derive(Debug, Clone)]
+pub struct LayeredValue {
+    /// Index of the layer (in [`RcdbOverlay::layers`]) that satisfied the request.
+    pub layer: usize,
+    /// Value stored in that layer.
+    pub value: Value,
+}
+
+/// An ordered stack of RCDB files, searched from the first (highest priority) layer down.
+///
+/// Every (run, condition) pair is answered by the first layer that holds a value for it, so a
+/// local snapshot with corrected conditions can be stacked on top of the official database
+/// without modifying it. Each layer is only asked for the conditions it defines, and the
+/// context's filters are evaluated inside each layer against that layer's own values.
+///
+/// ```no_run
+/// use gluex_rcdb::{context::Context, overlay::RcdbOverlay};
+///
+/// let overlay = RcdbOverlay::open(["my_conditions.sqlite", "rcdb.sqlite"])?;
+/// let ctx = Context::default().with_run_range(30_274..=30_280);
+/// for (run, values) in overlay.fetch(["polarization_angle"], &ctx)? {
+///     if let Some(angle) = values.get("polarization_angle") {
+///         let source = overlay.layers()[angle.layer].connection_path();
+///         println!("{run}: {:?} from {source}", angle.value.as_float());
+///     }
+/// }
+/// # Ok::<(), gluex_rcdb::RCDBError>(())
+/// ```
+#[derive(Clone)]
+pub struct RcdbOverlay {
+    layers: Vec<RCDB>,
+}
+
+impl RcdbOverlay {
+    /// Stacks already opened databases, highest priority first.
+    #[must_use]
+    pub fn new(layers: Vec<RCDB>) -> Self {
+        Self { layers }
+    }
+
+    /// Opens each path with [`RCDB::open`] and stacks them, highest priority first.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if any of the databases cannot be opened.
+    pub fn open<P: AsRef<Path>>(paths: impl IntoIterator<Item = P>) -> RCDBResult<Self> {
+        Ok(Self::new(
+            paths
+                .into_iter()
+                .map(RCDB::open)
+                .collect::<RCDBResult<_>>()?,
+        ))
+    }
+
+    /// Returns the layers, highest priority first.
+    #[must_use]
+    pub fn layers(&self) -> &[RCDB] {
+        &self.layers
+    }
+
+    /// Fetches condition values for the context, falling through the layers for every run and
+    /// condition that a higher layer does not provide.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the conditions list is empty, if a requested condition is
+    /// not defined in any layer, if a filter refers to a condition that one of the queried layers
+    /// does not define, or if fetching from any layer fails.
+    pub fn fetch<S>(
+        &self,
+        condition_names: S,
+        context: &Context,
+    ) -> RCDBResult<BTreeMap<RunNumber, HashMap<String, LayeredValue>>>
+    where
+        S: IntoIterator,
+        S::Item: AsRef<str>,
+    {
+        let names: Vec<String> = condition_names
+            .into_iter()
+            .map(|name| name.as_ref().to_string())
+            .collect();
+        if names.is_empty() {
+            return Err(RCDBError::EmptyConditionList);
+        }
+        if let Some(missing) = names.iter().find(|name| {
+            self.layers
+                .iter()
+                .all(|db| db.condition_type(name).is_none())
+        }) {
+            return Err(RCDBError::ConditionTypeNotFound(missing.clone()));
+        }
+        let mut resolved: BTreeMap<RunNumber, HashMap<String, LayeredValue>> = BTreeMap::new();
+        for (layer, db) in self.layers.iter().enumerate() {
+            let known: Vec<&String> = names
+                .iter()
+                .filter(|name| db.condition_type(name).is_some())
+                .collect();
+            if known.is_empty() {
+                continue;
+            }
+            for (run, values) in db.fetch(known, context)? {
+                let entry = resolved.entry(run).or_default();
+                for (name, value) in values {
+                    entry.entry(name).or_insert(LayeredValue { layer, value });
+                }
+            }
+        }
+        Ok(resolved)
+    }
+
+    /// Returns the configuration files attached to `run` by the first layer that has any,
+    /// together with that layer's index.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if querying any layer fails.
+    pub fn files(&self, run: RunNumber) -> RCDBResult<Option<(usize, Vec<ConfigurationFile>)>> {
+        for (layer, db) in self.layers.iter().enumerate() {
+            let files = db.files(run)?;
+            if !files.is_empty() {
+                return Ok(Some((layer, files)));
+            }
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,102 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::BuildHasher,
+};
+
+pub use polars;
+
+use gluex_core::RunNumber;
+use polars::prelude::{
+    BooleanChunked, Column, DataFrame, DataType, Float64Chunked, Int64Chunked, IntoColumn,
+    NewChunkedArray, PolarsResult, StringChunked, TimeUnit, TimeZone,
+};
+
+use crate::{data::Value, models::ValueType};
+
+/// Name of the column [`to_dataframe`] puts in front of the conditions.
+pub const RUN_NUMBER_COLUMN: &str = "run_number";
+
+/// Lays out fetched conditions as a wide [`DataFrame`]: one row per run in ascending order, an
+/// `i64` [`RUN_NUMBER_COLUMN`] column, and one nullable column per name in `condition_names`,
+/// null for runs without the condition.
+///
+/// Column dtypes follow the condition types: `int` becomes `Int64`, `float` becomes `Float64`,
+/// `bool` becomes `Boolean`, `string`, `json`, and `blob` become `String`, and `time` becomes a
+/// UTC `Datetime` in microseconds. A condition that no run has is a column of `Null` dtype.
+///
+/// ```no_run
+/// use gluex_rcdb::{context::Context, database::RCDB, polars::to_dataframe};
+///
+/// let db = RCDB::open("rcdb.sqlite")?;
+/// let names = ["beam_current", "run_type", "run_start_time"];
+/// let values = db.fetch(names, &Context::default().with_run_range(30_000..=30_100))?;
+/// let df = to_dataframe(&values, names).expect("conditions fit in a data frame");
+/// println!("{df}");
+/// # Ok::<(), gluex_rcdb::RCDBError>(())
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if polars cannot assemble the frame, which does not happen for fetch results.
+pub fn to_dataframe<S, H: BuildHasher>(
+    values: &BTreeMap<RunNumber, HashMap<String, Value, H>>,
+    condition_names: S,
+) -> PolarsResult<DataFrame>
+where
+    S: IntoIterator,
+    S::Item: AsRef<str>,
+{
+    let mut columns = vec![Column::new(
+        RUN_NUMBER_COLUMN.into(),
+        values.keys().copied().collect::<Vec<RunNumber>>(),
+    )];
+    for name in condition_names {
+        let name = name.as_ref();
+        let cells: Vec<Option<&Value>> = values.values().map(|row| row.get(name)).collect();
+        columns.push(condition_column(name, &cells));
+    }
+    DataFrame::new(columns)
+}
+
+fn condition_column(name: &str, cells: &[Option<&Value>]) -> Column {
+    let value_type = cells
+        .iter()
+        .flatten()
+        .map(|value| value.value_type())
+        .next();
+    let name = name.into();
+    match value_type {
+        None => Column::full_null(name, cells.len(), &DataType::Null),
+        Some(ValueType::Int) => Int64Chunked::from_iter_options(
+            name,
+            cells.iter().map(|cell| cell.and_then(Value::as_int)),
+        )
+        .into_column(),
+        Some(ValueType::Float) => Float64Chunked::from_iter_options(
+            name,
+            cells.iter().map(|cell| cell.and_then(Value::as_float)),
+        )
+        .into_column(),
+        Some(ValueType::Bool) => BooleanChunked::from_iter_options(
+            name,
+            cells.iter().map(|cell| cell.and_then(Value::as_bool)),
+        )
+        .into_column(),
+        Some(ValueType::Time) => Int64Chunked::from_iter_options(
+            name,
+            cells.iter().map(|cell| {
+                cell.and_then(Value::as_time)
+                    .map(|time| time.timestamp_micros())
+            }),
+        )
+        .into_datetime(TimeUnit::Microseconds, Some(TimeZone::UTC))
+        .into_column(),
+        Some(ValueType::String | ValueType::Json | ValueType::Blob) => {
+            StringChunked::from_iter_options(
+                name,
+                cells.iter().map(|cell| cell.and_then(Value::as_string)),
+            )
+            .into_column()
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write as _,
+};
+
+use gluex_core::{
+    provenance::{sha256_file, sha256_hex, DatabaseKind, Discrepancy, Manifest, Query, RcdbQuery},
+    RunNumber,
+};
+
+use crate::{context::Context, data::Value, database::RCDB, RCDBError, RCDBResult};
+
+pub(crate) fn fetch_query(
+    database: &str,
+    conditions: &[String],
+    context: &Context,
+    values: &BTreeMap<RunNumber, HashMap<String, Value>>,
+) -> Query {
+    Query::Rcdb(RcdbQuery {
+        database: database.to_string(),
+        conditions: conditions.to_vec(),
+        selection: format!("{:?}", context.selection()),
+        filters: context.filters().iter().map(ToString::to_string).collect(),
+        runs: values.keys().copied().collect(),
+        digest: values_digest(values),
+    })
+}
+
+pub(crate) fn runs_query(database: &str, context: &Context, runs: &[RunNumber]) -> Query {
+    Query::Rcdb(RcdbQuery {
+        database: database.to_string(),
+        conditions: Vec::new(),
+        selection: format!("{:?}", context.selection()),
+        filters: context.filters().iter().map(ToString::to_string).collect(),
+        runs: runs.to_vec(),
+        digest: runs_digest(runs),
+    })
+}
+
+fn values_digest(values: &BTreeMap<RunNumber, HashMap<String, Value>>) -> String {
+    let mut text = String::new();
+    for (run, row) in values {
+        let mut names: Vec<&String> = row.keys().collect();
+        names.sort();
+        for name in names {
+            let _ = writeln!(text, "{run}\t{name}\t{:?}", row[name]);
+        }
+    }
+    sha256_hex(text.as_bytes())
+}
+
+fn runs_digest(runs: &[RunNumber]) -> String {
+    let text = runs.iter().fold(String::new(), |mut text, run| {
+        let _ = writeln!(text, "{run}");
+        text
+    });
+    sha256_hex(text.as_bytes())
+}
+
+impl RCDB {
+    /// Replays the RCDB queries in `manifest` against this database and reports every difference.
+    ///
+    /// Each recorded query is repeated for exactly the runs it returned, and the run list and a
+    /// digest of the condition values are compared with the recorded ones. Filters are kept in
+    /// the manifest for reference but are not re-applied. The hash of this database file is also
+    /// compared with the hashes of the RCDB files in the manifest. An empty result means the
+    /// manifest is reproduced exactly.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if an SQL query fails while replaying. Missing condition
+    /// types are reported as discrepancies instead.
+    pub fn verify_manifest(&self, manifest: &Manifest) -> RCDBResult<Vec<Discrepancy>> {
+        let mut discrepancies = Vec::new();
+        let recorded_hashes: Vec<&str> = manifest
+            .databases
+            .values()
+            .filter(|record| record.kind == DatabaseKind::Rcdb)
+            .filter_map(|record| record.sha256.as_deref())
+            .collect();
+        if !recorded_hashes.is_empty() {
+            match sha256_file(self.connection_path()) {
+                Ok(hash) if recorded_hashes.contains(&hash.as_str()) => {}
+                Ok(hash) => discrepancies.push(Discrepancy {
+                    query: None,
+                    message: format!(
+                        "RCDB file {} has hash {hash}, which is not recorded in the manifest",
+                        self.connection_path()
+                    ),
+                }),
+                Err(err) => discrepancies.push(Discrepancy {
+                    query: None,
+                    message: format!("could not hash {}: {err}", self.connection_path()),
+                }),
+            }
+        }
+        for (index, query) in manifest.queries.iter().enumerate() {
+            let Query::Rcdb(query) = query else {
+                continue;
+            };
+            let context = Context::default().with_runs(query.runs.iter().copied());
+            let (runs, digest) = if query.conditions.is_empty() {
+                let runs = self.fetch_runs(&context)?;
+                let digest = runs_digest(&runs);
+                (runs, digest)
+            } else {
+                match self.fetch(&query.conditions, &context) {
+                    Ok(values) => (values.keys().copied().collect(), values_digest(&values)),
+                    Err(RCDBError::SqliteError(err)) => return Err(err.into()),
+                    Err(err) => {
+                        discrepancies.push(Discrepancy {
+                            query: Some(index),
+                            message: err.to_string(),
+                        });
+                        continue;
+                    }
+                }
+            };
+            if runs != query.runs {
+                discrepancies.push(Discrepancy {
+                    query: Some(index),
+                    message: format!(
+                        "recorded {} runs, {} of them are still present",
+                        query.runs.len(),
+                        runs.len()
+                    ),
+                });
+            } else if digest != query.digest {
+                discrepancies.push(Discrepancy {
+                    query: Some(index),
+                    message: format!(
+                        "values of {} changed for the recorded runs",
+                        query.conditions.join(", ")
+                    ),
+                });
+            }
+        }
+        Ok(discrepancies)
+    }
+}
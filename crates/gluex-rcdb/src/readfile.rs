@@ -0,0 +1,167 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::BuildHasher,
+    io::{self, Write},
+};
+
+use gluex_core::RunNumber;
+
+use crate::{data::Value, database::RCDB, models::ValueType, RCDBError, RCDBResult};
+
+/// Name of the branch holding the run number.
+pub const RUN_BRANCH: &str = "run";
+
+/// Cell written for an empty or missing string, JSON, or blob value, which `TTree::ReadFile`
+/// reads back as an empty string. An empty cell would not be read as a field.
+pub const EMPTY_STRING: &str = "\"\"";
+
+/// Prefix of the flag branch that records whether a run has a value for a condition.
+pub const HAS_PREFIX: &str = "has_";
+
+/// One condition column of the exported table, read by ROOT as a branch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Branch {
+    name: String,
+    value_type: ValueType,
+}
+
+impl Branch {
+    /// Creates a branch for a condition of the given type.
+    pub fn new(name: impl Into<String>, value_type: ValueType) -> Self {
+        Self {
+            name: name.into(),
+            value_type,
+        }
+    }
+
+    /// Looks up the branch type of each condition in `db`, keeping the order of `condition_names`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RCDBError::ConditionTypeNotFound`] if a condition does not exist.
+    pub fn for_conditions<S>(db: &RCDB, condition_names: S) -> RCDBResult<Vec<Self>>
+    where
+        S: IntoIterator,
+        S::Item: AsRef<str>,
+    {
+        condition_names
+            .into_iter()
+            .map(|name| {
+                let name = name.as_ref();
+                db.condition_type(name)
+                    .map(|condition_type| Self::new(name, condition_type.value_type()))
+                    .ok_or_else(|| RCDBError::ConditionTypeNotFound(name.to_string()))
+            })
+            .collect()
+    }
+
+    /// Condition name, which is also the branch name.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// RCDB value type of the condition.
+    #[must_use]
+    pub fn value_type(&self) -> ValueType {
+        self.value_type
+    }
+
+    /// Leaf type code understood by `TTree::ReadFile`.
+    ///
+    /// Integers and times (seconds since the Unix epoch) become 64-bit `L` leaves, floats become
+    /// `D`, booleans `O`, and strings, JSON, and blobs `C`.
+    #[must_use]
+    pub fn type_code(&self) -> char {
+        match self.value_type {
+            ValueType::Int | ValueType::Time => 'L',
+            ValueType::Float => 'D',
+            ValueType::Bool => 'O',
+            ValueType::String | ValueType::Json | ValueType::Blob => 'C',
+        }
+    }
+
+    /// Name of the `O` branch that is `1` for runs with a value for this condition and `0` for
+    /// runs without one, e.g. `has_beam_current`.
+    #[must_use]
+    pub fn flag_name(&self) -> String {
+        format!("{HAS_PREFIX}{}", self.name)
+    }
+
+    fn cell(&self, value: Option<&Value>) -> String {
+        match self.value_type {
+            ValueType::Int => value
+                .and_then(Value::as_int)
+                .unwrap_or_default()
+                .to_string(),
+            ValueType::Float => value
+                .and_then(Value::as_float)
+                .unwrap_or_default()
+                .to_string(),
+            ValueType::Bool => {
+                u8::from(value.and_then(Value::as_bool).unwrap_or_default()).to_string()
+            }
+            ValueType::Time => value
+                .and_then(Value::as_time)
+                .map_or(0, |time| time.timestamp())
+                .to_string(),
+            ValueType::String | ValueType::Json | ValueType::Blob => {
+                match value.and_then(Value::as_string).unwrap_or_default() {
+                    "" => EMPTY_STRING.to_string(),
+                    text => text.replace(['\t', '\n', '\r'], " "),
+                }
+            }
+        }
+    }
+}
+
+/// Branch descriptor line for `TTree::ReadFile`, e.g.
+/// `run/L:event_count/L:has_event_count/O:beam_current/D:has_beam_current/O`.
+///
+/// Every condition branch is followed by its [`Branch::flag_name`] branch.
+#[must_use]
+pub fn descriptor(branches: &[Branch]) -> String {
+    std::iter::once(format!("{RUN_BRANCH}/L"))
+        .chain(branches.iter().flat_map(|branch| {
+            [
+                format!("{}/{}", branch.name, branch.type_code()),
+                format!("{}/O", branch.flag_name()),
+            ]
+        }))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Writes fetched conditions as `TTree::ReadFile` text: a tab-delimited table that ROOT reads into a
+/// flat `TTree` with one entry per run and one branch per condition.
+///
+/// The first line is the branch [`descriptor`], so the file loads with
+/// `tree->ReadFile("conditions.tsv", "", '\t')`. Runs without a value for a condition get `0` in
+/// its `has_` flag branch, and the value branch holds `0`, `false`, or an empty string, so select
+/// on the flag (e.g. `has_beam_current`) rather than on the value. Empty strings are written as
+/// [`EMPTY_STRING`], and tabs or line breaks inside strings are replaced by spaces.
+///
+/// # Errors
+///
+/// Returns any error raised by `writer`.
+pub fn write_text<W: Write, H: BuildHasher>(
+    mut writer: W,
+    branches: &[Branch],
+    rows: &BTreeMap<RunNumber, HashMap<String, Value, H>>,
+) -> io::Result<()> {
+    writeln!(writer, "{}", descriptor(branches))?;
+    for (run, values) in rows {
+        write!(writer, "{run}")?;
+        for branch in branches {
+            let value = values.get(&branch.name);
+            write!(
+                writer,
+                "\t{}\t{}",
+                branch.cell(value),
+                u8::from(value.is_some())
+            )?;
+        }
+        writeln!(writer)?;
+    }
+    writer.flush()
+}
@@ -0,0 +1,147 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::RangeBounds,
+    path::Path,
+};
+
+use gluex_core::{routing::RunRouter, run_set::RunContext, RunNumber};
+
+use crate::{context::Context, data::Value, database::RCDB, RCDBError, RCDBResult};
+
+/// RCDB files keyed by the run ranges they serve, queried as a single database.
+///
+/// Every run of a fetch is answered by the first route whose range contains it, or by the default
+/// database if no route does, so an analysis spanning several run periods can read conditions from
+/// a dedicated snapshot and the standard one through one handle. Each database is only asked for
+/// the part of the context's selection it serves, and the context's filters are evaluated inside
+/// each database. Runs that are neither routed nor covered by a default are left out of the result.
+///
+/// ```no_run
+/// use gluex_rcdb::{context::Context, database::RCDB, router::RcdbRouter};
+///
+/// let router = RcdbRouter::new()
+///     .open_route(61_000..=61_999, "primex_rcdb.sqlite")?
+///     .open_default("rcdb.sqlite")?;
+/// let ctx = Context::default().with_runs([30_274, 61_321]);
+/// for (run, values) in router.fetch(["event_count"], &ctx)? {
+///     let source = router.handle(run).map(RCDB::connection_path);
+///     println!("{run}: {:?} from {source:?}", values["event_count"].as_int());
+/// }
+/// # Ok::<(), gluex_rcdb::RCDBError>(())
+/// ```
+#[derive(Clone, Default)]
+pub struct RcdbRouter {
+    router: RunRouter<RCDB>,
+}
+
+impl RcdbRouter {
+    /// Creates a router without routes or default database.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serves the runs within `runs` from `db`, unless an earlier route already covers them.
+    #[must_use]
+    pub fn with_route(mut self, runs: impl RangeBounds<RunNumber>, db: RCDB) -> Self {
+        self.router = self.router.with_route(runs, db);
+        self
+    }
+
+    /// Serves every run that no route covers from `db`.
+    #[must_use]
+    pub fn with_default(mut self, db: RCDB) -> Self {
+        self.router = self.router.with_default(db);
+        self
+    }
+
+    /// Opens `path` with [`RCDB::open`] and adds it as a route (see [`RcdbRouter::with_route`]).
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the database cannot be opened.
+    pub fn open_route(
+        self,
+        runs: impl RangeBounds<RunNumber>,
+        path: impl AsRef<Path>,
+    ) -> RCDBResult<Self> {
+        Ok(self.with_route(runs, RCDB::open(path)?))
+    }
+
+    /// Opens `path` with [`RCDB::open`] and makes it the default database.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the database cannot be opened.
+    pub fn open_default(self, path: impl AsRef<Path>) -> RCDBResult<Self> {
+        Ok(self.with_default(RCDB::open(path)?))
+    }
+
+    /// Returns the routing table.
+    #[must_use]
+    pub fn router(&self) -> &RunRouter<RCDB> {
+        &self.router
+    }
+
+    /// Returns the database that serves `run`.
+    #[must_use]
+    pub fn handle(&self, run: RunNumber) -> Option<&RCDB> {
+        self.router.route(run)
+    }
+
+    /// Fetches condition values for the context, asking each database only for the runs it
+    /// serves.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if the conditions list is empty, if a database that serves
+    /// part of the selection does not define a requested condition or a condition used in a
+    /// filter, or if fetching from it fails.
+    pub fn fetch<S>(
+        &self,
+        condition_names: S,
+        context: &Context,
+    ) -> RCDBResult<BTreeMap<RunNumber, HashMap<String, Value>>>
+    where
+        S: IntoIterator,
+        S::Item: AsRef<str>,
+    {
+        let names: Vec<String> = condition_names
+            .into_iter()
+            .map(|name| name.as_ref().to_string())
+            .collect();
+        if names.is_empty() {
+            return Err(RCDBError::EmptyConditionList);
+        }
+        let mut resolved = BTreeMap::new();
+        for (index, db, runs) in self.router.plan(&context.run_set()) {
+            let values = db.fetch(&names, &context.clone().with_run_set(runs))?;
+            resolved.extend(
+                values
+                    .into_iter()
+                    .filter(|(run, _)| self.router.index(*run) == Some(index)),
+            );
+        }
+        Ok(resolved)
+    }
+
+    /// Returns the runs in the context's selection that satisfy its filters, each evaluated in the
+    /// database that serves the run.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if a filter refers to a condition that a queried database does
+    /// not define, or if querying it fails.
+    pub fn fetch_runs(&self, context: &Context) -> RCDBResult<Vec<RunNumber>> {
+        let mut runs = Vec::new();
+        for (index, db, selection) in self.router.plan(&context.run_set()) {
+            runs.extend(
+                db.fetch_runs(&context.clone().with_run_set(selection))?
+                    .into_iter()
+                    .filter(|run| self.router.index(*run) == Some(index)),
+            );
+        }
+        runs.sort_unstable();
+        Ok(runs)
+    }
+}
@@ -0,0 +1,228 @@
+use std::{ops::RangeInclusive, path::Path};
+
+use gluex_core::RunNumber;
+use rusqlite::{params, Connection, OpenFlags};
+
+use crate::{database::RCDB, models::ConditionTypeMeta, RCDBError, RCDBResult};
+
+/// Tables that are small and needed by every query, so they are copied in full.
+const FULL_TABLES: &[&str] = &["schema_versions", "run_periods"];
+
+const SCHEMA_SQL: &str = "SELECT sql FROM src.sqlite_master
+     WHERE type IN ('table', 'index') AND sql IS NOT NULL AND name NOT LIKE 'sqlite_%'
+     ORDER BY type = 'index', name";
+
+const EXTRACT_SQL: &str = "
+    INSERT INTO main.condition_types
+        SELECT * FROM src.condition_types WHERE id IN temp.keep_types;
+    INSERT INTO main.runs
+        SELECT r.* FROM src.runs r JOIN temp.keep_runs k
+        WHERE r.number BETWEEN k.run_min AND k.run_max;
+    INSERT INTO main.conditions
+        SELECT c.* FROM src.conditions c JOIN temp.keep_runs k
+        WHERE c.run_number BETWEEN k.run_min AND k.run_max
+          AND c.condition_type_id IN temp.keep_types;
+    INSERT INTO main.files_have_runs
+        SELECT f.* FROM src.files_have_runs f JOIN temp.keep_runs k
+        WHERE f.run_number BETWEEN k.run_min AND k.run_max;
+    INSERT INTO main.files
+        SELECT * FROM src.files WHERE id IN (SELECT files_id FROM main.files_have_runs);";
+
+const MERGE_SQL: &str = "
+    CREATE TEMP TABLE map_condition_types(old INTEGER PRIMARY KEY, new INTEGER, is_new INTEGER);
+    INSERT INTO temp.map_condition_types
+        SELECT o.id,
+               COALESCE(b.id, (SELECT COALESCE(MAX(id), 0) FROM main.condition_types) + o.id),
+               b.id IS NULL
+        FROM ovl.condition_types o
+        LEFT JOIN main.condition_types b ON b.name = o.name;
+    CREATE TEMP TABLE map_files(old INTEGER PRIMARY KEY, new INTEGER);
+    INSERT INTO temp.map_files
+        SELECT id, (SELECT COALESCE(MAX(id), 0) FROM main.files) + id FROM ovl.files;
+    INSERT OR IGNORE INTO main.runs SELECT * FROM ovl.runs;
+    DELETE FROM main.conditions
+        WHERE EXISTS (
+            SELECT 1 FROM ovl.conditions o
+            JOIN temp.map_condition_types m ON m.old = o.condition_type_id
+            WHERE o.run_number = main.conditions.run_number
+              AND m.new = main.conditions.condition_type_id
+        );";
+
+impl RCDB {
+    /// Writes a trimmed copy of this database to `output`.
+    ///
+    /// The snapshot keeps the full schema, the schema version table, the runs in `runs`,
+    /// and the values of the named conditions for those runs (every condition when
+    /// `conditions` is empty), along with any configuration files attached to the kept
+    /// runs. Other tables are created empty.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if a condition name is unknown, if `output` already
+    /// contains conflicting tables, or if any `SQLite` operation fails.
+    pub fn extract_snapshot<S>(
+        &self,
+        output: impl AsRef<Path>,
+        conditions: &[S],
+        runs: RangeInclusive<RunNumber>,
+    ) -> RCDBResult<()>
+    where
+        S: AsRef<str>,
+    {
+        let type_ids = if conditions.is_empty() {
+            self.condition_types()
+                .iter()
+                .map(ConditionTypeMeta::id)
+                .collect()
+        } else {
+            conditions
+                .iter()
+                .map(|name| {
+                    self.condition_type(name.as_ref())
+                        .map(|t| t.id())
+                        .ok_or_else(|| RCDBError::ConditionTypeNotFound(name.as_ref().to_string()))
+                })
+                .collect::<RCDBResult<Vec<_>>>()?
+        };
+        let mut conn = Connection::open(output)?;
+        conn.execute("ATTACH DATABASE ?1 AS src", [self.connection_path()])?;
+        let tx = conn.transaction()?;
+        copy_schema(&tx)?;
+        tx.execute_batch(
+            "CREATE TEMP TABLE keep_types(id INTEGER PRIMARY KEY);
+             CREATE TEMP TABLE keep_runs(run_min INTEGER, run_max INTEGER);",
+        )?;
+        {
+            let mut stmt = tx.prepare("INSERT OR IGNORE INTO temp.keep_types(id) VALUES (?1)")?;
+            for id in &type_ids {
+                stmt.execute([id])?;
+            }
+        }
+        tx.execute(
+            "INSERT INTO temp.keep_runs(run_min, run_max) VALUES (?1, ?2)",
+            params![runs.start(), runs.end()],
+        )?;
+        for table in FULL_TABLES {
+            if has_table(&tx, table)? {
+                tx.execute_batch(&format!(
+                    "INSERT INTO main.{table} SELECT * FROM src.{table};"
+                ))?;
+            }
+        }
+        tx.execute_batch(EXTRACT_SQL)?;
+        tx.execute_batch("DROP TABLE temp.keep_types; DROP TABLE temp.keep_runs;")?;
+        tx.commit()?;
+        conn.execute("DETACH DATABASE src", [])?;
+        Ok(())
+    }
+}
+
+/// Merges the RCDB file at `overlay` on top of the one at `base`, writing the result to `output`.
+///
+/// Condition types are matched by name and added with a fresh id when missing from `base`.
+/// Runs that only exist in `overlay` are added, and every condition value in `overlay` replaces
+/// the base value for the same run and condition. Configuration files are appended with
+/// remapped ids. Neither input file is modified.
+///
+/// # Errors
+///
+/// This method returns an error if `output` already exists, if the overlay's tables do not
+/// have the same columns as the base ones, or if any `SQLite` operation fails.
+pub fn merge(
+    base: impl AsRef<Path>,
+    overlay: impl AsRef<Path>,
+    output: impl AsRef<Path>,
+) -> RCDBResult<()> {
+    let output = output.as_ref().to_string_lossy().to_string();
+    Connection::open_with_flags(base, OpenFlags::SQLITE_OPEN_READ_ONLY)?
+        .execute("VACUUM INTO ?1", [&output])?;
+    let mut conn = Connection::open(&output)?;
+    conn.execute(
+        "ATTACH DATABASE ?1 AS ovl",
+        [overlay.as_ref().to_string_lossy()],
+    )?;
+    let tx = conn.transaction()?;
+    tx.execute_batch(MERGE_SQL)?;
+    insert_remapped(
+        &tx,
+        "condition_types",
+        &[("id", "m.new")],
+        "JOIN temp.map_condition_types m ON m.old = o.id WHERE m.is_new",
+    )?;
+    insert_remapped(
+        &tx,
+        "conditions",
+        &[
+            (
+                "id",
+                "(SELECT COALESCE(MAX(id), 0) FROM main.conditions) + o.id",
+            ),
+            ("condition_type_id", "m.new"),
+        ],
+        "JOIN temp.map_condition_types m ON m.old = o.condition_type_id",
+    )?;
+    insert_remapped(
+        &tx,
+        "files",
+        &[("id", "m.new")],
+        "JOIN temp.map_files m ON m.old = o.id",
+    )?;
+    insert_remapped(
+        &tx,
+        "files_have_runs",
+        &[("files_id", "m.new")],
+        "JOIN temp.map_files m ON m.old = o.files_id",
+    )?;
+    tx.commit()?;
+    conn.execute("DETACH DATABASE ovl", [])?;
+    Ok(())
+}
+
+/// Copies rows of `table` from the attached `ovl` database (aliased `o`) into `main`,
+/// replacing the listed columns with SQL expressions. `tail` supplies joins and filters.
+fn insert_remapped(
+    conn: &Connection,
+    table: &str,
+    remaps: &[(&str, &str)],
+    tail: &str,
+) -> rusqlite::Result<usize> {
+    let columns = conn
+        .prepare(&format!("PRAGMA main.table_info(\"{table}\")"))?
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    let targets = columns
+        .iter()
+        .map(|column| format!("\"{column}\""))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let values = columns
+        .iter()
+        .map(|column| {
+            remaps.iter().find(|(name, _)| name == column).map_or_else(
+                || format!("o.\"{column}\""),
+                |(_, expr)| (*expr).to_string(),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    conn.execute(
+        &format!("INSERT INTO main.\"{table}\" ({targets}) SELECT {values} FROM ovl.\"{table}\" o {tail}"),
+        [],
+    )
+}
+
+fn copy_schema(conn: &Connection) -> rusqlite::Result<()> {
+    let statements = conn
+        .prepare(SCHEMA_SQL)?
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    for sql in statements {
+        conn.execute_batch(&sql)?;
+    }
+    Ok(())
+}
+
+fn has_table(conn: &Connection, name: &str) -> rusqlite::Result<bool> {
+    conn.prepare("SELECT 1 FROM src.sqlite_master WHERE type = 'table' AND name = ?1")?
+        .exists([name])
+}
@@ -0,0 +1,115 @@
+use std::collections::{btree_map, HashMap, VecDeque};
+
+use gluex_core::RunNumber;
+
+use crate::{context::Context, data::Value, database::RCDB, RCDBError, RCDBResult};
+
+/// Number of runs [`FetchIter`] loads condition values for at a time unless told otherwise.
+pub const DEFAULT_CHUNK_SIZE: usize = 1000;
+
+/// Lazy iterator over fetched conditions, yielding `(run, values)` pairs in ascending run order.
+///
+/// Created by [`RCDB::fetch_iter`]. The runs matching the context are resolved when the iterator
+/// is created, but condition values are only loaded a chunk of runs at a time, so memory stays
+/// bounded by the chunk size however many runs are selected. Each item matches the entry
+/// [`RCDB::fetch`] would return for that run. After a chunk fails, its error is yielded and
+/// iteration ends.
+pub struct FetchIter {
+    db: RCDB,
+    names: Vec<String>,
+    pending: VecDeque<RunNumber>,
+    buffered: btree_map::IntoIter<RunNumber, HashMap<String, Value>>,
+    chunk_size: usize,
+}
+
+impl FetchIter {
+    /// Sets how many runs are loaded per query (at least one, [`DEFAULT_CHUNK_SIZE`] by
+    /// default). Larger chunks mean fewer queries but more memory per chunk.
+    #[must_use]
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Number of matching runs whose values have not been loaded yet; runs already loaded but
+    /// not yet yielded are not counted.
+    #[must_use]
+    pub fn remaining_runs(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+impl Iterator for FetchIter {
+    type Item = RCDBResult<(RunNumber, HashMap<String, Value>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(entry) = self.buffered.next() {
+                return Some(Ok(entry));
+            }
+            if self.pending.is_empty() {
+                return None;
+            }
+            let take = self.chunk_size.min(self.pending.len());
+            let ctx = Context::default().with_runs(self.pending.drain(..take));
+            match self.db.fetch(&self.names, &ctx) {
+                Ok(values) => self.buffered = values.into_iter(),
+                Err(err) => {
+                    self.pending.clear();
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
+
+impl RCDB {
+    /// Fetches conditions like [`RCDB::fetch`], but lazily: the runs matching `context` are
+    /// resolved up front (see [`RCDB::fetch_runs`]), and the returned iterator loads their
+    /// values [`DEFAULT_CHUNK_SIZE`] runs at a time (see [`FetchIter::with_chunk_size`]), so
+    /// survey scripts over every run need memory for only one chunk.
+    ///
+    /// ```no_run
+    /// use gluex_rcdb::{context::Context, database::RCDB};
+    ///
+    /// let db = RCDB::open("rcdb.sqlite")?;
+    /// for entry in db.fetch_iter(["event_count"], &Context::default())? {
+    ///     let (run, values) = entry?;
+    ///     println!("{run}: {:?}", values.get("event_count"));
+    /// }
+    /// # Ok::<(), gluex_rcdb::RCDBError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This method will return an error if the conditions list is empty, if any requested
+    /// condition cannot be found, or if resolving the matching runs fails. Errors loading values
+    /// are yielded by the iterator.
+    pub fn fetch_iter<S>(&self, condition_names: S, context: &Context) -> RCDBResult<FetchIter>
+    where
+        S: IntoIterator,
+        S::Item: AsRef<str>,
+    {
+        let names: Vec<String> = condition_names
+            .into_iter()
+            .map(|name| name.as_ref().to_string())
+            .collect();
+        if names.is_empty() {
+            return Err(RCDBError::EmptyConditionList);
+        }
+        if let Some(name) = names
+            .iter()
+            .find(|name| self.condition_type(name).is_none())
+        {
+            return Err(RCDBError::ConditionTypeNotFound(name.clone()));
+        }
+        let pending = self.fetch_runs(context)?.into();
+        Ok(FetchIter {
+            db: self.clone(),
+            names,
+            pending,
+            buffered: btree_map::IntoIter::default(),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        })
+    }
+}
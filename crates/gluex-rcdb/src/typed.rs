@@ -0,0 +1,173 @@
+use std::{collections::HashMap, hash::BuildHasher};
+
+use chrono::{DateTime, Utc};
+
+use crate::{data::Value, models::ValueType, RCDBError, RCDBResult};
+
+#[cfg(feature = "derive")]
+pub use gluex_rcdb_derive::RcdbConditions;
+
+/// A Rust type that the conditions of one run can be read into.
+///
+/// With the `derive` feature, `#[derive(RcdbConditions)]` implements it by reading every named
+/// field from the condition of the same name, or from the condition given with
+/// `#[rcdb(condition = "...")]`. The derived implementation is equivalent to this hand-written
+/// one:
+///
+/// ```no_run
+/// use std::collections::HashMap;
+///
+/// use gluex_rcdb::{
+///     context::Context,
+///     data::Value,
+///     database::RCDB,
+///     typed::{condition, RcdbConditions},
+///     RCDBResult,
+/// };
+///
+/// // #[derive(RcdbConditions)]
+/// struct BeamInfo {
+///     beam_current: f64,
+///     // #[rcdb(condition = "polarization_angle")]
+///     angle: Option<f64>,
+///     run_type: String,
+/// }
+///
+/// impl RcdbConditions for BeamInfo {
+///     const CONDITIONS: &'static [&'static str] =
+///         &["beam_current", "polarization_angle", "run_type"];
+///
+///     fn from_conditions(values: &HashMap<String, Value>) -> RCDBResult<Option<Self>> {
+///         let Some(beam_current) = condition(values, "beam_current")? else {
+///             return Ok(None);
+///         };
+///         let Some(angle) = condition(values, "polarization_angle")? else {
+///             return Ok(None);
+///         };
+///         let Some(run_type) = condition(values, "run_type")? else {
+///             return Ok(None);
+///         };
+///         Ok(Some(Self { beam_current, angle, run_type }))
+///     }
+/// }
+///
+/// let db = RCDB::open("rcdb.sqlite")?;
+/// let ctx = Context::default().with_run_range(30_000..=30_100);
+/// for (run, info) in db.fetch_typed::<BeamInfo>(&ctx)? {
+///     println!("{run}: {} nA, {}", info.beam_current, info.run_type);
+/// }
+/// # Ok::<(), gluex_rcdb::RCDBError>(())
+/// ```
+pub trait RcdbConditions: Sized {
+    /// Names of the conditions the type reads.
+    const CONDITIONS: &'static [&'static str];
+
+    /// Reads the conditions fetched for one run, or returns `None` if the run lacks a condition
+    /// that a non-[`Option`] field requires.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RCDBError::ConditionTypeMismatch`] if a condition stores a different type than
+    /// its field.
+    fn from_conditions(values: &HashMap<String, Value>) -> RCDBResult<Option<Self>>;
+}
+
+/// A Rust type a single RCDB condition value converts to.
+///
+/// Textual conditions (`string`, `json`, and `blob`) all convert to [`String`]; other types are
+/// never converted into each other.
+pub trait ConditionValue: Sized {
+    /// The condition type reported in [`RCDBError::ConditionTypeMismatch`].
+    const VALUE_TYPE: ValueType;
+
+    /// Converts a value, or returns `None` if it stores a different type.
+    fn from_value(value: &Value) -> Option<Self>;
+}
+
+impl ConditionValue for i64 {
+    const VALUE_TYPE: ValueType = ValueType::Int;
+
+    fn from_value(value: &Value) -> Option<Self> {
+        value.as_int()
+    }
+}
+
+impl ConditionValue for f64 {
+    const VALUE_TYPE: ValueType = ValueType::Float;
+
+    fn from_value(value: &Value) -> Option<Self> {
+        value.as_float()
+    }
+}
+
+impl ConditionValue for bool {
+    const VALUE_TYPE: ValueType = ValueType::Bool;
+
+    fn from_value(value: &Value) -> Option<Self> {
+        value.as_bool()
+    }
+}
+
+impl ConditionValue for String {
+    const VALUE_TYPE: ValueType = ValueType::String;
+
+    fn from_value(value: &Value) -> Option<Self> {
+        value.as_string().map(str::to_string)
+    }
+}
+
+impl ConditionValue for DateTime<Utc> {
+    const VALUE_TYPE: ValueType = ValueType::Time;
+
+    fn from_value(value: &Value) -> Option<Self> {
+        value.as_time()
+    }
+}
+
+/// A field of an [`RcdbConditions`] type: a [`ConditionValue`], which the run must have, or an
+/// [`Option`] of one, which is `None` for runs without the condition.
+pub trait ConditionField: Sized {
+    /// Converts the value of condition `name` for one run, returning `None` if the run is missing
+    /// a required condition.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`RCDBError::ConditionTypeMismatch`] if the value has a different type.
+    fn from_field(name: &str, value: Option<&Value>) -> RCDBResult<Option<Self>>;
+}
+
+impl<T: ConditionValue> ConditionField for T {
+    fn from_field(name: &str, value: Option<&Value>) -> RCDBResult<Option<Self>> {
+        value.map(|value| convert(name, value)).transpose()
+    }
+}
+
+impl<T: ConditionValue> ConditionField for Option<T> {
+    fn from_field(name: &str, value: Option<&Value>) -> RCDBResult<Option<Self>> {
+        value
+            .map(|value| convert(name, value))
+            .transpose()
+            .map(Some)
+    }
+}
+
+/// Reads condition `name` from the values fetched for one run; this is what derived
+/// [`RcdbConditions`] implementations call for each field.
+///
+/// # Errors
+///
+/// Returns [`RCDBError::ConditionTypeMismatch`] if the value has a different type than `T`.
+pub fn condition<T: ConditionField, H: BuildHasher>(
+    values: &HashMap<String, Value, H>,
+    name: &str,
+) -> RCDBResult<Option<T>> {
+    T::from_field(name, values.get(name))
+}
+
+fn convert<T: ConditionValue>(name: &str, value: &Value) -> RCDBResult<T> {
+    T::from_value(value).ok_or_else(|| RCDBError::ConditionTypeMismatch {
+        condition_name: name.to_string(),
+        expected: T::VALUE_TYPE,
+        actual: value.value_type(),
+    })
+}
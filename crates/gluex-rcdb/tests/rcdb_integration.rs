@@ -149,3 +149,128 @@ fn fetch_runs_with_alias() -> RCDBResult<()> {
     assert!(!runs.is_empty());
     Ok(())
 }
+
+#[test]
+fn condition_types_are_sorted_and_searchable() {
+    let db = open_db();
+    let types = db.condition_types();
+    assert!(types
+        .windows(2)
+        .all(|pair| pair[0].name() <= pair[1].name()));
+    let event_count = db
+        .condition_type("event_count")
+        .expect("missing event_count condition type");
+    assert_eq!(event_count.value_type(), ValueType::Int);
+    assert!(types.iter().any(|meta| meta.name() == "event_count"));
+    assert!(db.condition_type("definitely_not_a_condition").is_none());
+}
+
+#[test]
+fn aliases_can_be_looked_up_by_name() {
+    use conditions::aliases;
+
+    for name in aliases::NAMES {
+        assert!(aliases::get(name).is_some(), "missing alias {name}");
+    }
+    assert_eq!(
+        aliases::get("is_cosmic").map(|expr| expr.to_string()),
+        Some(aliases::is_cosmic().to_string())
+    );
+    assert!(aliases::get("not_an_alias").is_none());
+}
+
+#[test]
+fn sql_preview_inlines_parameters() -> RCDBResult<()> {
+    let expr = conditions::all([
+        conditions::string_cond("run_type").isin(["hd_all.tsg", "it's"]),
+        conditions::float_cond("beam_current").gt(2.0),
+        conditions::int_cond("event_count").gt(500_000).negate(),
+        conditions::float_cond("beam_current").lt(50.0),
+    ]);
+    assert_eq!(
+        expr.to_sql_preview()?,
+        "(cond_0.text_value IN ('hd_all.tsg', 'it''s') AND cond_1.float_value > 2.0 \
+         AND NOT (cond_2.int_value > 500000) AND cond_1.float_value < 50.0)"
+    );
+    Ok(())
+}
+
+#[test]
+fn files_are_listed_per_run() -> RCDBResult<()> {
+    let db = open_db();
+    assert!(db.files(-1)?.is_empty());
+    let files = db.files(2)?;
+    assert!(files.windows(2).all(|w| w[0].path() <= w[1].path()));
+    for file in &files {
+        assert!(!file.path().is_empty());
+    }
+    Ok(())
+}
+
+#[test]
+fn extracted_snapshot_keeps_selected_runs_and_conditions() -> RCDBResult<()> {
+    let db = open_db();
+    let output =
+        std::env::temp_dir().join(format!("gluex-rcdb-snapshot-{}.sqlite", std::process::id()));
+    let _ = std::fs::remove_file(&output);
+    db.extract_snapshot(&output, &["event_count"], 2..=5)?;
+    let snapshot = RCDB::open(&output)?;
+    let ctx = Context::default().with_run_range(2..=5);
+    let expected = db.fetch(["event_count"], &ctx)?;
+    let actual = snapshot.fetch(["event_count"], &ctx)?;
+    assert_eq!(
+        actual.keys().collect::<Vec<_>>(),
+        expected.keys().collect::<Vec<_>>()
+    );
+    for (run, row) in &actual {
+        assert_eq!(
+            row.get("event_count").and_then(Value::as_int),
+            expected[run].get("event_count").and_then(Value::as_int),
+        );
+    }
+    assert!(snapshot.condition_type("is_valid_run_end").is_none());
+    assert_eq!(snapshot.fetch_runs(&Context::default())?, vec![2, 3, 4, 5]);
+    std::fs::remove_file(&output).ok();
+    Ok(())
+}
+
+#[test]
+fn merging_a_snapshot_onto_itself_preserves_values() -> RCDBResult<()> {
+    let db = open_db();
+    let output =
+        std::env::temp_dir().join(format!("gluex-rcdb-merged-{}.sqlite", std::process::id()));
+    let _ = std::fs::remove_file(&output);
+    gluex_rcdb::snapshot::merge(rcdb_path(), rcdb_path(), &output)?;
+    let merged = RCDB::open(&output)?;
+    assert_eq!(merged.condition_types().len(), db.condition_types().len());
+    let ctx = Context::default().with_run_range(2..=5);
+    let expected = db.fetch(["event_count"], &ctx)?;
+    let actual = merged.fetch(["event_count"], &ctx)?;
+    for (run, row) in &expected {
+        assert_eq!(
+            actual[run].get("event_count").and_then(Value::as_int),
+            row.get("event_count").and_then(Value::as_int),
+        );
+    }
+    std::fs::remove_file(&output).ok();
+    Ok(())
+}
+
+#[cfg(feature = "provenance")]
+#[test]
+fn recorded_fetches_verify_against_the_same_database() -> RCDBResult<()> {
+    use gluex_core::provenance::Recorder;
+
+    let recorder = Recorder::new();
+    let db = open_db().with_recorder(recorder.clone());
+    let ctx = Context::default().with_run_range(2..=5);
+    db.fetch(["event_count"], &ctx)?;
+    db.fetch_runs(&ctx)?;
+    let manifest = recorder.manifest();
+    assert_eq!(manifest.rcdb_queries().count(), 2);
+    assert!(manifest
+        .rcdb_queries()
+        .all(|query| query.runs == [2, 3, 4, 5]));
+    assert!(open_db().verify_manifest(&manifest)?.is_empty());
+    Ok(())
+}
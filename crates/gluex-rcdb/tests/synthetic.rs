@@ -0,0 +1,709 @@
+#![allow(missing_docs)]
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use chrono::{Duration, TimeZone, Utc};
+use gluex_core::{
+    deprecation::{DeprecatedItem, DeprecationWarnings},
+    limits::{LimitError, QueryBudget, QueryLimits},
+    metrics::Metrics,
+    schema::SchemaMode,
+    sql_audit::{AuditMode, SqlAudit},
+    units::Unit,
+};
+use gluex_rcdb::{
+    backend::{Row, SqlValue, SqliteBackend, StorageBackend},
+    prelude::*,
+};
+use gluex_testdata::rcdb::RcdbBuilder;
+
+fn synthetic_db(name: &str) -> RCDB {
+    let path = std::env::temp_dir().join(format!(
+        "gluex-rcdb-synthetic-{}-{name}.sqlite",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    let mut builder = RcdbBuilder::new().describe("event_count", "Number of events");
+    for (run, current) in (1..=10).zip([0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0]) {
+        builder = builder
+            .condition(run, "event_count", run * 1000)
+            .condition(
+                run,
+                "run_type",
+                if run % 2 == 0 { "hd_all.tsg" } else { "cosmic" },
+            )
+            .condition(run, "beam_current", current)
+            .condition(run, "is_valid_run_end", run != 4)
+            .condition(
+                run,
+                "run_start_time",
+                Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap() + Duration::hours(run),
+            );
+    }
+    builder
+        .runs([11])
+        .file("/conf/daq.conf", "trigger = 1", [3, 4])
+        .run_period("test", 1..=11)
+        .write(&path)
+        .expect("failed to write synthetic RCDB");
+    RCDB::open(&path).expect("failed to open synthetic RCDB")
+}
+
+#[test]
+fn synthetic_pooled_handles_query_from_many_threads() -> RCDBResult<()> {
+    let path = synthetic_db("pooled").connection_path().to_string();
+    let db = RCDB::open_pooled(&path, 4)?;
+    assert_eq!(db.backend().sqlite().map(SqliteBackend::pool_size), Some(4));
+    assert_eq!(
+        RCDB::open_pooled(&path, 0)?
+            .backend()
+            .sqlite()
+            .map(SqliteBackend::pool_size),
+        Some(1)
+    );
+    let counts = std::thread::scope(|scope| {
+        let handles: Vec<_> = [3, 7, 3, 7, 3, 7, 3, 7]
+            .into_iter()
+            .map(|run| {
+                let db = db.clone();
+                scope.spawn(move || {
+                    let values = db.fetch(["event_count"], &Context::default().with_run(run))?;
+                    Ok(values[&run]["event_count"].as_int())
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("fetch thread panicked"))
+            .collect::<RCDBResult<Vec<_>>>()
+    })?;
+    assert_eq!(
+        counts,
+        [3000, 7000, 3000, 7000, 3000, 7000, 3000, 7000]
+            .map(Some)
+            .to_vec()
+    );
+    Ok(())
+}
+
+#[test]
+fn synthetic_condition_types_are_inferred() {
+    let db = synthetic_db("types");
+    let event_count = db
+        .condition_type("event_count")
+        .expect("missing event_count");
+    assert_eq!(event_count.value_type(), ValueType::Int);
+    assert_eq!(event_count.description(), "Number of events");
+    assert_eq!(
+        db.condition_type("run_start_time").map(|t| t.value_type()),
+        Some(ValueType::Time)
+    );
+}
+
+#[test]
+fn synthetic_fetch_values() -> RCDBResult<()> {
+    let db = synthetic_db("fetch");
+    let values = db.fetch(
+        ["event_count", "run_type", "is_valid_run_end"],
+        &Context::default().with_run_range(3..=4),
+    )?;
+    assert_eq!(values.len(), 2);
+    let run4 = values.get(&4).expect("missing run 4");
+    assert_eq!(run4.get("event_count").and_then(Value::as_int), Some(4000));
+    assert_eq!(
+        run4.get("run_type").and_then(Value::as_string),
+        Some("hd_all.tsg")
+    );
+    assert_eq!(
+        run4.get("is_valid_run_end").and_then(Value::as_bool),
+        Some(false)
+    );
+    Ok(())
+}
+
+#[test]
+fn synthetic_fetch_iter_streams_runs_in_chunks() -> RCDBResult<()> {
+    let db = synthetic_db("fetch-iter");
+    let names = ["event_count", "run_type"];
+    let ctx = Context::default()
+        .with_run_range(1..=11)
+        .filter(conditions::int_cond("event_count").ge(3000));
+    let expected = db.fetch(names, &ctx)?;
+    let mut iter = db.fetch_iter(names, &ctx)?.with_chunk_size(3);
+    assert_eq!(iter.remaining_runs(), 8);
+    let (run, values) = iter.next().expect("first run")?;
+    assert_eq!(run, 3);
+    assert_eq!(
+        values.get("event_count").and_then(Value::as_int),
+        Some(3000)
+    );
+    assert_eq!(iter.remaining_runs(), 5);
+    let streamed = iter.collect::<RCDBResult<Vec<_>>>()?;
+    assert_eq!(
+        streamed.iter().map(|(run, _)| *run).collect::<Vec<_>>(),
+        (4..=10).collect::<Vec<_>>()
+    );
+    assert!(streamed.iter().all(|(run, values)| {
+        values.get("event_count").and_then(Value::as_int)
+            == expected[run].get("event_count").and_then(Value::as_int)
+    }));
+    assert!(matches!(
+        db.fetch_iter(["missing"], &ctx),
+        Err(RCDBError::ConditionTypeNotFound(_))
+    ));
+    assert!(matches!(
+        db.fetch_iter(Vec::<String>::new(), &ctx),
+        Err(RCDBError::EmptyConditionList)
+    ));
+    Ok(())
+}
+
+#[test]
+fn synthetic_filters_and_files() -> RCDBResult<()> {
+    let db = synthetic_db("filters");
+    let ctx = Context::default()
+        .with_run_range(1..=11)
+        .filter(conditions::all([
+            conditions::string_cond("run_type").eq("hd_all.tsg"),
+            conditions::float_cond("beam_current").gt(0.5),
+        ]));
+    assert_eq!(db.fetch_runs(&ctx)?, vec![6, 8, 10]);
+    assert_eq!(
+        db.fetch_runs(&Context::default().with_run_range(..))?.len(),
+        11
+    );
+    let files = db.files(3)?;
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].content(), b"trigger = 1");
+    Ok(())
+}
+
+#[test]
+fn synthetic_explain_selection_names_failing_clauses() -> RCDBResult<()> {
+    use gluex_rcdb::explain::RunVerdict;
+
+    let db = synthetic_db("explain_selection");
+    let ctx = Context::default()
+        .with_runs([2, 4, 5, 6, 11, 99])
+        .filter(conditions::int_cond("event_count").gt(3000))
+        .filter(conditions::all([
+            conditions::string_cond("run_type").eq("hd_all.tsg"),
+            conditions::bool_cond("is_valid_run_end").is_true(),
+        ]));
+    let explanations = db.explain_selection(&ctx)?;
+    let lines: Vec<String> = explanations.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        lines,
+        [
+            "2: excluded by filter 0: event_count > 3000 (event_count = 2000)",
+            "4: excluded by filter 1: is_valid_run_end IS TRUE (is_valid_run_end = false)",
+            "5: excluded by filter 1: run_type == \"hd_all.tsg\" (run_type = \"cosmic\")",
+            "6: passed",
+            "11: excluded by filter 0: event_count > 3000 (event_count is missing)",
+            "99: not in the database",
+        ]
+    );
+    assert!(matches!(
+        explanations[0].verdict,
+        RunVerdict::Excluded { filter: 0, .. }
+    ));
+    let passed: Vec<_> = explanations
+        .iter()
+        .filter(|explanation| explanation.passed())
+        .map(|explanation| explanation.run)
+        .collect();
+    assert_eq!(passed, db.fetch_runs(&ctx)?);
+    assert!(matches!(
+        db.explain_selection(&Context::default().filter(conditions::int_cond("missing").eq(1))),
+        Err(RCDBError::ConditionTypeNotFound(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn synthetic_run_set_matches_run_range() -> RCDBResult<()> {
+    let db = synthetic_db("run_set");
+    let from_set = Context::default().with_run_set(RunSet::range(2..5));
+    assert_eq!(db.fetch_runs(&from_set)?, vec![2, 3, 4]);
+    assert_eq!(from_set.run_set(), RunSet::Range(2..=4));
+    Ok(())
+}
+
+#[test]
+fn synthetic_metrics_count_queries() -> RCDBResult<()> {
+    let db = synthetic_db("metrics").with_metrics(Metrics::new());
+    let ctx = Context::default().with_run_range(1..=5);
+    db.fetch(["event_count"], &ctx)?;
+    db.fetch_runs(&ctx)?;
+    assert!(db.fetch(["missing"], &ctx).is_err());
+    let report = db.metrics().expect("metrics attached");
+    assert_eq!(report.operations["rcdb.fetch"].count, 1);
+    assert_eq!(report.operations["rcdb.fetch"].rows, 5);
+    assert_eq!(report.operations["rcdb.fetch_runs"].rows, 5);
+    let types = report.caches["rcdb.condition_types"];
+    assert_eq!((types.hits, types.misses), (1, 1));
+    Ok(())
+}
+
+#[test]
+fn synthetic_overlay_falls_through_to_lower_layers() -> RCDBResult<()> {
+    let path = std::env::temp_dir().join(format!(
+        "gluex-rcdb-synthetic-{}-overlay_top.sqlite",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    RcdbBuilder::new()
+        .condition(2, "event_count", 99)
+        .file("/conf/daq.conf", "trigger = 2", [3])
+        .write(&path)
+        .expect("failed to write override RCDB");
+    let overlay = RcdbOverlay::new(vec![RCDB::open(&path)?, synthetic_db("overlay_base")]);
+    let ctx = Context::default().with_run_range(1..=3);
+    let values = overlay.fetch(["event_count", "run_type"], &ctx)?;
+    assert_eq!(values.len(), 3);
+    let event_count = &values[&2]["event_count"];
+    assert_eq!(
+        (event_count.layer, event_count.value.as_int()),
+        (0, Some(99))
+    );
+    assert_eq!(values[&1]["event_count"].layer, 1);
+    assert_eq!(values[&2]["run_type"].layer, 1);
+    let (layer, files) = overlay.files(3)?.expect("files for run 3");
+    assert_eq!((layer, files[0].content()), (0, &b"trigger = 2"[..]));
+    assert_eq!(overlay.files(4)?.map(|(layer, _)| layer), Some(1));
+    assert!(matches!(
+        overlay.fetch(["missing"], &ctx),
+        Err(RCDBError::ConditionTypeNotFound(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn synthetic_router_sends_runs_to_the_database_serving_them() -> RCDBResult<()> {
+    let path = std::env::temp_dir().join(format!(
+        "gluex-rcdb-synthetic-{}-router_period.sqlite",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    let mut builder = RcdbBuilder::new();
+    for run in 1..=10 {
+        builder = builder.condition(run, "event_count", 7);
+    }
+    builder
+        .write(&path)
+        .expect("failed to write run-period RCDB");
+    let router = RcdbRouter::new()
+        .with_route(4..=6, RCDB::open(&path)?)
+        .with_default(synthetic_db("router_default"));
+    let values = router.fetch(["event_count"], &Context::default().with_run_range(3..=7))?;
+    let counts: Vec<_> = values
+        .iter()
+        .map(|(run, values)| (*run, values["event_count"].as_int()))
+        .collect();
+    assert_eq!(
+        counts,
+        vec![
+            (3, Some(3000)),
+            (4, Some(7)),
+            (5, Some(7)),
+            (6, Some(7)),
+            (7, Some(7000)),
+        ]
+    );
+    assert_eq!(
+        router.handle(5).map(RCDB::connection_path),
+        Some(path.to_string_lossy().as_ref())
+    );
+    let selected = Context::default()
+        .with_runs([2, 5, 8])
+        .filter(conditions::int_cond("event_count").gt(1000));
+    assert_eq!(router.fetch_runs(&selected)?, vec![2, 8]);
+    let unrouted = RcdbRouter::new().with_route(4..=6, RCDB::open(&path)?);
+    assert_eq!(
+        unrouted
+            .fetch(["event_count"], &Context::default())?
+            .into_keys()
+            .collect::<Vec<_>>(),
+        vec![4, 5, 6]
+    );
+    assert!(matches!(
+        router.fetch(Vec::<String>::new(), &Context::default()),
+        Err(RCDBError::EmptyConditionList)
+    ));
+    Ok(())
+}
+
+/// Forwards every statement to a `SQLite` snapshot and counts them.
+struct CountingBackend {
+    inner: SqliteBackend,
+    statements: AtomicUsize,
+}
+
+impl StorageBackend for CountingBackend {
+    fn location(&self) -> &'static str {
+        "counting://synthetic"
+    }
+
+    fn query(
+        &self,
+        sql: &str,
+        params: &[SqlValue],
+        budget: Option<&QueryBudget>,
+        on_row: &mut dyn FnMut(Row<'_>) -> RCDBResult<()>,
+    ) -> RCDBResult<()> {
+        self.statements.fetch_add(1, Ordering::Relaxed);
+        self.inner.query(sql, params, budget, on_row)
+    }
+}
+
+#[test]
+fn synthetic_custom_backends_serve_the_query_layer() -> RCDBResult<()> {
+    let path = synthetic_db("custom_backend").connection_path().to_string();
+    let backend = Arc::new(CountingBackend {
+        inner: SqliteBackend::new(rusqlite::Connection::open(&path)?, path),
+        statements: AtomicUsize::new(0),
+    });
+    let db = RCDB::from_backend(backend.clone(), SchemaMode::Strict)?;
+    assert_eq!(db.connection_path(), "counting://synthetic");
+    assert!(db.try_connection().is_none());
+    let loaded = backend.statements.load(Ordering::Relaxed);
+    let ctx = Context::default()
+        .with_run_range(1..=10)
+        .filter(conditions::int_cond("event_count").gt(8000));
+    let values = db.fetch(["event_count"], &ctx)?;
+    assert_eq!(values.keys().copied().collect::<Vec<_>>(), vec![9, 10]);
+    assert_eq!(values[&9]["event_count"].as_int(), Some(9000));
+    assert_eq!(db.fetch_runs(&ctx)?, vec![9, 10]);
+    assert_eq!(backend.statements.load(Ordering::Relaxed), loaded + 2);
+    assert!(db.explain_fetch_runs(&ctx)?.steps.is_empty());
+    assert!(!synthetic_db("sqlite_backend")
+        .explain_fetch_runs(&ctx)?
+        .steps
+        .is_empty());
+    Ok(())
+}
+
+#[test]
+fn synthetic_sql_audit_logs_and_dry_runs() -> RCDBResult<()> {
+    let ctx = Context::default()
+        .with_run_range(1..=10)
+        .filter(conditions::string_cond("run_type").eq("cosmic"));
+    let audit = SqlAudit::new(AuditMode::Log);
+    let db = synthetic_db("audit_log").with_sql_audit(audit.clone());
+    assert_eq!(db.fetch_runs(&ctx)?, vec![1, 3, 5, 7, 9]);
+    let statements = audit.statements();
+    assert_eq!(statements.len(), 1);
+    assert!(statements[0].executed);
+    assert!(statements[0].params.contains(&"'cosmic'".to_string()));
+
+    let dry_run = SqlAudit::new(AuditMode::DryRun);
+    let db = synthetic_db("audit_dry_run").with_sql_audit(dry_run.clone());
+    assert!(db.fetch(["event_count"], &ctx)?.is_empty());
+    assert!(db.fetch_runs(&ctx)?.is_empty());
+    assert!(db.files(3)?.is_empty());
+    let statements = dry_run.statements();
+    assert_eq!(statements.len(), 3);
+    assert!(statements.iter().all(|statement| !statement.executed));
+    assert!(statements[2].to_string().contains("dry run"));
+    Ok(())
+}
+
+#[test]
+fn synthetic_explain_reports_query_plans() -> RCDBResult<()> {
+    let ctx = Context::default()
+        .with_run_range(1..=10)
+        .filter(conditions::string_cond("run_type").eq("cosmic"));
+    let audit = SqlAudit::new(AuditMode::Log);
+    let db = synthetic_db("explain").with_sql_audit(audit.clone());
+    let plan = db.explain_fetch(["event_count", "event_count"], &ctx)?;
+    assert!(plan.sql.starts_with("WITH matched_runs AS ("));
+    assert!(plan.params.contains(&"'cosmic'".to_string()));
+    assert!(!plan.steps.is_empty());
+    assert!(plan.to_string().contains("QUERY PLAN"));
+    let plan = db.explain_fetch_runs(&ctx)?;
+    assert!(plan.sql.starts_with("SELECT runs.number FROM runs"));
+    assert!(!plan.steps.is_empty());
+    assert!(audit.statements().is_empty());
+    assert!(matches!(
+        db.explain_fetch(["missing"], &ctx),
+        Err(RCDBError::ConditionTypeNotFound(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn synthetic_limits_reject_runaway_fetches() -> RCDBResult<()> {
+    let db = synthetic_db("limits").with_limits(QueryLimits::new().with_max_runs(5));
+    let ctx = Context::default().with_run_range(1..=10);
+    assert!(matches!(
+        db.fetch_runs(&ctx),
+        Err(RCDBError::LimitError(LimitError::TooManyRuns { limit: 5 }))
+    ));
+    assert!(matches!(
+        db.fetch(["event_count"], &Context::default().with_runs(1..=6)),
+        Err(RCDBError::LimitError(LimitError::TooManyRuns { limit: 5 }))
+    ));
+    assert_eq!(
+        db.fetch_runs(
+            &ctx.clone()
+                .filter(conditions::string_cond("run_type").eq("cosmic"))
+        )?,
+        vec![1, 3, 5, 7, 9]
+    );
+    let db = db.with_limits(QueryLimits::new().with_max_result_bytes(1000));
+    assert!(matches!(
+        db.fetch(["event_count", "run_type", "beam_current"], &ctx),
+        Err(RCDBError::LimitError(LimitError::ResultTooLarge {
+            limit: 1000
+        }))
+    ));
+    assert_eq!(
+        db.fetch(["event_count"], &ctx.with_run_range(1..=2))?.len(),
+        2
+    );
+    Ok(())
+}
+
+#[test]
+fn synthetic_values_carry_units() -> RCDBResult<()> {
+    let db = synthetic_db("units");
+    let values = db.fetch(
+        ["beam_current", "event_count"],
+        &Context::default().with_runs([3]),
+    )?;
+    let beam_current = &values[&3]["beam_current"];
+    assert_eq!(beam_current.unit(), Some(Unit::NanoA));
+    let quantity = beam_current.as_quantity().expect("beam_current has a unit");
+    assert!((quantity.value_in(Unit::A).unwrap() - 0.3e-9).abs() < 1e-18);
+    assert_eq!(values[&3]["event_count"].unit(), None);
+    assert_eq!(values[&3]["event_count"].as_quantity(), None);
+    Ok(())
+}
+
+#[test]
+fn synthetic_newer_schema_needs_best_effort_mode() -> RCDBResult<()> {
+    let path = synthetic_db("schema").connection_path().to_string();
+    let connection = rusqlite::Connection::open(&path)?;
+    connection.execute_batch(
+        "INSERT INTO schema_versions(version, comment) VALUES (3, 'future');
+         INSERT INTO condition_types(id, name, value_type, created)
+             VALUES (99, 'hologram', 'tensor', '2030-01-01 00:00:00');
+         CREATE TABLE run_tags(run_number INTEGER, tag TEXT);",
+    )?;
+    drop(connection);
+    assert!(matches!(
+        RCDB::open_with_schema_mode(&path, SchemaMode::Strict),
+        Err(RCDBError::NewerSchemaError(err)) if err.found == 3 && err.supported == 2
+    ));
+    let db = RCDB::open_with_schema_mode(&path, SchemaMode::BestEffort)?;
+    assert_eq!(db.schema_version(), 3);
+    assert!(db.condition_type("hologram").is_none());
+    let values = db.fetch(["event_count"], &Context::default().with_runs([2]))?;
+    assert_eq!(values[&2]["event_count"].as_int(), Some(2000));
+    Ok(())
+}
+
+#[test]
+fn synthetic_withdrawn_conditions_warn_with_replacement() -> RCDBResult<()> {
+    let path = std::env::temp_dir().join(format!(
+        "gluex-rcdb-synthetic-{}-withdrawn.sqlite",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_file(&path);
+    RcdbBuilder::new()
+        .describe("old_current", "Deprecated: use beam_current instead")
+        .condition(1, "old_current", 0.5)
+        .condition(1, "beam_current", 0.5)
+        .write(&path)
+        .expect("failed to write synthetic RCDB");
+    let warnings = DeprecationWarnings::new();
+    let db = RCDB::open(&path)?.with_deprecation_warnings(Some(warnings.clone()));
+    db.fetch(["beam_current"], &Context::default())?;
+    assert!(warnings.warnings().is_empty());
+    db.fetch(["old_current", "beam_current"], &Context::default())?;
+    db.fetch(["old_current"], &Context::default())?;
+    let reported = warnings.warnings();
+    assert_eq!(reported.len(), 1);
+    assert_eq!(reported[0].item, DeprecatedItem::RcdbCondition);
+    assert_eq!(reported[0].name, "old_current");
+    assert_eq!(reported[0].replacement.as_deref(), Some("beam_current"));
+    Ok(())
+}
+
+#[test]
+fn synthetic_open_from_bytes() -> RCDBResult<()> {
+    let path = synthetic_db("bytes").connection_path().to_string();
+    let bytes = std::fs::read(path).expect("failed to read synthetic RCDB");
+    let db = RCDB::open_from_bytes(&bytes)?;
+    assert_eq!(db.connection_path(), ":memory:");
+    assert_eq!(
+        db.fetch_runs(&Context::default().with_run_range(2..=4))?,
+        vec![2, 3, 4]
+    );
+    assert!(RCDB::open_from_bytes(b"not a database").is_err());
+    Ok(())
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn synthetic_conditions_read_into_derived_structs() -> RCDBResult<()> {
+    use gluex_rcdb::typed::RcdbConditions;
+
+    #[derive(RcdbConditions, Debug)]
+    struct BeamInfo {
+        #[rcdb(condition = "event_count")]
+        events: i64,
+        run_type: String,
+        is_valid_run_end: Option<bool>,
+    }
+
+    #[derive(RcdbConditions, Debug)]
+    struct WrongType {
+        #[allow(dead_code)]
+        run_type: f64,
+    }
+
+    assert_eq!(
+        BeamInfo::CONDITIONS,
+        ["event_count", "run_type", "is_valid_run_end"]
+    );
+    let db = synthetic_db("derive");
+    let info = db.fetch_typed::<BeamInfo>(&Context::default().with_runs([3, 4, 11]))?;
+    assert_eq!(info.keys().copied().collect::<Vec<_>>(), [3, 4]);
+    assert_eq!(info[&3].events, 3000);
+    assert_eq!(info[&3].run_type, "cosmic");
+    assert_eq!(info[&4].is_valid_run_end, Some(false));
+    assert!(matches!(
+        db.fetch_typed::<WrongType>(&Context::default().with_run(3)),
+        Err(RCDBError::ConditionTypeMismatch {
+            expected: ValueType::Float,
+            actual: ValueType::String,
+            ..
+        })
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn synthetic_metadata_round_trips_through_serde() -> RCDBResult<()> {
+    use gluex_rcdb::models::{ConditionTypeMeta, ConfigurationFile};
+
+    let db = synthetic_db("serde");
+    let json = serde_json::to_value(db.condition_type("event_count").unwrap()).unwrap();
+    assert_eq!(json["value_type"], "Int");
+    assert_eq!(json["description"], "Number of events");
+    let meta: ConditionTypeMeta = serde_json::from_value(json).unwrap();
+    assert_eq!(meta.name(), "event_count");
+
+    let files = serde_json::to_string(&db.files(3)?).unwrap();
+    let files: Vec<ConfigurationFile> = serde_json::from_str(&files).unwrap();
+    assert_eq!(files[0].content(), b"trigger = 1");
+    Ok(())
+}
+
+#[cfg(feature = "readfile")]
+#[test]
+fn synthetic_conditions_export_as_readfile_text() -> RCDBResult<()> {
+    use gluex_rcdb::readfile::{write_text, Branch};
+
+    let db = synthetic_db("readfile");
+    let names = [
+        "event_count",
+        "beam_current",
+        "is_valid_run_end",
+        "run_type",
+    ];
+    let branches = Branch::for_conditions(&db, names)?;
+    let rows = db.fetch(names, &Context::default().with_runs([3, 4, 11]))?;
+    let mut text = Vec::new();
+    write_text(&mut text, &branches, &rows).unwrap();
+    assert_eq!(
+        String::from_utf8(text).unwrap(),
+        "run/L:event_count/L:has_event_count/O:beam_current/D:has_beam_current/O:\
+         is_valid_run_end/O:has_is_valid_run_end/O:run_type/C:has_run_type/O\n\
+         3\t3000\t1\t0.3\t1\t1\t1\tcosmic\t1\n\
+         4\t4000\t1\t0.4\t1\t0\t1\thd_all.tsg\t1\n\
+         11\t0\t0\t0\t0\t0\t0\t\"\"\t0\n"
+    );
+    assert!(matches!(
+        Branch::for_conditions(&db, ["missing"]),
+        Err(RCDBError::ConditionTypeNotFound(_))
+    ));
+    Ok(())
+}
+
+#[cfg(feature = "polars")]
+#[test]
+fn synthetic_conditions_convert_to_polars_dataframes() -> RCDBResult<()> {
+    use gluex_rcdb::polars::{
+        polars::prelude::{DataType, TimeUnit, TimeZone as PolarsTimeZone},
+        to_dataframe,
+    };
+
+    let db = synthetic_db("polars");
+    let names = [
+        "event_count",
+        "beam_current",
+        "is_valid_run_end",
+        "run_type",
+        "run_start_time",
+    ];
+    let values = db.fetch(names, &Context::default().with_runs([3, 4, 11]))?;
+    let df = to_dataframe(&values, names).unwrap();
+    assert_eq!(df.shape(), (3, 6));
+    assert_eq!(
+        df.get_column_names_str(),
+        [
+            "run_number",
+            "event_count",
+            "beam_current",
+            "is_valid_run_end",
+            "run_type",
+            "run_start_time",
+        ]
+    );
+    assert_eq!(
+        df.dtypes(),
+        [
+            DataType::Int64,
+            DataType::Int64,
+            DataType::Float64,
+            DataType::Boolean,
+            DataType::String,
+            DataType::Datetime(TimeUnit::Microseconds, Some(PolarsTimeZone::UTC)),
+        ]
+    );
+    let runs = df.column("run_number").unwrap().i64().unwrap();
+    assert_eq!(
+        runs.into_iter().collect::<Vec<_>>(),
+        [Some(3), Some(4), Some(11)]
+    );
+    let valid = df.column("is_valid_run_end").unwrap().bool().unwrap();
+    assert_eq!(
+        valid.into_iter().collect::<Vec<_>>(),
+        [Some(true), Some(false), None]
+    );
+    let types = df.column("run_type").unwrap().str().unwrap();
+    assert_eq!(
+        types.into_iter().collect::<Vec<_>>(),
+        [Some("cosmic"), Some("hd_all.tsg"), None]
+    );
+    let start = Utc.with_ymd_and_hms(2020, 1, 1, 3, 0, 0).unwrap();
+    let times = df.column("run_start_time").unwrap().datetime().unwrap();
+    assert_eq!(
+        times.physical().into_iter().next().flatten(),
+        Some(start.timestamp_micros())
+    );
+
+    let empty = db.fetch(names, &Context::default().with_run(11))?;
+    let df = to_dataframe(&empty, names).unwrap();
+    assert_eq!(df.shape(), (1, 6));
+    assert!(df.dtypes()[1..]
+        .iter()
+        .all(|dtype| *dtype == DataType::Null));
+    Ok(())
+}
@@ -0,0 +1,447 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::RangeInclusive,
+    path::Path,
+};
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use gluex_core::{Id, RunNumber};
+use rusqlite::{params, Connection, Transaction};
+
+use crate::{TestDataError, TestDataResult, TIMESTAMP_FORMAT};
+
+const SCHEMA_SQL: &str = "
+    CREATE TABLE schemaVersions(id INTEGER PRIMARY KEY, schemaVersion INTEGER NOT NULL);
+    CREATE TABLE directories(
+        id INTEGER PRIMARY KEY, created TEXT NOT NULL, modified TEXT NOT NULL, name TEXT NOT NULL,
+        parentId INTEGER NOT NULL DEFAULT 0, authorId INTEGER NOT NULL DEFAULT 1,
+        comment TEXT, isDeprecated INTEGER NOT NULL DEFAULT 0,
+        deprecatedByUserId INTEGER NOT NULL DEFAULT 0, isLocked INTEGER NOT NULL DEFAULT 0,
+        lockedByUserId INTEGER NOT NULL DEFAULT 0);
+    CREATE TABLE typeTables(
+        id INTEGER PRIMARY KEY, created TEXT NOT NULL, modified TEXT NOT NULL,
+        directoryId INTEGER NOT NULL, name TEXT NOT NULL, nRows INTEGER NOT NULL,
+        nColumns INTEGER NOT NULL, nAssignments INTEGER NOT NULL DEFAULT 0,
+        authorId INTEGER NOT NULL DEFAULT 1, comment TEXT, isDeprecated INTEGER NOT NULL DEFAULT 0,
+        deprecatedByUserId INTEGER NOT NULL DEFAULT 0, isLocked INTEGER NOT NULL DEFAULT 0,
+        lockedByUserId INTEGER NOT NULL DEFAULT 0, lockTime TEXT);
+    CREATE TABLE columns(
+        id INTEGER PRIMARY KEY, created TEXT NOT NULL, modified TEXT NOT NULL, name TEXT NOT NULL,
+        typeId INTEGER NOT NULL, columnType TEXT NOT NULL, `order` INTEGER NOT NULL, comment TEXT);
+    CREATE TABLE variations(
+        id INTEGER PRIMARY KEY, created TEXT NOT NULL, modified TEXT NOT NULL, name TEXT NOT NULL,
+        description TEXT, authorId INTEGER NOT NULL DEFAULT 1, comment TEXT,
+        parentId INTEGER NOT NULL DEFAULT 0, isLocked INTEGER NOT NULL DEFAULT 0, lockTime TEXT,
+        lockedByUserId INTEGER NOT NULL DEFAULT 0, goBackBehavior INTEGER NOT NULL DEFAULT 0,
+        goBackTime TEXT, isDeprecated INTEGER NOT NULL DEFAULT 0,
+        deprecatedByUserId INTEGER NOT NULL DEFAULT 0);
+    CREATE TABLE runRanges(
+        id INTEGER PRIMARY KEY, created TEXT NOT NULL, modified TEXT NOT NULL, name TEXT,
+        runMin INTEGER NOT NULL, runMax INTEGER NOT NULL, comment TEXT);
+    CREATE TABLE eventRanges(
+        id INTEGER PRIMARY KEY, created TEXT NOT NULL, modified TEXT NOT NULL,
+        runNumber INTEGER NOT NULL, eventMin INTEGER NOT NULL, eventMax INTEGER NOT NULL,
+        comment TEXT);
+    CREATE TABLE constantSets(
+        id INTEGER PRIMARY KEY, created TEXT NOT NULL, modified TEXT NOT NULL, vault TEXT NOT NULL,
+        constantTypeId INTEGER NOT NULL);
+    CREATE TABLE assignments(
+        id INTEGER PRIMARY KEY, created TEXT NOT NULL, modified TEXT NOT NULL,
+        variationId INTEGER NOT NULL, runRangeId INTEGER, eventRangeId INTEGER,
+        authorId INTEGER NOT NULL DEFAULT 1, comment TEXT, constantSetId INTEGER NOT NULL);
+    CREATE TABLE users(
+        id INTEGER PRIMARY KEY, created TEXT NOT NULL, lastActionTime TEXT NOT NULL,
+        name TEXT NOT NULL, password TEXT, roles TEXT NOT NULL DEFAULT '',
+        info TEXT NOT NULL DEFAULT '');
+    CREATE INDEX fk_assignments_constantSets ON assignments(constantSetId);
+    CREATE INDEX fk_assignments_runRanges ON assignments(runRangeId);
+    CREATE INDEX fk_assignments_variations ON assignments(variationId);
+    CREATE INDEX fk_columns_typeTables ON columns(typeId);
+    CREATE INDEX fk_constantSets_typeTables ON constantSets(constantTypeId);
+    CREATE INDEX fk_typeTables_directories ON typeTables(directoryId);
+    INSERT INTO schemaVersions(id, schemaVersion) VALUES (1, 5);";
+
+/// Name of the variation every CCDB database starts with.
+pub const DEFAULT_VARIATION: &str = "default";
+
+/// Name of the user (with identifier 1) credited as the author of every generated row unless
+/// [`CcdbBuilder::author`] names another.
+pub const DEFAULT_USER: &str = "anonymous";
+
+struct TableSpec {
+    path: String,
+    n_rows: u32,
+    columns: Vec<(String, String)>,
+    comment: String,
+    author: Option<String>,
+}
+
+/// A set of constants for one table, variation, and run range.
+#[derive(Debug, Clone)]
+pub struct Assignment {
+    table: String,
+    variation: String,
+    runs: RangeInclusive<RunNumber>,
+    created: Option<DateTime<Utc>>,
+    values: Vec<Vec<String>>,
+}
+
+impl Assignment {
+    /// Creates an assignment of `values` (one inner iterator per row) to the table at `path`
+    /// for the runs in `runs`, in the `default` variation.
+    pub fn new<R, V>(path: &str, runs: RangeInclusive<RunNumber>, values: R) -> Self
+    where
+        R: IntoIterator,
+        R::Item: IntoIterator<Item = V>,
+        V: ToString,
+    {
+        Self {
+            table: path.to_string(),
+            variation: DEFAULT_VARIATION.to_string(),
+            runs,
+            created: None,
+            values: values
+                .into_iter()
+                .map(|row| row.into_iter().map(|value| value.to_string()).collect())
+                .collect(),
+        }
+    }
+
+    /// Places the assignment in another variation.
+    #[must_use]
+    pub fn with_variation(mut self, variation: &str) -> Self {
+        self.variation = variation.to_string();
+        self
+    }
+
+    /// Sets the creation time explicitly.
+    ///
+    /// By default, assignments are created one minute apart starting at
+    /// [`CcdbBuilder::epoch`], in the order they were added, so later assignments take
+    /// precedence.
+    #[must_use]
+    pub fn with_created(mut self, created: DateTime<Utc>) -> Self {
+        self.created = Some(created);
+        self
+    }
+}
+
+/// Describes a synthetic CCDB database and writes it to a `SQLite` file.
+///
+/// ```no_run
+/// use gluex_testdata::ccdb::{Assignment, CcdbBuilder};
+///
+/// CcdbBuilder::new()
+///     .table("/test/demo/mytable", 2, &[("x", "double"), ("y", "double")])
+///     .assign(Assignment::new("/test/demo/mytable", 0..=100, [[1.0, 2.0], [3.0, 4.0]]))
+///     .write("ccdb.sqlite")?;
+/// # Ok::<(), gluex_testdata::TestDataError>(())
+/// ```
+pub struct CcdbBuilder {
+    users: Vec<(String, String)>,
+    variations: Vec<(String, Option<String>)>,
+    go_back_times: HashMap<String, DateTime<Utc>>,
+    tables: Vec<TableSpec>,
+    assignments: Vec<Assignment>,
+}
+
+impl Default for CcdbBuilder {
+    fn default() -> Self {
+        Self {
+            users: vec![(DEFAULT_USER.to_string(), String::new())],
+            variations: vec![(DEFAULT_VARIATION.to_string(), None)],
+            go_back_times: HashMap::new(),
+            tables: Vec::new(),
+            assignments: Vec::new(),
+        }
+    }
+}
+
+impl CcdbBuilder {
+    /// Creates a builder containing only the `default` variation.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Time at which the generated directories, tables, and variations are created, and the
+    /// starting point for assignment creation times.
+    #[must_use]
+    pub fn epoch() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap()
+    }
+
+    /// Adds a user named `name` with the free-form `info` (usually a full name). Users get
+    /// identifiers in the order they are added, starting at 2 after [`DEFAULT_USER`].
+    #[must_use]
+    pub fn user(mut self, name: &str, info: &str) -> Self {
+        self.users.push((name.to_string(), info.to_string()));
+        self
+    }
+
+    /// Adds a variation whose parent is `parent`.
+    #[must_use]
+    pub fn variation(mut self, name: &str, parent: &str) -> Self {
+        self.variations
+            .push((name.to_string(), Some(parent.to_string())));
+        self
+    }
+
+    /// Enables go-back on `variation`, so lookups that fall back to its parent search the parent
+    /// as of `time` at the latest.
+    #[must_use]
+    pub fn go_back(mut self, variation: &str, time: DateTime<Utc>) -> Self {
+        self.go_back_times.insert(variation.to_string(), time);
+        self
+    }
+
+    /// Declares a table at the absolute `path` with `n_rows` rows and the given
+    /// `(name, type)` columns, where the type is one of the CCDB column type names
+    /// (`int`, `uint`, `long`, `ulong`, `double`, `bool`, `string`). Missing parent
+    /// directories are created.
+    #[must_use]
+    pub fn table(mut self, path: &str, n_rows: u32, columns: &[(&str, &str)]) -> Self {
+        self.tables.push(TableSpec {
+            path: path.to_string(),
+            n_rows,
+            columns: columns
+                .iter()
+                .map(|(name, kind)| ((*name).to_string(), (*kind).to_string()))
+                .collect(),
+            comment: String::new(),
+            author: None,
+        });
+        self
+    }
+
+    /// Sets the comment of the most recently declared table.
+    #[must_use]
+    pub fn comment(mut self, comment: &str) -> Self {
+        if let Some(table) = self.tables.last_mut() {
+            table.comment = comment.to_string();
+        }
+        self
+    }
+
+    /// Credits the most recently declared table to the user `name`, which must be added with
+    /// [`CcdbBuilder::user`] (or be [`DEFAULT_USER`]).
+    #[must_use]
+    pub fn author(mut self, name: &str) -> Self {
+        if let Some(table) = self.tables.last_mut() {
+            table.author = Some(name.to_string());
+        }
+        self
+    }
+
+    /// Adds an assignment.
+    #[must_use]
+    pub fn assign(mut self, assignment: Assignment) -> Self {
+        self.assignments.push(assignment);
+        self
+    }
+
+    /// Writes the database to a new `SQLite` file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if `path` already contains CCDB tables, if an assignment
+    /// references an undeclared table or variation or has the wrong shape, or if any `SQLite`
+    /// operation fails.
+    pub fn write(&self, path: impl AsRef<Path>) -> TestDataResult<()> {
+        let mut conn = Connection::open(path)?;
+        let tx = conn.transaction()?;
+        tx.execute_batch(SCHEMA_SQL)?;
+        let stamp = Self::epoch().format(TIMESTAMP_FORMAT).to_string();
+        for ((name, info), id) in self.users.iter().zip(1..) {
+            tx.execute(
+                "INSERT INTO users(id, created, lastActionTime, name, roles, info)
+                 VALUES (?1, ?2, ?2, ?3, '', ?4)",
+                params![id, stamp, name, info],
+            )?;
+        }
+        let variation_ids = self.write_variations(&tx, &stamp)?;
+        let table_ids = self.write_tables(&tx, &stamp)?;
+        for (id, assignment) in (1..).zip(&self.assignments) {
+            let spec = self
+                .tables
+                .iter()
+                .find(|spec| spec.path == assignment.table)
+                .ok_or_else(|| TestDataError::UnknownTable(assignment.table.clone()))?;
+            let variation_id = *variation_ids
+                .get(assignment.variation.as_str())
+                .ok_or_else(|| TestDataError::UnknownVariation(assignment.variation.clone()))?;
+            check_shape(spec, &assignment.values)?;
+            let created = assignment
+                .created
+                .unwrap_or_else(|| Self::epoch() + Duration::minutes(id))
+                .format(TIMESTAMP_FORMAT)
+                .to_string();
+            let vault = assignment
+                .values
+                .iter()
+                .flatten()
+                .map(|value| value.replace('|', "&delimeter"))
+                .collect::<Vec<_>>()
+                .join("|");
+            tx.execute(
+                "INSERT INTO runRanges(id, created, modified, name, runMin, runMax, comment)
+                 VALUES (?1, ?2, ?2, '', ?3, ?4, '')",
+                params![id, created, assignment.runs.start(), assignment.runs.end()],
+            )?;
+            tx.execute(
+                "INSERT INTO constantSets(id, created, modified, vault, constantTypeId)
+                 VALUES (?1, ?2, ?2, ?3, ?4)",
+                params![id, created, vault, table_ids[spec.path.as_str()]],
+            )?;
+            tx.execute(
+                "INSERT INTO assignments(id, created, modified, variationId, runRangeId,
+                                         comment, constantSetId)
+                 VALUES (?1, ?2, ?2, ?3, ?1, '', ?1)",
+                params![id, created, variation_id],
+            )?;
+        }
+        tx.execute_batch(
+            "UPDATE typeTables SET
+                 nColumns = (SELECT COUNT(*) FROM columns WHERE typeId = typeTables.id),
+                 nAssignments = (
+                     SELECT COUNT(*) FROM constantSets WHERE constantTypeId = typeTables.id);",
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn write_variations<'a>(
+        &'a self,
+        tx: &Transaction<'_>,
+        stamp: &str,
+    ) -> TestDataResult<HashMap<&'a str, Id>> {
+        let ids: HashMap<&str, Id> = self
+            .variations
+            .iter()
+            .zip(1..)
+            .map(|((name, _), id)| (name.as_str(), id))
+            .collect();
+        for (name, parent) in &self.variations {
+            let parent_id = match parent {
+                Some(parent) => *ids
+                    .get(parent.as_str())
+                    .ok_or_else(|| TestDataError::UnknownVariation(parent.clone()))?,
+                None => 0,
+            };
+            let go_back_time = self
+                .go_back_times
+                .get(name)
+                .map(|time| time.format(TIMESTAMP_FORMAT).to_string());
+            tx.execute(
+                "INSERT INTO variations(id, created, modified, name, description, comment, parentId,
+                                        goBackBehavior, goBackTime)
+                 VALUES (?1, ?2, ?2, ?3, '', '', ?4, ?5, ?6)",
+                params![
+                    ids[name.as_str()],
+                    stamp,
+                    name,
+                    parent_id,
+                    i64::from(go_back_time.is_some()),
+                    go_back_time
+                ],
+            )?;
+        }
+        Ok(ids)
+    }
+
+    fn write_tables<'a>(
+        &'a self,
+        tx: &Transaction<'_>,
+        stamp: &str,
+    ) -> TestDataResult<HashMap<&'a str, Id>> {
+        let mut directories: BTreeMap<String, Id> = BTreeMap::new();
+        let mut next_directory_id: Id = 0;
+        let mut table_ids = HashMap::new();
+        let mut column_id: Id = 0;
+        for (spec, table_id) in self.tables.iter().zip(1..) {
+            let (dirs, name) = split_table_path(&spec.path)?;
+            let author_id: Id = match &spec.author {
+                Some(author) => (1..)
+                    .zip(&self.users)
+                    .find_map(|(id, (user, _))| (user == author).then_some(id))
+                    .ok_or_else(|| TestDataError::UnknownUser(author.clone()))?,
+                None => 1,
+            };
+            let mut parent_id: Id = 0;
+            let mut dir_path = String::new();
+            for dir in dirs {
+                dir_path.push('/');
+                dir_path.push_str(dir);
+                parent_id = if let Some(id) = directories.get(&dir_path) {
+                    *id
+                } else {
+                    next_directory_id += 1;
+                    let id = next_directory_id;
+                    tx.execute(
+                        "INSERT INTO directories(id, created, modified, name, parentId, comment)
+                         VALUES (?1, ?2, ?2, ?3, ?4, '')",
+                        params![id, stamp, dir, parent_id],
+                    )?;
+                    directories.insert(dir_path.clone(), id);
+                    id
+                };
+            }
+            tx.execute(
+                "INSERT INTO typeTables(id, created, modified, directoryId, name, nRows, nColumns,
+                                        authorId, comment)
+                 VALUES (?1, ?2, ?2, ?3, ?4, ?5, 0, ?6, ?7)",
+                params![
+                    table_id,
+                    stamp,
+                    parent_id,
+                    name,
+                    spec.n_rows,
+                    author_id,
+                    spec.comment
+                ],
+            )?;
+            for (order, (column, kind)) in (0..).zip(&spec.columns) {
+                column_id += 1;
+                tx.execute(
+                    "INSERT INTO columns(id, created, modified, name, typeId, columnType, `order`,
+                                         comment)
+                     VALUES (?1, ?2, ?2, ?3, ?4, ?5, ?6, '')",
+                    params![column_id, stamp, column, table_id, kind, order],
+                )?;
+            }
+            table_ids.insert(spec.path.as_str(), table_id);
+        }
+        Ok(table_ids)
+    }
+}
+
+fn split_table_path(path: &str) -> TestDataResult<(Vec<&str>, &str)> {
+    let invalid = || TestDataError::InvalidTablePath(path.to_string());
+    let mut parts: Vec<&str> = path
+        .strip_prefix('/')
+        .ok_or_else(invalid)?
+        .split('/')
+        .collect();
+    let name = parts
+        .pop()
+        .filter(|name| !name.is_empty())
+        .ok_or_else(invalid)?;
+    if parts.iter().any(|part| part.is_empty()) {
+        return Err(invalid());
+    }
+    Ok((parts, name))
+}
+
+fn check_shape(spec: &TableSpec, values: &[Vec<String>]) -> TestDataResult<()> {
+    let bad_row = values.iter().find(|row| row.len() != spec.columns.len());
+    if values.len() != spec.n_rows as usize || bad_row.is_some() {
+        return Err(TestDataError::ShapeMismatch {
+            table: spec.path.clone(),
+            rows: values.len(),
+            columns: bad_row.map_or(spec.columns.len(), Vec::len),
+            expected_rows: spec.n_rows as usize,
+            expected_columns: spec.columns.len(),
+        });
+    }
+    Ok(())
+}
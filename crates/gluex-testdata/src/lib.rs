@@ -0,0 +1,63 @@
+//! Synthetic `GlueX` CCDB and RCDB databases for tests.
+//!
+//! [`ccdb::CcdbBuilder`] and [`rcdb::RcdbBuilder`] describe a handful of directories, tables,
+//! assignments, runs, and conditions in code and write them to a fresh `SQLite` file using the
+//! same schema as the production databases. Integration tests (here and in downstream crates)
+//! can then open the generated file with `gluex-ccdb`/`gluex-rcdb` instead of relying on a
+//! private copy of the real databases.
+use thiserror::Error;
+
+/// CCDB database builder.
+pub mod ccdb;
+/// RCDB database builder.
+pub mod rcdb;
+
+/// Errors raised while generating a synthetic database.
+#[derive(Error, Debug)]
+pub enum TestDataError {
+    /// Wrapper around [`rusqlite::Error`].
+    #[error("{0}")]
+    SqliteError(#[from] rusqlite::Error),
+    /// A CCDB table path was not absolute or had no table name.
+    #[error("invalid table path \"{0}\"")]
+    InvalidTablePath(String),
+    /// An assignment referenced a table that was never declared.
+    #[error("table \"{0}\" was not declared")]
+    UnknownTable(String),
+    /// A variation (or a variation's parent) was never declared.
+    #[error("variation \"{0}\" was not declared")]
+    UnknownVariation(String),
+    /// A table author was never declared as a user.
+    #[error("user \"{0}\" was not declared")]
+    UnknownUser(String),
+    /// An assignment's values did not match the declared table shape.
+    #[error("assignment for \"{table}\" has shape {rows}x{columns}, expected {expected_rows}x{expected_columns}")]
+    ShapeMismatch {
+        /// Table path.
+        table: String,
+        /// Rows supplied.
+        rows: usize,
+        /// Columns supplied in the first mismatching row.
+        columns: usize,
+        /// Rows declared for the table.
+        expected_rows: usize,
+        /// Columns declared for the table.
+        expected_columns: usize,
+    },
+    /// Two values of different types were given for the same RCDB condition.
+    #[error("condition \"{name}\" was declared as {expected} but given a {found} value")]
+    ConditionTypeMismatch {
+        /// Condition name.
+        name: String,
+        /// Type implied by the first value.
+        expected: &'static str,
+        /// Type of the conflicting value.
+        found: &'static str,
+    },
+}
+
+/// Convenience alias for results returned from the builders.
+pub type TestDataResult<T> = Result<T, TestDataError>;
+
+/// Timestamp format used for every date column in the generated files.
+pub(crate) const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
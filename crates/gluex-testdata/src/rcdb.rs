@@ -0,0 +1,300 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::RangeInclusive,
+    path::Path,
+};
+
+use chrono::{DateTime, Utc};
+use gluex_core::{provenance::sha256_hex, RunNumber};
+use rusqlite::{params, Connection};
+
+use crate::{TestDataError, TestDataResult, TIMESTAMP_FORMAT};
+
+const SCHEMA_SQL: &str = "
+    CREATE TABLE schema_versions(version INTEGER PRIMARY KEY, created TEXT, comment TEXT);
+    CREATE TABLE runs(number INTEGER PRIMARY KEY, started TEXT, finished TEXT);
+    CREATE TABLE condition_types(
+        id INTEGER PRIMARY KEY, name TEXT NOT NULL UNIQUE, value_type TEXT NOT NULL,
+        created TEXT NOT NULL, description TEXT NOT NULL DEFAULT '');
+    CREATE TABLE conditions(
+        id INTEGER PRIMARY KEY, text_value TEXT, int_value INTEGER NOT NULL DEFAULT 0,
+        float_value REAL NOT NULL DEFAULT 0, bool_value INTEGER NOT NULL DEFAULT 0,
+        time_value TEXT, run_number INTEGER NOT NULL, condition_type_id INTEGER NOT NULL,
+        created TEXT NOT NULL);
+    CREATE INDEX ix_conditions_run_number ON conditions(run_number);
+    CREATE INDEX ix_conditions_condition_type_id ON conditions(condition_type_id);
+    CREATE TABLE files(
+        id INTEGER PRIMARY KEY, path TEXT NOT NULL, sha256 TEXT NOT NULL, content TEXT NOT NULL,
+        description TEXT, importance INTEGER NOT NULL DEFAULT 0);
+    CREATE TABLE files_have_runs(files_id INTEGER NOT NULL, run_number INTEGER NOT NULL);
+    CREATE TABLE run_periods(
+        id INTEGER PRIMARY KEY, name TEXT NOT NULL, description TEXT, run_min INTEGER NOT NULL,
+        run_max INTEGER NOT NULL, start_date TEXT, end_date TEXT);
+    INSERT INTO schema_versions(version, comment) VALUES (2, 'synthetic');";
+
+/// A condition value, stored in the column matching its type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConditionValue {
+    /// `string` condition.
+    String(String),
+    /// `int` condition.
+    Int(i64),
+    /// `bool` condition.
+    Bool(bool),
+    /// `float` condition.
+    Float(f64),
+    /// `json` condition, stored as text.
+    Json(String),
+    /// `blob` condition, stored as text.
+    Blob(String),
+    /// `time` condition.
+    Time(DateTime<Utc>),
+}
+
+impl ConditionValue {
+    /// Returns the RCDB type identifier of the value.
+    #[must_use]
+    pub fn value_type(&self) -> &'static str {
+        match self {
+            ConditionValue::String(_) => "string",
+            ConditionValue::Int(_) => "int",
+            ConditionValue::Bool(_) => "bool",
+            ConditionValue::Float(_) => "float",
+            ConditionValue::Json(_) => "json",
+            ConditionValue::Blob(_) => "blob",
+            ConditionValue::Time(_) => "time",
+        }
+    }
+}
+
+impl From<&str> for ConditionValue {
+    fn from(value: &str) -> Self {
+        ConditionValue::String(value.to_string())
+    }
+}
+
+impl From<String> for ConditionValue {
+    fn from(value: String) -> Self {
+        ConditionValue::String(value)
+    }
+}
+
+impl From<i64> for ConditionValue {
+    fn from(value: i64) -> Self {
+        ConditionValue::Int(value)
+    }
+}
+
+impl From<i32> for ConditionValue {
+    fn from(value: i32) -> Self {
+        ConditionValue::Int(value.into())
+    }
+}
+
+impl From<bool> for ConditionValue {
+    fn from(value: bool) -> Self {
+        ConditionValue::Bool(value)
+    }
+}
+
+impl From<f64> for ConditionValue {
+    fn from(value: f64) -> Self {
+        ConditionValue::Float(value)
+    }
+}
+
+impl From<DateTime<Utc>> for ConditionValue {
+    fn from(value: DateTime<Utc>) -> Self {
+        ConditionValue::Time(value)
+    }
+}
+
+struct ConfigurationFile {
+    path: String,
+    content: String,
+    runs: Vec<RunNumber>,
+}
+
+/// Describes a synthetic RCDB database and writes it to a `SQLite` file.
+///
+/// Condition types are declared implicitly by the first value given for them, and runs are
+/// added for every run that has a condition or file.
+///
+/// ```no_run
+/// use gluex_testdata::rcdb::RcdbBuilder;
+///
+/// let mut builder = RcdbBuilder::new();
+/// for run in 1..=10 {
+///     builder = builder
+///         .condition(run, "event_count", run * 1000)
+///         .condition(run, "run_type", "hd_all.tsg");
+/// }
+/// builder.write("rcdb.sqlite")?;
+/// # Ok::<(), gluex_testdata::TestDataError>(())
+/// ```
+#[derive(Default)]
+pub struct RcdbBuilder {
+    runs: BTreeSet<RunNumber>,
+    descriptions: BTreeMap<String, String>,
+    conditions: Vec<(RunNumber, String, ConditionValue)>,
+    files: Vec<ConfigurationFile>,
+    run_periods: Vec<(String, RangeInclusive<RunNumber>)>,
+}
+
+impl RcdbBuilder {
+    /// Creates an empty builder.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds runs that need not carry any condition.
+    #[must_use]
+    pub fn runs(mut self, runs: impl IntoIterator<Item = RunNumber>) -> Self {
+        self.runs.extend(runs);
+        self
+    }
+
+    /// Sets the value of condition `name` for `run`.
+    #[must_use]
+    pub fn condition(
+        mut self,
+        run: RunNumber,
+        name: &str,
+        value: impl Into<ConditionValue>,
+    ) -> Self {
+        self.runs.insert(run);
+        self.conditions.push((run, name.to_string(), value.into()));
+        self
+    }
+
+    /// Sets the description of condition type `name`.
+    #[must_use]
+    pub fn describe(mut self, name: &str, description: &str) -> Self {
+        self.descriptions
+            .insert(name.to_string(), description.to_string());
+        self
+    }
+
+    /// Attaches a configuration file with the given `content` to `runs`.
+    #[must_use]
+    pub fn file(
+        mut self,
+        path: &str,
+        content: &str,
+        runs: impl IntoIterator<Item = RunNumber>,
+    ) -> Self {
+        let runs: Vec<RunNumber> = runs.into_iter().collect();
+        self.runs.extend(&runs);
+        self.files.push(ConfigurationFile {
+            path: path.to_string(),
+            content: content.to_string(),
+            runs,
+        });
+        self
+    }
+
+    /// Adds a row to the `run_periods` table.
+    #[must_use]
+    pub fn run_period(mut self, name: &str, runs: RangeInclusive<RunNumber>) -> Self {
+        self.run_periods.push((name.to_string(), runs));
+        self
+    }
+
+    /// Writes the database to a new `SQLite` file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// This method returns an error if `path` already contains RCDB tables, if a condition was
+    /// given values of different types, or if any `SQLite` operation fails.
+    pub fn write(&self, path: impl AsRef<Path>) -> TestDataResult<()> {
+        let stamp = crate::ccdb::CcdbBuilder::epoch()
+            .format(TIMESTAMP_FORMAT)
+            .to_string();
+        let mut types: Vec<(&str, &'static str)> = Vec::new();
+        for (_, name, value) in &self.conditions {
+            match types.iter().find(|(known, _)| known == name) {
+                Some((_, expected)) if *expected != value.value_type() => {
+                    return Err(TestDataError::ConditionTypeMismatch {
+                        name: name.clone(),
+                        expected,
+                        found: value.value_type(),
+                    });
+                }
+                Some(_) => {}
+                None => types.push((name, value.value_type())),
+            }
+        }
+        let mut conn = Connection::open(path)?;
+        let tx = conn.transaction()?;
+        tx.execute_batch(SCHEMA_SQL)?;
+        for (id, (name, value_type)) in (1..).zip(&types) {
+            let description = self.descriptions.get(*name).map_or("", String::as_str);
+            tx.execute(
+                "INSERT INTO condition_types(id, name, value_type, created, description)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![id, name, value_type, stamp, description],
+            )?;
+        }
+        for run in &self.runs {
+            tx.execute(
+                "INSERT INTO runs(number, started, finished) VALUES (?1, NULL, NULL)",
+                [run],
+            )?;
+        }
+        {
+            let mut stmt = tx.prepare(
+                "INSERT INTO conditions(text_value, int_value, float_value, bool_value, time_value,
+                                        run_number, condition_type_id, created)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6,
+                         (SELECT id FROM condition_types WHERE name = ?7), ?8)",
+            )?;
+            for (run, name, value) in &self.conditions {
+                let (text, int, float, flag, time): (Option<&str>, i64, f64, bool, Option<String>) =
+                    match value {
+                        ConditionValue::String(text)
+                        | ConditionValue::Json(text)
+                        | ConditionValue::Blob(text) => (Some(text), 0, 0.0, false, None),
+                        ConditionValue::Int(int) => (None, *int, 0.0, false, None),
+                        ConditionValue::Float(float) => (None, 0, *float, false, None),
+                        ConditionValue::Bool(flag) => (None, 0, 0.0, *flag, None),
+                        ConditionValue::Time(time) => (
+                            None,
+                            0,
+                            0.0,
+                            false,
+                            Some(time.format(TIMESTAMP_FORMAT).to_string()),
+                        ),
+                    };
+                stmt.execute(params![text, int, float, flag, time, run, name, stamp])?;
+            }
+        }
+        for (id, file) in (1..).zip(&self.files) {
+            tx.execute(
+                "INSERT INTO files(id, path, sha256, content, description, importance)
+                 VALUES (?1, ?2, ?3, ?4, '', 0)",
+                params![
+                    id,
+                    file.path,
+                    sha256_hex(file.content.as_bytes()),
+                    file.content
+                ],
+            )?;
+            for run in &file.runs {
+                tx.execute(
+                    "INSERT INTO files_have_runs(files_id, run_number) VALUES (?1, ?2)",
+                    params![id, run],
+                )?;
+            }
+        }
+        for (id, (name, runs)) in (1..).zip(&self.run_periods) {
+            tx.execute(
+                "INSERT INTO run_periods(id, name, description, run_min, run_max)
+                 VALUES (?1, ?2, '', ?3, ?4)",
+                params![id, name, runs.start(), runs.end()],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,308 @@
+"""
+    GlueX
+
+Julia bindings for reading GlueX calibration constants (CCDB) and run conditions (RCDB) from local
+SQLite snapshots, built on the C API of the `gluex-ffi` crate.
+
+The shared library is located through the `GLUEX_FFI_LIB` environment variable (read when the
+package is precompiled), falling back to `libgluex_ffi` on the system library path.
+"""
+module GlueX
+
+# `fetch` extends `Base.fetch`, so it needs no export
+import Base: fetch
+
+export CCDB, RCDB, GlueXError, fetch_string, columns, runs
+
+const libgluex = get(ENV, "GLUEX_FFI_LIB", "libgluex_ffi")
+
+const STATUS_OK = Cint(0)
+const STATUS_BUFFER_TOO_SMALL = Cint(5)
+const STATUS_NAMES = (
+    "ok",
+    "null argument",
+    "invalid string",
+    "not found",
+    "invalid argument",
+    "buffer too small",
+    "type mismatch",
+    "database",
+    "panic",
+)
+
+"""
+    GlueXError(status, message)
+
+A failed call into `libgluex_ffi`, carrying the `GluexStatus` code and the library's message.
+"""
+struct GlueXError <: Exception
+    status::Cint
+    message::String
+end
+
+function Base.showerror(io::IO, err::GlueXError)
+    name = get(STATUS_NAMES, err.status + 1, "status $(err.status)")
+    print(io, "GlueXError ($name): ", err.message)
+end
+
+function check(status::Cint)
+    if status != STATUS_OK
+        message = ccall((:gluex_last_error, libgluex), Cstring, ())
+        throw(GlueXError(status, message == C_NULL ? "" : unsafe_string(message)))
+    end
+    return nothing
+end
+
+# Calls `f(buffer, capacity, len)` once to learn the required length, then again with a buffer of
+# that size.
+function fill_buffer(f, ::Type{T}) where {T}
+    len = Ref{Csize_t}(0)
+    status = f(Ptr{T}(C_NULL), 0, len)
+    status == STATUS_BUFFER_TOO_SMALL || check(status)
+    buffer = Vector{T}(undef, len[])
+    check(f(buffer, length(buffer), len))
+    return buffer
+end
+
+function fill_string(f)
+    len = Ref{Csize_t}(0)
+    status = f(Ptr{UInt8}(C_NULL), 0, len)
+    status == STATUS_BUFFER_TOO_SMALL || check(status)
+    buffer = Vector{UInt8}(undef, len[] + 1)
+    check(f(buffer, length(buffer), len))
+    return String(buffer[1:len[]])
+end
+
+"""
+    CCDB(path)
+
+Opens the CCDB SQLite snapshot at `path`. The handle is released when it is garbage collected or
+passed to `close`.
+"""
+mutable struct CCDB
+    handle::Ptr{Cvoid}
+    function CCDB(path::AbstractString)
+        out = Ref{Ptr{Cvoid}}(C_NULL)
+        check(ccall((:gluex_ccdb_open, libgluex), Cint, (Cstring, Ref{Ptr{Cvoid}}), path, out))
+        return finalizer(close, new(out[]))
+    end
+end
+
+function Base.close(db::CCDB)
+    if db.handle != C_NULL
+        ccall((:gluex_ccdb_close, libgluex), Cvoid, (Ptr{Cvoid},), db.handle)
+        db.handle = C_NULL
+    end
+    return nothing
+end
+
+function shape(db::CCDB, request::AbstractString)
+    n_rows = Ref{Csize_t}(0)
+    n_columns = Ref{Csize_t}(0)
+    check(ccall(
+        (:gluex_ccdb_shape, libgluex),
+        Cint,
+        (Ptr{Cvoid}, Cstring, Ref{Csize_t}, Ref{Csize_t}),
+        db.handle,
+        request,
+        n_rows,
+        n_columns,
+    ))
+    return Int(n_rows[]), Int(n_columns[])
+end
+
+"""
+    fetch(db::CCDB, request) -> Matrix{Float64}
+    fetch(Int64, db::CCDB, request) -> Matrix{Int64}
+
+Fetches the constants selected by a `JCalibration`-style request string
+(`<path>[:<run>[:<variation>[:<timestamp>]]]`) as a rows × columns matrix.
+"""
+fetch(db::CCDB, request::AbstractString) = fetch(Float64, db, request)
+
+function fetch(::Type{T}, db::CCDB, request::AbstractString) where {T<:Union{Float64,Int64}}
+    n_rows, n_columns = shape(db, request)
+    values = fill_buffer(T) do buffer, capacity, len
+        if T === Float64
+            ccall(
+                (:gluex_ccdb_fetch_doubles, libgluex),
+                Cint,
+                (Ptr{Cvoid}, Cstring, Ptr{Float64}, Csize_t, Ref{Csize_t}),
+                db.handle,
+                request,
+                buffer,
+                capacity,
+                len,
+            )
+        else
+            ccall(
+                (:gluex_ccdb_fetch_longs, libgluex),
+                Cint,
+                (Ptr{Cvoid}, Cstring, Ptr{Int64}, Csize_t, Ref{Csize_t}),
+                db.handle,
+                request,
+                buffer,
+                capacity,
+                len,
+            )
+        end
+    end
+    # the library writes rows contiguously
+    return permutedims(reshape(values, n_columns, n_rows))
+end
+
+"""
+    fetch_string(db::CCDB, request, row, column) -> String
+
+One cell (1-based `row` and `column`) of the constants selected by `request`, formatted as text.
+"""
+function fetch_string(db::CCDB, request::AbstractString, row::Integer, column::Integer)
+    return fill_string() do buffer, capacity, len
+        ccall(
+            (:gluex_ccdb_fetch_string, libgluex),
+            Cint,
+            (Ptr{Cvoid}, Cstring, Csize_t, Csize_t, Ptr{UInt8}, Csize_t, Ref{Csize_t}),
+            db.handle,
+            request,
+            row - 1,
+            column - 1,
+            buffer,
+            capacity,
+            len,
+        )
+    end
+end
+
+"""
+    columns(db::CCDB, request) -> Vector{String}
+
+The column names of the table selected by `request`.
+"""
+function columns(db::CCDB, request::AbstractString)
+    _, n_columns = shape(db, request)
+    return map(1:n_columns) do column
+        fill_string() do buffer, capacity, len
+            ccall(
+                (:gluex_ccdb_column_name, libgluex),
+                Cint,
+                (Ptr{Cvoid}, Cstring, Csize_t, Ptr{UInt8}, Csize_t, Ref{Csize_t}),
+                db.handle,
+                request,
+                column - 1,
+                buffer,
+                capacity,
+                len,
+            )
+        end
+    end
+end
+
+"""
+    RCDB(path)
+
+Opens the RCDB SQLite snapshot at `path`. The handle is released when it is garbage collected or
+passed to `close`.
+"""
+mutable struct RCDB
+    handle::Ptr{Cvoid}
+    function RCDB(path::AbstractString)
+        out = Ref{Ptr{Cvoid}}(C_NULL)
+        check(ccall((:gluex_rcdb_open, libgluex), Cint, (Cstring, Ref{Ptr{Cvoid}}), path, out))
+        return finalizer(close, new(out[]))
+    end
+end
+
+function Base.close(db::RCDB)
+    if db.handle != C_NULL
+        ccall((:gluex_rcdb_close, libgluex), Cvoid, (Ptr{Cvoid},), db.handle)
+        db.handle = C_NULL
+    end
+    return nothing
+end
+
+"""
+    fetch(T, db::RCDB, condition, run)
+
+The value of `condition` for `run` as `T`, one of `Int64`, `Float64`, `Bool`, or `String`
+(times are returned as RFC 3339 strings).
+"""
+function fetch(::Type{Int64}, db::RCDB, condition::AbstractString, run::Integer)
+    out = Ref{Int64}(0)
+    check(ccall(
+        (:gluex_rcdb_fetch_long, libgluex),
+        Cint,
+        (Ptr{Cvoid}, Cstring, Int64, Ref{Int64}),
+        db.handle,
+        condition,
+        run,
+        out,
+    ))
+    return out[]
+end
+
+function fetch(::Type{Float64}, db::RCDB, condition::AbstractString, run::Integer)
+    out = Ref{Float64}(0.0)
+    check(ccall(
+        (:gluex_rcdb_fetch_double, libgluex),
+        Cint,
+        (Ptr{Cvoid}, Cstring, Int64, Ref{Float64}),
+        db.handle,
+        condition,
+        run,
+        out,
+    ))
+    return out[]
+end
+
+function fetch(::Type{Bool}, db::RCDB, condition::AbstractString, run::Integer)
+    out = Ref{Bool}(false)
+    check(ccall(
+        (:gluex_rcdb_fetch_bool, libgluex),
+        Cint,
+        (Ptr{Cvoid}, Cstring, Int64, Ref{Bool}),
+        db.handle,
+        condition,
+        run,
+        out,
+    ))
+    return out[]
+end
+
+function fetch(::Type{String}, db::RCDB, condition::AbstractString, run::Integer)
+    return fill_string() do buffer, capacity, len
+        ccall(
+            (:gluex_rcdb_fetch_string, libgluex),
+            Cint,
+            (Ptr{Cvoid}, Cstring, Int64, Ptr{UInt8}, Csize_t, Ref{Csize_t}),
+            db.handle,
+            condition,
+            run,
+            buffer,
+            capacity,
+            len,
+        )
+    end
+end
+
+"""
+    runs(db::RCDB, selection) -> Vector{Int64}
+
+The runs in RCDB matching a run selection string such as `"S18+F18-[41050..41100]-41234"`, in
+ascending order.
+"""
+function runs(db::RCDB, selection::AbstractString)
+    return fill_buffer(Int64) do buffer, capacity, len
+        ccall(
+            (:gluex_rcdb_runs, libgluex),
+            Cint,
+            (Ptr{Cvoid}, Cstring, Ptr{Int64}, Csize_t, Ref{Csize_t}),
+            db.handle,
+            selection,
+            buffer,
+            capacity,
+            len,
+        )
+    end
+end
+
+end
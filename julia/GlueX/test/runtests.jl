@@ -0,0 +1,15 @@
+using GlueX
+using Test
+
+@testset "GlueX" begin
+    missing_path = joinpath(mktempdir(), "missing.sqlite")
+    err = try
+        CCDB(missing_path)
+        nothing
+    catch err
+        err
+    end
+    @test err isa GlueXError
+    @test !isempty(err.message)
+    @test_throws GlueXError RCDB(missing_path)
+end